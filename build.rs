@@ -0,0 +1,65 @@
+//! Builds the web report from `html_report/` so the binary never embeds a
+//! report older than the payloads it sends.
+//!
+//! Needs `bun` and `html_report/node_modules` (`bun install`). Without them
+//! the committed `assets/index.html` is embedded instead, with a warning, as
+//! it may predate `html_report/src`.
+
+use std::env;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+const SOURCES: &[&str] = &[
+    "html_report/src",
+    "html_report/index.html",
+    "html_report/package.json",
+    "html_report/bun.lock",
+    "html_report/vite.config.ts",
+    "html_report/tsconfig.json",
+    "html_report/tsconfig.app.json",
+    "html_report/tsconfig.node.json",
+    "assets/index.html",
+];
+
+fn main() {
+    for source in SOURCES {
+        println!("cargo:rerun-if-changed={source}");
+    }
+    println!("cargo:rerun-if-env-changed=PEEL_PREBUILT_REPORT");
+
+    let out_dir = PathBuf::from(env::var_os("OUT_DIR").expect("set by cargo")).join("report");
+    let root = PathBuf::from(env::var_os("CARGO_MANIFEST_DIR").expect("set by cargo"));
+    let app = root.join("html_report");
+
+    let prebuilt = env::var_os("PEEL_PREBUILT_REPORT").is_some_and(|v| !v.is_empty() && v != "0");
+    let why = if prebuilt {
+        None
+    } else if !app.join("node_modules").is_dir() {
+        Some("html_report/node_modules is missing (run `bun install` in html_report)")
+    } else if Command::new("bun").arg("--version").output().is_err() {
+        Some("bun is not on PATH")
+    } else {
+        build(&app, &out_dir);
+        return;
+    };
+
+    if let Some(why) = why {
+        println!(
+            "cargo:warning=embedding the prebuilt assets/index.html, which may be older than html_report/src: {why}"
+        );
+    }
+    std::fs::create_dir_all(&out_dir).expect("create report dir");
+    std::fs::copy(root.join("assets/index.html"), out_dir.join("index.html"))
+        .expect("copy assets/index.html");
+}
+
+/// `bun run build` into `out_dir`, failing the cargo build when it fails.
+fn build(app: &Path, out_dir: &Path) {
+    let status = Command::new("bun")
+        .args(["run", "build", "--outDir"])
+        .arg(out_dir)
+        .current_dir(app)
+        .status()
+        .expect("run bun");
+    assert!(status.success(), "building html_report failed ({status})");
+}
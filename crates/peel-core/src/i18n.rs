@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Message catalogs compiled in, keyed by language tag. They live at the
+/// workspace root and hold the CLI's messages as well as the library's.
+/// English is the source catalog; other catalogs may be partial and fall back
+/// to it key by key.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../../../locales/en.ftl")),
+    ("zh-CN", include_str!("../../../locales/zh-CN.ftl")),
+];
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+struct Catalog {
+    lang: &'static str,
+    messages: HashMap<&'static str, String>,
+    fallback: HashMap<&'static str, String>,
+}
+
+/// Look up a message by key and substitute `{ $name }` placeholders.
+///
+/// Usually called through the `t!` macro:
+/// `t!("runtime-unknown", name = name)`.
+#[macro_export]
+macro_rules! t {
+    ($key:expr) => {
+        $crate::i18n::message($key, &[])
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)?) => {
+        $crate::i18n::message($key, &[$((stringify!($name), $value.to_string())),+])
+    };
+}
+
+/// Select the catalog from `--lang`, falling back to the usual locale
+/// environment variables (`LC_ALL`, `LC_MESSAGES`, `LANG`) and then English.
+pub fn init(lang_override: Option<&str>) {
+    let _ = CATALOG.set(load(lang_override));
+}
+
+fn load(lang_override: Option<&str>) -> Catalog {
+    let requested = lang_override
+        .map(str::to_string)
+        .or_else(|| {
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|var| std::env::var(var).ok())
+                .find(|v| !v.is_empty())
+        })
+        .unwrap_or_default();
+
+    let lang = resolve(&requested);
+    Catalog {
+        lang,
+        messages: parse(source(lang)),
+        fallback: parse(source("en")),
+    }
+}
+
+fn catalog() -> &'static Catalog {
+    CATALOG.get_or_init(|| load(None))
+}
+
+/// The active language tag (e.g. "en", "zh-CN").
+pub fn lang() -> &'static str {
+    catalog().lang
+}
+
+/// The message for `key` in the active language, with each `{ $name }`
+/// replaced by its value; the key itself if no catalog has it.
+pub fn message(key: &str, args: &[(&str, String)]) -> String {
+    let c = catalog();
+    let mut out = c
+        .messages
+        .get(key)
+        .or_else(|| c.fallback.get(key))
+        .cloned()
+        .unwrap_or_else(|| key.to_string());
+    for (name, value) in args {
+        out = out.replace(&format!("{{ ${name} }}"), value);
+    }
+    out
+}
+
+/// Map a locale such as `zh_CN.UTF-8` or `de` to a compiled-in catalog tag.
+fn resolve(requested: &str) -> &'static str {
+    let normalized = requested
+        .split(['.', '@'])
+        .next()
+        .unwrap_or("")
+        .replace('_', "-");
+    let language = normalized.split('-').next().unwrap_or("");
+
+    CATALOGS
+        .iter()
+        .find(|(tag, _)| tag.eq_ignore_ascii_case(&normalized))
+        .or_else(|| {
+            CATALOGS
+                .iter()
+                .find(|(tag, _)| tag.split('-').next().is_some_and(|l| l.eq_ignore_ascii_case(language)))
+        })
+        .map(|(tag, _)| *tag)
+        .unwrap_or("en")
+}
+
+fn source(lang: &str) -> &'static str {
+    CATALOGS
+        .iter()
+        .find(|(tag, _)| *tag == lang)
+        .map(|(_, src)| *src)
+        .unwrap_or("")
+}
+
+/// Parse the single-line `key = value` subset of Fluent syntax used by our
+/// catalogs. A literal `\n` in a value becomes a line break.
+fn parse(src: &'static str) -> HashMap<&'static str, String> {
+    src.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once(" = "))
+        .map(|(key, value)| (key.trim(), value.trim().replace("\\n", "\n")))
+        .collect()
+}
@@ -0,0 +1,726 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Take};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::instruction::Instruction;
+use super::provenance::{self, PREDICATE_TYPE, REFERENCE_DIGEST, REFERENCE_TYPE};
+use super::run_config;
+use super::{FileChange, FileEntry, ImageInfo, LayerInfo, Listing, checksum};
+
+/// Parsed result from a tar archive: image metadata and where each layer is.
+/// No files are listed; `open_layer` streams a layer for that.
+pub struct ArchiveResult {
+    pub info: ImageInfo,
+    /// Where each layer blob's data starts in the archive and its length, keyed by diff_id.
+    pub layer_offsets: HashMap<String, (u64, u64)>,
+    /// Raw image config JSON, if the archive carries one.
+    pub config: Option<Vec<u8>>,
+}
+
+/// Optional callback invoked after each layer is parsed.
+pub type OnLayerParsed = Box<dyn FnMut()>;
+
+// ---- Docker-format archive structs (manifest.json) ----
+
+#[derive(Deserialize)]
+struct DockerManifestEntry {
+    #[serde(rename = "Config")]
+    config: String,
+    #[serde(rename = "Layers")]
+    layers: Vec<String>,
+    #[serde(rename = "RepoTags", default)]
+    repo_tags: Vec<String>,
+}
+
+// ---- OCI-layout archive structs (index.json) ----
+
+#[derive(Deserialize)]
+struct OciIndex {
+    manifests: Vec<OciDescriptor>,
+}
+
+#[derive(Deserialize)]
+struct OciDescriptor {
+    digest: String,
+    #[serde(default)]
+    size: u64,
+    #[serde(rename = "mediaType", default)]
+    media_type: String,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+}
+
+impl OciDescriptor {
+    /// Whether the blob is compressed, so its size is what a registry
+    /// stores rather than the plain tar `docker save` writes.
+    fn is_compressed(&self) -> bool {
+        ["gzip", "zstd"].iter().any(|c| self.media_type.ends_with(c))
+    }
+
+    /// An image index or Docker manifest list, rather than an image manifest.
+    fn is_index(&self) -> bool {
+        self.media_type.contains("image.index") || self.media_type.contains("manifest.list")
+    }
+
+    /// BuildKit's manifest of attestations about another manifest.
+    fn is_attestation(&self) -> bool {
+        self.annotations.get(REFERENCE_TYPE).is_some_and(|t| t == "attestation-manifest")
+    }
+}
+
+#[derive(Deserialize)]
+struct OciManifest {
+    config: OciDescriptor,
+    layers: Vec<OciDescriptor>,
+    #[serde(default)]
+    annotations: HashMap<String, String>,
+}
+
+// ---- Shared config struct (used by both formats) ----
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    architecture: Option<String>,
+    created: Option<String>,
+    rootfs: Rootfs,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+}
+
+#[derive(Deserialize)]
+struct Rootfs {
+    diff_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct HistoryEntry {
+    created_by: Option<String>,
+    created: Option<String>,
+    #[serde(default)]
+    empty_layer: bool,
+}
+
+/// Parse a tar archive file, auto-detecting Docker vs OCI format.
+///
+/// `name` and `tag` are used for the returned `ImageInfo` (caller decides how
+/// to derive them — from the CLI ref, from the filename, etc.).
+///
+/// If `diff_ids_hint` is provided (from a prior `docker inspect` call), those
+/// are used instead of reading the config from inside the archive, and layer
+/// sizes are left at 0 for the caller to fill in. Otherwise each layer is
+/// read once to sum its file sizes, one entry at a time.
+///
+/// `on_layer` is called once per layer found (for progress reporting).
+pub fn parse_archive(
+    path: &Path,
+    name: &str,
+    tag: &str,
+    diff_ids_hint: Option<&[String]>,
+    on_layer: &mut Option<OnLayerParsed>,
+) -> Result<ArchiveResult> {
+    let _phase = tracing::debug_span!("parse").entered();
+    // Peek at the archive to detect format
+    let format = detect_format(path)?;
+    tracing::debug!("format of {}: {format:?}", path.display());
+
+    match format {
+        ArchiveFormat::Docker => parse_docker_format(path, name, tag, diff_ids_hint, on_layer),
+        ArchiveFormat::Oci => parse_oci_format(path, name, tag, on_layer),
+    }
+}
+
+#[derive(Debug)]
+enum ArchiveFormat {
+    Docker,
+    Oci,
+}
+
+fn detect_format(path: &Path) -> Result<ArchiveFormat> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = tar::Archive::new(file);
+
+    for entry_result in archive.entries().context("Failed to read tar entries")? {
+        let entry = entry_result.context("Failed to read tar entry")?;
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+
+        if entry_path == "manifest.json" {
+            return Ok(ArchiveFormat::Docker);
+        }
+        if entry_path == "index.json" {
+            return Ok(ArchiveFormat::Oci);
+        }
+    }
+
+    anyhow::bail!("Unrecognized archive format: no manifest.json or index.json found")
+}
+
+// ---- Docker-format parsing ----
+
+fn parse_docker_format(
+    path: &Path,
+    name: &str,
+    tag: &str,
+    diff_ids_hint: Option<&[String]>,
+    on_layer: &mut Option<OnLayerParsed>,
+) -> Result<ArchiveResult> {
+    let file = std::fs::File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    let mut archive = tar::Archive::new(file);
+    let want_sizes = diff_ids_hint.is_none();
+
+    let mut layer_sizes: HashMap<String, u64> = HashMap::new();
+    let mut entry_offsets: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut manifest_data: Option<Vec<DockerManifestEntry>> = None;
+    let mut configs: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut index: Option<OciIndex> = None;
+
+    for entry_result in archive.entries().context("Failed to read tar entries")? {
+        let mut entry = entry_result.context("Failed to read tar entry")?;
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+
+        if entry_path == "manifest.json" {
+            let mut content = String::new();
+            entry.read_to_string(&mut content)?;
+            manifest_data = Some(
+                serde_json::from_str(&content)
+                    .context("Failed to parse manifest.json")?,
+            );
+        } else if entry_path == "index.json" {
+            // Docker 25+ writes an OCI index alongside manifest.json
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            index = serde_json::from_slice(&data).ok();
+        } else if entry_path.ends_with(".json") && entry_path != "manifest.json" {
+            // Could be the image config (e.g. "abc123.json")
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            configs.insert(entry_path, data);
+        } else if entry_path.ends_with("/layer.tar") {
+            if let Some(cb) = on_layer {
+                cb();
+            }
+            let _layer = tracing::debug_span!("layer", layer = %entry_path).entered();
+            entry_offsets.insert(entry_path.clone(), (entry.raw_file_position(), entry.size()));
+            let size = if want_sizes {
+                layer_size(&mut entry)
+                    .with_context(|| format!("Failed to parse layer {entry_path}"))?
+            } else {
+                0
+            };
+            layer_sizes.insert(entry_path, size);
+        }
+    }
+
+    let manifest_entries = manifest_data.context("manifest.json not found in archive")?;
+    let me = manifest_entries
+        .into_iter()
+        .next()
+        .context("Empty manifest in archive")?;
+
+    // Modern Docker (v25+) uses OCI-layout archives where layers are stored as
+    // blobs/sha256/<hash> instead of <id>/layer.tar.  If any manifest layer
+    // paths weren't found in the first pass, do a second pass targeting them.
+    let missing: Vec<String> = me
+        .layers
+        .iter()
+        .filter(|p| !layer_sizes.contains_key(p.as_str()))
+        .cloned()
+        .collect();
+
+    if !missing.is_empty() {
+        let file = std::fs::File::open(path)
+            .with_context(|| format!("Failed to open {}", path.display()))?;
+        let mut archive = tar::Archive::new(file);
+
+        for entry_result in archive.entries().context("Failed to read tar entries")? {
+            let mut entry = entry_result.context("Failed to read tar entry")?;
+            let entry_path = entry.path()?.to_string_lossy().to_string();
+
+            if missing.iter().any(|m| m == &entry_path) {
+                if let Some(cb) = on_layer {
+                    cb();
+                }
+                let _layer = tracing::debug_span!("layer", layer = %entry_path).entered();
+                entry_offsets.insert(entry_path.clone(), (entry.raw_file_position(), entry.size()));
+                let size = if want_sizes {
+                    layer_size(&mut entry)
+                        .with_context(|| format!("Failed to parse layer {entry_path}"))?
+                } else {
+                    0
+                };
+                layer_sizes.insert(entry_path, size);
+            }
+        }
+    }
+
+    // Resolve diff_ids: prefer hint from CLI, fall back to config in archive
+    let (architecture, created, diff_ids, created_by_list, created_list) = if let Some(hint) =
+        diff_ids_hint
+    {
+        // Caller already knows the diff_ids (from `docker inspect`), no config needed
+        (None, None, hint.to_vec(), Vec::new(), Vec::new())
+    } else {
+        // Read the image config from inside the archive
+        let config_data = configs
+            .get(&me.config)
+            .with_context(|| format!("Config {} not found in archive", me.config))?;
+        let config: ImageConfig =
+            serde_json::from_slice(config_data).context("Failed to parse image config")?;
+
+        let mut cbl: Vec<Option<String>> = Vec::new();
+        let mut created: Vec<Option<String>> = Vec::new();
+        for entry in &config.history {
+            if !entry.empty_layer {
+                cbl.push(entry.created_by.clone());
+                created.push(entry.created.clone());
+            }
+        }
+
+        (config.architecture, config.created, config.rootfs.diff_ids, cbl, created)
+    };
+
+    // Derive name/tag from RepoTags if caller didn't provide meaningful ones
+    let (final_name, final_tag) = if name.is_empty() {
+        if let Some(repo_tag) = me.repo_tags.first() {
+            parse_image_ref(repo_tag)
+        } else {
+            (name.to_string(), tag.to_string())
+        }
+    } else {
+        (name.to_string(), tag.to_string())
+    };
+
+    // Build layer info + offsets keyed by diff_id
+    let mut layer_offsets: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut layers = Vec::with_capacity(diff_ids.len());
+    let mut total_size = 0u64;
+
+    for (i, diff_id) in diff_ids.iter().enumerate() {
+        let size = me
+            .layers
+            .get(i)
+            .and_then(|tar_path| layer_sizes.get(tar_path))
+            .copied()
+            .unwrap_or(0);
+        total_size += size;
+
+        layers.push(LayerInfo {
+            digest: diff_id.clone(),
+            created_by: created_by_list.get(i).cloned().flatten(),
+            instruction: created_by_list
+                .get(i)
+                .and_then(|c| c.as_deref())
+                .and_then(Instruction::parse),
+            created: created_list.get(i).cloned().flatten(),
+            size,
+            // Layers in docker save archives are plain tars
+            compressed_size: None,
+            files: Vec::new(),
+        });
+
+        if let Some(offset) = me.layers.get(i).and_then(|p| entry_offsets.get(p)) {
+            layer_offsets.insert(diff_id.clone(), *offset);
+        }
+    }
+
+    let config_data = configs.get(&me.config).map(Vec::as_slice);
+    // The index entry's digest only; the blobs it may lead to aren't kept
+    let manifest_digests = index
+        .iter()
+        .flat_map(|i| &i.manifests)
+        .find(|d| !d.is_attestation())
+        .map(|d| d.digest.clone())
+        .into_iter()
+        .collect();
+    Ok(ArchiveResult {
+        info: ImageInfo {
+            name: final_name,
+            tag: Some(final_tag),
+            id: config_data.map(super::config_digest),
+            manifest_digests,
+            architecture,
+            created,
+            distro: None,
+            provenance: provenance::read(config_data, None),
+            labels: super::config_labels(config_data),
+            run_config: run_config::read(config_data),
+            annotations: BTreeMap::new(),
+            total_size,
+            layers,
+            roots: Vec::new(),
+        },
+        layer_offsets,
+        config: configs.remove(&me.config),
+    })
+}
+
+// ---- OCI-layout parsing ----
+
+fn parse_oci_format(
+    path: &Path,
+    name: &str,
+    tag: &str,
+    on_layer: &mut Option<OnLayerParsed>,
+) -> Result<ArchiveResult> {
+    // Pass 1: read index.json and small blobs (manifest, config).
+    let file = std::fs::File::open(path)?;
+    let mut archive = tar::Archive::new(file);
+
+    let mut index_data: Option<Vec<u8>> = None;
+    let mut small_blobs: HashMap<String, Vec<u8>> = HashMap::new();
+    let mut blob_offsets: HashMap<String, (u64, u64)> = HashMap::new();
+
+    for entry_result in archive.entries()? {
+        let mut entry = entry_result?;
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+
+        if entry_path == "index.json" {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            index_data = Some(data);
+        } else if let Some(hash) = entry_path.strip_prefix("blobs/sha256/") {
+            blob_offsets.insert(format!("sha256:{hash}"), (entry.raw_file_position(), entry.size()));
+            if entry.size() < 1_000_000 {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data)?;
+                small_blobs.insert(format!("sha256:{hash}"), data);
+            }
+        }
+    }
+
+    // Resolve index -> manifest -> config
+    let mut index: OciIndex = serde_json::from_slice(
+        index_data
+            .as_ref()
+            .context("index.json not found in OCI archive")?,
+    )
+    .context("Failed to parse index.json")?;
+
+    // Multi-platform saves point index.json at the image's own index, which
+    // lists the manifests and any attestations about them
+    let mut manifest_digests = Vec::new();
+    while let Some(nested) = index.manifests.first().filter(|d| d.is_index()) {
+        tracing::debug!("index.json points at image index {}", nested.digest);
+        manifest_digests.push(nested.digest.clone());
+        let data = small_blobs
+            .get(&nested.digest)
+            .with_context(|| format!("Index blob {} not found", nested.digest))?;
+        index = serde_json::from_slice(data).context("Failed to parse OCI image index")?;
+    }
+
+    let manifest_desc = index
+        .manifests
+        .iter()
+        .find(|d| !d.is_attestation())
+        .context("No manifests in index.json")?;
+    tracing::debug!(
+        "using manifest {} of {} in the index",
+        manifest_desc.digest,
+        index.manifests.len()
+    );
+    manifest_digests.push(manifest_desc.digest.clone());
+    let statement = provenance_statement(&index, &manifest_desc.digest, &small_blobs);
+    let manifest: OciManifest = serde_json::from_slice(
+        small_blobs
+            .get(&manifest_desc.digest)
+            .with_context(|| format!("Manifest blob {} not found", manifest_desc.digest))?,
+    )
+    .context("Failed to parse OCI manifest")?;
+    // The manifest's own annotations win over its index entry's
+    let annotations: BTreeMap<String, String> = manifest_desc
+        .annotations
+        .iter()
+        .chain(&manifest.annotations)
+        .map(|(k, v)| (k.clone(), v.clone()))
+        .collect();
+
+    let config: ImageConfig = serde_json::from_slice(
+        small_blobs
+            .get(&manifest.config.digest)
+            .with_context(|| format!("Config blob {} not found", manifest.config.digest))?,
+    )
+    .context("Failed to parse OCI image config")?;
+
+    let diff_ids = config.rootfs.diff_ids;
+
+    // Build compressed-digest -> diff_id mapping
+    let mut digest_to_diffid: HashMap<&str, &str> = HashMap::new();
+    for (i, layer_desc) in manifest.layers.iter().enumerate() {
+        if let Some(diff_id) = diff_ids.get(i) {
+            digest_to_diffid.insert(&layer_desc.digest, diff_id);
+        }
+    }
+
+    // Pass 2: sum each layer's file sizes, a blob at a time; blobs are
+    // compressed, so their sizes aren't what other backends report
+    let mut sizes_by_diff_id: HashMap<String, u64> = HashMap::new();
+
+    let file = std::fs::File::open(path)?;
+    let mut archive = tar::Archive::new(file);
+
+    for entry_result in archive.entries()? {
+        let mut entry = entry_result?;
+        let entry_path = entry.path()?.to_string_lossy().to_string();
+
+        let Some(hash) = entry_path.strip_prefix("blobs/sha256/") else {
+            continue;
+        };
+        let digest_str = format!("sha256:{hash}");
+        if let Some(diff_id) = digest_to_diffid.get(digest_str.as_str())
+            && !sizes_by_diff_id.contains_key(*diff_id)
+        {
+            if let Some(cb) = on_layer {
+                cb();
+            }
+            let _layer = tracing::debug_span!("layer", layer = %digest_str).entered();
+            let size = layer_size(&mut entry)
+                .with_context(|| format!("Failed to parse layer {digest_str}"))?;
+            sizes_by_diff_id.insert((*diff_id).to_string(), size);
+        }
+    }
+
+    // Match non-empty history entries to diff_ids
+    let mut created_by_list: Vec<Option<String>> = Vec::new();
+    let mut created_list: Vec<Option<String>> = Vec::new();
+    for entry in &config.history {
+        if !entry.empty_layer {
+            created_by_list.push(entry.created_by.clone());
+            created_list.push(entry.created.clone());
+        }
+    }
+
+    let mut layers = Vec::with_capacity(diff_ids.len());
+    let mut total_size = 0u64;
+    let mut layer_offsets: HashMap<String, (u64, u64)> = HashMap::new();
+
+    for (i, digest) in diff_ids.iter().enumerate() {
+        if let Some(offset) = manifest.layers.get(i).and_then(|d| blob_offsets.get(&d.digest)) {
+            layer_offsets.insert(digest.clone(), *offset);
+        }
+        // Blob sizes are compressed; report the files' size like other backends
+        let size = sizes_by_diff_id.get(digest).copied().unwrap_or(0);
+        total_size += size;
+        layers.push(LayerInfo {
+            digest: digest.clone(),
+            created_by: created_by_list.get(i).cloned().flatten(),
+            instruction: created_by_list
+                .get(i)
+                .and_then(|c| c.as_deref())
+                .and_then(Instruction::parse),
+            created: created_list.get(i).cloned().flatten(),
+            size,
+            compressed_size: manifest
+                .layers
+                .get(i)
+                .filter(|d| d.is_compressed())
+                .map(|d| d.size),
+            files: Vec::new(),
+        });
+    }
+
+    let config_data = small_blobs.get(&manifest.config.digest).map(Vec::as_slice);
+    Ok(ArchiveResult {
+        info: ImageInfo {
+            name: name.to_string(),
+            tag: Some(tag.to_string()),
+            id: Some(manifest.config.digest.clone()),
+            manifest_digests,
+            architecture: config.architecture,
+            created: config.created,
+            distro: None,
+            provenance: provenance::read(config_data, statement),
+            labels: super::config_labels(config_data),
+            run_config: run_config::read(config_data),
+            annotations,
+            total_size,
+            layers,
+            roots: Vec::new(),
+        },
+        layer_offsets,
+        config: small_blobs.remove(&manifest.config.digest),
+    })
+}
+
+/// The SLSA provenance statement BuildKit attached to the manifest `digest`,
+/// if the archive kept its attestation manifest.
+fn provenance_statement<'a>(
+    index: &OciIndex,
+    digest: &str,
+    blobs: &'a HashMap<String, Vec<u8>>,
+) -> Option<&'a [u8]> {
+    let attestation = index.manifests.iter().find(|d| {
+        d.is_attestation() && d.annotations.get(REFERENCE_DIGEST).is_some_and(|r| r == digest)
+    })?;
+    let manifest: OciManifest = serde_json::from_slice(blobs.get(&attestation.digest)?).ok()?;
+    let layer = manifest.layers.iter().find(|l| {
+        l.annotations
+            .get(PREDICATE_TYPE)
+            .is_some_and(|t| t.contains("slsa.dev/provenance"))
+    })?;
+    blobs.get(&layer.digest).map(Vec::as_slice)
+}
+
+// ---- Layer parsing (shared by both formats) ----
+
+/// Open the layer blob at `offset` in the archive as stored.
+pub fn open_blob(path: &Path, offset: u64, size: u64) -> Result<Take<File>> {
+    let mut file = File::open(path)
+        .with_context(|| format!("Failed to open {}", path.display()))?;
+    file.seek(SeekFrom::Start(offset))?;
+    Ok(file.take(size))
+}
+
+/// Open the layer blob at `offset` in the archive as an uncompressed tar stream.
+pub fn open_layer(path: &Path, offset: u64, size: u64) -> Result<Box<dyn Read>> {
+    let mut reader = BufReader::new(open_blob(path, offset, size)?);
+    let is_gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+
+    if is_gzip {
+        Ok(Box::new(flate2::read::GzDecoder::new(reader)))
+    } else {
+        Ok(Box::new(reader))
+    }
+}
+
+/// Read a layer tar and list the files `listing` keeps, sorted by path
+/// (auto-detects gzip).
+pub fn parse_layer_entry<R: Read>(entry: R, listing: &Listing) -> Result<Vec<FileEntry>> {
+    let mut files = Vec::new();
+    each_layer_file(entry, listing, &mut |file| {
+        files.push(file);
+        Ok(())
+    })?;
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Total size of a layer tar's files (auto-detects gzip), reading headers
+/// only where it can.
+fn layer_size<R: Read>(entry: R) -> Result<u64> {
+    let mut reader = BufReader::new(entry);
+    let is_gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+    if is_gzip {
+        sum_sizes(flate2::read::GzDecoder::new(reader))
+    } else {
+        sum_sizes(reader)
+    }
+}
+
+fn sum_sizes<R: Read>(reader: R) -> Result<u64> {
+    let mut total = 0;
+    for entry in tar::Archive::new(reader).entries()?.flatten() {
+        let header = entry.header();
+        let is_whiteout = entry
+            .path()
+            .ok()
+            .and_then(|p| p.file_name().map(|n| n.to_string_lossy().starts_with(".wh.")))
+            .unwrap_or(false);
+        if !header.entry_type().is_dir() && !is_whiteout {
+            total += entry.size();
+        }
+    }
+    Ok(total)
+}
+
+/// Call `each` with every file of a layer tar (auto-detects gzip) that
+/// `listing` keeps, as it is read, in archive order, holding one entry at a
+/// time. Stops at the first error `each` returns.
+pub fn each_layer_file<R: Read>(
+    reader: R,
+    listing: &Listing,
+    each: &mut dyn FnMut(FileEntry) -> Result<()>,
+) -> Result<()> {
+    let mut reader = BufReader::new(reader);
+    let is_gzip = reader.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+
+    if is_gzip {
+        each_inner_file(flate2::read::GzDecoder::new(reader), listing, each)
+    } else {
+        each_inner_file(reader, listing, each)
+    }
+}
+
+fn each_inner_file<R: Read>(
+    reader: R,
+    listing: &Listing,
+    each: &mut dyn FnMut(FileEntry) -> Result<()>,
+) -> Result<()> {
+    let mut archive = tar::Archive::new(reader);
+
+    for entry_result in archive.entries()? {
+        let mut entry = match entry_result {
+            Ok(e) => e,
+            Err(_) => continue,
+        };
+
+        if entry.header().entry_type().is_dir() {
+            continue;
+        }
+
+        let path = match entry.path() {
+            Ok(p) => p.to_path_buf(),
+            Err(_) => continue,
+        };
+
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let is_whiteout = name.starts_with(".wh.");
+        if !listing.roots.keeps(&path, is_whiteout) {
+            continue;
+        }
+        let size = if is_whiteout { 0 } else { entry.size() };
+        let is_file = !is_whiteout && entry.header().entry_type().is_file();
+        let (mode, uid, gid) = if is_file {
+            let header = entry.header();
+            (
+                header.mode().ok().map(|m| m & 0o7777),
+                header.uid().ok().and_then(|id| u32::try_from(id).ok()),
+                header.gid().ok().and_then(|id| u32::try_from(id).ok()),
+            )
+        } else {
+            (None, None, None)
+        };
+        let scan = if is_file {
+            checksum::scan(&mut entry, size, listing.checksums)
+        } else {
+            checksum::Scan::default()
+        };
+
+        each(FileEntry {
+            path,
+            size,
+            is_whiteout,
+            change: FileChange::from_whiteout(is_whiteout),
+            entropy: scan.entropy,
+            sha256: scan.sha256,
+            elf: scan.elf,
+            mode,
+            uid,
+            gid,
+        })?;
+    }
+    Ok(())
+}
+
+// ---- Helpers ----
+
+/// Parse `name:tag` handling registry port syntax (`registry:5000/foo:bar`).
+pub fn parse_image_ref(image: &str) -> (String, String) {
+    if let Some((n, t)) = image.rsplit_once(':') {
+        if t.contains('/') {
+            (image.to_string(), "latest".to_string())
+        } else {
+            (n.to_string(), t.to_string())
+        }
+    } else {
+        (image.to_string(), "latest".to_string())
+    }
+}
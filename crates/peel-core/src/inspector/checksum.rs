@@ -0,0 +1,76 @@
+use std::io::{self, Read};
+use std::path::Path;
+
+use sha2::{Digest, Sha256};
+
+use super::entropy;
+
+/// Hashes everything read through it, so a file can be sampled for entropy
+/// and checksummed in a single pass.
+pub struct HashingReader<R: Read> {
+    inner: R,
+    hasher: Sha256,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+        }
+    }
+
+    /// Read whatever is left and return `sha256:<hex>` of the whole stream.
+    pub fn finish(mut self) -> io::Result<String> {
+        io::copy(&mut self, &mut io::sink())?;
+        Ok(format!("sha256:{:x}", self.hasher.finalize()))
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Leading bytes of ELF executables and shared objects.
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+/// What reading a regular file's content found.
+#[derive(Debug, Default)]
+pub struct Scan {
+    pub entropy: Option<f32>,
+    pub sha256: Option<String>,
+    pub elf: bool,
+}
+
+/// Whether `scan` has anything to find in a file, for backends where opening
+/// every file costs a syscall. Only files whose name doesn't already say
+/// what they are are checked for ELF magic.
+pub fn wanted(path: &Path, size: u64, checksums: bool) -> bool {
+    let no_extension = path.extension().is_none();
+    checksums || size >= entropy::MIN_SIZE || (no_extension && size >= ELF_MAGIC.len() as u64)
+}
+
+/// ELF magic, entropy sample (files of `entropy::MIN_SIZE` or more) and, if
+/// `checksums` is set, the sha256 of a regular file's content, reading it
+/// only once.
+pub fn scan(reader: impl Read, size: u64, checksums: bool) -> Scan {
+    let sample = size >= entropy::MIN_SIZE;
+    let mut reader = HashingReader::new(reader);
+    let mut head = Vec::with_capacity(ELF_MAGIC.len());
+    if (&mut reader).take(ELF_MAGIC.len() as u64).read_to_end(&mut head).is_err() {
+        return Scan::default();
+    }
+    // The head is already hashed, so the sample rereads it from the buffer
+    let entropy = sample
+        .then(|| entropy::sample(head.as_slice().chain(&mut reader)))
+        .flatten();
+    Scan {
+        entropy,
+        sha256: checksums.then(|| reader.finish().ok()).flatten(),
+        elf: head == ELF_MAGIC,
+    }
+}
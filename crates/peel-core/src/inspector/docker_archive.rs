@@ -0,0 +1,85 @@
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+
+use super::archive;
+use super::{FileEntry, ImageInfo, Inspector, LayerInfo, Listing};
+
+/// Reads layers from a pre-existing tar archive (`docker save`, `podman save`,
+/// `ctr image export`, or any OCI-layout tar).
+pub struct DockerArchiveInspector {
+    archive_path: PathBuf,
+    listing: Listing,
+    layer_offsets: HashMap<String, (u64, u64)>,
+    config: Option<Vec<u8>>,
+}
+
+impl DockerArchiveInspector {
+    pub fn new(archive_path: PathBuf, listing: Listing) -> Self {
+        Self {
+            archive_path,
+            listing,
+            layer_offsets: HashMap::new(),
+            config: None,
+        }
+    }
+}
+
+impl Inspector for DockerArchiveInspector {
+    fn inspect(&mut self, _image: &str) -> Result<ImageInfo> {
+        let filename = self
+            .archive_path
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        let result = archive::parse_archive(
+            &self.archive_path,
+            &filename,
+            "",
+            None,
+            &mut None,
+        )
+        .with_context(|| format!("Failed to parse archive {}", self.archive_path.display()))?;
+
+        self.layer_offsets = result.layer_offsets;
+        self.config = result.config;
+
+        Ok(result.info)
+    }
+
+    fn list_files(&mut self, layer: &LayerInfo) -> Result<Vec<FileEntry>> {
+        archive::parse_layer_entry(self.read_layer(layer)?, &self.listing)
+            .with_context(|| format!("Failed to parse layer {}", layer.digest))
+    }
+
+    fn for_each_file(
+        &mut self,
+        layer: &LayerInfo,
+        each: &mut dyn FnMut(FileEntry) -> Result<()>,
+    ) -> Result<()> {
+        archive::each_layer_file(self.read_layer(layer)?, &self.listing, each)
+    }
+
+    fn read_layer(&mut self, layer: &LayerInfo) -> Result<Box<dyn Read>> {
+        let (offset, size) = self
+            .layer_offsets
+            .get(&layer.digest)
+            .with_context(|| format!("Layer {} not found in archive", layer.digest))?;
+        archive::open_layer(&self.archive_path, *offset, *size)
+    }
+
+    fn read_blob(&mut self, layer: &LayerInfo) -> Result<Option<Box<dyn Read>>> {
+        let (offset, size) = self
+            .layer_offsets
+            .get(&layer.digest)
+            .with_context(|| format!("Layer {} not found in archive", layer.digest))?;
+        Ok(Some(Box::new(archive::open_blob(&self.archive_path, *offset, *size)?)))
+    }
+
+    fn config(&self) -> Option<&[u8]> {
+        self.config.as_deref()
+    }
+}
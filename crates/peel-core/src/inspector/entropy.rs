@@ -0,0 +1,63 @@
+use std::io::Read;
+
+/// Files smaller than this are never sampled; small high-entropy files (keys,
+/// hashes, icons) are common and not what this check is for.
+pub const MIN_SIZE: u64 = 1024 * 1024;
+
+/// Bytes read from the start of a file to estimate its entropy.
+const SAMPLE_SIZE: usize = 64 * 1024;
+
+/// Leading bytes of formats that are expected to look random: compressed
+/// archives, images, audio/video and fonts.
+const KNOWN_MAGIC: &[&[u8]] = &[
+    b"\x1f\x8b",             // gzip
+    b"PK\x03\x04",           // zip, jar, apk, whl
+    b"BZh",                  // bzip2
+    b"\xfd7zXZ\x00",         // xz
+    b"\x28\xb5\x2f\xfd",     // zstd
+    b"\x04\x22\x4d\x18",     // lz4
+    b"7z\xbc\xaf\x27\x1c",   // 7z
+    b"Rar!",                 // rar
+    b"\x89PNG",              // png
+    b"\xff\xd8\xff",         // jpeg
+    b"GIF8",                 // gif
+    b"RIFF",                 // webp, wav, avi
+    b"OggS",                 // ogg
+    b"ID3",                  // mp3
+    b"fLaC",                 // flac
+    b"%PDF",                 // pdf (compressed streams)
+    b"wOFF",                 // woff
+    b"wOF2",                 // woff2
+    b"\x1a\x45\xdf\xa3",     // mkv, webm
+];
+
+/// Shannon entropy (bits per byte, 0.0–8.0) of the start of a file that is
+/// not a recognized compressed or media format. `None` if the format is
+/// recognized or nothing could be read.
+pub fn sample(reader: impl Read) -> Option<f32> {
+    let mut buf = Vec::with_capacity(SAMPLE_SIZE);
+    reader.take(SAMPLE_SIZE as u64).read_to_end(&mut buf).ok()?;
+    if buf.is_empty() || is_known_format(&buf) {
+        return None;
+    }
+
+    let mut counts = [0u32; 256];
+    for &b in &buf {
+        counts[b as usize] += 1;
+    }
+    let len = buf.len() as f32;
+    let entropy = counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = c as f32 / len;
+            -p * p.log2()
+        })
+        .sum();
+    Some(entropy)
+}
+
+fn is_known_format(head: &[u8]) -> bool {
+    // ISO base media (mp4, mov, heic, avif) keeps its tag at offset 4
+    KNOWN_MAGIC.iter().any(|m| head.starts_with(m)) || head.get(4..8) == Some(b"ftyp")
+}
@@ -0,0 +1,112 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Dockerfile instructions a `created_by` can start with.
+const KEYWORDS: &[&str] = &[
+    "ADD",
+    "ARG",
+    "CMD",
+    "COPY",
+    "ENTRYPOINT",
+    "ENV",
+    "EXPOSE",
+    "HEALTHCHECK",
+    "LABEL",
+    "MAINTAINER",
+    "ONBUILD",
+    "RUN",
+    "SHELL",
+    "STOPSIGNAL",
+    "USER",
+    "VOLUME",
+    "WORKDIR",
+];
+
+/// A layer's `created_by`, split into the Dockerfile instruction and what
+/// follows it.
+///
+/// The classic builder records `RUN apt-get update` as
+/// `/bin/sh -c apt-get update`, and every other instruction as
+/// `/bin/sh -c #(nop) COPY …`; BuildKit records `RUN /bin/sh -c apt-get
+/// update # buildkit`. Both prefix RUN with the build args in effect, as
+/// `|2 A=1 B=2`. All of that is removed here.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Instruction {
+    /// Upper case, e.g. "RUN", "COPY"
+    pub instruction: String,
+
+    /// e.g. "apt-get update" for RUN, "--from=build /out /app" for COPY
+    pub arguments: String,
+}
+
+impl Instruction {
+    /// `None` for history written by other tools, which doesn't start
+    /// with a Dockerfile instruction.
+    pub fn parse(created_by: &str) -> Option<Self> {
+        let text = created_by.trim();
+        let text = text.strip_suffix("# buildkit").unwrap_or(text).trim_end();
+
+        if let Some(rest) = text.strip_prefix("/bin/sh -c #(nop)") {
+            return Self::split(rest.trim_start());
+        }
+        // The classic builder's RUN: the shell command, maybe after build args
+        if text.starts_with("/bin/sh -c ") || build_arg_count(text).is_some() {
+            return Some(Self::run(text));
+        }
+        Self::split(text)
+    }
+
+    fn split(text: &str) -> Option<Self> {
+        let (keyword, arguments) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let keyword = keyword.to_ascii_uppercase();
+        if !KEYWORDS.contains(&keyword.as_str()) {
+            return None;
+        }
+        if keyword == "RUN" {
+            return Some(Self::run(arguments));
+        }
+        Some(Self {
+            instruction: keyword,
+            arguments: arguments.trim().to_string(),
+        })
+    }
+
+    /// RUN with the build args and `/bin/sh -c` taken off the command.
+    fn run(text: &str) -> Self {
+        let mut text = text.trim();
+        if let Some(count) = build_arg_count(text) {
+            // `|N` and then N `NAME=value` words
+            for _ in 0..=count {
+                text = text
+                    .split_once(char::is_whitespace)
+                    .map_or("", |(_, rest)| rest)
+                    .trim_start();
+            }
+        }
+        let command = text.strip_prefix("/bin/sh -c ").unwrap_or(text);
+        Self {
+            instruction: "RUN".to_string(),
+            arguments: command.trim().to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.arguments.is_empty() {
+            write!(f, "{}", self.instruction)
+        } else {
+            write!(f, "{} {}", self.instruction, self.arguments)
+        }
+    }
+}
+
+/// N from a leading `|N`, the number of build args recorded before a RUN.
+fn build_arg_count(text: &str) -> Option<usize> {
+    text.strip_prefix('|')?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
+}
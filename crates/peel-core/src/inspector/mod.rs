@@ -0,0 +1,423 @@
+pub mod archive;
+pub mod checksum;
+pub mod docker_archive;
+pub mod entropy;
+pub mod instruction;
+pub mod oci;
+pub mod provenance;
+pub mod run_config;
+
+#[cfg(target_os = "linux")]
+pub mod overlay2;
+
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use instruction::Instruction;
+use provenance::Provenance;
+use run_config::RunConfig;
+
+/// Full inspection result for a container image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageInfo {
+    /// Image reference as provided by the user (e.g. "nginx:latest", "./image.tar")
+    pub name: String,
+
+    /// Image tag (e.g. "latest")
+    pub tag: Option<String>,
+
+    /// Image ID: `sha256:<hex>` of the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// `sha256:<hex>` digests a registry knows the image by: its manifest's,
+    /// and the index's for a multi-platform image. Empty when the backend
+    /// has neither
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub manifest_digests: Vec<String>,
+
+    /// Target architecture (e.g. "amd64")
+    pub architecture: Option<String>,
+
+    /// When the image was built, RFC 3339 as the config records it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// Distribution named by the final filesystem's release files; filled
+    /// in by `peel inspect` after the layers are listed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub distro: Option<Distro>,
+
+    /// How the image was built, when the build left provenance behind
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+
+    /// `Labels` from the image config, e.g. `org.opencontainers.image.source`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub labels: BTreeMap<String, String>,
+
+    /// Annotations on the OCI manifest, and on its entry in the index; only
+    /// backends with the manifest have them
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub annotations: BTreeMap<String, String>,
+
+    /// Entrypoint, environment, user and the rest of how a container runs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_config: Option<RunConfig>,
+
+    /// Total uncompressed size across all layers, in bytes
+    pub total_size: u64,
+
+    /// Layers in order (base first)
+    pub layers: Vec<LayerInfo>,
+
+    /// Subtrees the inspection was limited to with `--root`; when set, sizes
+    /// only count files below them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<PathBuf>,
+}
+
+impl ImageInfo {
+    /// Total compressed size, if every layer's is known.
+    pub fn compressed_size(&self) -> Option<u64> {
+        self.layers.iter().map(|l| l.compressed_size).sum()
+    }
+}
+
+/// The distribution an image is built on.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Distro {
+    /// e.g. "debian", "alpine"
+    pub id: String,
+
+    /// e.g. "12", "3.20.1"
+    pub version_id: Option<String>,
+
+    /// Human-readable name, e.g. "Debian GNU/Linux 12 (bookworm)"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+impl Distro {
+    /// The name to show: `PRETTY_NAME` if the image has one, else id and version.
+    pub fn label(&self) -> String {
+        match (&self.name, &self.version_id) {
+            (Some(name), _) => name.clone(),
+            (None, Some(version)) => format!("{} {version}", self.id),
+            (None, None) => self.id.clone(),
+        }
+    }
+}
+
+/// Metadata about a single layer in an image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LayerInfo {
+    /// Layer digest (e.g. sha256:abc123...)
+    pub digest: String,
+
+    /// The Dockerfile command that created this layer (if available)
+    pub created_by: Option<String>,
+
+    /// `created_by` split into instruction and arguments, without the shell
+    /// wrapper and build-arg prefix builders add; absent if it doesn't
+    /// start with a Dockerfile instruction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<Instruction>,
+
+    /// When the builder recorded this layer's history entry, RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    /// Total size of files in this layer, in bytes; what it takes on disk
+    pub size: u64,
+
+    /// Size of the compressed layer blob, as stored in a registry and
+    /// transferred on pull. Only known when the backend has the manifest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<u64>,
+
+    /// Files in this layer (populated separately via list_files)
+    pub files: Vec<FileEntry>,
+}
+
+impl LayerInfo {
+    /// Compressed over uncompressed size; lower compresses better. `None`
+    /// when the compressed size isn't known or the layer is empty.
+    pub fn compression_ratio(&self) -> Option<f64> {
+        let compressed = self.compressed_size?;
+        (self.size > 0).then(|| compressed as f64 / self.size as f64)
+    }
+}
+
+/// A single file entry within a layer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileEntry {
+    /// Full path within the layer
+    pub path: PathBuf,
+
+    /// File size in bytes
+    pub size: u64,
+
+    /// Whether this is a whiteout (deletion marker)
+    pub is_whiteout: bool,
+
+    /// What this entry does to the filesystem of the layers below it
+    #[serde(default)]
+    pub change: FileChange,
+
+    /// Sampled entropy in bits per byte, for large files in no recognized
+    /// compressed or media format
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entropy: Option<f32>,
+
+    /// `sha256:<hex>` of the content of regular files, with `--checksums`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    /// Content starts with the ELF magic (programs and shared objects)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub elf: bool,
+
+    /// Permission bits including setuid, setgid and sticky (`mode & 0o7777`);
+    /// regular files only, since symlink modes mean nothing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+
+    /// Owner and group of regular files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+}
+
+/// How a layer entry changes the filesystem built from the layers below it.
+///
+/// Backends only know whiteouts from regular files, so they report `Added` or
+/// `Deleted`; `analysis::merged::classify` fills in `Modified` once every
+/// layer has been listed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileChange {
+    /// New path not present in any lower layer
+    #[default]
+    Added,
+    /// Replaces a path from a lower layer
+    Modified,
+    /// Whiteout hiding a lower path
+    Deleted,
+}
+
+impl FileChange {
+    /// Initial classification from the entry alone, before lower layers are known.
+    pub fn from_whiteout(is_whiteout: bool) -> Self {
+        if is_whiteout { FileChange::Deleted } else { FileChange::Added }
+    }
+}
+
+/// What a backend includes in file listings; each backend takes one when
+/// it's created. The default lists everything, without checksums.
+#[derive(Debug, Clone, Default)]
+pub struct Listing {
+    /// Only list files below these subtrees
+    pub roots: Roots,
+
+    /// Hash the content of every regular file
+    pub checksums: bool,
+}
+
+/// Strip `./` and `/` prefixes so paths from every backend compare equal.
+pub fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect()
+}
+
+/// Subtrees of the image filesystem to restrict file listings to (`--root`).
+/// Empty means the whole filesystem.
+#[derive(Debug, Clone, Default)]
+pub struct Roots(Vec<PathBuf>);
+
+impl Roots {
+    pub fn new(paths: &[String]) -> Self {
+        Self(paths.iter().map(|p| normalize(Path::new(p))).collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.0
+    }
+
+    /// Whether a layer entry belongs in the listing. A whiteout is kept if
+    /// what it deletes overlaps a root, since it can hide files below one.
+    pub fn keeps(&self, path: &Path, is_whiteout: bool) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        let path = normalize(path);
+        if !is_whiteout {
+            return self.0.iter().any(|root| path.starts_with(root));
+        }
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        let parent = path.parent().unwrap_or(Path::new(""));
+        let target = match name.strip_prefix(".wh.") {
+            Some(".wh..opq") | None => parent.to_path_buf(),
+            Some(hidden) => parent.join(hidden),
+        };
+        self.may_contain(&target)
+    }
+
+    /// Whether anything at or below `dir` can be inside a root, so walks can
+    /// skip directories that can't.
+    pub fn may_contain(&self, dir: &Path) -> bool {
+        let dir = normalize(dir);
+        self.is_empty()
+            || self
+                .0
+                .iter()
+                .any(|root| root.starts_with(&dir) || dir.starts_with(root))
+    }
+}
+
+/// Common interface for reading image layers from different backends.
+pub trait Inspector {
+    /// Inspect an image and return full metadata with layers.
+    fn inspect(&mut self, image: &str) -> Result<ImageInfo>;
+
+    /// Like `inspect`, but may skip whatever only `list_files` and the
+    /// readers need, such as saving the image; those can't be used after it.
+    fn inspect_metadata(&mut self, image: &str) -> Result<ImageInfo> {
+        self.inspect(image)
+    }
+
+    /// List all files in a specific layer.
+    fn list_files(&mut self, layer: &LayerInfo) -> Result<Vec<FileEntry>>;
+
+    /// Call `each` with the files of a layer one at a time, in the order the
+    /// layer stores them, so huge layers can be filtered or summed without
+    /// holding their listing. Stops at the first error `each` returns.
+    ///
+    /// The default reads the layer's tar from `read_layer` and lists
+    /// everything, without checksums.
+    fn for_each_file(
+        &mut self,
+        layer: &LayerInfo,
+        each: &mut dyn FnMut(FileEntry) -> Result<()>,
+    ) -> Result<()> {
+        archive::each_layer_file(self.read_layer(layer)?, &Listing::default(), each)
+    }
+
+    /// Stream a layer's contents as an uncompressed tar. Can be called more
+    /// than once per layer.
+    fn read_layer(&mut self, layer: &LayerInfo) -> Result<Box<dyn Read>>;
+
+    /// Stream a layer blob exactly as stored (possibly compressed), or `None`
+    /// if the backend has no blob, only an unpacked directory.
+    fn read_blob(&mut self, layer: &LayerInfo) -> Result<Option<Box<dyn Read>>>;
+
+    /// Raw image config JSON, if the backend has it.
+    fn config(&self) -> Option<&[u8]>;
+}
+
+/// Receives progress from slow backend steps, such as saving an image and
+/// parsing its layers; the CLI draws it as spinners and bars.
+pub trait Progress {
+    /// `done` finished and `next` started, with no known length.
+    fn step(&self, done: &str, next: &str);
+
+    /// `message` started, counting up to `total` in `unit` ("bytes" or
+    /// "layers").
+    fn start(&self, message: &str, total: u64, unit: &str);
+
+    /// `amount` more units are done.
+    fn advance(&self, amount: u64);
+}
+
+#[derive(Deserialize)]
+struct LabelsConfig {
+    config: Option<LabelsRunConfig>,
+}
+
+#[derive(Deserialize)]
+struct LabelsRunConfig {
+    #[serde(rename = "Labels")]
+    labels: Option<BTreeMap<String, String>>,
+}
+
+/// `Labels` from a raw image config; empty if there are none or the config
+/// can't be read.
+pub fn config_labels(config: Option<&[u8]>) -> BTreeMap<String, String> {
+    config
+        .and_then(|c| serde_json::from_slice::<LabelsConfig>(c).ok())
+        .and_then(|c| c.config?.labels)
+        .unwrap_or_default()
+}
+
+/// Image ID of a raw image config: `sha256:<hex>` of its bytes.
+pub fn config_digest(config: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(config))
+}
+
+/// Seconds since the epoch of an RFC 3339 timestamp as image configs record
+/// them, e.g. `2024-05-01T12:00:00.123456789Z` or `…T14:00:00+02:00`.
+pub fn parse_created(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let number = |range: std::ops::Range<usize>| text.get(range)?.parse::<i64>().ok();
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if !matches!(text.get(10..11), Some("T" | "t" | " ")) || !(1..=12).contains(&month) {
+        return None;
+    }
+
+    // Skip fractional seconds, then read the offset
+    let zone = text[19..].trim_start_matches(|c: char| c == '.' || c.is_ascii_digit());
+    let offset = match zone {
+        "Z" | "z" | "" => 0,
+        _ => {
+            let sign = match zone.get(..1)? {
+                "+" => 1,
+                "-" => -1,
+                _ => return None,
+            };
+            let hours: i64 = zone.get(1..3)?.parse().ok()?;
+            let minutes: i64 = zone.get(4..6)?.parse().ok()?;
+            sign * (hours * 3600 + minutes * 60)
+        }
+    };
+
+    // Howard Hinnant's days-from-civil
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    Some(days * 86400 + hour * 3600 + minute * 60 + second - offset)
+}
+
+/// Compute chain IDs from diff IDs, which is how runtimes key unpacked layers:
+/// the same diff on top of a different parent is a different layer on disk.
+///
+/// chain\[0\] = diff\[0\]
+/// chain\[i\] = sha256(chain\[i-1\] + " " + diff\[i\])
+pub fn chain_ids(diff_ids: &[String]) -> Vec<String> {
+    let mut chain_ids: Vec<String> = Vec::with_capacity(diff_ids.len());
+    for (i, diff_id) in diff_ids.iter().enumerate() {
+        if i == 0 {
+            chain_ids.push(diff_id.clone());
+        } else {
+            let input = format!("{} {}", chain_ids[i - 1], diff_id);
+            let hash = Sha256::digest(input.as_bytes());
+            chain_ids.push(format!("sha256:{hash:x}"));
+        }
+    }
+    chain_ids
+}
@@ -1,22 +1,37 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::io::{Read, Write};
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use anyhow::{bail, Context, Result};
-use indicatif::ProgressBar;
 use serde::Deserialize;
 
 use super::archive::{self, ArchiveResult};
-use super::{FileEntry, ImageInfo, Inspector, LayerInfo};
+use super::instruction::Instruction;
+use super::{FileEntry, ImageInfo, Inspector, LayerInfo, Listing, Progress};
 use crate::probe::RuntimeKind;
+use crate::temp_files;
 
 // --- Docker CLI JSON output ---
 
 #[derive(Deserialize)]
 struct DockerInspect {
+    /// `sha256:<hex>` from Docker, bare hex from Podman
+    #[serde(rename = "Id", default)]
+    id: Option<String>,
+    /// Manifest digest; Podman only
+    #[serde(rename = "Digest", default)]
+    digest: Option<String>,
+    /// `name@sha256:<hex>`, one per repository the image was pulled from or
+    /// pushed to
+    #[serde(rename = "RepoDigests", default)]
+    repo_digests: Option<Vec<String>>,
     #[serde(rename = "Architecture")]
     architecture: Option<String>,
+    #[serde(rename = "Created", default)]
+    created: Option<String>,
     #[serde(rename = "Size", default)]
     size: u64,
     #[serde(rename = "RootFS")]
@@ -33,81 +48,77 @@ struct InspectRootFS {
 struct HistoryLine {
     #[serde(rename = "CreatedBy", default)]
     created_by: Option<String>,
+    #[serde(rename = "CreatedAt", default)]
+    created_at: Option<String>,
     #[serde(rename = "Size", default)]
     size: String,
 }
 
+/// A layer's `(created_by, created, size)` from `docker image history`.
+type LayerHistory = (Option<String>, Option<String>, u64);
+
 /// Reads layers via the container runtime CLI (`docker`/`podman`/`ctr`).
 /// Cross-platform, no root needed, but slower (requires CLI calls).
 pub struct OciInspector {
     cmd: String,
     kind: RuntimeKind,
-    cached_files: HashMap<String, Vec<FileEntry>>,
-    cache_populated: bool,
-    progress: Option<ProgressBar>,
+    listing: Listing,
+    progress: Option<Rc<dyn Progress>>,
+    /// The saved image, kept until drop so layers can be re-read
+    saved: Option<PathBuf>,
+    layer_offsets: HashMap<String, (u64, u64)>,
+    config: Option<Vec<u8>>,
 }
 
 impl OciInspector {
-    pub fn new(cmd: String, kind: RuntimeKind) -> Self {
+    pub fn new(cmd: String, kind: RuntimeKind, listing: Listing) -> Self {
         Self {
             cmd,
             kind,
-            cached_files: HashMap::new(),
-            cache_populated: false,
+            listing,
             progress: None,
+            saved: None,
+            layer_offsets: HashMap::new(),
+            config: None,
         }
     }
 
-    /// Attach a progress bar (clone of a Spinner's inner bar) for status updates.
-    pub fn set_progress_bar(&mut self, bar: ProgressBar) {
-        self.progress = Some(bar);
+    /// Report saving and parsing to `progress`.
+    pub fn set_progress(&mut self, progress: Rc<dyn Progress>) {
+        self.progress = Some(progress);
     }
 
     fn finish_step(&self, done_msg: impl Into<String>, next_msg: impl Into<String>) {
-        if let Some(bar) = &self.progress {
-            use crossterm::style::Stylize;
-            bar.finish_and_clear();
-            eprintln!("{} {}", "✔".green(), done_msg.into());
-            bar.reset();
-            bar.set_style(
-                indicatif::ProgressStyle::default_spinner()
-                    .template("{spinner:.dim} {msg}")
-                    .unwrap(),
-            );
-            bar.set_message(next_msg.into());
-            bar.enable_steady_tick(std::time::Duration::from_millis(80));
+        if let Some(progress) = &self.progress {
+            progress.step(&done_msg.into(), &next_msg.into());
         }
     }
 
     fn start_parse_progress(&self, total: u64) {
-        if let Some(bar) = &self.progress {
-            bar.set_length(total);
-            bar.set_position(0);
-            bar.set_style(
-                indicatif::ProgressStyle::with_template(
-                    "{spinner:.dim} Parsing layers [{bar:20}] {pos}/{len} ({elapsed_precise:.>5})",
-                )
-                .unwrap()
-                .with_key("elapsed_precise", |state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write| {
-                    let _ = write!(w, "{}s", state.elapsed().as_secs());
-                })
-                .progress_chars("━╸░"),
-            );
+        if let Some(progress) = &self.progress {
+            progress.start("Parsing layers", total, "layers");
         }
     }
 
     fn make_progress_callback(&self) -> Option<archive::OnLayerParsed> {
-        self.progress.clone().map(|bar| {
-            Box::new(move || bar.inc(1)) as archive::OnLayerParsed
-        })
+        self.progress
+            .clone()
+            .map(|progress| Box::new(move || progress.advance(1)) as archive::OnLayerParsed)
     }
 
+    /// Unique per call: commands that compare images keep several saves open.
+    /// Tracked, so an interrupted save doesn't leave the archive behind.
     fn temp_path() -> PathBuf {
-        std::env::temp_dir().join(format!("peel-save-{}.tar", std::process::id()))
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let n = NEXT.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("peel-save-{}-{n}.tar", std::process::id()));
+        temp_files::track(&path);
+        path
     }
 
     /// Save/export the image to a temp file.
     fn save_to_file(&self, image: &str, total_size: Option<u64>) -> Result<PathBuf> {
+        let _phase = tracing::debug_span!("save").entered();
         match self.kind {
             RuntimeKind::Containerd => self.save_via_export(image),
             RuntimeKind::Docker | RuntimeKind::Podman => self.save_via_pipe(image, total_size),
@@ -119,12 +130,14 @@ impl OciInspector {
         let tmp = Self::temp_path();
         let tmp_str = tmp.to_string_lossy();
 
-        let output = Command::new(&self.cmd)
-            .args(["image", "export", &tmp_str, image])
+        let mut cmd = Command::new(&self.cmd);
+        cmd.args(["image", "export", &tmp_str, image]);
+        tracing::debug!("running {cmd:?}");
+        let output = cmd
             .output()
             .with_context(|| format!("Failed to run '{} image export'", self.cmd))?;
         if !output.status.success() {
-            let _ = std::fs::remove_file(&tmp);
+            temp_files::remove(&tmp);
             let stderr = String::from_utf8_lossy(&output.stderr);
             bail!("Failed to export '{}': {}", image, stderr.trim());
         }
@@ -140,6 +153,7 @@ impl OciInspector {
         if matches!(self.kind, RuntimeKind::Podman) {
             cmd.arg("--format=docker-archive");
         }
+        tracing::debug!("running {cmd:?}");
 
         let mut child = cmd
             .stdout(Stdio::piped())
@@ -151,19 +165,8 @@ impl OciInspector {
         let mut file = std::fs::File::create(&tmp)
             .with_context(|| format!("Failed to create {}", tmp.display()))?;
 
-        if let (Some(bar), Some(total)) = (&self.progress, total_size.filter(|&s| s > 0)) {
-            bar.set_length(total);
-            bar.set_position(0);
-            bar.set_style(
-                indicatif::ProgressStyle::with_template(
-                    "{spinner:.dim} {msg} [{bar:20}] {bytes}/{total_bytes} ({elapsed_precise:.>5})",
-                )
-                .unwrap()
-                .with_key("elapsed_precise", |state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write| {
-                    let _ = write!(w, "{}s", state.elapsed().as_secs());
-                })
-                .progress_chars("━╸░"),
-            );
+        if let (Some(progress), Some(total)) = (&self.progress, total_size.filter(|&s| s > 0)) {
+            progress.start(&format!("Saving {} ...", image), total, "bytes");
 
             let mut buf = [0u8; 64 * 1024];
             loop {
@@ -172,7 +175,7 @@ impl OciInspector {
                     break;
                 }
                 file.write_all(&buf[..n])?;
-                bar.inc(n as u64);
+                progress.advance(n as u64);
             }
         } else {
             std::io::copy(&mut stdout, &mut file)?;
@@ -182,7 +185,7 @@ impl OciInspector {
         drop(stdout);
         let status = child.wait()?;
         if !status.success() {
-            let _ = std::fs::remove_file(&tmp);
+            temp_files::remove(&tmp);
             let mut stderr_str = String::new();
             if let Some(mut stderr) = child.stderr.take() {
                 let _ = stderr.read_to_string(&mut stderr_str);
@@ -194,19 +197,22 @@ impl OciInspector {
     }
 
     fn store_result(&mut self, result: ArchiveResult) -> ImageInfo {
-        self.cached_files = result.files;
-        self.cache_populated = true;
+        self.layer_offsets = result.layer_offsets;
+        self.config = result.config;
         result.info
     }
 
     // ---- Docker / Podman: fast metadata via CLI ----
 
-    fn inspect_via_cli(&mut self, image: &str) -> Result<ImageInfo> {
-        let (name, tag) = archive::parse_image_ref(image);
-
+    /// `docker image inspect` and `docker image history`, with the history
+    /// reduced to one `(created_by, created, size)` per layer.
+    fn read_cli_metadata(&self, image: &str) -> Result<(DockerInspect, Vec<LayerHistory>)> {
+        let _phase = tracing::debug_span!("resolve").entered();
         // `docker image inspect`
-        let inspect_out = Command::new(&self.cmd)
-            .args(["image", "inspect", image, "--format", "{{json .}}"])
+        let mut cmd = Command::new(&self.cmd);
+        cmd.args(["image", "inspect", image, "--format", "{{json .}}"]);
+        tracing::debug!("running {cmd:?}");
+        let inspect_out = cmd
             .output()
             .with_context(|| {
                 format!("Failed to run '{} image inspect'", self.cmd)
@@ -225,13 +231,14 @@ impl OciInspector {
         let json = String::from_utf8_lossy(&inspect_out.stdout);
         let di: DockerInspect =
             serde_json::from_str(json.trim()).context("Failed to parse docker inspect JSON")?;
-        let diff_ids = di.rootfs.layers;
 
         // `docker image history`
-        let history_out = Command::new(&self.cmd)
-            .args([
-                "image", "history", image, "--no-trunc", "--format", "{{json .}}",
-            ])
+        let mut cmd = Command::new(&self.cmd);
+        cmd.args([
+            "image", "history", image, "--no-trunc", "--format", "{{json .}}",
+        ]);
+        tracing::debug!("running {cmd:?}");
+        let history_out = cmd
             .output()
             .with_context(|| {
                 format!("Failed to run '{} image history'", self.cmd)
@@ -263,12 +270,20 @@ impl OciInspector {
         history_entries.reverse();
 
         // Non-empty history entries correspond 1:1 to diff_ids
-        let non_empty: Vec<(Option<String>, u64)> = history_entries
+        let non_empty = history_entries
             .iter()
             .filter(|e| parse_docker_size(&e.size) > 0)
-            .map(|e| (e.created_by.clone(), parse_docker_size(&e.size)))
+            .map(|e| (e.created_by.clone(), e.created_at.clone(), parse_docker_size(&e.size)))
             .collect();
 
+        Ok((di, non_empty))
+    }
+
+    fn inspect_via_cli(&mut self, image: &str) -> Result<ImageInfo> {
+        let (name, tag) = archive::parse_image_ref(image);
+        let (di, non_empty) = self.read_cli_metadata(image)?;
+        let diff_ids = &di.rootfs.layers;
+
         // Save image and parse all layer file listings via shared archive lib
         let size_str = format_bytes(di.size);
         self.finish_step(
@@ -282,25 +297,74 @@ impl OciInspector {
         );
         self.start_parse_progress(diff_ids.len() as u64);
         let mut on_layer = self.make_progress_callback();
-        let result = archive::parse_archive(&tmp, &name, &tag, Some(&diff_ids), &mut on_layer);
-        let _ = std::fs::remove_file(&tmp);
+        let result = archive::parse_archive(
+            &tmp,
+            &name,
+            &tag,
+            Some(diff_ids),
+            &mut on_layer,
+        );
+        self.saved = Some(tmp);
         let mut result = result?;
 
         // Override layer metadata with the richer CLI-sourced info
         let mut total_size = 0u64;
         for (i, layer) in result.info.layers.iter_mut().enumerate() {
-            if let Some((created_by, size)) = non_empty.get(i) {
+            if let Some((created_by, created, size)) = non_empty.get(i) {
                 layer.created_by = created_by.clone();
+                layer.created = created.clone();
+                layer.instruction = created_by.as_deref().and_then(Instruction::parse);
                 layer.size = *size;
                 total_size += size;
             }
         }
         result.info.total_size = total_size;
-        result.info.architecture = di.architecture;
+        apply_inspect(&mut result.info, di);
 
         Ok(self.store_result(result))
     }
 
+    /// Layers from the CLI's history alone, without saving the image.
+    fn metadata_via_cli(&mut self, image: &str) -> Result<ImageInfo> {
+        let (name, tag) = archive::parse_image_ref(image);
+        let (mut di, non_empty) = self.read_cli_metadata(image)?;
+
+        let layers: Vec<LayerInfo> = std::mem::take(&mut di.rootfs.layers)
+            .into_iter()
+            .enumerate()
+            .map(|(i, digest)| {
+                let (created_by, created, size) = non_empty.get(i).cloned().unwrap_or_default();
+                LayerInfo {
+                    digest,
+                    instruction: created_by.as_deref().and_then(Instruction::parse),
+                    created_by,
+                    created,
+                    size,
+                    compressed_size: None,
+                    files: Vec::new(),
+                }
+            })
+            .collect();
+        let mut info = ImageInfo {
+            name,
+            tag: Some(tag),
+            id: None,
+            manifest_digests: Vec::new(),
+            architecture: None,
+            created: None,
+            distro: None,
+            provenance: None,
+            labels: BTreeMap::new(),
+            annotations: BTreeMap::new(),
+            run_config: None,
+            total_size: layers.iter().map(|l| l.size).sum(),
+            layers,
+            roots: Vec::new(),
+        };
+        apply_inspect(&mut info, di);
+        Ok(info)
+    }
+
     // ---- Containerd (ctr): metadata + files from OCI export ----
 
     fn inspect_via_export(&mut self, image: &str) -> Result<ImageInfo> {
@@ -320,7 +384,7 @@ impl OciInspector {
         self.start_parse_progress(num_layers_guess);
         let mut on_layer = self.make_progress_callback();
         let result = archive::parse_archive(&tmp, &name, &tag, None, &mut on_layer);
-        let _ = std::fs::remove_file(&tmp);
+        self.saved = Some(tmp);
 
         Ok(self.store_result(result?))
     }
@@ -334,14 +398,87 @@ impl Inspector for OciInspector {
         }
     }
 
+    fn inspect_metadata(&mut self, image: &str) -> Result<ImageInfo> {
+        match self.kind {
+            // ctr has no history to read; the export is the only source
+            RuntimeKind::Containerd => self.inspect_via_export(image),
+            RuntimeKind::Docker | RuntimeKind::Podman => self.metadata_via_cli(image),
+        }
+    }
+
     fn list_files(&mut self, layer: &LayerInfo) -> Result<Vec<FileEntry>> {
-        if !self.cache_populated {
-            bail!("inspect() must be called before list_files()");
+        archive::parse_layer_entry(self.read_layer(layer)?, &self.listing)
+            .with_context(|| format!("Failed to parse layer {}", layer.digest))
+    }
+
+    fn for_each_file(
+        &mut self,
+        layer: &LayerInfo,
+        each: &mut dyn FnMut(FileEntry) -> Result<()>,
+    ) -> Result<()> {
+        archive::each_layer_file(self.read_layer(layer)?, &self.listing, each)
+    }
+
+    fn read_layer(&mut self, layer: &LayerInfo) -> Result<Box<dyn Read>> {
+        let saved = self
+            .saved
+            .as_ref()
+            .context("inspect() must be called before read_layer()")?;
+        let (offset, size) = self
+            .layer_offsets
+            .get(&layer.digest)
+            .with_context(|| format!("Layer {} not found in save output", layer.digest))?;
+        archive::open_layer(saved, *offset, *size)
+    }
+
+    fn read_blob(&mut self, layer: &LayerInfo) -> Result<Option<Box<dyn Read>>> {
+        let saved = self
+            .saved
+            .as_ref()
+            .context("inspect() must be called before read_blob()")?;
+        let (offset, size) = self
+            .layer_offsets
+            .get(&layer.digest)
+            .with_context(|| format!("Layer {} not found in save output", layer.digest))?;
+        Ok(Some(Box::new(archive::open_blob(saved, *offset, *size)?)))
+    }
+
+    fn config(&self) -> Option<&[u8]> {
+        self.config.as_deref()
+    }
+}
+
+impl Drop for OciInspector {
+    fn drop(&mut self) {
+        if let Some(saved) = &self.saved {
+            temp_files::remove(saved);
         }
+    }
+}
 
-        self.cached_files
-            .remove(&layer.digest)
-            .with_context(|| format!("Layer {} not found in save output", layer.digest))
+/// Image-level fields from `docker image inspect`, which win over what the
+/// saved archive says.
+fn apply_inspect(info: &mut ImageInfo, di: DockerInspect) {
+    info.architecture = di.architecture;
+    info.created = di.created;
+    if let Some(id) = di.id {
+        info.id = Some(if id.starts_with("sha256:") {
+            id
+        } else {
+            format!("sha256:{id}")
+        });
+    }
+    let mut digests: Vec<String> = di
+        .repo_digests
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|r| Some(r.split_once('@')?.1.to_string()))
+        .chain(di.digest)
+        .collect();
+    digests.sort();
+    digests.dedup();
+    if !digests.is_empty() {
+        info.manifest_digests = digests;
     }
 }
 
@@ -0,0 +1,428 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::Read;
+use std::os::unix::fs::{FileTypeExt, MetadataExt};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::instruction::Instruction;
+use super::{provenance, run_config};
+use super::{FileChange, FileEntry, ImageInfo, Inspector, LayerInfo, Listing, checksum};
+
+/// Reads layers directly from overlay2 storage on disk.
+/// Fastest path — no decompression, but requires root.
+pub struct Overlay2Inspector {
+    storage_root: PathBuf,
+    listing: Listing,
+    config: Option<Vec<u8>>,
+}
+
+#[derive(Deserialize)]
+struct Repositories {
+    #[serde(rename = "Repositories")]
+    repositories: HashMap<String, HashMap<String, String>>,
+}
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    architecture: Option<String>,
+    created: Option<String>,
+    rootfs: Rootfs,
+    #[serde(default)]
+    history: Vec<HistoryEntry>,
+}
+
+#[derive(Deserialize)]
+struct Rootfs {
+    diff_ids: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct HistoryEntry {
+    created_by: Option<String>,
+    created: Option<String>,
+    #[serde(default)]
+    empty_layer: bool,
+}
+
+impl Overlay2Inspector {
+    pub fn new(storage_root: PathBuf, listing: Listing) -> Self {
+        Self {
+            storage_root,
+            listing,
+            config: None,
+        }
+    }
+
+    /// Parse "name:tag" or "name" (defaults to "latest"), look up in repositories.json.
+    /// Returns (name, tag, config_digest_hex); the tag is empty for an image ID.
+    fn resolve_image(&self, image: &str) -> Result<(String, String, String)> {
+        // An image ID, as `peel build` gets for an untagged build; no tag
+        if let Some(hex) = image.strip_prefix("sha256:")
+            && hex.len() == 64
+            && hex.bytes().all(|b| b.is_ascii_hexdigit())
+        {
+            return Ok((image.to_string(), String::new(), hex.to_string()));
+        }
+        let (name, tag) = if let Some((n, t)) = image.rsplit_once(':') {
+            // If the part after ':' contains '/', it's a registry port, not a tag
+            if t.contains('/') {
+                (image.to_string(), "latest".to_string())
+            } else {
+                (n.to_string(), t.to_string())
+            }
+        } else {
+            (image.to_string(), "latest".to_string())
+        };
+
+        let repos_path = self.storage_root.join("image/overlay2/repositories.json");
+        let repos_data = fs::read_to_string(&repos_path)
+            .with_context(|| format!("Failed to read {}", repos_path.display()))?;
+        let repos: Repositories = serde_json::from_str(&repos_data)
+            .with_context(|| format!("Failed to parse {}", repos_path.display()))?;
+
+        let tagged_ref = format!("{name}:{tag}");
+
+        let tags = repos
+            .repositories
+            .get(&name)
+            .with_context(|| format!("Image '{name}' not found in repositories.json"))?;
+
+        let config_digest = tags
+            .get(&tagged_ref)
+            .with_context(|| format!("Tag '{tag}' not found for image '{name}'"))?;
+
+        let digest_hex = config_digest
+            .strip_prefix("sha256:")
+            .unwrap_or(config_digest);
+
+        Ok((name, tag, digest_hex.to_string()))
+    }
+
+    /// Manifest digests recorded for image `id` as `name@sha256:<hex>`
+    /// entries in repositories.json; empty if it can't be read.
+    fn repo_digests(&self, id: &str) -> Vec<String> {
+        let repos_path = self.storage_root.join("image/overlay2/repositories.json");
+        let Some(repos) = fs::read(&repos_path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<Repositories>(&data).ok())
+        else {
+            return Vec::new();
+        };
+        let mut digests: Vec<String> = repos
+            .repositories
+            .values()
+            .flatten()
+            .filter(|(_, image)| *image == id)
+            .filter_map(|(reference, _)| Some(reference.split_once('@')?.1.to_string()))
+            .collect();
+        digests.sort();
+        digests.dedup();
+        digests
+    }
+
+    fn read_image_config(&mut self, digest_hex: &str) -> Result<ImageConfig> {
+        let config_path = self
+            .storage_root
+            .join("image/overlay2/imagedb/content/sha256")
+            .join(digest_hex);
+        let config_data = fs::read(&config_path)
+            .with_context(|| format!("Failed to read image config {}", config_path.display()))?;
+        let config = serde_json::from_slice(&config_data).context("Failed to parse image config")?;
+        self.config = Some(config_data);
+        Ok(config)
+    }
+
+    fn get_cache_id(&self, chain_id: &str) -> Result<String> {
+        let chain_hex = chain_id.strip_prefix("sha256:").unwrap_or(chain_id);
+        let path = self
+            .storage_root
+            .join("image/overlay2/layerdb/sha256")
+            .join(chain_hex)
+            .join("cache-id");
+        let cache_id = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read cache-id for chain {chain_id}"))?;
+        Ok(cache_id.trim().to_string())
+    }
+
+    fn get_layer_size(&self, chain_id: &str) -> Result<u64> {
+        let chain_hex = chain_id.strip_prefix("sha256:").unwrap_or(chain_id);
+        let path = self
+            .storage_root
+            .join("image/overlay2/layerdb/sha256")
+            .join(chain_hex)
+            .join("size");
+        let size_str = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read size for chain {chain_id}"))?;
+        size_str.trim().parse::<u64>().context("Failed to parse layer size")
+    }
+
+    fn diff_dir(&self, layer: &LayerInfo) -> Result<PathBuf> {
+        let cache_id = self.get_cache_id(&layer.digest)?;
+        let diff_dir = self.storage_root.join("overlay2").join(&cache_id).join("diff");
+
+        if !diff_dir.exists() {
+            anyhow::bail!("Layer directory not found: {}", diff_dir.display());
+        }
+        Ok(diff_dir)
+    }
+
+    fn walk_layer_dir(
+        dir: &Path,
+        base: &Path,
+        listing: &Listing,
+        each: &mut dyn FnMut(FileEntry) -> Result<()>,
+    ) -> Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            let metadata = entry.metadata()?;
+            let relative = path.strip_prefix(base).unwrap_or(&path).to_path_buf();
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            if metadata.is_dir() {
+                if listing.roots.may_contain(&relative) {
+                    if is_opaque(&path) {
+                        each(FileEntry {
+                            path: relative.join(OPAQUE_WHITEOUT),
+                            size: 0,
+                            is_whiteout: true,
+                            change: FileChange::from_whiteout(true),
+                            entropy: None,
+                            sha256: None,
+                            elf: false,
+                            mode: None,
+                            uid: None,
+                            gid: None,
+                        })?;
+                    }
+                    Self::walk_layer_dir(&path, base, listing, each)?;
+                }
+            } else {
+                // overlayfs records a deletion as a 0/0 character device under
+                // the deleted name; list it the way layer tars spell it
+                let device_whiteout = metadata.file_type().is_char_device() && metadata.rdev() == 0;
+                let relative = if device_whiteout {
+                    relative.with_file_name(format!(".wh.{name}"))
+                } else {
+                    relative
+                };
+                let is_whiteout = device_whiteout || name.starts_with(".wh.");
+                if !listing.roots.keeps(&relative, is_whiteout) {
+                    continue;
+                }
+                let size = if is_whiteout { 0 } else { metadata.len() };
+                let checksums = listing.checksums;
+                let scan = if metadata.is_file() && checksum::wanted(&path, size, checksums) {
+                    fs::File::open(&path)
+                        .map(|file| checksum::scan(file, size, checksums))
+                        .unwrap_or_default()
+                } else {
+                    checksum::Scan::default()
+                };
+                let regular = metadata.is_file() && !is_whiteout;
+                each(FileEntry {
+                    path: relative,
+                    size,
+                    is_whiteout,
+                    change: FileChange::from_whiteout(is_whiteout),
+                    entropy: scan.entropy,
+                    sha256: scan.sha256,
+                    elf: scan.elf,
+                    mode: regular.then(|| metadata.mode() & 0o7777),
+                    uid: regular.then(|| metadata.uid()),
+                    gid: regular.then(|| metadata.gid()),
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Inspector for Overlay2Inspector {
+    fn inspect(&mut self, image: &str) -> Result<ImageInfo> {
+        let _phase = tracing::debug_span!("resolve").entered();
+        let (name, tag, digest_hex) = self.resolve_image(image)?;
+        let config = self.read_image_config(&digest_hex)?;
+        let chain_ids = super::chain_ids(&config.rootfs.diff_ids);
+
+        // Match history entries (skipping empty layers) to diff_ids
+        let mut created_by_list: Vec<Option<String>> = Vec::new();
+        let mut created_list: Vec<Option<String>> = Vec::new();
+        for entry in &config.history {
+            if !entry.empty_layer {
+                created_by_list.push(entry.created_by.clone());
+                created_list.push(entry.created.clone());
+            }
+        }
+
+        let mut layers = Vec::with_capacity(chain_ids.len());
+        let mut total_size = 0u64;
+
+        for (i, chain_id) in chain_ids.iter().enumerate() {
+            let size = self.get_layer_size(chain_id).unwrap_or(0);
+            total_size += size;
+            layers.push(LayerInfo {
+                digest: chain_id.clone(),
+                created_by: created_by_list.get(i).cloned().flatten(),
+                instruction: created_by_list
+                    .get(i)
+                    .and_then(|c| c.as_deref())
+                    .and_then(Instruction::parse),
+                created: created_list.get(i).cloned().flatten(),
+                size,
+                compressed_size: None,
+                files: Vec::new(),
+            });
+        }
+
+        let id = format!("sha256:{digest_hex}");
+        Ok(ImageInfo {
+            name: name.to_string(),
+            tag: (!tag.is_empty()).then_some(tag),
+            manifest_digests: self.repo_digests(&id),
+            id: Some(id),
+            architecture: config.architecture,
+            created: config.created,
+            distro: None,
+            provenance: provenance::read(self.config.as_deref(), None),
+            labels: super::config_labels(self.config.as_deref()),
+            run_config: run_config::read(self.config.as_deref()),
+            annotations: BTreeMap::new(),
+            total_size,
+            layers,
+            roots: Vec::new(),
+        })
+    }
+
+    fn list_files(&mut self, layer: &LayerInfo) -> Result<Vec<FileEntry>> {
+        let _layer = tracing::debug_span!("layer", layer = %layer.digest).entered();
+        let diff_dir = self.diff_dir(layer)?;
+        let mut entries = Vec::new();
+        Self::walk_layer_dir(&diff_dir, &diff_dir, &self.listing, &mut |entry| {
+            entries.push(entry);
+            Ok(())
+        })?;
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    fn for_each_file(
+        &mut self,
+        layer: &LayerInfo,
+        each: &mut dyn FnMut(FileEntry) -> Result<()>,
+    ) -> Result<()> {
+        // The tar `read_layer` builds keeps whiteouts as devices, so walk
+        // the directory instead
+        let diff_dir = self.diff_dir(layer)?;
+        Self::walk_layer_dir(&diff_dir, &diff_dir, &self.listing, each)
+    }
+
+    fn read_layer(&mut self, layer: &LayerInfo) -> Result<Box<dyn Read>> {
+        let diff_dir = self.diff_dir(layer)?;
+        let (reader, writer) = std::io::pipe()?;
+
+        // Overlay whiteouts are 0/0 character devices on disk and go into the
+        // stream as such; opaque directories get a `.wh..wh..opq` marker. A
+        // write error (including the reader hanging up) just ends the
+        // archive early.
+        std::thread::spawn(move || {
+            let mut builder = tar::Builder::new(writer);
+            builder.follow_symlinks(false);
+            let _ = builder
+                .append_path_with_name(&diff_dir, ".")
+                .and_then(|_| append_layer_dir(&mut builder, &diff_dir, &diff_dir))
+                .and_then(|_| builder.finish());
+        });
+        Ok(Box::new(reader))
+    }
+
+    fn read_blob(&mut self, _layer: &LayerInfo) -> Result<Option<Box<dyn Read>>> {
+        // Only the unpacked diff directories are kept
+        Ok(None)
+    }
+
+    fn config(&self) -> Option<&[u8]> {
+        self.config.as_deref()
+    }
+}
+
+/// Marker file that hides everything below its directory in lower layers,
+/// as layer tars spell an opaque directory.
+const OPAQUE_WHITEOUT: &str = ".wh..wh..opq";
+
+/// Whether overlayfs hides lower layers' contents of `dir`: the
+/// `trusted.overlay.opaque` xattr, or `user.overlay.opaque` for rootless
+/// storage, set to `y`.
+fn is_opaque(dir: &Path) -> bool {
+    ["trusted.overlay.opaque", "user.overlay.opaque"]
+        .iter()
+        .any(|name| matches!(xattr::get(dir, name), Ok(Some(v)) if v == b"y"))
+}
+
+/// Append everything below `dir` to `builder` under `./`, writing an opaque
+/// marker into each opaque directory right after the directory itself.
+fn append_layer_dir<W: std::io::Write>(
+    builder: &mut tar::Builder<W>,
+    dir: &Path,
+    base: &Path,
+) -> std::io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = Path::new(".").join(path.strip_prefix(base).unwrap_or(&path));
+        builder.append_path_with_name(&path, &name)?;
+        if fs::symlink_metadata(&path)?.is_dir() {
+            if is_opaque(&path) {
+                let mut header = tar::Header::new_gnu();
+                header.set_size(0);
+                header.set_mode(0o644);
+                header.set_cksum();
+                builder.append_data(&mut header, name.join(OPAQUE_WHITEOUT), std::io::empty())?;
+            }
+            append_layer_dir(builder, &path, base)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process::Command;
+
+    #[test]
+    fn char_device_whiteout_is_listed_as_wh_entry() {
+        let dir = std::env::temp_dir().join(format!("peel-overlay2-test-{}", std::process::id()));
+        fs::create_dir_all(dir.join("etc")).unwrap();
+        fs::write(dir.join("etc/kept.conf"), "kept").unwrap();
+        let made = Command::new("mknod")
+            .arg(dir.join("etc/gone.conf"))
+            .args(["c", "0", "0"])
+            .status()
+            .is_ok_and(|s| s.success());
+        if !made {
+            // Making device nodes needs CAP_MKNOD
+            fs::remove_dir_all(&dir).unwrap();
+            return;
+        }
+
+        let mut entries = Vec::new();
+        let listed = Overlay2Inspector::walk_layer_dir(&dir, &dir, &Listing::default(), &mut |e| {
+            entries.push(e);
+            Ok(())
+        });
+        fs::remove_dir_all(&dir).unwrap();
+        listed.unwrap();
+
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        let listed: Vec<_> = entries
+            .iter()
+            .map(|e| (e.path.to_str().unwrap(), e.is_whiteout, e.size))
+            .collect();
+        assert_eq!(listed, [("etc/.wh.gone.conf", true, 0), ("etc/kept.conf", false, 4)]);
+        assert_eq!(entries[0].change, FileChange::from_whiteout(true));
+    }
+}
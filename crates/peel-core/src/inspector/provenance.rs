@@ -0,0 +1,259 @@
+use std::collections::{BTreeMap, HashMap};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Config key BuildKit 0.10 embedded build details under, before
+/// provenance attestations replaced it.
+const BUILDINFO_KEY: &str = "moby.buildkit.buildinfo.v1";
+
+/// Annotation on the index entry of an attestation manifest.
+pub const REFERENCE_TYPE: &str = "vnd.docker.reference.type";
+/// Annotation naming the image manifest an attestation is about.
+pub const REFERENCE_DIGEST: &str = "vnd.docker.reference.digest";
+/// Annotation on each layer of an attestation manifest.
+pub const PREDICATE_TYPE: &str = "in-toto.io/predicate-type";
+
+/// How an image was built, from BuildKit's provenance attestation or the
+/// build info it used to embed in the config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Provenance {
+    /// What this was read from: the attestation's predicate type (e.g.
+    /// "https://slsa.dev/provenance/v0.2"), "buildinfo" or "labels"
+    pub source: String,
+
+    /// Builder ID, or BuildKit version, e.g. "buildkit v0.13.0"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builder: Option<String>,
+
+    /// e.g. "dockerfile.v0", or the image a `# syntax=` line names
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frontend: Option<String>,
+
+    /// `--build-arg` values, by name
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub build_args: BTreeMap<String, String>,
+
+    /// Repository the build context came from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs_source: Option<String>,
+
+    /// Commit the build context was at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vcs_revision: Option<String>,
+
+    /// Images the build pulled, e.g. base images and `COPY --from` sources
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub base_images: Vec<BaseImage>,
+}
+
+/// An image the build pulled.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseImage {
+    /// As resolved, e.g. "docker.io/library/alpine:3.20"
+    pub reference: String,
+
+    /// `sha256:<hex>` it was pinned to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+}
+
+/// Build details from an attestation (in-toto statement), falling back to
+/// the image config's embedded build info, then its OCI labels for the
+/// repository and commit. `None` if neither says anything.
+pub fn read(config: Option<&[u8]>, statement: Option<&[u8]>) -> Option<Provenance> {
+    let config: Option<Value> = config.and_then(|c| serde_json::from_slice(c).ok());
+    let mut provenance = statement
+        .and_then(from_statement)
+        .or_else(|| config.as_ref().and_then(from_buildinfo));
+
+    let labels = config
+        .as_ref()
+        .and_then(|c| c.pointer("/config/Labels"))
+        .and_then(Value::as_object);
+    let label = |key: &str| labels.and_then(|l| l.get(key)).and_then(Value::as_str);
+    let (source, revision) = (
+        label("org.opencontainers.image.source"),
+        label("org.opencontainers.image.revision"),
+    );
+    if provenance.is_none() && revision.is_some() {
+        provenance = Some(Provenance {
+            source: "labels".to_string(),
+            ..Provenance::default()
+        });
+    }
+    let mut provenance = provenance?;
+    if provenance.vcs_revision.is_none() {
+        provenance.vcs_revision = revision.map(str::to_string);
+        provenance.vcs_source = provenance.vcs_source.or(source.map(str::to_string));
+    }
+    Some(provenance)
+}
+
+/// SLSA provenance, v0.2 or v1, as BuildKit writes it.
+fn from_statement(data: &[u8]) -> Option<Provenance> {
+    let statement: Value = serde_json::from_slice(data).ok()?;
+    let source = statement.get("predicateType")?.as_str()?;
+    if !source.contains("slsa.dev/provenance") {
+        return None;
+    }
+    let predicate = statement.get("predicate")?;
+    // v0.2 first, then v1
+    let find = |paths: &[&str]| paths.iter().find_map(|p| predicate.pointer(p));
+    let text = |paths: &[&str]| {
+        find(paths)
+            .and_then(Value::as_str)
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+    };
+
+    let args = find(&[
+        "/invocation/parameters/args",
+        "/buildDefinition/externalParameters/request/args",
+    ])
+    .and_then(Value::as_object);
+    let arg = |key: &str| args.and_then(|a| a.get(key)).and_then(Value::as_str);
+    let build_args = args
+        .into_iter()
+        .flatten()
+        .filter_map(|(key, value)| {
+            Some((
+                key.strip_prefix("build-arg:")?.to_string(),
+                value.as_str()?.to_string(),
+            ))
+        })
+        .collect();
+
+    let version = text(&["/runDetails/builder/version/buildkit"]).map(|v| format!("buildkit {v}"));
+    let builder = text(&["/builder/id", "/runDetails/builder/id"]).or(version);
+
+    // The `# syntax=` frontend image is a material too, but not one the
+    // image is built from
+    let frontend_image = arg("source").map(repository);
+    let base_images = find(&["/materials", "/buildDefinition/resolvedDependencies"])
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|material| {
+            let image = purl_image(material.get("uri")?.as_str()?)?;
+            let digest = material
+                .pointer("/digest/sha256")
+                .and_then(Value::as_str)
+                .map(|hex| format!("sha256:{hex}"));
+            Some(BaseImage {
+                reference: image,
+                digest,
+            })
+        })
+        .filter(|image| frontend_image.is_none_or(|f| repository(&image.reference) != f))
+        .collect();
+
+    // A `# syntax=` line builds with the gateway frontend, running the image
+    // it names
+    let frontend = text(&[
+        "/invocation/parameters/frontend",
+        "/buildDefinition/externalParameters/request/frontend",
+    ])
+    .map(|f| match arg("source") {
+        Some(image) if f == "gateway.v0" => image.to_string(),
+        _ => f,
+    });
+
+    Some(Provenance {
+        source: source.to_string(),
+        builder,
+        frontend,
+        build_args,
+        vcs_source: text(&[
+            "/metadata/https:~1~1mobyproject.org~1buildkit@v1#metadata/vcs/source",
+            "/runDetails/metadata/buildkit_metadata/vcs/source",
+        ]),
+        vcs_revision: text(&[
+            "/metadata/https:~1~1mobyproject.org~1buildkit@v1#metadata/vcs/revision",
+            "/runDetails/metadata/buildkit_metadata/vcs/revision",
+        ]),
+        base_images,
+    })
+}
+
+#[derive(Deserialize)]
+struct BuildInfo {
+    frontend: Option<String>,
+    #[serde(default)]
+    attrs: HashMap<String, Option<String>>,
+    #[serde(default)]
+    sources: Vec<BuildSource>,
+}
+
+#[derive(Deserialize)]
+struct BuildSource {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "ref")]
+    reference: String,
+    pin: Option<String>,
+}
+
+/// The base64 build info BuildKit 0.10 put in the config.
+fn from_buildinfo(config: &Value) -> Option<Provenance> {
+    let encoded = config.get(BUILDINFO_KEY)?.as_str()?;
+    let info: BuildInfo = serde_json::from_slice(&BASE64.decode(encoded).ok()?).ok()?;
+
+    let build_args = info
+        .attrs
+        .iter()
+        .filter_map(|(key, value)| {
+            Some((key.strip_prefix("build-arg:")?.to_string(), value.clone()?))
+        })
+        .collect();
+    let git = info.sources.iter().find(|s| s.kind == "git");
+    let base_images = info
+        .sources
+        .iter()
+        .filter(|s| s.kind == "docker-image")
+        .map(|s| BaseImage {
+            reference: s
+                .reference
+                .split('@')
+                .next()
+                .unwrap_or(&s.reference)
+                .to_string(),
+            digest: s.pin.clone(),
+        })
+        .collect();
+
+    Some(Provenance {
+        source: "buildinfo".to_string(),
+        builder: None,
+        frontend: info.frontend,
+        build_args,
+        vcs_source: git.map(|g| g.reference.clone()),
+        vcs_revision: git.and_then(|g| g.pin.clone()),
+        base_images,
+    })
+}
+
+/// `pkg:docker/alpine@3.20?platform=linux%2Famd64` as `alpine:3.20`; `None`
+/// for materials that aren't images, like git repositories.
+fn purl_image(uri: &str) -> Option<String> {
+    let rest = uri.strip_prefix("pkg:docker/")?;
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    Some(match rest.rsplit_once('@') {
+        Some((name, version)) if version.starts_with("sha256:") => format!("{name}@{version}"),
+        Some((name, version)) => format!("{name}:{version}"),
+        None => rest.to_string(),
+    })
+}
+
+/// `docker.io/docker/dockerfile:1` as `docker/dockerfile`, for comparing
+/// references written differently.
+fn repository(reference: &str) -> &str {
+    let name = reference.split('@').next().unwrap_or(reference);
+    let name = match name.rsplit_once(':') {
+        Some((repo, tag)) if !tag.contains('/') => repo,
+        _ => name,
+    };
+    name.strip_prefix("docker.io/").unwrap_or(name)
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// How a container from the image runs: the runtime fields of the image
+/// config.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct RunConfig {
+    /// `NAME=value`, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<Vec<String>>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+
+    /// As set by `USER`, e.g. "app" or "1000:1000"; absent means root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// e.g. "8080/tcp"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub exposed_ports: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volumes: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<Healthcheck>,
+}
+
+impl RunConfig {
+    fn is_empty(&self) -> bool {
+        self.env.is_empty()
+            && self.entrypoint.is_none()
+            && self.cmd.is_none()
+            && self.working_dir.is_none()
+            && self.user.is_none()
+            && self.exposed_ports.is_empty()
+            && self.volumes.is_empty()
+            && self.healthcheck.is_none()
+    }
+}
+
+/// `HEALTHCHECK`, with durations in seconds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Healthcheck {
+    /// `["CMD", …]`, `["CMD-SHELL", "…"]`, or `["NONE"]` to turn off the
+    /// base image's
+    pub test: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interval: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<f64>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u32>,
+}
+
+// ---- Image config JSON ----
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    config: Option<ContainerConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct ContainerConfig {
+    env: Option<Vec<String>>,
+    entrypoint: Option<Vec<String>>,
+    cmd: Option<Vec<String>>,
+    working_dir: Option<String>,
+    user: Option<String>,
+    exposed_ports: Option<BTreeMap<String, serde_json::Value>>,
+    volumes: Option<BTreeMap<String, serde_json::Value>>,
+    healthcheck: Option<HealthConfig>,
+}
+
+/// Durations are in nanoseconds; zero means the default.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct HealthConfig {
+    #[serde(default)]
+    test: Vec<String>,
+    interval: Option<u64>,
+    timeout: Option<u64>,
+    start_period: Option<u64>,
+    retries: Option<u32>,
+}
+
+/// The runtime fields of a raw image config; `None` if it sets none or
+/// can't be read.
+pub fn read(config: Option<&[u8]>) -> Option<RunConfig> {
+    let config = serde_json::from_slice::<ImageConfig>(config?)
+        .ok()?
+        .config?;
+    let seconds = |ns: Option<u64>| ns.filter(|&ns| ns > 0).map(|ns| ns as f64 / 1e9);
+    let run = RunConfig {
+        env: config.env.unwrap_or_default(),
+        entrypoint: config.entrypoint,
+        cmd: config.cmd,
+        working_dir: config.working_dir.filter(|d| !d.is_empty()),
+        user: config.user.filter(|u| !u.is_empty()),
+        exposed_ports: config
+            .exposed_ports
+            .unwrap_or_default()
+            .into_keys()
+            .collect(),
+        volumes: config.volumes.unwrap_or_default().into_keys().collect(),
+        healthcheck: config.healthcheck.map(|h| Healthcheck {
+            test: h.test,
+            interval: seconds(h.interval),
+            timeout: seconds(h.timeout),
+            start_period: seconds(h.start_period),
+            retries: h.retries.filter(|&r| r > 0),
+        }),
+    };
+    (!run.is_empty()).then_some(run)
+}
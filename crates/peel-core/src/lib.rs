@@ -0,0 +1,58 @@
+//! Container image inspection behind the `peel` CLI: find the local container
+//! runtimes, then read an image's metadata and list the files of each layer,
+//! from the runtime's storage, through its CLI, or from a saved archive.
+//!
+//! Every backend implements [`inspector::Inspector`]:
+//!
+//! ```no_run
+//! use peel_core::inspector::{Inspector, Listing};
+//! use peel_core::inspector::docker_archive::DockerArchiveInspector;
+//!
+//! let mut inspector = DockerArchiveInspector::new("image.tar".into(), Listing::default());
+//! let mut info = inspector.inspect("image.tar")?;
+//! for layer in &mut info.layers {
+//!     layer.files = inspector.list_files(layer)?;
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! `inspect` reads the image's metadata and where its layers are, not their
+//! files. [`inspector::Inspector::for_each_file`] hands over one file at a
+//! time instead of a layer's whole listing, reading the layer as it goes:
+//! overlay2 walks its directory, and archive backends stream its tar out of
+//! the archive, so memory stays bounded however many files an image holds.
+//!
+//! ```no_run
+//! # use peel_core::inspector::{Inspector, Listing};
+//! # use peel_core::inspector::docker_archive::DockerArchiveInspector;
+//! # let mut inspector = DockerArchiveInspector::new("image.tar".into(), Listing::default());
+//! let info = inspector.inspect("image.tar")?;
+//! let mut largest = 0;
+//! for layer in &info.layers {
+//!     inspector.for_each_file(layer, &mut |file| {
+//!         largest = largest.max(file.size);
+//!         Ok(())
+//!     })?;
+//! }
+//! # Ok::<(), anyhow::Error>(())
+//! ```
+//!
+//! [`probe::probe`] finds Docker, Podman and containerd and whether their
+//! storage can be read directly; [`inspector::oci::OciInspector`] and, on
+//! Linux, `inspector::overlay2::Overlay2Inspector` read the images they hold.
+//! What file listings include, such as only some subtrees or a sha256 per
+//! file, is an [`inspector::Listing`] each backend is created with.
+//!
+//! Temporary files a backend writes, such as `docker save` output, are
+//! listed in [`temp_files`] until they're removed, so a signal handler can
+//! clean them up.
+//!
+//! Messages, errors included, come from the catalogs in `locales/` through
+//! [`t!`]; [`i18n::init`] picks the language.
+
+#[macro_use]
+pub mod i18n;
+
+pub mod inspector;
+pub mod probe;
+pub mod temp_files;
@@ -16,6 +16,7 @@ pub fn find_binary(name: &str) -> Option<PathBuf> {
 /// Run a command and return true if it exits successfully.
 /// Used to check if a daemon is alive (e.g. `docker info`).
 pub fn check_daemon(cmd: &str, args: &[&str]) -> bool {
+    tracing::debug!("running {cmd} {}", args.join(" "));
     Command::new(cmd)
         .args(args)
         .stdout(std::process::Stdio::null())
@@ -26,6 +27,7 @@ pub fn check_daemon(cmd: &str, args: &[&str]) -> bool {
 
 /// Run a command and capture its stdout as a String.
 pub fn command_output(cmd: &str, args: &[&str]) -> Option<String> {
+    tracing::debug!("running {cmd} {}", args.join(" "));
     let output = Command::new(cmd)
         .args(args)
         .stderr(std::process::Stdio::null())
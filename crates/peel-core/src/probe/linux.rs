@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 
@@ -61,7 +61,7 @@ fn detect_docker_storage_driver() -> StorageDriver {
     StorageDriver::Unknown
 }
 
-fn guess_storage_driver(storage_root: &PathBuf) -> StorageDriver {
+fn guess_storage_driver(storage_root: &Path) -> StorageDriver {
     // Guess by checking which directories exist
     let candidates = [
         ("overlay2", StorageDriver::Overlay2),
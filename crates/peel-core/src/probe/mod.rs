@@ -97,15 +97,30 @@ pub struct ProbeResult {
 
 #[cfg(target_os = "linux")]
 pub fn probe() -> Result<ProbeResult> {
-    linux::probe()
+    linux::probe().inspect(log_found)
 }
 
 #[cfg(target_os = "macos")]
 pub fn probe() -> Result<ProbeResult> {
-    macos::probe()
+    macos::probe().inspect(log_found)
 }
 
 #[cfg(target_os = "windows")]
 pub fn probe() -> Result<ProbeResult> {
-    windows::probe()
+    windows::probe().inspect(log_found)
+}
+
+fn log_found(result: &ProbeResult) {
+    for (i, rt) in result.runtimes.iter().enumerate() {
+        tracing::debug!(
+            binary = %rt.binary_path.display(),
+            storage = %rt.storage_root.display(),
+            driver = ?rt.storage_driver,
+            can_read = rt.can_read,
+            running = rt.is_running,
+            default = result.default == Some(i),
+            "found {:?}",
+            rt.kind
+        );
+    }
 }
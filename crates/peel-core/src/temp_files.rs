@@ -0,0 +1,33 @@
+//! Temporary files that outlive a function call, such as an image saved
+//! with `docker save`, tracked so they can be removed if the process is
+//! interrupted before the code that made them gets to.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+static TRACKED: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+
+/// Remember `path` for [`remove_all`]; call before creating the file.
+pub fn track(path: &Path) {
+    TRACKED.lock().unwrap().push(path.to_path_buf());
+}
+
+/// Delete `path`, if it's there, and stop tracking it.
+pub fn remove(path: &Path) {
+    let _ = std::fs::remove_file(path);
+    TRACKED.lock().unwrap().retain(|p| p != path);
+}
+
+/// Stop tracking `path`, leaving the file for the user.
+pub fn keep(path: &Path) {
+    TRACKED.lock().unwrap().retain(|p| p != path);
+}
+
+/// Delete every tracked file, e.g. on Ctrl-C.
+pub fn remove_all() {
+    // A panic while the lock was held mustn't keep the files around
+    let mut tracked = TRACKED.lock().unwrap_or_else(|e| e.into_inner());
+    for path in tracked.drain(..) {
+        let _ = std::fs::remove_file(path);
+    }
+}
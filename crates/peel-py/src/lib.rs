@@ -0,0 +1,120 @@
+//! Python bindings for `peel-core`, built with maturin:
+//!
+//! ```python
+//! import peel
+//!
+//! info = peel.inspect_image("python:3.12-slim")
+//! for layer in info["layers"]:
+//!     print(layer["size"], layer["created_by"])
+//! ```
+//!
+//! Results are the dicts `json.loads` gives for `peel_core::inspector::ImageInfo`.
+
+use std::path::Path;
+
+use anyhow::{Result, anyhow};
+use peel_core::inspector::docker_archive::DockerArchiveInspector;
+use peel_core::inspector::oci::OciInspector;
+use peel_core::inspector::{self, ImageInfo, Inspector, Listing};
+use peel_core::probe::{self, RuntimeKind};
+use peel_core::t;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+/// Inspect an image and list the files of every layer.
+///
+/// `image` is a reference the local runtime knows, or a `.tar`/`.tar.gz`
+/// archive. Images are read from the runtime's storage when it is readable,
+/// else through its CLI, as with `use_oci=True`. `runtime` picks docker,
+/// podman or containerd over the detected default.
+///
+/// `checksums` adds each file's sha256.
+#[pyfunction]
+#[pyo3(signature = (image, *, use_oci = false, runtime = None, checksums = false))]
+fn inspect_image(
+    py: Python<'_>,
+    image: &str,
+    use_oci: bool,
+    runtime: Option<&str>,
+    checksums: bool,
+) -> PyResult<Py<PyAny>> {
+    let listing = Listing {
+        checksums,
+        ..Listing::default()
+    };
+    let json = py
+        .detach(|| {
+            let info = inspect(image, use_oci, runtime, listing)?;
+            serde_json::to_string(&info).map_err(anyhow::Error::from)
+        })
+        .map_err(|e| PyRuntimeError::new_err(format!("{e:#}")))?;
+    let loads = py.import("json")?.getattr("loads")?;
+    Ok(loads.call1((json,))?.unbind())
+}
+
+fn inspect(image: &str, use_oci: bool, runtime: Option<&str>, listing: Listing) -> Result<ImageInfo> {
+    let mut inspector = open_inspector(image, use_oci, runtime, listing)?;
+    let mut info = inspector.inspect(image)?;
+    for layer in &mut info.layers {
+        layer.files = inspector.list_files(layer)?;
+    }
+    Ok(info)
+}
+
+/// Archives directly; otherwise the runtime's storage if this process can
+/// read it, else its CLI. Unlike the CLI, never escalates with sudo.
+fn open_inspector(
+    image: &str,
+    use_oci: bool,
+    runtime: Option<&str>,
+    listing: Listing,
+) -> Result<Box<dyn Inspector>> {
+    let path = Path::new(image);
+    let is_archive = matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("tar" | "gz" | "tgz")
+    );
+    if is_archive {
+        return Ok(Box::new(DockerArchiveInspector::new(path.into(), listing)));
+    }
+
+    let probed = probe::probe()?;
+    let rt = match runtime {
+        Some(name) => {
+            let kind = RuntimeKind::from_name(name)
+                .ok_or_else(|| anyhow!(t!("runtime-unknown", name = name)))?;
+            let rt = probed.runtimes.iter().find(|rt| rt.kind.matches(&kind));
+            Some(rt.ok_or_else(|| anyhow!(t!("runtime-not-detected", name = name)))?)
+        }
+        None => probed.default.map(|i| &probed.runtimes[i]),
+    };
+    let Some(rt) = rt else {
+        return Ok(Box::new(OciInspector::new(
+            "docker".to_string(),
+            RuntimeKind::Docker,
+            listing,
+        )));
+    };
+
+    match rt.storage_driver {
+        #[cfg(target_os = "linux")]
+        probe::StorageDriver::Overlay2 | probe::StorageDriver::Fuse | probe::StorageDriver::Vfs
+            if rt.can_read && !use_oci =>
+        {
+            Ok(Box::new(inspector::overlay2::Overlay2Inspector::new(
+                rt.storage_root.clone(),
+                listing,
+            )))
+        }
+        _ => Ok(Box::new(OciInspector::new(
+            rt.binary_path.display().to_string(),
+            rt.kind.clone(),
+            listing,
+        ))),
+    }
+}
+
+#[pymodule]
+fn peel(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(inspect_image, m)?)
+}
@@ -1,26 +1,90 @@
 import { useState, useMemo, useRef } from "react";
-import { Container, Cpu } from "lucide-react";
-import devData from "../data/test.json";
-import type { ImageInfo, FileEntry } from "@/types";
+import { Container, Cpu, GitCommitHorizontal, ShieldAlert, ShieldCheck } from "lucide-react";
+import type { ImageInfo, FileEntry, ClusterCheck, ImageClass, PostureReport, Provenance, RuntimeKind, Toolchain, VerifyReport } from "@/types";
 import { formatBytes } from "@/lib/format";
+import { t, type Key } from "@/lib/i18n";
 import { LayerList } from "@/components/LayerList";
+import { BuildTimeline } from "@/components/BuildTimeline";
 import { FilePanel } from "@/components/FilePanel";
 import { Toolbar, type ViewMode, type FileViewMode } from "@/components/Toolbar";
 import { useSectionFocus } from "@/hooks/useSectionFocus";
+import { ContrastToggle } from "@/components/ContrastToggle";
+import { BaseSplitBar } from "@/components/BaseSplitBar";
+import { WasteDialog } from "@/components/WasteDialog";
+import { ContentSummary, FileTypeSummary } from "@/components/ContentBar";
+import { DirDialog } from "@/components/DirDialog";
+import { RuntimeDialog } from "@/components/RuntimeDialog";
 
-function loadData(): ImageInfo {
-  const el = document.getElementById("__PEEL_DATA__");
-  if (el?.textContent?.trim()) return JSON.parse(el.textContent);
-  return devData as ImageInfo;
+const CLASS_LABELS: Record<ImageClass, Key> = {
+  distro: "classDistro",
+  minimal: "classMinimal",
+  distroless: "classDistroless",
+  scratch: "classScratch",
+};
+
+const RUNTIME_NAMES: Record<RuntimeKind, string> = {
+  python: "Python",
+  node: "Node.js",
+  java: "Java",
+  go: "Go",
+};
+
+/** One line per detail the build recorded, for the header tooltip. */
+function provenanceLines(p: Provenance): string {
+  const lines = [t("provenance") + ` (${p.source})`];
+  const builder = [p.builder, p.frontend].filter(Boolean).join(" / ");
+  if (builder) lines.push(`${t("builtBy")}: ${builder}`);
+  const vcs = [p.vcs_source, p.vcs_revision].filter(Boolean).join(" @ ");
+  if (vcs) lines.push(`${t("vcsSource")}: ${vcs}`);
+  const args = Object.entries(p.build_args ?? {});
+  if (args.length) {
+    lines.push(`${t("buildArgs")}: ${args.map(([k, v]) => `${k}=${v}`).join(" ")}`);
+  }
+  for (const image of p.base_images ?? []) {
+    const pinned = image.digest && !image.reference.includes("@");
+    lines.push(`${t("pulledImages")}: ${image.reference}${pinned ? "@" + image.digest : ""}`);
+  }
+  return lines.join("\n");
+}
+
+/** Each layer that didn't hash to its diff_id, for the header tooltip. */
+function mismatchLines(verify: VerifyReport): string {
+  return (verify.mismatches ?? [])
+    .map((m) => `#${m.layer}: ${m.expected} ≠ ${m.actual ?? m.error}`)
+    .join("\n");
 }
 
-const image = loadData();
+/** Full image ID and manifest digests, for the header tooltip. */
+function digestLines(image: ImageInfo): string {
+  return [
+    `${t("imageId")}: ${image.id}`,
+    ...(image.manifest_digests ?? []).map((d) => `${t("manifestDigest")}: ${d}`),
+  ].join("\n");
+}
 
-function App() {
+/** Labels, then annotations, as `key=value` lines under a heading each. */
+function metadataLines(image: ImageInfo): string {
+  return (
+    [
+      ["labels", image.labels],
+      ["annotations", image.annotations],
+    ] as const
+  )
+    .filter(([, entries]) => entries)
+    .map(([heading, entries]) =>
+      [t(heading), ...Object.entries(entries!).map(([k, v]) => `  ${k}=${v}`)].join("\n"),
+    )
+    .join("\n");
+}
+
+function App({ image }: { image: ImageInfo }) {
   const [selectedLayer, setSelectedLayer] = useState(0);
-  const [viewMode, setViewMode] = useState<ViewMode>("layer");
+  const [viewMode, setViewMode] = useState<ViewMode>(image.merged ? "merged" : "layer");
   const [fileViewMode, setFileViewMode] = useState<FileViewMode>("tree");
   const [filter, setFilter] = useState("");
+  const [wasteOpen, setWasteOpen] = useState(false);
+  const [dirsOpen, setDirsOpen] = useState(false);
+  const [runtimeOpen, setRuntimeOpen] = useState(false);
 
   const toolbarRef = useRef<HTMLDivElement>(null);
   const layerRef = useRef<HTMLDivElement>(null);
@@ -36,7 +100,19 @@ function App() {
 
   useSectionFocus(sectionRefs);
 
+  // Only shown when every layer's blob size is known
+  const compressed = useMemo(
+    () =>
+      image.layers.every((l) => l.compressed_size !== undefined)
+        ? image.layers.reduce((sum, l) => sum + (l.compressed_size ?? 0), 0)
+        : undefined,
+    [image],
+  );
+
   const files = useMemo<FileEntry[]>(() => {
+    if (viewMode === "merged" && image.merged) {
+      return image.merged.map((f) => ({ path: f.path, size: f.size, is_whiteout: false }));
+    }
     if (viewMode === "layer") {
       return image.layers[selectedLayer].files;
     }
@@ -46,14 +122,14 @@ function App() {
       all.push(...image.layers[i].files);
     }
     return all;
-  }, [selectedLayer, viewMode]);
+  }, [image, selectedLayer, viewMode]);
 
   return (
     <div className="h-screen flex flex-col bg-background">
       {/* Header */}
-      <div className="border-b px-4 py-3 shrink-0">
+      <header className="border-b px-4 py-3 shrink-0">
         <div className="flex items-center gap-3">
-          <Container className="size-5 text-primary" />
+          <Container className="size-5 text-primary" aria-hidden />
           <h1 className="text-lg font-bold tracking-tight">
             {image.name}
             {image.tag && (
@@ -61,22 +137,123 @@ function App() {
             )}
           </h1>
           <div className="flex items-center gap-3 text-sm text-muted-foreground ml-2">
+            {image.id && (
+              <code className="text-xs" title={digestLines(image)}>
+                {image.id.replace(/^sha256:/, "").slice(0, 12)}
+              </code>
+            )}
+            {image.verify &&
+              (image.verify.mismatches?.length ? (
+                <span
+                  className="flex items-center gap-1 text-destructive"
+                  title={mismatchLines(image.verify)}
+                >
+                  <ShieldAlert className="size-3.5" aria-hidden />
+                  {image.verify.mismatches.length} {t("diffIdMismatches")}
+                </span>
+              ) : (
+                <span className="flex items-center gap-1" title={t("verifiedHint")}>
+                  <ShieldCheck className="size-3.5" aria-hidden />
+                  {t("verified")}
+                </span>
+              ))}
             {image.architecture && (
               <span className="flex items-center gap-1">
-                <Cpu className="size-3.5" />
+                <Cpu className="size-3.5" aria-hidden />
                 {image.architecture}
               </span>
             )}
-            <span>{formatBytes(image.total_size)}</span>
-            <span>{image.layers.length} layers</span>
+            {image.distro && (
+              <span>
+                {image.distro.name ??
+                  [image.distro.id, image.distro.version_id].filter(Boolean).join(" ")}
+              </span>
+            )}
+            {image.class && (
+              <span title={image.class.shell ? undefined : t("noShell")}>
+                {t(CLASS_LABELS[image.class.class])}
+              </span>
+            )}
+            {image.runtimes && (
+              <span title={image.runtimes.map((r) => "/" + r.path).join("\n")}>
+                {image.runtimes
+                  .map((r) => [RUNTIME_NAMES[r.kind], r.version].filter(Boolean).join(" "))
+                  .join(", ")}
+              </span>
+            )}
+            {image.provenance && (
+              <span className="flex items-center gap-1" title={provenanceLines(image.provenance)}>
+                <GitCommitHorizontal className="size-3.5" aria-hidden />
+                {image.provenance.vcs_revision?.slice(0, 12) ??
+                  image.provenance.frontend ??
+                  image.provenance.builder ??
+                  t("provenance")}
+              </span>
+            )}
+            {image.run_config && (
+              <button className="hover:underline" onClick={() => setRuntimeOpen(true)}>
+                {t("runtime")}
+              </button>
+            )}
+            {(image.labels || image.annotations) && (
+              <span title={metadataLines(image)}>
+                {t("labels")} ({Object.keys({ ...image.labels, ...image.annotations }).length})
+              </span>
+            )}
+            {image.dirs ? (
+              <button
+                className="hover:underline"
+                onClick={() => setDirsOpen(true)}
+                title={t("dirSizes")}
+              >
+                {formatBytes(image.total_size)}
+              </button>
+            ) : (
+              <span>{formatBytes(image.total_size)}</span>
+            )}
+            {compressed !== undefined && (
+              <span className="text-muted-foreground" title={t("compressedSize")}>
+                ({formatBytes(compressed)} {t("compressed")})
+              </span>
+            )}
+            {image.roots && (
+              <span
+                className="rounded bg-amber-100 px-1 text-xs text-amber-800"
+                title={`${t("partialHint")} ${image.roots.map((r) => `/${r}`).join(", ")}`}
+              >
+                {t("partial")}
+              </span>
+            )}
+            <span>{image.layers.length} {t("layers")}</span>
+            {image.cluster && <ClusterBadge check={image.cluster} />}
+            {image.toolchain && <ToolchainBadge tools={image.toolchain} />}
+            {image.posture && <PostureBadge posture={image.posture} />}
+            {image.waste && (
+              <button
+                className={
+                  image.waste.wasted_size > 0
+                    ? "text-amber-700 hover:underline"
+                    : "text-emerald-700 hover:underline"
+                }
+                onClick={() => setWasteOpen(true)}
+                title={`${formatBytes(image.waste.wasted_size)} ${t("wasted")}`}
+              >
+                {(image.waste.efficiency * 100).toFixed(1)}% {t("efficiency")}
+              </button>
+            )}
           </div>
+          {image.base && <BaseSplitBar split={image.base} total={image.total_size} />}
+          <ContrastToggle />
         </div>
-      </div>
+        {image.content && <ContentSummary sizes={image.content.totals} />}
+        {image.file_types && <FileTypeSummary sizes={image.file_types.totals} />}
+      </header>
 
       {/* Toolbar */}
       <Toolbar
         viewMode={viewMode}
         onViewModeChange={setViewMode}
+        hasMerged={!!image.merged}
         fileViewMode={fileViewMode}
         onFileViewModeChange={setFileViewMode}
         filter={filter}
@@ -85,16 +262,26 @@ function App() {
       />
 
       {/* 2-column layout */}
-      <div className="flex flex-1 min-h-0">
+      <main className="flex flex-1 min-h-0">
         {/* Left: layer list */}
-        <div className="w-80 shrink-0 border-r">
-          <LayerList
+        <div className="w-80 shrink-0 border-r flex flex-col">
+          <BuildTimeline
             layers={image.layers}
             selectedIndex={selectedLayer}
             onSelect={setSelectedLayer}
-            viewMode={viewMode}
-            sectionRef={layerRef}
           />
+          <div className="flex-1 min-h-0">
+            <LayerList
+              layers={image.layers}
+              selectedIndex={selectedLayer}
+              onSelect={setSelectedLayer}
+              viewMode={viewMode}
+              baseLayers={image.base?.layers}
+              content={image.content?.layers}
+              dockerfile={image.dockerfile}
+              sectionRef={layerRef}
+            />
+          </div>
         </div>
 
         {/* Right: file browser */}
@@ -107,9 +294,85 @@ function App() {
             fileRef={fileRef}
           />
         </div>
-      </div>
+      </main>
+
+      {wasteOpen && image.waste && (
+        <WasteDialog
+          waste={image.waste}
+          junk={image.junk}
+          duplicates={image.duplicates}
+          strip={image.strip}
+          onClose={() => setWasteOpen(false)}
+        />
+      )}
+      {runtimeOpen && image.run_config && (
+        <RuntimeDialog config={image.run_config} onClose={() => setRuntimeOpen(false)} />
+      )}
+      {dirsOpen && image.dirs && (
+        <DirDialog dirs={image.dirs} onClose={() => setDirsOpen(false)} />
+      )}
     </div>
   );
 }
 
+/** Where the image's repository runs; amber when some workload runs another build. */
+function ClusterBadge({ check }: { check: ClusterCheck }) {
+  if (check.workloads.length === 0) {
+    return <span>{t("clusterNone")}</span>;
+  }
+  const mismatch = check.workloads.some((w) => w.status === "mismatch");
+  const title = check.workloads
+    .map((w) => `${w.status}  ${w.namespace}/${w.name} [${w.container}] ${w.digest ?? ""}`)
+    .join("\n");
+  return (
+    <span className={mismatch ? "text-amber-700" : "text-emerald-700"} title={title}>
+      {mismatch
+        ? t("clusterMismatch")
+        : `${t("runningIn")} ${check.workloads.length} ${t("workloads")}`}
+    </span>
+  );
+}
+
+/** Build tools in the final image; the tooltip lists them with their sizes. */
+function ToolchainBadge({ tools }: { tools: Toolchain[] }) {
+  const size = tools.reduce((sum, tool) => sum + tool.size, 0);
+  const title = tools
+    .map((tool) => `${formatBytes(tool.size)}  ${tool.name}  ${tool.programs.map((p) => `/${p}`).join(", ")}`)
+    .join("\n");
+  return (
+    <span className="text-amber-700" title={`${t("toolchainHint")}\n${title}`}>
+      {formatBytes(size)} {t("buildTools")}
+    </span>
+  );
+}
+
+/** Who the image runs as, amber for root; the tooltip lists setuid and world-writable files. */
+function PostureBadge({ posture }: { posture: PostureReport }) {
+  const octal = (mode: number) => mode.toString(8).padStart(4, "0");
+  const lines = [
+    ...posture.setuid.map((f) => `${t("setuidFiles")}  ${f.layer + 1}  ${octal(f.mode)}  /${f.path}`),
+    ...posture.world_writable.map((f) => `${t("worldWritable")}  ${f.layer + 1}  ${octal(f.mode)}  /${f.path}`),
+  ];
+  const flagged = posture.runs_as_root === true || posture.world_writable.length > 0;
+  const label =
+    posture.runs_as_root === true
+      ? t("runsAsRoot")
+      : posture.user
+        ? `${t("runsAs")} ${posture.user}`
+        : null;
+  const counts = [
+    posture.setuid.length > 0 && `${posture.setuid.length} ${t("setuidFiles")}`,
+    posture.world_writable.length > 0 && `${posture.world_writable.length} ${t("worldWritable")}`,
+  ].filter(Boolean);
+  if (!label && counts.length === 0) return null;
+  return (
+    <span
+      className={flagged ? "text-amber-700" : "text-muted-foreground"}
+      title={[t("postureHint"), ...lines].join("\n")}
+    >
+      {[label, ...counts].filter(Boolean).join(" · ")}
+    </span>
+  );
+}
+
 export default App;
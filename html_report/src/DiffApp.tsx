@@ -0,0 +1,114 @@
+import { useState } from "react";
+import { GitCompareArrows, ChevronRight, ChevronDown, Layers } from "lucide-react";
+import type { ImageDiff, DiffSide, LayerDiff } from "@/types";
+import { formatBytes, formatDelta } from "@/lib/format";
+import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
+import { DiffTreemap } from "@/components/DiffTreemap";
+import { ContrastToggle } from "@/components/ContrastToggle";
+
+function sideLabel(side: DiffSide) {
+  return side.tag ? `${side.name}:${side.tag}` : side.name;
+}
+
+function LayerRow({ layer, tone }: { layer: LayerDiff; tone: "removed" | "added" | "shared" }) {
+  return (
+    <div
+      className={cn(
+        "px-3 py-2 border-l-2",
+        tone === "removed" && "border-destructive bg-destructive/5",
+        tone === "added" && "border-primary bg-primary/5",
+        tone === "shared" && "border-transparent"
+      )}
+    >
+      <div className="flex items-center gap-1.5">
+        <Layers className="size-3 text-muted-foreground shrink-0" />
+        <code className="text-xs text-muted-foreground">{layer.digest.slice(7, 19)}</code>
+        <span className="text-xs font-medium ml-auto">{formatBytes(layer.size)}</span>
+      </div>
+      {layer.created_by && (
+        <p className="text-[10px] text-muted-foreground font-mono truncate leading-tight mt-0.5">
+          {layer.created_by}
+        </p>
+      )}
+    </div>
+  );
+}
+
+function DiffApp({ diff }: { diff: ImageDiff }) {
+  const [sharedOpen, setSharedOpen] = useState(false);
+
+  const shared = diff.layers.filter((l) => l.status === "shared");
+  const removed = diff.layers.filter((l) => l.status === "removed");
+  const added = diff.layers.filter((l) => l.status === "added");
+  const delta = diff.right.total_size - diff.left.total_size;
+
+  return (
+    <div className="h-screen flex flex-col bg-background">
+      {/* Header */}
+      <header className="border-b px-4 py-3 shrink-0">
+        <div className="flex items-center gap-3">
+          <GitCompareArrows className="size-5 text-primary" aria-hidden />
+          <h1 className="text-lg font-bold tracking-tight">
+            {sideLabel(diff.left)}
+            <span className="text-muted-foreground font-normal"> → </span>
+            {sideLabel(diff.right)}
+          </h1>
+          <div className="flex items-center gap-3 text-sm text-muted-foreground ml-2">
+            <span>
+              {formatBytes(diff.left.total_size)} → {formatBytes(diff.right.total_size)}
+            </span>
+            <span className={cn("font-medium", delta > 0 ? "text-destructive" : "text-primary")}>
+              {formatDelta(delta)}
+            </span>
+            <span>{diff.shared_layers} {t("sharedLayers")}</span>
+          </div>
+          <ContrastToggle />
+        </div>
+      </header>
+
+      <main className="flex flex-1 min-h-0">
+        {/* Left: side-by-side layers */}
+        <div className="w-[36rem] shrink-0 border-r overflow-y-auto">
+          <button
+            aria-expanded={sharedOpen}
+            className="w-full flex items-center gap-1.5 px-3 py-2 text-xs text-muted-foreground border-b hover:bg-muted/50"
+            onClick={() => setSharedOpen((o) => !o)}
+          >
+            {sharedOpen ? <ChevronDown className="size-3" aria-hidden /> : <ChevronRight className="size-3" aria-hidden />}
+            {shared.length} {t("sharedLayers")} ({formatBytes(diff.shared_size)})
+          </button>
+          {sharedOpen && shared.map((l) => <LayerRow key={l.digest} layer={l} tone="shared" />)}
+
+          <div className="grid grid-cols-2 divide-x">
+            <div>
+              <div className="px-3 py-1.5 text-xs font-medium border-b truncate">
+                {sideLabel(diff.left)}
+              </div>
+              {removed.length === 0 && (
+                <p className="px-3 py-2 text-xs text-muted-foreground">{t("noUniqueLayers")}</p>
+              )}
+              {removed.map((l) => <LayerRow key={l.digest} layer={l} tone="removed" />)}
+            </div>
+            <div>
+              <div className="px-3 py-1.5 text-xs font-medium border-b truncate">
+                {sideLabel(diff.right)}
+              </div>
+              {added.length === 0 && (
+                <p className="px-3 py-2 text-xs text-muted-foreground">{t("noUniqueLayers")}</p>
+              )}
+              {added.map((l) => <LayerRow key={l.digest} layer={l} tone="added" />)}
+            </div>
+          </div>
+        </div>
+
+        {/* Right: directory delta treemap */}
+        <div className="flex-1 min-w-0 flex flex-col">
+          <DiffTreemap dirs={diff.dirs} />
+        </div>
+      </main>
+    </div>
+  );
+}
+
+export default DiffApp;
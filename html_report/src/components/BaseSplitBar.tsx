@@ -0,0 +1,25 @@
+import type { BaseSplit } from "@/types";
+import { formatBytes } from "@/lib/format";
+import { t } from "@/lib/i18n";
+
+export function BaseSplitBar({ split, total }: { split: BaseSplit; total: number }) {
+  const inheritedPct = total > 0 ? (split.inherited_size / total) * 100 : 0;
+
+  return (
+    <div className="flex items-center gap-2 text-xs text-muted-foreground ml-4">
+      <div className="flex h-2 w-32 overflow-hidden rounded-full bg-muted" aria-hidden>
+        <div className="bg-muted-foreground/40" style={{ width: `${inheritedPct}%` }} />
+        <div className="flex-1 bg-primary" />
+      </div>
+      <span>
+        {t("inheritedFrom")} <span className="font-medium text-foreground">{split.name}</span>{" "}
+        {formatBytes(split.inherited_size)}
+      </span>
+      <span>·</span>
+      <span>
+        {t("addedOnTop")}{" "}
+        <span className="font-medium text-foreground">{formatBytes(split.added_size)}</span>
+      </span>
+    </div>
+  );
+}
@@ -0,0 +1,52 @@
+import type { LayerInfo } from "@/types";
+import { formatAge } from "@/lib/format";
+import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
+
+/** Each layer as a tick at the time it was created, oldest at the left. Base
+ * image layers are usually built long before the rest, so the gap shows how
+ * old the base is. */
+export function BuildTimeline({
+  layers,
+  selectedIndex,
+  onSelect,
+}: {
+  layers: LayerInfo[];
+  selectedIndex: number;
+  onSelect: (i: number) => void;
+}) {
+  const times = layers.map((l) => (l.created ? Date.parse(l.created) : NaN));
+  const known = times.filter((time) => !Number.isNaN(time));
+  if (known.length < 2) return null;
+  const first = Math.min(...known);
+  const last = Math.max(...known);
+  if (first === last) return null;
+
+  return (
+    <div className="px-3 py-2 border-b text-[10px] text-muted-foreground" title={t("buildTimeline")}>
+      <div className="relative h-3 mx-1" role="group" aria-label={t("buildTimeline")}>
+        <div className="absolute inset-x-0 top-1/2 h-px bg-border" aria-hidden />
+        {times.map((time, i) =>
+          Number.isNaN(time) ? null : (
+            <button
+              key={layers[i].digest}
+              tabIndex={-1}
+              className={cn(
+                "absolute top-0 h-3 w-1 -translate-x-1/2 rounded-sm",
+                i === selectedIndex ? "bg-primary" : "bg-muted-foreground/50 hover:bg-foreground"
+              )}
+              style={{ left: `${((time - first) / (last - first)) * 100}%` }}
+              title={`#${i + 1} · ${layers[i].created} (${formatAge(new Date(time))} ${t("ago")})`}
+              aria-label={`#${i + 1}`}
+              onClick={() => onSelect(i)}
+            />
+          )
+        )}
+      </div>
+      <div className="flex justify-between mt-1">
+        <span>{new Date(first).toLocaleDateString()}</span>
+        <span>{new Date(last).toLocaleDateString()}</span>
+      </div>
+    </div>
+  );
+}
@@ -1,6 +1,7 @@
 import { useEffect, useState } from "react";
 import { X, Copy, Check } from "lucide-react";
 import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
 
 type ViewMode = "pretty" | "original";
 
@@ -39,6 +40,8 @@ export function CommandDialog({
   return (
     <div
       role="dialog"
+      aria-modal="true"
+      aria-labelledby="command-dialog-title"
       className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
       onClick={onClose}
     >
@@ -48,8 +51,8 @@ export function CommandDialog({
       >
         {/* Header */}
         <div className="flex items-center justify-between px-4 py-3 border-b shrink-0">
-          <h2 className="text-sm font-medium">
-            Layer {layerIndex + 1} Command
+          <h2 id="command-dialog-title" className="text-sm font-medium">
+            {t("layerCommand")} #{layerIndex + 1}
           </h2>
           <div className="flex items-center gap-1.5">
             {/* View toggle */}
@@ -73,6 +76,7 @@ export function CommandDialog({
               className="p-1.5 rounded hover:bg-muted text-muted-foreground hover:text-foreground transition-colors"
               onClick={copyToClipboard}
               title="Copy"
+              aria-label="Copy"
             >
               {copied ? (
                 <Check className="size-4 text-green-500" />
@@ -84,6 +88,7 @@ export function CommandDialog({
               className="p-1.5 rounded hover:bg-muted text-muted-foreground hover:text-foreground transition-colors"
               onClick={onClose}
               title="Close"
+              aria-label="Close"
             >
               <X className="size-4" />
             </button>
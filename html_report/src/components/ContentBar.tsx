@@ -0,0 +1,100 @@
+import type { Category, CategorySizes, FileType, FileTypeSizes } from "@/types";
+import { formatBytes } from "@/lib/format";
+import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
+
+const CATEGORIES: { key: Category; label: () => string; color: string }[] = [
+  { key: "os", label: () => t("categoryOs"), color: "bg-sky-500" },
+  { key: "runtime", label: () => t("categoryRuntime"), color: "bg-violet-500" },
+  { key: "code", label: () => t("categoryCode"), color: "bg-emerald-500" },
+  { key: "assets", label: () => t("categoryAssets"), color: "bg-amber-400" },
+  { key: "data", label: () => t("categoryData"), color: "bg-pink-500" },
+  { key: "cache", label: () => t("categoryCache"), color: "bg-red-400" },
+  { key: "other", label: () => t("categoryOther"), color: "bg-muted-foreground/40" },
+];
+
+function present(sizes: CategorySizes) {
+  const total = Object.values(sizes).reduce((sum, n) => sum + (n ?? 0), 0);
+  const parts = CATEGORIES.filter((c) => (sizes[c.key] ?? 0) > 0).map((c) => ({
+    ...c,
+    bytes: sizes[c.key]!,
+  }));
+  return { total, parts };
+}
+
+/** Stacked bar of bytes per content category, with a tooltip per segment. */
+export function ContentBar({ sizes, className }: { sizes: CategorySizes; className?: string }) {
+  const { total, parts } = present(sizes);
+  if (total === 0) return null;
+
+  return (
+    <div
+      className={cn("flex h-1.5 overflow-hidden rounded-full bg-muted", className)}
+      role="img"
+      aria-label={parts.map((p) => `${p.label()} ${formatBytes(p.bytes)}`).join(", ")}
+    >
+      {parts.map((p) => (
+        <div
+          key={p.key}
+          className={p.color}
+          style={{ width: `${(p.bytes / total) * 100}%` }}
+          title={`${p.label()} ${formatBytes(p.bytes)}`}
+        />
+      ))}
+    </div>
+  );
+}
+
+/** Image-wide totals: the bar plus a legend with sizes and shares. */
+export function ContentSummary({ sizes }: { sizes: CategorySizes }) {
+  const { total, parts } = present(sizes);
+  if (total === 0) return null;
+
+  return (
+    <div className="flex items-center gap-3 mt-2 text-xs text-muted-foreground">
+      <span>{t("contentMix")}</span>
+      <ContentBar sizes={sizes} className="w-40" />
+      {parts.map((p) => (
+        <span key={p.key} className="flex items-center gap-1">
+          <span className={cn("size-2 rounded-full", p.color)} aria-hidden />
+          {p.label()}{" "}
+          <span className="font-medium text-foreground">{formatBytes(p.bytes)}</span>
+          <span>({((p.bytes / total) * 100).toFixed(0)}%)</span>
+        </span>
+      ))}
+    </div>
+  );
+}
+
+const FILE_TYPES: { key: FileType; label: () => string }[] = [
+  { key: "executable", label: () => t("typeExecutable") },
+  { key: "shared_lib", label: () => t("typeSharedLib") },
+  { key: "python", label: () => t("typePython") },
+  { key: "javascript", label: () => t("typeJavaScript") },
+  { key: "docs", label: () => t("typeDocs") },
+  { key: "locale", label: () => t("typeLocale") },
+  { key: "image", label: () => t("typeImage") },
+  { key: "archive", label: () => t("typeArchive") },
+  { key: "other", label: () => t("typeOther") },
+];
+
+/** Image-wide bytes per file type, largest first. */
+export function FileTypeSummary({ sizes }: { sizes: FileTypeSizes }) {
+  const total = Object.values(sizes).reduce((sum, n) => sum + (n ?? 0), 0);
+  if (total === 0) return null;
+  const parts = FILE_TYPES.filter((f) => (sizes[f.key] ?? 0) > 0)
+    .map((f) => ({ ...f, bytes: sizes[f.key]! }))
+    .sort((a, b) => b.bytes - a.bytes);
+
+  return (
+    <div className="flex items-center gap-3 mt-1 text-xs text-muted-foreground">
+      <span>{t("fileTypes")}</span>
+      {parts.map((p) => (
+        <span key={p.key}>
+          {p.label()} <span className="font-medium text-foreground">{formatBytes(p.bytes)}</span>{" "}
+          ({((p.bytes / total) * 100).toFixed(0)}%)
+        </span>
+      ))}
+    </div>
+  );
+}
@@ -0,0 +1,24 @@
+import { Contrast } from "lucide-react";
+import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
+import { useHighContrast } from "@/hooks/useHighContrast";
+
+export function ContrastToggle() {
+  const [high, toggle] = useHighContrast();
+
+  return (
+    <button
+      type="button"
+      aria-pressed={high}
+      aria-label={t("highContrast")}
+      title={t("highContrast")}
+      className={cn(
+        "ml-auto p-1.5 rounded-md border transition-colors",
+        high ? "bg-foreground text-background" : "text-muted-foreground hover:text-foreground"
+      )}
+      onClick={toggle}
+    >
+      <Contrast className="size-4" />
+    </button>
+  );
+}
@@ -0,0 +1,168 @@
+import { useEffect, useMemo, useRef, useState } from "react";
+import type { DirDelta } from "@/types";
+import { formatDelta } from "@/lib/format";
+import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
+
+export interface Rect<T> {
+  item: T;
+  x: number;
+  y: number;
+  w: number;
+  h: number;
+}
+
+export function parentOf(path: string): string {
+  const i = path.lastIndexOf("/");
+  return i === -1 ? "" : path.slice(0, i);
+}
+
+/**
+ * Peel the largest entry off along the longer side, then lay out the rest in
+ * the remaining space. Coordinates are percentages of the container.
+ */
+export function layout<T>(
+  items: T[],
+  weight: (item: T) => number,
+  x: number,
+  y: number,
+  w: number,
+  h: number
+): Rect<T>[] {
+  if (items.length === 0) return [];
+  const [first, ...rest] = items;
+  if (rest.length === 0) return [{ item: first, x, y, w, h }];
+
+  const total = items.reduce((sum, d) => sum + weight(d), 0);
+  const share = total === 0 ? 1 / items.length : weight(first) / total;
+  if (w >= h) {
+    return [
+      { item: first, x, y, w: w * share, h },
+      ...layout(rest, weight, x + w * share, y, w * (1 - share), h),
+    ];
+  }
+  return [
+    { item: first, x, y, w, h: h * share },
+    ...layout(rest, weight, x, y + h * share, w, h * (1 - share)),
+  ];
+}
+
+export function DiffTreemap({ dirs }: { dirs: DirDelta[] }) {
+  const [root, setRoot] = useState("");
+  const [focused, setFocused] = useState(0);
+  const mapRef = useRef<HTMLDivElement>(null);
+
+  const children = useMemo(
+    () =>
+      dirs
+        .filter((d) => parentOf(d.path) === root)
+        .sort((a, b) => Math.abs(b.delta) - Math.abs(a.delta)),
+    [dirs, root]
+  );
+
+  const rects = useMemo(
+    () => layout(children, (d) => Math.abs(d.delta), 0, 0, 100, 100),
+    [children]
+  );
+  const crumbs = root ? root.split("/") : [];
+
+  useEffect(() => setFocused(0), [root]);
+
+  function hasChildren(path: string) {
+    return dirs.some((d) => parentOf(d.path) === path);
+  }
+
+  function focusCell(i: number) {
+    setFocused(i);
+    mapRef.current?.querySelectorAll<HTMLElement>("[data-cell]")[i]?.focus();
+  }
+
+  // Cells are ordered largest first; arrows step through that order.
+  function handleKeyDown(e: React.KeyboardEvent) {
+    if (e.key === "ArrowRight" || e.key === "ArrowDown") {
+      e.preventDefault();
+      focusCell(Math.min(focused + 1, rects.length - 1));
+    } else if (e.key === "ArrowLeft" || e.key === "ArrowUp") {
+      e.preventDefault();
+      focusCell(Math.max(focused - 1, 0));
+    } else if (e.key === "Home") {
+      e.preventDefault();
+      focusCell(0);
+    } else if (e.key === "End") {
+      e.preventDefault();
+      focusCell(rects.length - 1);
+    } else if ((e.key === "Backspace" || e.key === "Escape") && root) {
+      e.preventDefault();
+      setRoot(parentOf(root));
+    }
+  }
+
+  return (
+    <>
+      <nav aria-label={t("treemap")} className="flex items-center gap-1 px-4 py-2 border-b text-xs shrink-0">
+        <button
+          className="text-primary hover:underline"
+          aria-label={t("treemapUp")}
+          disabled={!root}
+          onClick={() => setRoot(parentOf(root))}
+        >
+          ..
+        </button>
+        <button className="text-primary hover:underline" onClick={() => setRoot("")}>
+          /
+        </button>
+        {crumbs.map((part, i) => (
+          <button
+            key={i}
+            className="text-primary hover:underline"
+            onClick={() => setRoot(crumbs.slice(0, i + 1).join("/"))}
+          >
+            {part}/
+          </button>
+        ))}
+        <span id="treemap-legend" className="ml-auto text-muted-foreground">
+          {t("treemapLegend")}
+        </span>
+      </nav>
+      <div
+        ref={mapRef}
+        role="group"
+        aria-label={t("treemap")}
+        aria-describedby="treemap-legend"
+        className="relative flex-1 m-2"
+        onKeyDown={handleKeyDown}
+      >
+        {rects.length === 0 && (
+          <div className="flex items-center justify-center h-full text-muted-foreground text-sm">
+            {t("treemapEmpty")}
+          </div>
+        )}
+        {rects.map((r, i) => {
+          const name = r.item.path.slice(root ? root.length + 1 : 0);
+          const drillable = hasChildren(r.item.path);
+          return (
+            <button
+              key={r.item.path}
+              data-cell
+              tabIndex={i === focused ? 0 : -1}
+              aria-label={`/${r.item.path} ${formatDelta(r.item.delta)}`}
+              aria-disabled={!drillable}
+              title={`/${r.item.path}  ${formatDelta(r.item.delta)}`}
+              className={cn(
+                "absolute border border-background overflow-hidden text-left p-1 text-[10px]",
+                r.item.delta > 0 ? "bg-destructive/70 text-white" : "bg-primary/70 text-white",
+                drillable ? "cursor-pointer hover:opacity-90" : "cursor-default"
+              )}
+              style={{ left: `${r.x}%`, top: `${r.y}%`, width: `${r.w}%`, height: `${r.h}%` }}
+              onFocus={() => setFocused(i)}
+              onClick={() => drillable && setRoot(r.item.path)}
+            >
+              <div className="font-medium truncate">{name}</div>
+              <div className="truncate">{formatDelta(r.item.delta)}</div>
+            </button>
+          );
+        })}
+      </div>
+    </>
+  );
+}
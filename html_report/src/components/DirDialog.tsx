@@ -0,0 +1,117 @@
+import { useEffect, useMemo, useState } from "react";
+import { X } from "lucide-react";
+import type { DirSize } from "@/types";
+import { formatBytes } from "@/lib/format";
+import { t } from "@/lib/i18n";
+import { cn } from "@/lib/utils";
+import { layout, parentOf } from "@/components/DiffTreemap";
+
+/** Treemap of the final filesystem's directories, from the rollups peel computed. */
+export function DirDialog({ dirs, onClose }: { dirs: DirSize[]; onClose: () => void }) {
+  const [root, setRoot] = useState("");
+
+  useEffect(() => {
+    function onKey(e: KeyboardEvent) {
+      if (e.key !== "Escape") return;
+      if (root) setRoot(parentOf(root));
+      else onClose();
+    }
+    document.addEventListener("keydown", onKey);
+    return () => document.removeEventListener("keydown", onKey);
+  }, [root, onClose]);
+
+  const children = useMemo(
+    () => dirs.filter((d) => parentOf(d.path) === root).sort((a, b) => b.size - a.size),
+    [dirs, root]
+  );
+  const rects = useMemo(() => layout(children, (d) => d.size, 0, 0, 100, 100), [children]);
+  const crumbs = root ? root.split("/") : [];
+
+  function hasChildren(path: string) {
+    return dirs.some((d) => parentOf(d.path) === path);
+  }
+
+  return (
+    <div
+      role="dialog"
+      aria-modal="true"
+      aria-labelledby="dir-dialog-title"
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
+      onClick={onClose}
+    >
+      <div
+        className="bg-background border rounded-lg shadow-lg max-w-4xl w-full mx-4 h-[80vh] flex flex-col"
+        onClick={(e) => e.stopPropagation()}
+      >
+        {/* Header */}
+        <div className="flex items-center justify-between px-4 py-3 border-b shrink-0">
+          <h2 id="dir-dialog-title" className="text-sm font-medium">
+            {t("dirSizes")}
+          </h2>
+          <button
+            className="p-1.5 rounded hover:bg-muted text-muted-foreground hover:text-foreground transition-colors"
+            onClick={onClose}
+            title="Close"
+            aria-label="Close"
+          >
+            <X className="size-4" aria-hidden />
+          </button>
+        </div>
+
+        <nav aria-label={t("dirSizes")} className="flex items-center gap-1 px-4 py-2 border-b text-xs shrink-0">
+          <button
+            className="text-primary hover:underline"
+            aria-label={t("treemapUp")}
+            disabled={!root}
+            onClick={() => setRoot(parentOf(root))}
+          >
+            ..
+          </button>
+          <button className="text-primary hover:underline" onClick={() => setRoot("")}>
+            /
+          </button>
+          {crumbs.map((part, i) => (
+            <button
+              key={i}
+              className="text-primary hover:underline"
+              onClick={() => setRoot(crumbs.slice(0, i + 1).join("/"))}
+            >
+              {part}/
+            </button>
+          ))}
+        </nav>
+
+        {/* Body */}
+        <div className="relative flex-1 m-2">
+          {rects.length === 0 && (
+            <div className="flex items-center justify-center h-full text-muted-foreground text-sm">
+              {t("dirSizesEmpty")}
+            </div>
+          )}
+          {rects.map((r) => {
+            const name = r.item.path.slice(root ? root.length + 1 : 0);
+            const drillable = hasChildren(r.item.path);
+            const label = `/${r.item.path}  ${formatBytes(r.item.size)} · ${r.item.files} ${t("files")}`;
+            return (
+              <button
+                key={r.item.path}
+                aria-label={label}
+                aria-disabled={!drillable}
+                title={label}
+                className={cn(
+                  "absolute border border-background overflow-hidden text-left p-1 text-[10px] bg-primary/70 text-white",
+                  drillable ? "cursor-pointer hover:opacity-90" : "cursor-default"
+                )}
+                style={{ left: `${r.x}%`, top: `${r.y}%`, width: `${r.w}%`, height: `${r.h}%` }}
+                onClick={() => drillable && setRoot(r.item.path)}
+              >
+                <div className="font-medium truncate">{name}</div>
+                <div className="truncate">{formatBytes(r.item.size)}</div>
+              </button>
+            );
+          })}
+        </div>
+      </div>
+    </div>
+  );
+}
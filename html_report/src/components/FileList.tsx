@@ -1,7 +1,9 @@
-import { useState, useMemo, useRef, useCallback, useEffect, type RefObject } from "react";
+import { useState, useMemo, useRef, useCallback, useEffect, useId, type RefObject } from "react";
 import { File, ArrowUp, ArrowDown, ArrowUpDown } from "lucide-react";
+import type { FileChange } from "@/types";
 import { formatBytes } from "@/lib/format";
 import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
 import { usePagination } from "@/hooks/usePagination";
 import { Pagination } from "./Pagination";
 
@@ -11,8 +13,13 @@ export interface FileListItem {
   name: string;
   size: number;
   is_whiteout: boolean;
+  change?: FileChange;
+  entropy?: number;
 }
 
+/** Bits per byte at which a file is flagged; matches peel's analysis threshold. */
+const HIGH_ENTROPY = 7.5;
+
 type SortKey = "name" | "size";
 type SortDir = "asc" | "desc";
 
@@ -41,6 +48,7 @@ export function FileList({
   const [sortKey, setSortKey] = useState<SortKey>(defaultSortKey);
   const [sortDir, setSortDir] = useState<SortDir>(defaultSortDir);
   const [selectedIndex, setSelectedIndex] = useState(-1);
+  const listId = useId();
 
   const sorted = useMemo(() => {
     const copy = [...items];
@@ -122,12 +130,16 @@ export function FileList({
       className={cn("flex flex-col h-full", sectionRef && focusRing)}
       ref={mergedRef}
       tabIndex={sectionRef ? -1 : undefined}
+      role="group"
+      aria-label={t("fileList")}
+      aria-activedescendant={selectedIndex >= 0 ? `${listId}-${selectedIndex}` : undefined}
       onKeyDown={handleKeyDown}
     >
       {/* Sort header */}
       <div className="flex items-center border-b bg-background px-3 py-1.5 text-sm font-medium shrink-0">
         <button
           tabIndex={-1}
+          aria-label={t("sortByName")}
           className="flex items-center gap-1 hover:text-foreground text-muted-foreground transition-colors"
           onClick={() => toggleSort("name")}
         >
@@ -136,6 +148,7 @@ export function FileList({
         </button>
         <button
           tabIndex={-1}
+          aria-label={t("sortBySize")}
           className="flex items-center gap-1 ml-auto hover:text-foreground text-muted-foreground transition-colors"
           onClick={() => toggleSort("size")}
         >
@@ -145,7 +158,7 @@ export function FileList({
       </div>
 
       {/* File rows */}
-      <div className="flex-1 overflow-hidden px-1 pt-1">
+      <div role="listbox" aria-label={t("fileList")} className="flex-1 overflow-hidden px-1 pt-1">
         {items.length === 0 ? (
           <div className="flex items-center justify-center h-full text-muted-foreground text-sm">
             {emptyMessage}
@@ -159,6 +172,9 @@ export function FileList({
             return (
               <div
                 key={`${file.name}-${page}-${i}`}
+                id={`${listId}-${i}`}
+                role="option"
+                aria-selected={i === selectedIndex}
                 className={cn(
                   "flex items-center gap-2 py-0.5 px-2 text-sm rounded relative",
                   i === selectedIndex ? "bg-primary/10" : "hover:bg-muted/50"
@@ -168,7 +184,7 @@ export function FileList({
                   className="absolute inset-y-0 left-0 bg-primary/5 rounded"
                   style={{ width: `${pct}%` }}
                 />
-                <File className="size-3.5 shrink-0 text-muted-foreground relative" />
+                <File className="size-3.5 shrink-0 text-muted-foreground relative" aria-hidden />
                 <span
                   className={cn(
                     "truncate relative",
@@ -176,8 +192,24 @@ export function FileList({
                   )}
                 >
                   {displayName}
-                  {file.is_whiteout && " (deleted)"}
+                  {file.is_whiteout && ` (${t("deleted")})`}
                 </span>
+                {file.change === "modified" && (
+                  <span
+                    className="shrink-0 rounded bg-amber-500/15 px-1 text-[10px] text-amber-700 relative"
+                    title={t("modifiedHint")}
+                  >
+                    {t("modified")}
+                  </span>
+                )}
+                {file.entropy !== undefined && file.entropy >= HIGH_ENTROPY && (
+                  <span
+                    className="shrink-0 rounded bg-red-500/15 px-1 text-[10px] text-red-700 relative"
+                    title={`${t("highEntropyHint")} (${file.entropy.toFixed(2)})`}
+                  >
+                    {t("highEntropy")}
+                  </span>
+                )}
                 <span className="ml-auto shrink-0 text-xs text-muted-foreground tabular-nums relative">
                   {formatBytes(file.size)}
                 </span>
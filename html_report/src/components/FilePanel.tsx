@@ -3,6 +3,7 @@ import type { FileEntry } from "@/types";
 import type { FileViewMode } from "./Toolbar";
 import { FileTreeSplit } from "./FileTreeSplit";
 import { FileList } from "./FileList";
+import { t } from "@/lib/i18n";
 
 export function FilePanel({
   files,
@@ -26,7 +27,7 @@ export function FilePanel({
   if (filtered.length === 0) {
     return (
       <div className="flex items-center justify-center h-full text-muted-foreground text-sm">
-        {filter ? "No files match filter" : "No files in this layer"}
+        {filter ? t("noFilesMatch") : t("noFilesInLayer")}
       </div>
     );
   }
@@ -39,6 +40,8 @@ export function FilePanel({
     name: f.path,
     size: f.size,
     is_whiteout: f.is_whiteout,
+    change: f.change,
+    entropy: f.entropy,
   }));
 
   return <FileList items={items} sectionRef={fileRef} />;
@@ -1,4 +1,4 @@
-import { useState, useMemo, useEffect, type RefObject } from "react";
+import { useState, useMemo, useEffect, useId, type RefObject } from "react";
 import {
   ChevronRight,
   Folder,
@@ -9,6 +9,7 @@ import type { FileEntry, TreeNode } from "@/types";
 import { formatBytes } from "@/lib/format";
 import { buildTree } from "@/lib/tree";
 import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
 import { FileList } from "./FileList";
 
 function sortedDirChildren(node: TreeNode): TreeNode[] {
@@ -22,6 +23,7 @@ function DirTreeNode({
   depth,
   expandedDirs,
   selectedDir,
+  idOf,
   onSelect,
   onToggle,
 }: {
@@ -29,6 +31,7 @@ function DirTreeNode({
   depth: number;
   expandedDirs: Set<TreeNode>;
   selectedDir: TreeNode | null;
+  idOf: (node: TreeNode) => string | undefined;
   onSelect: (node: TreeNode) => void;
   onToggle: (node: TreeNode) => void;
 }) {
@@ -39,6 +42,11 @@ function DirTreeNode({
   return (
     <>
       <div
+        id={idOf(node)}
+        role="treeitem"
+        aria-level={depth + 1}
+        aria-selected={isSelected}
+        aria-expanded={dirs.length > 0 ? isOpen : undefined}
         data-dir-selected={isSelected || undefined}
         className={cn(
           "flex items-center gap-1 py-0.5 px-2 rounded text-sm cursor-pointer",
@@ -51,30 +59,35 @@ function DirTreeNode({
         }}
       >
         <ChevronRight
+          aria-hidden
           className={cn(
             "size-3.5 shrink-0 text-muted-foreground transition-transform",
             dirs.length === 0 && "invisible",
             isOpen && "rotate-90"
           )}
         />
-        <Folder className="size-3.5 shrink-0 text-blue-500" />
+        <Folder className="size-3.5 shrink-0 text-blue-500" aria-hidden />
         <span className="truncate">{node.name || "/"}</span>
         <span className="ml-auto shrink-0 text-xs text-muted-foreground">
           {formatBytes(node.size)}
         </span>
       </div>
-      {isOpen &&
-        dirs.map((child) => (
-          <DirTreeNode
-            key={child.name}
-            node={child}
-            depth={depth + 1}
-            expandedDirs={expandedDirs}
-            selectedDir={selectedDir}
-            onSelect={onSelect}
-            onToggle={onToggle}
-          />
-        ))}
+      {isOpen && dirs.length > 0 && (
+        <div role="group">
+          {dirs.map((child) => (
+            <DirTreeNode
+              key={child.name}
+              node={child}
+              depth={depth + 1}
+              expandedDirs={expandedDirs}
+              selectedDir={selectedDir}
+              idOf={idOf}
+              onSelect={onSelect}
+              onToggle={onToggle}
+            />
+          ))}
+        </div>
+      )}
     </>
   );
 }
@@ -108,19 +121,30 @@ export function FileTreeSplit({
     [activeDir]
   );
 
-  // Flat list of visible dirs for keyboard nav (null = root)
-  const visibleDirs = useMemo(() => {
+  // Flat list of visible dirs for keyboard nav (null = root), plus each
+  // visible dir's parent for ArrowLeft
+  const { visibleDirs, parents } = useMemo(() => {
     const result: (TreeNode | null)[] = [null];
-    function walk(node: TreeNode) {
+    const parentOf = new Map<TreeNode, TreeNode | null>();
+    function walk(node: TreeNode, parent: TreeNode | null) {
       for (const child of sortedDirChildren(node)) {
         result.push(child);
-        if (expandedDirs.has(child)) walk(child);
+        parentOf.set(child, parent);
+        if (expandedDirs.has(child)) walk(child, child);
       }
     }
-    walk(tree); // root children always visible
-    return result;
+    walk(tree, null); // root children always visible
+    return { visibleDirs: result, parents: parentOf };
   }, [tree, expandedDirs]);
 
+  // Stable-per-render element ids for aria-activedescendant
+  const treeId = useId();
+  const ids = useMemo(
+    () => new Map(visibleDirs.map((d, i) => [d, `${treeId}-${i}`])),
+    [visibleDirs, treeId]
+  );
+  const idOf = (node: TreeNode | null) => ids.get(node);
+
   function toggleExpand(node: TreeNode) {
     setExpandedDirs((prev) => {
       const next = new Set(prev);
@@ -165,6 +189,24 @@ export function FileTreeSplit({
       if (idx > 0) {
         setSelectedDir(visibleDirs[idx - 1]);
       }
+    } else if (e.key === "Home") {
+      e.preventDefault();
+      setSelectedDir(null);
+    } else if (e.key === "End") {
+      e.preventDefault();
+      setSelectedDir(visibleDirs[visibleDirs.length - 1]);
+    } else if (e.key === "ArrowRight") {
+      e.preventDefault();
+      if (selectedDir === null) return;
+      const children = sortedDirChildren(selectedDir);
+      if (children.length === 0) return;
+      if (!expandedDirs.has(selectedDir)) toggleExpand(selectedDir);
+      else setSelectedDir(children[0]);
+    } else if (e.key === "ArrowLeft") {
+      e.preventDefault();
+      if (selectedDir === null) return;
+      if (expandedDirs.has(selectedDir)) toggleExpand(selectedDir);
+      else setSelectedDir(parents.get(selectedDir) ?? null);
     } else if (e.key === "Enter") {
       e.preventDefault();
       if (selectedDir !== null) {
@@ -179,10 +221,13 @@ export function FileTreeSplit({
       <div
         ref={treeRef}
         tabIndex={-1}
+        role="tree"
+        aria-label={t("directoryTree")}
+        aria-activedescendant={idOf(selectedDir)}
         className={`w-2/5 border-r overflow-auto p-2 ${focusRing}`}
         onKeyDown={handleKeyDown}
       >
-        <div className="flex items-center gap-1 mb-1 px-2">
+        <div role="presentation" className="flex items-center gap-1 mb-1 px-2">
           <button
             tabIndex={-1}
             className="flex items-center gap-1 text-xs text-muted-foreground hover:text-foreground transition-colors"
@@ -203,6 +248,10 @@ export function FileTreeSplit({
         </div>
         {/* Root entry */}
         <div
+          id={idOf(null)}
+          role="treeitem"
+          aria-level={1}
+          aria-selected={selectedDir === null}
           data-dir-selected={selectedDir === null || undefined}
           className={cn(
             "flex items-center gap-1 py-0.5 px-2 rounded text-sm cursor-pointer",
@@ -211,8 +260,8 @@ export function FileTreeSplit({
           style={{ paddingLeft: "8px" }}
           onClick={() => setSelectedDir(null)}
         >
-          <ChevronRight className="size-3.5 shrink-0 invisible" />
-          <Folder className="size-3.5 shrink-0 text-blue-500" />
+          <ChevronRight className="size-3.5 shrink-0 invisible" aria-hidden />
+          <Folder className="size-3.5 shrink-0 text-blue-500" aria-hidden />
           <span className="truncate">/ root</span>
           <span className="ml-auto shrink-0 text-xs text-muted-foreground">
             {formatBytes(tree.size)}
@@ -225,6 +274,7 @@ export function FileTreeSplit({
             depth={1}
             expandedDirs={expandedDirs}
             selectedDir={selectedDir}
+            idOf={idOf}
             onSelect={setSelectedDir}
             onToggle={toggleExpand}
           />
@@ -237,7 +287,7 @@ export function FileTreeSplit({
           items={fileChildren}
           defaultSortKey="size"
           defaultSortDir="desc"
-          emptyMessage="No files in this directory"
+          emptyMessage={t("noFilesInDir")}
           sectionRef={fileRef}
         />
       </div>
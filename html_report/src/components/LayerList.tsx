@@ -1,24 +1,52 @@
-import { useState, useEffect, type RefObject } from "react";
+import { useState, useEffect, Fragment, type RefObject } from "react";
 import { Layers, Terminal } from "lucide-react";
-import type { LayerInfo } from "@/types";
+import type { CategorySizes, DockerfileMap, LayerInfo, SourceRef } from "@/types";
 import type { ViewMode } from "./Toolbar";
-import { formatBytes } from "@/lib/format";
+import { formatAge, formatBytes } from "@/lib/format";
 import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
 import { CommandDialog } from "./CommandDialog";
+import { ContentBar } from "./ContentBar";
 
 const focusRing = "focus:outline-none focus:bg-primary/10";
 
+/** Same thresholds as the terminal report: at least 1 MiB, and the blob
+ * is 90% or more of the layer's size. */
+function poorlyCompressed(layer: LayerInfo): boolean {
+  return (
+    layer.compressed_size !== undefined &&
+    layer.size >= 1024 * 1024 &&
+    layer.compressed_size >= layer.size * 0.9
+  );
+}
+
+/** `Dockerfile:12`, or `Dockerfile:12-15` for a multi-line instruction. */
+function sourceLocation(path: string, source: SourceRef): string {
+  return source.end_line > source.line
+    ? `${path}:${source.line}-${source.end_line}`
+    : `${path}:${source.line}`;
+}
+
 export function LayerList({
   layers,
   selectedIndex,
   onSelect,
   viewMode,
+  baseLayers = 0,
+  content,
+  dockerfile,
   sectionRef,
 }: {
   layers: LayerInfo[];
   selectedIndex: number;
   onSelect: (i: number) => void;
   viewMode: ViewMode;
+  /** Number of leading layers inherited from the base image */
+  baseLayers?: number;
+  /** Bytes per content category, one entry per layer */
+  content?: CategorySizes[];
+  /** Dockerfile line behind each layer */
+  dockerfile?: DockerfileMap;
   sectionRef: RefObject<HTMLDivElement | null>;
 }) {
   const [commandLayer, setCommandLayer] = useState<number | null>(null);
@@ -41,6 +69,12 @@ export function LayerList({
     } else if (e.key === "ArrowUp") {
       e.preventDefault();
       onSelect(Math.max(selectedIndex - 1, 0));
+    } else if (e.key === "Home") {
+      e.preventDefault();
+      onSelect(0);
+    } else if (e.key === "End") {
+      e.preventDefault();
+      onSelect(layers.length - 1);
     } else if (e.key === "Enter") {
       e.preventDefault();
       if (layers[selectedIndex].created_by) {
@@ -53,73 +87,126 @@ export function LayerList({
     <div
       ref={sectionRef}
       tabIndex={-1}
+      role="listbox"
+      aria-label={t("layerList")}
+      aria-activedescendant={`layer-option-${selectedIndex}`}
       className={cn("py-1 h-full overflow-y-auto", focusRing)}
       onKeyDown={handleKeyDown}
     >
       {layers.map((layer, i) => {
         const selected = i === selectedIndex;
-        // In accumulated mode, highlight all layers up to selected
-        const included = viewMode === "accumulated" && i <= selectedIndex;
+        // In accumulated mode, highlight all layers up to selected; the merged
+        // view draws from every layer
+        const included =
+          viewMode === "merged" || (viewMode === "accumulated" && i <= selectedIndex);
 
         return (
-          <button
-            key={layer.digest}
-            data-layer-index={i}
-            tabIndex={-1}
-            className={cn(
-              "w-full text-left px-3 py-2 flex items-start gap-2 transition-colors",
-              selected
-                ? "bg-primary/10 border-l-2 border-primary"
-                : included
-                  ? "bg-muted/40 border-l-2 border-primary/30"
-                  : "border-l-2 border-transparent hover:bg-muted/50"
+          <Fragment key={layer.digest}>
+            {baseLayers > 0 && i === baseLayers && (
+              <div
+                aria-hidden
+                className="px-3 pt-2 pb-1 text-[10px] uppercase tracking-wide text-muted-foreground"
+              >
+                {t("addedOnTop")}
+              </div>
             )}
-            onClick={() => onSelect(i)}
-          >
-            <span
+            <button
+              id={`layer-option-${i}`}
+              role="option"
+              aria-selected={selected}
+              data-layer-index={i}
+              tabIndex={-1}
               className={cn(
-                "flex items-center justify-center size-5 rounded-full text-[10px] font-bold shrink-0 mt-0.5",
+                "w-full text-left px-3 py-2 flex items-start gap-2 transition-colors",
                 selected
-                  ? "bg-primary text-primary-foreground"
-                  : "bg-muted text-muted-foreground"
+                  ? "bg-primary/10 border-l-2 border-primary"
+                  : included
+                    ? "bg-muted/40 border-l-2 border-primary/30"
+                    : "border-l-2 border-transparent hover:bg-muted/50"
               )}
+              onClick={() => onSelect(i)}
             >
-              {i + 1}
-            </span>
-            <div className="min-w-0 flex-1">
-              <div className="flex items-center gap-1.5">
-                <Layers className="size-3 text-muted-foreground shrink-0" />
-                <code className="text-xs text-muted-foreground">
-                  {layer.digest.slice(7, 19)}
-                </code>
-              </div>
-              <div className="flex items-center gap-2 mt-0.5 text-xs">
-                <span className="font-medium">{formatBytes(layer.size)}</span>
-                <span className="text-muted-foreground">
-                  {layer.files.length.toLocaleString()} files
-                </span>
-              </div>
-              {layer.created_by && (
-                <div className="flex items-center gap-1 mt-0.5">
-                  <p className="text-[10px] text-muted-foreground font-mono truncate leading-tight flex-1 min-w-0">
-                    {layer.created_by}
-                  </p>
-                  <span
-                    role="button"
-                    tabIndex={-1}
-                    className="shrink-0 p-0.5 rounded hover:bg-muted text-muted-foreground hover:text-foreground transition-colors"
-                    title="View full command"
-                    onClick={(e) => {
-                      e.stopPropagation();
-                      setCommandLayer(i);
-                    }}
-                  >
-                    <Terminal className="size-3" />
+              <span
+                className={cn(
+                  "flex items-center justify-center size-5 rounded-full text-[10px] font-bold shrink-0 mt-0.5",
+                  selected
+                    ? "bg-primary text-primary-foreground"
+                    : "bg-muted text-muted-foreground"
+                )}
+              >
+                {i + 1}
+              </span>
+              <div className="min-w-0 flex-1">
+                <div className="flex items-center gap-1.5">
+                  <Layers className="size-3 text-muted-foreground shrink-0" aria-hidden />
+                  <code className="text-xs text-muted-foreground">
+                    {layer.digest.slice(7, 19)}
+                  </code>
+                  {i < baseLayers && (
+                    <span className="ml-auto rounded bg-muted px-1 text-[10px] text-muted-foreground">
+                      {t("base")}
+                    </span>
+                  )}
+                </div>
+                <div className="flex items-center gap-2 mt-0.5 text-xs">
+                  <span className="font-medium" title={t("diskSize")}>
+                    {formatBytes(layer.size)}
+                  </span>
+                  {layer.compressed_size !== undefined && (
+                    <span
+                      className={poorlyCompressed(layer) ? "text-amber-700" : "text-muted-foreground"}
+                      title={poorlyCompressed(layer) ? t("poorCompression") : t("compressedSize")}
+                    >
+                      ({formatBytes(layer.compressed_size)} {t("compressed")}
+                      {layer.size > 0 &&
+                        `, ${((layer.compressed_size / layer.size) * 100).toFixed(1)}%`}
+                      )
+                    </span>
+                  )}
+                  <span className="text-muted-foreground">
+                    {layer.files.length.toLocaleString()} {t("files")}
                   </span>
+                  {layer.created && (
+                    <span
+                      className="ml-auto text-muted-foreground"
+                      title={`${t("layerCreated")}: ${layer.created}`}
+                    >
+                      {formatAge(new Date(layer.created))} {t("ago")}
+                    </span>
+                  )}
                 </div>
-              )}
-            </div>
-          </button>
+                {content?.[i] && <ContentBar sizes={content[i]} className="mt-1" />}
+                {layer.created_by && (
+                  <div className="flex items-center gap-1 mt-0.5">
+                    <p className="text-[10px] text-muted-foreground font-mono truncate leading-tight flex-1 min-w-0">
+                      {layer.created_by}
+                    </p>
+                    <span
+                      role="button"
+                      tabIndex={-1}
+                      className="shrink-0 p-0.5 rounded hover:bg-muted text-muted-foreground hover:text-foreground transition-colors"
+                      title={t("viewFullCommand")}
+                      aria-label={t("viewFullCommand")}
+                      onClick={(e) => {
+                        e.stopPropagation();
+                        setCommandLayer(i);
+                      }}
+                    >
+                      <Terminal className="size-3" aria-hidden />
+                    </span>
+                  </div>
+                )}
+                {dockerfile?.layers[i] && (
+                  <p
+                    className="text-[10px] text-muted-foreground font-mono truncate leading-tight mt-0.5"
+                    title={`${t("dockerfileLine")}: ${dockerfile.layers[i]!.text}`}
+                  >
+                    {sourceLocation(dockerfile.path, dockerfile.layers[i]!)}
+                  </p>
+                )}
+              </div>
+            </button>
+          </Fragment>
         );
       })}
 
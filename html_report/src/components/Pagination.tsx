@@ -1,3 +1,4 @@
+import { t } from "@/lib/i18n";
 import {
   ChevronLeft,
   ChevronRight,
@@ -20,38 +21,45 @@ export function Pagination({
   if (totalPages <= 1) return null;
 
   return (
-    <div className="flex items-center justify-center gap-1.5 px-3 py-1.5 text-xs text-muted-foreground border-t shrink-0">
+    <nav
+      aria-label={t("pagination")}
+      className="flex items-center justify-center gap-1.5 px-3 py-1.5 text-xs text-muted-foreground border-t shrink-0"
+    >
       <button
         className={btnClass}
+        aria-label={t("firstPage")}
         disabled={page === 0}
         onClick={() => setPage(0)}
       >
-        <ChevronsLeft className="size-3.5" />
+        <ChevronsLeft className="size-3.5" aria-hidden />
       </button>
       <button
         className={btnClass}
+        aria-label={t("previousPage")}
         disabled={page === 0}
         onClick={() => setPage(page - 1)}
       >
-        <ChevronLeft className="size-3.5" />
+        <ChevronLeft className="size-3.5" aria-hidden />
       </button>
-      <span className="px-1.5">
+      <span className="px-1.5" aria-live="polite">
         Page {page + 1} of {totalPages}
       </span>
       <button
         className={btnClass}
+        aria-label={t("nextPage")}
         disabled={page >= totalPages - 1}
         onClick={() => setPage(page + 1)}
       >
-        <ChevronRight className="size-3.5" />
+        <ChevronRight className="size-3.5" aria-hidden />
       </button>
       <button
         className={btnClass}
+        aria-label={t("lastPage")}
         disabled={page >= totalPages - 1}
         onClick={() => setPage(totalPages - 1)}
       >
-        <ChevronsRight className="size-3.5" />
+        <ChevronsRight className="size-3.5" aria-hidden />
       </button>
-    </div>
+    </nav>
   );
 }
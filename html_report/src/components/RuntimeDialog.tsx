@@ -0,0 +1,81 @@
+import { useEffect } from "react";
+import { X } from "lucide-react";
+import type { Healthcheck, RunConfig } from "@/types";
+import { t, type Key } from "@/lib/i18n";
+
+/** `curl -f http://localhost/ (interval 30s, retries 3)`, as in the terminal report. */
+function healthcheck(check: Healthcheck): string {
+  const [kind, ...rest] = check.test;
+  if (kind === "NONE") return "none";
+  const test = kind === "CMD-SHELL" ? rest.join(" ") : kind === "CMD" ? JSON.stringify(rest) : check.test.join(" ");
+  const options = [
+    check.interval !== undefined && `interval ${check.interval}s`,
+    check.timeout !== undefined && `timeout ${check.timeout}s`,
+    check.start_period !== undefined && `start-period ${check.start_period}s`,
+    check.retries !== undefined && `retries ${check.retries}`,
+  ].filter(Boolean);
+  return options.length ? `${test} (${options.join(", ")})` : test;
+}
+
+/** Entrypoint, environment, user and the rest of how a container from the image runs. */
+export function RuntimeDialog({ config, onClose }: { config: RunConfig; onClose: () => void }) {
+  useEffect(() => {
+    function onKey(e: KeyboardEvent) {
+      if (e.key === "Escape") onClose();
+    }
+    document.addEventListener("keydown", onKey);
+    return () => document.removeEventListener("keydown", onKey);
+  }, [onClose]);
+
+  const rows: [Key, string | string[] | undefined][] = [
+    ["runtimeEntrypoint", config.entrypoint && JSON.stringify(config.entrypoint)],
+    ["runtimeCmd", config.cmd && JSON.stringify(config.cmd)],
+    ["runtimeWorkdir", config.working_dir],
+    ["runtimeUser", config.user],
+    ["runtimePorts", config.exposed_ports?.join(", ")],
+    ["runtimeVolumes", config.volumes?.join(", ")],
+    ["runtimeHealthcheck", config.healthcheck && healthcheck(config.healthcheck)],
+    ["runtimeEnv", config.env],
+  ];
+
+  return (
+    <div
+      role="dialog"
+      aria-modal="true"
+      aria-labelledby="runtime-dialog-title"
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
+      onClick={onClose}
+    >
+      <div
+        className="bg-background border rounded-lg shadow-lg max-w-3xl w-full mx-4 max-h-[80vh] flex flex-col"
+        onClick={(e) => e.stopPropagation()}
+      >
+        <div className="flex items-center justify-between px-4 py-3 border-b shrink-0">
+          <h2 id="runtime-dialog-title" className="text-sm font-medium">
+            {t("runtime")}
+          </h2>
+          <button
+            className="p-1.5 rounded hover:bg-muted text-muted-foreground hover:text-foreground transition-colors"
+            onClick={onClose}
+            title="Close"
+            aria-label="Close"
+          >
+            <X className="size-4" aria-hidden />
+          </button>
+        </div>
+        <dl className="overflow-auto p-4 grid grid-cols-[auto_1fr] gap-x-4 gap-y-1.5 text-xs">
+          {rows
+            .filter(([, value]) => value !== undefined && value.length > 0)
+            .map(([label, value]) => (
+              <div key={label} className="contents">
+                <dt className="text-muted-foreground">{t(label)}</dt>
+                <dd className="font-mono break-all">
+                  {Array.isArray(value) ? value.map((v) => <div key={v}>{v}</div>) : value}
+                </dd>
+              </div>
+            ))}
+        </dl>
+      </div>
+    </div>
+  );
+}
@@ -1,8 +1,9 @@
 import { useState, useRef, type RefObject } from "react";
 import { Search, FolderTree, ArrowDownWideNarrow } from "lucide-react";
 import { cn } from "@/lib/utils";
+import { t } from "@/lib/i18n";
 
-export type ViewMode = "layer" | "accumulated";
+export type ViewMode = "layer" | "accumulated" | "merged";
 export type FileViewMode = "tree" | "size";
 
 const focusRing = "focus:outline-none focus:bg-primary/10";
@@ -18,10 +19,12 @@ function Toggle<T extends string>({
   options: { value: T; label: string; icon?: React.ReactNode }[];
 }) {
   return (
-    <div className="flex rounded-md border bg-muted/50 p-0.5">
+    <div role="radiogroup" className="flex rounded-md border bg-muted/50 p-0.5">
       {options.map((opt) => (
         <button
           key={opt.value}
+          role="radio"
+          aria-checked={value === opt.value}
           tabIndex={-1}
           className={cn(
             "flex items-center gap-1.5 px-2.5 py-1 text-xs font-medium rounded transition-colors",
@@ -42,6 +45,7 @@ function Toggle<T extends string>({
 export function Toolbar({
   viewMode,
   onViewModeChange,
+  hasMerged,
   fileViewMode,
   onFileViewModeChange,
   filter,
@@ -50,6 +54,7 @@ export function Toolbar({
 }: {
   viewMode: ViewMode;
   onViewModeChange: (v: ViewMode) => void;
+  hasMerged: boolean;
   fileViewMode: FileViewMode;
   onFileViewModeChange: (v: FileViewMode) => void;
   filter: string;
@@ -60,6 +65,13 @@ export function Toolbar({
   const inputRef = useRef<HTMLInputElement>(null);
   const widgetCount = 3;
 
+  const viewModes: { value: ViewMode; label: string }[] = [
+    { value: "layer", label: "This Layer" },
+    { value: "accumulated", label: "Accumulated" },
+  ];
+  // The merged filesystem is only in the report when peel ran with --merged
+  if (hasMerged) viewModes.push({ value: "merged", label: "Merged" });
+
   function handleKeyDown(e: React.KeyboardEvent) {
     // When typing in search, only handle Escape to return to widget nav
     if (document.activeElement === inputRef.current) {
@@ -81,7 +93,8 @@ export function Toolbar({
     } else if (e.key === " " || e.key === "Enter") {
       e.preventDefault();
       if (activeIndex === 0) {
-        onViewModeChange(viewMode === "layer" ? "accumulated" : "layer");
+        const i = viewModes.findIndex((m) => m.value === viewMode);
+        onViewModeChange(viewModes[(i + 1) % viewModes.length].value);
       } else if (activeIndex === 1) {
         onFileViewModeChange(fileViewMode === "tree" ? "size" : "tree");
       } else if (activeIndex === 2) {
@@ -94,6 +107,8 @@ export function Toolbar({
     <div
       ref={sectionRef}
       tabIndex={-1}
+      role="toolbar"
+      aria-label={t("toolbar")}
       className={cn("flex items-center gap-3 px-4 py-2 border-b bg-muted/30 shrink-0", focusRing)}
       onKeyDown={handleKeyDown}
       onFocus={(e) => {
@@ -111,10 +126,7 @@ export function Toolbar({
         <Toggle
           value={viewMode}
           onChange={onViewModeChange}
-          options={[
-            { value: "layer", label: "This Layer" },
-            { value: "accumulated", label: "Accumulated" },
-          ]}
+          options={viewModes}
         />
       </div>
 
@@ -130,10 +142,11 @@ export function Toolbar({
       </div>
 
       <div className={cn("flex items-center gap-1.5 flex-1 max-w-xs ml-auto rounded-md px-1", activeIndex === 2 && widgetHighlight)}>
-        <Search className="size-3.5 text-muted-foreground shrink-0" />
+        <Search className="size-3.5 text-muted-foreground shrink-0" aria-hidden />
         <input
           ref={inputRef}
-          type="text"
+          type="search"
+          aria-label={t("filterFiles")}
           tabIndex={-1}
           placeholder="Filter files..."
           value={filter}
@@ -0,0 +1,176 @@
+import { useEffect } from "react";
+import { X } from "lucide-react";
+import type { DuplicateReport, Junk, JunkKind, StripReport, WasteReport } from "@/types";
+import { formatBytes } from "@/lib/format";
+import { t, type Key } from "@/lib/i18n";
+
+const JUNK_LABELS: Record<JunkKind, Key> = {
+  apt_lists: "junkAptLists",
+  apt_cache: "junkAptCache",
+  pip_cache: "junkPipCache",
+  npm_cache: "junkNpmCache",
+  yarn_cache: "junkYarnCache",
+  pycache: "junkPycache",
+  git: "junkGit",
+};
+
+export function WasteDialog({
+  waste,
+  junk,
+  duplicates,
+  strip,
+  onClose,
+}: {
+  waste: WasteReport;
+  junk?: Junk[];
+  duplicates?: DuplicateReport;
+  strip?: StripReport;
+  onClose: () => void;
+}) {
+  useEffect(() => {
+    function onKey(e: KeyboardEvent) {
+      if (e.key === "Escape") onClose();
+    }
+    document.addEventListener("keydown", onKey);
+    return () => document.removeEventListener("keydown", onKey);
+  }, [onClose]);
+
+  return (
+    <div
+      role="dialog"
+      aria-modal="true"
+      aria-labelledby="waste-dialog-title"
+      className="fixed inset-0 z-50 flex items-center justify-center bg-black/50"
+      onClick={onClose}
+    >
+      <div
+        className="bg-background border rounded-lg shadow-lg max-w-2xl w-full mx-4 max-h-[80vh] flex flex-col"
+        onClick={(e) => e.stopPropagation()}
+      >
+        {/* Header */}
+        <div className="flex items-center justify-between px-4 py-3 border-b shrink-0">
+          <h2 id="waste-dialog-title" className="text-sm font-medium">
+            {t("wastedSpace")} · {formatBytes(waste.wasted_size)} ·{" "}
+            {(waste.efficiency * 100).toFixed(1)}% {t("efficiency")}
+          </h2>
+          <button
+            className="p-1.5 rounded hover:bg-muted text-muted-foreground hover:text-foreground transition-colors"
+            onClick={onClose}
+            title="Close"
+            aria-label="Close"
+          >
+            <X className="size-4" aria-hidden />
+          </button>
+        </div>
+
+        {/* Body */}
+        <div className="overflow-auto p-2">
+          {waste.paths.length === 0 ? (
+            <p className="p-4 text-sm text-muted-foreground">{t("noWaste")}</p>
+          ) : (
+            <table className="w-full text-sm">
+              <thead className="text-xs text-muted-foreground">
+                <tr>
+                  <th className="text-left font-medium px-2 py-1">{t("path")}</th>
+                  <th className="text-right font-medium px-2 py-1">{t("layers")}</th>
+                  <th className="text-right font-medium px-2 py-1">{t("wasted")}</th>
+                </tr>
+              </thead>
+              <tbody>
+                {waste.paths.map((p) => (
+                  <tr key={p.path} className="hover:bg-muted/50">
+                    <td className="px-2 py-0.5 font-mono text-xs break-all">
+                      /{p.path}
+                      {p.deleted && (
+                        <span className="ml-1 text-red-500">({t("deleted")})</span>
+                      )}
+                    </td>
+                    <td className="px-2 py-0.5 text-right text-xs text-muted-foreground tabular-nums">
+                      {p.layers.map((l) => l + 1).join(", ")}
+                    </td>
+                    <td className="px-2 py-0.5 text-right text-xs tabular-nums">
+                      {formatBytes(p.wasted_size)}
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
+          {junk && junk.length > 0 && (
+            <table className="w-full text-sm mt-4">
+              <caption className="text-left text-xs font-medium px-2 py-1">{t("removable")}</caption>
+              <tbody>
+                {junk.map((j) => (
+                  <tr key={j.kind} className="hover:bg-muted/50">
+                    <td className="px-2 py-0.5 text-xs">{t(JUNK_LABELS[j.kind])}</td>
+                    <td
+                      className="px-2 py-0.5 font-mono text-xs break-all"
+                      title={j.paths.map((p) => `/${p}`).join("\n")}
+                    >
+                      /{j.paths[0]}
+                      {j.paths.length > 1 && (
+                        <span className="ml-1 text-muted-foreground">+{j.paths.length - 1}</span>
+                      )}
+                    </td>
+                    <td className="px-2 py-0.5 text-right text-xs text-muted-foreground tabular-nums">
+                      {j.layers.map((l) => l.layer + 1).join(", ")}
+                    </td>
+                    <td className="px-2 py-0.5 text-right text-xs tabular-nums">
+                      {formatBytes(j.size)}
+                    </td>
+                  </tr>
+                ))}
+              </tbody>
+            </table>
+          )}
+          {duplicates && duplicates.groups.length > 0 && (
+            <section className="mt-4 px-2 text-sm">
+              <h3 className="text-xs font-medium py-1">
+                {t("duplicates")} · {formatBytes(duplicates.wasted_size)}
+              </h3>
+              <ul className="text-xs">
+                {duplicates.groups.slice(0, 20).map((g) => (
+                  <li key={g.sha256} className="py-0.5">
+                    <div className="flex justify-between">
+                      <span className="text-muted-foreground">
+                        {g.copies.length} × {formatBytes(g.size)}
+                      </span>
+                      <span className="tabular-nums">
+                        {formatBytes(g.size * (g.copies.length - 1))}
+                      </span>
+                    </div>
+                    {g.copies.map((c) => (
+                      <div key={`${c.layer}:${c.path}`} className="font-mono break-all pl-2">
+                        <span className="text-muted-foreground tabular-nums mr-2">{c.layer + 1}</span>
+                        /{c.path}
+                      </div>
+                    ))}
+                  </li>
+                ))}
+              </ul>
+            </section>
+          )}
+          {strip && (
+            <section className="mt-4 px-2 text-sm">
+              <h3 className="text-xs font-medium py-1">
+                {t("howToShrink")} · {formatBytes(strip.size)}
+              </h3>
+              <ul className="text-xs">
+                {strip.candidates.map((c) => (
+                  <li key={c.kind} className="flex justify-between py-0.5">
+                    <span className="font-mono">/{c.path}</span>
+                    <span className="tabular-nums">{formatBytes(c.size)}</span>
+                  </li>
+                ))}
+              </ul>
+              <p className="text-xs text-muted-foreground mt-2">{t("stripHint")}</p>
+              <pre className="mt-1 rounded bg-muted p-2 text-xs whitespace-pre-wrap break-all">
+                RUN ... && {strip.snippet}
+              </pre>
+            </section>
+          )}
+        </div>
+      </div>
+    </div>
+  );
+}
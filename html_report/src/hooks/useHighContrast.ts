@@ -0,0 +1,29 @@
+import { useEffect, useState } from "react";
+
+const STORAGE_KEY = "peel:contrast";
+
+/**
+ * High-contrast toggle. The choice is stored per browser and applied as
+ * `data-contrast` on <html>; without a stored choice the OS preference
+ * (`prefers-contrast: more`) applies via CSS.
+ */
+export function useHighContrast(): [boolean, () => void] {
+  const [high, setHigh] = useState(() => {
+    const stored = localStorage.getItem(STORAGE_KEY);
+    if (stored) return stored === "high";
+    return window.matchMedia("(prefers-contrast: more)").matches;
+  });
+
+  useEffect(() => {
+    document.documentElement.dataset.contrast = high ? "high" : "normal";
+  }, [high]);
+
+  function toggle() {
+    setHigh((h) => {
+      localStorage.setItem(STORAGE_KEY, h ? "normal" : "high");
+      return !h;
+    });
+  }
+
+  return [high, toggle];
+}
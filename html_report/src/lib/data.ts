@@ -0,0 +1,15 @@
+import devData from "../../data/test.json";
+import type { ImageInfo, ImageDiff } from "@/types";
+
+export type ReportData = ImageInfo | ImageDiff;
+
+/** Read the payload injected by `peel` (falls back to the dev fixture). */
+export function loadData(): ReportData {
+  const el = document.getElementById("__PEEL_DATA__");
+  if (el?.textContent?.trim()) return JSON.parse(el.textContent);
+  return devData as ImageInfo;
+}
+
+export function isDiff(data: ReportData): data is ImageDiff {
+  return "left" in data && "right" in data;
+}
@@ -0,0 +1,255 @@
+// Report UI strings. `peel` sets <html lang> from its active message catalog
+// (see locales/*.ftl); add a block here to translate the report as well.
+// Missing keys fall back to English.
+
+const en = {
+  layers: "layers",
+  files: "files",
+  compressed: "compressed",
+  compressedSize: "Compressed size: what a registry stores and a pull downloads",
+  diskSize: "Uncompressed size: what the layer takes on disk",
+  poorCompression: "Compresses poorly: already-compressed or random data?",
+  noFilesMatch: "No files match filter",
+  noFilesInLayer: "No files in this layer",
+  noFilesInDir: "No files in this directory",
+  viewFullCommand: "View full command",
+  dockerfileLine: "Dockerfile instruction",
+  layerCreated: "Layer created",
+  imageId: "Image ID",
+  manifestDigest: "Manifest digest",
+  verified: "verified",
+  verifiedHint: "Every layer blob hashes to its diff_id",
+  diffIdMismatches: "layers don't match their diff_id",
+  buildTimeline: "Build timeline: when each layer was created",
+  ago: "ago",
+  sharedLayers: "shared layers",
+  noUniqueLayers: "No unique layers",
+  treemapLegend: "Size change per directory — red grew, teal shrank",
+  treemapEmpty: "No size changes below this directory",
+  highContrast: "High contrast",
+  layerList: "Layers",
+  directoryTree: "Directories",
+  fileList: "Files",
+  toolbar: "View options",
+  filterFiles: "Filter files",
+  deleted: "deleted",
+  sortByName: "Sort by name",
+  sortBySize: "Sort by size",
+  pagination: "Pages",
+  firstPage: "First page",
+  previousPage: "Previous page",
+  nextPage: "Next page",
+  lastPage: "Last page",
+  treemap: "Directory size changes",
+  treemapUp: "Up one directory",
+  layerCommand: "Layer command",
+  base: "base",
+  inheritedFrom: "inherited from",
+  addedOnTop: "added on top",
+  modified: "modified",
+  modifiedHint: "Overwrites a file from a lower layer",
+  highEntropy: "high entropy",
+  highEntropyHint: "Looks packed or encrypted; bits per byte",
+  wasted: "wasted",
+  wastedSpace: "Wasted space",
+  efficiency: "efficiency",
+  noWaste: "No bytes are hidden by later layers",
+  path: "Path",
+  partial: "partial",
+  partialHint: "Only files below these paths are counted:",
+  runningIn: "running in",
+  workloads: "workloads",
+  clusterMismatch: "cluster runs a different build",
+  clusterNone: "not running in the cluster",
+  contentMix: "Content by category",
+  categoryOs: "OS",
+  categoryRuntime: "runtime",
+  categoryCode: "code",
+  categoryAssets: "assets",
+  categoryData: "data",
+  categoryCache: "cache",
+  categoryOther: "other",
+  classDistro: "distro",
+  classMinimal: "minimal",
+  classDistroless: "distroless",
+  classScratch: "scratch",
+  noShell: "No shell in the image",
+  provenance: "Build provenance",
+  builtBy: "Built by",
+  vcsSource: "Source",
+  buildArgs: "Build args",
+  pulledImages: "Pulled",
+  labels: "Labels",
+  annotations: "Annotations",
+  runtime: "Runtime",
+  runtimeEntrypoint: "Entrypoint",
+  runtimeCmd: "Cmd",
+  runtimeWorkdir: "Working dir",
+  runtimeUser: "User",
+  runtimePorts: "Ports",
+  runtimeVolumes: "Volumes",
+  runtimeHealthcheck: "Healthcheck",
+  runtimeEnv: "Env",
+  dirSizes: "Directory sizes",
+  dirSizesEmpty: "No directories below this one",
+  fileTypes: "File types",
+  typeExecutable: "executable",
+  typeSharedLib: "shared lib",
+  typePython: "Python",
+  typeJavaScript: "JavaScript",
+  typeDocs: "docs",
+  typeLocale: "locale",
+  typeImage: "image",
+  typeArchive: "archive",
+  typeOther: "other",
+  removable: "Removable files",
+  junkAptLists: "apt package lists",
+  junkAptCache: "downloaded .deb files",
+  junkPipCache: "pip cache",
+  junkNpmCache: "npm cache",
+  junkYarnCache: "yarn cache",
+  junkPycache: "Python bytecode",
+  junkGit: "git repository",
+  buildTools: "build tools",
+  duplicates: "Duplicate files",
+  howToShrink: "How to shrink",
+  stripHint: "Remove these in the RUN instructions that install them; a later RUN rm doesn't shrink the image.",
+  toolchainHint: "Compilers and build tools in the final image; a multi-stage build would leave them out",
+  runsAsRoot: "runs as root",
+  runsAs: "runs as",
+  setuidFiles: "setuid/setgid",
+  worldWritable: "world-writable",
+  postureHint: "Security posture: layer, mode and path of setuid/setgid and world-writable files",
+};
+
+export type Key = keyof typeof en;
+
+const messages: Record<string, Partial<Record<Key, string>>> = {
+  en,
+  "zh-CN": {
+    layers: "层",
+    files: "个文件",
+    compressed: "压缩后",
+    compressedSize: "压缩大小：镜像仓库存储和拉取时下载的大小",
+    diskSize: "未压缩大小：该层在磁盘上占用的大小",
+    poorCompression: "压缩效果差：是否为已压缩或随机数据？",
+    noFilesMatch: "没有匹配的文件",
+    noFilesInLayer: "该层没有文件",
+    noFilesInDir: "该目录没有文件",
+    viewFullCommand: "查看完整命令",
+    dockerfileLine: "Dockerfile 指令",
+    layerCreated: "层创建时间",
+    imageId: "镜像 ID",
+    manifestDigest: "清单摘要",
+    verified: "已校验",
+    verifiedHint: "每个层的内容都与其 diff_id 一致",
+    diffIdMismatches: "层与 diff_id 不一致",
+    buildTimeline: "构建时间线：各层的创建时间",
+    ago: "前",
+    sharedLayers: "个共享层",
+    noUniqueLayers: "没有独有的层",
+    treemapLegend: "各目录大小变化 — 红色增大，青色减小",
+    treemapEmpty: "该目录下没有大小变化",
+    highContrast: "高对比度",
+    layerList: "镜像层",
+    directoryTree: "目录",
+    fileList: "文件",
+    toolbar: "视图选项",
+    filterFiles: "筛选文件",
+    deleted: "已删除",
+    sortByName: "按名称排序",
+    sortBySize: "按大小排序",
+    pagination: "分页",
+    firstPage: "第一页",
+    previousPage: "上一页",
+    nextPage: "下一页",
+    lastPage: "最后一页",
+    treemap: "目录大小变化",
+    treemapUp: "返回上一级目录",
+    layerCommand: "镜像层命令",
+    base: "基础",
+    inheritedFrom: "继承自",
+    addedOnTop: "新增",
+    modified: "已修改",
+    modifiedHint: "覆盖了下层中的文件",
+    highEntropy: "高熵",
+    highEntropyHint: "疑似加壳或加密；每字节比特数",
+    wasted: "浪费",
+    wastedSpace: "浪费的空间",
+    efficiency: "效率",
+    noWaste: "没有被上层隐藏的数据",
+    path: "路径",
+    partial: "部分",
+    partialHint: "仅计入以下路径中的文件：",
+    runningIn: "运行于",
+    workloads: "个工作负载",
+    clusterMismatch: "集群运行的是另一个构建",
+    clusterNone: "未在集群中运行",
+    contentMix: "按类别划分的内容",
+    categoryOs: "系统",
+    categoryRuntime: "运行时",
+    categoryCode: "代码",
+    categoryAssets: "静态资源",
+    categoryData: "数据",
+    categoryCache: "缓存",
+    categoryOther: "其他",
+    classDistro: "发行版",
+    classMinimal: "精简",
+    classDistroless: "distroless",
+    classScratch: "scratch",
+    noShell: "镜像中没有 shell",
+    provenance: "构建来源",
+    builtBy: "构建工具",
+    vcsSource: "源码",
+    buildArgs: "构建参数",
+    pulledImages: "拉取的镜像",
+    labels: "标签",
+    annotations: "注解",
+    runtime: "运行配置",
+    runtimeEntrypoint: "入口",
+    runtimeCmd: "命令",
+    runtimeWorkdir: "工作目录",
+    runtimeUser: "用户",
+    runtimePorts: "端口",
+    runtimeVolumes: "卷",
+    runtimeHealthcheck: "健康检查",
+    runtimeEnv: "环境变量",
+    dirSizes: "目录大小",
+    dirSizesEmpty: "该目录下没有子目录",
+    fileTypes: "文件类型",
+    typeExecutable: "可执行文件",
+    typeSharedLib: "共享库",
+    typePython: "Python",
+    typeJavaScript: "JavaScript",
+    typeDocs: "文档",
+    typeLocale: "本地化数据",
+    typeImage: "图片",
+    typeArchive: "压缩包",
+    typeOther: "其他",
+    removable: "可移除的文件",
+    junkAptLists: "apt 软件包列表",
+    junkAptCache: "已下载的 .deb 文件",
+    junkPipCache: "pip 缓存",
+    junkNpmCache: "npm 缓存",
+    junkYarnCache: "yarn 缓存",
+    junkPycache: "Python 字节码",
+    junkGit: "git 仓库",
+    buildTools: "构建工具",
+    duplicates: "重复文件",
+    howToShrink: "如何瘦身",
+    stripHint: "请在安装它们的 RUN 指令中删除；之后单独的 RUN rm 不会减小镜像。",
+    toolchainHint: "最终镜像中的编译器和构建工具；多阶段构建可将其省去",
+    runsAsRoot: "以 root 运行",
+    runsAs: "运行用户",
+    setuidFiles: "setuid/setgid",
+    worldWritable: "所有人可写",
+    postureHint: "安全概况：setuid/setgid 与所有人可写文件的层、权限和路径",
+  },
+};
+
+const lang = document.documentElement.lang || "en";
+const active = messages[lang] ?? messages[lang.split("-")[0]] ?? {};
+
+export function t(key: Key): string {
+  return active[key] ?? en[key];
+}
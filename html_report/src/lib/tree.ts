@@ -31,6 +31,8 @@ export function buildTree(files: FileEntry[]): TreeNode {
       if (isLast) {
         child.size = file.size;
         child.is_whiteout = file.is_whiteout;
+        child.change = file.change;
+        child.entropy = file.entropy;
         child.isFile = true;
       }
 
@@ -2,9 +2,13 @@ import { StrictMode } from 'react'
 import { createRoot } from 'react-dom/client'
 import './index.css'
 import App from './App.tsx'
+import DiffApp from './DiffApp.tsx'
+import { loadData, isDiff } from './lib/data'
+
+const data = loadData()
 
 createRoot(document.getElementById('root')!).render(
   <StrictMode>
-    <App />
+    {isDiff(data) ? <DiffApp diff={data} /> : <App image={data} />}
   </StrictMode>,
 )
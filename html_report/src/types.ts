@@ -1,28 +1,363 @@
 export interface ImageInfo {
   name: string;
   tag: string | null;
+  /** "sha256:<hex>" of the config */
+  id?: string;
+  /** "sha256:<hex>" of the manifest, and of the index for a multi-platform image */
+  manifest_digests?: string[];
   architecture: string | null;
+  /** RFC 3339, from the image config */
+  created?: string;
+  /** From the final filesystem's os-release or other release file */
+  distro?: Distro;
+  /** From a BuildKit provenance attestation or embedded build info */
+  provenance?: Provenance;
+  /** Image config Labels */
+  labels?: Record<string, string>;
+  /** OCI manifest annotations; only when the backend has the manifest */
+  annotations?: Record<string, string>;
+  /** Runtime fields of the image config */
+  run_config?: RunConfig;
   total_size: number;
   layers: LayerInfo[];
+  /** Set with --root: sizes only count files below these subtrees */
+  roots?: string[];
+  base?: BaseSplit;
+  merged?: MergedFile[];
+  waste?: WasteReport;
+  /** Well-known removable files, largest first */
+  junk?: Junk[];
+  /** Build tools left in the final filesystem, largest first */
+  toolchain?: Toolchain[];
+  /** Python, Node.js, Java and Go installations */
+  runtimes?: Runtime[];
+  /** Identical files stored more than once; needs --checksums */
+  duplicates?: DuplicateReport;
+  /** setuid and world-writable files, and whether the image runs as root */
+  posture?: PostureReport;
+  /** Docs, locales and debug symbols worth stripping */
+  strip?: StripReport;
+  content?: ContentBreakdown;
+  file_types?: FileTypeBreakdown;
+  class?: ClassReport;
+  /** Cumulative size of every directory in the final filesystem */
+  dirs?: DirSize[];
+  /** Set with --kubecontext/--namespace */
+  cluster?: ClusterCheck;
+  /** Set with --dockerfile */
+  dockerfile?: DockerfileMap;
+  /** Set with --verify */
+  verify?: VerifyReport;
+}
+
+export interface VerifyReport {
+  /** Layers whose uncompressed content hashes to their diff_id */
+  verified: number;
+  /** Layers with no blob to hash, or a zstd one */
+  skipped: number;
+  mismatches?: DiffIdMismatch[];
+}
+
+export interface DiffIdMismatch {
+  layer: number;
+  /** The diff_id the config declares */
+  expected: string;
+  /** Absent if the blob couldn't be read */
+  actual?: string;
+  error?: string;
+}
+
+export interface Provenance {
+  /** Attestation predicate type, "buildinfo" or "labels" */
+  source: string;
+  builder?: string;
+  frontend?: string;
+  build_args?: Record<string, string>;
+  vcs_source?: string;
+  vcs_revision?: string;
+  base_images?: { reference: string; digest?: string }[];
+}
+
+export interface RunConfig {
+  env?: string[];
+  entrypoint?: string[];
+  cmd?: string[];
+  working_dir?: string;
+  user?: string;
+  exposed_ports?: string[];
+  volumes?: string[];
+  healthcheck?: Healthcheck;
+}
+
+export interface Healthcheck {
+  test: string[];
+  /** Seconds */
+  interval?: number;
+  timeout?: number;
+  start_period?: number;
+  retries?: number;
+}
+
+export interface DirSize {
+  path: string;
+  /** Bytes of every file below the directory, at any depth */
+  size: number;
+  files: number;
+}
+
+export type ImageClass = "distro" | "minimal" | "distroless" | "scratch";
+
+export interface ClassReport {
+  class: ImageClass;
+  package_db: string | null;
+  shell: boolean;
+  /** Largest files of the final filesystem; only without a package manager */
+  largest?: MergedFile[];
+}
+
+export interface DockerfileMap {
+  path: string;
+  /** One per layer; null where no instruction matched */
+  layers: (SourceRef | null)[];
+}
+
+export interface SourceRef {
+  /** 1-based */
+  line: number;
+  end_line: number;
+  /** The instruction's first line */
+  text: string;
+}
+
+export interface ClusterCheck {
+  context: string | null;
+  namespace: string | null;
+  digests: string[];
+  workloads: Workload[];
+}
+
+export interface Workload {
+  namespace: string;
+  /** e.g. "Deployment/web" */
+  name: string;
+  container: string;
+  image: string;
+  digest: string | null;
+  status: "match" | "mismatch" | "unknown";
+  pods: number;
+}
+
+export type Category = "os" | "runtime" | "code" | "assets" | "data" | "cache" | "other";
+
+/** Bytes per category; categories with no files are omitted */
+export type CategorySizes = Partial<Record<Category, number>>;
+
+export interface ContentBreakdown {
+  layers: CategorySizes[];
+  totals: CategorySizes;
+}
+
+export type FileType =
+  | "executable"
+  | "shared_lib"
+  | "python"
+  | "javascript"
+  | "docs"
+  | "locale"
+  | "image"
+  | "archive"
+  | "other";
+
+/** Bytes per file type; types with no files are omitted */
+export type FileTypeSizes = Partial<Record<FileType, number>>;
+
+export interface FileTypeBreakdown {
+  layers: FileTypeSizes[];
+  totals: FileTypeSizes;
+}
+
+export type JunkKind =
+  | "apt_lists"
+  | "apt_cache"
+  | "pip_cache"
+  | "npm_cache"
+  | "yarn_cache"
+  | "pycache"
+  | "git";
+
+export interface Junk {
+  kind: JunkKind;
+  paths: string[];
+  /** Bytes in those directories across all layers */
+  size: number;
+  layers: { layer: number; size: number }[];
+}
+
+export interface Toolchain {
+  name: string;
+  kind: "compiler" | "build_tool" | "headers" | "package_manager";
+  /** Programs found, without a leading "/" */
+  programs: string[];
+  size: number;
+  files: number;
+}
+
+export interface PostureReport {
+  /** USER from the image config, if set */
+  user: string | null;
+  /** null when the image config isn't available */
+  runs_as_root: boolean | null;
+  setuid: PostureFile[];
+  /** Regular files only; directories aren't listed */
+  world_writable: PostureFile[];
+}
+
+export interface PostureFile {
+  path: string;
+  layer: number;
+  /** Permission bits (mode & 0o7777) */
+  mode: number;
+  uid: number | null;
+  gid: number | null;
+}
+
+export interface DuplicateReport {
+  /** Bytes stored beyond the first copy of each file */
+  wasted_size: number;
+  /** Largest waste first */
+  groups: {
+    sha256: string;
+    /** Size of one copy */
+    size: number;
+    copies: { layer: number; path: string }[];
+  }[];
+}
+
+export interface StripReport {
+  candidates: {
+    kind: "man" | "docs" | "info" | "locales" | "debug_symbols";
+    path: string;
+    size: number;
+    files: number;
+    layers: number[];
+  }[];
+  size: number;
+  /** rm command to append to the RUN instructions of those layers */
+  snippet: string;
+}
+
+export interface WasteReport {
+  wasted_size: number;
+  efficiency: number;
+  paths: WastedPath[];
+}
+
+export interface WastedPath {
+  path: string;
+  wasted_size: number;
+  layers: number[];
+  deleted: boolean;
+}
+
+export interface MergedFile {
+  path: string;
+  size: number;
+  layer: number;
+}
+
+export interface Distro {
+  /** e.g. "debian", "alpine" */
+  id: string;
+  version_id: string | null;
+  /** e.g. "Debian GNU/Linux 12 (bookworm)" */
+  name?: string;
+}
+
+export type RuntimeKind = "python" | "node" | "java" | "go";
+
+export interface Runtime {
+  kind: RuntimeKind;
+  /** "3.12.2" from a version file, "3.12" from a directory name */
+  version: string | null;
+  /** Installation root, e.g. "usr/local/go" */
+  path: string;
+  layer: number;
+}
+
+export interface BaseSplit {
+  name: string;
+  layers: number;
+  inherited_size: number;
+  added_size: number;
 }
 
 export interface LayerInfo {
   digest: string;
   created_by: string | null;
+  /** created_by without the shell wrapper and build-arg prefix */
+  instruction?: { instruction: string; arguments: string };
+  /** RFC 3339, from the config's history entry for the layer */
+  created?: string;
+  /** Uncompressed, i.e. on disk */
   size: number;
+  /** Blob size in a registry, when the backend knows it */
+  compressed_size?: number;
   files: FileEntry[];
 }
 
+export type FileChange = "added" | "modified" | "deleted";
+
 export interface FileEntry {
   path: string;
   size: number;
   is_whiteout: boolean;
+  change?: FileChange;
+  /** Sampled bits per byte; only set for large files of unrecognized format */
+  entropy?: number;
+  /** "sha256:<hex>" of the content, with --checksums */
+  sha256?: string;
 }
 
 export interface TreeNode {
   name: string;
   size: number;
   is_whiteout: boolean;
+  change?: FileChange;
+  entropy?: number;
   children: Map<string, TreeNode>;
   isFile: boolean;
 }
+
+export type LayerStatus = "shared" | "removed" | "added";
+
+export interface DiffSide {
+  name: string;
+  tag: string | null;
+  architecture: string | null;
+  total_size: number;
+  layer_count: number;
+}
+
+export interface LayerDiff {
+  status: LayerStatus;
+  digest: string;
+  created_by: string | null;
+  size: number;
+  file_count: number;
+}
+
+export interface DirDelta {
+  path: string;
+  left_size: number;
+  right_size: number;
+  delta: number;
+}
+
+export interface ImageDiff {
+  left: DiffSide;
+  right: DiffSide;
+  shared_layers: number;
+  shared_size: number;
+  layers: LayerDiff[];
+  dirs: DirDelta[];
+}
@@ -0,0 +1,568 @@
+# English messages (source catalog).
+#
+# Syntax is the single-line subset of Project Fluent: `key = value`, with
+# `{ $name }` placeholders. Use `\n` for a line break. Other catalogs only need
+# the keys they translate; missing keys fall back to this file.
+
+## Runtime selection
+
+runtime-unknown = Unknown runtime '{ $name }'. Valid options: docker, podman, containerd
+runtime-not-detected = Runtime '{ $name }' was not detected on this system. Run `peel probe` to see available runtimes.
+runtime-none-installed = No container runtime detected. Install Docker or Podman, or use a tar archive.
+runtimes-none = No container runtimes detected
+runtimes-label = Runtimes
+selected-label = Selected
+selected-detail = (storage: { $root }, method: { $method })
+
+## Inspection progress
+
+spinner-resolving = Resolving image metadata...
+spinner-reading-layer = Reading layer { $current }/{ $total } ...
+spinner-resolving-image = Resolving { $image } ...
+inspected-layers = Inspected { $count } layers
+resolved-metadata = Resolved image metadata
+resolved-image = Resolved { $image } ({ $count } layers)
+spinner-verifying = Hashing layers against their diff_ids ...
+verify-done = Verified { $verified } layers, { $mismatched } mismatched
+
+## Output
+
+inspect-id = id: { $id }
+inspect-digests = digest: { $digests }
+inspect-arch = arch: { $arch }
+inspect-distro = distro: { $distro }
+inspect-class = class: { $class }
+inspect-runtimes = runtimes: { $runtimes }
+inspect-built-by = built by: { $builder }
+inspect-vcs = source: { $source }
+inspect-build-args = build args: { $args }
+inspect-base-images = pulled: { $images }
+inspect-labels = labels:
+inspect-annotations = annotations:
+class-distro = distro
+class-minimal = minimal
+class-distroless = distroless
+class-scratch = scratch
+class-no-shell = no shell
+inspect-total-size = total size: { $size } bytes
+inspect-partial = partial: only files under { $roots } are counted
+inspect-verified = verified: { $verified } of { $total } layers match their diff_id ({ $skipped } skipped)
+inspect-diff-id-mismatch = layer { $layer } does not match its diff_id { $expected }: { $found }
+inspect-created = created: { $date } ({ $age } ago)
+inspect-compressed-size = compressed: { $size } bytes (registry and pull size)
+inspect-poor-compression = compresses poorly: already-compressed or random data?
+inspect-inherited-size = inherited from { $base }: { $size } ({ $count } layers)
+inspect-added-size = added on top: { $size }
+inspect-added-layers = ── added on top of base ──
+inspect-efficiency = efficiency: { $efficiency }
+inspect-wasted-size = wasted: { $size }
+efficiency-below-target = Image efficiency { $efficiency } is below the --min-efficiency target of { $target }
+inspect-wasted-paths = Wasted space (size, layers, path)
+inspect-high-entropy = Possibly packed or encrypted files (size, bits/byte, layer, path)
+inspect-deleted = deleted
+inspect-largest = Largest files; no package manager accounts for them (size, layer, path)
+inspect-cache-mounts = Build caches in layers; cache mounts would save { $size } (size, layers, path)
+inspect-junk = Removable files; leaving them out would save { $size } (size, what, where; layers that added them)
+junk-dirs = { $count } directories, e.g. { $path }
+junk-apt-lists = apt package lists
+junk-apt-cache = downloaded .deb files
+junk-pip-cache = pip cache
+junk-npm-cache = npm cache
+junk-yarn-cache = yarn cache
+junk-pycache = Python bytecode
+junk-git = git repository
+junk-fix-apt-lists = End the RUN that runs apt-get update with `rm -rf /var/lib/apt/lists/*`
+junk-fix-apt-cache = Run `apt-get clean` in the same RUN as apt-get install
+junk-fix-pip-cache = Use `pip install --no-cache-dir`
+junk-fix-npm-cache = Run `npm cache clean --force` in the same RUN as npm install
+junk-fix-yarn-cache = Run `yarn cache clean` in the same RUN as yarn install
+junk-fix-pycache = Set `ENV PYTHONDONTWRITEBYTECODE=1`, or add `__pycache__` to .dockerignore
+junk-fix-git = Add `.git` to .dockerignore
+inspect-toolchain = Build tools in the final image; a multi-stage build would leave out { $size } (size, tool, programs)
+tool-compiler = compiler
+tool-build-tool = build tool
+tool-headers = headers
+tool-package-manager = package manager
+tool-files = { $count } files
+inspect-duplicates = Duplicate files; copies beyond the first take { $size } (wasted; layer, path of each copy)
+duplicates-copies = { $count } copies of { $size }
+duplicates-more = … and { $count } more groups (see --json)
+inspect-posture = Security posture (layer, mode, owner, path)
+posture-user-unknown = user unknown: the image config isn't available
+posture-root-default = runs as root: no USER set
+posture-root = runs as root (USER { $user })
+posture-user = runs as { $user }
+posture-setuid = { $count } setuid/setgid files:
+posture-world-writable = { $count } world-writable files:
+posture-more = … and { $count } more (see --json)
+inspect-strip = How to shrink: stripping these would save { $size } (size, layers, what, where)
+strip-man = man pages
+strip-docs = docs
+strip-info = info pages
+strip-locales = locales
+strip-debug = debug symbols
+strip-hint = Remove them in the RUN instructions of those layers; a later RUN rm doesn't shrink the image:
+inspect-content = Content by category (per layer, then totals)
+category-os = os
+category-runtime = runtime
+category-code = code
+category-assets = assets
+category-data = data
+category-cache = cache
+category-other = other
+inspect-file-types = File types (largest per layer, then totals)
+file-type-executable = executable
+file-type-shared-lib = shared lib
+file-type-python = python
+file-type-javascript = javascript
+file-type-docs = docs
+file-type-locale = locale
+file-type-image = image
+file-type-archive = archive
+file-type-other = other
+inspect-merged-summary = Merged filesystem: { $count } files, { $size } (size, layer, path)
+base-unrelated = { $image } does not share any leading layers with { $base }
+wrote-file = Wrote { $path }
+wrote-file-size = Wrote { $path } ({ $size })
+wrote-index = Wrote { $count } tar entries to { $path }
+emit-index-no-blobs = --emit-index needs the layer blobs; direct storage access only has them unpacked (try --use-oci)
+write-json-failed = Failed to write JSON to { $path }
+write-csv-failed = Failed to write CSV to { $path }
+log-file-failed = Failed to open log file { $path }
+dockerfile-read-failed = Failed to read Dockerfile { $path }
+dockerfile-no-match = No layer matched an instruction in { $path }; is it the Dockerfile this image was built from?
+junit-write-failed = Failed to write JUnit report to { $path }
+output-junit-unsupported = --output junit:<file> is only for lint, check and inspect --min-efficiency, which check something
+output-csv-unsupported = --output csv is only for inspect, which lists files
+fail-on-unsupported = --fail-on is only for inspect, check and lint
+inspect-only-flag = { $flag } is only for inspect and build, which inspect an image
+base-unsupported = --base is only for inspect, build and lint
+debug-http-unsupported = --debug-http only applies to verify, pull-size, push-estimate and copy, which talk to a registry
+preset-config-read-failed = Failed to read { $path }
+preset-config-invalid = { $path } is not a valid config; it takes {"preset": "security"}, "size", "compliance" or "ci"
+fail-on-severity-lint-only = A severity in --fail-on is for lint; inspect and check take size>SIZE, layers>N, files>N, efficiency<PERCENT or secrets
+no-files-fail-on = --no-files lists no files, so --fail-on can only take size>SIZE and layers>N
+verify-failed = { $count } layers do not match their diff_id; the image may be corrupted or tampered with
+fail-on-lint-severity-only = lint --fail-on takes a severity (info, warning or error)
+write-html-failed = Failed to write HTML to { $path }
+output-unsupported = Unsupported --output target '{ $spec }' (expected bundle:<dir>, junit:<file>, csv[:<file>] or github)
+bundle-create-failed = Failed to create bundle directory { $path }
+bundle-write-failed = Failed to write { $path }
+wrote-bundle = Wrote report bundle to { $path } ({ $count } files)
+
+## Privilege escalation banner
+
+escalate-direct = Reading layers directly via { $driver } — much faster,
+escalate-needs-root = but { $sudo } needs root to access { $path }
+escalate-rerun = Re-running as root...
+escalate-hint = Can't sudo? Use --no-sudo to fall back to the OCI API.
+escalate-refused = Cannot read storage without root. Remove --no-sudo or use --use-oci.
+escalate-already = Already escalated but still cannot read { $path }. Check permissions.
+
+## Web report
+
+report-available = Report available at { $url }
+report-stop = Press Ctrl+C to stop.
+
+## peel probe
+
+probe-none = No container runtimes detected.
+probe-header = Detected container runtimes:
+probe-default = (default)
+probe-binary = Binary:
+probe-storage-root = Storage root:
+probe-storage-driver = Storage driver:
+probe-daemon-running = Daemon running:
+probe-storage-readable = Storage readable:
+probe-run-as-root = no (run as root)
+yes = yes
+no = no
+
+## peel diff / peel compare
+
+diff-total-size = total size: { $left } → { $right } ({ $delta })
+diff-layers = layers: { $shared } shared ({ $shared_size }), { $removed } removed, { $added } added
+diff-layer-size = size: { $size }
+diff-top-dirs = Largest directory changes
+compare-layer-reuse = Layer reuse
+compare-grew = { $image } is { $delta } larger than { $previous } ({ $size } in total)
+
+## peel shared
+
+shared-no-images = No tagged images found in the local runtime.
+shared-layers = Shared layers (digest, size, images using it)
+shared-more = … and { $count } more shared layers (see --json)
+shared-total = On disk: { $disk } for { $total } of images; sharing saves { $saved }
+
+## peel push-estimate
+
+spinner-checking-blob = Checking blob { $current }/{ $total } on { $registry } ...
+push-checked = Checked { $image } ({ $missing } of { $total } blobs missing)
+push-total = Transfer { $transfer } of { $total } ({ $missing } blobs missing at the destination)
+registry-bad-reference = Invalid image reference '{ $reference }'
+registry-manifest-failed = Failed to fetch manifest for { $reference } (HTTP { $status })
+registry-bad-manifest = Unrecognised manifest for { $reference }
+registry-head-failed = Blob check failed for { $url } (HTTP { $status })
+registry-request-failed = Request to { $url } failed
+registry-read-failed = Failed to read response from { $url }
+registry-unauthorized = Not authorized for { $registry }; run `docker login { $registry }`
+
+## peel pull-size
+
+pull-spinner-local = Reading local layers ...
+pull-local = Found { $count } local layers
+pull-no-platform = No { $platform } image in the index (available: { $available }); pick one with --platform
+pull-platform = Platform { $platform }
+pull-layer-local = local
+pull-layer-download = download
+pull-total = Pull { $transfer } of { $total } ({ $present } of { $count } layers already local)
+
+## peel layers
+
+layers-summary = { $layers } layers, { $size } in total
+
+## peel history
+
+history-summary = { $entries } steps, { $layers } with a layer, { $size } in total
+
+## peel stats
+
+stats-layers = layers: { $count }
+stats-size = size: { $size }
+stats-size-compressed = size: { $size } ({ $compressed } compressed)
+stats-files = files: { $files } in { $directories } directories
+stats-file-size = file size: median { $median }, largest { $max } ({ $path })
+stats-layer-files = in layers: { $files } files counting every copy, { $whiteouts } whiteouts
+stats-top-dirs = Largest top-level directories (size, files, path)
+
+## peel verify
+
+verify-archive = peel verify compares a local image with its tag in a registry; name the image by that tag, not an archive
+verify-spinner-remote = Resolving { $reference } in the registry ...
+verify-resolved-remote = Resolved { $reference }
+verify-manifest-digest = manifest: { $digest }
+verify-current = The local image is what the tag points at
+verify-status-stale = Stale: the tag has moved on since the local image was pulled
+verify-status-diverged = Diverged: the local image was built or changed locally and never pulled from a registry
+verify-stale = The local image is stale; pull { $reference } to update it
+verify-diverged = The local image differs from { $reference }
+
+## peel repro
+
+repro-layers = layers: { $identical } identical ({ $left } and { $right } in total)
+repro-files = files: { $files } compared, { $differing } differ
+repro-config = config differs in: { $fields }
+repro-content-heading = Content differs (size, path)
+repro-metadata-heading = Same content, different metadata (path, what changed)
+repro-more = … and { $count } more
+repro-identical = Identical: same image ID
+repro-metadata-only = Reproducible: every file has the same content; only timestamps and metadata differ
+repro-content-differs = Not reproducible: { $count } paths differ in content
+repro-failed = { $count } paths differ in content between the two builds
+
+## peel serve
+
+serve-listening = Serving inspections at { $url }
+serve-bind-failed = Failed to listen on { $address }
+serve-request = { $method } { $path } → { $status }
+serve-cached = { $image } unchanged since it was inspected; reusing the report
+serve-bad-request = Malformed HTTP request
+serve-body-too-large = Request body is larger than { $max } bytes
+serve-head-too-large = Request line and headers are larger than { $max } bytes
+serve-bad-body = Expected a JSON body like {"image": "nginx:latest"}: { $error }
+serve-missing-param = Missing query parameter '{ $name }'
+serve-bad-method = { $method } is not allowed here
+serve-not-found = No endpoint at { $path }; try POST /inspect, GET /images or GET /diff
+serve-archive = peel serve only inspects images the runtime holds, not archives
+
+## peel --rpc
+
+rpc-with-command = --rpc takes no command or image; send requests on stdin instead
+rpc-cancelled = Request cancelled
+rpc-parse-error = Not valid JSON: { $error }
+rpc-invalid-request = Not a JSON-RPC request: { $error }
+rpc-unknown-method = Unknown method '{ $method }'; try inspect, list_files, diff or cancel
+rpc-bad-params = Invalid params: { $error }
+rpc-listed-layer = Listed layer { $index } of { $image }
+rpc-bad-layer-index = No layer at index { $index } (indexes are 0..{ $last })
+
+## Plugins
+
+plugin-failed = Failed to run plugin { $path }
+plugins-none = No plugins found: put a peel-<name> executable on PATH to add `peel <name>`
+
+## --query
+
+query-unexpected = Unexpected { $token } at column { $column } of the query
+query-end = end of query
+query-unterminated = Unclosed { $quote } at column { $column } of the query
+query-bad-literal = Invalid literal at column { $column } of the query: { $error }
+query-step-zero = A slice step can't be 0
+query-unknown-function = Unknown function { $name }() in the query
+query-arity = { $name }() takes { $expected } arguments, not { $count }
+query-arity-min = { $name }() takes at least { $expected } arguments, not { $count }
+query-bad-arg = { $name }() can't take a { $kind } as argument { $position }
+query-bad-expref = &expressions can only be passed to functions like sort_by()
+query-schema = --query filters reports; peel schema prints the schema as it is
+
+## peel squash-sim
+
+squash-bad-range = Invalid layer range { $from }..{ $to } (layers are numbered 1..{ $last })
+squash-no-layers = The image has no layers to squash
+squash-range = Squashing layers { $from }..{ $to } ({ $count } layers)
+squash-layer-size = layers: { $before } → { $after } ({ $files } files)
+squash-image-size = image: { $before } → { $after } ({ $layers_before } → { $layers_after } layers)
+squash-kept-whiteouts = { $count } whiteouts kept to hide files from earlier layers
+squash-saved = Squashing would save { $size }
+
+## peel recompress
+
+recompress-bad-level = Invalid zstd level { $level } (expected 1-22)
+recompress-spinner = Compressing layer { $current }/{ $total } ...
+recompress-done = Compressed { $count } layers
+recompress-total = gzip { $gzip } → zstd -{ $level } { $zstd } ({ $change })
+
+## peel sbom
+
+sbom-spinner = Reading package databases in layer { $current }/{ $total } ...
+sbom-done = Found { $count } packages
+sbom-none = No dpkg or apk package database found
+sbom-total = { $count } packages (distro: { $distro })
+
+## peel scan
+
+scan-spinner = Looking up vulnerabilities with { $scanner } ...
+scan-done = Found { $count } vulnerabilities
+scan-osv-failed = Failed to query OSV at { $url }
+scan-osv-status = OSV at { $url } answered with status { $status }
+scan-not-installed = Failed to run { $scanner }; is it installed?
+scan-failed = { $scanner } failed: { $error }
+scan-bad-output = Failed to parse { $scanner }'s JSON output
+scan-layer = Layer { $layer }  { $command }
+scan-no-layer = Not traced to a layer
+scan-clean = No known vulnerabilities in { $packages } packages
+scan-total = { $count } vulnerabilities in { $affected } of { $packages } packages
+
+## peel secrets
+
+secrets-spinner = Reading layer { $current }/{ $total } ...
+secrets-done = Found { $count } possible secrets
+secrets-layer = Layer { $layer }  { $command }
+secrets-hidden = deleted later, still in this layer
+secrets-none = No secrets found in { $files } files
+secrets-total = { $count } possible secrets in { $files } files
+secrets-hidden-note = { $count } of them are in files a later layer deleted or replaced; they can still be extracted from the image
+secrets-annotation = { $location } in layer { $layer }
+secret-private-key = private key
+secret-aws-access-key = AWS access key
+secret-aws-secret-key = AWS secret key
+secret-gcp-service-account = GCP service account
+secret-gcp-api-key = GCP API key
+secret-github-token = GitHub token
+secret-slack-token = Slack token
+secret-stripe-key = Stripe key
+secret-credentials-file = credentials file
+secret-env-file = .env file
+secret-generic = secret
+
+## peel licenses
+
+licenses-spinner = Reading license files in layer { $current }/{ $total } ...
+licenses-done = Found { $count } license files
+licenses-none = No package licenses or license files found
+licenses-summary = Licenses (license, packages, other license files)
+licenses-unknown = unknown
+licenses-files = License files outside package docs (layer, license, path)
+licenses-more = … and { $count } more (see --json)
+licenses-unknown-packages = { $count } packages declare no license: { $names }
+
+## peel ldd
+
+ldd-spinner = Reading ELF files in layer { $current }/{ $total } ...
+ldd-done = Checked { $count } dynamically linked files ({ $broken } with missing libraries)
+ldd-layer = layer { $layer }
+ldd-not-found = not found
+ldd-elsewhere = found off the search path: { $paths }
+ldd-ok = All libraries of { $count } dynamically linked files were found ({ $static_files } statically linked)
+ldd-broken = { $broken } of { $count } dynamically linked files need libraries missing from the image
+ldd-annotation = { $path } needs { $libraries }, missing from the image
+
+## peel lint
+
+lint-error = error
+lint-warning = warning
+lint-info = info
+lint-large-layer = Layer { $layer } is { $size } from a single RUN: { $instruction }
+lint-add-instead-of-copy = Layer { $layer } uses ADD for local files; COPY does the same without unpacking or downloading: { $instruction }
+lint-base-latest = Base image { $base } floats with :latest; pin a tag or digest
+lint-package-cache = Layer { $layer } leaves { $cache } behind ({ $size } in { $path })
+lint-runs-as-root = Runs as root: the config sets no USER
+lint-runs-as-root-user = Runs as root (USER { $user })
+lint-clean = No lint findings
+lint-summary = { $errors } errors, { $warnings } warnings, { $infos } info
+lint-failed = { $count } lint findings at { $level } or above
+lint-custom = Matches a custom rule
+lint-custom-file = { $message }: { $path } ({ $size }, layer { $layer })
+lint-custom-total = { $message }: files matching { $pattern } total { $size }
+lint-config-read-failed = Failed to read lint config { $path }
+lint-config-invalid = { $path } is not a valid lint config
+lint-config-no-threshold = Rule { $rule } has no max_size setting
+lint-config-bad-id = Custom rule id "{ $id }" is empty, repeated or the name of a built-in rule
+
+## peel check
+
+check-no-limits = Nothing to check; give at least one of --max-size, --max-layer-size, --max-files, --min-efficiency, --forbid, --baseline, --budget or --fail-on
+check-max-size = Image is { $size } (limit { $limit })
+check-max-layer-size = No layer is over { $limit }
+check-max-layer-size-over = { $count } layers are over { $limit }; layer { $layer } is { $size }
+check-max-files = { $files } files (limit { $limit })
+check-min-efficiency = Efficiency { $efficiency } (at least { $limit })
+check-forbidden-none = No file matches { $pattern }
+check-forbidden-found = { $count } files match { $pattern }, e.g. { $path } in layer { $layer }
+check-max-growth = Image is { $size }, { $delta } ({ $percent }) from the baseline's { $baseline } (limit { $limit })
+check-new-files-none = No new file over { $limit }
+check-new-files-found = { $count } new files over { $limit }; the largest is { $path } ({ $size }) in layer { $layer }
+check-baseline-no-limits = --baseline needs --max-growth or --max-new-file to say what growth is too much
+check-not-a-baseline = { $path } is not a peel inspect --json report
+check-budget = { $path } holds { $size } (budget { $limit })
+check-more = … and { $count } more
+check-passed = All { $total } checks passed
+check-failed = { $failed } of { $total } checks failed
+check-max-layers = { $layers } layers (limit { $limit })
+check-secrets-none = No secrets in { $files } files scanned
+check-secrets-found = { $count } secrets found, e.g. in { $path } in layer { $layer }
+
+## peel build
+
+build-unsupported-runtime = peel build needs Docker or Podman (buildx needs Docker); the runtime is { $runtime }
+build-compare-needs-tag = --compare needs a -t/--tag for the build, to know which image came before it
+build-compare-json = --compare prints a text comparison and can't be combined with --json or --query
+build-failed = The build failed ({ $status })
+build-no-image-id = The build didn't report an image ID
+build-done = Built { $id } ({ $image })
+build-done-untagged = Built { $id }
+build-unchanged = The build produced the same image as before
+build-no-previous = No previous { $image } to compare with
+build-previous = previous ({ $id })
+
+## peel watch
+
+watch-started = Watching { $image } every { $seconds }s for a new build; press Ctrl-C to stop
+watch-run = { $size } in { $layers } layers, { $efficiency } efficient
+watch-delta = { $delta } since the last build
+watch-same-size = Same size as the last build
+watch-new-layer = + layer { $layer }  { $size }
+watch-load-failed = Couldn't inspect the new build: { $error }
+watch-image-missing = Can't find { $image }
+
+## peel flatten
+
+spinner-flattening-layer = Writing layer { $current }/{ $total } ...
+flatten-merged = Merged { $layers } layers ({ $files } files)
+flatten-done = Wrote { $path } ({ $size }, was { $before })
+flatten-bad-config = Image config is not a JSON object
+
+## peel export-layer
+
+spinner-exporting-layer = Exporting layer { $index } ...
+export-layer-done = Exported layer { $index } ({ $digest })
+export-layer-wrote = Wrote { $path } ({ $size }, { $digest })
+export-layer-bad-index = No layer { $index } (layers are numbered 1..{ $last })
+export-layer-not-found = No layer matches { $layer }
+export-layer-ambiguous = { $layer } matches { $count } layers; use a longer prefix
+
+## peel extract
+
+extract-not-found = { $path } is not a file in the final filesystem
+extract-not-in-layer = { $path } is not a file in layer { $layer }
+extract-symlink = { $path } is a symbolic link to { $target }
+extract-done = Wrote { $path } ({ $size }, from layer { $layer })
+
+## peel cat
+
+cat-too-large = { $path } is { $size }, over the { $limit } limit; raise --max-size or use `peel extract`
+
+## peel diff-file
+
+diff-file-identical = { $path } is identical in both images ({ $digest })
+diff-file-binary = { $path } differs (binary or too large to diff; size, sha256, image)
+
+## Cluster check
+
+inspect-cluster = Running in the cluster (status, pods, workload)
+inspect-runtime = Runtime
+runtime-entrypoint = entrypoint: { $value }
+runtime-cmd = cmd: { $value }
+runtime-workdir = workdir: { $value }
+runtime-user = user: { $value }
+runtime-ports = ports: { $value }
+runtime-volumes = volumes: { $value }
+runtime-healthcheck = healthcheck: { $value }
+runtime-env = env:
+cluster-none = No pods in the cluster run this repository
+cluster-match = this image
+cluster-differs = different
+cluster-unknown = unknown
+cluster-mismatch = { $workload } [{ $container }] runs a different build ({ $digest })
+kube-run-failed = Failed to run kubectl
+kube-failed = kubectl get pods failed: { $error }
+kube-parse-failed = Failed to parse kubectl output
+
+## peel du
+
+du-not-found = No files under { $path }
+du-layer-only = Layer { $layer } only: { $files } files, ignoring what other layers add or delete
+du-tree-pruned = … { $entries } more below the size limit
+
+## peel find
+
+find-added = added
+find-modified = modified
+find-deleted = deleted
+find-summary = { $count } matches, { $visible } in the final filesystem
+
+## peel grep
+
+grep-bad-pattern = Invalid pattern '{ $pattern }'
+grep-binary = binary file matches
+grep-hidden = (not in final filesystem)
+grep-summary = { $count } matches in { $files } files
+grep-skipped = { $count } files over --max-size not searched (largest { $size })
+
+## peel copy
+
+spinner-copying-image = Copying { $image }...
+spinner-copying-layer = Copying layer { $current }/{ $total }...
+copy-copied-image = Copied { $image } ({ $layers } layers)
+copy-summary = Pushed { $pushed } blobs ({ $size }), mounted { $mounted }, { $skipped } already present
+copy-dropped = Dropped { $layers } layers and { $entries } paths
+copy-bad-level = Invalid gzip level { $level } (expected 0-9)
+copy-bad-drop = --drop-layer { $prefix } has no hex digits to match a layer digest
+copy-drop-ambiguous = --drop-layer { $prefix } matches { $count } layers; give more of the digest
+copy-drop-no-match = --drop-layer { $prefix } matches no layer of the image
+registry-blob-failed = Failed to fetch blob { $url } (HTTP { $status })
+registry-push-failed = Upload to { $url } failed (HTTP { $status })
+repack-unsupported-media-type = Can't rewrite layers of type { $media_type } (only tar and tar+gzip)
+repack-read-failed = Failed to read layer archive
+repack-write-failed = Failed to write { $path }
+
+## --debug-http
+
+http-trace = { $method } { $url } → { $status } ({ $ms } ms, auth: { $auth })
+http-dump-failed = Failed to write HTTP debug dump to { $path }
+
+## peel render
+
+render-read-failed = Failed to read { $path }
+render-not-a-report = { $path } is not a peel inspect, diff or compare report
+render-no-html = HTML output is not available for compare reports
+
+## peel update
+
+update-missing = Could not find `{ $updater }`. Reinstall peel via the shell installer to get the updater:\n\n  curl --proto '=https' --tlsv1.2 -LsSf https://github.com/fudanglp/peel/releases/latest/download/peel-installer.sh | sh
+update-failed = Update failed (exit code: { $code })
+
+## --timings
+
+timings-title = Timings
+timings-total = total
@@ -0,0 +1,564 @@
+# 简体中文 (Simplified Chinese). Untranslated keys fall back to en.ftl.
+
+## Runtime selection
+
+runtime-unknown = 未知的运行时 '{ $name }'。可选值：docker、podman、containerd
+runtime-not-detected = 本机未检测到运行时 '{ $name }'。运行 `peel probe` 查看可用的运行时。
+runtime-none-installed = 未检测到容器运行时。请安装 Docker 或 Podman，或者使用 tar 归档文件。
+runtimes-none = 未检测到容器运行时
+runtimes-label = 运行时
+selected-label = 已选择
+selected-detail = （存储：{ $root }，方式：{ $method }）
+
+## Inspection progress
+
+spinner-resolving = 正在解析镜像元数据...
+spinner-reading-layer = 正在读取第 { $current }/{ $total } 层 ...
+spinner-resolving-image = 正在解析 { $image } ...
+inspected-layers = 已检查 { $count } 层
+resolved-metadata = 已解析镜像元数据
+resolved-image = 已解析 { $image }（{ $count } 层）
+spinner-verifying = 正在将各层内容与 diff_id 比对...
+verify-done = 已校验 { $verified } 层，{ $mismatched } 层不匹配
+
+## Output
+
+inspect-id = 镜像 ID：{ $id }
+inspect-digests = 摘要：{ $digests }
+inspect-arch = 架构：{ $arch }
+inspect-distro = 发行版：{ $distro }
+inspect-class = 类型：{ $class }
+inspect-runtimes = 语言运行时：{ $runtimes }
+inspect-built-by = 构建工具：{ $builder }
+inspect-vcs = 源码：{ $source }
+inspect-build-args = 构建参数：{ $args }
+inspect-base-images = 拉取的镜像：{ $images }
+inspect-labels = 标签：
+inspect-annotations = 注解：
+class-distro = 发行版
+class-minimal = 精简
+class-distroless = distroless
+class-scratch = scratch
+class-no-shell = 无 shell
+inspect-total-size = 总大小：{ $size } 字节
+inspect-partial = 部分统计：仅计入 { $roots } 下的文件
+inspect-verified = 校验：{ $total } 层中有 { $verified } 层与 diff_id 一致（跳过 { $skipped } 层）
+inspect-diff-id-mismatch = 第 { $layer } 层与其 diff_id { $expected } 不一致：{ $found }
+inspect-created = 创建于：{ $date }（{ $age }前）
+inspect-compressed-size = 压缩后：{ $size } 字节（镜像仓库中及拉取时的大小）
+inspect-poor-compression = 压缩效果差：是否为已压缩或随机数据？
+inspect-inherited-size = 继承自 { $base }：{ $size }（{ $count } 层）
+inspect-added-size = 新增：{ $size }
+inspect-added-layers = ── 基础镜像之上新增的层 ──
+inspect-efficiency = 效率：{ $efficiency }
+inspect-wasted-size = 浪费：{ $size }
+efficiency-below-target = 镜像效率 { $efficiency } 低于 --min-efficiency 目标 { $target }
+inspect-wasted-paths = 浪费的空间（大小、层、路径）
+inspect-high-entropy = 疑似加壳或加密的文件（大小、比特/字节、层、路径）
+inspect-deleted = 已删除
+inspect-largest = 最大的文件；没有包管理器可说明其来源（大小、层、路径）
+inspect-cache-mounts = 层中包含构建缓存；使用缓存挂载可节省 { $size }（大小、层、路径）
+inspect-junk = 可移除的文件；不放入镜像可节省 { $size }（大小、类型、位置；引入它们的层）
+junk-dirs = { $count } 个目录，例如 { $path }
+junk-apt-lists = apt 软件包列表
+junk-apt-cache = 已下载的 .deb 文件
+junk-pip-cache = pip 缓存
+junk-npm-cache = npm 缓存
+junk-yarn-cache = yarn 缓存
+junk-pycache = Python 字节码
+junk-git = git 仓库
+junk-fix-apt-lists = 在执行 apt-get update 的 RUN 末尾加上 `rm -rf /var/lib/apt/lists/*`
+junk-fix-apt-cache = 在 apt-get install 所在的 RUN 中执行 `apt-get clean`
+junk-fix-pip-cache = 使用 `pip install --no-cache-dir`
+junk-fix-npm-cache = 在 npm install 所在的 RUN 中执行 `npm cache clean --force`
+junk-fix-yarn-cache = 在 yarn install 所在的 RUN 中执行 `yarn cache clean`
+junk-fix-pycache = 设置 `ENV PYTHONDONTWRITEBYTECODE=1`，或将 `__pycache__` 加入 .dockerignore
+junk-fix-git = 将 `.git` 加入 .dockerignore
+inspect-toolchain = 最终镜像中的构建工具；多阶段构建可省去 { $size }（大小、工具、程序）
+tool-compiler = 编译器
+tool-build-tool = 构建工具
+tool-headers = 头文件
+tool-package-manager = 包管理器
+tool-files = { $count } 个文件
+inspect-duplicates = 重复文件；除第一份外的副本占用 { $size }（浪费；每份副本的层和路径）
+duplicates-copies = { $count } 份 { $size } 的副本
+duplicates-more = …… 另有 { $count } 组（见 --json）
+inspect-posture = 安全概况（层、权限、属主、路径）
+posture-user-unknown = 运行用户未知：无法获取镜像配置
+posture-root-default = 以 root 运行：未设置 USER
+posture-root = 以 root 运行（USER { $user }）
+posture-user = 以 { $user } 运行
+posture-setuid = { $count } 个 setuid/setgid 文件：
+posture-world-writable = { $count } 个所有人可写的文件：
+posture-more = …… 另有 { $count } 个（见 --json）
+inspect-strip = 如何瘦身：删除以下内容可节省 { $size }（大小、层、类型、位置）
+strip-man = man 手册
+strip-docs = 文档
+strip-info = info 手册
+strip-locales = 本地化文件
+strip-debug = 调试符号
+strip-hint = 请在这些层的 RUN 指令中删除它们；之后单独的 RUN rm 不会减小镜像：
+inspect-content = 按类别划分的内容（逐层，然后为合计）
+category-os = 系统
+category-runtime = 运行时
+category-code = 代码
+category-assets = 静态资源
+category-data = 数据
+category-cache = 缓存
+category-other = 其他
+inspect-file-types = 文件类型（各层占比最大的类型，然后是总计）
+file-type-executable = 可执行文件
+file-type-shared-lib = 共享库
+file-type-python = Python
+file-type-javascript = JavaScript
+file-type-docs = 文档
+file-type-locale = 本地化数据
+file-type-image = 图片
+file-type-archive = 压缩包
+file-type-other = 其他
+inspect-merged-summary = 合并后的文件系统：{ $count } 个文件，{ $size }（大小、层、路径）
+base-unrelated = { $image } 与 { $base } 没有共同的底层
+wrote-file = 已写入 { $path }
+wrote-file-size = 已写入 { $path }（{ $size }）
+wrote-index = 已将 { $count } 个 tar 条目写入 { $path }
+emit-index-no-blobs = --emit-index 需要层的原始 blob；直接读取存储时只有解包后的目录（可尝试 --use-oci）
+write-json-failed = 无法写入 JSON 到 { $path }
+write-csv-failed = 无法写入 CSV 到 { $path }
+log-file-failed = 无法打开日志文件 { $path }
+dockerfile-read-failed = 无法读取 Dockerfile { $path }
+dockerfile-no-match = 没有层与 { $path } 中的指令匹配；这是构建该镜像所用的 Dockerfile 吗？
+junit-write-failed = 无法写入 JUnit 报告到 { $path }
+output-junit-unsupported = --output junit:<文件> 仅适用于会做检查的 lint、check 和 inspect --min-efficiency
+output-csv-unsupported = --output csv 仅适用于会列出文件的 inspect
+fail-on-unsupported = --fail-on 仅适用于 inspect、check 和 lint
+inspect-only-flag = { $flag } 仅适用于会检查镜像的 inspect 和 build
+base-unsupported = --base 仅适用于 inspect、build 和 lint
+debug-http-unsupported = --debug-http 仅适用于会访问镜像仓库的 verify、pull-size、push-estimate 和 copy
+preset-config-read-failed = 无法读取 { $path }
+preset-config-invalid = { $path } 不是有效的配置；格式为 {"preset": "security"}、"size"、"compliance" 或 "ci"
+fail-on-severity-lint-only = --fail-on 中的严重级别仅用于 lint；inspect 和 check 接受 size>大小、layers>N、files>N、efficiency<百分比 或 secrets
+no-files-fail-on = --no-files 不列出文件，因此 --fail-on 只能使用 size>大小 和 layers>N
+verify-failed = { $count } 层与其 diff_id 不一致；镜像可能已损坏或被篡改
+fail-on-lint-severity-only = lint 的 --fail-on 只接受严重级别（info、warning 或 error）
+write-html-failed = 无法写入 HTML 到 { $path }
+output-unsupported = 不支持的 --output 目标 '{ $spec }'（应为 bundle:<目录>、junit:<文件>、csv[:<文件>] 或 github）
+bundle-create-failed = 无法创建报告包目录 { $path }
+bundle-write-failed = 无法写入 { $path }
+wrote-bundle = 已写入报告包 { $path }（{ $count } 个文件）
+
+## Privilege escalation banner
+
+escalate-direct = 直接通过 { $driver } 读取镜像层 — 速度快得多，
+escalate-needs-root = 但 { $sudo } 需要 root 权限才能访问 { $path }
+escalate-rerun = 正在以 root 身份重新运行...
+escalate-hint = 无法使用 sudo？使用 --no-sudo 改用 OCI API。
+escalate-refused = 没有 root 权限无法读取存储。请去掉 --no-sudo 或使用 --use-oci。
+escalate-already = 已提升权限但仍无法读取 { $path }。请检查权限。
+
+## Web report
+
+report-available = 报告地址：{ $url }
+report-stop = 按 Ctrl+C 停止。
+
+## peel probe
+
+probe-none = 未检测到容器运行时。
+probe-header = 检测到的容器运行时：
+probe-default = （默认）
+probe-binary = 可执行文件：
+probe-storage-root = 存储目录：
+probe-storage-driver = 存储驱动：
+probe-daemon-running = 守护进程运行中：
+probe-storage-readable = 存储可读：
+probe-run-as-root = 否（请以 root 运行）
+yes = 是
+no = 否
+
+## peel diff / peel compare
+
+diff-total-size = 总大小：{ $left } → { $right }（{ $delta }）
+diff-layers = 镜像层：{ $shared } 个共享（{ $shared_size }），{ $removed } 个移除，{ $added } 个新增
+diff-layer-size = 大小：{ $size }
+diff-top-dirs = 变化最大的目录
+compare-layer-reuse = 镜像层复用
+compare-grew = { $image } 比 { $previous } 大 { $delta }（共 { $size }）
+
+## peel shared
+
+shared-no-images = 本地运行时中没有找到带标签的镜像。
+shared-layers = 共享层（摘要、大小、使用它的镜像）
+shared-more = …… 另有 { $count } 个共享层（见 --json）
+shared-total = 磁盘占用：{ $disk }，镜像合计 { $total }；共享节省了 { $saved }
+
+## peel push-estimate
+
+spinner-checking-blob = 正在检查 { $registry } 上的 blob { $current }/{ $total } ...
+push-checked = 已检查 { $image }（缺少 { $total } 个 blob 中的 { $missing } 个）
+push-total = 需传输 { $transfer }，共 { $total }（目标仓库缺少 { $missing } 个 blob）
+registry-bad-reference = 无效的镜像引用 '{ $reference }'
+registry-manifest-failed = 无法获取 { $reference } 的清单（HTTP { $status }）
+registry-bad-manifest = 无法识别 { $reference } 的清单
+registry-head-failed = 检查 blob 失败：{ $url }（HTTP { $status }）
+registry-request-failed = 请求 { $url } 失败
+registry-read-failed = 无法读取 { $url } 的响应
+registry-unauthorized = 未获得 { $registry } 的授权，请运行 `docker login { $registry }`
+
+## peel pull-size
+
+pull-spinner-local = 正在读取本地层 ...
+pull-local = 找到 { $count } 个本地层
+pull-no-platform = 索引中没有 { $platform } 镜像（可用：{ $available }）；请用 --platform 选择
+pull-platform = 平台 { $platform }
+pull-layer-local = 本地已有
+pull-layer-download = 需下载
+pull-total = 拉取 { $transfer }，共 { $total }（{ $count } 层中 { $present } 层本地已有）
+
+## peel layers
+
+layers-summary = 共 { $layers } 层，{ $size }
+
+## peel history
+
+history-summary = 共 { $entries } 个步骤，其中 { $layers } 个产生了层，共 { $size }
+
+## peel stats
+
+stats-layers = 层数：{ $count }
+stats-size = 大小：{ $size }
+stats-size-compressed = 大小：{ $size }（压缩后 { $compressed }）
+stats-files = 文件：{ $directories } 个目录中的 { $files } 个文件
+stats-file-size = 文件大小：中位数 { $median }，最大 { $max }（{ $path }）
+stats-layer-files = 各层合计：{ $files } 个文件（含每个副本），{ $whiteouts } 个 whiteout
+stats-top-dirs = 最大的顶层目录（大小、文件数、路径）
+
+## peel verify
+
+verify-archive = peel verify 将本地镜像与其在仓库中的标签比较；请用该标签指定镜像，而不是归档文件
+verify-spinner-remote = 正在从仓库解析 { $reference } ...
+verify-resolved-remote = 已解析 { $reference }
+verify-manifest-digest = 清单：{ $digest }
+verify-current = 本地镜像与标签指向的镜像一致
+verify-status-stale = 已过时：本地镜像拉取后，标签已指向新的镜像
+verify-status-diverged = 已分叉：本地镜像是在本地构建或修改的，从未从仓库拉取
+verify-stale = 本地镜像已过时；拉取 { $reference } 以更新
+verify-diverged = 本地镜像与 { $reference } 不一致
+
+## peel repro
+
+repro-layers = 层：{ $identical } 层相同（共 { $left } 层与 { $right } 层）
+repro-files = 文件：比较了 { $files } 个，{ $differing } 个不同
+repro-config = 配置中不同的字段：{ $fields }
+repro-content-heading = 内容不同（大小、路径）
+repro-metadata-heading = 内容相同、元数据不同（路径、变化）
+repro-more = … 另有 { $count } 个
+repro-identical = 完全相同：镜像 ID 一致
+repro-metadata-only = 可复现：所有文件内容相同，仅时间戳和元数据不同
+repro-content-differs = 不可复现：{ $count } 个路径内容不同
+repro-failed = 两次构建之间有 { $count } 个路径内容不同
+
+## peel serve
+
+serve-listening = 检查服务地址：{ $url }
+serve-bind-failed = 无法监听 { $address }
+serve-request = { $method } { $path } → { $status }
+serve-cached = { $image } 自上次检查后未变化，复用报告
+serve-bad-request = HTTP 请求格式错误
+serve-body-too-large = 请求体超过 { $max } 字节
+serve-head-too-large = 请求行和请求头超过 { $max } 字节
+serve-bad-body = 请求体应为 JSON，例如 {"image": "nginx:latest"}：{ $error }
+serve-missing-param = 缺少查询参数 '{ $name }'
+serve-bad-method = 此处不允许 { $method }
+serve-not-found = { $path } 没有对应接口；可用 POST /inspect、GET /images 或 GET /diff
+serve-archive = peel serve 只检查运行时中的镜像，不检查归档文件
+
+## peel --rpc
+
+rpc-with-command = --rpc 不接受命令或镜像，请通过 stdin 发送请求
+rpc-cancelled = 请求已取消
+rpc-parse-error = 不是有效的 JSON：{ $error }
+rpc-invalid-request = 不是 JSON-RPC 请求：{ $error }
+rpc-unknown-method = 未知方法 '{ $method }'，可用 inspect、list_files、diff 或 cancel
+rpc-bad-params = 参数无效：{ $error }
+rpc-listed-layer = 已列出 { $image } 的第 { $index } 层
+rpc-bad-layer-index = 没有索引为 { $index } 的层（索引为 0..{ $last }）
+
+## Plugins
+
+plugin-failed = 无法运行插件 { $path }
+plugins-none = 未找到插件：在 PATH 中放置 peel-<name> 可执行文件即可添加 `peel <name>`
+
+## --query
+
+query-unexpected = 查询第 { $column } 列出现意外的 { $token }
+query-end = 查询结尾
+query-unterminated = 查询第 { $column } 列的 { $quote } 未闭合
+query-bad-literal = 查询第 { $column } 列的字面量无效：{ $error }
+query-step-zero = 切片步长不能为 0
+query-unknown-function = 查询中的函数 { $name }() 不存在
+query-arity = { $name }() 需要 { $expected } 个参数，实际为 { $count }
+query-arity-min = { $name }() 至少需要 { $expected } 个参数，实际为 { $count }
+query-bad-arg = { $name }() 的第 { $position } 个参数不能是 { $kind }
+query-bad-expref = &表达式只能作为 sort_by() 等函数的参数
+query-schema = --query 用于筛选报告；peel schema 只会原样输出 schema
+
+## peel squash-sim
+
+squash-bad-range = 无效的层范围 { $from }..{ $to }（层编号为 1..{ $last }）
+squash-no-layers = 该镜像没有可合并的层
+squash-range = 合并第 { $from }..{ $to } 层（共 { $count } 层）
+squash-layer-size = 层：{ $before } → { $after }（{ $files } 个文件）
+squash-image-size = 镜像：{ $before } → { $after }（{ $layers_before } → { $layers_after } 层）
+squash-kept-whiteouts = 保留了 { $count } 个删除标记以隐藏更早层中的文件
+squash-saved = 合并可节省 { $size }
+
+## peel recompress
+
+recompress-bad-level = 无效的 zstd 级别 { $level }（应为 1-22）
+recompress-spinner = 正在压缩第 { $current }/{ $total } 层 ...
+recompress-done = 已压缩 { $count } 层
+recompress-total = gzip { $gzip } → zstd -{ $level } { $zstd }（{ $change }）
+
+## peel sbom
+
+sbom-spinner = 正在读取第 { $current }/{ $total } 层的软件包数据库 ...
+sbom-done = 找到 { $count } 个软件包
+sbom-none = 未找到 dpkg 或 apk 软件包数据库
+sbom-total = { $count } 个软件包（发行版：{ $distro }）
+
+## peel scan
+
+scan-spinner = 正在通过 { $scanner } 查询漏洞 ...
+scan-done = 发现 { $count } 个漏洞
+scan-osv-failed = 查询 OSV 失败：{ $url }
+scan-osv-status = OSV（{ $url }）返回状态码 { $status }
+scan-not-installed = 无法运行 { $scanner }，是否已安装？
+scan-failed = { $scanner } 运行失败：{ $error }
+scan-bad-output = 无法解析 { $scanner } 的 JSON 输出
+scan-layer = 第 { $layer } 层  { $command }
+scan-no-layer = 未能对应到具体层
+scan-clean = { $packages } 个软件包中没有已知漏洞
+scan-total = { $packages } 个软件包中有 { $affected } 个存在漏洞，共 { $count } 个
+
+## peel secrets
+
+secrets-spinner = 正在读取第 { $current }/{ $total } 层 ...
+secrets-done = 发现 { $count } 处疑似密钥
+secrets-layer = 第 { $layer } 层  { $command }
+secrets-hidden = 已被后续层删除，但仍在本层中
+secrets-none = 在 { $files } 个文件中未发现密钥
+secrets-total = { $files } 个文件中有 { $count } 处疑似密钥
+secrets-hidden-note = 其中 { $count } 处位于被后续层删除或替换的文件中，仍可从镜像中提取
+secrets-annotation = 第 { $layer } 层中的 { $location }
+secret-private-key = 私钥
+secret-aws-access-key = AWS 访问密钥
+secret-aws-secret-key = AWS 秘密密钥
+secret-gcp-service-account = GCP 服务账号
+secret-gcp-api-key = GCP API 密钥
+secret-github-token = GitHub 令牌
+secret-slack-token = Slack 令牌
+secret-stripe-key = Stripe 密钥
+secret-credentials-file = 凭据文件
+secret-env-file = .env 文件
+secret-generic = 密钥
+
+## peel licenses
+
+licenses-spinner = 正在读取第 { $current }/{ $total } 层的许可证文件 ...
+licenses-done = 找到 { $count } 个许可证文件
+licenses-none = 未找到软件包许可证或许可证文件
+licenses-summary = 许可证（许可证、软件包数、其他许可证文件数）
+licenses-unknown = 未知
+licenses-files = 软件包文档之外的许可证文件（层、许可证、路径）
+licenses-more = … 另有 { $count } 个（见 --json）
+licenses-unknown-packages = { $count } 个软件包未声明许可证：{ $names }
+
+## peel ldd
+
+ldd-spinner = 正在读取第 { $current }/{ $total } 层的 ELF 文件 ...
+ldd-done = 已检查 { $count } 个动态链接文件（{ $broken } 个缺少库）
+ldd-layer = 第 { $layer } 层
+ldd-not-found = 未找到
+ldd-elsewhere = 在搜索路径之外找到：{ $paths }
+ldd-ok = { $count } 个动态链接文件所需的库均已找到（另有 { $static_files } 个静态链接）
+ldd-broken = { $count } 个动态链接文件中有 { $broken } 个需要镜像中缺失的库
+ldd-annotation = { $path } 需要镜像中缺失的 { $libraries }
+
+## peel lint
+
+lint-error = 错误
+lint-warning = 警告
+lint-info = 提示
+lint-large-layer = 第 { $layer } 层由单个 RUN 生成，大小为 { $size }：{ $instruction }
+lint-add-instead-of-copy = 第 { $layer } 层用 ADD 添加本地文件；COPY 效果相同且不会解压或下载：{ $instruction }
+lint-base-latest = 基础镜像 { $base } 使用浮动的 :latest；请固定标签或摘要
+lint-package-cache = 第 { $layer } 层遗留了{ $cache }（{ $path } 中 { $size }）
+lint-runs-as-root = 以 root 运行：配置未设置 USER
+lint-runs-as-root-user = 以 root 运行（USER { $user }）
+lint-clean = 没有 lint 问题
+lint-summary = { $errors } 个错误，{ $warnings } 个警告，{ $infos } 个提示
+lint-failed = 有 { $count } 个 lint 问题达到或超过{ $level }级别
+lint-custom = 匹配自定义规则
+lint-custom-file = { $message }：{ $path }（{ $size }，第 { $layer } 层）
+lint-custom-total = { $message }：匹配 { $pattern } 的文件共 { $size }
+lint-config-read-failed = 无法读取 lint 配置 { $path }
+lint-config-invalid = { $path } 不是有效的 lint 配置
+lint-config-no-threshold = 规则 { $rule } 没有 max_size 设置
+lint-config-bad-id = 自定义规则 ID "{ $id }" 为空、重复或与内置规则同名
+
+## peel check
+
+check-no-limits = 没有可检查的项；请至少指定 --max-size、--max-layer-size、--max-files、--min-efficiency、--forbid、--baseline、--budget 或 --fail-on 之一
+check-max-size = 镜像大小为 { $size }（上限 { $limit }）
+check-max-layer-size = 没有层超过 { $limit }
+check-max-layer-size-over = { $count } 层超过 { $limit }；第 { $layer } 层为 { $size }
+check-max-files = { $files } 个文件（上限 { $limit }）
+check-min-efficiency = 效率 { $efficiency }（至少 { $limit }）
+check-forbidden-none = 没有文件匹配 { $pattern }
+check-forbidden-found = { $count } 个文件匹配 { $pattern }，例如第 { $layer } 层中的 { $path }
+check-max-growth = 镜像大小为 { $size }，相对基线的 { $baseline } 变化 { $delta }（{ $percent }）（上限 { $limit }）
+check-new-files-none = 没有超过 { $limit } 的新文件
+check-new-files-found = { $count } 个新文件超过 { $limit }；最大的是第 { $layer } 层中的 { $path }（{ $size }）
+check-baseline-no-limits = --baseline 需要配合 --max-growth 或 --max-new-file 指定允许的增长
+check-not-a-baseline = { $path } 不是 peel inspect --json 报告
+check-budget = { $path } 占用 { $size }（预算 { $limit }）
+check-more = … 另有 { $count } 个
+check-passed = 全部 { $total } 项检查通过
+check-failed = { $total } 项检查中有 { $failed } 项未通过
+check-max-layers = { $layers } 层（上限 { $limit }）
+check-secrets-none = 已扫描 { $files } 个文件，未发现密钥
+check-secrets-found = 发现 { $count } 处密钥，例如第 { $layer } 层的 { $path }
+
+## peel build
+
+build-unsupported-runtime = peel build 需要 Docker 或 Podman（buildx 需要 Docker）；当前运行时为 { $runtime }
+build-compare-needs-tag = --compare 需要为构建指定 -t/--tag，才能知道之前的镜像是哪个
+build-compare-json = --compare 输出的是文本对比，不能与 --json 或 --query 一起使用
+build-failed = 构建失败（{ $status }）
+build-no-image-id = 构建没有报告镜像 ID
+build-done = 已构建 { $id }（{ $image }）
+build-done-untagged = 已构建 { $id }
+build-unchanged = 本次构建生成的镜像与之前相同
+build-no-previous = 没有之前的 { $image } 可供比较
+build-previous = 之前（{ $id }）
+
+## peel watch
+
+watch-started = 每 { $seconds } 秒检查一次 { $image } 是否有新构建；按 Ctrl-C 停止
+watch-run = { $size }，共 { $layers } 层，效率 { $efficiency }
+watch-delta = 比上次构建 { $delta }
+watch-same-size = 与上次构建大小相同
+watch-new-layer = + 第 { $layer } 层  { $size }
+watch-load-failed = 无法检查新构建：{ $error }
+watch-image-missing = 找不到 { $image }
+
+## peel flatten
+
+spinner-flattening-layer = 正在写入第 { $current }/{ $total } 层 ...
+flatten-merged = 已合并 { $layers } 层（{ $files } 个文件）
+flatten-done = 已写入 { $path }（{ $size }，原为 { $before }）
+flatten-bad-config = 镜像配置不是 JSON 对象
+
+## peel export-layer
+
+spinner-exporting-layer = 正在导出第 { $index } 层 ...
+export-layer-done = 已导出第 { $index } 层（{ $digest }）
+export-layer-wrote = 已写入 { $path }（{ $size }，{ $digest }）
+export-layer-bad-index = 没有第 { $index } 层（层编号为 1..{ $last }）
+export-layer-not-found = 没有与 { $layer } 匹配的层
+export-layer-ambiguous = { $layer } 匹配了 { $count } 层，请使用更长的前缀
+
+## peel extract
+
+extract-not-found = { $path } 不是最终文件系统中的文件
+extract-not-in-layer = { $path } 不是第 { $layer } 层中的文件
+extract-symlink = { $path } 是指向 { $target } 的符号链接
+extract-done = 已写入 { $path }（{ $size }，来自第 { $layer } 层）
+
+## peel cat
+
+cat-too-large = { $path } 大小为 { $size }，超过 { $limit } 的限制；请调大 --max-size 或使用 `peel extract`
+
+## peel diff-file
+
+diff-file-identical = { $path } 在两个镜像中完全相同（{ $digest }）
+diff-file-binary = { $path } 不同（二进制或过大，无法逐行比较；大小、sha256、镜像）
+
+## Cluster check
+
+inspect-cluster = 在集群中运行（状态、Pod 数、工作负载）
+inspect-runtime = 运行配置
+runtime-entrypoint = 入口：{ $value }
+runtime-cmd = 命令：{ $value }
+runtime-workdir = 工作目录：{ $value }
+runtime-user = 用户：{ $value }
+runtime-ports = 端口：{ $value }
+runtime-volumes = 卷：{ $value }
+runtime-healthcheck = 健康检查：{ $value }
+runtime-env = 环境变量：
+cluster-none = 集群中没有运行该仓库镜像的 Pod
+cluster-match = 即此镜像
+cluster-differs = 不同
+cluster-unknown = 未知
+cluster-mismatch = { $workload } [{ $container }] 运行的是另一个构建（{ $digest }）
+kube-run-failed = 无法运行 kubectl
+kube-failed = kubectl get pods 失败：{ $error }
+kube-parse-failed = 无法解析 kubectl 输出
+
+## peel du
+
+du-not-found = { $path } 下没有文件
+du-layer-only = 仅第 { $layer } 层：{ $files } 个文件，未计入其他层的新增或删除
+du-tree-pruned = … 另有 { $entries } 项低于大小下限
+
+## peel find
+
+find-added = 新增
+find-modified = 修改
+find-deleted = 删除
+find-summary = 共 { $count } 处匹配，其中 { $visible } 处在最终文件系统中
+
+## peel grep
+
+grep-bad-pattern = 无效的模式 '{ $pattern }'
+grep-binary = 二进制文件匹配
+grep-hidden = （最终文件系统中不可见）
+grep-summary = { $files } 个文件中共 { $count } 处匹配
+grep-skipped = { $count } 个文件超过 --max-size，未搜索（最大 { $size }）
+
+## peel copy
+
+spinner-copying-image = 正在复制 { $image } ...
+spinner-copying-layer = 正在复制第 { $current }/{ $total } 层 ...
+copy-copied-image = 已复制 { $image }（{ $layers } 层）
+copy-summary = 已推送 { $pushed } 个 blob（{ $size }），挂载 { $mounted } 个，{ $skipped } 个已存在
+copy-dropped = 已移除 { $layers } 层和 { $entries } 个路径
+copy-bad-level = 无效的 gzip 级别 { $level }（应为 0-9）
+copy-bad-drop = --drop-layer { $prefix } 不含可匹配层摘要的十六进制数字
+copy-drop-ambiguous = --drop-layer { $prefix } 匹配了 { $count } 个层；请给出更长的摘要
+copy-drop-no-match = --drop-layer { $prefix } 未匹配镜像的任何层
+registry-blob-failed = 无法获取 blob { $url }（HTTP { $status }）
+registry-push-failed = 上传到 { $url } 失败（HTTP { $status }）
+repack-unsupported-media-type = 无法重写 { $media_type } 类型的层（仅支持 tar 和 tar+gzip）
+repack-read-failed = 无法读取层归档
+repack-write-failed = 无法写入 { $path }
+
+## --debug-http
+
+http-trace = { $method } { $url } → { $status }（{ $ms } 毫秒，认证：{ $auth }）
+http-dump-failed = 无法将 HTTP 调试信息写入 { $path }
+
+## peel render
+
+render-read-failed = 无法读取 { $path }
+render-not-a-report = { $path } 不是 peel inspect、diff 或 compare 生成的报告
+render-no-html = compare 报告不支持 HTML 输出
+
+## peel update
+
+update-missing = 找不到 `{ $updater }`。请通过 shell 安装脚本重新安装 peel 以获取更新程序：\n\n  curl --proto '=https' --tlsv1.2 -LsSf https://github.com/fudanglp/peel/releases/latest/download/peel-installer.sh | sh
+update-failed = 更新失败（退出码：{ $code }）
+
+## --timings
+
+timings-title = 耗时
+timings-total = 总计
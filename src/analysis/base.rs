@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::ImageInfo;
+
+/// How much of an image is inherited from its base and how much was added on top.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaseSplit {
+    /// Base image reference as provided by the user
+    pub name: String,
+
+    /// Number of leading layers shared with the base
+    pub layers: usize,
+
+    /// Bytes in the inherited layers
+    pub inherited_size: u64,
+
+    /// Bytes in the layers built on top of the base
+    pub added_size: u64,
+}
+
+/// Match the base image's layers against the leading layers of `image` by digest.
+///
+/// Returns `None` if `image` does not start with any of the base's layers.
+pub fn split(image: &ImageInfo, base: &ImageInfo) -> Option<BaseSplit> {
+    let layers = image
+        .layers
+        .iter()
+        .zip(&base.layers)
+        .take_while(|(l, b)| l.digest == b.digest)
+        .count();
+    if layers == 0 {
+        return None;
+    }
+
+    let inherited_size: u64 = image.layers[..layers].iter().map(|l| l.size).sum();
+    Some(BaseSplit {
+        name: base.name.clone(),
+        layers,
+        inherited_size,
+        added_size: image.total_size.saturating_sub(inherited_size),
+    })
+}
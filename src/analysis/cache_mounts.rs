@@ -0,0 +1,95 @@
+use std::cmp::Reverse;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::normalize;
+use crate::inspector::ImageInfo;
+
+/// Build tool caches that BuildKit can keep out of the image with
+/// `RUN --mount=type=cache`: (directory, extra mount options).
+const CACHE_DIRS: &[(&str, &str)] = &[
+    ("var/cache/apt", ",sharing=locked"),
+    ("var/lib/apt/lists", ",sharing=locked"),
+    ("var/cache/apk", ""),
+    ("var/cache/dnf", ""),
+    ("var/cache/yum", ""),
+    ("root/.cache/go-build", ""),
+    ("root/go/pkg/mod", ""),
+    ("go/pkg/mod", ""),
+    ("root/.cache/pip", ""),
+    ("root/.cache/uv", ""),
+    ("root/.cache/pypoetry", ""),
+    ("root/.npm", ""),
+    ("root/.cache/yarn", ""),
+    ("usr/local/share/.cache/yarn", ""),
+    ("root/.local/share/pnpm/store", ""),
+    ("root/.m2", ""),
+    ("root/.gradle/caches", ""),
+    ("root/.cargo/registry", ""),
+    ("root/.cargo/git", ""),
+    ("usr/local/cargo/registry", ""),
+    ("usr/local/cargo/git", ""),
+    ("root/.composer/cache", ""),
+    ("root/.cache/ccache", ""),
+];
+
+/// A build cache directory whose contents were committed into the image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheLeak {
+    /// Cache directory, without a leading `/`
+    pub path: String,
+
+    /// Bytes under `path` across all layers; what a cache mount would save
+    pub size: u64,
+
+    /// Layers (by index) that wrote into the directory
+    pub layers: Vec<usize>,
+
+    /// `--mount` flag to add to the `RUN` instructions of those layers
+    pub mount: String,
+}
+
+/// Cache directories with content in any layer whose command didn't already
+/// mount a cache there, largest first. Without a package manager in the image
+/// (`packages` false) the OS package caches under `/var` can only have been
+/// copied in, so no cache mount is suggested for them.
+pub fn detect(info: &ImageInfo, packages: bool) -> Vec<CacheLeak> {
+    let mut leaks: Vec<CacheLeak> = CACHE_DIRS
+        .iter()
+        .filter(|(dir, _)| packages || !dir.starts_with("var/"))
+        .filter_map(|(dir, options)| {
+            let mut size = 0;
+            let mut layers = Vec::new();
+            for (index, layer) in info.layers.iter().enumerate() {
+                // Already mounted there; whatever is left came from elsewhere
+                let target = format!("target=/{dir}");
+                if layer
+                    .created_by
+                    .as_deref()
+                    .is_some_and(|c| c.contains(&target))
+                {
+                    continue;
+                }
+                let bytes: u64 = layer
+                    .files
+                    .iter()
+                    .filter(|f| !f.is_whiteout && normalize(&f.path).starts_with(Path::new(dir)))
+                    .map(|f| f.size)
+                    .sum();
+                if bytes > 0 {
+                    size += bytes;
+                    layers.push(index);
+                }
+            }
+            (size > 0).then(|| CacheLeak {
+                path: dir.to_string(),
+                size,
+                layers,
+                mount: format!("--mount=type=cache,target=/{dir}{options}"),
+            })
+        })
+        .collect();
+    leaks.sort_by_key(|l| Reverse(l.size));
+    leaks
+}
@@ -0,0 +1,87 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::ImageInfo;
+
+/// Side-by-side comparison of any number of images, in the order given.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Comparison {
+    pub images: Vec<ImageSummary>,
+
+    /// Every distinct layer across all images, in first-seen order
+    pub layers: Vec<LayerReuse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageSummary {
+    pub name: String,
+    pub tag: Option<String>,
+    pub total_size: u64,
+    pub layer_count: usize,
+
+    /// Layers that also appear in at least one other image
+    pub shared_layers: usize,
+
+    /// Bytes in layers no other image has
+    pub unique_size: u64,
+
+    /// Total size change relative to the previous image in the list
+    pub size_delta: Option<i64>,
+}
+
+/// A single layer and the images (by index into `images`) that contain it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LayerReuse {
+    pub digest: String,
+    pub created_by: Option<String>,
+    pub size: u64,
+    pub images: Vec<usize>,
+}
+
+/// Compare images by layer digest. Only metadata is needed, not file listings.
+pub fn compare(infos: &[ImageInfo]) -> Comparison {
+    let mut layers: Vec<LayerReuse> = Vec::new();
+    let mut index_by_digest: HashMap<&str, usize> = HashMap::new();
+
+    for (image_idx, info) in infos.iter().enumerate() {
+        for layer in &info.layers {
+            let idx = *index_by_digest.entry(&layer.digest).or_insert_with(|| {
+                layers.push(LayerReuse {
+                    digest: layer.digest.clone(),
+                    created_by: layer.created_by.clone(),
+                    size: layer.size,
+                    images: Vec::new(),
+                });
+                layers.len() - 1
+            });
+            if !layers[idx].images.contains(&image_idx) {
+                layers[idx].images.push(image_idx);
+            }
+        }
+    }
+
+    let images = infos
+        .iter()
+        .enumerate()
+        .map(|(image_idx, info)| {
+            let (shared, unique): (Vec<_>, Vec<_>) = info
+                .layers
+                .iter()
+                .partition(|l| layers[index_by_digest[l.digest.as_str()]].images.len() > 1);
+            ImageSummary {
+                name: info.name.clone(),
+                tag: info.tag.clone(),
+                total_size: info.total_size,
+                layer_count: info.layers.len(),
+                shared_layers: shared.len(),
+                unique_size: unique.iter().map(|l| l.size).sum(),
+                size_delta: image_idx
+                    .checked_sub(1)
+                    .map(|prev| info.total_size as i64 - infos[prev].total_size as i64),
+            }
+        })
+        .collect();
+
+    Comparison { images, layers }
+}
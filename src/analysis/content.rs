@@ -0,0 +1,206 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::normalize;
+use crate::inspector::ImageInfo;
+
+/// Coarse bucket for what a file is, guessed from its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Category {
+    /// Distribution packages: binaries, libraries and config under the usual system paths
+    Os,
+    /// Language runtimes and third-party packages (site-packages, node_modules, JDKs)
+    Runtime,
+    /// Application code and scripts
+    Code,
+    /// Images, fonts, stylesheets and media
+    Assets,
+    /// Models, databases and datasets
+    Data,
+    /// Package manager caches, bytecode caches and temp files
+    Cache,
+    Other,
+}
+
+impl Category {
+    /// Every category, in display order.
+    pub const ALL: [Category; 7] = [
+        Category::Os,
+        Category::Runtime,
+        Category::Code,
+        Category::Assets,
+        Category::Data,
+        Category::Cache,
+        Category::Other,
+    ];
+
+    /// Classify a file by path. Checks run from most to least specific, so a
+    /// cache under `/usr` is a cache and a PNG under `/usr/share` is OS.
+    pub fn of(path: &Path) -> Self {
+        let path = normalize(path);
+        let path = path.to_string_lossy();
+        let ext = path
+            .rsplit_once('.')
+            .filter(|(_, e)| !e.contains('/'))
+            .map(|(_, e)| e.to_ascii_lowercase())
+            .unwrap_or_default();
+        let has_dir = |dir: &str| path.starts_with(dir) || path.contains(&format!("/{dir}"));
+
+        if CACHE_DIRS.iter().any(|d| has_dir(d)) || CACHE_EXTENSIONS.contains(&ext.as_str()) {
+            Category::Cache
+        } else if RUNTIME_DIRS.iter().any(|d| has_dir(d)) {
+            Category::Runtime
+        } else if is_os_path(&path) {
+            Category::Os
+        } else if DATA_EXTENSIONS.contains(&ext.as_str()) {
+            Category::Data
+        } else if ASSET_EXTENSIONS.contains(&ext.as_str()) {
+            Category::Assets
+        } else if CODE_EXTENSIONS.contains(&ext.as_str())
+            || CODE_DIRS.iter().any(|d| path.starts_with(d))
+        {
+            Category::Code
+        } else {
+            Category::Other
+        }
+    }
+}
+
+/// Directories (matched at any depth) holding caches and scratch files.
+const CACHE_DIRS: &[&str] = &[
+    "tmp/",
+    "var/tmp/",
+    "var/cache/",
+    "var/lib/apt/lists/",
+    ".cache/",
+    ".npm/",
+    ".yarn/cache/",
+    ".m2/repository/",
+    ".gradle/caches/",
+    ".cargo/registry/",
+    "go/pkg/mod/cache/",
+    "__pycache__/",
+];
+const CACHE_EXTENSIONS: &[&str] = &["pyc", "pyo"];
+
+/// Directories (matched at any depth) holding interpreters, VMs and packages.
+const RUNTIME_DIRS: &[&str] = &[
+    "site-packages/",
+    "dist-packages/",
+    "node_modules/",
+    "usr/lib/jvm/",
+    "opt/java/",
+    "usr/local/go/",
+    "go/pkg/mod/",
+    "usr/local/lib/python",
+    "usr/lib/python",
+    "usr/local/lib/ruby/",
+    "usr/lib/ruby/",
+    "usr/local/bundle/",
+    ".rustup/",
+    ".cargo/",
+    "usr/local/bin/node",
+    "usr/local/bin/python",
+];
+
+/// Top-level directories that belong to the distribution.
+fn is_os_path(path: &str) -> bool {
+    const OS_DIRS: &[&str] = &[
+        "bin/",
+        "sbin/",
+        "lib/",
+        "lib32/",
+        "lib64/",
+        "libx32/",
+        "boot/",
+        "etc/",
+        "var/lib/dpkg/",
+        "var/lib/rpm/",
+        "var/lib/apk/",
+        "var/log/",
+    ];
+    if path.starts_with("usr/") {
+        // /usr/local and /usr/src are where images put their own software
+        !path.starts_with("usr/local/") && !path.starts_with("usr/src/")
+    } else {
+        OS_DIRS.iter().any(|d| path.starts_with(d))
+    }
+}
+
+const DATA_EXTENSIONS: &[&str] = &[
+    "bin",
+    "pt",
+    "pth",
+    "onnx",
+    "safetensors",
+    "gguf",
+    "ckpt",
+    "h5",
+    "pb",
+    "tflite",
+    "pkl",
+    "npy",
+    "npz",
+    "parquet",
+    "arrow",
+    "csv",
+    "tsv",
+    "db",
+    "sqlite",
+    "sqlite3",
+    "mdb",
+];
+
+const ASSET_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "avif", "css", "scss", "woff", "woff2",
+    "ttf", "otf", "eot", "mp3", "mp4", "wav", "ogg", "webm", "html", "htm", "map",
+];
+
+const CODE_EXTENSIONS: &[&str] = &[
+    "py", "js", "mjs", "cjs", "ts", "tsx", "jsx", "go", "rb", "php", "java", "jar", "war", "class",
+    "kt", "scala", "rs", "c", "h", "cc", "cpp", "hpp", "cs", "dll", "sh", "lua", "pl", "ex", "exs",
+    "beam",
+];
+
+/// Where images conventionally put their own application.
+const CODE_DIRS: &[&str] = &[
+    "app/",
+    "srv/",
+    "workspace/",
+    "usr/src/",
+    "home/",
+    "opt/app/",
+];
+
+/// Bytes per category, per layer and in total.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentBreakdown {
+    /// One map per layer (base = 0); categories with no files are left out
+    pub layers: Vec<BTreeMap<Category, u64>>,
+
+    /// Sum over all layers
+    pub totals: BTreeMap<Category, u64>,
+}
+
+/// Classify every file in every layer. Whiteouts are skipped; bytes that a
+/// later layer hides still count towards the layer that shipped them.
+pub fn analyze(info: &ImageInfo) -> ContentBreakdown {
+    let mut totals = BTreeMap::new();
+    let layers = info
+        .layers
+        .iter()
+        .map(|layer| {
+            let mut sizes = BTreeMap::new();
+            for file in layer.files.iter().filter(|f| !f.is_whiteout) {
+                let category = Category::of(&file.path);
+                *sizes.entry(category).or_insert(0) += file.size;
+                *totals.entry(category).or_insert(0) += file.size;
+            }
+            sizes
+        })
+        .collect();
+    ContentBreakdown { layers, totals }
+}
@@ -0,0 +1,162 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged;
+use crate::inspector::{ImageInfo, LayerInfo};
+
+/// How deep to aggregate directory sizes when comparing two filesystems.
+const DIR_DEPTH: usize = 3;
+
+/// Comparison of two images: `left` is the baseline, `right` the new image.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageDiff {
+    pub left: DiffSide,
+    pub right: DiffSide,
+
+    /// Number of leading layers both images have in common
+    pub shared_layers: usize,
+
+    /// Total size of the shared layers, in bytes
+    pub shared_size: u64,
+
+    /// Layer-by-layer comparison, shared layers first
+    pub layers: Vec<LayerDiff>,
+
+    /// Directories whose size differs in the merged filesystem, largest change first
+    pub dirs: Vec<DirDelta>,
+}
+
+/// Summary of one side of a diff.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DiffSide {
+    pub name: String,
+    pub tag: Option<String>,
+    pub architecture: Option<String>,
+    pub total_size: u64,
+    pub layer_count: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerStatus {
+    /// Present in both images
+    Shared,
+    /// Only in the left image
+    Removed,
+    /// Only in the right image
+    Added,
+}
+
+/// A layer from either image, tagged with which side(s) it belongs to.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LayerDiff {
+    pub status: LayerStatus,
+    pub digest: String,
+    pub created_by: Option<String>,
+    pub size: u64,
+    pub file_count: usize,
+}
+
+/// Size change of a single directory between the two merged filesystems.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct DirDelta {
+    pub path: PathBuf,
+    pub left_size: u64,
+    pub right_size: u64,
+    pub delta: i64,
+}
+
+/// Compare two fully inspected images (file listings must be populated).
+pub fn diff(left: &ImageInfo, right: &ImageInfo) -> ImageDiff {
+    let shared_layers = left
+        .layers
+        .iter()
+        .zip(&right.layers)
+        .take_while(|(l, r)| l.digest == r.digest)
+        .count();
+    let shared_size = left.layers[..shared_layers].iter().map(|l| l.size).sum();
+
+    let mut layers: Vec<LayerDiff> = Vec::new();
+    layers.extend(
+        left.layers[..shared_layers]
+            .iter()
+            .map(|l| layer_diff(l, LayerStatus::Shared)),
+    );
+    layers.extend(
+        left.layers[shared_layers..]
+            .iter()
+            .map(|l| layer_diff(l, LayerStatus::Removed)),
+    );
+    layers.extend(
+        right.layers[shared_layers..]
+            .iter()
+            .map(|l| layer_diff(l, LayerStatus::Added)),
+    );
+
+    let left_dirs = dir_sizes(left);
+    let right_dirs = dir_sizes(right);
+    let mut dirs: Vec<DirDelta> = left_dirs
+        .keys()
+        .chain(right_dirs.keys())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .map(|path| {
+            let left_size = left_dirs.get(path).copied().unwrap_or(0);
+            let right_size = right_dirs.get(path).copied().unwrap_or(0);
+            DirDelta {
+                path: path.clone(),
+                left_size,
+                right_size,
+                delta: right_size as i64 - left_size as i64,
+            }
+        })
+        .filter(|d| d.delta != 0)
+        .collect();
+    dirs.sort_by_key(|d| std::cmp::Reverse(d.delta.unsigned_abs()));
+
+    ImageDiff {
+        left: side(left),
+        right: side(right),
+        shared_layers,
+        shared_size,
+        layers,
+        dirs,
+    }
+}
+
+fn side(info: &ImageInfo) -> DiffSide {
+    DiffSide {
+        name: info.name.clone(),
+        tag: info.tag.clone(),
+        architecture: info.architecture.clone(),
+        total_size: info.total_size,
+        layer_count: info.layers.len(),
+    }
+}
+
+fn layer_diff(layer: &LayerInfo, status: LayerStatus) -> LayerDiff {
+    LayerDiff {
+        status,
+        digest: layer.digest.clone(),
+        created_by: layer.created_by.clone(),
+        size: layer.size,
+        file_count: layer.files.len(),
+    }
+}
+
+/// Cumulative size of every directory up to `DIR_DEPTH` in the merged filesystem.
+fn dir_sizes(info: &ImageInfo) -> BTreeMap<PathBuf, u64> {
+    let mut sizes: BTreeMap<PathBuf, u64> = BTreeMap::new();
+    for file in merged::merge(info).values() {
+        for dir in file.path.ancestors().skip(1) {
+            let depth = dir.components().count();
+            if depth == 0 || depth > DIR_DEPTH {
+                continue;
+            }
+            *sizes.entry(dir.to_path_buf()).or_default() += file.size;
+        }
+    }
+    sizes
+}
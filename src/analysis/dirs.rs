@@ -0,0 +1,158 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::merged;
+use crate::inspector::ImageInfo;
+
+/// Cumulative size of one directory.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirSize {
+    /// Normalized path (no leading `/`)
+    pub path: PathBuf,
+
+    /// Bytes of every file below this directory, at any depth
+    pub size: u64,
+
+    /// Files below this directory, at any depth
+    pub files: usize,
+}
+
+/// A directory or file of the tree `peel du --tree` draws.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+    /// Last path component; empty for the top of the tree
+    pub name: String,
+
+    pub is_dir: bool,
+
+    /// Bytes of the file, or of every file below the directory
+    pub size: u64,
+
+    /// Files below the directory at any depth; 1 for a file
+    pub files: usize,
+
+    /// Largest first; empty for files and directories at the depth limit
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<TreeNode>,
+
+    /// Children left out for being under the size limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pruned: Option<Pruned>,
+}
+
+/// Entries of a directory too small to show, summed.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Pruned {
+    pub entries: usize,
+    pub size: u64,
+}
+
+/// Every directory of the final filesystem with its cumulative size, in path
+/// order, so viewers don't have to fold the file listings themselves.
+pub fn analyze(info: &ImageInfo) -> Vec<DirSize> {
+    let files = merged::merge(info);
+    rollup(
+        files.values().map(|f| (f.path.as_path(), f.size)),
+        Path::new(""),
+        usize::MAX,
+    )
+    .into_values()
+    .collect()
+}
+
+/// Add each file's size to every directory between `root` and the file, down
+/// to `max_depth` levels below `root`. Files outside `root` are ignored.
+pub fn rollup<'a>(
+    files: impl IntoIterator<Item = (&'a Path, u64)>,
+    root: &Path,
+    max_depth: usize,
+) -> BTreeMap<PathBuf, DirSize> {
+    let mut dirs: BTreeMap<PathBuf, DirSize> = BTreeMap::new();
+    for (file, size) in files {
+        let Ok(rel) = file.strip_prefix(root) else {
+            continue;
+        };
+        let parents = rel.components().count().saturating_sub(1);
+        let mut dir = root.to_path_buf();
+        for component in rel.components().take(parents.min(max_depth)) {
+            dir.push(component);
+            let entry = dirs.entry(dir.clone()).or_insert_with(|| DirSize {
+                path: dir.clone(),
+                size: 0,
+                files: 0,
+            });
+            entry.size += size;
+            entry.files += 1;
+        }
+    }
+    dirs
+}
+
+/// Fold the files below `root` into a tree `max_depth` levels deep, leaving
+/// out entries smaller than `min_size`. Files outside `root` are ignored.
+pub fn tree<'a>(
+    files: impl IntoIterator<Item = (&'a Path, u64)>,
+    root: &Path,
+    max_depth: usize,
+    min_size: u64,
+) -> TreeNode {
+    let mut top = Fold {
+        is_dir: true,
+        ..Fold::default()
+    };
+    for (file, size) in files {
+        let Ok(rel) = file.strip_prefix(root) else {
+            continue;
+        };
+        top.size += size;
+        top.files += 1;
+        let depth = rel.components().count();
+        let mut node = &mut top;
+        for (i, component) in rel.components().take(max_depth).enumerate() {
+            let name = component.as_os_str().to_string_lossy().into_owned();
+            node = node.children.entry(name).or_default();
+            node.is_dir |= i + 1 < depth;
+            node.size += size;
+            node.files += 1;
+        }
+    }
+    top.into_node(String::new(), min_size)
+}
+
+/// A tree node while files are being added, children by name.
+#[derive(Default)]
+struct Fold {
+    is_dir: bool,
+    size: u64,
+    files: usize,
+    children: BTreeMap<String, Fold>,
+}
+
+impl Fold {
+    fn into_node(self, name: String, min_size: u64) -> TreeNode {
+        let mut children = Vec::new();
+        let mut pruned: Option<Pruned> = None;
+        for (name, child) in self.children {
+            if child.size < min_size {
+                let pruned = pruned.get_or_insert_default();
+                pruned.entries += 1;
+                pruned.size += child.size;
+            } else {
+                children.push(child.into_node(name, min_size));
+            }
+        }
+        // Stable, so equal sizes stay in name order
+        children.sort_by_key(|c| Reverse(c.size));
+        TreeNode {
+            name,
+            is_dir: self.is_dir,
+            size: self.size,
+            files: self.files,
+            children,
+            pruned,
+        }
+    }
+}
@@ -0,0 +1,89 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+
+use super::merged;
+pub use crate::inspector::Distro;
+use crate::inspector::{ImageInfo, Inspector};
+
+/// Files that name the distribution, most informative first. Old images
+/// predate `os-release`; their release files only give a version.
+const RELEASE_FILES: &[&str] = &[
+    "etc/os-release",
+    "usr/lib/os-release",
+    "etc/alpine-release",
+    "etc/debian_version",
+    "etc/redhat-release",
+];
+
+/// Whether `path` (normalized) is a file `identify` reads.
+pub fn wanted(path: &Path) -> bool {
+    RELEASE_FILES.iter().any(|p| path == Path::new(p))
+}
+
+/// The distribution named by the final filesystem's release files, given
+/// as (normalized path, content). The most informative file present wins.
+pub fn identify(files: &[(PathBuf, Vec<u8>)]) -> Option<Distro> {
+    RELEASE_FILES.iter().find_map(|wanted| {
+        let (path, content) = files.iter().find(|(p, _)| p == Path::new(wanted))?;
+        let text = String::from_utf8_lossy(content);
+        match path.file_name()?.to_str()? {
+            "os-release" => parse_os_release(&text),
+            "alpine-release" => Some(Distro {
+                id: "alpine".to_string(),
+                version_id: Some(text.trim().to_string()),
+                name: None,
+            }),
+            // "12.5", or "bookworm/sid" on testing and on Ubuntu
+            "debian_version" => Some(Distro {
+                id: "debian".to_string(),
+                version_id: text
+                    .trim()
+                    .split('.')
+                    .next()
+                    .filter(|v| v.parse::<u32>().is_ok())
+                    .map(str::to_string),
+                name: None,
+            }),
+            // "CentOS Linux release 7.9.2009 (Core)"
+            _ => {
+                let line = text.lines().next()?.trim();
+                let id = match line.split_whitespace().next()?.to_lowercase().as_str() {
+                    "red" => "rhel".to_string(),
+                    other => other.to_string(),
+                };
+                Some(Distro {
+                    id,
+                    version_id: line
+                        .split_once(" release ")
+                        .and_then(|(_, rest)| rest.split_whitespace().next())
+                        .map(str::to_string),
+                    name: Some(line.to_string()),
+                })
+            }
+        }
+    })
+}
+
+fn parse_os_release(text: &str) -> Option<Distro> {
+    let value = |key: &str| {
+        text.lines()
+            .find_map(|l| l.strip_prefix(key)?.strip_prefix('='))
+            .map(|v| v.trim().trim_matches('"').to_string())
+            .filter(|v| !v.is_empty())
+    };
+    Some(Distro {
+        id: value("ID")?,
+        version_id: value("VERSION_ID"),
+        name: value("PRETTY_NAME").or_else(|| value("NAME")),
+    })
+}
+
+/// Read the release files the final filesystem shows and identify the
+/// distribution. `None` for scratch and distroless images without them.
+/// `etc/os-release` is usually a link to `usr/lib/os-release`, which is
+/// read for itself.
+pub fn read(info: &ImageInfo, inspector: &mut dyn Inspector) -> Result<Option<Distro>> {
+    let paths: Vec<PathBuf> = RELEASE_FILES.iter().map(PathBuf::from).collect();
+    Ok(identify(&merged::read_files(info, inspector, &paths)?))
+}
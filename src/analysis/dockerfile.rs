@@ -0,0 +1,305 @@
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::{ImageInfo, instruction};
+
+/// Instructions that can add a layer. The rest only change the config.
+const LAYER_INSTRUCTIONS: &[&str] = &["RUN", "COPY", "ADD", "WORKDIR"];
+
+/// `<<EOF`, `<<-EOF`, `<<"EOF"` or `<<'EOF'`.
+static HEREDOC: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"<<(-?)(?:"(\w+)"|'(\w+)'|(\w+))"#).expect("heredoc pattern"));
+
+/// Which Dockerfile instruction made each layer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockerfileMap {
+    /// As given to `--dockerfile`
+    pub path: PathBuf,
+
+    /// One per layer, base first; `None` for layers no instruction matched,
+    /// such as the base image's
+    pub layers: Vec<Option<SourceRef>>,
+}
+
+/// Where an instruction is in the Dockerfile.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceRef {
+    /// 1-based
+    pub line: usize,
+
+    /// Last line, after continuations and heredocs
+    pub end_line: usize,
+
+    /// The instruction's first line, as written
+    pub text: String,
+}
+
+/// An instruction, with continuation lines joined and heredoc bodies
+/// appended after a newline.
+#[derive(Debug)]
+struct Instruction {
+    /// Upper case, e.g. "RUN"
+    keyword: String,
+    args: String,
+    source: SourceRef,
+}
+
+#[derive(Debug)]
+struct Stage {
+    /// `AS` name, lower case
+    name: Option<String>,
+    /// Image or earlier stage it starts from, lower case
+    base: String,
+    instructions: Vec<Instruction>,
+}
+
+/// Match each layer's `created_by` to the final stage's instructions.
+///
+/// Layers are matched from the last one back, so base image layers, which
+/// come first, can't take an instruction from the Dockerfile.
+pub fn map(path: PathBuf, text: &str, info: &ImageInfo) -> DockerfileMap {
+    let stages = parse(text);
+    let instructions: Vec<&Instruction> = match stages.len().checked_sub(1) {
+        Some(last) => chain(&stages, last)
+            .into_iter()
+            .filter(|i| LAYER_INSTRUCTIONS.contains(&i.keyword.as_str()))
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let mut layers = vec![None; info.layers.len()];
+    let mut next = instructions.len();
+    for (index, layer) in info.layers.iter().enumerate().rev() {
+        let Some(parsed) = &layer.instruction else {
+            continue;
+        };
+        let step = Step::new(parsed);
+        if let Some(found) = instructions[..next].iter().rposition(|i| step.matches(i)) {
+            layers[index] = Some(instructions[found].source.clone());
+            next = found;
+        }
+    }
+    DockerfileMap { path, layers }
+}
+
+/// Instructions that build stage `index`, starting with those of the earlier
+/// stage it's based on, if any.
+fn chain(stages: &[Stage], index: usize) -> Vec<&Instruction> {
+    let stage = &stages[index];
+    let parent = stages[..index]
+        .iter()
+        .rposition(|s| s.name.as_deref() == Some(stage.base.as_str()));
+    let mut instructions = parent.map_or_else(Vec::new, |p| chain(stages, p));
+    instructions.extend(&stage.instructions);
+    instructions
+}
+
+/// Split a Dockerfile into stages of instructions.
+fn parse(text: &str) -> Vec<Stage> {
+    let lines: Vec<&str> = text.lines().collect();
+    let escape = escape_char(&lines);
+
+    let mut stages: Vec<Stage> = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let first = lines[i].trim();
+        if first.is_empty() || first.starts_with('#') {
+            i += 1;
+            continue;
+        }
+
+        // Continuation lines, skipping comments between them
+        let start = i;
+        let mut logical = String::new();
+        loop {
+            let line = lines[i].trim_end();
+            match line.strip_suffix(escape) {
+                Some(rest) if i + 1 < lines.len() => {
+                    logical.push_str(rest);
+                    logical.push(' ');
+                    i += 1;
+                    // Comments and blank lines inside an instruction are dropped
+                    while i + 1 < lines.len()
+                        && (lines[i].trim().is_empty() || lines[i].trim_start().starts_with('#'))
+                    {
+                        i += 1;
+                    }
+                }
+                _ => {
+                    logical.push_str(line);
+                    break;
+                }
+            }
+        }
+        i += 1;
+
+        let (keyword, args) = match logical.trim().split_once(char::is_whitespace) {
+            Some((keyword, args)) => (keyword.to_ascii_uppercase(), args.trim().to_string()),
+            None => (logical.trim().to_ascii_uppercase(), String::new()),
+        };
+        let mut args = args;
+
+        // Heredoc bodies follow the instruction's last line, in order
+        if matches!(keyword.as_str(), "RUN" | "COPY" | "ADD") {
+            let markers: Vec<(bool, String)> = HEREDOC
+                .captures_iter(&args)
+                .filter_map(|m| {
+                    let delimiter = m.get(2).or(m.get(3)).or(m.get(4))?;
+                    Some((!m[1].is_empty(), delimiter.as_str().to_string()))
+                })
+                .collect();
+            for (strip_tabs, delimiter) in markers {
+                while i < lines.len() {
+                    let line = lines[i];
+                    i += 1;
+                    let body = if strip_tabs {
+                        line.trim_start_matches('\t')
+                    } else {
+                        line
+                    };
+                    if body == delimiter {
+                        break;
+                    }
+                    args.push('\n');
+                    args.push_str(body);
+                }
+            }
+        }
+
+        let source = SourceRef {
+            line: start + 1,
+            end_line: i,
+            text: lines[start].trim().to_string(),
+        };
+        if keyword == "FROM" {
+            let words: Vec<&str> = args
+                .split_whitespace()
+                .filter(|w| !w.starts_with("--"))
+                .collect();
+            let name = match words.as_slice() {
+                [_, as_, name, ..] if as_.eq_ignore_ascii_case("as") => {
+                    Some(name.to_ascii_lowercase())
+                }
+                _ => None,
+            };
+            stages.push(Stage {
+                name,
+                base: words.first().unwrap_or(&"").to_ascii_lowercase(),
+                instructions: Vec::new(),
+            });
+        } else if let Some(stage) = stages.last_mut() {
+            stage.instructions.push(Instruction {
+                keyword,
+                args,
+                source,
+            });
+        }
+    }
+    stages
+}
+
+/// The `# escape=` parser directive, if the file starts with one.
+fn escape_char(lines: &[&str]) -> char {
+    for line in lines {
+        let Some(directive) = line.trim().strip_prefix('#') else {
+            break;
+        };
+        if let Some((key, value)) = directive.split_once('=')
+            && key.trim().eq_ignore_ascii_case("escape")
+            && let Some(c) = value.trim().chars().next()
+        {
+            return c;
+        }
+    }
+    '\\'
+}
+
+/// A layer's instruction, reduced to what a Dockerfile instruction can be
+/// compared with.
+struct Step {
+    keyword: String,
+    /// Command for RUN, destination for COPY and ADD, arguments otherwise
+    subject: String,
+}
+
+impl Step {
+    fn new(layer: &instruction::Instruction) -> Self {
+        let args = &layer.arguments;
+        let subject = match layer.instruction.as_str() {
+            "RUN" => run_command(args),
+            "COPY" | "ADD" => destination(args),
+            _ => squash(args),
+        };
+        Step {
+            keyword: layer.instruction.clone(),
+            subject,
+        }
+    }
+
+    fn matches(&self, instruction: &Instruction) -> bool {
+        if self.keyword != instruction.keyword || self.subject.is_empty() {
+            return false;
+        }
+        match self.keyword.as_str() {
+            "RUN" => {
+                let (line, body) = instruction
+                    .args
+                    .split_once('\n')
+                    .unwrap_or((&instruction.args, ""));
+                let command = run_command(line);
+                let body = squash(body);
+                // Builders differ in how they record heredocs, so the body
+                // or the instruction line may be all that's left
+                self.subject == command
+                    || (!body.is_empty()
+                        && (self.subject.contains(&body) || body.contains(&self.subject)))
+                    || (HEREDOC.is_match(line) && self.subject.contains(&command))
+            }
+            "COPY" | "ADD" => {
+                let line = instruction.args.lines().next().unwrap_or("");
+                self.subject == destination(line)
+            }
+            _ => self.subject == squash(&instruction.args),
+        }
+    }
+}
+
+/// A RUN's command without BuildKit's build-arg prefix (`|2 A=1 B=2`),
+/// flags like `--mount=…`, or the shell.
+fn run_command(args: &str) -> String {
+    let mut words: Vec<&str> = args.split_whitespace().collect();
+    if let Some(count) = words
+        .first()
+        .and_then(|w| w.strip_prefix('|'))
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        words.drain(..(count + 1).min(words.len()));
+    }
+    let flags = words.iter().take_while(|w| w.starts_with("--")).count();
+    words.drain(..flags);
+    let command = words.join(" ");
+    let command = command.strip_prefix("/bin/sh -c ").unwrap_or(&command);
+    squash(command)
+}
+
+/// Where a COPY or ADD puts its files: the last argument, in shell or JSON
+/// form, without a trailing `/`.
+fn destination(args: &str) -> String {
+    let args = args.trim();
+    let last = match serde_json::from_str::<Vec<String>>(args) {
+        Ok(list) => list.last().cloned().unwrap_or_default(),
+        Err(_) => args.split_whitespace().last().unwrap_or("").to_string(),
+    };
+    match last.trim_end_matches('/') {
+        "" if !last.is_empty() => "/".to_string(),
+        dest => dest.to_string(),
+    }
+}
+
+/// Collapse runs of whitespace, as continuation lines leave them.
+fn squash(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
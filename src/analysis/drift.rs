@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::ImageInfo;
+
+/// How the local copy of an image relates to what its tag points at in the
+/// registry.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DriftStatus {
+    /// Same config, or pulled by the digest the tag points at now
+    Current,
+    /// Pulled from a registry, but the tag has moved on since
+    Stale,
+    /// Never pulled by any digest: built or changed locally
+    Diverged,
+}
+
+/// What a tag resolves to in the registry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteImage {
+    /// Normalized reference, e.g. "docker.io/library/nginx:latest"
+    pub reference: String,
+
+    /// Platform picked from a multi-platform index, e.g. "linux/amd64"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+
+    /// `sha256:<hex>` of what the tag points at: the index for a
+    /// multi-platform image, else the manifest
+    pub digest: String,
+
+    /// The platform's manifest; the same as `digest` without an index
+    pub manifest_digest: String,
+
+    /// Config digest, which is the image ID
+    pub id: String,
+}
+
+/// The local image's digests next to the registry's.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DriftReport {
+    pub image: String,
+
+    /// Config digest of the local image, when the backend knows it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_id: Option<String>,
+
+    /// Registry digests the local image was pulled by
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub local_digests: Vec<String>,
+
+    pub remote: RemoteImage,
+
+    pub status: DriftStatus,
+}
+
+/// Compare by config first, since a rebuilt manifest can wrap the same one,
+/// then by the digests the runtime recorded on pull.
+pub fn compare(local: &ImageInfo, remote: RemoteImage) -> DriftReport {
+    let same_config = local.id.as_ref() == Some(&remote.id);
+    let pulled_current = local
+        .manifest_digests
+        .iter()
+        .any(|d| *d == remote.digest || *d == remote.manifest_digest);
+    let status = if same_config || pulled_current {
+        DriftStatus::Current
+    } else if local.manifest_digests.is_empty() {
+        DriftStatus::Diverged
+    } else {
+        DriftStatus::Stale
+    };
+    DriftReport {
+        image: local.name.clone(),
+        local_id: local.id.clone(),
+        local_digests: local.manifest_digests.clone(),
+        remote,
+        status,
+    }
+}
@@ -0,0 +1,90 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::normalize;
+use crate::inspector::ImageInfo;
+
+/// Identical content stored more than once, across layers or paths.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    /// Bytes stored beyond the first copy of each file
+    pub wasted_size: u64,
+
+    /// Largest waste first
+    pub groups: Vec<DuplicateGroup>,
+}
+
+/// Copies of one file content.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    /// `sha256:<hex>` of the content
+    pub sha256: String,
+
+    /// Size of one copy
+    pub size: u64,
+
+    /// Every copy, in layer order
+    pub copies: Vec<DuplicateCopy>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateCopy {
+    /// Index into the image's layers (base = 0)
+    pub layer: usize,
+
+    /// Normalized path (no leading `/`)
+    pub path: PathBuf,
+}
+
+impl DuplicateGroup {
+    /// Bytes all copies but one take up.
+    pub fn wasted(&self) -> u64 {
+        self.size * (self.copies.len() as u64 - 1)
+    }
+}
+
+/// Group non-empty regular files by content hash. Needs the hashes from
+/// `--checksums`; `None` without them.
+pub fn detect(info: &ImageInfo) -> Option<DuplicateReport> {
+    let mut by_hash: BTreeMap<&str, DuplicateGroup> = BTreeMap::new();
+    let mut hashed = false;
+    for (index, layer) in info.layers.iter().enumerate() {
+        for file in layer.files.iter().filter(|f| !f.is_whiteout) {
+            let Some(sha256) = file.sha256.as_deref() else {
+                continue;
+            };
+            hashed = true;
+            if file.size == 0 {
+                continue;
+            }
+            by_hash
+                .entry(sha256)
+                .or_insert_with(|| DuplicateGroup {
+                    sha256: sha256.to_string(),
+                    size: file.size,
+                    copies: Vec::new(),
+                })
+                .copies
+                .push(DuplicateCopy {
+                    layer: index,
+                    path: normalize(&file.path),
+                });
+        }
+    }
+    if !hashed {
+        return None;
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_hash
+        .into_values()
+        .filter(|g| g.copies.len() > 1)
+        .collect();
+    groups.sort_by_key(|g| Reverse(g.wasted()));
+    Some(DuplicateReport {
+        wasted_size: groups.iter().map(DuplicateGroup::wasted).sum(),
+        groups,
+    })
+}
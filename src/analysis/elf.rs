@@ -0,0 +1,407 @@
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsString;
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::{MergedFile, normalize};
+
+const ELF_MAGIC: &[u8] = b"\x7fELF";
+
+/// Symbolic links followed while resolving one path, as in the kernel.
+const MAX_LINKS: usize = 40;
+
+/// Where the dynamic loader looks when nothing else says otherwise: glibc's
+/// trusted directories and musl's default path. Debian's multiarch
+/// directories come from `ld.so.conf.d`.
+const DEFAULT_DIRS: &[&str] = &["lib", "usr/lib", "lib64", "usr/lib64", "usr/local/lib"];
+
+/// Copies of a missing library found off the search path, reported as a hint.
+const MAX_ELSEWHERE: usize = 3;
+
+// e_type
+const ET_EXEC: u16 = 2;
+const ET_DYN: u16 = 3;
+
+// p_type
+const PT_LOAD: u32 = 1;
+const PT_DYNAMIC: u32 = 2;
+const PT_INTERP: u32 = 3;
+
+// d_tag
+const DT_NULL: u64 = 0;
+const DT_NEEDED: u64 = 1;
+const DT_STRTAB: u64 = 5;
+const DT_RPATH: u64 = 15;
+const DT_RUNPATH: u64 = 29;
+
+/// The parts of an executable or shared object the dynamic loader reads.
+#[derive(Debug, Clone, Default)]
+pub struct Dynamic {
+    /// `PT_INTERP`: the dynamic loader, e.g. `/lib64/ld-linux-x86-64.so.2`
+    pub interpreter: Option<String>,
+
+    /// `DT_NEEDED` entries, in load order
+    pub needed: Vec<String>,
+
+    /// `DT_RPATH` directories, searched before `LD_LIBRARY_PATH`
+    pub rpath: Vec<String>,
+
+    /// `DT_RUNPATH` directories, searched after it
+    pub runpath: Vec<String>,
+
+    pub is_64: bool,
+}
+
+/// A dynamically linked ELF file in the final filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ElfObject {
+    pub path: PathBuf,
+
+    /// Layer that ships it (base = 0)
+    pub layer: usize,
+
+    pub interpreter: Option<Dependency>,
+    pub needed: Vec<Dependency>,
+}
+
+impl ElfObject {
+    /// Whether the loader would fail to start or load this file.
+    pub fn is_broken(&self) -> bool {
+        self.interpreter
+            .iter()
+            .chain(&self.needed)
+            .any(|d| d.path.is_none())
+    }
+}
+
+/// A file the loader needs and where it finds it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    /// As the ELF file names it, e.g. `libssl.so.3`
+    pub name: String,
+
+    /// Where it resolves to in the final filesystem, symlinks followed;
+    /// `None` if it's missing
+    pub path: Option<PathBuf>,
+
+    /// Files of that name outside the search path, when it's missing: a
+    /// directory the loader wasn't told about, e.g. with `LD_LIBRARY_PATH`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub elsewhere: Vec<PathBuf>,
+}
+
+/// Read an ELF file's program headers and dynamic section. `None` for
+/// anything but an executable or shared object.
+pub fn parse(data: &[u8]) -> Option<Dynamic> {
+    if !data.starts_with(ELF_MAGIC) {
+        return None;
+    }
+    let r = Reader {
+        data,
+        is_64: *data.get(4)? == 2,
+        big_endian: *data.get(5)? == 2,
+    };
+    if !matches!(r.u16(16)?, ET_EXEC | ET_DYN) {
+        return None;
+    }
+    let (phoff, phentsize, phnum) = if r.is_64 {
+        (r.u64(32)?, r.u16(54)?, r.u16(56)?)
+    } else {
+        (u64::from(r.u32(28)?), r.u16(42)?, r.u16(44)?)
+    };
+
+    let mut loads = Vec::new();
+    let mut dynamic = None;
+    let mut dynamic_info = Dynamic {
+        is_64: r.is_64,
+        ..Default::default()
+    };
+    // Offsets come from the file, so arithmetic on them is checked and a
+    // file they'd overflow is skipped. An `at` inside `data` can't overflow
+    // when a field's offset is added.
+    for i in 0..u64::from(phnum) {
+        let at = r.offset(phoff.checked_add(i.checked_mul(u64::from(phentsize))?)?)?;
+        let (kind, offset, vaddr, filesz) = if r.is_64 {
+            (r.u32(at)?, r.u64(at + 8)?, r.u64(at + 16)?, r.u64(at + 32)?)
+        } else {
+            (
+                r.u32(at)?,
+                u64::from(r.u32(at + 4)?),
+                u64::from(r.u32(at + 8)?),
+                u64::from(r.u32(at + 16)?),
+            )
+        };
+        match kind {
+            PT_LOAD => loads.push((vaddr, offset, filesz)),
+            PT_DYNAMIC => dynamic = Some((offset, filesz)),
+            PT_INTERP => dynamic_info.interpreter = r.str(offset).map(str::to_string),
+            _ => {}
+        }
+    }
+    let Some((offset, size)) = dynamic else {
+        return Some(dynamic_info);
+    };
+
+    // String offsets are relative to DT_STRTAB, an address to map back to
+    // the file through the loadable segments
+    let entry_size = if r.is_64 { 16 } else { 8 };
+    let mut entries = Vec::new();
+    let mut strtab = None;
+    for i in 0..size / entry_size {
+        let at = r.offset(offset.checked_add(i.checked_mul(entry_size)?)?)?;
+        let (tag, value) = if r.is_64 {
+            (r.u64(at)?, r.u64(at + 8)?)
+        } else {
+            (u64::from(r.u32(at)?), u64::from(r.u32(at + 4)?))
+        };
+        match tag {
+            DT_NULL => break,
+            DT_STRTAB => strtab = Some(value),
+            _ => entries.push((tag, value)),
+        }
+    }
+    let strtab = strtab.and_then(|addr| {
+        loads
+            .iter()
+            .find(|(vaddr, _, filesz)| {
+                vaddr
+                    .checked_add(*filesz)
+                    .is_some_and(|end| (*vaddr..end).contains(&addr))
+            })
+            .and_then(|(vaddr, offset, _)| (addr - vaddr).checked_add(*offset))
+    })?;
+
+    for (tag, value) in entries {
+        let Some(text) = r.str(strtab.checked_add(value)?) else {
+            continue;
+        };
+        let paths = || {
+            text.split(':')
+                .filter(|p| !p.is_empty())
+                .map(str::to_string)
+        };
+        match tag {
+            DT_NEEDED => dynamic_info.needed.push(text.to_string()),
+            DT_RPATH => dynamic_info.rpath.extend(paths()),
+            DT_RUNPATH => dynamic_info.runpath.extend(paths()),
+            _ => {}
+        }
+    }
+    Some(dynamic_info)
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    is_64: bool,
+    big_endian: bool,
+}
+
+impl Reader<'_> {
+    /// `offset` as an index into `data`, if it is one.
+    fn offset(&self, offset: u64) -> Option<usize> {
+        usize::try_from(offset).ok().filter(|&at| at < self.data.len())
+    }
+
+    fn bytes<const N: usize>(&self, at: usize) -> Option<[u8; N]> {
+        self.data.get(at..at.checked_add(N)?)?.try_into().ok()
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        let b = self.bytes(at)?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        let b = self.bytes(at)?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    }
+
+    fn u64(&self, at: usize) -> Option<u64> {
+        let b = self.bytes(at)?;
+        Some(if self.big_endian {
+            u64::from_be_bytes(b)
+        } else {
+            u64::from_le_bytes(b)
+        })
+    }
+
+    /// NUL-terminated string at file offset `at`.
+    fn str(&self, at: u64) -> Option<&str> {
+        let rest = self.data.get(usize::try_from(at).ok()?..)?;
+        let end = rest.iter().position(|&b| b == 0)?;
+        std::str::from_utf8(&rest[..end]).ok()
+    }
+}
+
+/// The final filesystem, with symbolic links, to resolve paths the way the
+/// loader would see them.
+pub struct Rootfs<'a> {
+    files: &'a BTreeMap<PathBuf, MergedFile>,
+
+    /// Normalized link path to its target as stored
+    links: HashMap<PathBuf, PathBuf>,
+
+    /// File name to every path with that name, for `Dependency::elsewhere`
+    names: HashMap<OsString, Vec<&'a Path>>,
+}
+
+impl<'a> Rootfs<'a> {
+    /// `links` holds the symbolic links the final filesystem shows.
+    pub fn new(files: &'a BTreeMap<PathBuf, MergedFile>, links: HashMap<PathBuf, PathBuf>) -> Self {
+        let mut names: HashMap<OsString, Vec<&Path>> = HashMap::new();
+        for path in files.keys() {
+            if let Some(name) = path.file_name() {
+                names
+                    .entry(name.to_os_string())
+                    .or_default()
+                    .push(path.as_path());
+            }
+        }
+        Self {
+            files,
+            links,
+            names,
+        }
+    }
+
+    /// The file `path` (absolute or relative to the root) leads to, after
+    /// following symbolic links in any component.
+    pub fn resolve(&self, path: &Path) -> Option<PathBuf> {
+        let mut pending: Vec<OsString> = Vec::new();
+        push_components(&mut pending, path);
+        let mut current = PathBuf::new();
+        let mut hops = 0;
+        while let Some(part) = pending.pop() {
+            if part == ".." {
+                current.pop();
+                continue;
+            }
+            current.push(&part);
+            if let Some(target) = self.links.get(&current) {
+                hops += 1;
+                if hops > MAX_LINKS {
+                    return None;
+                }
+                current.pop();
+                if target.has_root() {
+                    current = PathBuf::new();
+                }
+                push_components(&mut pending, target);
+            }
+        }
+        self.files.contains_key(&current).then_some(current)
+    }
+
+    /// Resolve the interpreter and every needed library of the file at
+    /// `path`. `ld_library_path` comes from the image's environment and
+    /// `conf_dirs` from `ld.so.conf` or musl's path file.
+    ///
+    /// Each file is checked on its own: the search path of the executable
+    /// that loads a library isn't applied to the library's own dependencies.
+    pub fn check(
+        &self,
+        path: &Path,
+        layer: usize,
+        dynamic: &Dynamic,
+        ld_library_path: &[String],
+        conf_dirs: &[String],
+    ) -> ElfObject {
+        let origin = Path::new("/").join(path.parent().unwrap_or(Path::new("")));
+        let expand = |dir: &String| {
+            let dir = dir
+                .replace("${ORIGIN}", "$ORIGIN")
+                .replace("$ORIGIN", &origin.to_string_lossy())
+                .replace("${LIB}", "$LIB")
+                .replace("$LIB", if dynamic.is_64 { "lib64" } else { "lib" });
+            normalize(Path::new(&dir))
+        };
+        // DT_RPATH is ignored when DT_RUNPATH is present
+        let rpath = if dynamic.runpath.is_empty() {
+            &dynamic.rpath[..]
+        } else {
+            &[]
+        };
+        let dirs: Vec<PathBuf> = rpath
+            .iter()
+            .chain(ld_library_path)
+            .chain(&dynamic.runpath)
+            .chain(conf_dirs)
+            .map(expand)
+            .chain(DEFAULT_DIRS.iter().map(PathBuf::from))
+            .collect();
+
+        let interpreter = dynamic.interpreter.as_ref().map(|name| Dependency {
+            name: name.clone(),
+            path: self.resolve(Path::new(name)),
+            elsewhere: Vec::new(),
+        });
+        let needed = dynamic
+            .needed
+            .iter()
+            .map(|name| {
+                let found = if name.contains('/') {
+                    self.resolve(Path::new(name))
+                } else {
+                    dirs.iter().find_map(|d| self.resolve(&d.join(name)))
+                };
+                let elsewhere = if found.is_none() {
+                    self.names
+                        .get(Path::new(name).as_os_str())
+                        .into_iter()
+                        .flatten()
+                        .take(MAX_ELSEWHERE)
+                        .map(|p| p.to_path_buf())
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+                Dependency {
+                    name: name.clone(),
+                    path: found,
+                    elsewhere,
+                }
+            })
+            .collect();
+
+        ElfObject {
+            path: path.to_path_buf(),
+            layer,
+            interpreter,
+            needed,
+        }
+    }
+}
+
+/// Push `path`'s components onto `pending` so the first pops first.
+fn push_components(pending: &mut Vec<OsString>, path: &Path) {
+    let parts: Vec<OsString> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => Some(part.to_os_string()),
+            Component::ParentDir => Some(OsString::from("..")),
+            _ => None,
+        })
+        .collect();
+    pending.extend(parts.into_iter().rev());
+}
+
+/// Library directories listed in `ld.so.conf`-style files (one per line,
+/// `#` comments, `include` lines skipped) or musl's `ld-musl-*.path`
+/// (separated by newlines or colons).
+pub fn conf_dirs(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty() && !l.starts_with("include "))
+        .flat_map(|l| l.split(':'))
+        .map(str::to_string)
+        .collect()
+}
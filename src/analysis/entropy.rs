@@ -0,0 +1,46 @@
+use std::cmp::Reverse;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::ImageInfo;
+
+/// Bits per byte above which a file is treated as packed or encrypted. Plain
+/// text sits around 4–5 and native code around 6; only compressed or
+/// encrypted data gets this close to 8.
+pub const THRESHOLD: f32 = 7.5;
+
+/// A large file whose contents look random but whose format wasn't recognized.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighEntropyFile {
+    pub path: PathBuf,
+    pub size: u64,
+
+    /// Index of the layer that ships the file (base = 0)
+    pub layer: usize,
+
+    /// Sampled entropy in bits per byte
+    pub entropy: f32,
+}
+
+/// Every sampled file at or above `THRESHOLD`, largest first.
+pub fn scan(info: &ImageInfo) -> Vec<HighEntropyFile> {
+    let mut found: Vec<HighEntropyFile> = info
+        .layers
+        .iter()
+        .enumerate()
+        .flat_map(|(layer, l)| {
+            l.files.iter().filter_map(move |f| {
+                let entropy = f.entropy.filter(|&e| e >= THRESHOLD)?;
+                Some(HighEntropyFile {
+                    path: f.path.clone(),
+                    size: f.size,
+                    layer,
+                    entropy,
+                })
+            })
+        })
+        .collect();
+    found.sort_by_key(|f| Reverse(f.size));
+    found
+}
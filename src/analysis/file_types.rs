@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::normalize;
+use crate::inspector::{FileEntry, ImageInfo};
+
+/// What kind of file something is, from its ELF magic, name and location.
+/// Unlike `content::Category`, this says nothing about who put it there.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FileType {
+    /// ELF programs
+    Executable,
+    /// ELF shared objects and static libraries
+    SharedLib,
+    /// Python source and bytecode
+    Python,
+    /// JavaScript and TypeScript, including source maps
+    #[serde(rename = "javascript")]
+    JavaScript,
+    /// Man pages, info pages, licenses and other documentation
+    Docs,
+    /// Message catalogs, locale definitions and timezone data
+    Locale,
+    /// Raster and vector images
+    Image,
+    /// Compressed files and package archives (tarballs, jars, wheels, debs)
+    Archive,
+    Other,
+}
+
+impl FileType {
+    /// Every type, in display order.
+    pub const ALL: [FileType; 9] = [
+        FileType::Executable,
+        FileType::SharedLib,
+        FileType::Python,
+        FileType::JavaScript,
+        FileType::Docs,
+        FileType::Locale,
+        FileType::Image,
+        FileType::Archive,
+        FileType::Other,
+    ];
+
+    /// Classify a file entry. Locations that hold one kind of file (man
+    /// pages, locales) win over extensions, which win over the ELF magic.
+    pub fn of(file: &FileEntry) -> Self {
+        let path = normalize(&file.path);
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_ascii_lowercase())
+            .unwrap_or_default();
+        let ext = name.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+        let in_dir = |dir: &str| has_dir(&path, dir);
+
+        if LOCALE_DIRS.iter().any(|d| in_dir(d)) || ext == "mo" {
+            FileType::Locale
+        } else if DOC_DIRS.iter().any(|d| in_dir(d))
+            || DOC_EXTENSIONS.contains(&ext)
+            || DOC_NAMES.iter().any(|n| name.starts_with(n))
+        {
+            FileType::Docs
+        } else if is_library_name(&name) {
+            FileType::SharedLib
+        } else if PYTHON_EXTENSIONS.contains(&ext) {
+            FileType::Python
+        } else if JS_EXTENSIONS.contains(&ext) {
+            FileType::JavaScript
+        } else if IMAGE_EXTENSIONS.contains(&ext) {
+            FileType::Image
+        } else if ARCHIVE_EXTENSIONS.contains(&ext) {
+            FileType::Archive
+        } else if file.elf {
+            FileType::Executable
+        } else {
+            FileType::Other
+        }
+    }
+}
+
+/// Directories (matched at any depth) holding nothing but locale data.
+const LOCALE_DIRS: &[&str] = &[
+    "usr/share/locale",
+    "usr/lib/locale",
+    "usr/share/i18n",
+    "usr/share/zoneinfo",
+];
+
+/// Directories (matched at any depth) holding nothing but documentation.
+const DOC_DIRS: &[&str] = &[
+    "usr/share/doc",
+    "usr/share/man",
+    "usr/share/info",
+    "usr/share/help",
+];
+
+const DOC_EXTENSIONS: &[&str] = &["md", "rst", "txt", "pdf", "adoc", "info"];
+
+/// File name prefixes of licenses and readmes shipped with packages.
+const DOC_NAMES: &[&str] = &[
+    "readme",
+    "license",
+    "licence",
+    "copying",
+    "changelog",
+    "notice",
+];
+
+const PYTHON_EXTENSIONS: &[&str] = &["py", "pyc", "pyo", "pyi", "pyd"];
+
+const JS_EXTENSIONS: &[&str] = &["js", "mjs", "cjs", "ts", "tsx", "jsx", "map"];
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "png", "jpg", "jpeg", "gif", "svg", "ico", "webp", "avif", "bmp", "tif", "tiff",
+];
+
+const ARCHIVE_EXTENSIONS: &[&str] = &[
+    "tar", "gz", "tgz", "bz2", "xz", "zst", "lz4", "zip", "7z", "jar", "war", "whl", "egg", "deb",
+    "rpm", "apk", "gem", "nupkg",
+];
+
+/// `lib*.so`, `*.so.1.2` and static archives `*.a`.
+fn is_library_name(name: &str) -> bool {
+    name.ends_with(".so") || name.contains(".so.") || name.ends_with(".a")
+}
+
+/// Whether `dir` appears as a run of whole components in `path`.
+fn has_dir(path: &Path, dir: &str) -> bool {
+    let path = path.to_string_lossy();
+    path.starts_with(&format!("{dir}/")) || path.contains(&format!("/{dir}/"))
+}
+
+/// Bytes per file type, per layer and in total.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileTypeBreakdown {
+    /// One map per layer (base = 0); types with no files are left out
+    pub layers: Vec<BTreeMap<FileType, u64>>,
+
+    /// Sum over all layers
+    pub totals: BTreeMap<FileType, u64>,
+}
+
+/// Classify every file in every layer. Like `content::analyze`, whiteouts
+/// are skipped and hidden bytes count towards the layer that shipped them.
+pub fn analyze(info: &ImageInfo) -> FileTypeBreakdown {
+    let mut totals = BTreeMap::new();
+    let layers = info
+        .layers
+        .iter()
+        .map(|layer| {
+            let mut sizes = BTreeMap::new();
+            for file in layer.files.iter().filter(|f| !f.is_whiteout) {
+                let kind = FileType::of(file);
+                *sizes.entry(kind).or_insert(0) += file.size;
+                *totals.entry(kind).or_insert(0) += file.size;
+            }
+            sizes
+        })
+        .collect();
+    FileTypeBreakdown { layers, totals }
+}
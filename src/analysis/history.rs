@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::ImageInfo;
+use crate::inspector::instruction::Instruction;
+
+/// One step of the image's build, as `docker history` lists them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    /// Index into the image's layers (base = 0); `None` for steps that only
+    /// changed the config, like ENV, LABEL or CMD
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<usize>,
+
+    /// Bytes the step's layer holds; 0 without one
+    pub size: u64,
+
+    /// RFC 3339
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_by: Option<String>,
+
+    /// `created_by` parsed; absent if it doesn't start with a Dockerfile
+    /// instruction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<Instruction>,
+
+    /// e.g. "buildkit.dockerfile.v0"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    #[serde(default)]
+    history: Vec<ConfigEntry>,
+}
+
+#[derive(Deserialize)]
+struct ConfigEntry {
+    created: Option<String>,
+    created_by: Option<String>,
+    comment: Option<String>,
+    #[serde(default)]
+    empty_layer: bool,
+}
+
+/// Every history entry of the raw image config, base first, with the layer
+/// each non-empty one made. Without a config history, one entry per layer.
+pub fn entries(info: &ImageInfo, config: Option<&[u8]>) -> Vec<HistoryEntry> {
+    let history = config
+        .and_then(|c| serde_json::from_slice::<ImageConfig>(c).ok())
+        .map(|c| c.history)
+        .unwrap_or_default();
+
+    if history.is_empty() {
+        return info
+            .layers
+            .iter()
+            .enumerate()
+            .map(|(i, layer)| HistoryEntry {
+                layer: Some(i),
+                size: layer.size,
+                created: layer.created.clone(),
+                created_by: layer.created_by.clone(),
+                instruction: layer.instruction.clone(),
+                comment: None,
+            })
+            .collect();
+    }
+
+    // Non-empty entries match the layers in order
+    let mut layers = 0..info.layers.len();
+    history
+        .into_iter()
+        .map(|entry| {
+            let layer = if entry.empty_layer {
+                None
+            } else {
+                layers.next()
+            };
+            HistoryEntry {
+                layer,
+                size: layer.map_or(0, |i| info.layers[i].size),
+                instruction: entry.created_by.as_deref().and_then(Instruction::parse),
+                created: entry.created,
+                created_by: entry.created_by,
+                comment: entry.comment.filter(|c| !c.is_empty()),
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,106 @@
+use std::cmp::Reverse;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::{self, MergedFile};
+use crate::inspector::ImageInfo;
+
+/// Files listed as the image's inventory when there is no package manager.
+const TOP_FILES: usize = 10;
+
+/// Package databases: (manager, path of the database in the final filesystem).
+const PACKAGE_DBS: &[(&str, &str)] = &[
+    ("dpkg", "var/lib/dpkg/status"),
+    ("apk", "lib/apk/db/installed"),
+    ("rpm", "var/lib/rpm/rpmdb.sqlite"),
+    ("rpm", "var/lib/rpm/Packages"),
+    ("rpm", "var/lib/rpm/Packages.db"),
+    ("rpm", "usr/lib/sysimage/rpm/rpmdb.sqlite"),
+    ("rpm", "usr/lib/sysimage/rpm/Packages.db"),
+];
+
+/// Distroless images record their Debian packages one file per package here,
+/// without dpkg itself.
+const DISTROLESS_STATUS_DIR: &str = "var/lib/dpkg/status.d";
+
+const SHELLS: &[&str] = &[
+    "bin/sh",
+    "bin/bash",
+    "bin/ash",
+    "bin/busybox",
+    "usr/bin/sh",
+    "usr/bin/bash",
+];
+
+/// What kind of base an image was built on, which decides whether
+/// package-level findings apply to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageClass {
+    /// A distribution with a package manager and its database
+    Distro,
+    /// A shell and basic tools but no package database (e.g. busybox)
+    Minimal,
+    /// Distribution files without a shell or package manager
+    Distroless,
+    /// Nothing but what the image added itself
+    Scratch,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClassReport {
+    pub class: ImageClass,
+
+    /// Package manager whose database was found, or `dpkg` for distroless
+    /// `status.d` entries
+    pub package_db: Option<String>,
+
+    pub shell: bool,
+
+    /// Largest files of the final filesystem, when no package manager
+    /// accounts for them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub largest: Vec<MergedFile>,
+}
+
+/// Classify the image from its final filesystem.
+pub fn classify(info: &ImageInfo) -> ClassReport {
+    let files = merged::merge(info);
+    let has = |path: &str| files.contains_key(Path::new(path));
+
+    let package_db = PACKAGE_DBS
+        .iter()
+        .find(|(_, path)| has(path))
+        .map(|(manager, _)| manager.to_string());
+    let distroless_db = files
+        .keys()
+        .any(|p| p.starts_with(DISTROLESS_STATUS_DIR) && p != Path::new(DISTROLESS_STATUS_DIR));
+    let shell = SHELLS.iter().any(|s| has(s));
+
+    let class = if package_db.is_some() {
+        ImageClass::Distro
+    } else if shell {
+        ImageClass::Minimal
+    } else if distroless_db || has("etc/os-release") || has("usr/lib/os-release") {
+        ImageClass::Distroless
+    } else {
+        ImageClass::Scratch
+    };
+
+    let largest = if class == ImageClass::Distro {
+        Vec::new()
+    } else {
+        let mut all: Vec<MergedFile> = files.into_values().collect();
+        all.sort_by_key(|f| Reverse(f.size));
+        all.truncate(TOP_FILES);
+        all
+    };
+
+    ClassReport {
+        class,
+        package_db: package_db.or_else(|| distroless_db.then(|| "dpkg".to_string())),
+        shell,
+        largest,
+    }
+}
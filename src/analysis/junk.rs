@@ -0,0 +1,118 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Component, Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::normalize;
+use crate::inspector::ImageInfo;
+
+/// Well-known files that images ship by accident and never need at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JunkKind {
+    /// Package indexes left by `apt-get update`
+    AptLists,
+    /// Downloaded `.deb` files
+    AptCache,
+    /// pip's wheel and HTTP cache
+    PipCache,
+    /// npm's package cache
+    NpmCache,
+    /// Yarn's package cache
+    YarnCache,
+    /// Compiled Python bytecode
+    Pycache,
+    /// Git repositories copied in with the build context
+    Git,
+}
+
+/// Where each kind lives: the directory components to look for, and whether
+/// they must start at the root or may appear at any depth (e.g. in a home).
+const PATTERNS: &[(JunkKind, &[&str], bool)] = &[
+    (JunkKind::AptLists, &["var", "lib", "apt", "lists"], true),
+    (JunkKind::AptCache, &["var", "cache", "apt"], true),
+    (JunkKind::PipCache, &[".cache", "pip"], false),
+    (JunkKind::NpmCache, &[".npm"], false),
+    (JunkKind::YarnCache, &[".cache", "yarn"], false),
+    (JunkKind::Pycache, &["__pycache__"], false),
+    (JunkKind::Git, &[".git"], false),
+];
+
+/// One kind of junk found in the image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Junk {
+    pub kind: JunkKind,
+
+    /// Directories holding it, without a leading `/`, in path order
+    pub paths: Vec<PathBuf>,
+
+    /// Bytes in those directories across all layers; what leaving them out
+    /// of the layers would save, even where a later layer deletes them
+    pub size: u64,
+
+    /// Layers that wrote into them, in layer order
+    pub layers: Vec<JunkLayer>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JunkLayer {
+    /// Index into the image's layers (base = 0)
+    pub layer: usize,
+
+    pub size: u64,
+}
+
+/// Every kind of junk with content in any layer, largest first.
+pub fn detect(info: &ImageInfo) -> Vec<Junk> {
+    let mut found: BTreeMap<JunkKind, (BTreeSet<PathBuf>, BTreeMap<usize, u64>)> = BTreeMap::new();
+    for (index, layer) in info.layers.iter().enumerate() {
+        for file in layer.files.iter().filter(|f| !f.is_whiteout) {
+            let path = normalize(&file.path);
+            let Some((kind, dir)) = locate(&path) else {
+                continue;
+            };
+            let (paths, layers) = found.entry(kind).or_default();
+            paths.insert(dir);
+            *layers.entry(index).or_insert(0) += file.size;
+        }
+    }
+
+    let mut junk: Vec<Junk> = found
+        .into_iter()
+        .map(|(kind, (paths, layers))| Junk {
+            kind,
+            paths: paths.into_iter().collect(),
+            size: layers.values().sum(),
+            layers: layers
+                .into_iter()
+                .map(|(layer, size)| JunkLayer { layer, size })
+                .collect(),
+        })
+        .filter(|j| j.size > 0)
+        .collect();
+    junk.sort_by_key(|j| Reverse(j.size));
+    junk
+}
+
+/// The kind of junk `path` is part of and the directory that holds it.
+fn locate(path: &Path) -> Option<(JunkKind, PathBuf)> {
+    let parts: Vec<&str> = path
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    PATTERNS.iter().find_map(|(kind, pattern, anchored)| {
+        // The file itself must be below the directory, not the directory
+        let last = parts.len().checked_sub(pattern.len() + 1)?;
+        let start = if *anchored {
+            (parts[..pattern.len()] == **pattern).then_some(0)?
+        } else {
+            (0..=last).find(|&i| parts[i..i + pattern.len()] == **pattern)?
+        };
+        let dir = parts[..start + pattern.len()].iter().collect();
+        Some((*kind, dir))
+    })
+}
@@ -0,0 +1,274 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::packages::{Package, PackageKind};
+
+/// License texts are small; anything larger is something else.
+pub const MAX_SIZE: u64 = 1024 * 1024;
+
+/// Debian keeps each package's copyright file here.
+const DEB_DOC_DIR: &str = "usr/share/doc";
+
+/// Full texts Debian's base-files ships for copyright files to refer to; they
+/// say nothing about what's installed.
+const COMMON_LICENSES: &str = "usr/share/common-licenses/";
+
+/// File names (before any extension, case-insensitive) that hold licenses.
+const NAMES: &[&str] = &["license", "licence", "copying", "notice", "copyright"];
+
+/// Source files that happen to share those names, e.g. `license.py`.
+const CODE_EXTENSIONS: &[&str] = &[
+    "py", "pyc", "js", "mjs", "ts", "c", "h", "go", "rs", "rb", "java", "class", "json", "html",
+    "so",
+];
+
+/// A license file in the final filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseFile {
+    pub path: PathBuf,
+
+    /// Layer that ships it (base = 0)
+    pub layer: usize,
+
+    /// Licenses named by its text; empty if none were recognized
+    pub licenses: Vec<String>,
+
+    /// Debian package whose copyright file this is
+    pub package: Option<String>,
+}
+
+/// An installed package with the licenses it declares.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageLicense {
+    pub name: String,
+    pub version: String,
+    pub kind: PackageKind,
+
+    /// From apk's `L:` field, or the `License:` fields of a Debian package's
+    /// machine-readable copyright file; empty if it declares none
+    pub licenses: Vec<String>,
+
+    /// First layer that installed it (base = 0)
+    pub layer: usize,
+}
+
+/// How much of the image is under one license.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseCount {
+    pub license: String,
+
+    /// Installed packages declaring it
+    pub packages: usize,
+
+    /// License files outside package docs naming it, e.g. vendored libraries
+    /// and application dependencies
+    pub files: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LicenseSummary {
+    /// Most used first
+    pub licenses: Vec<LicenseCount>,
+
+    /// Packages that declare no license
+    pub unknown_packages: usize,
+
+    /// License files whose text wasn't recognized
+    pub unknown_files: usize,
+}
+
+/// Fingerprints of common license texts, most specific first: the LGPL
+/// mentions the GPL, and BSD-3-Clause contains BSD-2-Clause.
+static TEXTS: LazyLock<Vec<(&str, Regex)>> = LazyLock::new(|| {
+    [
+        ("AGPL-3.0", r"gnu affero general public license,? version 3"),
+        ("LGPL-3.0", r"gnu lesser general public license,? version 3"),
+        ("LGPL-2.1", r"gnu lesser general public license,? version 2\.1"),
+        ("LGPL-2.0", r"gnu library general public license,? version 2"),
+        ("GPL-3.0", r"gnu general public license,? version 3"),
+        ("GPL-2.0", r"gnu general public license,? version 2"),
+        ("Apache-2.0", r"apache license,? version 2\.0"),
+        ("MPL-2.0", r"mozilla public license,? (?:version |v\.? ?)2\.0"),
+        ("EPL-2.0", r"eclipse public license,? (?:- )?v(?:ersion )?2\.0"),
+        (
+            "BSD-3-Clause",
+            r"redistribution and use in source and binary forms.*neither the name",
+        ),
+        (
+            "BSD-2-Clause",
+            r"redistribution and use in source and binary forms",
+        ),
+        (
+            "MIT",
+            r"permission is hereby granted, free of charge, to any person obtaining a copy",
+        ),
+        (
+            "ISC",
+            r"permission to use, copy, modify, and(?:/or)? distribute this software for any purpose with or without fee is hereby granted",
+        ),
+        (
+            "Zlib",
+            r"altered source versions must be plainly marked as such",
+        ),
+        (
+            "Unlicense",
+            r"this is free and unencumbered software released into the public domain",
+        ),
+        ("CC0-1.0", r"cc0 1\.0 universal"),
+        ("PSF-2.0", r"python software foundation license"),
+    ]
+    .into_iter()
+    .map(|(id, pattern)| (id, Regex::new(pattern).expect("license pattern")))
+    .collect()
+});
+
+static SPDX_ID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?m)SPDX-License-Identifier:\s*([A-Za-z0-9.+()\- ]+?)\s*(?:\*/|-->|$)")
+        .expect("SPDX pattern")
+});
+
+/// Older Debian copyright files point at the full text instead of naming
+/// the license, e.g. `/usr/share/common-licenses/GPL-2`.
+static COMMON_LICENSE_REF: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"/usr/share/common-licenses/([A-Za-z0-9.+-]*[A-Za-z0-9+])")
+        .expect("common-licenses pattern")
+});
+
+/// Whether `path` (normalized) of `size` bytes is a license file to read.
+pub fn candidate(path: &Path, size: u64) -> bool {
+    if size == 0 || size > MAX_SIZE || path.starts_with(COMMON_LICENSES) {
+        return false;
+    }
+    let Some(name) = path.file_name() else {
+        return false;
+    };
+    let name = name.to_string_lossy().to_lowercase();
+    if name
+        .rsplit_once('.')
+        .is_some_and(|(_, ext)| CODE_EXTENSIONS.contains(&ext))
+    {
+        return false;
+    }
+    let stem = name.split(['.', '-', '_']).next().unwrap_or(&name);
+    NAMES.contains(&stem)
+}
+
+/// The package a Debian copyright file at `path` belongs to.
+fn deb_package(path: &Path) -> Option<String> {
+    let dir = path.parent()?;
+    (path.file_name()? == "copyright" && dir.parent()? == Path::new(DEB_DOC_DIR))
+        .then(|| dir.file_name().map(|n| n.to_string_lossy().into_owned()))?
+}
+
+/// Recognize the licenses in one license file.
+pub fn identify(path: &Path, layer: usize, content: &[u8]) -> LicenseFile {
+    let text = String::from_utf8_lossy(content);
+    let licenses = if text.starts_with("Format:") && text.contains("copyright-format") {
+        dep5(&text)
+    } else if let Some(ids) = SPDX_ID.captures(&text) {
+        vec![ids[1].to_string()]
+    } else if COMMON_LICENSE_REF.is_match(&text) {
+        let mut licenses: Vec<String> = Vec::new();
+        for name in COMMON_LICENSE_REF.captures_iter(&text) {
+            if !licenses.iter().any(|l| *l == name[1]) {
+                licenses.push(name[1].to_string());
+            }
+        }
+        licenses
+    } else {
+        // Line breaks and indentation differ between copies of the same text
+        let flat = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let flat = flat.to_lowercase();
+        TEXTS
+            .iter()
+            .find(|(_, pattern)| pattern.is_match(&flat))
+            .map(|(id, _)| vec![id.to_string()])
+            .unwrap_or_default()
+    };
+    LicenseFile {
+        path: path.to_path_buf(),
+        layer,
+        licenses,
+        package: deb_package(path),
+    }
+}
+
+/// The short names in the `License:` fields of a machine-readable Debian
+/// copyright file, in order of appearance.
+fn dep5(text: &str) -> Vec<String> {
+    let mut licenses: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let Some(value) = line.strip_prefix("License:") else {
+            continue;
+        };
+        let value = value.trim();
+        if !value.is_empty() && !licenses.iter().any(|l| l == value) {
+            licenses.push(value.to_string());
+        }
+    }
+    licenses
+}
+
+/// Pair installed packages with their declared licenses: apk's own field, or
+/// the package's Debian copyright file among `files`.
+pub fn packages(packages: &[Package], files: &[LicenseFile]) -> Vec<PackageLicense> {
+    let copyright: BTreeMap<&str, &LicenseFile> = files
+        .iter()
+        .filter_map(|f| Some((f.package.as_deref()?, f)))
+        .collect();
+    packages
+        .iter()
+        .map(|p| {
+            let licenses = match p.kind {
+                PackageKind::Apk => p.license.iter().cloned().collect(),
+                PackageKind::Deb => copyright
+                    .get(p.name.as_str())
+                    .map(|f| f.licenses.clone())
+                    .unwrap_or_default(),
+            };
+            PackageLicense {
+                name: p.name.clone(),
+                version: p.version.clone(),
+                kind: p.kind,
+                licenses,
+                layer: p.layer,
+            }
+        })
+        .collect()
+}
+
+/// Count packages and stray license files per license.
+pub fn summarize(packages: &[PackageLicense], files: &[LicenseFile]) -> LicenseSummary {
+    let mut counts: BTreeMap<&str, (usize, usize)> = BTreeMap::new();
+    for p in packages {
+        for license in &p.licenses {
+            counts.entry(license).or_default().0 += 1;
+        }
+    }
+    let stray: Vec<&LicenseFile> = files.iter().filter(|f| f.package.is_none()).collect();
+    for f in &stray {
+        for license in &f.licenses {
+            counts.entry(license).or_default().1 += 1;
+        }
+    }
+
+    let mut licenses: Vec<LicenseCount> = counts
+        .into_iter()
+        .map(|(license, (packages, files))| LicenseCount {
+            license: license.to_string(),
+            packages,
+            files,
+        })
+        .collect();
+    licenses.sort_by_key(|l| Reverse(l.packages + l.files));
+    LicenseSummary {
+        licenses,
+        unknown_packages: packages.iter().filter(|p| p.licenses.is_empty()).count(),
+        unknown_files: stray.iter().filter(|f| f.licenses.is_empty()).count(),
+    }
+}
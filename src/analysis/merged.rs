@@ -0,0 +1,153 @@
+use std::collections::{BTreeMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub use crate::inspector::normalize;
+use crate::inspector::{FileChange, ImageInfo, Inspector, LayerInfo};
+
+/// Marker file that hides everything below its directory in lower layers.
+pub const OPAQUE_WHITEOUT: &str = ".wh..wh..opq";
+
+/// A file visible in the final filesystem of an image.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MergedFile {
+    /// Normalized path (no leading `./` or `/`)
+    pub path: PathBuf,
+
+    /// File size in bytes
+    pub size: u64,
+
+    /// Index of the layer that provides this file (base = 0)
+    pub layer: usize,
+}
+
+/// Apply all layers in order, honouring whiteouts, and return the files a
+/// container started from this image would see, keyed by path.
+pub fn merge(info: &ImageInfo) -> BTreeMap<PathBuf, MergedFile> {
+    let mut files: BTreeMap<PathBuf, MergedFile> = BTreeMap::new();
+    for (index, layer) in info.layers.iter().enumerate() {
+        apply_layer(&mut files, index, layer);
+    }
+    files
+}
+
+/// Content of the files at `paths` (normalized) as the final filesystem
+/// shows them, read from the layers that provide them. Missing paths and
+/// entries that aren't regular files, like symbolic links, are left out.
+pub fn read_files(
+    info: &ImageInfo,
+    inspector: &mut dyn Inspector,
+    paths: &[PathBuf],
+) -> Result<Vec<(PathBuf, Vec<u8>)>> {
+    let visible = merge(info);
+    let mut by_layer: BTreeMap<usize, HashSet<&Path>> = BTreeMap::new();
+    for path in paths {
+        if let Some(file) = visible.get(path) {
+            by_layer.entry(file.layer).or_default().insert(&file.path);
+        }
+    }
+
+    let mut files = Vec::new();
+    for (index, mut wanted) in by_layer {
+        let mut archive = tar::Archive::new(inspector.read_layer(&info.layers[index])?);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            let path = normalize(&entry.path()?);
+            if !wanted.remove(path.as_path()) {
+                continue;
+            }
+            if entry.header().entry_type().is_file() {
+                let mut content = Vec::new();
+                entry
+                    .read_to_end(&mut content)
+                    .context(t!("repack-read-failed"))?;
+                files.push((path, content));
+            }
+            // Layers are read only as far as needed
+            if wanted.is_empty() {
+                break;
+            }
+        }
+    }
+    Ok(files)
+}
+
+/// Set `change` on every file entry: whiteouts are `Deleted`, and a regular
+/// file is `Modified` if its path is visible from the layers below, else `Added`.
+pub fn classify(info: &mut ImageInfo) {
+    let mut files: BTreeMap<PathBuf, MergedFile> = BTreeMap::new();
+    for (index, layer) in info.layers.iter_mut().enumerate() {
+        // Compare against the state before this layer's own whiteouts, so a
+        // file in an opaque directory that existed below still counts as modified.
+        for entry in &mut layer.files {
+            entry.change = if entry.is_whiteout {
+                FileChange::Deleted
+            } else if files.contains_key(&normalize(&entry.path)) {
+                FileChange::Modified
+            } else {
+                FileChange::Added
+            };
+        }
+        apply_layer(&mut files, index, layer);
+    }
+}
+
+/// Apply one layer's whiteouts and files on top of `files`, returning the
+/// lower-layer entries it deleted or overwrote.
+pub fn apply_layer(
+    files: &mut BTreeMap<PathBuf, MergedFile>,
+    index: usize,
+    layer: &LayerInfo,
+) -> Vec<MergedFile> {
+    let mut displaced = Vec::new();
+
+    // Whiteouts only hide content from lower layers, so apply them before
+    // adding this layer's own files.
+    for entry in layer.files.iter().filter(|f| f.is_whiteout) {
+        let path = normalize(&entry.path);
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+
+        if name == OPAQUE_WHITEOUT {
+            displaced.extend(remove_children(files, &parent));
+        } else if let Some(hidden) = name.strip_prefix(".wh.") {
+            let target = parent.join(hidden);
+            displaced.extend(files.remove(&target));
+            displaced.extend(remove_children(files, &target));
+        }
+    }
+
+    for entry in layer.files.iter().filter(|f| !f.is_whiteout) {
+        let path = normalize(&entry.path);
+        // A file replacing a directory hides everything that was below it
+        displaced.extend(remove_children(files, &path));
+        displaced.extend(files.insert(
+            path.clone(),
+            MergedFile {
+                path,
+                size: entry.size,
+                layer: index,
+            },
+        ));
+    }
+
+    displaced
+}
+
+/// Remove every entry strictly below `dir`, returning what was removed.
+fn remove_children(files: &mut BTreeMap<PathBuf, MergedFile>, dir: &Path) -> Vec<MergedFile> {
+    // Paths order component-wise, so a subtree is a contiguous range.
+    let doomed: Vec<PathBuf> = files
+        .range(dir.to_path_buf()..)
+        .map(|(p, _)| p)
+        .take_while(|p| p.starts_with(dir))
+        .filter(|p| p.as_path() != dir)
+        .cloned()
+        .collect();
+    doomed.iter().filter_map(|path| files.remove(path)).collect()
+}
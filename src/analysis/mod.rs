@@ -0,0 +1,33 @@
+pub mod base;
+pub mod cache_mounts;
+pub mod compare;
+pub mod content;
+pub mod diff;
+pub mod dirs;
+pub mod distro;
+pub mod dockerfile;
+pub mod drift;
+pub mod duplicates;
+pub mod elf;
+pub mod entropy;
+pub mod file_types;
+pub mod history;
+pub mod image_class;
+pub mod junk;
+pub mod licenses;
+pub mod merged;
+pub mod packages;
+pub mod posture;
+pub mod pull;
+pub mod repro;
+pub mod push;
+pub mod runtimes;
+pub mod secrets;
+pub mod shared;
+pub mod spdx;
+pub mod squash;
+pub mod stats;
+pub mod strip;
+pub mod toolchain;
+pub mod verify;
+pub mod waste;
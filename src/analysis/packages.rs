@@ -0,0 +1,176 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::distro::{self, Distro};
+use super::merged;
+use crate::inspector::ImageInfo;
+
+/// Package databases peel can read. rpm keeps its database in SQLite or
+/// Berkeley DB, which isn't read here.
+const DPKG_STATUS: &str = "var/lib/dpkg/status";
+const APK_INSTALLED: &str = "lib/apk/db/installed";
+
+/// Distroless images record their Debian packages one file per package here,
+/// next to `.md5sums` lists.
+const DPKG_STATUS_DIR: &str = "var/lib/dpkg/status.d";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PackageKind {
+    /// Debian and Ubuntu packages, from dpkg's status
+    Deb,
+    /// Alpine packages, from apk's installed database
+    Apk,
+}
+
+/// A package installed in the final filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+    pub kind: PackageKind,
+    pub arch: Option<String>,
+
+    /// Source package it was built from, when named differently (e.g.
+    /// `glibc` for `libc6`); advisories are usually filed against it
+    pub source: Option<String>,
+
+    /// Version of the source package, when it differs from `version` (dpkg
+    /// only, e.g. after a binNMU)
+    pub source_version: Option<String>,
+
+    /// License as the package declares it (apk only)
+    pub license: Option<String>,
+
+    /// Maintainer as the package declares it
+    pub supplier: Option<String>,
+
+    /// First layer whose database lists this version (base = 0)
+    pub layer: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageList {
+    pub distro: Option<Distro>,
+
+    /// Sorted by kind, then name
+    pub packages: Vec<Package>,
+}
+
+/// Whether `path` (normalized) is a file `list` needs the content of.
+pub fn wanted(path: &Path) -> bool {
+    path == Path::new(DPKG_STATUS)
+        || path == Path::new(APK_INSTALLED)
+        || distro::wanted(path)
+        || (path.parent() == Some(Path::new(DPKG_STATUS_DIR))
+            && path.extension().is_none_or(|e| e != "md5sums"))
+}
+
+/// Packages of the final filesystem. `contents` holds every layer's copy of
+/// each `wanted` file as (layer, normalized path, content); older copies
+/// tell which layer first installed each package.
+pub fn list(info: &ImageInfo, contents: &[(usize, PathBuf, Vec<u8>)]) -> PackageList {
+    let visible = merged::merge(info);
+    let is_visible =
+        |layer: usize, path: &Path| visible.get(path).is_some_and(|f| f.layer == layer);
+
+    let mut first_layer: HashMap<(PackageKind, String, String), usize> = HashMap::new();
+    let mut packages = Vec::new();
+    let mut release_files = Vec::new();
+    for (layer, path, content) in contents {
+        if distro::wanted(path) {
+            if is_visible(*layer, path) {
+                release_files.push((path.clone(), content.clone()));
+            }
+            continue;
+        }
+        let text = String::from_utf8_lossy(content);
+        let kind = if path == Path::new(APK_INSTALLED) {
+            PackageKind::Apk
+        } else {
+            PackageKind::Deb
+        };
+        for package in parse(kind, &text, *layer) {
+            let key = (kind, package.name.clone(), package.version.clone());
+            let first = first_layer.entry(key).or_insert(*layer);
+            *first = (*first).min(*layer);
+            if is_visible(*layer, path) {
+                packages.push(package);
+            }
+        }
+    }
+
+    for package in &mut packages {
+        let key = (package.kind, package.name.clone(), package.version.clone());
+        package.layer = first_layer[&key];
+    }
+    packages.sort_by(|a, b| (a.kind as u8, &a.name).cmp(&(b.kind as u8, &b.name)));
+    packages.dedup_by(|a, b| a.kind == b.kind && a.name == b.name && a.version == b.version);
+    PackageList {
+        distro: distro::identify(&release_files),
+        packages,
+    }
+}
+
+/// Parse a dpkg status file (or one `status.d` entry) or apk's installed
+/// database: stanzas of `Key: value` (dpkg) or `K:value` (apk) lines.
+fn parse(kind: PackageKind, text: &str, layer: usize) -> Vec<Package> {
+    let (name, version, arch, source, license, supplier) = match kind {
+        PackageKind::Deb => (
+            "Package",
+            "Version",
+            "Architecture",
+            "Source",
+            "",
+            "Maintainer",
+        ),
+        PackageKind::Apk => ("P", "V", "A", "o", "L", "m"),
+    };
+    text.split("\n\n")
+        .filter_map(|stanza| {
+            let mut fields: HashMap<&str, &str> = HashMap::new();
+            for line in stanza.lines() {
+                // dpkg continues long values on indented lines
+                if line.starts_with([' ', '\t']) {
+                    continue;
+                }
+                if let Some((key, value)) = line.split_once(':') {
+                    fields.insert(key, value.trim());
+                }
+            }
+            // Removed packages can linger in dpkg's status with their config files
+            if fields
+                .get("Status")
+                .is_some_and(|s| !s.ends_with(" installed"))
+            {
+                return None;
+            }
+            let field = |key: &str| fields.get(key).map(|v| v.to_string());
+            let name = field(name)?;
+            // dpkg appends the source version when it differs: "glibc (2.36-9)"
+            let (source, source_version) = match fields.get(source) {
+                Some(s) => match s.split_once(" (") {
+                    Some((source, v)) => (source, Some(v.trim_end_matches(')').trim())),
+                    None => (s.trim(), None),
+                },
+                None => ("", None),
+            };
+            let source = Some(source)
+                .filter(|s| !s.is_empty() && *s != name)
+                .map(str::to_string);
+            Some(Package {
+                name,
+                version: field(version)?,
+                kind,
+                arch: field(arch),
+                source,
+                source_version: source_version.map(str::to_string),
+                license: field(license),
+                supplier: field(supplier),
+                layer,
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,128 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::{self, normalize};
+use crate::inspector::ImageInfo;
+
+const SETUID: u32 = 0o4000;
+const SETGID: u32 = 0o2000;
+const WORLD_WRITABLE: u32 = 0o002;
+
+/// What a security reviewer checks first: who the image runs as and which
+/// files grant or leak privileges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostureReport {
+    /// `User` from the image config, if set
+    pub user: Option<String>,
+
+    /// No `User`, or user `root` / `0`; `None` when the config isn't available
+    pub runs_as_root: Option<bool>,
+
+    /// setuid or setgid regular files in the final filesystem, by layer
+    pub setuid: Vec<PostureFile>,
+
+    /// Regular files anyone can write to in the final filesystem, by layer.
+    /// Directories aren't listed, so they aren't checked.
+    pub world_writable: Vec<PostureFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostureFile {
+    pub path: PathBuf,
+
+    /// Layer that ships the file (base = 0)
+    pub layer: usize,
+
+    /// Permission bits (`mode & 0o7777`)
+    pub mode: u32,
+
+    pub uid: Option<u32>,
+    pub gid: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    #[serde(default)]
+    config: Option<RunConfig>,
+}
+
+#[derive(Deserialize)]
+struct RunConfig {
+    #[serde(rename = "User", default)]
+    user: Option<String>,
+}
+
+/// Check the final filesystem's file modes and the config's `User`.
+/// `config` is the raw image config, if the backend has it.
+pub fn analyze(info: &ImageInfo, config: Option<&[u8]>) -> PostureReport {
+    let config: Option<ImageConfig> = config.and_then(|c| serde_json::from_slice(c).ok());
+    let user = config
+        .as_ref()
+        .and_then(|c| c.config.as_ref()?.user.clone())
+        .filter(|u| !u.is_empty());
+    let runs_as_root = config.map(|_| {
+        user.as_deref().is_none_or(|u| {
+            let name = u.split(':').next().unwrap_or(u);
+            name == "root" || name == "0"
+        })
+    });
+
+    let visible = merged::merge(info);
+    let mut setuid = Vec::new();
+    let mut world_writable = Vec::new();
+    for (layer, l) in info.layers.iter().enumerate() {
+        for file in &l.files {
+            let Some(mode) = file.mode else { continue };
+            let path = normalize(&file.path);
+            if visible.get(&path).is_none_or(|f| f.layer != layer) {
+                continue;
+            }
+            let entry = || PostureFile {
+                path: path.clone(),
+                layer,
+                mode,
+                uid: file.uid,
+                gid: file.gid,
+            };
+            if mode & (SETUID | SETGID) != 0 {
+                setuid.push(entry());
+            }
+            if mode & WORLD_WRITABLE != 0 {
+                world_writable.push(entry());
+            }
+        }
+    }
+
+    PostureReport {
+        user,
+        runs_as_root,
+        setuid,
+        world_writable,
+    }
+}
+
+/// `ls -l` style permissions of a regular file, e.g. `-rwsr-xr-x`.
+pub fn mode_string(mode: u32) -> String {
+    let bit = |mask: u32, c: char| if mode & mask != 0 { c } else { '-' };
+    let exec = |mask: u32, special: u32, set: char| match (mode & mask != 0, mode & special != 0) {
+        (true, true) => set,
+        (false, true) => set.to_ascii_uppercase(),
+        (true, false) => 'x',
+        (false, false) => '-',
+    };
+    [
+        '-',
+        bit(0o400, 'r'),
+        bit(0o200, 'w'),
+        exec(0o100, SETUID, 's'),
+        bit(0o040, 'r'),
+        bit(0o020, 'w'),
+        exec(0o010, SETGID, 's'),
+        bit(0o004, 'r'),
+        bit(0o002, 'w'),
+        exec(0o001, 0o1000, 't'),
+    ]
+    .iter()
+    .collect()
+}
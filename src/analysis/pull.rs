@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+/// A layer of the remote image and whether the local store already has it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullLayer {
+    /// Blob digest from the manifest
+    pub digest: String,
+
+    /// Uncompressed digest from the image config
+    pub diff_id: Option<String>,
+
+    /// Compressed size, i.e. what goes over the wire
+    pub size: u64,
+
+    pub present: bool,
+}
+
+/// What pulling one image would download.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PullEstimate {
+    pub image: String,
+
+    /// Platform picked from a multi-platform index, e.g. "linux/amd64"
+    pub platform: Option<String>,
+
+    /// Layers in manifest order (base first)
+    pub layers: Vec<PullLayer>,
+
+    pub total_size: u64,
+
+    /// Bytes of the layers missing locally
+    pub transfer_size: u64,
+}
+
+/// Sum up what is missing. Like `push::estimate`, the manifest and config
+/// are a few KB and are not counted.
+pub fn estimate(image: String, platform: Option<String>, layers: Vec<PullLayer>) -> PullEstimate {
+    PullEstimate {
+        image,
+        platform,
+        total_size: layers.iter().map(|l| l.size).sum(),
+        transfer_size: layers.iter().filter(|l| !l.present).map(|l| l.size).sum(),
+        layers,
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashSet;
+
+use serde::{Deserialize, Serialize};
+
+use crate::registry::Reference;
+
+/// A blob an image needs at the destination, and whether it is already there.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Blob {
+    pub digest: String,
+    pub size: u64,
+    pub present: bool,
+}
+
+/// What promoting one image would transfer.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImagePlan {
+    pub source: String,
+    pub destination: String,
+
+    /// Config and layer blobs across every platform the image has
+    pub blob_count: usize,
+    pub total_size: u64,
+
+    /// Blobs the destination repository is missing
+    pub missing: Vec<Blob>,
+    pub transfer_size: u64,
+}
+
+/// Per-image plans plus the aggregate for the whole promotion.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PushEstimate {
+    pub images: Vec<ImagePlan>,
+    pub total_size: u64,
+
+    /// Bytes to transfer, counting a blob once per destination repository
+    pub transfer_size: u64,
+    pub missing_blobs: usize,
+}
+
+/// Build the estimate from each image's blobs and their presence at the destination.
+///
+/// Blob sizes are the compressed sizes from the manifest, i.e. what goes over
+/// the wire. Manifests themselves are a few KB and are not counted.
+pub fn estimate(images: Vec<(String, Reference, Vec<Blob>)>) -> PushEstimate {
+    let mut seen: HashSet<(String, String, String)> = HashSet::new();
+    let mut total_size = 0;
+    let mut transfer_size = 0;
+    let mut missing_blobs = 0;
+
+    let images = images
+        .into_iter()
+        .map(|(source, destination, blobs)| {
+            let image_total: u64 = blobs.iter().map(|b| b.size).sum();
+            let missing: Vec<Blob> = blobs.iter().filter(|b| !b.present).cloned().collect();
+            let image_transfer: u64 = missing.iter().map(|b| b.size).sum();
+
+            total_size += image_total;
+            for blob in &missing {
+                let key = (
+                    destination.registry.clone(),
+                    destination.repository.clone(),
+                    blob.digest.clone(),
+                );
+                if seen.insert(key) {
+                    transfer_size += blob.size;
+                    missing_blobs += 1;
+                }
+            }
+
+            ImagePlan {
+                source,
+                destination: destination.to_string(),
+                blob_count: blobs.len(),
+                total_size: image_total,
+                missing,
+                transfer_size: image_transfer,
+            }
+        })
+        .collect();
+
+    PushEstimate {
+        images,
+        total_size,
+        transfer_size,
+        missing_blobs,
+    }
+}
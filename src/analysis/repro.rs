@@ -0,0 +1,245 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::merged::{self, normalize};
+use crate::inspector::{FileEntry, ImageInfo};
+
+/// Config fields that follow from the layers, so they differ whenever a
+/// layer does and say nothing on their own.
+const DERIVED_FIELDS: &[&str] = &["rootfs"];
+
+/// Whether two builds of the same Dockerfile came out the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    /// Same image ID: config and every layer are byte for byte the same
+    Identical,
+    /// Every file has the same content; timestamps, modes, owners or the
+    /// config differ
+    MetadataOnly,
+    /// Some files differ in content, or exist in one build only
+    ContentDiffers,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PathChange {
+    /// Only in the left build
+    Removed,
+    /// Only in the right build
+    Added,
+    /// In both, with different content
+    Changed,
+    /// Same content, different mode or owner
+    Metadata,
+}
+
+/// A path of the final filesystem that isn't the same in both builds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathDiff {
+    /// Normalized path (no leading `/`)
+    pub path: PathBuf,
+
+    pub change: PathChange,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<FileFacts>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right: Option<FileFacts>,
+}
+
+/// What was compared of one side's file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileFacts {
+    pub size: u64,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<u32>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gid: Option<u32>,
+}
+
+impl FileFacts {
+    fn same_content(&self, other: &FileFacts) -> bool {
+        self.size == other.size && self.sha256 == other.sha256
+    }
+}
+
+impl From<&FileEntry> for FileFacts {
+    fn from(entry: &FileEntry) -> Self {
+        Self {
+            size: entry.size,
+            sha256: entry.sha256.clone(),
+            mode: entry.mode,
+            uid: entry.uid,
+            gid: entry.gid,
+        }
+    }
+}
+
+/// Two builds compared file by file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReproReport {
+    pub left: String,
+    pub right: String,
+    pub verdict: Verdict,
+
+    /// Layers at the same position with the same diff_id
+    pub identical_layers: usize,
+    pub left_layers: usize,
+    pub right_layers: usize,
+
+    /// Paths in either final filesystem
+    pub files: usize,
+
+    /// Config fields that differ, e.g. "created", "config.Labels", or
+    /// "history[].created" when history entries differ only in their
+    /// timestamps; layer digests are left out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub config: Vec<String>,
+
+    /// Paths that differ, in path order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<PathDiff>,
+}
+
+impl ReproReport {
+    /// Paths whose content differs or that one build lacks.
+    pub fn content_diffs(&self) -> impl Iterator<Item = &PathDiff> {
+        self.paths
+            .iter()
+            .filter(|p| p.change != PathChange::Metadata)
+    }
+}
+
+/// Compare the final filesystems of two builds. File listings must carry
+/// checksums, or files of equal size count as the same.
+pub fn compare(
+    left: &ImageInfo,
+    left_config: Option<&[u8]>,
+    right: &ImageInfo,
+    right_config: Option<&[u8]>,
+) -> ReproReport {
+    let left_files = visible_entries(left);
+    let right_files = visible_entries(right);
+    let paths: BTreeSet<&PathBuf> = left_files.keys().chain(right_files.keys()).collect();
+
+    let mut diffs = Vec::new();
+    for path in &paths {
+        let left = left_files.get(*path).map(|e| FileFacts::from(*e));
+        let right = right_files.get(*path).map(|e| FileFacts::from(*e));
+        let change = match (&left, &right) {
+            (Some(_), None) => PathChange::Removed,
+            (None, Some(_)) => PathChange::Added,
+            (Some(l), Some(r)) if !l.same_content(r) => PathChange::Changed,
+            (Some(l), Some(r)) if l != r => PathChange::Metadata,
+            _ => continue,
+        };
+        diffs.push(PathDiff {
+            path: (*path).clone(),
+            change,
+            left,
+            right,
+        });
+    }
+
+    let same_id = left.id.is_some() && left.id == right.id;
+    let verdict = if same_id {
+        Verdict::Identical
+    } else if diffs.iter().any(|d| d.change != PathChange::Metadata) {
+        Verdict::ContentDiffers
+    } else {
+        Verdict::MetadataOnly
+    };
+
+    ReproReport {
+        left: left.name.clone(),
+        right: right.name.clone(),
+        verdict,
+        identical_layers: left
+            .layers
+            .iter()
+            .zip(&right.layers)
+            .filter(|(l, r)| l.digest == r.digest)
+            .count(),
+        left_layers: left.layers.len(),
+        right_layers: right.layers.len(),
+        files: paths.len(),
+        config: config_diffs(left_config, right_config),
+        paths: diffs,
+    }
+}
+
+/// The entry behind each path of the final filesystem.
+fn visible_entries(info: &ImageInfo) -> BTreeMap<PathBuf, &FileEntry> {
+    let visible = merged::merge(info);
+    let mut entries = BTreeMap::new();
+    for (layer, l) in info.layers.iter().enumerate() {
+        for file in l.files.iter().filter(|f| !f.is_whiteout) {
+            let path = normalize(&file.path);
+            if visible.get(&path).is_some_and(|f| f.layer == layer) {
+                entries.insert(path, file);
+            }
+        }
+    }
+    entries
+}
+
+/// Top-level config fields that differ, one level deeper for the runtime
+/// `config` object. Empty if either config is missing or unreadable.
+fn config_diffs(left: Option<&[u8]>, right: Option<&[u8]>) -> Vec<String> {
+    let parse = |c: Option<&[u8]>| c.and_then(|c| serde_json::from_slice::<Value>(c).ok());
+    let (Some(Value::Object(left)), Some(Value::Object(right))) = (parse(left), parse(right))
+    else {
+        return Vec::new();
+    };
+
+    let keys: BTreeSet<&String> = left.keys().chain(right.keys()).collect();
+    let mut fields = Vec::new();
+    for key in keys {
+        let (l, r) = (left.get(key), right.get(key));
+        if l == r || DERIVED_FIELDS.contains(&key.as_str()) {
+            continue;
+        }
+        match (key.as_str(), l, r) {
+            ("config", Some(Value::Object(l)), Some(Value::Object(r))) => {
+                let inner: BTreeSet<&String> = l.keys().chain(r.keys()).collect();
+                fields.extend(
+                    inner
+                        .into_iter()
+                        .filter(|k| l.get(*k) != r.get(*k))
+                        .map(|k| format!("config.{k}")),
+                );
+            }
+            ("history", Some(l), Some(r)) if without_created(l) == without_created(r) => {
+                fields.push("history[].created".to_string());
+            }
+            _ => fields.push(key.clone()),
+        }
+    }
+    fields
+}
+
+/// History entries with their timestamps removed.
+fn without_created(history: &Value) -> Value {
+    let mut history = history.clone();
+    if let Value::Array(entries) = &mut history {
+        for entry in entries {
+            if let Value::Object(entry) = entry {
+                entry.remove("created");
+            }
+        }
+    }
+    history
+}
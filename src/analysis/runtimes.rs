@@ -0,0 +1,219 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use anyhow::Result;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::merged::{self, MergedFile};
+use crate::inspector::{ImageInfo, Inspector};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeKind {
+    Python,
+    Node,
+    Java,
+    Go,
+}
+
+impl RuntimeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            RuntimeKind::Python => "Python",
+            RuntimeKind::Node => "Node.js",
+            RuntimeKind::Java => "Java",
+            RuntimeKind::Go => "Go",
+        }
+    }
+}
+
+/// A language runtime installed in the final filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Runtime {
+    pub kind: RuntimeKind,
+
+    /// As precise as the image tells: "3.12.2" from a version file, or just
+    /// "3.12" from a directory name; `None` when nothing says
+    pub version: Option<String>,
+
+    /// Where it's installed, e.g. "usr/local/lib/python3.12" or "usr/local/go"
+    pub path: PathBuf,
+
+    /// Layer that installed it (base = 0)
+    pub layer: usize,
+}
+
+/// Python's standard library, e.g. `usr/local/lib/python3.12/os.py`; a
+/// `site-packages` directory alone doesn't mean there's an interpreter.
+static PYTHON_STDLIB: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^((?:.*/)?lib(?:64)?)/python(\d+\.\d+)/os\.py$").expect("python pattern")
+});
+
+/// JDK and JRE directories named after their release, e.g.
+/// `usr/lib/jvm/java-17-openjdk-amd64` or `opt/java/jdk-21.0.2+13`.
+static JAVA_DIR_VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?:java|jdk|jre|openjdk)-?(\d+(?:\.\d+)*)").expect("java pattern")
+});
+
+/// Node.js installs named after their release, e.g. nvm's
+/// `root/.nvm/versions/node/v20.11.1` or `opt/node-v20.11.1-linux-x64`.
+static NODE_DIR_VERSION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"node[-/]v(\d+\.\d+\.\d+)").expect("node pattern"));
+
+/// Files only a real JDK or JRE has next to `bin/java`, unlike the
+/// `usr/bin/java` link Debian's alternatives install.
+const JAVA_HOME_FILES: &[&str] = &["release", "lib/modules", "lib/rt.jar"];
+
+/// An installation found by its files, before its version file is read.
+struct Install {
+    kind: RuntimeKind,
+    root: PathBuf,
+    layer: usize,
+    version: Option<String>,
+    version_file: Option<PathBuf>,
+}
+
+/// Find the runtimes in the final filesystem and read their version files.
+pub fn read(info: &ImageInfo, inspector: &mut dyn Inspector) -> Result<Vec<Runtime>> {
+    let visible = merged::merge(info);
+    let installs = find(&visible);
+    let paths: Vec<PathBuf> = installs
+        .iter()
+        .filter_map(|i| i.version_file.clone())
+        .collect();
+    let contents: BTreeMap<PathBuf, Vec<u8>> = merged::read_files(info, inspector, &paths)?
+        .into_iter()
+        .collect();
+
+    let mut runtimes: Vec<Runtime> = installs
+        .into_iter()
+        .map(|install| {
+            let from_file = install
+                .version_file
+                .as_ref()
+                .and_then(|p| contents.get(p))
+                .and_then(|c| parse_version(install.kind, &String::from_utf8_lossy(c)));
+            Runtime {
+                kind: install.kind,
+                version: from_file.or(install.version),
+                path: install.root,
+                layer: install.layer,
+            }
+        })
+        .collect();
+    runtimes.sort_by(|a, b| (a.kind, &a.path).cmp(&(b.kind, &b.path)));
+    Ok(runtimes)
+}
+
+/// Installations recognized by their layout: Python's standard library,
+/// `bin/node`, `bin/java` and `bin/go` under an installation root.
+fn find(visible: &BTreeMap<PathBuf, MergedFile>) -> Vec<Install> {
+    let mut installs = Vec::new();
+    for (path, file) in visible {
+        let text = path.to_string_lossy();
+        if let Some(m) = PYTHON_STDLIB.captures(&text) {
+            let lib = Path::new(&m[1]);
+            let prefix = lib.parent().unwrap_or(Path::new(""));
+            let version = m[2].to_string();
+            installs.push(Install {
+                kind: RuntimeKind::Python,
+                root: lib.join(format!("python{version}")),
+                layer: file.layer,
+                version_file: Some(prefix.join(format!("include/python{version}/patchlevel.h"))),
+                version: Some(version),
+            });
+            continue;
+        }
+
+        let Some(bin) = path.parent().filter(|p| p.ends_with("bin")) else {
+            continue;
+        };
+        let root = bin.parent().unwrap_or(Path::new("")).to_path_buf();
+        let install = |kind, version_file: &str, version| Install {
+            kind,
+            version_file: Some(root.join(version_file)),
+            root: root.clone(),
+            layer: file.layer,
+            version,
+        };
+        match path.file_name().and_then(|n| n.to_str()) {
+            Some("node") => {
+                let version = NODE_DIR_VERSION
+                    .captures(&root.to_string_lossy())
+                    .map(|m| m[1].to_string());
+                installs.push(install(
+                    RuntimeKind::Node,
+                    "include/node/node_version.h",
+                    version,
+                ));
+            }
+            // On Java 8 a JDK has a JRE with its own `bin/java` in `jre/`
+            Some("java")
+                if JAVA_HOME_FILES
+                    .iter()
+                    .any(|f| visible.contains_key(&root.join(f)))
+                    && !(root.ends_with("jre")
+                        && root
+                            .parent()
+                            .is_some_and(|jdk| visible.contains_key(&jdk.join("bin/java")))) =>
+            {
+                let version = JAVA_DIR_VERSION
+                    .captures(&root.to_string_lossy())
+                    .map(|m| m[1].to_string());
+                installs.push(install(RuntimeKind::Java, "release", version));
+            }
+            // Only the toolchain's own `bin/go`, not a program named go
+            Some("go")
+                if visible.contains_key(&root.join("src/runtime/runtime.go"))
+                    || visible.contains_key(&root.join("VERSION")) =>
+            {
+                installs.push(install(RuntimeKind::Go, "VERSION", None));
+            }
+            _ => {}
+        }
+    }
+    installs
+}
+
+/// The version a runtime's version file states.
+fn parse_version(kind: RuntimeKind, text: &str) -> Option<String> {
+    let define = |name: &str| {
+        text.lines().find_map(|l| {
+            let rest = l
+                .trim()
+                .strip_prefix("#define")?
+                .trim_start()
+                .strip_prefix(name)?;
+            rest.starts_with([' ', '\t'])
+                .then(|| rest.trim().trim_matches('"').to_string())
+        })
+    };
+    match kind {
+        // #define PY_VERSION "3.12.2"
+        RuntimeKind::Python => define("PY_VERSION"),
+        // #define NODE_MAJOR_VERSION 20, and minor and patch likewise
+        RuntimeKind::Node => Some(format!(
+            "{}.{}.{}",
+            define("NODE_MAJOR_VERSION")?,
+            define("NODE_MINOR_VERSION")?,
+            define("NODE_PATCH_VERSION")?
+        )),
+        // JAVA_VERSION="17.0.10"
+        RuntimeKind::Java => text.lines().find_map(|l| {
+            Some(
+                l.strip_prefix("JAVA_VERSION=")?
+                    .trim_matches('"')
+                    .to_string(),
+            )
+        }),
+        // go1.22.1, then the build time on newer releases
+        RuntimeKind::Go => text
+            .lines()
+            .next()?
+            .trim()
+            .strip_prefix("go")
+            .map(str::to_string),
+    }
+}
@@ -0,0 +1,239 @@
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+use regex::bytes::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Files larger than this aren't read; credentials live in small files.
+pub const MAX_SIZE: u64 = 1024 * 1024;
+
+/// Bits per character below which a generic `password = ...` value is taken
+/// for a placeholder or a word rather than a real secret.
+const MIN_VALUE_ENTROPY: f64 = 3.0;
+
+/// Directories full of sample keys and example configs.
+const SKIPPED_DIRS: &[&str] = &["usr/share/doc/", "usr/share/man/", "usr/share/licenses/"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    PrivateKey,
+    AwsAccessKey,
+    AwsSecretKey,
+    GcpServiceAccount,
+    GcpApiKey,
+    GithubToken,
+    SlackToken,
+    StripeKey,
+    /// Registry or package index credentials: `.docker/config.json`,
+    /// `.npmrc`, `.pypirc`, `.netrc`, `.git-credentials`
+    CredentialsFile,
+    /// A `.env` file with at least one value set
+    EnvFile,
+    /// `password = ...`, `api_key: ...` and the like, with a random-looking value
+    Generic,
+}
+
+/// A possible secret in one layer's copy of a file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secret {
+    /// Layer holding this copy of the file (base = 0)
+    pub layer: usize,
+
+    /// Normalized path, without a leading `/`
+    pub path: PathBuf,
+
+    pub kind: SecretKind,
+
+    /// 1-based line of the match; `None` when the whole file is the finding
+    pub line: Option<usize>,
+
+    /// What matched, with the secret itself masked
+    pub excerpt: String,
+
+    /// The final filesystem doesn't show this copy, but it can still be
+    /// extracted from the layer
+    pub hidden: bool,
+}
+
+struct Rule {
+    kind: SecretKind,
+    pattern: Regex,
+    /// Capture group holding the secret, for masking and entropy checks
+    group: usize,
+}
+
+static RULES: LazyLock<Vec<Rule>> = LazyLock::new(|| {
+    let rule = |kind, pattern: &str, group| Rule {
+        kind,
+        pattern: Regex::new(pattern).expect("secret pattern"),
+        group,
+    };
+    vec![
+        rule(
+            SecretKind::PrivateKey,
+            r"-----BEGIN ((?:RSA |EC |DSA |OPENSSH |ENCRYPTED |PGP )?PRIVATE KEY(?: BLOCK)?)-----",
+            1,
+        ),
+        rule(
+            SecretKind::AwsAccessKey,
+            r"\b((?:AKIA|ASIA)[0-9A-Z]{16})\b",
+            1,
+        ),
+        rule(
+            SecretKind::AwsSecretKey,
+            r#"(?i)aws_?secret_?access_?key["']?\s*[=:]\s*["']?([A-Za-z0-9/+=]{40})\b"#,
+            1,
+        ),
+        rule(
+            SecretKind::GcpServiceAccount,
+            r#""type"\s*:\s*"(service_account)""#,
+            1,
+        ),
+        rule(SecretKind::GcpApiKey, r"\b(AIza[0-9A-Za-z_-]{35})\b", 1),
+        rule(
+            SecretKind::GithubToken,
+            r"\b(gh[pousr]_[A-Za-z0-9]{36,}|github_pat_[A-Za-z0-9_]{60,})\b",
+            1,
+        ),
+        rule(
+            SecretKind::SlackToken,
+            r"\b(xox[abprs]-[A-Za-z0-9-]{10,})\b",
+            1,
+        ),
+        rule(
+            SecretKind::StripeKey,
+            r"\b((?:sk|rk)_live_[A-Za-z0-9]{24,})\b",
+            1,
+        ),
+        rule(
+            SecretKind::Generic,
+            r#"(?i)(?:password|passwd|secret|token|api_?key|access_?key|client_?secret)["']?\s*[=:]\s*["']?([^\s"',;]{8,})"#,
+            1,
+        ),
+    ]
+});
+
+/// Whether a file at `path` (normalized) of `size` bytes is worth reading.
+pub fn candidate(path: &Path, size: u64) -> bool {
+    let text = path.to_string_lossy();
+    size > 0 && size <= MAX_SIZE && !SKIPPED_DIRS.iter().any(|d| text.starts_with(d))
+}
+
+/// Call `found` with the kind, line and masked excerpt of every possible
+/// secret in `content`.
+pub fn scan(path: &Path, content: &[u8], mut found: impl FnMut(SecretKind, Option<usize>, String)) {
+    // Binaries would match token patterns by accident
+    if content.contains(&0) {
+        return;
+    }
+    if let Some(kind) = file_kind(path, content) {
+        found(kind, None, String::new());
+    }
+    for (number, line) in content.split(|&b| b == b'\n').enumerate() {
+        for rule in RULES.iter() {
+            let Some(secret) = rule.pattern.captures(line).and_then(|c| c.get(rule.group)) else {
+                continue;
+            };
+            if rule.kind == SecretKind::Generic && !looks_random(secret.as_bytes()) {
+                continue;
+            }
+            found(
+                rule.kind,
+                Some(number + 1),
+                mask(line, secret.start(), secret.end(), rule.kind),
+            );
+            // One finding per line; the specific rules come first
+            break;
+        }
+    }
+}
+
+/// Findings about the file as a whole, judged by its name.
+fn file_kind(path: &Path, content: &[u8]) -> Option<SecretKind> {
+    let name = path.file_name()?.to_string_lossy();
+    let text = String::from_utf8_lossy(content);
+    let has_value = |line: &str| {
+        let line = line.trim();
+        !line.starts_with('#')
+            && line
+                .split_once('=')
+                .is_some_and(|(_, v)| !v.trim().trim_matches(['"', '\'']).is_empty())
+    };
+    let parent_is = |dir: &str| {
+        path.parent()
+            .and_then(Path::file_name)
+            .is_some_and(|p| p == dir)
+    };
+    match name.as_ref() {
+        ".env" => text.lines().any(has_value).then_some(SecretKind::EnvFile),
+        n if n.starts_with(".env.")
+            && !["example", "sample", "template", "dist"]
+                .iter()
+                .any(|s| n.ends_with(s)) =>
+        {
+            text.lines().any(has_value).then_some(SecretKind::EnvFile)
+        }
+        ".git-credentials" | ".netrc" => Some(SecretKind::CredentialsFile),
+        ".npmrc" => text
+            .contains("_authToken")
+            .then_some(SecretKind::CredentialsFile),
+        ".pypirc" => text
+            .contains("password")
+            .then_some(SecretKind::CredentialsFile),
+        "config.json" if parent_is(".docker") => text
+            .contains("\"auth\"")
+            .then_some(SecretKind::CredentialsFile),
+        "credentials" if parent_is(".aws") => Some(SecretKind::CredentialsFile),
+        _ => None,
+    }
+}
+
+/// Shannon entropy of `value` in bits per byte, to tell `hunter2` and
+/// `changeme` from generated secrets.
+fn looks_random(value: &[u8]) -> bool {
+    if value.starts_with(b"${") || value.starts_with(b"<") || value.starts_with(b"%") {
+        return false;
+    }
+    let mut counts = [0u32; 256];
+    for &b in value {
+        counts[b as usize] += 1;
+    }
+    let len = value.len() as f64;
+    let entropy: f64 = counts
+        .iter()
+        .filter(|&&c| c > 0)
+        .map(|&c| {
+            let p = f64::from(c) / len;
+            -p * p.log2()
+        })
+        .sum();
+    entropy >= MIN_VALUE_ENTROPY
+}
+
+/// The line around the match with all but the first four characters of the
+/// secret replaced. Key headers are names, not secrets, so they stay.
+fn mask(line: &[u8], start: usize, end: usize, kind: SecretKind) -> String {
+    let from = start.saturating_sub(40);
+    let to = (end + 20).min(line.len());
+    let before = String::from_utf8_lossy(&line[from..start]);
+    let secret = String::from_utf8_lossy(&line[start..end]);
+    let after = String::from_utf8_lossy(&line[end..to]);
+    let secret = if kind == SecretKind::PrivateKey || kind == SecretKind::GcpServiceAccount {
+        secret.into_owned()
+    } else {
+        let shown: String = secret.chars().take(4).collect();
+        format!(
+            "{shown}{}",
+            "*".repeat(secret.chars().count().saturating_sub(4).min(12))
+        )
+    };
+    format!(
+        "{}{}{secret}{after}{}",
+        if from > 0 { "…" } else { "" },
+        before.trim_start(),
+        if to < line.len() { "…" } else { "" }
+    )
+    .trim_end()
+    .to_string()
+}
@@ -0,0 +1,74 @@
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+use super::compare::{self, LayerReuse};
+use crate::inspector::ImageInfo;
+
+/// Where the disk used by a set of images goes, counting each layer once.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SharedReport {
+    /// Sum of the image sizes, as if no layer were shared
+    pub total_size: u64,
+
+    /// Bytes of every distinct layer; what the images actually take on disk
+    pub disk_size: u64,
+
+    pub images: Vec<SharedImage>,
+
+    /// Layers used by more than one image, largest first
+    pub layers: Vec<LayerReuse>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SharedImage {
+    /// Reference the image was inspected under
+    pub reference: String,
+
+    /// Other references to the same image ID
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    pub size: u64,
+
+    /// Bytes in layers another image also uses
+    pub shared_size: u64,
+
+    /// Bytes in layers only this image uses; what removing it would free
+    pub unique_size: u64,
+
+    pub layer_count: usize,
+}
+
+/// Attribute every layer of `infos` to the images using it. `images` pairs
+/// each image's reference with its aliases, in the same order as `infos`.
+pub fn analyze(infos: &[ImageInfo], images: Vec<(String, Vec<String>)>) -> SharedReport {
+    let comparison = compare::compare(infos);
+    let images = images
+        .into_iter()
+        .zip(infos.iter().zip(comparison.images))
+        .map(|((reference, aliases), (info, summary))| SharedImage {
+            reference,
+            aliases,
+            size: info.total_size,
+            shared_size: info.total_size.saturating_sub(summary.unique_size),
+            unique_size: summary.unique_size,
+            layer_count: summary.layer_count,
+        })
+        .collect();
+
+    let disk_size = comparison.layers.iter().map(|l| l.size).sum();
+    let mut layers: Vec<LayerReuse> = comparison
+        .layers
+        .into_iter()
+        .filter(|l| l.images.len() > 1)
+        .collect();
+    layers.sort_by_key(|l| Reverse(l.size));
+
+    SharedReport {
+        total_size: infos.iter().map(|i| i.total_size).sum(),
+        disk_size,
+        images,
+        layers,
+    }
+}
@@ -0,0 +1,203 @@
+//! Checking declared licenses against SPDX license expressions, so SPDX
+//! documents only carry identifiers a validator accepts.
+
+/// SPDX license list identifiers that packages commonly declare, including
+/// the deprecated `-only`-less forms older packages still use.
+const LICENSES: &[&str] = &[
+    "0BSD",
+    "AFL-2.1",
+    "AFL-3.0",
+    "AGPL-3.0",
+    "AGPL-3.0-only",
+    "AGPL-3.0-or-later",
+    "Apache-1.0",
+    "Apache-1.1",
+    "Apache-2.0",
+    "APSL-2.0",
+    "Artistic-1.0",
+    "Artistic-1.0-Perl",
+    "Artistic-2.0",
+    "Beerware",
+    "blessing",
+    "BSD-1-Clause",
+    "BSD-2-Clause",
+    "BSD-2-Clause-Patent",
+    "BSD-3-Clause",
+    "BSD-3-Clause-Clear",
+    "BSD-4-Clause",
+    "BSD-4-Clause-UC",
+    "BSL-1.0",
+    "bzip2-1.0.6",
+    "CC-BY-3.0",
+    "CC-BY-4.0",
+    "CC-BY-SA-3.0",
+    "CC-BY-SA-4.0",
+    "CC0-1.0",
+    "CDDL-1.0",
+    "CDDL-1.1",
+    "CECILL-2.1",
+    "curl",
+    "EPL-1.0",
+    "EPL-2.0",
+    "EUPL-1.1",
+    "EUPL-1.2",
+    "FSFAP",
+    "FSFUL",
+    "FSFULLR",
+    "FTL",
+    "GFDL-1.2",
+    "GFDL-1.2-only",
+    "GFDL-1.2-or-later",
+    "GFDL-1.3",
+    "GFDL-1.3-only",
+    "GFDL-1.3-or-later",
+    "GPL-1.0",
+    "GPL-1.0+",
+    "GPL-1.0-only",
+    "GPL-1.0-or-later",
+    "GPL-2.0",
+    "GPL-2.0+",
+    "GPL-2.0-only",
+    "GPL-2.0-or-later",
+    "GPL-3.0",
+    "GPL-3.0+",
+    "GPL-3.0-only",
+    "GPL-3.0-or-later",
+    "HPND",
+    "ICU",
+    "IJG",
+    "Imlib2",
+    "Info-ZIP",
+    "IPL-1.0",
+    "ISC",
+    "LGPL-2.0",
+    "LGPL-2.0+",
+    "LGPL-2.0-only",
+    "LGPL-2.0-or-later",
+    "LGPL-2.1",
+    "LGPL-2.1+",
+    "LGPL-2.1-only",
+    "LGPL-2.1-or-later",
+    "LGPL-3.0",
+    "LGPL-3.0+",
+    "LGPL-3.0-only",
+    "LGPL-3.0-or-later",
+    "Libpng",
+    "libpng-2.0",
+    "libtiff",
+    "LPPL-1.3c",
+    "MIT",
+    "MIT-0",
+    "MIT-CMU",
+    "MPL-1.1",
+    "MPL-2.0",
+    "MPL-2.0-no-copyleft-exception",
+    "MS-PL",
+    "NCSA",
+    "NTP",
+    "OFL-1.1",
+    "OLDAP-2.8",
+    "OpenSSL",
+    "PHP-3.0",
+    "PHP-3.01",
+    "PostgreSQL",
+    "PSF-2.0",
+    "Python-2.0",
+    "Ruby",
+    "SGI-B-2.0",
+    "Sleepycat",
+    "SMLNJ",
+    "TCL",
+    "Unicode-3.0",
+    "Unicode-DFS-2015",
+    "Unicode-DFS-2016",
+    "Unlicense",
+    "UPL-1.0",
+    "Vim",
+    "W3C",
+    "WTFPL",
+    "X11",
+    "XFree86-1.1",
+    "Zlib",
+    "zlib-acknowledgement",
+    "ZPL-2.1",
+];
+
+/// SPDX exceptions that may follow `WITH`.
+const EXCEPTIONS: &[&str] = &[
+    "Autoconf-exception-2.0",
+    "Autoconf-exception-3.0",
+    "Bison-exception-2.2",
+    "Classpath-exception-2.0",
+    "Font-exception-2.0",
+    "GCC-exception-2.0",
+    "GCC-exception-3.1",
+    "Linux-syscall-note",
+    "LLVM-exception",
+    "OCaml-LGPL-linking-exception",
+    "OpenJDK-assembly-exception-1.0",
+    "openvpn-openssl-exception",
+    "Qt-GPL-exception-1.0",
+    "Qt-LGPL-exception-1.1",
+    "Universal-FOSS-exception-1.0",
+];
+
+/// `declared` as an SPDX license expression with canonical identifiers, or
+/// `None` if it isn't one or names a license not in the lists above.
+/// Identifiers and operators match case-insensitively, as SPDX allows.
+pub fn expression(declared: &str) -> Option<String> {
+    let spaced = declared.replace('(', " ( ").replace(')', " ) ");
+    let tokens: Vec<&str> = spaced.split_whitespace().collect();
+    let mut out = Vec::with_capacity(tokens.len());
+    let mut rest = tokens.as_slice();
+    compound(&mut rest, &mut out)?;
+    rest.is_empty()
+        .then(|| out.join(" ").replace("( ", "(").replace(" )", ")"))
+}
+
+/// `simple (AND|OR simple)*`, where a simple expression may be parenthesized.
+fn compound(rest: &mut &[&str], out: &mut Vec<String>) -> Option<()> {
+    simple(rest, out)?;
+    while let Some(op) = rest.first().and_then(|t| operator(t, &["AND", "OR"])) {
+        out.push(op.to_string());
+        *rest = &rest[1..];
+        simple(rest, out)?;
+    }
+    Some(())
+}
+
+/// `( compound )`, or a license with an optional `+` and `WITH` exception.
+fn simple(rest: &mut &[&str], out: &mut Vec<String>) -> Option<()> {
+    let (&token, tail) = rest.split_first()?;
+    *rest = tail;
+    if token == "(" {
+        out.push("(".to_string());
+        compound(rest, out)?;
+        let (&close, tail) = rest.split_first()?;
+        (close == ")").then_some(())?;
+        *rest = tail;
+        out.push(")".to_string());
+        return Some(());
+    }
+    // Deprecated ids such as "GPL-2.0+" are listed with their `+`
+    let license = match (find(LICENSES, token), token.strip_suffix('+')) {
+        (Some(id), _) => id.to_string(),
+        (None, Some(base)) => format!("{}+", find(LICENSES, base)?),
+        (None, None) => return None,
+    };
+    out.push(license);
+    if let Some(with) = rest.first().and_then(|t| operator(t, &["WITH"])) {
+        let exception = find(EXCEPTIONS, rest.get(1)?)?;
+        out.extend([with.to_string(), exception.to_string()]);
+        *rest = &rest[2..];
+    }
+    Some(())
+}
+
+fn operator(token: &str, ops: &[&'static str]) -> Option<&'static str> {
+    ops.iter().copied().find(|op| op.eq_ignore_ascii_case(token))
+}
+
+fn find(list: &[&'static str], token: &str) -> Option<&'static str> {
+    list.iter().copied().find(|id| id.eq_ignore_ascii_case(token))
+}
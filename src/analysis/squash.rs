@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::merged::{self, MergedFile};
+use crate::inspector::ImageInfo;
+
+/// What squashing a contiguous range of layers into one would produce.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SquashEstimate {
+    /// First squashed layer (index into the image's layers)
+    pub from: usize,
+
+    /// Last squashed layer, inclusive
+    pub to: usize,
+
+    /// File bytes in the range's layers as they are today
+    pub original_size: u64,
+
+    /// File bytes in the single layer that would replace them
+    pub squashed_size: u64,
+
+    /// Files the squashed layer would contain
+    pub file_count: usize,
+
+    /// Whiteouts that must stay because they hide files from below the range
+    pub kept_whiteouts: usize,
+
+    /// Image size and layer count before and after
+    pub image_size: u64,
+    pub squashed_image_size: u64,
+    pub layer_count: usize,
+    pub squashed_layer_count: usize,
+}
+
+/// Replay layers `from..=to` on their own: overwrites collapse to the last
+/// copy and files deleted within the range vanish, while whiteouts aimed at
+/// lower layers are carried into the squashed layer.
+///
+/// `from` and `to` index layers (base = 0). Fails when `from..=to` is empty
+/// or runs past the last layer, including for an image without layers; the
+/// error numbers layers from 1, as text output does.
+pub fn simulate(info: &ImageInfo, from: usize, to: usize) -> Result<SquashEstimate> {
+    if info.layers.is_empty() {
+        bail!(t!("squash-no-layers"));
+    }
+    let Some(range) = info.layers.get(from..=to).filter(|r| !r.is_empty()) else {
+        bail!(t!(
+            "squash-bad-range",
+            from = from + 1,
+            to = to + 1,
+            last = info.layers.len()
+        ));
+    };
+
+    let mut files: BTreeMap<PathBuf, MergedFile> = BTreeMap::new();
+    for (offset, layer) in range.iter().enumerate() {
+        merged::apply_layer(&mut files, from + offset, layer);
+    }
+
+    // A whiteout still matters if what it hides could come from below the range.
+    let mut below: BTreeMap<PathBuf, MergedFile> = BTreeMap::new();
+    for (index, layer) in info.layers[..from].iter().enumerate() {
+        merged::apply_layer(&mut below, index, layer);
+    }
+    let mut kept_whiteouts = 0;
+    for layer in range {
+        for entry in layer.files.iter().filter(|f| f.is_whiteout) {
+            let path = merged::normalize(&entry.path);
+            let hidden = path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let target = match name.strip_prefix(".wh.") {
+                Some(".wh..opq") | None => hidden,
+                Some(name) => hidden.join(name),
+            };
+            let hides_lower = below
+                .range(target.clone()..)
+                .next()
+                .is_some_and(|(p, _)| p.starts_with(&target));
+            if hides_lower {
+                kept_whiteouts += 1;
+            }
+        }
+    }
+
+    // Both sides count file contents, not tar headers or padding
+    let original_size: u64 = range
+        .iter()
+        .flat_map(|l| &l.files)
+        .filter(|f| !f.is_whiteout)
+        .map(|f| f.size)
+        .sum();
+    let squashed_size: u64 = files.values().map(|f| f.size).sum();
+    let saved = original_size.saturating_sub(squashed_size);
+    let layer_count = info.layers.len();
+
+    Ok(SquashEstimate {
+        from,
+        to,
+        original_size,
+        squashed_size,
+        file_count: files.len(),
+        kept_whiteouts,
+        image_size: info.total_size,
+        squashed_image_size: info.total_size.saturating_sub(saved),
+        layer_count,
+        squashed_layer_count: layer_count - (to - from),
+    })
+}
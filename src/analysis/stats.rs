@@ -0,0 +1,92 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::dirs::{self, DirSize};
+use super::merged;
+use crate::inspector::ImageInfo;
+
+/// Largest top-level directories to list.
+const TOP_DIRS: usize = 10;
+
+/// A few numbers that describe an image, for dashboards and comparisons.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageStats {
+    pub layers: usize,
+
+    /// Uncompressed, as in `inspect`
+    pub total_size: u64,
+
+    /// Only when the backend knows every layer's
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<u64>,
+
+    /// Files in the final filesystem
+    pub files: usize,
+
+    /// Directories of the final filesystem that hold files, at any depth
+    pub directories: usize,
+
+    /// Of the final filesystem's files; the lower middle one for an even
+    /// count
+    pub median_file_size: u64,
+
+    pub max_file_size: u64,
+
+    /// Path of the largest file; absent without files
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub largest_file: Option<PathBuf>,
+
+    /// Files across all layers, counting each copy and deleted ones
+    pub layer_files: usize,
+
+    /// Whiteouts across all layers
+    pub whiteouts: usize,
+
+    /// Largest top-level directories of the final filesystem, largest first
+    pub top_directories: Vec<DirSize>,
+}
+
+pub fn analyze(info: &ImageInfo) -> ImageStats {
+    let files = merged::merge(info);
+
+    let mut sizes: Vec<u64> = files.values().map(|f| f.size).collect();
+    sizes.sort_unstable();
+    let largest = files.values().max_by_key(|f| f.size);
+
+    let directories = dirs::rollup(
+        files.values().map(|f| (f.path.as_path(), f.size)),
+        Path::new(""),
+        usize::MAX,
+    )
+    .len();
+    let mut top_directories: Vec<DirSize> = dirs::rollup(
+        files.values().map(|f| (f.path.as_path(), f.size)),
+        Path::new(""),
+        1,
+    )
+    .into_values()
+    .collect();
+    top_directories.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+    top_directories.truncate(TOP_DIRS);
+
+    let entries = info.layers.iter().flat_map(|l| &l.files);
+    let whiteouts = entries.clone().filter(|f| f.is_whiteout).count();
+
+    ImageStats {
+        layers: info.layers.len(),
+        total_size: info.total_size,
+        compressed_size: info.compressed_size(),
+        files: files.len(),
+        directories,
+        median_file_size: sizes
+            .get(sizes.len().saturating_sub(1) / 2)
+            .copied()
+            .unwrap_or(0),
+        max_file_size: sizes.last().copied().unwrap_or(0),
+        largest_file: largest.map(|f| f.path.clone()),
+        layer_files: entries.count() - whiteouts,
+        whiteouts,
+        top_directories,
+    }
+}
@@ -0,0 +1,111 @@
+use std::cmp::Reverse;
+use std::collections::BTreeSet;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged;
+use crate::inspector::ImageInfo;
+
+/// Files a runtime image can do without, and the directory holding them.
+const CANDIDATES: &[(StripKind, &str)] = &[
+    (StripKind::Man, "usr/share/man"),
+    (StripKind::Docs, "usr/share/doc"),
+    (StripKind::Info, "usr/share/info"),
+    (StripKind::Locales, "usr/share/locale"),
+    (StripKind::DebugSymbols, "usr/lib/debug"),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StripKind {
+    Man,
+    Docs,
+    Info,
+    Locales,
+    /// Separate debug symbols from `-dbg` packages
+    DebugSymbols,
+}
+
+/// Documentation, translations and debug symbols in the final filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripCandidate {
+    pub kind: StripKind,
+
+    /// Directory, without a leading `/`
+    pub path: String,
+
+    /// Bytes in the final filesystem; what removing them in the layers that
+    /// add them would save
+    pub size: u64,
+
+    pub files: usize,
+
+    /// Layers providing those files, in layer order
+    pub layers: Vec<usize>,
+}
+
+/// What is worth stripping and the command that would do it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StripReport {
+    /// Largest first
+    pub candidates: Vec<StripCandidate>,
+
+    /// Sum of the candidates' sizes
+    pub size: u64,
+
+    /// `rm` to append to the `RUN` instructions that install these files
+    pub snippet: String,
+}
+
+/// Strip candidates with any bytes in the final filesystem, or `None` if
+/// there are none.
+pub fn detect(info: &ImageInfo) -> Option<StripReport> {
+    let files = merged::merge(info);
+    let mut candidates: Vec<StripCandidate> = CANDIDATES
+        .iter()
+        .map(|(kind, dir)| StripCandidate {
+            kind: *kind,
+            path: dir.to_string(),
+            size: 0,
+            files: 0,
+            layers: Vec::new(),
+        })
+        .collect();
+    let mut layers: Vec<BTreeSet<usize>> = vec![BTreeSet::new(); CANDIDATES.len()];
+
+    for file in files.values() {
+        let index = CANDIDATES
+            .iter()
+            .position(|(_, dir)| file.path.starts_with(Path::new(dir)));
+        let Some(index) = index else {
+            continue;
+        };
+        candidates[index].size += file.size;
+        candidates[index].files += 1;
+        layers[index].insert(file.layer);
+    }
+
+    for (candidate, layers) in candidates.iter_mut().zip(layers) {
+        candidate.layers = layers.into_iter().collect();
+    }
+    candidates.retain(|c| c.size > 0);
+    if candidates.is_empty() {
+        return None;
+    }
+    candidates.sort_by_key(|c| Reverse(c.size));
+
+    let targets: Vec<String> = candidates
+        .iter()
+        .map(|c| match c.kind {
+            StripKind::DebugSymbols => format!("/{}", c.path),
+            // Keep the directories; some packages' postinst scripts expect them
+            _ => format!("/{}/*", c.path),
+        })
+        .collect();
+    Some(StripReport {
+        size: candidates.iter().map(|c| c.size).sum(),
+        snippet: format!("rm -rf {}", targets.join(" ")),
+        candidates,
+    })
+}
@@ -0,0 +1,231 @@
+use std::cmp::Reverse;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged;
+use crate::inspector::ImageInfo;
+
+/// Directories executables are looked for in.
+const BIN_DIRS: &[&str] = &["bin/", "usr/bin/", "usr/local/bin/", "sbin/", "usr/sbin/"];
+
+/// Build tools to look for: what they're called, programs (matched with any
+/// target triple prefix or version suffix, e.g. `x86_64-linux-gnu-gcc-12`)
+/// and the directories they install their support files under.
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "gcc",
+        kind: ToolKind::Compiler,
+        programs: &["gcc", "cc", "cpp", "g++", "c++", "gfortran"],
+        dirs: &["usr/lib/gcc/", "usr/libexec/gcc/", "usr/include/c++/"],
+    },
+    Tool {
+        name: "clang",
+        kind: ToolKind::Compiler,
+        programs: &["clang", "clang++", "clangd"],
+        dirs: &["usr/lib/llvm-", "usr/lib/clang/"],
+    },
+    Tool {
+        name: "binutils",
+        kind: ToolKind::Compiler,
+        programs: &[
+            "ld", "ld.bfd", "ld.gold", "as", "objdump", "objcopy", "readelf",
+        ],
+        dirs: &[],
+    },
+    Tool {
+        name: "rust",
+        kind: ToolKind::Compiler,
+        programs: &["rustc", "cargo", "rustup"],
+        dirs: &[
+            "usr/local/rustup/",
+            "usr/local/cargo/",
+            "root/.rustup/",
+            "root/.cargo/",
+        ],
+    },
+    Tool {
+        name: "go",
+        kind: ToolKind::Compiler,
+        programs: &["go", "gofmt"],
+        dirs: &["usr/local/go/", "usr/lib/go-"],
+    },
+    Tool {
+        name: "jdk",
+        kind: ToolKind::Compiler,
+        programs: &["javac", "jar", "jlink"],
+        dirs: &[],
+    },
+    Tool {
+        name: "make",
+        kind: ToolKind::BuildTool,
+        programs: &["make", "gmake"],
+        dirs: &[],
+    },
+    Tool {
+        name: "cmake",
+        kind: ToolKind::BuildTool,
+        programs: &["cmake", "ctest", "cpack"],
+        dirs: &["usr/share/cmake-", "usr/local/share/cmake-"],
+    },
+    Tool {
+        name: "autotools",
+        kind: ToolKind::BuildTool,
+        programs: &["autoconf", "automake", "libtool", "libtoolize", "m4"],
+        dirs: &[
+            "usr/share/autoconf/",
+            "usr/share/automake-",
+            "usr/share/libtool/",
+        ],
+    },
+    Tool {
+        name: "ninja",
+        kind: ToolKind::BuildTool,
+        programs: &["ninja", "meson"],
+        dirs: &[],
+    },
+    Tool {
+        name: "pkg-config",
+        kind: ToolKind::BuildTool,
+        programs: &["pkg-config", "pkgconf"],
+        dirs: &[],
+    },
+    Tool {
+        name: "include",
+        kind: ToolKind::Headers,
+        programs: &[],
+        dirs: &["usr/include/", "usr/local/include/"],
+    },
+    Tool {
+        name: "pip",
+        kind: ToolKind::PackageManager,
+        programs: &["pip", "pip3", "poetry", "pipenv", "uv"],
+        dirs: &[],
+    },
+    Tool {
+        name: "npm",
+        kind: ToolKind::PackageManager,
+        programs: &["npm", "npx", "yarn", "pnpm", "corepack"],
+        dirs: &[
+            "usr/local/lib/node_modules/npm/",
+            "usr/lib/node_modules/npm/",
+        ],
+    },
+    Tool {
+        name: "composer",
+        kind: ToolKind::PackageManager,
+        programs: &["composer"],
+        dirs: &[],
+    },
+    Tool {
+        name: "maven",
+        kind: ToolKind::PackageManager,
+        programs: &["mvn", "gradle"],
+        dirs: &["usr/share/maven/", "opt/gradle/"],
+    },
+];
+
+struct Tool {
+    name: &'static str,
+    kind: ToolKind,
+    programs: &'static [&'static str],
+    dirs: &'static [&'static str],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolKind {
+    /// Compilers, linkers and assemblers
+    Compiler,
+    /// make, cmake and friends
+    BuildTool,
+    /// C/C++ headers from `-dev` packages
+    Headers,
+    /// Language package managers, only needed to install dependencies
+    PackageManager,
+}
+
+/// A build tool left in the final filesystem.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Toolchain {
+    pub name: String,
+
+    pub kind: ToolKind,
+
+    /// Its programs found, without a leading `/`
+    pub programs: Vec<String>,
+
+    /// Bytes of its programs and support files in the final filesystem
+    pub size: u64,
+
+    pub files: usize,
+}
+
+/// Build tools present in the final filesystem, largest first. Each file
+/// counts towards the first tool it belongs to.
+pub fn detect(info: &ImageInfo) -> Vec<Toolchain> {
+    let mut found: Vec<Toolchain> = TOOLS
+        .iter()
+        .map(|tool| Toolchain {
+            name: tool.name.to_string(),
+            kind: tool.kind,
+            programs: Vec::new(),
+            size: 0,
+            files: 0,
+        })
+        .collect();
+
+    for file in merged::merge(info).values() {
+        let path = file.path.to_string_lossy();
+        let program = BIN_DIRS
+            .iter()
+            .find_map(|dir| path.strip_prefix(dir))
+            .filter(|name| !name.contains('/'))
+            .map(base_name);
+        let provides = |tool: &Tool| {
+            program.is_some_and(|p| tool.programs.iter().any(|prog| is_program(p, prog)))
+        };
+        let owner = TOOLS
+            .iter()
+            .position(|tool| provides(tool) || tool.dirs.iter().any(|dir| path.starts_with(dir)));
+        let Some(index) = owner else {
+            continue;
+        };
+        let entry = &mut found[index];
+        if provides(&TOOLS[index]) {
+            entry.programs.push(path.to_string());
+        }
+        entry.size += file.size;
+        entry.files += 1;
+    }
+
+    // A support directory alone (say a stray `usr/lib/gcc`) isn't a
+    // toolchain; headers have no programs to begin with
+    let mut found: Vec<Toolchain> = found
+        .into_iter()
+        .filter(|t| t.files > 0 && (!t.programs.is_empty() || t.kind == ToolKind::Headers))
+        .collect();
+    found.sort_by_key(|t| Reverse(t.size));
+    found
+}
+
+/// `gcc-12` from `x86_64-linux-gnu-gcc-12`: the program name without a
+/// target triple in front.
+fn base_name(name: &str) -> &str {
+    match name.find("-linux-") {
+        Some(i) => name[i + "-linux-".len()..]
+            .split_once('-')
+            .map_or(name, |(_, rest)| rest),
+        None => name,
+    }
+}
+
+/// Whether `name` is `program`, maybe with a version behind it (`gcc-12`,
+/// `pip3.11`).
+fn is_program(name: &str, program: &str) -> bool {
+    name.strip_prefix(program).is_some_and(|version| {
+        version
+            .trim_start_matches('-')
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '.')
+    })
+}
@@ -0,0 +1,84 @@
+use std::io::Read;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+
+use crate::inspector::checksum::HashingReader;
+use crate::inspector::{ImageInfo, Inspector};
+
+/// Leading bytes of a zstd frame; `read_layer` only undoes gzip, so such
+/// layers can't be hashed uncompressed.
+const ZSTD_MAGIC: &[u8] = b"\x28\xb5\x2f\xfd";
+
+/// Layer contents hashed against the diff_ids the config declares (`--verify`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyReport {
+    /// Layers whose uncompressed content hashes to their diff_id
+    pub verified: usize,
+
+    /// Layers with no blob to hash, such as unpacked overlay2 directories, or
+    /// a zstd blob
+    pub skipped: usize,
+
+    /// Layers whose content doesn't match, base first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mismatches: Vec<DiffIdMismatch>,
+}
+
+/// A layer whose content isn't what its diff_id says.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiffIdMismatch {
+    /// Layer index (base = 0)
+    pub layer: usize,
+
+    /// The diff_id the config declares
+    pub expected: String,
+
+    /// `sha256:<hex>` of the uncompressed content; absent if the blob
+    /// couldn't be read to the end
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub actual: Option<String>,
+
+    /// Why the blob couldn't be read
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Hash every layer the backend has a blob for, uncompressed, and compare it
+/// with the layer's diff_id.
+pub fn verify(info: &ImageInfo, inspector: &mut dyn Inspector) -> Result<VerifyReport> {
+    let mut report = VerifyReport::default();
+    for (i, layer) in info.layers.iter().enumerate() {
+        // Unpacked layers would be hashed as a tar peel wrote, not the original
+        let Some(mut blob) = inspector.read_blob(layer)? else {
+            report.skipped += 1;
+            continue;
+        };
+        let mut magic = [0u8; 4];
+        let read = blob.read(&mut magic)?;
+        if magic[..read] == *ZSTD_MAGIC {
+            report.skipped += 1;
+            continue;
+        }
+
+        let actual = inspector
+            .read_layer(layer)
+            .and_then(|reader| Ok(HashingReader::new(reader).finish()?));
+        match actual {
+            Ok(actual) if actual == layer.digest => report.verified += 1,
+            Ok(actual) => report.mismatches.push(DiffIdMismatch {
+                layer: i,
+                expected: layer.digest.clone(),
+                actual: Some(actual),
+                error: None,
+            }),
+            Err(e) => report.mismatches.push(DiffIdMismatch {
+                layer: i,
+                expected: layer.digest.clone(),
+                actual: None,
+                error: Some(format!("{e:#}")),
+            }),
+        }
+    }
+    Ok(report)
+}
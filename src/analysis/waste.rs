@@ -0,0 +1,78 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use super::merged::{self, MergedFile};
+use crate::inspector::ImageInfo;
+
+/// Number of offending paths kept in the report.
+const TOP_PATHS: usize = 20;
+
+/// Bytes shipped in lower layers that the final filesystem never shows.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WasteReport {
+    /// Total bytes that a rebuild without the shadowed copies would save
+    pub wasted_size: u64,
+
+    /// Share of the image's bytes that end up visible, from 0.0 to 1.0
+    pub efficiency: f64,
+
+    /// Paths with the most wasted bytes, largest first
+    pub paths: Vec<WastedPath>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WastedPath {
+    pub path: PathBuf,
+
+    /// Bytes in lower-layer copies that were overwritten or deleted
+    pub wasted_size: u64,
+
+    /// Layers (by index) that shipped a copy that was later shadowed
+    pub layers: Vec<usize>,
+
+    /// Whether the path is gone from the final filesystem
+    pub deleted: bool,
+}
+
+/// Replay the layers and total up every file that a later layer overwrote or deleted.
+pub fn analyze(info: &ImageInfo) -> WasteReport {
+    let mut files: BTreeMap<PathBuf, MergedFile> = BTreeMap::new();
+    let mut shadowed: BTreeMap<PathBuf, (u64, Vec<usize>)> = BTreeMap::new();
+
+    for (index, layer) in info.layers.iter().enumerate() {
+        for old in merged::apply_layer(&mut files, index, layer) {
+            let entry = shadowed.entry(old.path).or_default();
+            entry.0 += old.size;
+            entry.1.push(old.layer);
+        }
+    }
+
+    let wasted_size: u64 = shadowed.values().map(|(size, _)| size).sum();
+    let efficiency = if info.total_size == 0 {
+        1.0
+    } else {
+        1.0 - wasted_size as f64 / info.total_size as f64
+    };
+
+    let mut paths: Vec<WastedPath> = shadowed
+        .into_iter()
+        .filter(|(_, (size, _))| *size > 0)
+        .map(|(path, (wasted_size, layers))| WastedPath {
+            deleted: !files.contains_key(&path),
+            path,
+            wasted_size,
+            layers,
+        })
+        .collect();
+    paths.sort_by_key(|p| Reverse(p.wasted_size));
+    paths.truncate(TOP_PATHS);
+
+    WasteReport {
+        wasted_size,
+        efficiency,
+        paths,
+    }
+}
@@ -0,0 +1,202 @@
+use std::fs;
+use std::io;
+use std::process::{Command, Stdio};
+
+use anyhow::{Context, Result, bail};
+
+use super::compare::print_comparison;
+use super::inspect::{self, open_inspector, print_runtime_summary, select_method};
+use super::output::Output;
+use super::shared::runtime_output;
+use crate::analysis::compare;
+use crate::config::{self, AppConfig};
+use crate::probe::{RuntimeInfo, RuntimeKind};
+use crate::progress::{self, Spinner};
+use crate::style::Stylize;
+use crate::temp_files;
+
+/// Flags that only apply to `peel build`.
+pub struct Options<'a> {
+    /// Arguments passed on to the build, e.g. `-t app:dev .`
+    pub args: &'a [String],
+
+    /// Build with `docker buildx build` instead of `build`
+    pub buildx: bool,
+
+    /// Compare with the image the tag named before the build
+    pub compare: bool,
+}
+
+/// Build an image with the default runtime, then inspect what it built.
+pub fn run(
+    opts: &Options,
+    inspect_opts: &inspect::Options,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    // The comparison is a text table with no place in the JSON report
+    if opts.compare && output.is_json() {
+        bail!(t!("build-compare-json"));
+    }
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    let Some(idx) = cfg.probe.default else {
+        bail!(t!("runtime-none-installed"));
+    };
+    let rt = &cfg.probe.runtimes[idx];
+    if rt.kind.matches(&RuntimeKind::Containerd)
+        || (opts.buildx && !rt.kind.matches(&RuntimeKind::Docker))
+    {
+        bail!(t!(
+            "build-unsupported-runtime",
+            runtime = rt.kind.to_string()
+        ));
+    }
+    let tag = tag(opts.args);
+    let previous = match (opts.compare, tag) {
+        (false, _) => None,
+        (true, None) => bail!(t!("build-compare-needs-tag")),
+        // Nothing to compare with on the first build
+        (true, Some(tag)) => image_id(rt, tag),
+    };
+
+    let id = build(rt, opts)?;
+    let done = match tag {
+        Some(tag) => t!("build-done", id = short_id(&id), image = tag),
+        None => t!("build-done-untagged", id = short_id(&id)),
+    };
+    progress::done(done);
+
+    // Reading the storage directly needs root, and escalating would re-run
+    // the whole command, build included
+    let use_oci = use_oci || !rt.can_read;
+    let image = tag.unwrap_or(&id);
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    if opts.compare {
+        match previous {
+            Some(previous) if previous == id => eprintln!("{}", t!("build-unchanged").dim()),
+            Some(previous) => compare_builds(cfg, &previous, image, use_oci, no_sudo)?,
+            None => eprintln!("{}", t!("build-no-previous", image = image).dim()),
+        }
+    }
+    inspect::inspect_image(cfg, image, inspect_opts, use_oci, output, no_sudo)
+}
+
+/// Run the build and return the ID of the image it made.
+fn build(rt: &RuntimeInfo, opts: &Options) -> Result<String> {
+    let iidfile = std::env::temp_dir().join(format!("peel-build-{}.iid", std::process::id()));
+    temp_files::track(&iidfile);
+    let mut args: Vec<&str> = if opts.buildx {
+        vec!["buildx", "build"]
+    } else {
+        vec!["build"]
+    };
+    let iidfile_arg = iidfile.to_string_lossy();
+    args.extend(["--iidfile", &iidfile_arg]);
+    // buildx leaves the image in its build cache unless told to load it
+    let placed = opts.args.iter().any(|a| {
+        ["--load", "--push", "--output", "-o"]
+            .iter()
+            .any(|flag| a == flag || a.starts_with(&format!("{flag}=")))
+    });
+    if opts.buildx && !placed {
+        args.push("--load");
+    }
+    args.extend(opts.args.iter().map(String::as_str));
+
+    let cmd = rt.binary_path.display();
+    tracing::debug!("running {cmd} {}", args.join(" "));
+    // Build output goes to stderr so stdout stays free for `--json -`
+    let status = Command::new(&rt.binary_path)
+        .args(&args)
+        .stdout(Stdio::from(io::stderr()))
+        .status()
+        .with_context(|| format!("Failed to run '{cmd} {}'", args[0]))?;
+    if !status.success() {
+        temp_files::remove(&iidfile);
+        bail!(t!("build-failed", status = status.to_string()));
+    }
+    let id = fs::read_to_string(&iidfile);
+    temp_files::remove(&iidfile);
+    let id = id.context(t!("build-no-image-id"))?;
+    Ok(id.trim().to_string())
+}
+
+/// The first `-t`/`--tag` given to the build, in any form the build's flag
+/// parser takes: `--tag NAME`, `--tag=NAME`, `-t NAME`, `-tNAME`, `-t=NAME`,
+/// or after `-q` as in `-qt NAME`.
+fn tag(args: &[String]) -> Option<&str> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let value = if let Some(long) = arg.strip_prefix("--") {
+            match long.strip_prefix("tag") {
+                Some("") => "",
+                Some(value) => match value.strip_prefix('=') {
+                    Some(value) => value,
+                    None => continue,
+                },
+                None => continue,
+            }
+        } else if let Some(short) = arg.strip_prefix('-') {
+            match short.trim_start_matches('q').strip_prefix('t') {
+                Some(value) => value.strip_prefix('=').unwrap_or(value),
+                None => continue,
+            }
+        } else {
+            continue;
+        };
+        if value.is_empty() {
+            return args.next().map(String::as_str);
+        }
+        return Some(value);
+    }
+    None
+}
+
+/// The ID `image` has now; `None` if the runtime doesn't have it.
+fn image_id(rt: &RuntimeInfo, image: &str) -> Option<String> {
+    runtime_output(rt, &["image", "inspect", "--format", "{{.Id}}", image])
+        .ok()
+        .map(|out| out.trim().to_string())
+        .filter(|id| !id.is_empty())
+}
+
+/// The first 12 hex digits of an image ID, as `docker images` shows it.
+fn short_id(id: &str) -> &str {
+    let hex = id.strip_prefix("sha256:").unwrap_or(id);
+    hex.get(..12).unwrap_or(hex)
+}
+
+/// Size and layer reuse of the previous build against this one.
+fn compare_builds(
+    cfg: &AppConfig,
+    previous: &str,
+    image: &str,
+    use_oci: bool,
+    no_sudo: bool,
+) -> Result<()> {
+    // Layer digests and sizes are all we need, as with `peel compare`
+    let mut infos = Vec::with_capacity(2);
+    for reference in [previous, image] {
+        let spinner = Spinner::new(t!("spinner-resolving-image", image = reference));
+        let mut inspector = open_inspector(cfg, reference, use_oci, no_sudo, &spinner)?;
+        let info = inspector.inspect(reference)?;
+        spinner.finish(t!(
+            "resolved-image",
+            image = reference,
+            count = info.layers.len()
+        ));
+        infos.push(info);
+    }
+    let refs = [
+        t!("build-previous", id = short_id(previous)),
+        image.to_string(),
+    ];
+    print_comparison(&refs, &compare::compare(&infos));
+    println!();
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::io::{self, Write};
+
+use anyhow::{Result, bail};
+
+use super::export_layer::find_layer;
+use super::extract::with_file;
+use super::inspect::{
+    format_bytes, load_image_with_inspector, print_runtime_summary, select_method,
+};
+use crate::config;
+
+/// Print `path` from `image` to stdout, refusing files over `max_size` bytes.
+/// `layer` (index or digest prefix) picks that layer's copy instead of the
+/// final one.
+pub fn run(
+    image: &str,
+    path: &str,
+    layer: Option<&str>,
+    max_size: u64,
+    use_oci: bool,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(false, runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let layer = layer.map(|l| find_layer(&info, l)).transpose()?;
+
+    with_file(&info, inspector.as_mut(), path, layer, |content, size| {
+        if size > max_size {
+            bail!(t!(
+                "cat-too-large",
+                path = path,
+                size = format_bytes(size),
+                limit = format_bytes(max_size)
+            ));
+        }
+        let mut stdout = io::stdout().lock();
+        io::copy(content, &mut stdout)?;
+        stdout.flush()?;
+        Ok(())
+    })?;
+    Ok(())
+}
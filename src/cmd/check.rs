@@ -0,0 +1,786 @@
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+
+use super::find::glob_to_regex;
+use super::inspect::{
+    format_bytes, load_image_with_inspector, print_runtime_summary, select_method,
+};
+use super::junit::{self, Failure, TestCase, TestSuite};
+use super::output::{Annotation, Output, annotate, read_json, write_json};
+use crate::analysis::merged::{self, normalize};
+use crate::analysis::secrets::Secret;
+use crate::analysis::waste;
+use crate::config;
+use crate::inspector::{ImageInfo, Inspector};
+use crate::lint::Severity;
+use crate::style::Stylize;
+
+/// Paths listed under a failed `--forbid` or `--max-new-file`.
+const SHOWN_MATCHES: usize = 10;
+
+/// Limits for `peel check`; each one given is a check.
+pub struct Options<'a> {
+    /// Total image size, in bytes
+    pub max_size: Option<u64>,
+
+    /// Size of any one layer, in bytes
+    pub max_layer_size: Option<u64>,
+
+    /// Files in the final filesystem
+    pub max_files: Option<usize>,
+
+    /// Image efficiency, in percent
+    pub min_efficiency: Option<f64>,
+
+    /// Globs no layer may ship a file for
+    pub forbid: &'a [String],
+
+    /// Saved `peel inspect --json` output to compare against
+    pub baseline: Option<&'a Path>,
+
+    /// How much bigger than the baseline the image may get
+    pub max_growth: Option<Growth>,
+
+    /// Largest file allowed that the baseline didn't have, in bytes
+    pub max_new_file: Option<u64>,
+
+    /// Size limits for directories of the final filesystem
+    pub budgets: &'a [Budget],
+
+    /// `--fail-on` conditions, checked after the limits above
+    pub fail_on: &'a [FailOn],
+}
+
+/// `--fail-on`: a lint severity, or a condition inspect and check fail on.
+#[derive(Debug, Clone, Copy)]
+pub enum FailOn {
+    /// Lint findings at this severity or above (lint only)
+    Severity(Severity),
+    /// `size>SIZE`
+    Size(u64),
+    /// `layers>N`
+    Layers(usize),
+    /// `files>N`, counting the final filesystem
+    Files(usize),
+    /// `efficiency<PERCENT`
+    Efficiency(f64),
+    /// `secrets`: a credential in any layer
+    Secrets,
+}
+
+/// What a failed gate was about. Each class exits with its own code, so a
+/// pipeline can tell a size regression from a leaked key without parsing
+/// the output; see the README's exit code table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum FailureClass {
+    Size = 3,
+    Layers = 4,
+    Files = 5,
+    Efficiency = 6,
+    ForbiddenPath = 7,
+    Secrets = 8,
+    Lint = 9,
+}
+
+/// A gate the image failed; `main` exits with the class's code.
+#[derive(Debug)]
+pub struct GateFailed {
+    pub class: FailureClass,
+    pub message: String,
+}
+
+impl fmt::Display for GateFailed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for GateFailed {}
+
+/// `--budget /usr/lib/python3<=150MB`: the most a directory of the final
+/// filesystem may hold.
+#[derive(Debug, Clone)]
+pub struct Budget {
+    /// Normalized, without a leading `/`
+    pub path: PathBuf,
+    pub limit: u64,
+}
+
+/// Growth allowed over the baseline: a share of its size, or bytes.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Growth {
+    Percent(f64),
+    Bytes(u64),
+}
+
+#[derive(Serialize)]
+pub struct CheckReport {
+    pub image: String,
+
+    /// Whether every check passed
+    pub passed: bool,
+
+    /// In the order of `Options`' fields; one per `--forbid`
+    pub checks: Vec<CheckResult>,
+}
+
+#[derive(Serialize)]
+pub struct CheckResult {
+    #[serde(flatten)]
+    pub check: Check,
+
+    pub passed: bool,
+}
+
+/// A limit and what the image measured against it.
+#[derive(Serialize)]
+#[serde(tag = "check", rename_all = "snake_case")]
+pub enum Check {
+    MaxSize {
+        limit: u64,
+        size: u64,
+    },
+    MaxLayerSize {
+        limit: u64,
+        /// Layers over the limit
+        layers: Vec<LayerSize>,
+    },
+    MaxFiles {
+        limit: usize,
+        files: usize,
+    },
+    MinEfficiency {
+        /// Percent
+        limit: f64,
+        /// Percent
+        efficiency: f64,
+    },
+    ForbiddenPath {
+        pattern: String,
+        /// Layer entries that match, hidden or not
+        matches: Vec<LayerPath>,
+    },
+    MaxGrowth {
+        limit: Growth,
+        /// Total size of the baseline
+        baseline: u64,
+        size: u64,
+    },
+    MaxNewFile {
+        limit: u64,
+        /// Files the baseline's final filesystem didn't have, over the limit
+        files: Vec<NewFile>,
+    },
+    Budget {
+        /// Directory, without a leading `/`
+        path: PathBuf,
+        limit: u64,
+        /// Bytes of every file below it in the final filesystem
+        size: u64,
+    },
+    MaxLayers {
+        limit: usize,
+        layers: usize,
+    },
+    Secrets {
+        files_scanned: usize,
+        /// Every copy in every layer, hidden or not
+        secrets: Vec<Secret>,
+    },
+}
+
+#[derive(Serialize)]
+pub struct LayerSize {
+    /// Base = 0
+    pub layer: usize,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct NewFile {
+    /// Base = 0
+    pub layer: usize,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+#[derive(Serialize)]
+pub struct LayerPath {
+    /// Base = 0
+    pub layer: usize,
+    pub path: PathBuf,
+}
+
+/// Parse a size like `500MB`, `1.5G` or `2048`. Units count in 1024s, as
+/// peel prints sizes.
+pub fn parse_size(s: &str) -> Result<u64, String> {
+    let s = s.trim();
+    let split = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(s.len());
+    let (number, unit) = s.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("expected a size like 500MB, got `{s}`"))?;
+    let shift = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 0,
+        "K" | "KB" | "KIB" => 10,
+        "M" | "MB" | "MIB" => 20,
+        "G" | "GB" | "GIB" => 30,
+        "T" | "TB" | "TIB" => 40,
+        _ => return Err(format!("unknown size unit `{unit}`")),
+    };
+    Ok((number * (1u64 << shift) as f64) as u64)
+}
+
+/// Parse `--max-growth`: a percentage like `5%`, or a size like `50MB`.
+pub fn parse_growth(s: &str) -> Result<Growth, String> {
+    match s.trim().strip_suffix('%') {
+        Some(percent) => percent
+            .trim()
+            .parse()
+            .map(Growth::Percent)
+            .map_err(|_| format!("expected a percentage like 5%, got `{s}`")),
+        None => parse_size(s).map(Growth::Bytes),
+    }
+}
+
+/// Parse `--budget`: a directory and the most it may hold, e.g.
+/// `/app/node_modules<=80MB`.
+pub fn parse_budget(s: &str) -> Result<Budget, String> {
+    let (path, limit) = s
+        .split_once("<=")
+        .ok_or_else(|| format!("expected PATH<=SIZE, got `{s}`"))?;
+    Ok(Budget {
+        path: normalize(Path::new(path.trim())),
+        limit: parse_size(limit)?,
+    })
+}
+
+/// Parse `--fail-on`: `size>SIZE`, `layers>N`, `files>N`,
+/// `efficiency<PERCENT`, `secrets`, or a lint severity.
+pub fn parse_fail_on(s: &str) -> Result<FailOn, String> {
+    let s = s.trim();
+    if s.eq_ignore_ascii_case("secrets") {
+        return Ok(FailOn::Secrets);
+    }
+    if let Ok(severity) = Severity::from_str(s, true) {
+        return Ok(FailOn::Severity(severity));
+    }
+    let unknown = || {
+        format!(
+            "expected size>SIZE, layers>N, files>N, efficiency<PERCENT, secrets or a lint severity, got `{s}`"
+        )
+    };
+    let split = s.find(['<', '>']).ok_or_else(unknown)?;
+    let (name, value) = (s[..split].trim(), s[split + 1..].trim());
+    let count = |value: &str| {
+        value
+            .parse()
+            .map_err(|_| format!("expected a count, got `{value}`"))
+    };
+    match (name.to_ascii_lowercase().as_str(), &s[split..=split]) {
+        ("size", ">") => parse_size(value).map(FailOn::Size),
+        ("layers", ">") => count(value).map(FailOn::Layers),
+        ("files", ">") => count(value).map(FailOn::Files),
+        ("efficiency", "<") => value
+            .trim_end_matches('%')
+            .trim()
+            .parse()
+            .map(FailOn::Efficiency)
+            .map_err(|_| format!("expected a percentage like 90, got `{value}`")),
+        _ => Err(unknown()),
+    }
+}
+
+/// The checks `--fail-on` asks for. Lint severities are skipped; callers
+/// reject them first.
+pub fn fail_on_checks(
+    info: &ImageInfo,
+    inspector: &mut dyn Inspector,
+    fail_on: &[FailOn],
+) -> Result<Vec<Check>> {
+    let mut checks = Vec::new();
+    for condition in fail_on {
+        checks.push(match *condition {
+            FailOn::Severity(_) => continue,
+            FailOn::Size(limit) => Check::MaxSize {
+                limit,
+                size: info.total_size,
+            },
+            FailOn::Layers(limit) => Check::MaxLayers {
+                limit,
+                layers: info.layers.len(),
+            },
+            FailOn::Files(limit) => Check::MaxFiles {
+                limit,
+                files: merged::merge(info).len(),
+            },
+            FailOn::Efficiency(limit) => Check::MinEfficiency {
+                limit,
+                efficiency: waste::analyze(info).efficiency * 100.0,
+            },
+            FailOn::Secrets => {
+                let report = super::secrets::scan_layers(info, inspector)?;
+                Check::Secrets {
+                    files_scanned: report.files_scanned,
+                    secrets: report.secrets,
+                }
+            }
+        });
+    }
+    Ok(checks)
+}
+
+/// Fail unless every condition is one inspect and check understand.
+pub fn reject_severities(fail_on: &[FailOn]) -> Result<()> {
+    if fail_on.iter().any(|f| matches!(f, FailOn::Severity(_))) {
+        bail!(t!("fail-on-severity-lint-only"));
+    }
+    Ok(())
+}
+
+/// Measure each check's result.
+pub fn evaluate(checks: Vec<Check>) -> Vec<CheckResult> {
+    checks
+        .into_iter()
+        .map(|check| CheckResult {
+            passed: check.passed(),
+            check,
+        })
+        .collect()
+}
+
+/// The error for failed checks, carrying the exit code of the first
+/// failing class in the README's table.
+pub fn gate_failed(checks: &[CheckResult]) -> Option<GateFailed> {
+    let failed: Vec<&CheckResult> = checks.iter().filter(|c| !c.passed).collect();
+    let class = failed.iter().map(|c| c.check.class()).min()?;
+    Some(GateFailed {
+        class,
+        message: t!("check-failed", failed = failed.len(), total = checks.len()),
+    })
+}
+
+/// Read a saved `peel inspect --json` report.
+fn load_baseline(path: &Path) -> Result<ImageInfo> {
+    let data = fs::read_to_string(path)
+        .with_context(|| t!("render-read-failed", path = path.display()))?;
+    read_json(&data).with_context(|| t!("check-not-a-baseline", path = path.display()))
+}
+
+/// Measure the image against every limit given and fail if any is exceeded.
+pub fn run(
+    image: &str,
+    opts: &Options,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    let forbidden = opts
+        .forbid
+        .iter()
+        .map(|glob| {
+            Regex::new(&glob_to_regex(glob)).with_context(|| t!("grep-bad-pattern", pattern = glob))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    reject_severities(opts.fail_on)?;
+    let baseline = opts.baseline.map(load_baseline).transpose()?;
+    if baseline.is_some() && opts.max_growth.is_none() && opts.max_new_file.is_none() {
+        bail!(t!("check-baseline-no-limits"));
+    }
+    if opts.max_size.is_none()
+        && opts.max_layer_size.is_none()
+        && opts.max_files.is_none()
+        && opts.min_efficiency.is_none()
+        && forbidden.is_empty()
+        && baseline.is_none()
+        && opts.budgets.is_empty()
+        && opts.fail_on.is_empty()
+    {
+        bail!(t!("check-no-limits"));
+    }
+
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let visible = merged::merge(&info);
+
+    let mut checks = Vec::new();
+    if let Some(limit) = opts.max_size {
+        checks.push(Check::MaxSize {
+            limit,
+            size: info.total_size,
+        });
+    }
+    if let Some(limit) = opts.max_layer_size {
+        let layers = info
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| l.size > limit)
+            .map(|(layer, l)| LayerSize {
+                layer,
+                size: l.size,
+            })
+            .collect();
+        checks.push(Check::MaxLayerSize { limit, layers });
+    }
+    if let Some(limit) = opts.max_files {
+        checks.push(Check::MaxFiles {
+            limit,
+            files: visible.len(),
+        });
+    }
+    if let Some(limit) = opts.min_efficiency {
+        checks.push(Check::MinEfficiency {
+            limit,
+            efficiency: waste::analyze(&info).efficiency * 100.0,
+        });
+    }
+    for (glob, re) in opts.forbid.iter().zip(&forbidden) {
+        let matches = info
+            .layers
+            .iter()
+            .enumerate()
+            .flat_map(|(layer, l)| {
+                l.files
+                    .iter()
+                    .filter(|f| !f.is_whiteout)
+                    .map(move |f| (layer, normalize(&f.path)))
+            })
+            .filter(|(_, path)| re.is_match(&path.to_string_lossy()))
+            .map(|(layer, path)| LayerPath { layer, path })
+            .collect();
+        checks.push(Check::ForbiddenPath {
+            pattern: glob.clone(),
+            matches,
+        });
+    }
+    if let Some(baseline) = &baseline {
+        if let Some(limit) = opts.max_growth {
+            checks.push(Check::MaxGrowth {
+                limit,
+                baseline: baseline.total_size,
+                size: info.total_size,
+            });
+        }
+        if let Some(limit) = opts.max_new_file {
+            let before = merged::merge(baseline);
+            let files = visible
+                .values()
+                .filter(|f| f.size > limit && !before.contains_key(&f.path))
+                .map(|f| NewFile {
+                    layer: f.layer,
+                    path: f.path.clone(),
+                    size: f.size,
+                })
+                .collect();
+            checks.push(Check::MaxNewFile { limit, files });
+        }
+    }
+    for budget in opts.budgets {
+        let size = visible
+            .values()
+            .filter(|f| f.path.starts_with(&budget.path))
+            .map(|f| f.size)
+            .sum();
+        checks.push(Check::Budget {
+            path: budget.path.clone(),
+            limit: budget.limit,
+            size,
+        });
+    }
+    checks.extend(fail_on_checks(&info, inspector.as_mut(), opts.fail_on)?);
+
+    let checks = evaluate(checks);
+    let report = CheckReport {
+        image: info.name.clone(),
+        passed: checks.iter().all(|c| c.passed),
+        checks,
+    };
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "check", &json_str, None)?;
+        }
+        Output::Json(dest) => write_json(dest, &report)?,
+        Output::Web | Output::Text | Output::Csv(_) => print_check(&report),
+        Output::Github => {
+            print_check(&report);
+            for c in report.checks.iter().filter(|c| !c.passed) {
+                let title = format!("peel check: {}", c.check.name());
+                annotate(Annotation::Error, &title, &c.check.describe());
+            }
+        }
+        Output::Junit(path) => {
+            print_check(&report);
+            junit::write(path, &[test_suite(&report)])?;
+        }
+    }
+
+    // Checked after the report is written so CI still gets it on failure
+    if let Some(failure) = gate_failed(&report.checks) {
+        bail!(failure);
+    }
+    Ok(())
+}
+
+impl Check {
+    pub fn passed(&self) -> bool {
+        match self {
+            Check::MaxSize { limit, size } => size <= limit,
+            Check::MaxLayerSize { layers, .. } => layers.is_empty(),
+            Check::MaxFiles { limit, files } => files <= limit,
+            Check::MinEfficiency { limit, efficiency } => efficiency >= limit,
+            Check::ForbiddenPath { matches, .. } => matches.is_empty(),
+            Check::MaxGrowth {
+                limit,
+                baseline,
+                size,
+            } => {
+                let growth = *size as f64 - *baseline as f64;
+                match limit {
+                    Growth::Percent(percent) => growth <= *baseline as f64 * percent / 100.0,
+                    Growth::Bytes(bytes) => growth <= *bytes as f64,
+                }
+            }
+            Check::MaxNewFile { files, .. } => files.is_empty(),
+            Check::Budget { limit, size, .. } => size <= limit,
+            Check::MaxLayers { limit, layers } => layers <= limit,
+            Check::Secrets { secrets, .. } => secrets.is_empty(),
+        }
+    }
+
+    /// The exit code class a failure of this check falls under.
+    pub fn class(&self) -> FailureClass {
+        match self {
+            Check::MaxSize { .. }
+            | Check::MaxLayerSize { .. }
+            | Check::MaxGrowth { .. }
+            | Check::MaxNewFile { .. }
+            | Check::Budget { .. } => FailureClass::Size,
+            Check::MaxLayers { .. } => FailureClass::Layers,
+            Check::MaxFiles { .. } => FailureClass::Files,
+            Check::MinEfficiency { .. } => FailureClass::Efficiency,
+            Check::ForbiddenPath { .. } => FailureClass::ForbiddenPath,
+            Check::Secrets { .. } => FailureClass::Secrets,
+        }
+    }
+
+    /// The flag that asked for the check.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Check::MaxSize { .. } => "max-size",
+            Check::MaxLayerSize { .. } => "max-layer-size",
+            Check::MaxFiles { .. } => "max-files",
+            Check::MinEfficiency { .. } => "min-efficiency",
+            Check::ForbiddenPath { .. } => "forbid",
+            Check::MaxGrowth { .. } => "max-growth",
+            Check::MaxNewFile { .. } => "max-new-file",
+            Check::Budget { .. } => "budget",
+            Check::MaxLayers { .. } => "max-layers",
+            Check::Secrets { .. } => "secrets",
+        }
+    }
+
+    /// What was measured against the limit, in one line.
+    pub fn describe(&self) -> String {
+        match self {
+            Check::MaxSize { limit, size } => t!(
+                "check-max-size",
+                size = format_bytes(*size),
+                limit = format_bytes(*limit)
+            ),
+            Check::MaxLayerSize { limit, layers } => match layers.iter().max_by_key(|l| l.size) {
+                Some(largest) => t!(
+                    "check-max-layer-size-over",
+                    count = layers.len(),
+                    limit = format_bytes(*limit),
+                    layer = largest.layer + 1,
+                    size = format_bytes(largest.size)
+                ),
+                None => t!("check-max-layer-size", limit = format_bytes(*limit)),
+            },
+            Check::MaxFiles { limit, files } => {
+                t!("check-max-files", files = files, limit = limit)
+            }
+            Check::MinEfficiency { limit, efficiency } => t!(
+                "check-min-efficiency",
+                efficiency = format!("{efficiency:.1}%"),
+                limit = format!("{limit}%")
+            ),
+            Check::ForbiddenPath { pattern, matches } => match matches.first() {
+                Some(first) => t!(
+                    "check-forbidden-found",
+                    pattern = pattern,
+                    count = matches.len(),
+                    path = format!("/{}", first.path.display()),
+                    layer = first.layer + 1
+                ),
+                None => t!("check-forbidden-none", pattern = pattern),
+            },
+            Check::MaxGrowth {
+                limit,
+                baseline,
+                size,
+            } => {
+                let delta = *size as i64 - *baseline as i64;
+                let sign = if delta < 0 { "-" } else { "+" };
+                let percent = if *baseline == 0 {
+                    0.0
+                } else {
+                    delta as f64 * 100.0 / *baseline as f64
+                };
+                t!(
+                    "check-max-growth",
+                    size = format_bytes(*size),
+                    delta = format!("{sign}{}", format_bytes(delta.unsigned_abs())),
+                    percent = format!("{percent:+.1}%"),
+                    baseline = format_bytes(*baseline),
+                    limit = match limit {
+                        Growth::Percent(p) => format!("+{p}%"),
+                        Growth::Bytes(b) => format!("+{}", format_bytes(*b)),
+                    }
+                )
+            }
+            Check::MaxNewFile { limit, files } => match files.iter().max_by_key(|f| f.size) {
+                Some(largest) => t!(
+                    "check-new-files-found",
+                    count = files.len(),
+                    limit = format_bytes(*limit),
+                    path = format!("/{}", largest.path.display()),
+                    size = format_bytes(largest.size),
+                    layer = largest.layer + 1
+                ),
+                None => t!("check-new-files-none", limit = format_bytes(*limit)),
+            },
+            Check::Budget { path, limit, size } => t!(
+                "check-budget",
+                path = format!("/{}", path.display()),
+                size = format_bytes(*size),
+                limit = format_bytes(*limit)
+            ),
+            Check::MaxLayers { limit, layers } => {
+                t!("check-max-layers", layers = layers, limit = limit)
+            }
+            Check::Secrets {
+                files_scanned,
+                secrets,
+            } => match secrets.first() {
+                Some(first) => t!(
+                    "check-secrets-found",
+                    count = secrets.len(),
+                    path = format!("/{}", first.path.display()),
+                    layer = first.layer + 1
+                ),
+                None => t!("check-secrets-none", files = files_scanned),
+            },
+        }
+    }
+
+    /// Files behind the result, listed under it: layer, path and size.
+    fn listed(&self) -> Vec<(usize, &PathBuf, Option<u64>)> {
+        match self {
+            Check::ForbiddenPath { matches, .. } => {
+                matches.iter().map(|m| (m.layer, &m.path, None)).collect()
+            }
+            Check::MaxNewFile { files, .. } => files
+                .iter()
+                .map(|f| (f.layer, &f.path, Some(f.size)))
+                .collect(),
+            // A file with several secrets is listed once
+            Check::Secrets { secrets, .. } => {
+                let mut files: Vec<_> = secrets.iter().map(|s| (s.layer, &s.path, None)).collect();
+                files.dedup();
+                files
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// One line per check, then a summary if they all passed.
+pub fn print_check(r: &CheckReport) {
+    print_results(&r.checks);
+    // A failure ends with the command's error, which says how many failed
+    if r.passed {
+        println!();
+        println!("{}", t!("check-passed", total = r.checks.len()).green());
+    }
+}
+
+/// One line per check, with the files behind it.
+pub fn print_results(checks: &[CheckResult]) {
+    for c in checks {
+        let mark = if c.passed { "✔".green() } else { "✘".red() };
+        let name = format!("{:<16}", c.check.name());
+        println!("{mark} {}  {}", name.bold(), c.check.describe());
+        let listed = c.check.listed();
+        for (layer, path, size) in listed.iter().take(SHOWN_MATCHES) {
+            let mut note = t!("ldd-layer", layer = layer + 1);
+            if let Some(size) = size {
+                note = format!("{}, {note}", format_bytes(*size));
+            }
+            println!("    /{}  {}", path.display(), note.dim());
+        }
+        if listed.len() > SHOWN_MATCHES {
+            let more = t!("check-more", count = listed.len() - SHOWN_MATCHES);
+            println!("    {}", more.dim());
+        }
+    }
+}
+
+/// One test case per check.
+fn test_suite(r: &CheckReport) -> TestSuite {
+    TestSuite {
+        name: r.image.clone(),
+        cases: r
+            .checks
+            .iter()
+            .map(|c| test_case(c, "peel.check"))
+            .collect(),
+    }
+}
+
+/// A check as a JUnit test case, listing the files behind a failure.
+pub fn test_case(c: &CheckResult, classname: &str) -> TestCase {
+    let describe = c.check.describe();
+    let mut details: Vec<String> = c
+        .check
+        .listed()
+        .into_iter()
+        .map(|(layer, path, size)| {
+            let mut note = t!("ldd-layer", layer = layer + 1);
+            if let Some(size) = size {
+                note = format!("{}, {note}", format_bytes(size));
+            }
+            format!("/{} ({note})", path.display())
+        })
+        .collect();
+    if details.is_empty() {
+        details.push(describe.clone());
+    }
+    TestCase {
+        classname: classname.to_string(),
+        name: c.check.name().to_string(),
+        failure: (!c.passed).then(|| Failure {
+            message: describe,
+            kind: "error".to_string(),
+            details,
+        }),
+        output: Vec::new(),
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::Result;
+
+use super::inspect::{format_bytes, open_inspector, print_runtime_summary, select_method};
+use super::output::{Annotation, Output, annotate, write_json};
+use crate::analysis::compare::{self, Comparison};
+use crate::config;
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+pub fn run(
+    images: &[String],
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, &images[0], use_oci));
+
+    // Layer digests and sizes are all we need, so skip file enumeration.
+    let mut infos = Vec::with_capacity(images.len());
+    for image in images {
+        let spinner = Spinner::new(t!("spinner-resolving-image", image = image));
+        let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+        let info = inspector.inspect(image)?;
+        spinner.finish(t!("resolved-image", image = image, count = info.layers.len()));
+        infos.push(info);
+    }
+
+    let result = compare::compare(&infos);
+
+    // There is no web view for comparisons, so the terminal table stands in for it.
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&result)?;
+            super::report::write_bundle(dir, "compare", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &result),
+        Output::Web | Output::Text | Output::Junit(_) | Output::Csv(_) => {
+            print_comparison(images, &result);
+            Ok(())
+        }
+        Output::Github => {
+            print_comparison(images, &result);
+            for (i, img) in result.images.iter().enumerate() {
+                let Some(delta) = img.size_delta.filter(|d| *d > 0) else {
+                    continue;
+                };
+                let message = t!(
+                    "compare-grew",
+                    image = &images[i],
+                    delta = format_bytes(delta as u64),
+                    previous = &images[i - 1],
+                    size = format_bytes(img.total_size)
+                );
+                annotate(Annotation::Warning, "peel compare", &message);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Size table followed by the layer reuse matrix. `refs` label the columns.
+pub fn print_comparison(refs: &[String], c: &Comparison) {
+    let name_width = refs.iter().map(|r| r.len()).max().unwrap_or(5).max(5);
+
+    println!(
+        "{:<name_width$}  {:>10}  {:>10}  {:>6}  {:>6}  {:>10}",
+        "IMAGE", "SIZE", "CHANGE", "LAYERS", "SHARED", "UNIQUE"
+    );
+    for (r, img) in refs.iter().zip(&c.images) {
+        let change = img
+            .size_delta
+            .map(|d| {
+                let sign = if d < 0 { "-" } else { "+" };
+                format!("{sign}{}", format_bytes(d.unsigned_abs()))
+            })
+            .unwrap_or_else(|| "-".to_string());
+        println!(
+            "{:<name_width$}  {:>10}  {:>10}  {:>6}  {:>6}  {:>10}",
+            r,
+            format_bytes(img.total_size),
+            change,
+            img.layer_count,
+            img.shared_layers,
+            format_bytes(img.unique_size),
+        );
+    }
+    println!();
+
+    // Reuse matrix: one column per image, numbered in argument order
+    println!("{}", t!("compare-layer-reuse").dim());
+    let columns: Vec<String> = (1..=refs.len()).map(|i| format!("{i:>2}")).collect();
+    println!("  {:<19}  {:>10}  {}", "DIGEST", "SIZE", columns.join(" "));
+    for layer in &c.layers {
+        let marks: Vec<String> = (0..refs.len())
+            .map(|i| {
+                if layer.images.contains(&i) {
+                    format!("{:>2}", "●")
+                } else {
+                    format!("{:>2}", "·")
+                }
+            })
+            .collect();
+        let short = layer.digest.get(..19).unwrap_or(&layer.digest);
+        println!(
+            "  {:<19}  {:>10}  {}",
+            short,
+            format_bytes(layer.size),
+            marks.join(" ")
+        );
+    }
+    println!();
+    for (i, r) in refs.iter().enumerate() {
+        println!("  {} {}", format!("{:>2}", i + 1).dim(), r);
+    }
+}
@@ -0,0 +1,437 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use serde_json::Value;
+
+use super::inspect::format_bytes;
+use crate::progress::Spinner;
+use crate::registry::client::Body;
+use crate::registry::trace::Trace;
+use crate::registry::{Client, Reference};
+use crate::repack::{self, Compression, LayerFilter};
+use crate::temp_files;
+
+/// What to change while copying.
+pub struct Transform {
+    /// Layer digests (or unambiguous prefixes) to leave out entirely
+    pub drop_layers: Vec<String>,
+
+    /// Paths to strip from every layer
+    pub filter: LayerFilter,
+
+    /// Re-gzip every layer at this level
+    pub recompress: Option<u32>,
+}
+
+impl Transform {
+    fn is_identity(&self) -> bool {
+        self.drop_layers.is_empty() && self.filter.is_empty() && self.recompress.is_none()
+    }
+
+    /// Fail on a `--drop-layer` with no hex digits to match, which would
+    /// otherwise match every layer.
+    fn validate(&self) -> Result<()> {
+        for prefix in &self.drop_layers {
+            let hex = prefix.strip_prefix("sha256:").unwrap_or(prefix);
+            if hex.is_empty() || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                bail!(t!("copy-bad-drop", prefix = prefix));
+            }
+        }
+        Ok(())
+    }
+
+    /// Indexes into `drop_layers` of the prefixes that match the layer with
+    /// blob `digest` and uncompressed `diff_id`.
+    fn drops_matching<'s>(
+        &'s self,
+        digest: &'s str,
+        diff_id: &'s str,
+    ) -> impl Iterator<Item = usize> + 's {
+        self.drop_layers.iter().enumerate().filter_map(move |(i, d)| {
+            let d = d.strip_prefix("sha256:").unwrap_or(d);
+            [digest, diff_id]
+                .iter()
+                .any(|id| id.strip_prefix("sha256:").unwrap_or(id).starts_with(d))
+                .then_some(i)
+        })
+    }
+}
+
+#[derive(Default)]
+struct Stats {
+    pushed_blobs: usize,
+    pushed_size: u64,
+    mounted_blobs: usize,
+    skipped_blobs: usize,
+    dropped_layers: usize,
+    dropped_entries: usize,
+}
+
+pub fn run(
+    source: &str,
+    destination: &str,
+    transform: &Transform,
+    trace: Option<Trace>,
+) -> Result<()> {
+    let source = Reference::parse(source)?;
+    let destination = Reference::parse(destination)?;
+    if let Some(level) = transform.recompress
+        && level > 9
+    {
+        bail!(t!("copy-bad-level", level = level));
+    }
+    transform.validate()?;
+
+    let mut copier = Copier {
+        client: Client::new(trace),
+        destination,
+        transform,
+        stats: Stats::default(),
+        dropped: HashSet::new(),
+    };
+
+    let (media_type, raw) = copier.client.manifest_raw(&source)?;
+    let manifest: Value = serde_json::from_slice(&raw)
+        .with_context(|| t!("registry-bad-manifest", reference = &source))?;
+
+    let bytes = if manifest.get("manifests").is_some() {
+        copier.copy_index(&source, manifest, raw)?
+    } else {
+        copier.copy_image(&source, manifest, raw)?
+    };
+    // An index's platforms have different layers, so a prefix need only
+    // match in one of them
+    let unmatched = (0..transform.drop_layers.len()).find(|i| !copier.dropped.contains(i));
+    if let Some(i) = unmatched {
+        bail!(t!("copy-drop-no-match", prefix = &transform.drop_layers[i]));
+    }
+    let destination = &copier.destination;
+    copier.client.put_manifest(
+        &destination.registry,
+        &destination.repository,
+        &destination.reference,
+        &media_type,
+        &bytes,
+    )?;
+
+    let stats = &copier.stats;
+    eprintln!(
+        "{}",
+        t!(
+            "copy-summary",
+            pushed = stats.pushed_blobs,
+            size = format_bytes(stats.pushed_size),
+            mounted = stats.mounted_blobs,
+            skipped = stats.skipped_blobs
+        )
+    );
+    if stats.dropped_layers > 0 || stats.dropped_entries > 0 {
+        eprintln!(
+            "{}",
+            t!(
+                "copy-dropped",
+                layers = stats.dropped_layers,
+                entries = stats.dropped_entries
+            )
+        );
+    }
+    println!(
+        "{}/{}@{}",
+        destination.registry,
+        destination.repository,
+        repack::digest_of(&bytes)
+    );
+    Ok(())
+}
+
+/// Copy state shared across the platforms of an index.
+struct Copier<'a> {
+    client: Client,
+    destination: Reference,
+    transform: &'a Transform,
+    stats: Stats,
+
+    /// Indexes into `drop_layers` that matched a layer
+    dropped: HashSet<usize>,
+}
+
+impl Copier<'_> {
+    /// Copy every platform an index lists and return the index rewritten to
+    /// point at the copies.
+    fn copy_index(
+        &mut self,
+        source: &Reference,
+        mut index: Value,
+        raw: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let children = index["manifests"]
+            .as_array_mut()
+            .with_context(|| t!("registry-bad-manifest", reference = source))?;
+        for child in children.iter_mut() {
+            let Some(child_digest) = child["digest"].as_str() else {
+                bail!(t!("registry-bad-manifest", reference = source));
+            };
+            let child_source = Reference {
+                reference: child_digest.to_string(),
+                ..source.clone()
+            };
+            let (child_type, child_raw) = self.client.manifest_raw(&child_source)?;
+            let manifest: Value = serde_json::from_slice(&child_raw)
+                .with_context(|| t!("registry-bad-manifest", reference = &child_source))?;
+            let bytes = self.copy_image(&child_source, manifest, child_raw)?;
+
+            let digest = repack::digest_of(&bytes);
+            self.client.put_manifest(
+                &self.destination.registry,
+                &self.destination.repository,
+                &digest,
+                &child_type,
+                &bytes,
+            )?;
+            child["digest"] = Value::from(digest);
+            child["size"] = Value::from(bytes.len());
+        }
+
+        if self.transform.is_identity() {
+            return Ok(raw);
+        }
+        Ok(serde_json::to_vec(&index)?)
+    }
+
+    /// Copy one image's config and layers, applying the transform, and return
+    /// the manifest to push.
+    fn copy_image(
+        &mut self,
+        source: &Reference,
+        mut manifest: Value,
+        raw: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let bad_manifest = || t!("registry-bad-manifest", reference = source);
+        let config_digest = manifest["config"]["digest"]
+            .as_str()
+            .with_context(bad_manifest)?
+            .to_string();
+        let mut config_bytes = Vec::new();
+        io::copy(
+            &mut self
+                .client
+                .fetch_blob(&source.registry, &source.repository, &config_digest)?,
+            &mut config_bytes,
+        )?;
+        let mut config: Value = serde_json::from_slice(&config_bytes).with_context(bad_manifest)?;
+
+        let layers = manifest["layers"]
+            .as_array()
+            .with_context(bad_manifest)?
+            .clone();
+        let diff_ids = config["rootfs"]["diff_ids"]
+            .as_array()
+            .with_context(bad_manifest)?
+            .clone();
+        if layers.len() != diff_ids.len() {
+            bail!(bad_manifest());
+        }
+
+        // History entries that produced a layer, in layer order
+        let mut history = config["history"].as_array().cloned().unwrap_or_default();
+        let mut layer_history: Vec<usize> = history
+            .iter()
+            .enumerate()
+            .filter(|(_, h)| !h["empty_layer"].as_bool().unwrap_or(false))
+            .map(|(i, _)| i)
+            .collect();
+        if layer_history.len() != layers.len() {
+            // Not a one-to-one mapping; leave history alone rather than guess
+            layer_history.clear();
+        }
+
+        let transform = self.transform;
+        let total = layers.len();
+        let mut new_layers = Vec::with_capacity(total);
+        let mut new_diff_ids = Vec::with_capacity(total);
+        let mut dropped_history = Vec::new();
+
+        let mut ids = Vec::with_capacity(total);
+        for (layer, diff_id) in layers.iter().zip(&diff_ids) {
+            let digest = layer["digest"].as_str().with_context(bad_manifest)?;
+            let diff_id = diff_id.as_str().with_context(bad_manifest)?;
+            ids.push((digest.to_string(), diff_id.to_string()));
+        }
+        // Each prefix names one layer; fail before pushing anything if it names more
+        let drops: Vec<Vec<usize>> = ids
+            .iter()
+            .map(|(digest, diff_id)| transform.drops_matching(digest, diff_id).collect())
+            .collect();
+        for (d, prefix) in transform.drop_layers.iter().enumerate() {
+            let count = drops.iter().filter(|m| m.contains(&d)).count();
+            if count > 1 {
+                bail!(t!("copy-drop-ambiguous", prefix = prefix, count = count));
+            }
+        }
+
+        let spinner = Spinner::new(t!("spinner-copying-image", image = source));
+        for (i, (mut layer, (digest, diff_id))) in layers.into_iter().zip(ids).enumerate() {
+            if !drops[i].is_empty() {
+                self.dropped.extend(&drops[i]);
+                self.stats.dropped_layers += 1;
+                if let Some(&h) = layer_history.get(i) {
+                    dropped_history.push(h);
+                }
+                continue;
+            }
+
+            spinner.set_message(t!("spinner-copying-layer", current = i + 1, total = total));
+            if transform.filter.is_empty() && transform.recompress.is_none() {
+                self.copy_blob(source, &digest)?;
+                new_diff_ids.push(Value::from(diff_id));
+                new_layers.push(layer);
+                continue;
+            }
+
+            let layer_type = layer["mediaType"].as_str().unwrap_or_default().to_string();
+            let input_compression = Compression::from_media_type(&layer_type)?;
+            let (output_compression, level) = match transform.recompress {
+                Some(level) => (Compression::Gzip, level),
+                None => (input_compression, flate2::Compression::default().level()),
+            };
+
+            let tmp = temp_path(&digest);
+            let result = self
+                .client
+                .fetch_blob(&source.registry, &source.repository, &digest)
+                .and_then(|input| {
+                    repack::rewrite_layer(
+                        input,
+                        input_compression,
+                        &transform.filter,
+                        output_compression,
+                        level,
+                        &tmp,
+                    )
+                })
+                .and_then(|rewritten| {
+                    self.push_blob(&rewritten.digest, rewritten.size, &Body::File(&tmp))?;
+                    Ok(rewritten)
+                });
+            temp_files::remove(&tmp);
+            let rewritten = result?;
+            self.stats.dropped_entries += rewritten.dropped;
+
+            if output_compression == Compression::Gzip && input_compression == Compression::None {
+                layer["mediaType"] = Value::from(gzip_media_type(&layer_type));
+            }
+            layer["digest"] = Value::from(rewritten.digest);
+            layer["size"] = Value::from(rewritten.size);
+            new_diff_ids.push(Value::from(rewritten.diff_id));
+            new_layers.push(layer);
+        }
+        spinner.finish(t!(
+            "copy-copied-image",
+            image = source,
+            layers = new_layers.len()
+        ));
+
+        if transform.is_identity() {
+            self.copy_blob(source, &config_digest)?;
+            return Ok(raw);
+        }
+
+        for h in dropped_history.into_iter().rev() {
+            history.remove(h);
+        }
+        if config.get("history").is_some() {
+            config["history"] = Value::from(history);
+        }
+        config["rootfs"]["diff_ids"] = Value::from(new_diff_ids);
+
+        let config_bytes = serde_json::to_vec(&config)?;
+        let config_digest = repack::digest_of(&config_bytes);
+        self.push_blob(
+            &config_digest,
+            config_bytes.len() as u64,
+            &Body::Bytes(&config_bytes),
+        )?;
+
+        manifest["config"]["digest"] = Value::from(config_digest);
+        manifest["config"]["size"] = Value::from(config_bytes.len());
+        manifest["layers"] = Value::from(new_layers);
+        Ok(serde_json::to_vec(&manifest)?)
+    }
+
+    /// Copy a blob unchanged, mounting it when both sides share a registry.
+    fn copy_blob(&mut self, source: &Reference, digest: &str) -> Result<()> {
+        let destination = &self.destination;
+        if self
+            .client
+            .blob_exists(&destination.registry, &destination.repository, digest)?
+        {
+            self.stats.skipped_blobs += 1;
+            return Ok(());
+        }
+        if source.registry == destination.registry
+            && self.client.mount_blob(
+                &destination.registry,
+                &destination.repository,
+                &source.repository,
+                digest,
+            )?
+        {
+            self.stats.mounted_blobs += 1;
+            return Ok(());
+        }
+
+        // Spool to disk so the upload can be replayed after an auth challenge
+        let tmp = temp_path(digest);
+        let result = self
+            .client
+            .fetch_blob(&source.registry, &source.repository, digest)
+            .and_then(|mut input| {
+                let mut file = fs::File::create(&tmp)?;
+                Ok(io::copy(&mut input, &mut file)?)
+            })
+            .and_then(|size| self.push_blob(digest, size, &Body::File(&tmp)));
+        temp_files::remove(&tmp);
+        result
+    }
+
+    /// Upload a blob unless the destination already has it.
+    fn push_blob(&mut self, digest: &str, size: u64, body: &Body) -> Result<()> {
+        let destination = &self.destination;
+        if self
+            .client
+            .blob_exists(&destination.registry, &destination.repository, digest)?
+        {
+            self.stats.skipped_blobs += 1;
+            return Ok(());
+        }
+        self.client
+            .push_blob(&destination.registry, &destination.repository, digest, body)?;
+        self.stats.pushed_blobs += 1;
+        self.stats.pushed_size += size;
+        Ok(())
+    }
+}
+
+/// The gzip variant of an uncompressed layer media type.
+fn gzip_media_type(media_type: &str) -> String {
+    if media_type.starts_with("application/vnd.docker.") {
+        format!("{media_type}.gzip")
+    } else {
+        format!("{media_type}+gzip")
+    }
+}
+
+/// Tracked, so an interrupted copy doesn't leave the blob behind.
+fn temp_path(digest: &str) -> PathBuf {
+    let hex = digest.trim_start_matches("sha256:");
+    let path = std::env::temp_dir().join(format!(
+        "peel-copy-{}-{}",
+        std::process::id(),
+        &hex[..hex.len().min(12)]
+    ));
+    temp_files::track(&path);
+    path
+}
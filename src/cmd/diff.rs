@@ -0,0 +1,114 @@
+use anyhow::Result;
+
+use super::inspect::{
+    fit, fit_start, format_bytes, load_image, print_runtime_summary, select_method,
+};
+use super::output::{Output, write_json};
+use crate::analysis::diff::{self, ImageDiff, LayerStatus};
+use crate::config;
+use crate::style::{self, Stylize};
+
+/// Number of directory changes shown in the terminal summary.
+const TOP_DIRS: usize = 15;
+
+pub fn run(
+    left: &str,
+    right: &str,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, left, use_oci));
+
+    let left_info = load_image(cfg, left, use_oci, no_sudo)?;
+    let right_info = load_image(cfg, right, use_oci, no_sudo)?;
+    let result = diff::diff(&left_info, &right_info);
+
+    match output {
+        Output::Web => {
+            let json_str = serde_json::to_string_pretty(&result)?;
+            let html = super::report::build_report(&json_str);
+            let name = format!("{}-vs-{}", left_info.name, right_info.name);
+            super::report::publish(&name, &json_str, &html)
+        }
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&result)?;
+            let html = super::report::build_report(&json_str);
+            super::report::write_bundle(dir, "diff", &json_str, Some(&html))
+        }
+        Output::Json(dest) => write_json(dest, &result),
+        Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_diff(&result);
+            Ok(())
+        }
+    }
+}
+
+/// Plain-text diff summary, as shown with `--no-web`.
+pub fn print_diff(d: &ImageDiff) {
+    println!(
+        "{} → {}",
+        display_ref(&d.left.name, &d.left.tag),
+        display_ref(&d.right.name, &d.right.tag)
+    );
+    println!(
+        "  {}",
+        t!(
+            "diff-total-size",
+            left = format_bytes(d.left.total_size),
+            right = format_bytes(d.right.total_size),
+            delta = format_delta(d.right.total_size as i64 - d.left.total_size as i64),
+        )
+    );
+    println!(
+        "  {}",
+        t!(
+            "diff-layers",
+            shared = d.shared_layers,
+            shared_size = format_bytes(d.shared_size),
+            removed = d.layers.iter().filter(|l| l.status == LayerStatus::Removed).count(),
+            added = d.layers.iter().filter(|l| l.status == LayerStatus::Added).count(),
+        )
+    );
+    println!();
+
+    for layer in d.layers.iter().filter(|l| l.status != LayerStatus::Shared) {
+        let marker = match layer.status {
+            LayerStatus::Removed => "-".red(),
+            _ => "+".green(),
+        };
+        println!("{} {}", marker, layer.digest);
+        if let Some(cmd) = &layer.created_by {
+            println!("  {}", fit(cmd, 2));
+        }
+        println!("  {}", t!("diff-layer-size", size = format_bytes(layer.size)));
+        println!();
+    }
+
+    if !d.dirs.is_empty() {
+        println!("{}", t!("diff-top-dirs").dim());
+        for dir in d.dirs.iter().take(TOP_DIRS) {
+            println!(
+                "  {:>10}  /{}",
+                format_delta(dir.delta),
+                style::style(fit_start(&dir.path.to_string_lossy(), 15)).cyan()
+            );
+        }
+    }
+}
+
+pub fn display_ref(name: &str, tag: &Option<String>) -> String {
+    match tag.as_deref() {
+        Some(t) if !t.is_empty() => format!("{name}:{t}"),
+        _ => name.to_string(),
+    }
+}
+
+fn format_delta(delta: i64) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_bytes(delta.unsigned_abs()))
+}
@@ -0,0 +1,112 @@
+use std::io::Read;
+
+use anyhow::Result;
+use similar::TextDiff;
+
+use super::extract::with_file;
+use super::inspect::{
+    format_bytes, load_image_with_inspector, print_runtime_summary, select_method,
+};
+use crate::config;
+use crate::inspector::checksum::HashingReader;
+use crate::style::Stylize;
+
+/// Files larger than this are compared by hash only.
+const MAX_TEXT_SIZE: u64 = 1024 * 1024;
+
+/// Lines of context around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// One image's copy of the file.
+struct Side {
+    size: u64,
+    sha256: String,
+
+    /// Content, if small enough and valid UTF-8 without NUL bytes
+    text: Option<String>,
+}
+
+/// Print a unified diff of `path` between two images, or compare hashes if
+/// either copy is binary or large.
+pub fn run(
+    left: &str,
+    right: &str,
+    path: &str,
+    use_oci: bool,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(false, runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, left, use_oci));
+
+    let read = |image: &str| -> Result<Side> {
+        let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+        let (side, _) = with_file(&info, inspector.as_mut(), path, None, |content, size| {
+            let mut reader = HashingReader::new(content);
+            let text = if size <= MAX_TEXT_SIZE {
+                let mut buf = Vec::new();
+                reader.read_to_end(&mut buf)?;
+                String::from_utf8(buf).ok().filter(|s| !s.contains('\0'))
+            } else {
+                None
+            };
+            Ok(Side {
+                size,
+                sha256: reader.finish()?,
+                text,
+            })
+        })?;
+        Ok(side)
+    };
+    let a = read(left)?;
+    let b = read(right)?;
+
+    if a.sha256 == b.sha256 {
+        println!(
+            "{}",
+            t!(
+                "diff-file-identical",
+                path = path,
+                digest = a.sha256.as_str()
+            )
+        );
+        return Ok(());
+    }
+
+    match (&a.text, &b.text) {
+        (Some(old), Some(new)) => {
+            let diff = TextDiff::from_lines(old, new);
+            let unified = diff
+                .unified_diff()
+                .context_radius(CONTEXT_LINES)
+                .header(&format!("{left}:{path}"), &format!("{right}:{path}"))
+                .to_string();
+            for line in unified.lines() {
+                if line.starts_with("+++") || line.starts_with("---") {
+                    println!("{}", line.bold());
+                } else if line.starts_with('+') {
+                    println!("{}", line.green());
+                } else if line.starts_with('-') {
+                    println!("{}", line.red());
+                } else if line.starts_with("@@") {
+                    println!("{}", line.cyan());
+                } else {
+                    println!("{line}");
+                }
+            }
+        }
+        _ => {
+            println!("{}", t!("diff-file-binary", path = path));
+            for (image, side) in [(left, &a), (right, &b)] {
+                println!(
+                    "  {:>10}  {}  {image}",
+                    format_bytes(side.size),
+                    side.sha256
+                );
+            }
+        }
+    }
+    Ok(())
+}
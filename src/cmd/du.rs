@@ -0,0 +1,194 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use serde::Serialize;
+
+use super::export_layer::find_layer;
+use super::inspect::{
+    fit, fit_start, format_bytes, load_image, print_runtime_summary, select_method,
+};
+use super::output::{Output, write_json};
+use crate::analysis::dirs::{self, DirSize, TreeNode};
+use crate::analysis::merged::{self, normalize};
+use crate::config;
+use crate::style::Stylize;
+
+/// Flags for `peel du`.
+pub struct Options<'a> {
+    /// Only count this layer's files (index or digest prefix), not the final
+    /// filesystem
+    pub layer: Option<&'a str>,
+
+    /// List directories at most this many levels below the path
+    pub max_depth: usize,
+
+    /// Draw files and directories as a tree instead of a list
+    pub tree: bool,
+
+    /// Leave tree entries smaller than this out
+    pub min_size: u64,
+}
+
+#[derive(Serialize)]
+pub struct DuReport {
+    /// Directory summarized, without a leading `/`; empty for the root
+    pub path: PathBuf,
+
+    /// Layer counted, if not the final filesystem (base = 0)
+    pub layer: Option<usize>,
+
+    pub max_depth: usize,
+
+    /// Bytes of every file below `path`
+    pub total: u64,
+
+    pub files: usize,
+
+    /// Subdirectories down to `max_depth`, in path order
+    pub dirs: Vec<DirSize>,
+
+    /// Files and directories down to `max_depth`, with `--tree`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tree: Option<TreeNode>,
+}
+
+/// Cumulative sizes of the directories below `path`, like `du --max-depth`.
+pub fn run(
+    image: &str,
+    path: Option<&str>,
+    opts: &Options,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let info = load_image(cfg, image, use_oci, no_sudo)?;
+    let layer = opts.layer.map(|l| find_layer(&info, l)).transpose()?;
+
+    // (path, size) of every file counted
+    let files: Vec<(PathBuf, u64)> = match layer {
+        Some(index) => info.layers[index]
+            .files
+            .iter()
+            .filter(|f| !f.is_whiteout)
+            .map(|f| (normalize(&f.path), f.size))
+            .collect(),
+        None => merged::merge(&info)
+            .into_values()
+            .map(|f| (f.path, f.size))
+            .collect(),
+    };
+
+    let root = normalize(Path::new(path.unwrap_or("/")));
+    let below: Vec<u64> = files
+        .iter()
+        .filter(|(file, _)| file.starts_with(&root))
+        .map(|(_, size)| *size)
+        .collect();
+    if below.is_empty() {
+        bail!(t!("du-not-found", path = format!("/{}", root.display())));
+    }
+    let dirs = dirs::rollup(
+        files.iter().map(|(file, size)| (file.as_path(), *size)),
+        &root,
+        opts.max_depth,
+    );
+    let tree = opts.tree.then(|| {
+        dirs::tree(
+            files.iter().map(|(file, size)| (file.as_path(), *size)),
+            &root,
+            opts.max_depth,
+            opts.min_size,
+        )
+    });
+    let report = DuReport {
+        path: root,
+        layer,
+        max_depth: opts.max_depth,
+        total: below.iter().sum(),
+        files: below.len(),
+        dirs: dirs.into_values().collect(),
+        tree,
+    };
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "du", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_report(&report);
+            Ok(())
+        }
+    }
+}
+
+/// One line per directory, then the total for the path, as `du` prints them;
+/// with `--tree`, the path and its total, then the tree below it.
+pub fn print_report(report: &DuReport) {
+    let path = fit_start(&report.path.to_string_lossy(), 13);
+    let total = format!("{:>10}", format_bytes(report.total));
+    match &report.tree {
+        Some(tree) => {
+            println!("{}  /{}", total.bold(), path.cyan().bold());
+            print_children(tree, "");
+        }
+        None => {
+            for dir in &report.dirs {
+                println!(
+                    "{:>10}  /{}",
+                    format_bytes(dir.size),
+                    fit_start(&dir.path.to_string_lossy(), 13).cyan()
+                );
+            }
+            println!("{}  /{}", total.bold(), path.cyan().bold());
+        }
+    }
+    if let Some(layer) = report.layer {
+        println!();
+        println!(
+            "{}",
+            t!("du-layer-only", layer = layer + 1, files = report.files).dim()
+        );
+    }
+}
+
+/// A line per child of `node`, largest first, each followed by its own
+/// children; then what was too small to show.
+fn print_children(node: &TreeNode, indent: &str) {
+    for (i, child) in node.children.iter().enumerate() {
+        let last = i + 1 == node.children.len() && node.pruned.is_none();
+        let (branch, below) = if last {
+            ("└── ", "    ")
+        } else {
+            ("├── ", "│   ")
+        };
+        let used = 12 + indent.chars().count() + branch.chars().count();
+        let name = if child.is_dir {
+            fit(&format!("{}/", child.name), used).cyan()
+        } else {
+            fit(&child.name, used).stylize()
+        };
+        println!(
+            "{:>10}  {}{}",
+            format_bytes(child.size),
+            format!("{indent}{branch}").dim(),
+            name
+        );
+        print_children(child, &format!("{indent}{below}"));
+    }
+    if let Some(pruned) = &node.pruned {
+        println!(
+            "{}  {}{}",
+            format!("{:>10}", format_bytes(pruned.size)).dim(),
+            format!("{indent}└── ").dim(),
+            t!("du-tree-pruned", entries = pruned.entries).dim()
+        );
+    }
+}
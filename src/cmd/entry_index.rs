@@ -0,0 +1,119 @@
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+
+use crate::inspector::{ImageInfo, Inspector};
+
+/// One tar entry of a layer blob, as written to `--emit-index`.
+#[derive(Serialize)]
+struct Record<'a> {
+    /// Layer index (0 = base layer)
+    layer: usize,
+
+    /// Layer digest, as listed in the report
+    digest: &'a str,
+
+    /// How the blob is stored: `none` or `gzip`
+    compression: &'static str,
+
+    path: String,
+
+    /// `file`, `dir`, `symlink`, `hardlink`, `char`, `block`, `fifo` or `other`
+    #[serde(rename = "type")]
+    kind: &'static str,
+
+    /// Where the entry's header starts in the uncompressed tar stream
+    header_offset: u64,
+
+    /// Where the entry's content starts in the uncompressed tar stream
+    offset: u64,
+
+    size: u64,
+
+    /// Byte range of the content in the blob as stored; only known for
+    /// uncompressed blobs, since a gzip stream can't be entered mid-way
+    blob: Option<Extent>,
+
+    mode: u32,
+    uid: u64,
+    gid: u64,
+    mtime: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    link: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Extent {
+    offset: u64,
+    size: u64,
+}
+
+/// Write one JSON line per tar entry of every layer blob to `dest`, so other
+/// tools can seek straight into the blobs. Returns the number of entries.
+pub fn write(info: &ImageInfo, inspector: &mut dyn Inspector, dest: &Path) -> Result<usize> {
+    let file =
+        File::create(dest).with_context(|| t!("write-json-failed", path = dest.display()))?;
+    let mut out = BufWriter::new(file);
+    let mut count = 0;
+
+    for (index, layer) in info.layers.iter().enumerate() {
+        let Some(blob) = inspector.read_blob(layer)? else {
+            bail!(t!("emit-index-no-blobs"));
+        };
+        let mut blob = BufReader::new(blob);
+        let gzip = blob.fill_buf()?.starts_with(&[0x1f, 0x8b]);
+        let stream: Box<dyn Read> = if gzip {
+            Box::new(flate2::read::GzDecoder::new(blob))
+        } else {
+            Box::new(blob)
+        };
+
+        let mut archive = tar::Archive::new(stream);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let entry = entry.context(t!("repack-read-failed"))?;
+            let header = entry.header();
+            let offset = entry.raw_file_position();
+            let size = entry.size();
+            let record = Record {
+                layer: index,
+                digest: &layer.digest,
+                compression: if gzip { "gzip" } else { "none" },
+                path: entry.path()?.to_string_lossy().into_owned(),
+                kind: kind_name(header.entry_type()),
+                header_offset: entry.raw_header_position(),
+                offset,
+                size,
+                blob: (!gzip).then_some(Extent { offset, size }),
+                mode: header.mode().unwrap_or(0),
+                uid: header.uid().unwrap_or(0),
+                gid: header.gid().unwrap_or(0),
+                mtime: header.mtime().unwrap_or(0),
+                link: entry.link_name()?.map(|l| l.to_string_lossy().into_owned()),
+            };
+            serde_json::to_writer(&mut out, &record)?;
+            out.write_all(b"\n")?;
+            count += 1;
+        }
+    }
+
+    out.flush()
+        .with_context(|| t!("write-json-failed", path = dest.display()))?;
+    Ok(count)
+}
+
+fn kind_name(kind: tar::EntryType) -> &'static str {
+    match kind {
+        tar::EntryType::Regular | tar::EntryType::Continuous => "file",
+        tar::EntryType::Directory => "dir",
+        tar::EntryType::Symlink => "symlink",
+        tar::EntryType::Link => "hardlink",
+        tar::EntryType::Char => "char",
+        tar::EntryType::Block => "block",
+        tar::EntryType::Fifo => "fifo",
+        _ => "other",
+    }
+}
@@ -0,0 +1,98 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use super::inspect::{format_bytes, open_inspector, print_runtime_summary, select_method};
+use crate::config;
+use crate::inspector::ImageInfo;
+use crate::progress::Spinner;
+use crate::repack::HashingWriter;
+use crate::style::Stylize;
+
+/// Write one layer of `image` to `output` as an uncompressed tar.
+///
+/// `layer` is a layer number (base = 1) or a digest prefix, as printed by
+/// `peel inspect`. Overlay2 layers are re-tarred from disk, so their
+/// digest won't match the registry's diff ID.
+pub fn run(
+    image: &str,
+    layer: &str,
+    output: &Path,
+    use_oci: bool,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(false, runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let info = inspector.inspect(image)?;
+    let index = find_layer(&info, layer)?;
+    let selected = &info.layers[index];
+
+    spinner.set_message(t!("spinner-exporting-layer", index = index + 1));
+    let file =
+        File::create(output).with_context(|| t!("repack-write-failed", path = output.display()))?;
+    let mut writer = HashingWriter::new(BufWriter::new(file));
+    io::copy(&mut inspector.read_layer(selected)?, &mut writer)
+        .and_then(|_| writer.flush())
+        .with_context(|| t!("repack-write-failed", path = output.display()))?;
+    spinner.finish(t!(
+        "export-layer-done",
+        index = index + 1,
+        digest = selected.digest.as_str()
+    ));
+
+    println!(
+        "{}",
+        t!(
+            "export-layer-wrote",
+            path = output.display().to_string().bold(),
+            size = format_bytes(writer.size()),
+            digest = writer.digest()
+        )
+    );
+    Ok(())
+}
+
+/// Resolve a layer index or unambiguous digest prefix.
+pub fn find_layer(info: &ImageInfo, layer: &str) -> Result<usize> {
+    if let Ok(number) = layer.parse::<usize>() {
+        if (1..=info.layers.len()).contains(&number) {
+            return Ok(number - 1);
+        }
+        bail!(t!(
+            "export-layer-bad-index",
+            index = number,
+            last = info.layers.len()
+        ));
+    }
+
+    let prefix = layer.strip_prefix("sha256:").unwrap_or(layer);
+    let matches: Vec<usize> = info
+        .layers
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| {
+            l.digest
+                .strip_prefix("sha256:")
+                .unwrap_or(&l.digest)
+                .starts_with(prefix)
+        })
+        .map(|(i, _)| i)
+        .collect();
+    match matches.as_slice() {
+        [index] => Ok(*index),
+        [] => bail!(t!("export-layer-not-found", layer = layer)),
+        _ => bail!(t!(
+            "export-layer-ambiguous",
+            layer = layer,
+            count = matches.len()
+        )),
+    }
+}
@@ -0,0 +1,126 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+
+use super::inspect::{
+    format_bytes, load_image_with_inspector, print_runtime_summary, select_method,
+};
+use crate::analysis::merged::{self, normalize};
+use crate::config;
+use crate::inspector::{ImageInfo, Inspector};
+use crate::style::Stylize;
+
+/// Hard links followed before giving up, in case of a cycle.
+const MAX_LINKS: usize = 8;
+
+/// Write the content of `path`, as the final filesystem of `image` shows it,
+/// to `output` (stdout if `None`).
+pub fn run(
+    image: &str,
+    path: &str,
+    output: Option<&Path>,
+    use_oci: bool,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(false, runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let (size, layer) = with_file(
+        &info,
+        inspector.as_mut(),
+        path,
+        None,
+        |content, _| match output {
+            Some(dest) => {
+                let mut file = File::create(dest)
+                    .with_context(|| t!("repack-write-failed", path = dest.display()))?;
+                io::copy(content, &mut file)
+                    .with_context(|| t!("repack-write-failed", path = dest.display()))
+            }
+            None => {
+                let mut stdout = io::stdout().lock();
+                let n = io::copy(content, &mut stdout)?;
+                stdout.flush()?;
+                Ok(n)
+            }
+        },
+    )?;
+
+    if let Some(output) = output {
+        println!(
+            "{}",
+            t!(
+                "extract-done",
+                path = output.display().to_string().bold(),
+                size = format_bytes(size),
+                layer = layer + 1
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Find `path` and hand its content and size to `f`. With `layer`, the copy
+/// that layer ships is used; otherwise the one the final filesystem shows.
+/// Returns what `f` returned and the index of the layer the file came from.
+pub fn with_file<T>(
+    info: &ImageInfo,
+    inspector: &mut dyn Inspector,
+    path: &str,
+    layer: Option<usize>,
+    f: impl FnOnce(&mut dyn Read, u64) -> Result<T>,
+) -> Result<(T, usize)> {
+    let files = layer.is_none().then(|| merged::merge(info));
+    let mut wanted = normalize(Path::new(path));
+
+    for _ in 0..MAX_LINKS {
+        let Some(index) = layer.or_else(|| Some(files.as_ref()?.get(&wanted)?.layer)) else {
+            bail!(t!("extract-not-found", path = path));
+        };
+
+        let mut archive = tar::Archive::new(inspector.read_layer(&info.layers[index])?);
+        let mut hard_link = None;
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            if normalize(&entry.path()?) != wanted {
+                continue;
+            }
+
+            let kind = entry.header().entry_type();
+            if kind.is_symlink() {
+                let target = entry.link_name()?.unwrap_or_default().into_owned();
+                bail!(t!(
+                    "extract-symlink",
+                    path = path,
+                    target = target.display()
+                ));
+            }
+            if kind.is_hard_link() {
+                // The content is stored under the link target
+                hard_link = entry.link_name()?.map(|t| normalize(&t));
+                break;
+            }
+            if !kind.is_file() {
+                continue;
+            }
+
+            let size = entry.size();
+            return Ok((f(&mut entry, size)?, index));
+        }
+
+        match (hard_link, layer) {
+            (Some(target), _) => wanted = target,
+            (None, Some(index)) => {
+                bail!(t!("extract-not-in-layer", path = path, layer = index + 1))
+            }
+            (None, None) => bail!(t!("extract-not-found", path = path)),
+        }
+    }
+    bail!(t!("extract-not-found", path = path))
+}
@@ -0,0 +1,301 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use super::inspect::{fit_start, format_bytes, open_inspector, print_runtime_summary, select_method};
+use super::output::{Output, write_json};
+use crate::analysis::merged::{OPAQUE_WHITEOUT, normalize};
+use crate::config;
+use crate::inspector::{FileChange, FileEntry};
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+/// Flags for `peel find`.
+pub struct Options {
+    /// Treat the pattern as a regular expression instead of a glob
+    pub regex: bool,
+
+    /// Match letters regardless of case
+    pub ignore_case: bool,
+}
+
+#[derive(Serialize)]
+pub struct FindReport {
+    pub pattern: String,
+    pub matches: Vec<FoundPath>,
+}
+
+/// A layer entry whose path matched.
+#[derive(Serialize)]
+pub struct FoundPath {
+    /// Layer holding the entry (base = 0)
+    pub layer: usize,
+
+    /// Normalized path, without a leading `/`; for deletions, the path deleted
+    pub path: PathBuf,
+
+    pub size: u64,
+
+    pub change: FileChange,
+
+    /// The final filesystem doesn't show this entry: a later layer
+    /// overwrote or deleted it, or it is itself a deletion
+    pub hidden: bool,
+}
+
+/// List the entries of every layer whose path matches `pattern`.
+///
+/// Layers are streamed a file at a time and only matches are kept, so huge
+/// images are searched in bounded memory.
+pub fn run(
+    image: &str,
+    pattern: &str,
+    opts: &Options,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    let source = if opts.regex {
+        pattern.to_string()
+    } else {
+        glob_to_regex(pattern)
+    };
+    let re = RegexBuilder::new(&source)
+        .case_insensitive(opts.ignore_case)
+        .build()
+        .with_context(|| t!("grep-bad-pattern", pattern = pattern))?;
+
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let info = inspector.inspect(image)?;
+
+    let mut search = Search::new(&re);
+    let num_layers = info.layers.len();
+    for (index, layer) in info.layers.iter().enumerate() {
+        spinner.set_message(t!("spinner-reading-layer", current = index + 1, total = num_layers));
+        inspector.for_each_file(layer, &mut |file| {
+            search.visit(index, file);
+            Ok(())
+        })?;
+        search.end_layer();
+    }
+    spinner.finish(t!("inspected-layers", count = num_layers));
+
+    let report = FindReport {
+        pattern: pattern.to_string(),
+        matches: search.finish(),
+    };
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "find", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_report(&report);
+            Ok(())
+        }
+    }
+}
+
+/// Matches found so far, and which of them the layers read so far leave
+/// visible. Only matching paths are tracked: an entry at the same path as a
+/// match matches too.
+struct Search<'a> {
+    re: &'a Regex,
+    matches: Vec<FoundPath>,
+
+    /// Visible matches by path, as indexes into `matches`
+    visible: BTreeMap<PathBuf, usize>,
+
+    /// This layer's deletions, applied at its end: each path with whether
+    /// it goes too, or only what's below it
+    removals: Vec<(PathBuf, bool)>,
+
+    /// This layer's matches, made visible at its end
+    additions: Vec<(PathBuf, usize)>,
+}
+
+impl<'a> Search<'a> {
+    fn new(re: &'a Regex) -> Self {
+        Self {
+            re,
+            matches: Vec::new(),
+            visible: BTreeMap::new(),
+            removals: Vec::new(),
+            additions: Vec::new(),
+        }
+    }
+
+    /// One entry of layer `index`. As in `merged::classify`, it is compared
+    /// with the layers below, before this layer's own deletions.
+    fn visit(&mut self, index: usize, file: FileEntry) {
+        let path = normalize(&file.path);
+        let (path, change) = if file.is_whiteout {
+            let Some(name) = path.file_name().map(|n| n.to_string_lossy().into_owned()) else {
+                return;
+            };
+            let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+            if name == OPAQUE_WHITEOUT {
+                self.removals.push((parent, false));
+                return;
+            }
+            let Some(target) = name.strip_prefix(".wh.") else {
+                return;
+            };
+            let target = parent.join(target);
+            self.removals.push((target.clone(), true));
+            (target, FileChange::Deleted)
+        } else {
+            // A file replacing a directory hides everything below it
+            if self.has_below(&path) {
+                self.removals.push((path.clone(), false));
+            }
+            let change = if self.visible.contains_key(&path) {
+                FileChange::Modified
+            } else {
+                FileChange::Added
+            };
+            (path, change)
+        };
+        if !self.re.is_match(&path.to_string_lossy()) {
+            return;
+        }
+        if !file.is_whiteout {
+            self.additions.push((path.clone(), self.matches.len()));
+        }
+        self.matches.push(FoundPath {
+            layer: index,
+            path,
+            size: file.size,
+            change,
+            hidden: true,
+        });
+    }
+
+    /// Apply the layer just read: deletions first, then its own matches.
+    fn end_layer(&mut self) {
+        for (path, itself) in std::mem::take(&mut self.removals) {
+            if itself {
+                self.visible.remove(&path);
+            }
+            let below: Vec<PathBuf> = self.below(&path).cloned().collect();
+            for child in below {
+                self.visible.remove(&child);
+            }
+        }
+        for (path, index) in std::mem::take(&mut self.additions) {
+            self.visible.insert(path, index);
+        }
+    }
+
+    /// Matches by layer and path, with what the final filesystem shows.
+    fn finish(mut self) -> Vec<FoundPath> {
+        for &index in self.visible.values() {
+            self.matches[index].hidden = false;
+        }
+        self.matches
+            .sort_by(|a, b| (a.layer, &a.path).cmp(&(b.layer, &b.path)));
+        self.matches
+    }
+
+    fn has_below(&self, dir: &Path) -> bool {
+        self.below(dir).next().is_some()
+    }
+
+    /// Visible matches strictly below `dir`; paths order component-wise, so
+    /// a subtree is a contiguous range.
+    fn below<'s>(&'s self, dir: &'s Path) -> impl Iterator<Item = &'s PathBuf> + 's {
+        self.visible
+            .range(dir.to_path_buf()..)
+            .map(|(p, _)| p)
+            .take_while(move |p| p.starts_with(dir))
+            .filter(move |p| p.as_path() != dir)
+    }
+}
+
+/// Anchored regex for a glob: `*` and `?` stay within one path component,
+/// `**` spans any number of them, and `[...]` is a character class. A
+/// pattern without `/` matches the file name at any depth.
+pub fn glob_to_regex(glob: &str) -> String {
+    let glob = glob.trim_start_matches('/');
+    let mut re = String::from(if glob.contains('/') { "^" } else { "^(?:.*/)?" });
+    let mut chars = glob.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    re.push_str("(?:.*/)?");
+                } else {
+                    re.push_str(".*");
+                }
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                re.push('[');
+                if chars.peek() == Some(&'!') {
+                    chars.next();
+                    re.push('^');
+                }
+                for c in chars.by_ref() {
+                    if c == ']' {
+                        break;
+                    }
+                    if c == '\\' {
+                        re.push('\\');
+                    }
+                    re.push(c);
+                }
+                re.push(']');
+            }
+            c => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    re
+}
+
+/// One line per match: layer, size, change and path.
+pub fn print_report(report: &FindReport) {
+    for m in &report.matches {
+        let change = match m.change {
+            FileChange::Added => t!("find-added"),
+            FileChange::Modified => t!("find-modified"),
+            FileChange::Deleted => t!("find-deleted"),
+        };
+        let path = format!("/{}", fit_start(&m.path.to_string_lossy(), 28));
+        let path = if m.hidden { path.dim() } else { path.cyan() };
+        println!(
+            "{:>3}  {:>10}  {:<8}  {path}",
+            m.layer + 1,
+            format_bytes(m.size),
+            change
+        );
+    }
+
+    if !report.matches.is_empty() {
+        println!();
+    }
+    let visible = report.matches.iter().filter(|m| !m.hidden).count();
+    println!(
+        "{}",
+        t!(
+            "find-summary",
+            count = report.matches.len(),
+            visible = visible
+        )
+    );
+}
@@ -0,0 +1,430 @@
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde_json::{Value, json};
+
+use super::inspect::{format_bytes, open_inspector, print_runtime_summary, select_method};
+use crate::analysis::merged::{OPAQUE_WHITEOUT, normalize};
+use crate::config;
+use crate::inspector::{ImageInfo, Inspector};
+use crate::progress::Spinner;
+use crate::repack::{HashingWriter, digest_of};
+use crate::style::Stylize;
+use crate::temp_files;
+
+const OCI_INDEX: &str = "application/vnd.oci.image.index.v1+json";
+const OCI_MANIFEST: &str = "application/vnd.oci.image.manifest.v1+json";
+const OCI_CONFIG: &str = "application/vnd.oci.image.config.v1+json";
+const OCI_LAYER: &str = "application/vnd.oci.image.layer.v1.tar";
+
+/// What `peel flatten` writes.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// Plain tar of the merged filesystem
+    Rootfs,
+    /// Single-layer image archive for `docker load` / `podman load`
+    Docker,
+    /// Single-layer image as an OCI layout tar
+    Oci,
+}
+
+/// One entry of a layer tar, as far as merging is concerned.
+enum Entry {
+    /// Hides a path (and everything below it) from lower layers
+    Whiteout(PathBuf),
+    /// Hides everything below a directory from lower layers
+    Opaque(PathBuf),
+    Dir(PathBuf),
+    File(PathBuf),
+}
+
+/// Merge every layer of `image` into one and write it to `output`.
+///
+/// Hard links are kept as they are, so a link whose target is replaced by a
+/// later layer ends up pointing at the replacement. They are written after
+/// everything else, so their targets exist by then.
+pub fn run(
+    image: &str,
+    output: &Path,
+    format: Format,
+    tag: Option<&str>,
+    use_oci: bool,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(false, runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let info = inspector.inspect(image)?;
+    let num_layers = info.layers.len();
+
+    // Pass 1: work out which layer provides each path of the final filesystem.
+    let mut winners: BTreeMap<PathBuf, usize> = BTreeMap::new();
+    for (i, layer) in info.layers.iter().enumerate() {
+        spinner.set_message(t!(
+            "spinner-reading-layer",
+            current = i + 1,
+            total = num_layers
+        ));
+        let entries = read_entries(inspector.read_layer(layer)?)?;
+        apply(&mut winners, i, &entries);
+    }
+
+    // Pass 2: copy each surviving entry from the layer that provides it.
+    let layer_path = match format {
+        Format::Rootfs => output.to_path_buf(),
+        Format::Docker | Format::Oci => {
+            let path =
+                std::env::temp_dir().join(format!("peel-flatten-{}.tar", std::process::id()));
+            temp_files::track(&path);
+            path
+        }
+    };
+    let layer = write_layer(inspector.as_mut(), &info, &winners, &layer_path, &spinner);
+    let result = layer.and_then(|layer| {
+        spinner.finish(t!(
+            "flatten-merged",
+            layers = num_layers,
+            files = layer.files
+        ));
+        let repo_tag = tag.map(str::to_string).or_else(|| {
+            info.tag
+                .as_ref()
+                .filter(|t| !t.is_empty())
+                .map(|t| format!("{}:{t}", info.name))
+        });
+        let config = flatten_config(inspector.config(), &info, &layer.diff_id)?;
+        let image = Image {
+            layer_path: &layer_path,
+            layer: &layer,
+            config: &config,
+            repo_tag: repo_tag.as_deref(),
+        };
+        match format {
+            Format::Rootfs => {}
+            Format::Docker => write_docker(output, &image)?,
+            Format::Oci => write_oci(output, &image)?,
+        }
+        Ok(layer)
+    });
+    if !matches!(format, Format::Rootfs) {
+        temp_files::remove(&layer_path);
+    }
+    let layer = result?;
+
+    let size = fs::metadata(output).map(|m| m.len()).unwrap_or(layer.size);
+    println!(
+        "{}",
+        t!(
+            "flatten-done",
+            path = output.display().to_string().bold(),
+            size = format_bytes(size),
+            before = format_bytes(info.total_size)
+        )
+    );
+    Ok(())
+}
+
+/// Read a layer's headers, classifying whiteouts. Overlay2 storage marks
+/// deletions with 0/0 character devices rather than `.wh.` files; its
+/// opaque directories come through `read_layer` as `.wh..wh..opq` markers.
+fn read_entries(reader: Box<dyn Read>) -> Result<Vec<Entry>> {
+    let mut entries = Vec::new();
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().context(t!("repack-read-failed"))? {
+        let entry = entry.context(t!("repack-read-failed"))?;
+        let path = normalize(&entry.path()?);
+        let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string()) else {
+            continue;
+        };
+        let parent = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        let header = entry.header();
+
+        entries.push(if name == OPAQUE_WHITEOUT {
+            Entry::Opaque(parent)
+        } else if let Some(hidden) = name.strip_prefix(".wh.") {
+            Entry::Whiteout(parent.join(hidden))
+        } else if header.entry_type().is_character_special()
+            && header.device_major().ok().flatten() == Some(0)
+            && header.device_minor().ok().flatten() == Some(0)
+        {
+            Entry::Whiteout(path)
+        } else if header.entry_type().is_dir() {
+            Entry::Dir(path)
+        } else {
+            Entry::File(path)
+        });
+    }
+    Ok(entries)
+}
+
+/// Apply one layer on top of `winners`, the same way `merged::apply_layer`
+/// does, except that directories are tracked too.
+fn apply(winners: &mut BTreeMap<PathBuf, usize>, layer: usize, entries: &[Entry]) {
+    for entry in entries {
+        match entry {
+            Entry::Whiteout(path) => {
+                winners.remove(path);
+                remove_children(winners, path);
+            }
+            Entry::Opaque(dir) => remove_children(winners, dir),
+            Entry::Dir(_) | Entry::File(_) => {}
+        }
+    }
+    for entry in entries {
+        match entry {
+            Entry::Dir(path) => {
+                winners.insert(path.clone(), layer);
+            }
+            Entry::File(path) => {
+                remove_children(winners, path);
+                winners.insert(path.clone(), layer);
+            }
+            Entry::Whiteout(_) | Entry::Opaque(_) => {}
+        }
+    }
+}
+
+fn remove_children(winners: &mut BTreeMap<PathBuf, usize>, dir: &Path) {
+    let doomed: Vec<PathBuf> = winners
+        .range(dir.to_path_buf()..)
+        .map(|(p, _)| p)
+        .take_while(|p| p.starts_with(dir))
+        .filter(|p| p.as_path() != dir)
+        .cloned()
+        .collect();
+    for path in doomed {
+        winners.remove(&path);
+    }
+}
+
+/// The merged layer as written to disk.
+struct FlatLayer {
+    diff_id: String,
+    size: u64,
+    files: usize,
+}
+
+fn write_layer(
+    inspector: &mut dyn Inspector,
+    info: &ImageInfo,
+    winners: &BTreeMap<PathBuf, usize>,
+    dest: &Path,
+    spinner: &Spinner,
+) -> Result<FlatLayer> {
+    let file =
+        File::create(dest).with_context(|| t!("repack-write-failed", path = dest.display()))?;
+    let mut builder = tar::Builder::new(HashingWriter::new(BufWriter::new(file)));
+    builder.mode(tar::HeaderMode::Complete);
+    let mut files = 0;
+    let mut hard_links = Vec::new();
+
+    let num_layers = info.layers.len();
+    for (i, layer) in info.layers.iter().enumerate() {
+        spinner.set_message(t!(
+            "spinner-flattening-layer",
+            current = i + 1,
+            total = num_layers
+        ));
+        let mut archive = tar::Archive::new(inspector.read_layer(layer)?);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            let path = normalize(&entry.path()?);
+            if winners.get(&path) != Some(&i) {
+                continue;
+            }
+
+            let mut header = entry.header().clone();
+            match entry.link_name()? {
+                Some(target) if header.entry_type().is_hard_link() => {
+                    hard_links.push((header, path, target.into_owned()));
+                    files += 1;
+                    continue;
+                }
+                Some(target) => {
+                    let target = target.into_owned();
+                    builder.append_link(&mut header, &path, &target)?;
+                }
+                None => builder.append_data(&mut header, &path, &mut entry)?,
+            }
+            if !header.entry_type().is_dir() {
+                files += 1;
+            }
+        }
+    }
+    for (mut header, path, target) in hard_links {
+        builder.append_link(&mut header, &path, &target)?;
+    }
+
+    let writer = builder.into_inner()?;
+    let layer = FlatLayer {
+        diff_id: writer.digest(),
+        size: writer.size(),
+        files,
+    };
+    writer
+        .into_inner()
+        .flush()
+        .with_context(|| t!("repack-write-failed", path = dest.display()))?;
+    Ok(layer)
+}
+
+/// The source config pointed at the single new layer. The original history
+/// is kept for reference, with every step marked as no longer owning a layer.
+fn flatten_config(source: Option<&[u8]>, info: &ImageInfo, diff_id: &str) -> Result<Vec<u8>> {
+    let mut config: Value = match source {
+        Some(raw) => serde_json::from_slice(raw).context(t!("flatten-bad-config"))?,
+        None => {
+            let mut config = json!({ "os": "linux" });
+            if let Some(arch) = &info.architecture {
+                config["architecture"] = json!(arch);
+            }
+            config
+        }
+    };
+    let Some(object) = config.as_object_mut() else {
+        bail!(t!("flatten-bad-config"));
+    };
+
+    object.insert(
+        "rootfs".into(),
+        json!({ "type": "layers", "diff_ids": [diff_id] }),
+    );
+    let mut history = object
+        .get("history")
+        .and_then(Value::as_array)
+        .cloned()
+        .unwrap_or_default();
+    for step in &mut history {
+        if let Some(step) = step.as_object_mut() {
+            step.insert("empty_layer".into(), json!(true));
+        }
+    }
+    history.push(json!({
+        "created_by": "peel flatten",
+        "comment": format!("flattened from {} layers", info.layers.len()),
+    }));
+    object.insert("history".into(), json!(history));
+
+    Ok(serde_json::to_vec(&config)?)
+}
+
+/// Everything needed to wrap the merged layer into an image archive.
+struct Image<'a> {
+    layer_path: &'a Path,
+    layer: &'a FlatLayer,
+    config: &'a [u8],
+    repo_tag: Option<&'a str>,
+}
+
+/// `docker save` layout: `manifest.json`, the config and `<id>/layer.tar`.
+fn write_docker(output: &Path, image: &Image) -> Result<()> {
+    let config_digest = digest_of(image.config);
+    let config_hex = hex(&config_digest);
+    let layer_name = format!("{}/layer.tar", hex(&image.layer.diff_id));
+    let manifest = json!([{
+        "Config": format!("{config_hex}.json"),
+        "RepoTags": image.repo_tag.into_iter().collect::<Vec<_>>(),
+        "Layers": [layer_name],
+    }]);
+
+    let mut builder = create_archive(output)?;
+    append_bytes(&mut builder, &format!("{config_hex}.json"), image.config)?;
+    builder.append_path_with_name(image.layer_path, &layer_name)?;
+    append_bytes(
+        &mut builder,
+        "manifest.json",
+        &serde_json::to_vec(&manifest)?,
+    )?;
+    finish_archive(builder, output)
+}
+
+/// OCI image layout with the layer stored uncompressed.
+fn write_oci(output: &Path, image: &Image) -> Result<()> {
+    let config_digest = digest_of(image.config);
+    let manifest = serde_json::to_vec(&json!({
+        "schemaVersion": 2,
+        "mediaType": OCI_MANIFEST,
+        "config": {
+            "mediaType": OCI_CONFIG,
+            "digest": config_digest,
+            "size": image.config.len(),
+        },
+        "layers": [{
+            "mediaType": OCI_LAYER,
+            "digest": image.layer.diff_id,
+            "size": image.layer.size,
+        }],
+    }))?;
+    let manifest_digest = digest_of(&manifest);
+
+    let mut descriptor = json!({
+        "mediaType": OCI_MANIFEST,
+        "digest": manifest_digest,
+        "size": manifest.len(),
+    });
+    if let Some(repo_tag) = image.repo_tag {
+        descriptor["annotations"] = json!({ "org.opencontainers.image.ref.name": repo_tag });
+    }
+    let index = json!({
+        "schemaVersion": 2,
+        "mediaType": OCI_INDEX,
+        "manifests": [descriptor],
+    });
+
+    let mut builder = create_archive(output)?;
+    append_bytes(
+        &mut builder,
+        "oci-layout",
+        br#"{"imageLayoutVersion":"1.0.0"}"#,
+    )?;
+    append_bytes(&mut builder, "index.json", &serde_json::to_vec(&index)?)?;
+    append_bytes(&mut builder, &blob_path(&manifest_digest), &manifest)?;
+    append_bytes(&mut builder, &blob_path(&config_digest), image.config)?;
+    builder.append_path_with_name(image.layer_path, blob_path(&image.layer.diff_id))?;
+    finish_archive(builder, output)
+}
+
+fn hex(digest: &str) -> &str {
+    digest.strip_prefix("sha256:").unwrap_or(digest)
+}
+
+fn blob_path(digest: &str) -> String {
+    format!("blobs/sha256/{}", hex(digest))
+}
+
+fn create_archive(output: &Path) -> Result<tar::Builder<BufWriter<File>>> {
+    let file =
+        File::create(output).with_context(|| t!("repack-write-failed", path = output.display()))?;
+    let mut builder = tar::Builder::new(BufWriter::new(file));
+    builder.mode(tar::HeaderMode::Deterministic);
+    Ok(builder)
+}
+
+fn append_bytes(
+    builder: &mut tar::Builder<BufWriter<File>>,
+    name: &str,
+    data: &[u8],
+) -> Result<()> {
+    let mut header = tar::Header::new_ustar();
+    header.set_size(data.len() as u64);
+    header.set_mode(0o644);
+    header.set_cksum();
+    builder.append_data(&mut header, name, data)?;
+    Ok(())
+}
+
+fn finish_archive(builder: tar::Builder<BufWriter<File>>, output: &Path) -> Result<()> {
+    builder
+        .into_inner()
+        .and_then(|mut w| w.flush())
+        .with_context(|| t!("repack-write-failed", path = output.display()))
+}
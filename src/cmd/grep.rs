@@ -0,0 +1,233 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use regex::bytes::{Regex, RegexBuilder};
+use serde::Serialize;
+
+use super::inspect::{
+    fit, format_bytes, load_image_with_inspector, print_runtime_summary, select_method,
+};
+use super::output::{Output, write_json};
+use crate::analysis::merged::{self, normalize};
+use crate::config;
+use crate::style::Stylize;
+
+/// Bytes of a matching line kept around the match.
+const CONTEXT_BYTES: usize = 160;
+
+/// Flags for `peel grep`.
+pub struct Options {
+    /// Match letters regardless of case
+    pub ignore_case: bool,
+
+    /// Skip files larger than this many bytes
+    pub max_size: u64,
+}
+
+#[derive(Serialize)]
+pub struct GrepReport {
+    pub pattern: String,
+    pub matches: Vec<GrepMatch>,
+
+    /// Files not searched because they exceed `--max-size`
+    pub skipped: Vec<SkippedFile>,
+}
+
+/// A matching line, or a matching binary file.
+#[derive(Serialize)]
+pub struct GrepMatch {
+    /// Layer holding this copy of the file (base = 0)
+    pub layer: usize,
+
+    /// Normalized path, without a leading `/`
+    pub path: PathBuf,
+
+    /// 1-based line number; `None` for binary files
+    pub line: Option<usize>,
+
+    /// The line (shortened around the match); `None` for binary files
+    pub text: Option<String>,
+
+    /// The final filesystem doesn't show this copy: a later layer
+    /// overwrote or deleted it
+    pub hidden: bool,
+}
+
+#[derive(Serialize)]
+pub struct SkippedFile {
+    pub layer: usize,
+    pub path: PathBuf,
+    pub size: u64,
+}
+
+/// Search the content of every regular file in every layer for `pattern`.
+pub fn run(
+    image: &str,
+    pattern: &str,
+    opts: &Options,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    let re = RegexBuilder::new(pattern)
+        .case_insensitive(opts.ignore_case)
+        .build()
+        .with_context(|| t!("grep-bad-pattern", pattern = pattern))?;
+
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let visible = merged::merge(&info);
+
+    let mut report = GrepReport {
+        pattern: pattern.to_string(),
+        matches: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for (index, layer) in info.layers.iter().enumerate() {
+        // The listing already honours --root
+        let listed: HashSet<PathBuf> = layer
+            .files
+            .iter()
+            .filter(|f| !f.is_whiteout)
+            .map(|f| normalize(&f.path))
+            .collect();
+
+        let mut archive = tar::Archive::new(inspector.read_layer(layer)?);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            let path = normalize(&entry.path()?);
+            if !entry.header().entry_type().is_file() || !listed.contains(&path) {
+                continue;
+            }
+            let size = entry.size();
+            if size > opts.max_size {
+                report.skipped.push(SkippedFile {
+                    layer: index,
+                    path,
+                    size,
+                });
+                continue;
+            }
+
+            let mut content = Vec::with_capacity(size as usize);
+            entry
+                .read_to_end(&mut content)
+                .context(t!("repack-read-failed"))?;
+            let hidden = visible.get(&path).is_none_or(|f| f.layer != index);
+            search(&re, &content, |line, text| {
+                report.matches.push(GrepMatch {
+                    layer: index,
+                    path: path.clone(),
+                    line,
+                    text,
+                    hidden,
+                })
+            });
+        }
+    }
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "grep", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_report(&report);
+            Ok(())
+        }
+    }
+}
+
+/// Call `found` for every matching line, or once with no line for a
+/// matching binary file.
+fn search(re: &Regex, content: &[u8], mut found: impl FnMut(Option<usize>, Option<String>)) {
+    if content.contains(&0) {
+        if re.is_match(content) {
+            found(None, None);
+        }
+        return;
+    }
+    for (number, line) in content.split(|&b| b == b'\n').enumerate() {
+        if let Some(m) = re.find(line) {
+            found(Some(number + 1), Some(excerpt(line, m.start())));
+        }
+    }
+}
+
+/// `line` without surrounding whitespace, cut down to `CONTEXT_BYTES` around
+/// `start` if it is longer.
+fn excerpt(line: &[u8], start: usize) -> String {
+    if line.len() <= CONTEXT_BYTES {
+        return String::from_utf8_lossy(line).trim().to_string();
+    }
+    let from = start.saturating_sub(CONTEXT_BYTES / 4);
+    let to = (from + CONTEXT_BYTES).min(line.len());
+    let text = String::from_utf8_lossy(&line[from..to]);
+    let text = text.trim_matches(|c: char| c == char::REPLACEMENT_CHARACTER || c.is_whitespace());
+    format!(
+        "{}{text}{}",
+        if from > 0 { "…" } else { "" },
+        if to < line.len() { "…" } else { "" }
+    )
+}
+
+/// One line per match: layer, `/path:line:` and the matching text.
+pub fn print_report(report: &GrepReport) {
+    for m in &report.matches {
+        let location = match m.line {
+            Some(line) => format!("/{}:{line}:", m.path.display()),
+            None => format!("/{}:", m.path.display()),
+        };
+        let text = match &m.text {
+            Some(text) => text.clone(),
+            None => t!("grep-binary"),
+        };
+        let hidden = if m.hidden {
+            t!("grep-hidden")
+        } else {
+            String::new()
+        };
+        let used = 5 + location.chars().count() + 1 + hidden.chars().count() + 2;
+        println!(
+            "{:>3}  {} {}  {}",
+            m.layer + 1,
+            location.magenta(),
+            fit(&text, used),
+            hidden.dim()
+        );
+    }
+
+    let files: HashSet<(usize, &PathBuf)> =
+        report.matches.iter().map(|m| (m.layer, &m.path)).collect();
+    if !report.matches.is_empty() {
+        println!();
+    }
+    println!(
+        "{}",
+        t!(
+            "grep-summary",
+            count = report.matches.len(),
+            files = files.len()
+        )
+    );
+    if !report.skipped.is_empty() {
+        let largest = report.skipped.iter().map(|s| s.size).max().unwrap_or(0);
+        println!(
+            "{}",
+            t!(
+                "grep-skipped",
+                count = report.skipped.len(),
+                size = format_bytes(largest)
+            )
+            .dim()
+        );
+    }
+}
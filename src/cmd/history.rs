@@ -0,0 +1,110 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::inspect::{
+    age, fit, format_bytes, open_inspector, print_runtime_summary, select_method,
+};
+use super::output::{Output, write_json};
+use crate::analysis::history::{self, HistoryEntry};
+use crate::config;
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+#[derive(Serialize)]
+pub struct HistoryReport {
+    pub image: String,
+
+    /// Base first, including steps that made no layer
+    pub entries: Vec<HistoryEntry>,
+}
+
+pub fn run(
+    image: &str,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    // The config and layer sizes are all we need, so skip file enumeration.
+    let spinner = Spinner::new(t!("spinner-resolving-image", image = image));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let info = inspector.inspect(image)?;
+    spinner.finish(t!(
+        "resolved-image",
+        image = image,
+        count = info.layers.len()
+    ));
+
+    let report = HistoryReport {
+        image: info.name.clone(),
+        entries: history::entries(&info, inspector.config()),
+    };
+
+    // No web view: the table is the whole report.
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "history", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_history(&report);
+            Ok(())
+        }
+    }
+}
+
+/// One row per entry; steps that made no layer are dimmed.
+pub fn print_history(report: &HistoryReport) {
+    const USED: usize = 3 + 2 + 4 + 2 + 10 + 2;
+    println!("{:>3}  {:>4}  {:>10}  CREATED BY", "#", "AGE", "SIZE");
+    for entry in &report.entries {
+        let layer = entry
+            .layer
+            .map_or_else(|| "-".to_string(), |i| (i + 1).to_string());
+        let age = entry.created.as_deref().and_then(age);
+        let columns = format!(
+            "{layer:>3}  {:>4}  {:>10}  ",
+            age.as_deref().unwrap_or("-"),
+            format_bytes(entry.size)
+        );
+        let (keyword, rest) = match (&entry.instruction, &entry.created_by) {
+            (Some(parsed), _) => (parsed.instruction.as_str(), one_line(&parsed.arguments)),
+            (None, Some(created_by)) => ("", one_line(created_by)),
+            (None, None) => ("", entry.comment.clone().unwrap_or_default()),
+        };
+        let step = match keyword {
+            "" => fit(&rest, USED),
+            _ => fit(&format!("{keyword} {rest}"), USED),
+        };
+        match step.strip_prefix(keyword) {
+            _ if entry.layer.is_none() => println!("{}", format!("{columns}{step}").dim()),
+            Some(rest) if !keyword.is_empty() => println!("{columns}{}{rest}", keyword.bold()),
+            _ => println!("{columns}{step}"),
+        }
+    }
+
+    let layers = report.entries.iter().filter(|e| e.layer.is_some()).count();
+    let size: u64 = report.entries.iter().map(|e| e.size).sum();
+    println!();
+    println!(
+        "{}",
+        t!(
+            "history-summary",
+            entries = report.entries.len(),
+            layers = layers,
+            size = format_bytes(size)
+        )
+        .dim()
+    );
+}
+
+/// Heredoc bodies and continuation lines, joined onto one line.
+fn one_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -1,20 +1,1305 @@
-use std::fs;
+use std::collections::BTreeMap;
 use std::io::{self, Write};
 use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 
-use anyhow::{Context, Result};
-use crossterm::style::{self, Stylize};
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
 
+use super::check::{self, CheckResult, FailOn, FailureClass};
+use super::entry_index;
+use super::junit::{self, Failure, TestCase, TestSuite};
+use super::layer_table::{self, Column, Row};
+use super::output::{Annotation, Output, annotate, write_csv, write_json};
+use super::preset::Analyzers;
+use crate::analysis::base::{self, BaseSplit};
+use crate::analysis::cache_mounts::{self, CacheLeak};
+use crate::analysis::content::{self, Category, ContentBreakdown};
+use crate::analysis::dirs::{self, DirSize};
+use crate::analysis::distro;
+use crate::analysis::dockerfile::{self, DockerfileMap, SourceRef};
+use crate::analysis::duplicates::{self, DuplicateReport};
+use crate::analysis::entropy::{self, HighEntropyFile};
+use crate::analysis::file_types::{self, FileType, FileTypeBreakdown};
+use crate::analysis::image_class::{self, ClassReport, ImageClass};
+use crate::analysis::junk::{self, Junk, JunkKind};
+use crate::analysis::merged::{self, MergedFile};
+use crate::analysis::posture::{self, PostureReport, mode_string};
+use crate::analysis::runtimes::{self, Runtime};
+use crate::analysis::strip::{self, StripKind, StripReport};
+use crate::analysis::toolchain::{self, ToolKind, Toolchain};
+use crate::analysis::verify::{self, VerifyReport};
+use crate::analysis::waste::{self, WasteReport};
 use crate::config;
-use crate::inspector::{self, Inspector};
+use crate::inspector::provenance::Provenance;
+use crate::inspector::run_config::{Healthcheck, RunConfig};
+use crate::inspector::{self, ImageInfo, Inspector, LayerInfo};
+use crate::kube::{self, ClusterCheck, Status};
+use crate::logging;
 use crate::probe::{RuntimeInfo, RuntimeKind, StorageDriver};
-use crate::progress::Spinner;
+use crate::progress::{self, Spinner};
+use crate::style::{self, Stylize};
 
-pub fn run(image: &str, use_oci: bool, json: Option<&str>, runtime: Option<String>, web: bool, no_sudo: bool) -> Result<()> {
-    config::init_from_cli(json.is_some(), runtime)?;
+/// Number of wasted paths shown in the terminal summary.
+const TOP_WASTED: usize = 10;
+
+/// setuid and world-writable files listed in the terminal summary.
+const TOP_POSTURE_FILES: usize = 20;
+
+/// File types named per layer in the terminal summary.
+const TOP_FILE_TYPES: usize = 3;
+
+/// Width, in cells, of the per-layer category bars; narrower terminals get
+/// shorter bars, down to `MIN_BAR_WIDTH`.
+const CONTENT_BAR_WIDTH: usize = 24;
+const MIN_BAR_WIDTH: usize = 8;
+
+/// Layers whose compressed blob is at least this share of their size gain
+/// little from compression: already-compressed assets or random data.
+const POOR_COMPRESSION: f64 = 0.9;
+
+/// Smaller layers are never flagged; tar and gzip overhead dominates them.
+const MIN_COMPRESSION_SIZE: u64 = 1024 * 1024;
+
+/// Columns kept for text when a line is cut to fit the terminal.
+const MIN_ROOM: usize = 12;
+
+/// Inspection result as written to JSON and the web report.
+#[derive(Serialize, Deserialize)]
+pub struct InspectReport {
+    #[serde(flatten)]
+    pub info: ImageInfo,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<BaseSplit>,
+
+    /// Final filesystem after applying every layer, with `--merged`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merged: Option<Vec<MergedFile>>,
+
+    /// Bytes in lower layers hidden by later overwrites and deletions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub waste: Option<WasteReport>,
+
+    /// Large files that look packed or encrypted, largest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub high_entropy: Vec<HighEntropyFile>,
+
+    /// Build caches committed into layers that a cache mount would keep out
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cache_mounts: Vec<CacheLeak>,
+
+    /// Well-known files nothing needs at runtime (package lists, caches,
+    /// `.git`), largest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub junk: Vec<Junk>,
+
+    /// Compilers, build tools, headers and package managers left in the final
+    /// filesystem, largest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub toolchain: Vec<Toolchain>,
+
+    /// Python, Node.js, Java and Go installations, with their versions
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub runtimes: Vec<Runtime>,
+
+    /// Identical files stored more than once, with `--checksums`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duplicates: Option<DuplicateReport>,
+
+    /// setuid and world-writable files, and whether the image runs as root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub posture: Option<PostureReport>,
+
+    /// Man pages, docs, locales and debug symbols that could be stripped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strip: Option<StripReport>,
+
+    /// Bytes per content category (OS, runtime, code, ...), per layer and in total
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<ContentBreakdown>,
+
+    /// Bytes per file type (executables, shared libraries, locales, ...), per
+    /// layer and in total
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file_types: Option<FileTypeBreakdown>,
+
+    /// Cumulative size of every directory in the final filesystem, in path order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dirs: Vec<DirSize>,
+
+    /// Distro, minimal, distroless or scratch, from the final filesystem
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<ClassReport>,
+
+    /// Workloads running this image's repository, with `--kubecontext` or
+    /// `--namespace`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cluster: Option<ClusterCheck>,
+
+    /// The Dockerfile line behind each layer, with `--dockerfile`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<DockerfileMap>,
+
+    /// Layer contents checked against their diff_ids, with `--verify`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verify: Option<VerifyReport>,
+}
+
+/// Flags that only apply to `peel inspect`.
+pub struct Options<'a> {
+    /// Base image to attribute inherited size to
+    pub base: Option<&'a str>,
+
+    /// Include the final merged filesystem
+    pub merged: bool,
+
+    /// Fail if efficiency (in percent) is below this
+    pub min_efficiency: Option<f64>,
+
+    /// Conditions to fail on, like `size>1GB` or `secrets`
+    pub fail_on: &'a [FailOn],
+
+    /// Write a tar entry index of the layer blobs here
+    pub emit_index: Option<&'a Path>,
+
+    /// Dockerfile to trace layers back to
+    pub dockerfile: Option<&'a Path>,
+
+    /// Cluster to check for workloads running this image
+    pub cluster: Option<kube::Target<'a>>,
+
+    /// List no files, only layer metadata; the OCI path then skips saving
+    /// the image
+    pub no_files: bool,
+
+    /// Hash every layer and compare it with its diff_id
+    pub verify: bool,
+
+    /// Analyzers to run, all of them unless a preset says otherwise
+    pub analyzers: Analyzers,
+}
+
+impl Options<'_> {
+    /// Every analyzer, no checks and no side outputs: the reports `peel serve`
+    /// and `--rpc` answer with.
+    pub fn all() -> Options<'static> {
+        Options {
+            base: None,
+            merged: false,
+            min_efficiency: None,
+            fail_on: &[],
+            emit_index: None,
+            dockerfile: None,
+            cluster: None,
+            no_files: false,
+            verify: false,
+            analyzers: Analyzers::ALL,
+        }
+    }
+}
+
+pub fn run(
+    image: &str,
+    opts: &Options,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    check::reject_severities(opts.fail_on)?;
+    if opts.no_files
+        && opts
+            .fail_on
+            .iter()
+            .any(|f| matches!(f, FailOn::Files(_) | FailOn::Efficiency(_) | FailOn::Secrets))
+    {
+        bail!(t!("no-files-fail-on"));
+    }
+    config::init_from_cli(output.is_json(), runtime)?;
     let cfg = config::get();
 
-    let method = if looks_like_archive(image) {
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+    inspect_image(cfg, image, opts, use_oci, output, no_sudo)
+}
+
+/// `run` once the runtime is known: analyze `image`, write the report and
+/// apply the thresholds.
+pub fn inspect_image(
+    cfg: &config::AppConfig,
+    image: &str,
+    opts: &Options,
+    use_oci: bool,
+    output: &Output,
+    no_sudo: bool,
+) -> Result<()> {
+    let (report, gates) = analyze(cfg, image, opts, use_oci, no_sudo, &mut |_, _| Ok(()))?;
+
+    let mut phase = Some(tracing::debug_span!("report").entered());
+    match output {
+        Output::Web => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            let html = super::report::build_report(&json_str);
+            // Serving lasts until Ctrl+C
+            phase.take();
+            super::report::publish(&report.info.name, &json_str, &html)?;
+        }
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            let html = super::report::build_report(&json_str);
+            super::report::write_bundle(dir, "inspect", &json_str, Some(&html))?;
+        }
+        Output::Json(dest) => write_json(dest, &report)?,
+        Output::Csv(dest) => write_csv(dest, &CSV_HEADER, csv_rows(&report.info))?,
+        Output::Text | Output::Github | Output::Junit(_) => print_report(&report),
+    }
+    drop(phase);
+
+    // Checked after the report is written so CI still gets it on failure
+    let efficiency = opts
+        .min_efficiency
+        .zip(report.waste.as_ref())
+        .map(|(target, waste)| {
+            let efficiency = waste.efficiency * 100.0;
+            (efficiency < target).then(|| {
+                t!(
+                    "efficiency-below-target",
+                    efficiency = format!("{efficiency:.1}%"),
+                    target = format!("{target}%")
+                )
+            })
+        });
+    let text = matches!(output, Output::Text | Output::Github | Output::Junit(_));
+    if text && !gates.is_empty() {
+        println!();
+        check::print_results(&gates);
+    }
+    match output {
+        Output::Github => {
+            if let Some(Some(message)) = &efficiency {
+                annotate(Annotation::Error, "peel inspect", message);
+            }
+            for c in gates.iter().filter(|c| !c.passed) {
+                let title = format!("peel inspect: {}", c.check.name());
+                annotate(Annotation::Error, &title, &c.check.describe());
+            }
+        }
+        Output::Junit(path) => {
+            let suite = checks_suite(&report.info.name, efficiency.clone(), &gates);
+            junit::write(path, &[suite])?;
+        }
+        _ => {}
+    }
+    // A layer that isn't what the image says outweighs any threshold
+    if let Some(verify) = report.verify.as_ref().filter(|v| !v.mismatches.is_empty()) {
+        bail!(t!("verify-failed", count = verify.mismatches.len()));
+    }
+    let gate = check::gate_failed(&gates);
+    match (efficiency.flatten(), gate) {
+        // The exit code is still the lowest failing class's
+        (Some(message), gate) => bail!(check::GateFailed {
+            class: gate.map_or(FailureClass::Efficiency, |g| {
+                g.class.min(FailureClass::Efficiency)
+            }),
+            message,
+        }),
+        (None, Some(gate)) => bail!(gate),
+        (None, None) => Ok(()),
+    }
+}
+
+/// Load `image`, run the analyzers `opts` asks for and evaluate its
+/// `--fail-on` checks, without writing anything. `on_layer` is called as in
+/// `load_image_each_layer`.
+pub fn analyze(
+    cfg: &config::AppConfig,
+    image: &str,
+    opts: &Options,
+    use_oci: bool,
+    no_sudo: bool,
+    on_layer: &mut dyn FnMut(usize, &LayerInfo) -> Result<()>,
+) -> Result<(InspectReport, Vec<CheckResult>)> {
+    // Read first, so a wrong path fails before the image is loaded
+    let dockerfile_text = opts
+        .dockerfile
+        .map(|path| {
+            std::fs::read_to_string(path)
+                .with_context(|| t!("dockerfile-read-failed", path = path.display()))
+        })
+        .transpose()?;
+    let (info, mut inspector) = if opts.no_files {
+        load_metadata_with_inspector(cfg, image, use_oci, no_sudo)?
+    } else {
+        let (mut info, mut inspector) =
+            load_image_each_layer(cfg, image, use_oci, no_sudo, on_layer)?;
+        info.distro = distro::read(&info, inspector.as_mut())?;
+        (info, inspector)
+    };
+    if let Some(dest) = opts.emit_index {
+        let count = entry_index::write(&info, inspector.as_mut(), dest)?;
+        progress::done(t!("wrote-index", count = count, path = dest.display()));
+    }
+    let verify = if opts.verify {
+        let spinner = Spinner::new(t!("spinner-verifying"));
+        let report = verify::verify(&info, inspector.as_mut())?;
+        spinner.finish(t!(
+            "verify-done",
+            verified = report.verified,
+            mismatched = report.mismatches.len()
+        ));
+        Some(report)
+    } else {
+        None
+    };
+    let cluster = match &opts.cluster {
+        Some(target) => {
+            let mut digests: Vec<String> = inspector
+                .config()
+                .map(crate::inspector::config_digest)
+                .into_iter()
+                .collect();
+            if !looks_like_archive(image)
+                && let Some(rt) = cfg.probe.default.map(|i| &cfg.probe.runtimes[i])
+            {
+                digests.extend(kube::repo_digests(&rt.binary_path.to_string_lossy(), image));
+            }
+            let check = kube::check(target, &info.name, digests)?;
+            warn_mismatches(&check);
+            Some(check)
+        }
+        None => None,
+    };
+    // Needs the image config, which goes with the inspector
+    let posture = opts
+        .analyzers
+        .posture
+        .then(|| posture::analyze(&info, inspector.config()));
+    let runtimes = if opts.analyzers.runtimes {
+        runtimes::read(&info, inspector.as_mut())?
+    } else {
+        Vec::new()
+    };
+    // `--fail-on secrets` reads the layers
+    let gates = check::fail_on_checks(&info, inspector.as_mut(), opts.fail_on)?;
+    let gates = check::evaluate(gates);
+    // Releases the OCI save output before a base image is loaded
+    drop(inspector);
+    let base = match opts.base {
+        Some(base_image) => Some(split_base(cfg, &info, base_image, use_oci, no_sudo)?),
+        None => None,
+    };
+    let analyzers = &opts.analyzers;
+    let start = Instant::now();
+    let phase = tracing::debug_span!("analyze").entered();
+    let merged = opts.merged.then(|| merged::merge(&info).into_values().collect());
+    let waste = analyzers.waste.then(|| waste::analyze(&info));
+    let high_entropy = if analyzers.entropy { entropy::scan(&info) } else { Vec::new() };
+    let class = (analyzers.class || analyzers.cache_mounts).then(|| image_class::classify(&info));
+    let cache_mounts = match &class {
+        Some(class) if analyzers.cache_mounts => {
+            cache_mounts::detect(&info, class.class == ImageClass::Distro)
+        }
+        _ => Vec::new(),
+    };
+    let junk = if analyzers.junk { junk::detect(&info) } else { Vec::new() };
+    let toolchain = if analyzers.toolchain { toolchain::detect(&info) } else { Vec::new() };
+    let duplicates = analyzers.duplicates.then(|| duplicates::detect(&info)).flatten();
+    let strip = analyzers.strip.then(|| strip::detect(&info)).flatten();
+    let content = analyzers.content.then(|| content::analyze(&info));
+    let file_types = analyzers.file_types.then(|| file_types::analyze(&info));
+    let dirs = if analyzers.content { dirs::analyze(&info) } else { Vec::new() };
+    let dockerfile = opts.dockerfile.zip(dockerfile_text).map(|(path, text)| {
+        let map = dockerfile::map(path.to_path_buf(), &text, &info);
+        if map.layers.iter().all(Option::is_none) {
+            tracing::warn!("{}", t!("dockerfile-no-match", path = path.display()));
+        }
+        map
+    });
+    drop(phase);
+    tracing::debug!("ran analyzers in {:.2?}", start.elapsed());
+    let report = InspectReport {
+        info,
+        base,
+        merged,
+        waste,
+        high_entropy,
+        cache_mounts,
+        junk,
+        toolchain,
+        runtimes,
+        duplicates,
+        posture,
+        strip,
+        content,
+        file_types,
+        dirs,
+        class: class.filter(|_| analyzers.class),
+        cluster,
+        dockerfile,
+        verify,
+    };
+    Ok((report, gates))
+}
+
+/// The `--min-efficiency` check as a JUnit test case.
+fn efficiency_case(failure: Option<String>) -> TestCase {
+    TestCase {
+        classname: "peel.inspect".to_string(),
+        name: "min-efficiency".to_string(),
+        failure: failure.map(|message| Failure {
+            details: vec![message.clone()],
+            message,
+            kind: "error".to_string(),
+        }),
+        output: Vec::new(),
+    }
+}
+
+/// The checks inspect ran: `--min-efficiency`, if given, and then each
+/// `--fail-on` condition.
+fn checks_suite(
+    image: &str,
+    efficiency: Option<Option<String>>,
+    gates: &[CheckResult],
+) -> TestSuite {
+    let cases = efficiency
+        .map(efficiency_case)
+        .into_iter()
+        .chain(gates.iter().map(|c| check::test_case(c, "peel.inspect")))
+        .collect();
+    TestSuite {
+        name: image.to_string(),
+        cases,
+    }
+}
+
+/// `Dockerfile:12`, or `Dockerfile:12-15` for an instruction over several
+/// lines.
+fn source_location(path: &Path, source: &SourceRef) -> String {
+    if source.end_line > source.line {
+        format!("{}:{}-{}", path.display(), source.line, source.end_line)
+    } else {
+        format!("{}:{}", path.display(), source.line)
+    }
+}
+
+/// How many layers matched their diff_id, then each one that didn't.
+fn print_verify(verify: &VerifyReport, layers: usize) {
+    let summary = t!(
+        "inspect-verified",
+        verified = verify.verified,
+        total = layers,
+        skipped = verify.skipped
+    );
+    if verify.mismatches.is_empty() {
+        println!("  {}", summary.green());
+        return;
+    }
+    println!("  {summary}");
+    for mismatch in &verify.mismatches {
+        let found = match (&mismatch.actual, &mismatch.error) {
+            (Some(actual), _) => actual.clone(),
+            (None, Some(error)) => error.clone(),
+            (None, None) => String::new(),
+        };
+        println!(
+            "    {}",
+            t!(
+                "inspect-diff-id-mismatch",
+                layer = mismatch.layer + 1,
+                expected = &mismatch.expected,
+                found = found
+            )
+            .red()
+        );
+    }
+}
+
+/// Builder, repository and commit, build args and pulled images, where the
+/// build recorded them.
+fn print_provenance(provenance: &Provenance) {
+    let builder = match (&provenance.builder, &provenance.frontend) {
+        (Some(builder), Some(frontend)) => Some(format!("{builder} ({frontend})")),
+        (builder, frontend) => builder.clone().or(frontend.clone()),
+    };
+    if let Some(builder) = builder {
+        println!("  {}", t!("inspect-built-by", builder = builder));
+    }
+    let source = match (&provenance.vcs_source, &provenance.vcs_revision) {
+        (Some(source), Some(revision)) => Some(format!("{source} @ {revision}")),
+        (source, revision) => source.clone().or(revision.clone()),
+    };
+    if let Some(source) = source {
+        println!("  {}", t!("inspect-vcs", source = source));
+    }
+    if !provenance.build_args.is_empty() {
+        let args: Vec<String> = provenance
+            .build_args
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect();
+        println!("  {}", t!("inspect-build-args", args = args.join(" ")));
+    }
+    if !provenance.base_images.is_empty() {
+        let images: Vec<String> = provenance
+            .base_images
+            .iter()
+            .map(|image| match &image.digest {
+                Some(digest) if !image.reference.contains('@') => {
+                    format!("{}@{digest}", image.reference)
+                }
+                _ => image.reference.clone(),
+            })
+            .collect();
+        println!("  {}", t!("inspect-base-images", images = images.join(", ")));
+    }
+}
+
+/// Labels or annotations, one `key=value` per line.
+fn print_metadata(heading: String, entries: &BTreeMap<String, String>) {
+    if entries.is_empty() {
+        return;
+    }
+    println!("  {heading}");
+    for (key, value) in entries {
+        println!("    {}", fit(&format!("{key}={value}"), 4).dim());
+    }
+}
+
+/// Columns of `--output csv`.
+const CSV_HEADER: [&str; 7] = [
+    "image", "layer", "digest", "command", "path", "size", "whiteout",
+];
+
+/// One row per file of every layer, for spreadsheets and database imports.
+fn csv_rows(info: &ImageInfo) -> impl Iterator<Item = Vec<String>> + '_ {
+    info.layers
+        .iter()
+        .enumerate()
+        .flat_map(move |(index, layer)| {
+            layer.files.iter().map(move |file| {
+                vec![
+                    info.name.clone(),
+                    index.to_string(),
+                    layer.digest.clone(),
+                    layer.created_by.clone().unwrap_or_default(),
+                    file.path.to_string_lossy().into_owned(),
+                    file.size.to_string(),
+                    file.is_whiteout.to_string(),
+                ]
+            })
+        })
+}
+
+/// Plain-text layer summary, as shown with `--no-web`.
+pub fn print_report(report: &InspectReport) {
+    let info = &report.info;
+    println!("{}", info.name);
+    if let Some(id) = &info.id {
+        println!("  {}", t!("inspect-id", id = id));
+    }
+    if !info.manifest_digests.is_empty() {
+        println!(
+            "  {}",
+            t!("inspect-digests", digests = info.manifest_digests.join(", "))
+        );
+    }
+    if let Some(arch) = &info.architecture {
+        println!("  {}", t!("inspect-arch", arch = arch));
+    }
+    if let Some(created) = info.created.as_deref().and_then(created) {
+        println!("  {created}");
+    }
+    if let Some(distro) = &info.distro {
+        println!("  {}", t!("inspect-distro", distro = distro.label()));
+    }
+    if let Some(class) = &report.class {
+        println!("  {}", t!("inspect-class", class = class_name(class)));
+    }
+    if !report.runtimes.is_empty() {
+        let names: Vec<String> = report
+            .runtimes
+            .iter()
+            .map(|r| match &r.version {
+                Some(version) => format!("{} {version}", r.kind.name()),
+                None => r.kind.name().to_string(),
+            })
+            .collect();
+        println!("  {}", t!("inspect-runtimes", runtimes = names.join(", ")));
+    }
+    if let Some(provenance) = &info.provenance {
+        print_provenance(provenance);
+    }
+    print_metadata(t!("inspect-labels"), &info.labels);
+    print_metadata(t!("inspect-annotations"), &info.annotations);
+    println!("  {}", t!("inspect-total-size", size = info.total_size));
+    if let Some(compressed) = info.compressed_size() {
+        println!("  {}", t!("inspect-compressed-size", size = compressed));
+    }
+    if !info.roots.is_empty() {
+        let roots: Vec<String> = info.roots.iter().map(|r| format!("/{}", r.display())).collect();
+        println!("  {}", t!("inspect-partial", roots = roots.join(", ")).yellow());
+    }
+    if let Some(verify) = &report.verify {
+        print_verify(verify, info.layers.len());
+    }
+    if let Some(base) = &report.base {
+        println!(
+            "  {}",
+            t!(
+                "inspect-inherited-size",
+                base = &base.name,
+                size = format_bytes(base.inherited_size),
+                count = base.layers,
+            )
+        );
+        println!("  {}", t!("inspect-added-size", size = format_bytes(base.added_size)));
+    }
+    if let Some(waste) = &report.waste {
+        println!(
+            "  {}",
+            t!(
+                "inspect-efficiency",
+                efficiency = format!("{:.1}%", waste.efficiency * 100.0).bold()
+            )
+        );
+        println!(
+            "  {}",
+            t!("inspect-wasted-size", size = format_bytes(waste.wasted_size))
+        );
+    }
+    println!();
+
+    if let Some(files) = &report.merged {
+        print_merged(files);
+        return;
+    }
+
+    let rows: Vec<Row> = info
+        .layers
+        .iter()
+        .enumerate()
+        .map(|(i, layer)| Row {
+            digest: &layer.digest,
+            size: layer.size,
+            compressed_size: layer.compressed_size,
+            poor_compression: layer.size >= MIN_COMPRESSION_SIZE
+                && layer.compression_ratio().is_some_and(|r| r >= POOR_COMPRESSION),
+            files: Some(layer.files.len()),
+            created: layer.created.as_deref(),
+            command: layer_table::command(
+                layer.instruction.as_ref(),
+                layer.created_by.as_deref(),
+            ),
+            source: report
+                .dockerfile
+                .as_ref()
+                .and_then(|map| Some(source_location(&map.path, map.layers.get(i)?.as_ref()?))),
+        })
+        .collect();
+    let mut columns = vec![Column::Index, Column::Digest, Column::Size];
+    if rows.iter().any(|r| r.compressed_size.is_some()) {
+        columns.push(Column::Compressed);
+    }
+    if info.layers.iter().any(|l| !l.files.is_empty()) {
+        columns.push(Column::Files);
+    }
+    if rows.iter().any(|r| r.created.is_some()) {
+        columns.push(Column::Created);
+    }
+    if report.dockerfile.is_some() {
+        columns.push(Column::Source);
+    }
+    columns.push(Column::Command);
+    let divider = report.base.as_ref().map(|b| (b.layers, t!("inspect-added-layers")));
+    layer_table::print(&rows, &columns, divider);
+    println!();
+
+    if let Some(run) = &info.run_config {
+        print_run_config(run);
+    }
+    if let Some(waste) = report.waste.as_ref().filter(|w| !w.paths.is_empty()) {
+        print_waste(waste);
+    }
+    if !report.high_entropy.is_empty() {
+        print_high_entropy(&report.high_entropy);
+    }
+    if let Some(class) = report.class.as_ref().filter(|c| !c.largest.is_empty()) {
+        print_largest(&class.largest);
+    }
+    if !report.cache_mounts.is_empty() {
+        print_cache_mounts(&report.cache_mounts);
+    }
+    if !report.junk.is_empty() {
+        print_junk(&report.junk, &report.info);
+    }
+    if !report.toolchain.is_empty() {
+        print_toolchain(&report.toolchain);
+    }
+    if let Some(duplicates) = report.duplicates.as_ref().filter(|d| !d.groups.is_empty()) {
+        print_duplicates(duplicates);
+    }
+    if let Some(posture) = &report.posture {
+        print_posture(posture);
+    }
+    if let Some(strip) = &report.strip {
+        print_strip(strip);
+    }
+    if let Some(content) = report.content.as_ref().filter(|c| !c.totals.is_empty()) {
+        print_content(content);
+    }
+    if let Some(types) = report.file_types.as_ref().filter(|t| !t.totals.is_empty()) {
+        print_file_types(types);
+    }
+    if let Some(cluster) = &report.cluster {
+        print_cluster(cluster);
+    }
+}
+
+/// Entrypoint, command, user, environment, ports, volumes and healthcheck
+/// from the image config.
+fn print_run_config(run: &RunConfig) {
+    println!("{}", t!("inspect-runtime").dim());
+    let json = |list: &Vec<String>| serde_json::to_string(list).unwrap_or_default();
+    if let Some(entrypoint) = &run.entrypoint {
+        println!("  {}", t!("runtime-entrypoint", value = json(entrypoint)));
+    }
+    if let Some(cmd) = &run.cmd {
+        println!("  {}", t!("runtime-cmd", value = json(cmd)));
+    }
+    if let Some(dir) = &run.working_dir {
+        println!("  {}", t!("runtime-workdir", value = dir));
+    }
+    if let Some(user) = &run.user {
+        println!("  {}", t!("runtime-user", value = user));
+    }
+    if !run.exposed_ports.is_empty() {
+        println!("  {}", t!("runtime-ports", value = run.exposed_ports.join(", ")));
+    }
+    if !run.volumes.is_empty() {
+        println!("  {}", t!("runtime-volumes", value = run.volumes.join(", ")));
+    }
+    if let Some(check) = &run.healthcheck {
+        println!("  {}", t!("runtime-healthcheck", value = healthcheck(check)));
+    }
+    if !run.env.is_empty() {
+        println!("  {}", t!("runtime-env"));
+        for var in &run.env {
+            println!("    {}", fit(var, 4).dim());
+        }
+    }
+}
+
+/// `curl -f http://localhost/ (interval 30s, retries 3)`, or `none` for
+/// `HEALTHCHECK NONE`.
+fn healthcheck(check: &Healthcheck) -> String {
+    let test = match check.test.split_first() {
+        Some((kind, rest)) if kind == "CMD-SHELL" => rest.join(" "),
+        Some((kind, rest)) if kind == "CMD" => serde_json::to_string(rest).unwrap_or_default(),
+        Some((kind, _)) if kind == "NONE" => return "none".to_string(),
+        _ => check.test.join(" "),
+    };
+    let mut options = Vec::new();
+    for (name, seconds) in [
+        ("interval", check.interval),
+        ("timeout", check.timeout),
+        ("start-period", check.start_period),
+    ] {
+        if let Some(seconds) = seconds {
+            options.push(format!("{name} {seconds}s"));
+        }
+    }
+    if let Some(retries) = check.retries {
+        options.push(format!("retries {retries}"));
+    }
+    if options.is_empty() {
+        test
+    } else {
+        format!("{test} ({})", options.join(", "))
+    }
+}
+
+/// Warn, whatever the output format, about workloads running a
+/// different build of the inspected repository.
+fn warn_mismatches(check: &ClusterCheck) {
+    for w in check.workloads.iter().filter(|w| w.status == Status::Mismatch) {
+        tracing::warn!(
+            "{}",
+            t!(
+                "cluster-mismatch",
+                workload = format!("{}/{}", w.namespace, w.name),
+                container = &w.container,
+                digest = w.digest.as_deref().unwrap_or_default()
+            )
+            .yellow()
+        );
+    }
+}
+
+/// Workloads running the inspected repository and whether they run this build.
+fn print_cluster(check: &ClusterCheck) {
+    if check.workloads.is_empty() {
+        println!("{}", t!("cluster-none").dim());
+        return;
+    }
+    println!("{}", t!("inspect-cluster").dim());
+    for w in &check.workloads {
+        let status = match w.status {
+            Status::Match => format!("{:<10}", t!("cluster-match")).green(),
+            Status::Mismatch => format!("{:<10}", t!("cluster-differs")).yellow(),
+            Status::Unknown => format!("{:<10}", t!("cluster-unknown")).dim(),
+        };
+        println!(
+            "  {status}  {:>3}  {}/{} [{}]",
+            w.pods, w.namespace, w.name, w.container
+        );
+    }
+}
+
+/// Paths with the most bytes shadowed by later layers.
+fn print_waste(waste: &WasteReport) {
+    println!("{}", t!("inspect-wasted-paths").dim());
+    for path in waste.paths.iter().take(TOP_WASTED) {
+        let layers: Vec<String> = path.layers.iter().map(|l| (l + 1).to_string()).collect();
+        let layers = layers.join(",");
+        let deleted = if path.deleted {
+            format!(" ({})", t!("inspect-deleted"))
+        } else {
+            String::new()
+        };
+        let used = 16 + layers.len().max(8) + 3 + deleted.chars().count();
+        println!(
+            "  {:>10}  {:<8}  /{}{}",
+            format_bytes(path.wasted_size),
+            layers,
+            style::style(fit_start(&path.path.to_string_lossy(), used)).cyan(),
+            deleted
+        );
+    }
+}
+
+/// Large files that look packed or encrypted, with the layer that ships them.
+fn print_high_entropy(files: &[HighEntropyFile]) {
+    println!("{}", t!("inspect-high-entropy").dim());
+    for file in files {
+        println!(
+            "  {:>10}  {:>4.2}  {:>3}  /{}",
+            format_bytes(file.size),
+            file.entropy,
+            file.layer + 1,
+            style::style(fit_start(&file.path.to_string_lossy(), 26)).yellow()
+        );
+    }
+}
+
+/// `distroless (dpkg, no shell)` and the like.
+fn class_name(report: &ClassReport) -> String {
+    let class = match report.class {
+        ImageClass::Distro => t!("class-distro"),
+        ImageClass::Minimal => t!("class-minimal"),
+        ImageClass::Distroless => t!("class-distroless"),
+        ImageClass::Scratch => t!("class-scratch"),
+    };
+    let mut details: Vec<String> = report.package_db.iter().cloned().collect();
+    if !report.shell {
+        details.push(t!("class-no-shell"));
+    }
+    if details.is_empty() {
+        class
+    } else {
+        format!("{class} ({})", details.join(", "))
+    }
+}
+
+/// The biggest files of an image without a package manager, which is all
+/// the inventory there is.
+fn print_largest(files: &[MergedFile]) {
+    println!("{}", t!("inspect-largest").dim());
+    for file in files {
+        println!(
+            "  {:>10}  {:>3}  /{}",
+            format_bytes(file.size),
+            file.layer + 1,
+            style::style(fit_start(&file.path.to_string_lossy(), 20)).cyan()
+        );
+    }
+}
+
+/// Removable files with the layers that added them and how to avoid it.
+fn print_junk(junk: &[Junk], info: &ImageInfo) {
+    let total: u64 = junk.iter().map(|j| j.size).sum();
+    println!("{}", t!("inspect-junk", size = format_bytes(total)).dim());
+    for j in junk {
+        let name = junk_name(j.kind);
+        let first = j.paths.first().map(|p| p.to_string_lossy()).unwrap_or_default();
+        let paths = if j.paths.len() == 1 {
+            format!("/{first}")
+        } else {
+            t!("junk-dirs", count = j.paths.len(), path = format!("/{first}"))
+        };
+        let used = 14 + name.chars().count() + 2;
+        println!(
+            "  {:>10}  {name}  {}",
+            format_bytes(j.size),
+            style::style(fit_start(&paths, used)).cyan()
+        );
+        for layer in &j.layers {
+            let created_by = info.layers[layer.layer].created_by.as_deref().unwrap_or("");
+            println!(
+                "{:>14}{:>3}  {:>10}  {}",
+                "",
+                layer.layer + 1,
+                format_bytes(layer.size),
+                fit(created_by, 31).dim()
+            );
+        }
+        println!("{:>14}{}", "", fit(&junk_fix(j.kind), 14));
+    }
+}
+
+/// Build tools in the final image, which a multi-stage build would leave behind.
+fn print_toolchain(tools: &[Toolchain]) {
+    let total: u64 = tools.iter().map(|t| t.size).sum();
+    println!("{}", t!("inspect-toolchain", size = format_bytes(total)).dim());
+    for tool in tools {
+        let kind = match tool.kind {
+            ToolKind::Compiler => t!("tool-compiler"),
+            ToolKind::BuildTool => t!("tool-build-tool"),
+            ToolKind::Headers => t!("tool-headers"),
+            ToolKind::PackageManager => t!("tool-package-manager"),
+        };
+        let label = format!("{} ({kind})", tool.name);
+        let programs: Vec<String> = tool.programs.iter().map(|p| format!("/{p}")).collect();
+        let detail = if programs.is_empty() {
+            t!("tool-files", count = tool.files)
+        } else {
+            format!("{}; {}", programs.join(", "), t!("tool-files", count = tool.files))
+        };
+        println!(
+            "  {:>10}  {:<24}  {}",
+            format_bytes(tool.size),
+            label,
+            fit(&detail, 14 + label.chars().count().max(24) + 2).cyan()
+        );
+    }
+}
+
+/// Who the image runs as, then setuid/setgid and world-writable files with
+/// the layer that ships each.
+fn print_posture(posture: &PostureReport) {
+    println!("{}", t!("inspect-posture").dim());
+    let user = match (&posture.user, posture.runs_as_root) {
+        (_, None) => t!("posture-user-unknown").dim().to_string(),
+        (None, Some(_)) => t!("posture-root-default").yellow().to_string(),
+        (Some(user), Some(true)) => t!("posture-root", user = user).yellow().to_string(),
+        (Some(user), Some(false)) => t!("posture-user", user = user).green().to_string(),
+    };
+    println!("  {user}");
+    for (files, label) in [
+        (&posture.setuid, t!("posture-setuid", count = posture.setuid.len())),
+        (
+            &posture.world_writable,
+            t!("posture-world-writable", count = posture.world_writable.len()),
+        ),
+    ] {
+        if files.is_empty() {
+            continue;
+        }
+        println!("  {label}");
+        for file in files.iter().take(TOP_POSTURE_FILES) {
+            let owner = match (file.uid, file.gid) {
+                (Some(uid), Some(gid)) => format!("{uid}:{gid}"),
+                _ => "-".to_string(),
+            };
+            println!(
+                "    {:>3}  {}  {:<11}  {}",
+                file.layer + 1,
+                mode_string(file.mode).yellow(),
+                owner,
+                fit(&format!("/{}", file.path.display()), 34)
+            );
+        }
+        if files.len() > TOP_POSTURE_FILES {
+            println!(
+                "    {}",
+                t!("posture-more", count = files.len() - TOP_POSTURE_FILES).dim()
+            );
+        }
+    }
+}
+
+/// The most wasteful groups of identical files, with where each copy is.
+fn print_duplicates(duplicates: &DuplicateReport) {
+    println!(
+        "{}",
+        t!("inspect-duplicates", size = format_bytes(duplicates.wasted_size)).dim()
+    );
+    for group in duplicates.groups.iter().take(TOP_WASTED) {
+        println!(
+            "  {:>10}  {}",
+            format_bytes(group.wasted()),
+            t!(
+                "duplicates-copies",
+                count = group.copies.len(),
+                size = format_bytes(group.size)
+            )
+        );
+        for copy in &group.copies {
+            println!(
+                "{:>14}{:>3}  /{}",
+                "",
+                copy.layer + 1,
+                style::style(fit_start(&copy.path.to_string_lossy(), 20)).cyan()
+            );
+        }
+    }
+    if duplicates.groups.len() > TOP_WASTED {
+        println!(
+            "  {}",
+            t!("duplicates-more", count = duplicates.groups.len() - TOP_WASTED).dim()
+        );
+    }
+}
+
+/// Docs, locales and debug symbols, with the `rm` that would drop them.
+fn print_strip(strip: &StripReport) {
+    println!("{}", t!("inspect-strip", size = format_bytes(strip.size)).dim());
+    for candidate in &strip.candidates {
+        let kind = match candidate.kind {
+            StripKind::Man => t!("strip-man"),
+            StripKind::Docs => t!("strip-docs"),
+            StripKind::Info => t!("strip-info"),
+            StripKind::Locales => t!("strip-locales"),
+            StripKind::DebugSymbols => t!("strip-debug"),
+        };
+        let layers: Vec<String> = candidate.layers.iter().map(|l| (l + 1).to_string()).collect();
+        println!(
+            "  {:>10}  {:<8}  {:<14}  /{}",
+            format_bytes(candidate.size),
+            layers.join(","),
+            kind,
+            style::style(&candidate.path).cyan()
+        );
+    }
+    println!("  {}", t!("strip-hint"));
+    println!("    {}", fit(&format!("RUN ... && {}", strip.snippet), 4).green());
+}
+
+pub fn junk_name(kind: JunkKind) -> String {
+    match kind {
+        JunkKind::AptLists => t!("junk-apt-lists"),
+        JunkKind::AptCache => t!("junk-apt-cache"),
+        JunkKind::PipCache => t!("junk-pip-cache"),
+        JunkKind::NpmCache => t!("junk-npm-cache"),
+        JunkKind::YarnCache => t!("junk-yarn-cache"),
+        JunkKind::Pycache => t!("junk-pycache"),
+        JunkKind::Git => t!("junk-git"),
+    }
+}
+
+/// How to keep this kind of junk out of the image.
+pub fn junk_fix(kind: JunkKind) -> String {
+    match kind {
+        JunkKind::AptLists => t!("junk-fix-apt-lists"),
+        JunkKind::AptCache => t!("junk-fix-apt-cache"),
+        JunkKind::PipCache => t!("junk-fix-pip-cache"),
+        JunkKind::NpmCache => t!("junk-fix-npm-cache"),
+        JunkKind::YarnCache => t!("junk-fix-yarn-cache"),
+        JunkKind::Pycache => t!("junk-fix-pycache"),
+        JunkKind::Git => t!("junk-fix-git"),
+    }
+}
+
+/// Leaked build caches with the mount that would keep them out.
+fn print_cache_mounts(leaks: &[CacheLeak]) {
+    let total: u64 = leaks.iter().map(|l| l.size).sum();
+    println!(
+        "{}",
+        t!("inspect-cache-mounts", size = format_bytes(total)).dim()
+    );
+    for leak in leaks {
+        let layers: Vec<String> = leak.layers.iter().map(|l| (l + 1).to_string()).collect();
+        let layers = layers.join(",");
+        let used = 16 + layers.len().max(8) + 3;
+        println!(
+            "  {:>10}  {:<8}  /{}",
+            format_bytes(leak.size),
+            layers,
+            style::style(fit_start(&leak.path, used)).cyan()
+        );
+        println!("{:>24}{}", "", fit(&format!("RUN {} ...", leak.mount), 24));
+    }
+}
+
+/// One stacked bar per layer showing its mix of categories, then the totals,
+/// which double as the legend.
+fn print_content(content: &ContentBreakdown) {
+    println!("{}", t!("inspect-content").dim());
+    let bar_width = config::width().map_or(CONTENT_BAR_WIDTH, |w| {
+        w.saturating_sub(21).clamp(MIN_BAR_WIDTH, CONTENT_BAR_WIDTH)
+    });
+    for (i, sizes) in content.layers.iter().enumerate() {
+        let size: u64 = sizes.values().sum();
+        let mut bar = String::new();
+        let mut filled = 0;
+        let mut running = 0;
+        for (category, bytes) in sizes {
+            // Round the running total so the segments always add up to the width
+            running += bytes;
+            let end = (running * bar_width as u64)
+                .checked_div(size)
+                .unwrap_or(0) as usize;
+            bar.push_str(&paint(*category, &"█".repeat(end - filled)));
+            filled = end;
+        }
+        bar.push_str(&" ".repeat(bar_width - filled));
+        println!("  {:>3}  {bar}  {}", i + 1, format_bytes(size));
+    }
+
+    let total: u64 = content.totals.values().sum();
+    let legend = Category::ALL.iter().filter_map(|category| {
+        let bytes = *content.totals.get(category)?;
+        let pct = if total > 0 { bytes as f64 / total as f64 * 100.0 } else { 0.0 };
+        let name = category_name(*category);
+        let rest = format!(" {} ({pct:.0}%)", format_bytes(bytes));
+        let len = name.chars().count() + rest.len();
+        Some((format!("{}{rest}", paint(*category, &name)), len))
+    });
+    print_wrapped(legend);
+}
+
+/// Print `(styled text, visible length)` items two spaces apart on indented
+/// lines, wrapping rather than running off a narrow terminal.
+fn print_wrapped(items: impl IntoIterator<Item = (String, usize)>) {
+    let mut line = String::new();
+    let mut line_len = 0;
+    for (item, len) in items {
+        if line_len > 0 && config::width().is_some_and(|w| 2 + line_len + 2 + len > w) {
+            println!("  {line}");
+            line.clear();
+            line_len = 0;
+        }
+        if line_len > 0 {
+            line.push_str("  ");
+            line_len += 2;
+        }
+        line.push_str(&item);
+        line_len += len;
+    }
+    println!("  {line}");
+}
+
+/// Share of each layer taken by its largest file types, then image-wide
+/// totals.
+fn print_file_types(types: &FileTypeBreakdown) {
+    println!("{}", t!("inspect-file-types").dim());
+    for (i, sizes) in types.layers.iter().enumerate() {
+        let size: u64 = sizes.values().sum();
+        if size == 0 {
+            continue;
+        }
+        let mut largest: Vec<(&FileType, &u64)> = sizes.iter().collect();
+        largest.sort_by_key(|(_, bytes)| std::cmp::Reverse(**bytes));
+        let shares: Vec<String> = largest
+            .iter()
+            .take(TOP_FILE_TYPES)
+            .map(|(kind, bytes)| {
+                let pct = **bytes as f64 / size as f64 * 100.0;
+                format!("{} {pct:.0}%", file_type_name(**kind))
+            })
+            .collect();
+        println!("  {:>3}  {}", i + 1, fit(&shares.join(", "), 7));
+    }
+
+    let total: u64 = types.totals.values().sum();
+    let legend = FileType::ALL.iter().filter_map(|kind| {
+        let bytes = *types.totals.get(kind)?;
+        let pct = if total > 0 { bytes as f64 / total as f64 * 100.0 } else { 0.0 };
+        let text = format!("{} {} ({pct:.0}%)", file_type_name(*kind), format_bytes(bytes));
+        let len = text.chars().count();
+        Some((text, len))
+    });
+    print_wrapped(legend);
+}
+
+fn file_type_name(kind: FileType) -> String {
+    match kind {
+        FileType::Executable => t!("file-type-executable"),
+        FileType::SharedLib => t!("file-type-shared-lib"),
+        FileType::Python => t!("file-type-python"),
+        FileType::JavaScript => t!("file-type-javascript"),
+        FileType::Docs => t!("file-type-docs"),
+        FileType::Locale => t!("file-type-locale"),
+        FileType::Image => t!("file-type-image"),
+        FileType::Archive => t!("file-type-archive"),
+        FileType::Other => t!("file-type-other"),
+    }
+}
+
+fn category_name(category: Category) -> String {
+    match category {
+        Category::Os => t!("category-os"),
+        Category::Runtime => t!("category-runtime"),
+        Category::Code => t!("category-code"),
+        Category::Assets => t!("category-assets"),
+        Category::Data => t!("category-data"),
+        Category::Cache => t!("category-cache"),
+        Category::Other => t!("category-other"),
+    }
+}
+
+fn paint(category: Category, text: &str) -> String {
+    let text = style::style(text);
+    match category {
+        Category::Os => text.blue(),
+        Category::Runtime => text.magenta(),
+        Category::Code => text.green(),
+        Category::Assets => text.yellow(),
+        Category::Data => text.cyan(),
+        Category::Cache => text.red(),
+        Category::Other => text.dark_grey(),
+    }
+    .to_string()
+}
+
+/// List the merged filesystem: one line per file with its size and source layer.
+fn print_merged(files: &[MergedFile]) {
+    let total: u64 = files.iter().map(|f| f.size).sum();
+    println!(
+        "{}",
+        t!("inspect-merged-summary", count = files.len(), size = format_bytes(total)).dim()
+    );
+    for file in files {
+        println!(
+            "  {:>10}  {:>3}  /{}",
+            format_bytes(file.size),
+            file.layer + 1,
+            fit_start(&file.path.to_string_lossy(), 20)
+        );
+    }
+}
+
+/// Read `base_image`'s layer digests and work out how much of `info` it accounts for.
+fn split_base(
+    cfg: &config::AppConfig,
+    info: &ImageInfo,
+    base_image: &str,
+    use_oci: bool,
+    no_sudo: bool,
+) -> Result<BaseSplit> {
+    // Only digests and sizes are compared, so skip file enumeration.
+    let spinner = Spinner::new(t!("spinner-resolving-image", image = base_image));
+    let mut inspector = open_inspector(cfg, base_image, use_oci, no_sudo, &spinner)?;
+    let base_info = inspector.inspect(base_image)?;
+    spinner.finish(t!("resolved-image", image = base_image, count = base_info.layers.len()));
+
+    base::split(info, &base_info)
+        .with_context(|| t!("base-unrelated", image = &info.name, base = base_image))
+}
+
+/// Name of the backend that `load_image` will pick for `image`.
+pub fn select_method(cfg: &config::AppConfig, image: &str, use_oci: bool) -> &'static str {
+    if looks_like_archive(image) {
         "archive"
     } else if use_oci {
         "oci"
@@ -26,16 +1311,100 @@ pub fn run(image: &str, use_oci: bool, json: Option<&str>, runtime: Option<Strin
                 _ => None,
             })
             .unwrap_or("oci")
-    };
+    }
+}
 
-    print_runtime_summary(cfg, method);
+/// Pick a backend for `image`, read its metadata and list the files of every layer.
+pub fn load_image(cfg: &config::AppConfig, image: &str, use_oci: bool, no_sudo: bool) -> Result<ImageInfo> {
+    load_image_with_inspector(cfg, image, use_oci, no_sudo).map(|(info, _)| info)
+}
 
-    let spinner = Spinner::new("Resolving image metadata...");
+/// `load_image`, keeping the backend open for reading layer contents afterwards.
+pub fn load_image_with_inspector(
+    cfg: &config::AppConfig,
+    image: &str,
+    use_oci: bool,
+    no_sudo: bool,
+) -> Result<(ImageInfo, Box<dyn Inspector>)> {
+    load_image_each_layer(cfg, image, use_oci, no_sudo, &mut |_, _| Ok(()))
+}
+
+/// `load_image_with_inspector`, calling `on_layer` with each layer's index
+/// once its files are listed. An error from `on_layer` stops loading.
+pub fn load_image_each_layer(
+    cfg: &config::AppConfig,
+    image: &str,
+    use_oci: bool,
+    no_sudo: bool,
+    on_layer: &mut dyn FnMut(usize, &LayerInfo) -> Result<()>,
+) -> Result<(ImageInfo, Box<dyn Inspector>)> {
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
 
+    let start = Instant::now();
+    let mut info = inspector.inspect(image)?;
+    tracing::debug!("read {image} in {:.2?}", start.elapsed());
+
+    let num_layers = info.layers.len();
+    let start = Instant::now();
+    for (i, layer) in info.layers.iter_mut().enumerate() {
+        spinner.set_message(t!("spinner-reading-layer", current = i + 1, total = num_layers));
+        let listed = Instant::now();
+        layer.files = inspector.list_files(layer)?;
+        tracing::trace!(
+            "listed {} files of {} in {:.2?}",
+            layer.files.len(),
+            layer.digest,
+            listed.elapsed()
+        );
+        on_layer(i, layer)?;
+    }
+    tracing::debug!("listed files of {num_layers} layers in {:.2?}", start.elapsed());
+    let roots = &config::listing().roots;
+    if !roots.is_empty() {
+        // Backend sizes cover whole layers; report only what's below the roots
+        for layer in &mut info.layers {
+            layer.size = layer.files.iter().map(|f| f.size).sum();
+            layer.compressed_size = None;
+        }
+        info.total_size = info.layers.iter().map(|l| l.size).sum();
+        info.roots = roots.paths().to_vec();
+    }
+    merged::classify(&mut info);
+    spinner.finish(t!("inspected-layers", count = num_layers));
+
+    Ok((info, inspector))
+}
+
+/// `load_image_with_inspector` without listing any files, for `--no-files`.
+pub fn load_metadata_with_inspector(
+    cfg: &config::AppConfig,
+    image: &str,
+    use_oci: bool,
+    no_sudo: bool,
+) -> Result<(ImageInfo, Box<dyn Inspector>)> {
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let info = inspector.inspect_metadata(image)?;
+    spinner.finish(t!("inspected-layers", count = info.layers.len()));
+    Ok((info, inspector))
+}
+
+/// Construct the inspector backend for `image` (escalating to sudo if needed).
+pub fn open_inspector(
+    cfg: &config::AppConfig,
+    image: &str,
+    use_oci: bool,
+    no_sudo: bool,
+    spinner: &Spinner,
+) -> Result<Box<dyn Inspector>> {
     // If the image looks like a tar file, use the archive inspector directly
-    let mut inspector: Box<dyn Inspector> = if looks_like_archive(image) {
+    let inspector: Box<dyn Inspector> = if looks_like_archive(image) {
+        tracing::debug!("reading {image} as an image archive");
+        config::set_backend("archive");
         Box::new(inspector::docker_archive::DockerArchiveInspector::new(
             image.into(),
+            config::listing().clone(),
         ))
     } else if use_oci {
         // Use OCI/runtime API path
@@ -47,8 +1416,10 @@ pub fn run(image: &str, use_oci: bool, json: Option<&str>, runtime: Option<Strin
                 (rt.binary_path.display().to_string(), rt.kind.clone())
             })
             .unwrap_or_else(|| ("docker".to_string(), RuntimeKind::Docker));
-        let mut oci = inspector::oci::OciInspector::new(cmd, kind);
-        oci.set_progress_bar(spinner.clone_bar());
+        tracing::debug!("reading {image} through {cmd}, as --use-oci asks");
+        config::set_backend("oci");
+        let mut oci = inspector::oci::OciInspector::new(cmd, kind, config::listing().clone());
+        oci.set_progress(spinner.progress());
         Box::new(oci)
     } else {
         // Direct storage access — may need sudo
@@ -57,110 +1428,57 @@ pub fn run(image: &str, use_oci: bool, json: Option<&str>, runtime: Option<Strin
             if !rt.can_read {
                 // Finish spinner before escalating — sudo re-execs the process
                 // and the parent's spinner would otherwise keep ticking.
-                spinner.finish("Resolved image metadata");
+                spinner.finish(t!("resolved-metadata"));
                 maybe_escalate(rt, no_sudo)?;
                 unreachable!();
             }
             match rt.storage_driver {
                 #[cfg(target_os = "linux")]
                 StorageDriver::Overlay2 | StorageDriver::Fuse | StorageDriver::Vfs => {
+                    tracing::debug!(
+                        "reading {image} from {} storage at {}",
+                        rt.kind,
+                        rt.storage_root.display()
+                    );
+                    config::set_backend("overlay2");
                     Box::new(inspector::overlay2::Overlay2Inspector::new(
                         rt.storage_root.clone(),
+                        config::listing().clone(),
                     ))
                 }
                 _ => {
                     // Unsupported storage driver for direct access, fall back to OCI
+                    tracing::debug!(
+                        "{:?} storage can't be read directly; reading {image} through {}",
+                        rt.storage_driver,
+                        rt.binary_path.display()
+                    );
+                    config::set_backend("oci");
                     let mut oci = inspector::oci::OciInspector::new(
                         rt.binary_path.display().to_string(),
                         rt.kind.clone(),
+                        config::listing().clone(),
                     );
-                    oci.set_progress_bar(spinner.clone_bar());
+                    oci.set_progress(spinner.progress());
                     Box::new(oci)
                 }
             }
         } else {
-            anyhow::bail!("No container runtime detected. Install Docker or Podman, or use a tar archive.");
+            anyhow::bail!(t!("runtime-none-installed"));
         }
     };
 
-    let mut info = inspector.inspect(image)?;
-
-    let num_layers = info.layers.len();
-    for (i, layer) in info.layers.iter_mut().enumerate() {
-        spinner.set_message(format!("Reading layer {}/{} ...", i + 1, num_layers));
-        layer.files = inspector.list_files(layer)?;
-    }
-    spinner.finish(format!("Inspected {} layers", num_layers));
-
-    if web {
-        let json_str = serde_json::to_string_pretty(&info)?;
-        let safe_name = info
-            .name
-            .replace(|c: char| !c.is_alphanumeric() && c != '-', "_");
-        let salt: u16 = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .map(|d| (d.as_millis() % 10000) as u16)
-            .unwrap_or(0);
-        let tmp = std::env::temp_dir();
-        let json_path = tmp.join(format!("peel-{safe_name}-{salt}.json"));
-        let html_path = tmp.join(format!("peel-{safe_name}-{salt}.html"));
-
-        fs::write(&json_path, &json_str)
-            .with_context(|| format!("Failed to write JSON to {}", json_path.display()))?;
-        eprintln!(
-            "{} Wrote {} ({})",
-            "✔".green(),
-            style::style(json_path.display()).cyan(),
-            format_bytes(json_str.len() as u64)
-        );
-
-        let html = super::report::build_report(&json_str);
-        fs::write(&html_path, &html)
-            .with_context(|| format!("Failed to write HTML to {}", html_path.display()))?;
-        eprintln!(
-            "{} Wrote {} ({})",
-            "✔".green(),
-            style::style(html_path.display()).cyan(),
-            format_bytes(html.len() as u64)
-        );
-
-        return super::report::serve(&html);
-    }
-
-    if let Some(dest) = json {
-        let output = serde_json::to_string_pretty(&info)?;
-        if dest == "-" {
-            println!("{output}");
-        } else {
-            fs::write(dest, &output)
-                .with_context(|| format!("Failed to write JSON to {dest}"))?;
-            eprintln!("{} Wrote {dest}", "✔".green());
-        }
-    } else {
-        println!("{}", info.name);
-        if let Some(arch) = &info.architecture {
-            println!("  arch: {arch}");
-        }
-        println!("  total size: {} bytes", info.total_size);
-        println!();
-        for layer in &info.layers {
-            println!("{}", layer.digest);
-            if let Some(cmd) = &layer.created_by {
-                println!("  {cmd}");
-            }
-            println!("  size: {} bytes", layer.size);
-            println!();
-        }
-    }
-
-    Ok(())
+    Ok(inspector)
 }
 
-fn print_runtime_summary(cfg: &config::AppConfig, method: &str) {
+pub fn print_runtime_summary(cfg: &config::AppConfig, method: &str) {
     let mut stderr = io::stderr();
 
     if cfg.probe.runtimes.is_empty() {
-        let _ = writeln!(stderr, "{} No container runtimes detected", "!".yellow().bold());
+        tracing::warn!("{}", t!("runtimes-none"));
+        return;
+    }
+    if !logging::status() {
         return;
     }
 
@@ -173,7 +1491,7 @@ fn print_runtime_summary(cfg: &config::AppConfig, method: &str) {
     let _ = writeln!(
         stderr,
         "{} {}",
-        "Runtimes".dim(),
+        t!("runtimes-label").dim(),
         detected.join(", ")
     );
 
@@ -181,18 +1499,97 @@ fn print_runtime_summary(cfg: &config::AppConfig, method: &str) {
         let rt = &cfg.probe.runtimes[idx];
         let _ = writeln!(
             stderr,
-            "{} {} (storage: {}, method: {})",
-            "Selected".dim(),
+            "{} {} {}",
+            t!("selected-label").dim(),
             style::style(&rt.kind).green().bold(),
-            style::style(rt.storage_root.display()).dim(),
-            style::style(method).dim(),
+            t!(
+                "selected-detail",
+                root = style::style(rt.storage_root.display()).dim(),
+                method = style::style(method).dim(),
+            ),
         );
     }
 
     let _ = writeln!(stderr);
 }
 
-fn format_bytes(bytes: u64) -> String {
+/// `text` cut to fit in what's left of the line after `used` columns, ending
+/// in `…` if anything was cut. Left whole when output isn't a terminal.
+pub fn fit(text: &str, used: usize) -> String {
+    let Some(room) = room(used) else {
+        return text.to_string();
+    };
+    if text.chars().count() <= room {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(room - 1).collect();
+    cut.push('…');
+    cut
+}
+
+/// Like `fit`, but keeps the end of `text`, which for paths is the part
+/// that tells files apart.
+pub fn fit_start(text: &str, used: usize) -> String {
+    let Some(room) = room(used) else {
+        return text.to_string();
+    };
+    let count = text.chars().count();
+    if count <= room {
+        return text.to_string();
+    }
+    let mut cut = String::from('…');
+    cut.extend(text.chars().skip(count - room + 1));
+    cut
+}
+
+/// `text` cut to at most `width` characters, ending in `…` if anything was
+/// cut, for a column of fixed width. Unlike `fit`, cut on any output.
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        return text.to_string();
+    }
+    let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+    cut.push('…');
+    cut
+}
+
+/// Columns left after `used`, never fewer than `MIN_ROOM`.
+fn room(used: usize) -> Option<usize> {
+    config::width().map(|w| w.saturating_sub(used).max(MIN_ROOM))
+}
+
+/// `created: 2024-05-01 (3d ago)`; `None` if the timestamp can't be read.
+fn created(timestamp: &str) -> Option<String> {
+    Some(t!(
+        "inspect-created",
+        date = timestamp.get(..10).unwrap_or(timestamp),
+        age = age(timestamp)?
+    ))
+}
+
+/// How long ago an RFC 3339 timestamp was, e.g. `3d`; `None` if it can't be
+/// read.
+pub fn age(timestamp: &str) -> Option<String> {
+    let secs = inspector::parse_created(timestamp)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    Some(format_age(now - secs))
+}
+
+/// A compact duration: `45m`, `5h`, `12d`, `4mo`, `2y`.
+fn format_age(secs: i64) -> String {
+    let (minutes, hours, days) = (secs / 60, secs / 3600, secs / 86400);
+    match secs {
+        ..3600 => format!("{}m", minutes.max(0)),
+        3600..86400 => format!("{hours}h"),
+        _ if days < 60 => format!("{days}d"),
+        _ if days < 730 => format!("{}mo", days / 30),
+        _ => format!("{}y", days / 365),
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
     const UNITS: &[&str] = &["B", "KB", "MB", "GB"];
     let mut size = bytes as f64;
     for unit in UNITS {
@@ -208,7 +1605,7 @@ fn format_bytes(bytes: u64) -> String {
     format!("{:.1} TB", size)
 }
 
-fn looks_like_archive(image: &str) -> bool {
+pub fn looks_like_archive(image: &str) -> bool {
     let p = Path::new(image);
     matches!(
         p.extension().and_then(|e| e.to_str()),
@@ -225,18 +1622,25 @@ fn escalate_with_sudo() -> Result<()> {
         .args(&args)
         .env("PEEL_ESCALATED", "1")
         .status()?;
+    crate::pager::finish();
     std::process::exit(status.code().unwrap_or(1));
 }
 
+/// Escalate now if `method` reads storage this process can't, for
+/// long-running modes: escalating mid-way would start them over.
+pub fn escalate_early(cfg: &config::AppConfig, method: &str, no_sudo: bool) -> Result<()> {
+    match cfg.probe.default.map(|i| &cfg.probe.runtimes[i]) {
+        Some(rt) if method == "overlay2" && !rt.can_read => maybe_escalate(rt, no_sudo),
+        _ => Ok(()),
+    }
+}
+
 /// Auto-escalate to sudo unless --no-sudo is set.
 fn maybe_escalate(rt: &RuntimeInfo, no_sudo: bool) -> Result<()> {
     let already_escalated = std::env::var("PEEL_ESCALATED").is_ok();
 
     if already_escalated {
-        anyhow::bail!(
-            "Already escalated but still cannot read {}. Check permissions.",
-            rt.storage_root.display()
-        );
+        anyhow::bail!(t!("escalate-already", path = rt.storage_root.display()));
     }
 
     let mut stderr = io::stderr();
@@ -245,31 +1649,28 @@ fn maybe_escalate(rt: &RuntimeInfo, no_sudo: bool) -> Result<()> {
     writeln!(stderr, "  {}",  bar.dim())?;
     writeln!(
         stderr,
-        "  {} Reading layers directly via {} — much faster,",
+        "  {} {}",
         "▶".green().bold(),
-        style::style("overlay2").bold()
+        t!("escalate-direct", driver = style::style("overlay2").bold())
     )?;
     writeln!(
         stderr,
-        "  but {} needs root to access {}",
-        "sudo".bold(),
-        style::style(rt.storage_root.display()).dim()
+        "  {}",
+        t!(
+            "escalate-needs-root",
+            sudo = "sudo".bold(),
+            path = style::style(rt.storage_root.display()).dim()
+        )
     )?;
     writeln!(stderr)?;
-    writeln!(stderr, "  Re-running as root...")?;
+    writeln!(stderr, "  {}", t!("escalate-rerun"))?;
     writeln!(stderr)?;
-    writeln!(
-        stderr,
-        "  {}",
-        "Can't sudo? Use --no-sudo to fall back to the OCI API.".dim()
-    )?;
+    writeln!(stderr, "  {}", t!("escalate-hint").dim())?;
     writeln!(stderr, "  {}", bar.dim())?;
     writeln!(stderr)?;
 
     if no_sudo {
-        anyhow::bail!(
-            "Cannot read storage without root. Remove --no-sudo or use --use-oci."
-        );
+        anyhow::bail!(t!("escalate-refused"));
     }
 
     escalate_with_sudo()?;
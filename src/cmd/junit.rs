@@ -0,0 +1,125 @@
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::progress;
+
+/// A group of checks against one image, e.g. every lint rule that ran.
+pub struct TestSuite {
+    /// Image the checks ran against
+    pub name: String,
+
+    pub cases: Vec<TestCase>,
+}
+
+/// One check: a lint rule, or a threshold like `--min-efficiency`.
+pub struct TestCase {
+    /// Command that ran the check, e.g. "peel.lint"
+    pub classname: String,
+
+    /// Rule or threshold name
+    pub name: String,
+
+    /// Why the check failed; `None` if it passed
+    pub failure: Option<Failure>,
+
+    /// Findings below the failing level, shown as the case's output
+    pub output: Vec<String>,
+}
+
+pub struct Failure {
+    /// One-line summary
+    pub message: String,
+
+    /// Severity or kind of failure, e.g. "error"
+    pub kind: String,
+
+    /// Every finding, one per line
+    pub details: Vec<String>,
+}
+
+/// Write `suites` as a JUnit XML report, which Jenkins, GitLab and most
+/// other CI systems show as test results.
+pub fn write(path: &Path, suites: &[TestSuite]) -> Result<()> {
+    let count = |suite: &TestSuite| {
+        let failures = suite.cases.iter().filter(|c| c.failure.is_some()).count();
+        (suite.cases.len(), failures)
+    };
+    let (tests, failures) = suites
+        .iter()
+        .map(count)
+        .fold((0, 0), |(t, f), (tests, failures)| {
+            (t + tests, f + failures)
+        });
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="peel" tests="{tests}" failures="{failures}">"#
+    );
+    for suite in suites {
+        let (tests, failures) = count(suite);
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{tests}" failures="{failures}">"#,
+            escape(&suite.name)
+        );
+        for case in &suite.cases {
+            let _ = write!(
+                xml,
+                r#"    <testcase classname="{}" name="{}""#,
+                escape(&case.classname),
+                escape(&case.name)
+            );
+            if case.failure.is_none() && case.output.is_empty() {
+                xml.push_str("/>\n");
+                continue;
+            }
+            xml.push_str(">\n");
+            if let Some(failure) = &case.failure {
+                let _ = writeln!(
+                    xml,
+                    r#"      <failure message="{}" type="{}">{}</failure>"#,
+                    escape(&failure.message),
+                    escape(&failure.kind),
+                    escape(&failure.details.join("\n"))
+                );
+            }
+            if !case.output.is_empty() {
+                let _ = writeln!(
+                    xml,
+                    "      <system-out>{}</system-out>",
+                    escape(&case.output.join("\n"))
+                );
+            }
+            xml.push_str("    </testcase>\n");
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+
+    fs::write(path, xml).with_context(|| t!("junit-write-failed", path = path.display()))?;
+    progress::done(t!("wrote-file", path = path.display()));
+    Ok(())
+}
+
+/// Escape text for XML content and attribute values. Control characters
+/// XML 1.0 can't carry at all are dropped.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            '\n' | '\t' | '\r' => out.push(c),
+            c if c.is_control() => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
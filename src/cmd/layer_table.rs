@@ -0,0 +1,168 @@
+
+use super::inspect::{age, fit, format_bytes};
+use crate::config;
+use crate::inspector::instruction::Instruction;
+use crate::style::Stylize;
+
+/// A column of the layer table, picked with `--columns`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Column {
+    /// Position in the image, base = 1 as in all text output
+    Index,
+    /// Layer digest, shortened
+    Digest,
+    /// Size of the layer's files
+    Size,
+    /// Size of the compressed blob, when the backend has the manifest
+    Compressed,
+    /// Number of files in the layer
+    Files,
+    /// When the layer was built
+    Created,
+    /// Instruction that made the layer
+    Command,
+    /// Dockerfile line behind the layer, with --dockerfile
+    Source,
+}
+
+impl Column {
+    fn header(self) -> &'static str {
+        match self {
+            Column::Index => "#",
+            Column::Digest => "DIGEST",
+            Column::Size => "SIZE",
+            Column::Compressed => "COMPRESSED",
+            Column::Files => "FILES",
+            Column::Created => "CREATED",
+            Column::Command => "COMMAND",
+            Column::Source => "SOURCE",
+        }
+    }
+
+    /// Numbers line up on the right.
+    fn right_aligned(self) -> bool {
+        matches!(
+            self,
+            Column::Index | Column::Size | Column::Compressed | Column::Files
+        )
+    }
+}
+
+/// What the table shows of one layer; `None` where it isn't known.
+pub struct Row<'a> {
+    pub digest: &'a str,
+    pub size: u64,
+    pub compressed_size: Option<u64>,
+
+    /// The compressed size is close to the size on a layer big enough for
+    /// that to matter
+    pub poor_compression: bool,
+
+    pub files: Option<usize>,
+    pub created: Option<&'a str>,
+    pub command: String,
+    pub source: Option<String>,
+}
+
+/// Print one row per layer, base first, in `--columns` order or `defaults`.
+/// `divider` is a line to print before the row with that index.
+pub fn print(rows: &[Row], defaults: &[Column], divider: Option<(usize, String)>) {
+    let columns = config::columns().unwrap_or(defaults);
+    let cells: Vec<Vec<String>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, row)| columns.iter().map(|c| cell(*c, i, row)).collect())
+        .collect();
+    let widths: Vec<usize> = columns
+        .iter()
+        .enumerate()
+        .map(|(j, column)| {
+            cells
+                .iter()
+                .map(|row| row[j].chars().count())
+                .chain([column.header().len()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let header: Vec<String> = columns.iter().map(|c| c.header().to_string()).collect();
+    println!("{}", layout(columns, &widths, &header).join("  ").bold());
+    for (i, (row, cells)) in rows.iter().zip(&cells).enumerate() {
+        if let Some((_, text)) = divider.as_ref().filter(|(at, _)| *at == i) {
+            println!("{}", text.as_str().dim());
+        }
+        let styled: Vec<String> = columns
+            .iter()
+            .zip(layout(columns, &widths, cells))
+            .map(|(column, text)| match column {
+                Column::Index | Column::Created | Column::Source => text.dim().to_string(),
+                Column::Digest => text.cyan().to_string(),
+                Column::Compressed if row.poor_compression => text.yellow().to_string(),
+                _ => text,
+            })
+            .collect();
+        println!("{}", styled.join("  "));
+    }
+    if columns.contains(&Column::Compressed) && rows.iter().any(|r| r.poor_compression) {
+        println!();
+        println!("{}", format!("* {}", t!("inspect-poor-compression")).dim());
+    }
+}
+
+/// The instruction without builder noise if it could be parsed, else the
+/// history's command as recorded.
+pub fn command(instruction: Option<&Instruction>, created_by: Option<&str>) -> String {
+    match (instruction, created_by) {
+        (Some(parsed), _) => parsed.to_string(),
+        (None, Some(created_by)) => created_by.to_string(),
+        (None, None) => String::new(),
+    }
+}
+
+fn cell(column: Column, index: usize, row: &Row) -> String {
+    let known = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+    match column {
+        Column::Index => (index + 1).to_string(),
+        Column::Digest => row.digest.get(..19).unwrap_or(row.digest).to_string(),
+        Column::Size => format_bytes(row.size),
+        Column::Compressed => known(row.compressed_size.map(|size| {
+            let mark = if row.poor_compression { "*" } else { "" };
+            format!("{}{mark}", format_bytes(size))
+        })),
+        Column::Files => known(row.files.map(|n| n.to_string())),
+        Column::Created => known(row.created.map(|timestamp| {
+            let date = timestamp.get(..10).unwrap_or(timestamp);
+            match age(timestamp) {
+                Some(age) => format!("{date} ({age})"),
+                None => date.to_string(),
+            }
+        })),
+        Column::Command => row.command.split_whitespace().collect::<Vec<_>>().join(" "),
+        Column::Source => known(row.source.clone()),
+    }
+}
+
+/// Cells padded to their column's width. A text column at the end of the
+/// line isn't padded, so lines don't end in spaces, and is cut to fit the
+/// terminal instead.
+fn layout(columns: &[Column], widths: &[usize], cells: &[String]) -> Vec<String> {
+    let mut used = 0;
+    columns
+        .iter()
+        .zip(cells)
+        .enumerate()
+        .map(|(j, (column, text))| {
+            let width = widths[j];
+            let text = if column.right_aligned() {
+                format!("{text:>width$}")
+            } else if j + 1 == columns.len() {
+                fit(text, used)
+            } else {
+                format!("{text:<width$}")
+            };
+            used += width + 2;
+            text
+        })
+        .collect()
+}
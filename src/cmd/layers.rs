@@ -0,0 +1,130 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::inspect::{format_bytes, open_inspector, print_runtime_summary, select_method};
+use super::layer_table::{self, Column, Row};
+use super::output::{Output, write_json};
+use crate::config;
+use crate::inspector::LayerInfo;
+use crate::inspector::instruction::Instruction;
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+#[derive(Serialize)]
+pub struct LayersReport {
+    pub image: String,
+    pub total_size: u64,
+    pub layers: Vec<LayerSummary>,
+}
+
+/// A layer as `inspect` reports it, without its files.
+#[derive(Serialize)]
+pub struct LayerSummary {
+    pub digest: String,
+    pub size: u64,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+
+    pub created_by: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instruction: Option<Instruction>,
+}
+
+impl From<LayerInfo> for LayerSummary {
+    fn from(layer: LayerInfo) -> Self {
+        Self {
+            digest: layer.digest,
+            size: layer.size,
+            compressed_size: layer.compressed_size,
+            created: layer.created,
+            created_by: layer.created_by,
+            instruction: layer.instruction,
+        }
+    }
+}
+
+pub fn run(
+    image: &str,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let spinner = Spinner::new(t!("spinner-resolving-image", image = image));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let info = inspector.inspect_metadata(image)?;
+    spinner.finish(t!(
+        "resolved-image",
+        image = image,
+        count = info.layers.len()
+    ));
+
+    let report = LayersReport {
+        image: info.name,
+        total_size: info.total_size,
+        layers: info.layers.into_iter().map(LayerSummary::from).collect(),
+    };
+
+    // No web view: without files there is nothing to browse.
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "layers", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_layers(&report);
+            Ok(())
+        }
+    }
+}
+
+/// One row per layer, base first.
+pub fn print_layers(report: &LayersReport) {
+    let rows: Vec<Row> = report
+        .layers
+        .iter()
+        .map(|layer| Row {
+            digest: &layer.digest,
+            size: layer.size,
+            compressed_size: layer.compressed_size,
+            poor_compression: false,
+            files: None,
+            created: layer.created.as_deref(),
+            command: layer_table::command(
+                layer.instruction.as_ref(),
+                layer.created_by.as_deref(),
+            ),
+            source: None,
+        })
+        .collect();
+    let mut columns = vec![Column::Index, Column::Digest, Column::Size];
+    if rows.iter().any(|r| r.compressed_size.is_some()) {
+        columns.push(Column::Compressed);
+    }
+    if rows.iter().any(|r| r.created.is_some()) {
+        columns.push(Column::Created);
+    }
+    columns.push(Column::Command);
+    layer_table::print(&rows, &columns, None);
+    println!();
+    println!(
+        "{}",
+        t!(
+            "layers-summary",
+            layers = report.layers.len(),
+            size = format_bytes(report.total_size)
+        )
+        .dim()
+    );
+}
@@ -0,0 +1,273 @@
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::inspect::{load_image_with_inspector, print_runtime_summary, select_method};
+use super::output::{Annotation, Output, annotate, write_json};
+use crate::analysis::elf::{self, Dependency, ElfObject, Rootfs};
+use crate::analysis::merged::{self, normalize};
+use crate::config;
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+/// ELF files larger than this aren't parsed.
+const MAX_SIZE: u64 = 512 * 1024 * 1024;
+
+const LD_SO_CONF: &str = "etc/ld.so.conf";
+const LD_SO_CONF_DIR: &str = "etc/ld.so.conf.d";
+
+#[derive(Serialize)]
+pub struct LddReport {
+    pub image: String,
+
+    /// `LD_LIBRARY_PATH` from the image config's environment
+    pub ld_library_path: Vec<String>,
+
+    /// Dynamically linked executables and shared objects, by path
+    pub objects: Vec<ElfObject>,
+
+    /// Statically linked executables, which need nothing
+    pub static_files: usize,
+}
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    #[serde(default)]
+    config: Option<RunConfig>,
+}
+
+#[derive(Deserialize)]
+struct RunConfig {
+    #[serde(rename = "Env", default)]
+    env: Option<Vec<String>>,
+}
+
+/// Resolve the shared libraries every ELF file in the final filesystem
+/// needs, the way the dynamic loader would, and report the missing ones.
+pub fn run(
+    image: &str,
+    all: bool,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let visible = merged::merge(&info);
+    let ld_library_path = ld_library_path(inspector.config());
+
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut links = HashMap::new();
+    let mut parsed = Vec::new();
+    let mut conf_dirs = Vec::new();
+    let mut static_files = 0;
+    for (index, layer) in info.layers.iter().enumerate() {
+        // Symbolic links aren't told apart from other entries in listings,
+        // so every layer with something visible is read
+        let shown: HashSet<&Path> = visible
+            .values()
+            .filter(|f| f.layer == index)
+            .map(|f| f.path.as_path())
+            .collect();
+        if shown.is_empty() {
+            continue;
+        }
+        let candidates: HashSet<PathBuf> = layer
+            .files
+            .iter()
+            .filter(|f| f.elf || f.path.to_string_lossy().contains(".so"))
+            .map(|f| normalize(&f.path))
+            .collect();
+        spinner.set_message(t!(
+            "ldd-spinner",
+            current = index + 1,
+            total = info.layers.len()
+        ));
+
+        let mut archive = tar::Archive::new(inspector.read_layer(layer)?);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            let path = normalize(&entry.path()?);
+            if !shown.contains(path.as_path()) {
+                continue;
+            }
+            let kind = entry.header().entry_type();
+            if kind.is_symlink() {
+                if let Some(target) = entry.link_name()? {
+                    links.insert(path, target.into_owned());
+                }
+                continue;
+            }
+            if !kind.is_file() || entry.size() > MAX_SIZE {
+                continue;
+            }
+            let is_conf = is_conf(&path);
+            if !is_conf && !candidates.contains(&path) {
+                continue;
+            }
+
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut content)
+                .context(t!("repack-read-failed"))?;
+            if is_conf {
+                conf_dirs.extend(elf::conf_dirs(&String::from_utf8_lossy(&content)));
+            } else if let Some(dynamic) = elf::parse(&content) {
+                if dynamic.interpreter.is_none() && dynamic.needed.is_empty() {
+                    static_files += 1;
+                } else {
+                    parsed.push((path, index, dynamic));
+                }
+            }
+        }
+    }
+
+    let rootfs = Rootfs::new(&visible, links);
+    let mut objects: Vec<ElfObject> = parsed
+        .iter()
+        .map(|(path, layer, dynamic)| {
+            rootfs.check(path, *layer, dynamic, &ld_library_path, &conf_dirs)
+        })
+        .collect();
+    objects.sort_by(|a, b| a.path.cmp(&b.path));
+    let broken = objects.iter().filter(|o| o.is_broken()).count();
+    spinner.finish(t!("ldd-done", count = objects.len(), broken = broken));
+
+    let report = LddReport {
+        image: info.name.clone(),
+        ld_library_path,
+        objects,
+        static_files,
+    };
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "ldd", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Junit(_) | Output::Csv(_) => {
+            print_ldd(&report, all);
+            Ok(())
+        }
+        Output::Github => {
+            print_ldd(&report, all);
+            for object in report.objects.iter().filter(|o| o.is_broken()) {
+                let missing: Vec<&str> = object
+                    .interpreter
+                    .iter()
+                    .chain(&object.needed)
+                    .filter(|d| d.path.is_none())
+                    .map(|d| d.name.as_str())
+                    .collect();
+                let message = t!(
+                    "ldd-annotation",
+                    path = format!("/{}", object.path.display()),
+                    libraries = missing.join(", ")
+                );
+                annotate(Annotation::Error, "peel ldd", &message);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// `ld.so.conf`, its drop-ins, or musl's `ld-musl-<arch>.path`.
+fn is_conf(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    path == Path::new(LD_SO_CONF)
+        || (path.parent() == Some(Path::new(LD_SO_CONF_DIR)) && name.ends_with(".conf"))
+        || (path.parent() == Some(Path::new("etc"))
+            && name.starts_with("ld-musl-")
+            && name.ends_with(".path"))
+}
+
+fn ld_library_path(config: Option<&[u8]>) -> Vec<String> {
+    let Some(config) = config.and_then(|c| serde_json::from_slice::<ImageConfig>(c).ok()) else {
+        return Vec::new();
+    };
+    config
+        .config
+        .and_then(|c| c.env)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|e| e.strip_prefix("LD_LIBRARY_PATH="))
+        .flat_map(|v| v.split(':'))
+        .filter(|d| !d.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Files with missing dependencies (every dynamically linked file with
+/// `all`), then a summary.
+pub fn print_ldd(r: &LddReport, all: bool) {
+    for object in &r.objects {
+        let broken = object.is_broken();
+        if !all && !broken {
+            continue;
+        }
+        println!();
+        let path = format!("/{}", object.path.display());
+        println!(
+            "{}  {}",
+            if broken { path.red() } else { path.bold() },
+            t!("ldd-layer", layer = object.layer + 1).dim()
+        );
+        if let Some(interpreter) = &object.interpreter {
+            print_dependency(interpreter, all);
+        }
+        for dependency in &object.needed {
+            print_dependency(dependency, all);
+        }
+    }
+
+    println!();
+    let count = r.objects.len();
+    let broken = r.objects.iter().filter(|o| o.is_broken()).count();
+    if broken == 0 {
+        println!(
+            "{}",
+            t!("ldd-ok", count = count, static_files = r.static_files).green()
+        );
+    } else {
+        println!(
+            "{}",
+            t!(
+                "ldd-broken",
+                broken = broken.to_string().bold(),
+                count = count
+            )
+        );
+    }
+}
+
+fn print_dependency(d: &Dependency, all: bool) {
+    match &d.path {
+        Some(path) if all => {
+            println!("  {:<32}  {}", d.name, format!("/{}", path.display()).dim())
+        }
+        Some(_) => {}
+        None => {
+            let line = format!("  {:<32}  {}", d.name, t!("ldd-not-found"));
+            println!("{}", line.red());
+            if !d.elsewhere.is_empty() {
+                let paths: Vec<String> = d
+                    .elsewhere
+                    .iter()
+                    .map(|p| format!("/{}", p.display()))
+                    .collect();
+                println!(
+                    "    {}",
+                    t!("ldd-elsewhere", paths = paths.join(", ")).yellow()
+                );
+            }
+        }
+    }
+}
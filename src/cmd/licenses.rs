@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::inspect::{
+    fit, fit_start, load_image_with_inspector, print_runtime_summary, select_method,
+};
+use super::output::{Output, write_json};
+use crate::analysis::licenses::{self, LicenseFile, LicenseSummary, PackageLicense};
+use crate::analysis::merged::{self, normalize};
+use crate::config;
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+/// Stray license files listed in text output; --json has them all.
+const TOP_FILES: usize = 30;
+
+#[derive(Serialize)]
+pub struct LicensesReport {
+    pub image: String,
+
+    /// Layer digests, base first; `layer` fields index into these
+    pub layers: Vec<String>,
+
+    pub summary: LicenseSummary,
+
+    /// Installed packages, sorted by kind, then name
+    pub packages: Vec<PackageLicense>,
+
+    /// License files in the final filesystem, by path
+    pub files: Vec<LicenseFile>,
+}
+
+/// Collect the licenses installed packages declare and the license files
+/// shipped next to vendored code, for legal review.
+pub fn run(
+    image: &str,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let sbom = super::sbom::read_packages(&info, inspector.as_mut())?;
+
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let visible = merged::merge(&info);
+    let mut files = Vec::new();
+    for (index, layer) in info.layers.iter().enumerate() {
+        // Only the copies the final filesystem shows are shipped
+        let wanted: HashSet<_> = visible
+            .values()
+            .filter(|f| f.layer == index && licenses::candidate(&f.path, f.size))
+            .map(|f| f.path.clone())
+            .collect();
+        if wanted.is_empty() {
+            continue;
+        }
+        spinner.set_message(t!(
+            "licenses-spinner",
+            current = index + 1,
+            total = info.layers.len()
+        ));
+        let mut archive = tar::Archive::new(inspector.read_layer(layer)?);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            let path = normalize(&entry.path()?);
+            if !entry.header().entry_type().is_file() || !wanted.contains(&path) {
+                continue;
+            }
+            let mut content = Vec::new();
+            entry
+                .read_to_end(&mut content)
+                .context(t!("repack-read-failed"))?;
+            files.push(licenses::identify(&path, index, &content));
+        }
+    }
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    spinner.finish(t!("licenses-done", count = files.len()));
+
+    let packages = licenses::packages(&sbom.packages, &files);
+    let report = LicensesReport {
+        image: sbom.image,
+        layers: sbom.layers,
+        summary: licenses::summarize(&packages, &files),
+        packages,
+        files,
+    };
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "licenses", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_licenses(&report);
+            Ok(())
+        }
+    }
+}
+
+/// License totals, then the license files that belong to no package.
+pub fn print_licenses(r: &LicensesReport) {
+    let s = &r.summary;
+    if s.licenses.is_empty() && s.unknown_packages == 0 && s.unknown_files == 0 {
+        println!("{}", t!("licenses-none"));
+        return;
+    }
+
+    println!("{}", t!("licenses-summary").bold());
+    for count in &s.licenses {
+        println!(
+            "  {:<32}  {:>8}  {:>8}",
+            count.license, count.packages, count.files
+        );
+    }
+    if s.unknown_packages > 0 || s.unknown_files > 0 {
+        println!(
+            "  {}  {:>8}  {:>8}",
+            format!("{:<32}", t!("licenses-unknown")).yellow(),
+            s.unknown_packages,
+            s.unknown_files
+        );
+    }
+
+    let stray: Vec<&LicenseFile> = r.files.iter().filter(|f| f.package.is_none()).collect();
+    if !stray.is_empty() {
+        println!();
+        println!("{}", t!("licenses-files").bold());
+        for f in stray.iter().take(TOP_FILES) {
+            let names = if f.licenses.is_empty() {
+                "?".to_string()
+            } else {
+                f.licenses.join(", ")
+            };
+            println!(
+                "  {:>5}  {:<20}  {}",
+                f.layer + 1,
+                names,
+                fit_start(&format!("/{}", f.path.display()), 31).magenta()
+            );
+        }
+        if stray.len() > TOP_FILES {
+            println!(
+                "  {}",
+                t!("licenses-more", count = stray.len() - TOP_FILES).dim()
+            );
+        }
+    }
+
+    let unknown: Vec<&str> = r
+        .packages
+        .iter()
+        .filter(|p| p.licenses.is_empty())
+        .map(|p| p.name.as_str())
+        .collect();
+    if !unknown.is_empty() {
+        println!();
+        let line = t!(
+            "licenses-unknown-packages",
+            count = unknown.len(),
+            names = unknown.join(", ")
+        );
+        println!("{}", fit(&line, 0).yellow());
+    }
+}
@@ -0,0 +1,325 @@
+use std::path::Path;
+
+use anyhow::{Context as _, Result, bail};
+use clap::ValueEnum;
+use regex::Regex;
+use serde::Serialize;
+
+use super::check::{FailOn, FailureClass, GateFailed};
+use super::find::glob_to_regex;
+use super::inspect::{
+    fit, format_bytes, junk_fix, junk_name, load_image_with_inspector, print_runtime_summary,
+    select_method,
+};
+use super::junit::{self, Failure, TestCase, TestSuite};
+use super::output::{Annotation, Output, annotate, write_json};
+use crate::config;
+use crate::lint::{self, Context, Finding, Policy, RuleId, RuleName, Severity};
+use crate::style::Stylize;
+
+/// Config file read from the current directory when `--config` isn't given.
+const DEFAULT_CONFIG: &str = ".peel-lint.json";
+
+#[derive(Serialize)]
+pub struct LintReport {
+    pub image: String,
+
+    /// Rule violations, in rule order
+    pub findings: Vec<Finding>,
+}
+
+/// Parse `--rule ID=LEVEL`, where LEVEL is a severity or `off`.
+pub fn parse_rule(s: &str) -> Result<(RuleId, Option<Severity>), String> {
+    let (id, level) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected RULE=LEVEL, got `{s}`"))?;
+    let id = RuleId::from_str(id, true)?;
+    let severity = match level {
+        "off" => None,
+        level => Some(Severity::from_str(level, true)?),
+    };
+    Ok((id, severity))
+}
+
+pub struct Options<'a> {
+    /// Rule settings and custom rules; `.peel-lint.json` if it exists
+    pub config: Option<&'a Path>,
+
+    /// Severity overrides from `--rule`, over the config file's; `None`
+    /// turns a rule off
+    pub rules: &'a [(RuleId, Option<Severity>)],
+
+    /// Fail when a finding is at least this severe
+    pub fail_on: Severity,
+
+    /// Base image reference to check instead of the config's label
+    pub base: Option<&'a str>,
+}
+
+/// The lowest severity `--fail-on` gives, `error` without one; lint has no
+/// use for inspect's conditions.
+pub fn fail_on_severity(fail_on: &[FailOn]) -> Result<Severity> {
+    let mut lowest = None;
+    for condition in fail_on {
+        match condition {
+            FailOn::Severity(severity) => {
+                lowest = Some(lowest.map_or(*severity, |l: Severity| l.min(*severity)))
+            }
+            _ => bail!(t!("fail-on-lint-severity-only")),
+        }
+    }
+    Ok(lowest.unwrap_or(Severity::Error))
+}
+
+/// Read and check a lint config file.
+fn load_policy(path: &Path) -> Result<Policy> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| t!("lint-config-read-failed", path = path.display()))?;
+    let mut policy: Policy = serde_json::from_str(&text)
+        .with_context(|| t!("lint-config-invalid", path = path.display()))?;
+
+    for (id, rule) in &policy.rules {
+        if rule.max_size.is_some() && !lint::has_threshold(*id) {
+            bail!(t!(
+                "lint-config-no-threshold",
+                rule = rule_name(&RuleName::Builtin(*id))
+            ));
+        }
+    }
+    let mut seen = Vec::new();
+    for rule in &mut policy.custom {
+        if rule.id.is_empty()
+            || RuleId::from_str(&rule.id, false).is_ok()
+            || seen.contains(&rule.id)
+        {
+            bail!(t!("lint-config-bad-id", id = &rule.id));
+        }
+        seen.push(rule.id.clone());
+        rule.pattern = Some(
+            Regex::new(&glob_to_regex(&rule.path))
+                .with_context(|| t!("grep-bad-pattern", pattern = &rule.path))?,
+        );
+    }
+    Ok(policy)
+}
+
+/// Check the image against the lint rules and fail when a finding is at
+/// least as severe as `opts.fail_on`.
+pub fn run(
+    image: &str,
+    opts: Options,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    let default = Path::new(DEFAULT_CONFIG);
+    let mut policy = match opts.config {
+        Some(path) => load_policy(path)?,
+        None if default.is_file() => load_policy(default)?,
+        None => Policy::default(),
+    };
+    policy.override_severities(opts.rules);
+
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let ctx = Context {
+        info: &info,
+        config: inspector.config(),
+        base: opts.base,
+    };
+    let report = LintReport {
+        image: info.name.clone(),
+        findings: lint::run(&ctx, &policy),
+    };
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "lint", &json_str, None)?;
+        }
+        Output::Json(dest) => write_json(dest, &report)?,
+        Output::Web | Output::Text | Output::Csv(_) => print_lint(&report),
+        Output::Github => {
+            print_lint(&report);
+            for finding in &report.findings {
+                let level = match finding.severity {
+                    Severity::Error => Annotation::Error,
+                    Severity::Warning => Annotation::Warning,
+                    Severity::Info => Annotation::Notice,
+                };
+                let title = format!("peel lint: {}", rule_name(&finding.rule));
+                annotate(level, &title, &message(finding));
+            }
+        }
+        Output::Junit(path) => {
+            print_lint(&report);
+            junit::write(path, &[test_suite(&report, &policy, opts.fail_on)])?;
+        }
+    }
+
+    // Checked after the report is written so CI still gets it on failure
+    let failing = report
+        .findings
+        .iter()
+        .filter(|f| f.severity >= opts.fail_on)
+        .count();
+    if failing > 0 {
+        bail!(GateFailed {
+            class: FailureClass::Lint,
+            message: t!(
+                "lint-failed",
+                count = failing,
+                level = severity_name(opts.fail_on)
+            ),
+        });
+    }
+    Ok(())
+}
+
+/// One test case per rule that ran, failing when the rule has a finding
+/// at `fail_on` or above; findings below it are the case's output.
+fn test_suite(r: &LintReport, policy: &Policy, fail_on: Severity) -> TestSuite {
+    let cases = lint::enabled(policy)
+        .into_iter()
+        .map(|rule| {
+            let findings: Vec<&Finding> = r.findings.iter().filter(|f| f.rule == rule).collect();
+            let (failing, passing): (Vec<&Finding>, Vec<&Finding>) =
+                findings.into_iter().partition(|f| f.severity >= fail_on);
+            let line = |f: &&Finding| format!("{}: {}", severity_name(f.severity), message(f));
+            let failure = failing.first().map(|first| Failure {
+                message: message(first),
+                // The untranslated name, which CI tools may match on
+                kind: failing
+                    .iter()
+                    .map(|f| f.severity)
+                    .max()
+                    .and_then(|s| s.to_possible_value())
+                    .map(|v| v.get_name().to_string())
+                    .unwrap_or_default(),
+                details: failing.iter().map(line).collect(),
+            });
+            TestCase {
+                classname: "peel.lint".to_string(),
+                name: rule_name(&rule),
+                failure,
+                output: passing.iter().map(line).collect(),
+            }
+        })
+        .collect();
+    TestSuite {
+        name: r.image.clone(),
+        cases,
+    }
+}
+
+/// One line per finding, then a count by severity.
+pub fn print_lint(r: &LintReport) {
+    if r.findings.is_empty() {
+        println!("{}", t!("lint-clean").green());
+        return;
+    }
+    for finding in &r.findings {
+        let level = format!("{:<8}", severity_name(finding.severity));
+        let level = match finding.severity {
+            Severity::Error => level.red(),
+            Severity::Warning => level.yellow(),
+            Severity::Info => level.dim(),
+        };
+        let rule = rule_name(&finding.rule);
+        let message = message(finding);
+        println!(
+            "{level}  {}  {}",
+            rule.as_str().bold(),
+            fit(&message, 12 + rule.len())
+        );
+        if let Some(kind) = finding.detail.junk {
+            println!("          {}", fit(&junk_fix(kind), 10).green());
+        }
+    }
+
+    println!();
+    let count = |severity| r.findings.iter().filter(|f| f.severity == severity).count();
+    println!(
+        "{}",
+        t!(
+            "lint-summary",
+            errors = count(Severity::Error),
+            warnings = count(Severity::Warning),
+            infos = count(Severity::Info)
+        )
+    );
+}
+
+fn message(f: &Finding) -> String {
+    let d = &f.detail;
+    let layer = d.layer.map_or(0, |l| l + 1);
+    let subject = d.subject.clone().unwrap_or_default();
+    let id = match &f.rule {
+        RuleName::Builtin(id) => *id,
+        RuleName::Custom(_) => {
+            let message = d.message.clone().unwrap_or_else(|| t!("lint-custom"));
+            return match d.layer {
+                Some(_) => t!(
+                    "lint-custom-file",
+                    message = message,
+                    path = subject,
+                    size = format_bytes(d.size.unwrap_or(0)),
+                    layer = layer
+                ),
+                None => t!(
+                    "lint-custom-total",
+                    message = message,
+                    pattern = subject,
+                    size = format_bytes(d.size.unwrap_or(0))
+                ),
+            };
+        }
+    };
+    match id {
+        RuleId::LargeLayer => t!(
+            "lint-large-layer",
+            layer = layer,
+            size = format_bytes(d.size.unwrap_or(0)),
+            instruction = subject
+        ),
+        RuleId::AddInsteadOfCopy => t!(
+            "lint-add-instead-of-copy",
+            layer = layer,
+            instruction = subject
+        ),
+        RuleId::BaseLatest => t!("lint-base-latest", base = subject),
+        RuleId::PackageCache => t!(
+            "lint-package-cache",
+            layer = layer,
+            cache = d.junk.map(junk_name).unwrap_or_default(),
+            size = format_bytes(d.size.unwrap_or(0)),
+            path = subject
+        ),
+        RuleId::RunsAsRoot => match &d.subject {
+            Some(user) => t!("lint-runs-as-root-user", user = user),
+            None => t!("lint-runs-as-root"),
+        },
+    }
+}
+
+fn rule_name(rule: &RuleName) -> String {
+    match rule {
+        RuleName::Builtin(id) => id
+            .to_possible_value()
+            .map(|v| v.get_name().to_string())
+            .unwrap_or_default(),
+        RuleName::Custom(id) => id.clone(),
+    }
+}
+
+fn severity_name(severity: Severity) -> String {
+    match severity {
+        Severity::Error => t!("lint-error"),
+        Severity::Warning => t!("lint-warning"),
+        Severity::Info => t!("lint-info"),
+    }
+}
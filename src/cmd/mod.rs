@@ -1,4 +1,44 @@
+pub mod build;
+pub mod cat;
+pub mod check;
+pub mod compare;
+pub mod copy;
+pub mod diff;
+pub mod diff_file;
+pub mod du;
+pub mod entry_index;
+pub mod export_layer;
+pub mod extract;
+pub mod find;
+pub mod flatten;
+pub mod grep;
+pub mod history;
 pub mod inspect;
+pub mod junit;
+pub mod layer_table;
+pub mod layers;
+pub mod ldd;
+pub mod licenses;
+pub mod lint;
+pub mod output;
+pub mod plugin;
+pub mod preset;
 pub mod probe;
+pub mod pull_size;
+pub mod push_estimate;
+pub mod recompress;
+pub mod render;
+pub mod repro;
 pub mod report;
+pub mod rpc;
+pub mod sbom;
+pub mod scan;
+pub mod schema;
+pub mod secrets;
 pub mod self_update;
+pub mod serve;
+pub mod shared;
+pub mod squash_sim;
+pub mod stats;
+pub mod verify;
+pub mod watch;
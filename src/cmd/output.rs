@@ -0,0 +1,227 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, bail};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use super::sbom::{now, rfc3339};
+use crate::config;
+use crate::progress;
+use crate::timings::{self, Timings};
+
+/// Version of the JSON layout, `schema_version` in every envelope. Bumped
+/// when a field is renamed, removed or changes meaning; new fields don't.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// What `--json` writes: the command's result under `data`, with what a
+/// consumer needs to know which layout to expect.
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    schema_version: u32,
+
+    /// Version of peel that wrote the document
+    peel_version: &'static str,
+
+    /// When the document was written, RFC 3339 in UTC
+    generated_at: String,
+
+    /// How images were read (`overlay2`, `oci`, `archive` or `registry`);
+    /// absent for commands that read none
+    #[serde(skip_serializing_if = "Option::is_none")]
+    backend: Option<&'static str>,
+
+    /// Wall time per phase up to now, with `--timings`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    timings: Option<Timings>,
+
+    data: &'a T,
+}
+
+/// Where a command sends its result, resolved from `--json`, `--no-web` and `--output`.
+#[derive(Debug, Clone)]
+pub enum Output {
+    /// Human-readable summary on stdout
+    Text,
+    /// JSON to stdout (`-`) or to a file
+    Json(String),
+    /// Interactive report served on localhost
+    Web,
+    /// Report bundle directory for embedding in other sites (`--output bundle:<dir>`)
+    Bundle(PathBuf),
+    /// Text summary plus GitHub Actions workflow commands that annotate the
+    /// run with findings (`--output github`)
+    Github,
+    /// Text summary plus check results as a JUnit XML file
+    /// (`--output junit:<file>`); only for commands that check something
+    Junit(PathBuf),
+    /// One row per file to stdout (`--output csv`) or to a file
+    /// (`--output csv:<file>`); only for inspect
+    Csv(String),
+}
+
+impl Output {
+    pub fn from_cli(json: Option<&str>, no_web: bool, output: Option<&str>) -> Result<Self> {
+        if let Some(spec) = output {
+            match spec {
+                "github" => return Ok(Output::Github),
+                "csv" => return Ok(Output::Csv("-".to_string())),
+                _ => {}
+            }
+            return match spec.split_once(':') {
+                Some(("bundle", dir)) if !dir.is_empty() => Ok(Output::Bundle(dir.into())),
+                Some(("junit", file)) if !file.is_empty() => Ok(Output::Junit(file.into())),
+                Some(("csv", file)) if !file.is_empty() => Ok(Output::Csv(file.to_string())),
+                _ => bail!(t!("output-unsupported", spec = spec)),
+            };
+        }
+        Ok(match json {
+            Some(dest) => Output::Json(dest.to_string()),
+            None if no_web => Output::Text,
+            None => Output::Web,
+        })
+    }
+
+    /// Whether stdout is reserved for machine-readable output.
+    pub fn is_json(&self) -> bool {
+        matches!(self, Output::Json(_) | Output::Csv(_))
+    }
+}
+
+fn envelope<T: Serialize>(value: &T) -> Envelope<'_, T> {
+    Envelope {
+        schema_version: SCHEMA_VERSION,
+        peel_version: env!("CARGO_PKG_VERSION"),
+        generated_at: rfc3339(now()),
+        backend: config::backend(),
+        timings: timings::take(),
+        data: value,
+    }
+}
+
+/// Pretty-print `value` in the versioned envelope as JSON to stdout (`-`)
+/// or to the file at `dest`. `--query` selects from `value` itself and
+/// writes just what it selects.
+pub fn write_json<T: Serialize>(dest: &str, value: &T) -> Result<()> {
+    if config::query().is_some() {
+        return write_document(dest, value);
+    }
+    write_document(dest, &envelope(value))
+}
+
+/// Like [`write_json`], but on one line, appended to the file at `dest`:
+/// JSON Lines, for commands that report more than once.
+pub fn write_json_line<T: Serialize>(dest: &str, value: &T) -> Result<()> {
+    let line = match config::query() {
+        Some(query) => serde_json::to_string(&query.apply(&serde_json::to_value(value)?)?)?,
+        None => serde_json::to_string(&envelope(value))?,
+    };
+    if dest == "-" {
+        println!("{line}");
+        return Ok(());
+    }
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dest)
+        .with_context(|| t!("write-json-failed", path = dest))?;
+    writeln!(file, "{line}").with_context(|| t!("write-json-failed", path = dest))
+}
+
+/// Like [`write_json`] without the envelope, for documents in a format
+/// someone else defines (SPDX, CycloneDX).
+pub fn write_document<T: Serialize>(dest: &str, value: &T) -> Result<()> {
+    let output = match config::query() {
+        Some(query) => serde_json::to_string_pretty(&query.apply(&serde_json::to_value(value)?)?)?,
+        None => serde_json::to_string_pretty(value)?,
+    };
+    if dest == "-" {
+        println!("{output}");
+    } else {
+        fs::write(dest, &output).with_context(|| t!("write-json-failed", path = dest))?;
+        progress::done(t!("wrote-file", path = dest));
+    }
+    Ok(())
+}
+
+/// Parse JSON that [`write_json`] wrote, or the bare result older versions
+/// of peel wrote.
+pub fn read_json<T: DeserializeOwned>(data: &str) -> serde_json::Result<T> {
+    let mut value: Value = serde_json::from_str(data)?;
+    if let Some(data) = value
+        .as_object_mut()
+        .filter(|v| v.contains_key("schema_version"))
+        .and_then(|v| v.remove("data"))
+    {
+        value = data;
+    }
+    serde_json::from_value(value)
+}
+
+/// Write `rows` under `header` as CSV to stdout (`-`) or to the file at
+/// `dest`. Fields are quoted only when they need it, as RFC 4180 has it.
+pub fn write_csv<I>(dest: &str, header: &[&str], rows: I) -> Result<()>
+where
+    I: IntoIterator<Item = Vec<String>>,
+{
+    let mut csv = String::new();
+    let lines = std::iter::once(header.iter().map(|h| h.to_string()).collect()).chain(rows);
+    for row in lines {
+        let fields: Vec<String> = row.iter().map(|f| csv_field(f)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push_str("\r\n");
+    }
+    if dest == "-" {
+        print!("{csv}");
+    } else {
+        fs::write(dest, &csv).with_context(|| t!("write-csv-failed", path = dest))?;
+        progress::done(t!("wrote-file", path = dest));
+    }
+    Ok(())
+}
+
+/// Quote a field holding a separator, quote or line break, doubling its quotes.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// How prominently GitHub shows an annotation.
+#[derive(Debug, Clone, Copy)]
+pub enum Annotation {
+    Error,
+    Warning,
+    Notice,
+}
+
+/// Print a GitHub Actions workflow command that annotates the run (and the
+/// pull request it belongs to) with `message`.
+pub fn annotate(level: Annotation, title: &str, message: &str) {
+    let command = match level {
+        Annotation::Error => "error",
+        Annotation::Warning => "warning",
+        Annotation::Notice => "notice",
+    };
+    println!(
+        "::{command} title={}::{}",
+        escape_property(title),
+        escape_data(message)
+    );
+}
+
+/// Workflow command messages end at a newline, so those are percent-encoded.
+fn escape_data(text: &str) -> String {
+    text.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Properties also end at `,` and `:`.
+fn escape_property(text: &str) -> String {
+    escape_data(text).replace(':', "%3A").replace(',', "%2C")
+}
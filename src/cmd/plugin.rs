@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::output::{Output, write_json};
+use crate::style::{self, Stylize};
+
+/// `peel-foo` on PATH runs as `peel foo`.
+const PREFIX: &str = "peel-";
+
+/// An entry of `peel plugins --json`.
+#[derive(Serialize)]
+struct Plugin {
+    name: String,
+    path: PathBuf,
+}
+
+/// Where a plugin's name is in `args`, and its executable: the first word
+/// after the global flags, if it isn't a command and `peel-<word>` is on
+/// PATH. Plugins win over images of the same bare name.
+pub fn find(command: &clap::Command, args: &[OsString]) -> Option<(usize, PathBuf)> {
+    let mut i = 1;
+    while i < args.len() {
+        let arg = args[i].to_str()?;
+        if arg == "--" {
+            return None;
+        }
+        if let Some(long) = arg.strip_prefix("--") {
+            // Flags with a value take the next word unless given as `--flag=value`
+            let takes_value = command
+                .get_arguments()
+                .find(|a| a.get_long() == Some(long))
+                .is_some_and(|a| a.get_action().takes_values());
+            if takes_value {
+                i += 1;
+            }
+        } else if !arg.starts_with('-') {
+            let is_command = arg == "help" || command.find_subcommand(arg).is_some();
+            if is_command || !is_plugin_name(arg) {
+                return None;
+            }
+            return executable(arg).map(|path| (i, path));
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Global flags given before a plugin's name, passed on to it.
+pub struct Globals<'a> {
+    pub runtime: Option<&'a str>,
+    pub json: Option<&'a str>,
+    pub use_oci: bool,
+    pub no_sudo: bool,
+    pub lang: Option<&'a str>,
+
+    /// Only when it isn't the default, so the plugin's own flags apply
+    pub log_level: Option<&'a str>,
+}
+
+/// Run a plugin with the arguments after its name, then exit with its
+/// status. Global flags given before the name reach it as `PEEL_*`
+/// variables, which `peel` reads back, so `"$PEEL" inspect <image> --json`
+/// run from the plugin inspects the way the user asked.
+pub fn run(path: &Path, args: &[OsString], globals: &Globals) -> Result<()> {
+    let mut command = Command::new(path);
+    command.args(args).env("PEEL", env::current_exe()?);
+    let vars = [
+        ("PEEL_RUNTIME", globals.runtime),
+        ("PEEL_JSON", globals.json),
+        ("PEEL_USE_OCI", globals.use_oci.then_some("1")),
+        ("PEEL_NO_SUDO", globals.no_sudo.then_some("1")),
+        ("PEEL_LANG", globals.lang),
+        ("PEEL_LOG", globals.log_level),
+        // Settled here, as the plugin's own stdout may be piped
+        ("PEEL_COLOR", Some(if style::enabled() { "always" } else { "never" })),
+    ];
+    for (name, value) in vars {
+        match value {
+            Some(value) => command.env(name, value),
+            None => command.env_remove(name),
+        };
+    }
+
+    let status = command
+        .status()
+        .with_context(|| t!("plugin-failed", path = path.display()))?;
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+/// `peel plugins`: what `peel <name>` would run for each plugin on PATH.
+pub fn list(output: &Output) -> Result<()> {
+    let plugins: Vec<Plugin> = discover()
+        .into_iter()
+        .map(|(name, path)| Plugin { name, path })
+        .collect();
+    if let Output::Json(dest) = output {
+        return write_json(dest, &plugins);
+    }
+
+    if plugins.is_empty() {
+        println!("{}", t!("plugins-none"));
+        return Ok(());
+    }
+    let width = plugins.iter().map(|p| p.name.len()).max().unwrap_or(0);
+    for plugin in &plugins {
+        println!(
+            "  {:width$}  {}",
+            plugin.name.as_str().cyan(),
+            plugin.path.display().to_string().dim()
+        );
+    }
+    Ok(())
+}
+
+/// Plugins on PATH by name; the first directory holding a name wins, as
+/// when running it.
+fn discover() -> BTreeMap<String, PathBuf> {
+    let mut plugins = BTreeMap::new();
+    let Some(path) = env::var_os("PATH") else {
+        return plugins;
+    };
+    for dir in env::split_paths(&path) {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let file_name = entry.file_name();
+            let Some(name) = file_name
+                .to_str()
+                .and_then(|f| f.strip_prefix(PREFIX))
+                .map(|f| f.strip_suffix(env::consts::EXE_SUFFIX).unwrap_or(f))
+            else {
+                continue;
+            };
+            if is_plugin_name(name) && is_executable(&entry.path()) {
+                plugins
+                    .entry(name.to_string())
+                    .or_insert_with(|| entry.path());
+            }
+        }
+    }
+    plugins
+}
+
+/// The first `peel-<name>` on PATH.
+fn executable(name: &str) -> Option<PathBuf> {
+    let file_name = format!("{PREFIX}{name}{}", env::consts::EXE_SUFFIX);
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(&file_name))
+        .find(|path| is_executable(path))
+}
+
+/// Letters, digits, `-` and `_`: never a path or a tagged reference.
+fn is_plugin_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    path.metadata()
+        .is_ok_and(|m| m.is_file() && m.permissions().mode() & 0o111 != 0)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
@@ -0,0 +1,176 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use super::output::Output;
+
+/// Config file read from the current directory; its `preset` applies when
+/// `--preset` isn't given.
+const CONFIG: &str = ".peel.json";
+
+/// Curated combinations of inspect analyzers, output and thresholds, picked
+/// with `--preset`. Flags given explicitly take precedence over the preset's.
+#[derive(Debug, Clone, Copy, ValueEnum, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Preset {
+    /// Packed or encrypted files, leftover build tools, setuid and
+    /// world-writable files, the user the image runs as, language runtimes,
+    /// image class and per-file hashes
+    Security,
+    /// Wasted space, removable files, committed build caches, leftover build
+    /// tools, strippable docs, duplicate files (with `--checksums`) and content
+    /// and file type breakdowns
+    Size,
+    /// Final filesystem with per-file hashes and language runtimes, as JSON
+    /// on stdout
+    Compliance,
+    /// Plain-text waste summary that fails below 90% efficiency
+    Ci,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Config {
+    preset: Option<Preset>,
+}
+
+/// The preset `.peel.json` picks, if the file exists and names one.
+pub fn from_config() -> Result<Option<Preset>> {
+    let path = Path::new(CONFIG);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let text = std::fs::read_to_string(path)
+        .with_context(|| t!("preset-config-read-failed", path = CONFIG))?;
+    let config: Config =
+        serde_json::from_str(&text).with_context(|| t!("preset-config-invalid", path = CONFIG))?;
+    Ok(config.preset)
+}
+
+/// Which analyzers `peel inspect` runs.
+#[derive(Debug, Clone, Copy)]
+pub struct Analyzers {
+    pub waste: bool,
+    pub entropy: bool,
+    pub cache_mounts: bool,
+    pub junk: bool,
+    pub toolchain: bool,
+    pub strip: bool,
+    pub duplicates: bool,
+    pub content: bool,
+    pub file_types: bool,
+    pub class: bool,
+    pub posture: bool,
+    pub runtimes: bool,
+}
+
+impl Analyzers {
+    /// Everything; what runs without a preset.
+    pub const ALL: Analyzers = Analyzers {
+        waste: true,
+        entropy: true,
+        cache_mounts: true,
+        junk: true,
+        toolchain: true,
+        strip: true,
+        duplicates: true,
+        content: true,
+        file_types: true,
+        class: true,
+        posture: true,
+        runtimes: true,
+    };
+
+    /// Nothing; what runs with `--no-files`, as every analyzer reads files.
+    pub const NONE: Analyzers = Analyzers {
+        waste: false,
+        entropy: false,
+        cache_mounts: false,
+        junk: false,
+        toolchain: false,
+        strip: false,
+        duplicates: false,
+        content: false,
+        file_types: false,
+        class: false,
+        posture: false,
+        runtimes: false,
+    };
+}
+
+/// What a preset turns on.
+pub struct Settings {
+    pub analyzers: Analyzers,
+    pub checksums: bool,
+    pub merged: bool,
+    pub min_efficiency: Option<f64>,
+
+    /// Used unless `--json`, `--no-web` or `--output` is given
+    pub output: Option<Output>,
+}
+
+impl Preset {
+    pub fn settings(self) -> Settings {
+        let none = Analyzers::NONE;
+        match self {
+            Preset::Security => Settings {
+                analyzers: Analyzers {
+                    entropy: true,
+                    toolchain: true,
+                    class: true,
+                    posture: true,
+                    runtimes: true,
+                    ..none
+                },
+                checksums: true,
+                merged: false,
+                min_efficiency: None,
+                output: None,
+            },
+            Preset::Size => Settings {
+                analyzers: Analyzers {
+                    waste: true,
+                    cache_mounts: true,
+                    junk: true,
+                    toolchain: true,
+                    strip: true,
+                    duplicates: true,
+                    content: true,
+                    file_types: true,
+                    class: true,
+                    ..none
+                },
+                checksums: false,
+                merged: false,
+                min_efficiency: None,
+                output: None,
+            },
+            Preset::Compliance => Settings {
+                analyzers: Analyzers {
+                    class: true,
+                    runtimes: true,
+                    ..none
+                },
+                checksums: true,
+                merged: true,
+                min_efficiency: None,
+                output: Some(Output::Json("-".to_string())),
+            },
+            Preset::Ci => Settings {
+                analyzers: Analyzers {
+                    waste: true,
+                    cache_mounts: true,
+                    junk: true,
+                    class: true,
+                    ..none
+                },
+                checksums: false,
+                merged: false,
+                min_efficiency: Some(90.0),
+                output: Some(Output::Text),
+            },
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use anyhow::Result;
 
+use super::output::write_json;
 use crate::config;
 
 pub fn run(json: bool, runtime: Option<String>) -> Result<()> {
@@ -7,31 +8,33 @@ pub fn run(json: bool, runtime: Option<String>) -> Result<()> {
     let cfg = config::get();
 
     if cfg.json {
-        println!("{}", serde_json::to_string_pretty(&cfg.probe)?);
+        write_json("-", &cfg.probe)?;
     } else if cfg.probe.runtimes.is_empty() {
-        println!("No container runtimes detected.");
+        println!("{}", t!("probe-none"));
     } else {
-        println!("Detected container runtimes:\n");
+        println!("{}\n", t!("probe-header"));
         for (i, rt) in cfg.probe.runtimes.iter().enumerate() {
             let marker = if cfg.probe.default == Some(i) {
-                " (default)"
+                format!(" {}", t!("probe-default"))
             } else {
-                ""
+                String::new()
             };
             println!("  {}{}", rt.kind, marker);
-            println!("    Binary:           {}", rt.binary_path.display());
-            println!("    Storage root:     {}", rt.storage_root.display());
-            println!("    Storage driver:   {}", rt.storage_driver);
+            println!("    {:<18}{}", t!("probe-binary"), rt.binary_path.display());
+            println!("    {:<18}{}", t!("probe-storage-root"), rt.storage_root.display());
+            println!("    {:<18}{}", t!("probe-storage-driver"), rt.storage_driver);
             println!(
-                "    Daemon running:   {}",
-                if rt.is_running { "yes" } else { "no" }
+                "    {:<18}{}",
+                t!("probe-daemon-running"),
+                if rt.is_running { t!("yes") } else { t!("no") }
             );
             println!(
-                "    Storage readable: {}",
+                "    {:<18}{}",
+                t!("probe-storage-readable"),
                 if rt.can_read {
-                    "yes"
+                    t!("yes")
                 } else {
-                    "no (run as root)"
+                    t!("probe-run-as-root")
                 }
             );
             println!();
@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+
+use super::inspect::format_bytes;
+use super::output::{Output, write_json};
+use super::shared::runtime_output;
+use crate::analysis::pull::{self, PullEstimate, PullLayer};
+use crate::config;
+use crate::inspector;
+use crate::probe::RuntimeKind;
+use crate::progress::Spinner;
+use crate::registry::client::{Descriptor, Manifest};
+use crate::registry::trace::Trace;
+use crate::registry::{Client, Reference};
+use crate::style::Stylize;
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    rootfs: Rootfs,
+}
+
+#[derive(Deserialize)]
+struct Rootfs {
+    #[serde(default)]
+    diff_ids: Vec<String>,
+}
+
+/// What the local runtime already holds: unpacked layers by chain ID
+/// (Docker, Podman) or content blobs by digest (containerd).
+#[derive(Default)]
+struct LocalStore {
+    chains: HashSet<String>,
+    blobs: HashSet<String>,
+}
+
+pub fn run(
+    image: &str,
+    platform: Option<&str>,
+    output: &Output,
+    runtime: Option<String>,
+    trace: Option<Trace>,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    let spinner = Spinner::new(t!("pull-spinner-local"));
+    let local = local_store(cfg)?;
+    spinner.finish(t!(
+        "pull-local",
+        count = local.chains.len() + local.blobs.len()
+    ));
+
+    let reference = Reference::parse(image)?;
+    let spinner = Spinner::new(t!("spinner-resolving-image", image = &reference));
+    let mut client = Client::new(trace);
+    config::set_backend("registry");
+    let (manifest, platform) = resolve_platform(&mut client, &reference, platform)?;
+    let config = manifest
+        .config
+        .as_ref()
+        .with_context(|| t!("registry-bad-manifest", reference = &reference))?;
+    let config: ImageConfig = serde_json::from_reader(client.fetch_blob(
+        &reference.registry,
+        &reference.repository,
+        &config.digest,
+    )?)
+    .with_context(|| t!("registry-bad-manifest", reference = &reference))?;
+
+    // A layer is only reused on top of the same parents, so match by chain
+    let chain_ids = inspector::chain_ids(&config.rootfs.diff_ids);
+    let layers: Vec<PullLayer> = manifest
+        .layers
+        .into_iter()
+        .enumerate()
+        .map(|(i, d)| PullLayer {
+            present: local.blobs.contains(&d.digest)
+                || chain_ids.get(i).is_some_and(|c| local.chains.contains(c)),
+            diff_id: config.rootfs.diff_ids.get(i).cloned(),
+            digest: d.digest,
+            size: d.size,
+        })
+        .collect();
+    spinner.finish(t!(
+        "resolved-image",
+        image = &reference,
+        count = layers.len()
+    ));
+
+    let result = pull::estimate(reference.to_string(), platform, layers);
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&result)?;
+            super::report::write_bundle(dir, "pull-size", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &result),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_estimate(&result);
+            Ok(())
+        }
+    }
+}
+
+/// The image manifest for `platform` (default: this machine's), following
+/// an index if `reference` points at one.
+fn resolve_platform(
+    client: &mut Client,
+    reference: &Reference,
+    platform: Option<&str>,
+) -> Result<(Manifest, Option<String>)> {
+    let manifest = client.manifest(reference)?;
+    if !manifest.is_index() {
+        return Ok((manifest, None));
+    }
+
+    let wanted = platform.map_or_else(host_platform, str::to_string);
+    let child = platform_entry(&manifest, &wanted)?;
+    let child_ref = Reference {
+        reference: child.digest.clone(),
+        ..reference.clone()
+    };
+    Ok((client.manifest(&child_ref)?, Some(wanted)))
+}
+
+/// The entry of `index` built for `platform`, given as `os/arch` or
+/// `os/arch/variant`.
+pub fn platform_entry<'a>(index: &'a Manifest, platform: &str) -> Result<&'a Descriptor> {
+    index
+        .manifests
+        .iter()
+        .find(|m| {
+            m.platform.as_ref().is_some_and(|p| {
+                p.to_string() == platform || format!("{}/{}", p.os, p.architecture) == platform
+            })
+        })
+        .with_context(|| {
+            let available: Vec<String> = index
+                .manifests
+                .iter()
+                .filter_map(|m| m.platform.as_ref().map(|p| p.to_string()))
+                .collect();
+            t!(
+                "pull-no-platform",
+                platform = platform,
+                available = available.join(", ")
+            )
+        })
+}
+
+/// `linux/<arch>` in the names registries use.
+pub fn host_platform() -> String {
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "amd64",
+        "aarch64" => "arm64",
+        "powerpc64" => "ppc64le",
+        other => other,
+    };
+    format!("linux/{arch}")
+}
+
+/// Layers of every image in the default runtime.
+fn local_store(cfg: &config::AppConfig) -> Result<LocalStore> {
+    let Some(idx) = cfg.probe.default else {
+        bail!(t!("runtime-none-installed"));
+    };
+    let rt = &cfg.probe.runtimes[idx];
+    let mut store = LocalStore::default();
+
+    if rt.kind.matches(&RuntimeKind::Containerd) {
+        // Layers stay in the content store as the blobs that were pulled
+        let out = runtime_output(rt, &["content", "ls", "-q"])?;
+        store.blobs = out.lines().map(|l| l.trim().to_string()).collect();
+        return Ok(store);
+    }
+
+    let out = runtime_output(rt, &["images", "-q", "--no-trunc"])?;
+    let ids: HashSet<&str> = out
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .collect();
+    if ids.is_empty() {
+        return Ok(store);
+    }
+    let mut args = vec!["image", "inspect", "--format", "{{json .RootFS.Layers}}"];
+    args.extend(ids);
+    let out = runtime_output(rt, &args)?;
+    for line in out.lines().filter(|l| !l.trim().is_empty()) {
+        let diff_ids: Vec<String> = serde_json::from_str(line)
+            .with_context(|| format!("Failed to parse image layers: {line}"))?;
+        store.chains.extend(inspector::chain_ids(&diff_ids));
+    }
+    Ok(store)
+}
+
+/// Layer table followed by the total.
+pub fn print_estimate(e: &PullEstimate) {
+    if let Some(platform) = &e.platform {
+        println!("{}", t!("pull-platform", platform = platform).dim());
+    }
+    println!("  {:<19}  {:>10}", "DIGEST", "SIZE");
+    for layer in &e.layers {
+        let short = layer.digest.get(..19).unwrap_or(&layer.digest);
+        let state = if layer.present {
+            t!("pull-layer-local").dim().to_string()
+        } else {
+            t!("pull-layer-download").yellow().to_string()
+        };
+        println!(
+            "  {:<19}  {:>10}  {}",
+            short,
+            format_bytes(layer.size),
+            state
+        );
+    }
+    println!();
+    let present = e.layers.iter().filter(|l| l.present).count();
+    println!(
+        "{}",
+        t!(
+            "pull-total",
+            transfer = format_bytes(e.transfer_size).bold(),
+            total = format_bytes(e.total_size),
+            present = present,
+            count = e.layers.len()
+        )
+    );
+}
@@ -0,0 +1,129 @@
+use std::collections::HashSet;
+
+use anyhow::Result;
+
+use super::inspect::format_bytes;
+use super::output::{Output, write_json};
+use crate::analysis::push::{self, Blob, PushEstimate};
+use crate::config;
+use crate::progress::Spinner;
+use crate::registry::client::Descriptor;
+use crate::registry::trace::Trace;
+use crate::registry::{Client, Reference};
+use crate::style::Stylize;
+
+pub fn run(
+    images: &[String],
+    from: &str,
+    to: &str,
+    output: &Output,
+    trace: Option<Trace>,
+) -> Result<()> {
+    let mut client = Client::new(trace);
+    config::set_backend("registry");
+    let mut plans = Vec::with_capacity(images.len());
+
+    for image in images {
+        let source = Reference::parse(&join_ref(from, image))?;
+        let destination = Reference::parse(&join_ref(to, image))?;
+
+        let spinner = Spinner::new(t!("spinner-resolving-image", image = &source));
+        let descriptors = collect_blobs(&mut client, &source)?;
+
+        let mut blobs = Vec::with_capacity(descriptors.len());
+        for (i, d) in descriptors.into_iter().enumerate() {
+            spinner.set_message(t!(
+                "spinner-checking-blob",
+                current = i + 1,
+                total = blobs.capacity(),
+                registry = &destination.registry
+            ));
+            let present = client.blob_exists(&destination.registry, &destination.repository, &d.digest)?;
+            blobs.push(Blob {
+                digest: d.digest,
+                size: d.size,
+                present,
+            });
+        }
+        let missing = blobs.iter().filter(|b| !b.present).count();
+        spinner.finish(t!("push-checked", image = &source, missing = missing, total = blobs.len()));
+
+        plans.push((source.to_string(), destination, blobs));
+    }
+
+    let result = push::estimate(plans);
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&result)?;
+            super::report::write_bundle(dir, "push-estimate", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &result),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_estimate(images, &result);
+            Ok(())
+        }
+    }
+}
+
+/// `registry[/prefix]` + `repo:tag` → `registry/prefix/repo:tag`.
+fn join_ref(prefix: &str, image: &str) -> String {
+    format!("{}/{}", prefix.trim_end_matches('/'), image)
+}
+
+/// Config and layer descriptors for every platform `reference` covers, without duplicates.
+fn collect_blobs(client: &mut Client, reference: &Reference) -> Result<Vec<Descriptor>> {
+    let manifest = client.manifest(reference)?;
+    let manifests = if manifest.is_index() {
+        manifest
+            .manifests
+            .iter()
+            .map(|child| {
+                let child_ref = Reference {
+                    reference: child.digest.clone(),
+                    ..reference.clone()
+                };
+                client.manifest(&child_ref)
+            })
+            .collect::<Result<Vec<_>>>()?
+    } else {
+        vec![manifest]
+    };
+
+    let mut seen = HashSet::new();
+    Ok(manifests
+        .into_iter()
+        .flat_map(|m| m.config.into_iter().chain(m.layers))
+        .filter(|d| seen.insert(d.digest.clone()))
+        .collect())
+}
+
+/// Per-image table followed by the deduplicated total.
+pub fn print_estimate(refs: &[String], e: &PushEstimate) {
+    let name_width = refs.iter().map(|r| r.len()).max().unwrap_or(5).max(5);
+
+    println!(
+        "{:<name_width$}  {:>6}  {:>7}  {:>10}  {:>10}",
+        "IMAGE", "BLOBS", "MISSING", "SIZE", "TRANSFER"
+    );
+    for (r, plan) in refs.iter().zip(&e.images) {
+        println!(
+            "{:<name_width$}  {:>6}  {:>7}  {:>10}  {:>10}",
+            r,
+            plan.blob_count,
+            plan.missing.len(),
+            format_bytes(plan.total_size),
+            format_bytes(plan.transfer_size),
+        );
+    }
+    println!();
+    println!(
+        "{}",
+        t!(
+            "push-total",
+            transfer = format_bytes(e.transfer_size).bold(),
+            total = format_bytes(e.total_size),
+            missing = e.missing_blobs,
+        )
+    );
+}
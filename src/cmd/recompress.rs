@@ -0,0 +1,184 @@
+use std::io::{self, Read, Write};
+
+use anyhow::{Context, Result, bail};
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use serde::Serialize;
+
+use super::inspect::{format_bytes, open_inspector, print_runtime_summary, select_method};
+use super::output::{Output, write_json};
+use crate::config;
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+/// Levels zstd accepts.
+const ZSTD_LEVELS: std::ops::RangeInclusive<i32> = 1..=22;
+
+#[derive(Serialize)]
+pub struct RecompressReport {
+    /// zstd level used
+    pub level: i32,
+
+    pub layers: Vec<LayerRecompress>,
+
+    /// Sums over all layers
+    pub tar_size: u64,
+    pub gzip_size: u64,
+    pub zstd_size: u64,
+}
+
+#[derive(Serialize)]
+pub struct LayerRecompress {
+    pub digest: String,
+
+    /// Bytes of the uncompressed layer tar
+    pub tar_size: u64,
+
+    /// The blob as stored today, when the backend knows it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub compressed_size: Option<u64>,
+
+    /// gzip at its default level, as most build tools push layers
+    pub gzip_size: u64,
+
+    pub zstd_size: u64,
+}
+
+/// Compress every layer with both gzip and zstd and compare the sizes.
+/// Nothing is written; this only measures.
+pub fn run(
+    image: &str,
+    level: i32,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    if !ZSTD_LEVELS.contains(&level) {
+        bail!(t!("recompress-bad-level", level = level));
+    }
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let spinner = Spinner::new(t!("spinner-resolving-image", image = image));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let info = inspector.inspect(image)?;
+
+    let mut layers = Vec::with_capacity(info.layers.len());
+    for (i, layer) in info.layers.iter().enumerate() {
+        spinner.set_message(t!(
+            "recompress-spinner",
+            current = i + 1,
+            total = info.layers.len()
+        ));
+        let (tar_size, gzip_size, zstd_size) =
+            measure(inspector.read_layer(layer)?, level).context(t!("repack-read-failed"))?;
+        layers.push(LayerRecompress {
+            digest: layer.digest.clone(),
+            tar_size,
+            compressed_size: layer.compressed_size,
+            gzip_size,
+            zstd_size,
+        });
+    }
+    spinner.finish(t!("recompress-done", count = layers.len()));
+
+    let report = RecompressReport {
+        level,
+        tar_size: layers.iter().map(|l| l.tar_size).sum(),
+        gzip_size: layers.iter().map(|l| l.gzip_size).sum(),
+        zstd_size: layers.iter().map(|l| l.zstd_size).sum(),
+        layers,
+    };
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "recompress", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_recompress(&report);
+            Ok(())
+        }
+    }
+}
+
+/// Uncompressed, gzip and zstd sizes of one pass over `layer`.
+fn measure(mut layer: impl Read, level: i32) -> io::Result<(u64, u64, u64)> {
+    let mut gzip = GzEncoder::new(Counter::default(), Compression::default());
+    let mut zstd = zstd::Encoder::new(Counter::default(), level)?;
+    let mut buf = vec![0u8; 256 * 1024];
+    let mut tar_size = 0;
+    loop {
+        let n = layer.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        gzip.write_all(&buf[..n])?;
+        zstd.write_all(&buf[..n])?;
+        tar_size += n as u64;
+    }
+    Ok((tar_size, gzip.finish()?.0, zstd.finish()?.0))
+}
+
+/// A writer that only counts what goes through it.
+#[derive(Default)]
+struct Counter(u64);
+
+impl Write for Counter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0 += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Per-layer table followed by the totals.
+pub fn print_recompress(r: &RecompressReport) {
+    println!(
+        "  {:<19}  {:>10}  {:>10}  {:>10}  {:>8}",
+        "DIGEST",
+        "TAR",
+        "GZIP",
+        format!("ZSTD -{}", r.level),
+        "CHANGE"
+    );
+    for layer in &r.layers {
+        let short = layer.digest.get(..19).unwrap_or(&layer.digest);
+        println!(
+            "  {:<19}  {:>10}  {:>10}  {:>10}  {:>8}",
+            short,
+            format_bytes(layer.tar_size),
+            format_bytes(layer.gzip_size),
+            format_bytes(layer.zstd_size),
+            change(layer.gzip_size, layer.zstd_size),
+        );
+    }
+    println!();
+    let line = t!(
+        "recompress-total",
+        gzip = format_bytes(r.gzip_size),
+        zstd = format_bytes(r.zstd_size),
+        level = r.level,
+        change = change(r.gzip_size, r.zstd_size)
+    );
+    if r.zstd_size < r.gzip_size {
+        println!("{}", line.green());
+    } else {
+        println!("{line}");
+    }
+}
+
+/// zstd's size relative to gzip's, e.g. "-23.4%".
+fn change(gzip: u64, zstd: u64) -> String {
+    if gzip == 0 {
+        return "-".to_string();
+    }
+    format!("{:+.1}%", (zstd as f64 - gzip as f64) / gzip as f64 * 100.0)
+}
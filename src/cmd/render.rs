@@ -0,0 +1,78 @@
+use std::fs;
+use std::io::{self, Read};
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde::Deserialize;
+
+use super::inspect::InspectReport;
+use super::output::{read_json, write_json};
+use crate::analysis::compare::Comparison;
+use crate::analysis::diff::ImageDiff;
+
+/// Output formats `peel render` can produce from a saved report.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Format {
+    /// The terminal summary printed with `--no-web`
+    Text,
+    /// Pretty-printed JSON, as written by `--json`
+    Json,
+    /// The self-contained web report
+    Html,
+}
+
+/// Any JSON document written by `peel inspect`, `peel diff` or `peel compare`.
+///
+/// Variants are tried in order, so the more specific shapes come first.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Document {
+    Diff(ImageDiff),
+    Comparison(Comparison),
+    Image(Box<InspectReport>),
+}
+
+/// Re-render a saved report without inspecting anything. `input` may be `-` for stdin.
+pub fn run(input: &str, format: Format) -> Result<()> {
+    let data = if input == "-" {
+        let mut buf = String::new();
+        io::stdin()
+            .read_to_string(&mut buf)
+            .context(t!("render-read-failed", path = input))?;
+        buf
+    } else {
+        fs::read_to_string(input).with_context(|| t!("render-read-failed", path = input))?
+    };
+    let doc: Document =
+        read_json(&data).with_context(|| t!("render-not-a-report", path = input))?;
+
+    match format {
+        Format::Text => match &doc {
+            Document::Image(report) => super::inspect::print_report(report),
+            Document::Diff(diff) => super::diff::print_diff(diff),
+            Document::Comparison(c) => {
+                let refs: Vec<String> = c
+                    .images
+                    .iter()
+                    .map(|img| super::diff::display_ref(&img.name, &img.tag))
+                    .collect();
+                super::compare::print_comparison(&refs, c);
+            }
+        },
+        Format::Json => match &doc {
+            Document::Image(report) => write_json("-", report)?,
+            Document::Diff(diff) => write_json("-", diff)?,
+            Document::Comparison(c) => write_json("-", c)?,
+        },
+        Format::Html => {
+            let json_str = match &doc {
+                Document::Image(report) => serde_json::to_string_pretty(report)?,
+                Document::Diff(diff) => serde_json::to_string_pretty(diff)?,
+                Document::Comparison(_) => bail!(t!("render-no-html")),
+            };
+            print!("{}", super::report::build_report(&json_str));
+        }
+    }
+
+    Ok(())
+}
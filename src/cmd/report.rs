@@ -1,16 +1,43 @@
+use std::fs;
 use std::io::{Read, Write};
 use std::net::TcpListener;
+use std::path::Path;
 
-use anyhow::Result;
-use crossterm::style::Stylize;
+use anyhow::{Context, Result};
+use serde::Serialize;
 
-const TEMPLATE: &str = include_str!("../../assets/index.html");
+use super::inspect::format_bytes;
+use crate::progress;
+use crate::style::{self, Stylize};
+use crate::temp_files;
+use crate::timings;
+
+/// The web report, built from `html_report/` by build.rs.
+const TEMPLATE: &str = include_str!(concat!(env!("OUT_DIR"), "/report/index.html"));
+
+/// Version of the report bundle layout and its JSON data. Bump on breaking changes.
+pub const BUNDLE_SCHEMA_VERSION: u32 = 1;
+
+/// `manifest.json` at the root of a report bundle (see docs/report-bundle.md).
+#[derive(Serialize)]
+struct BundleManifest<'a> {
+    schema_version: u32,
+    kind: &'a str,
+    peel_version: &'a str,
+    data: &'a str,
+    viewer: Option<&'a str>,
+}
 
 /// Inject JSON data into the HTML template by filling the empty `__PEEL_DATA__` script tag.
+///
+/// The document language is set to the active message catalog so the report
+/// UI can pick matching strings.
 pub fn build_report(json: &str) -> String {
     // Escape any </script> inside JSON to prevent premature tag closure
     let safe_json = json.replace("</script>", "<\\/script>");
-    TEMPLATE.replace(
+    TEMPLATE
+        .replacen(r#"<html lang="en">"#, &format!(r#"<html lang="{}">"#, crate::i18n::lang()), 1)
+        .replace(
         r#"<script id="__PEEL_DATA__" type="application/json"></script>"#,
         &format!(
             r#"<script id="__PEEL_DATA__" type="application/json">{}</script>"#,
@@ -19,13 +46,86 @@ pub fn build_report(json: &str) -> String {
     )
 }
 
+/// Write the JSON payload and rendered HTML to the temp dir, then serve the report.
+///
+/// `name` is only used to derive readable file names.
+pub fn publish(name: &str, json_str: &str, html: &str) -> Result<()> {
+    let safe_name = name.replace(|c: char| !c.is_alphanumeric() && c != '-', "_");
+    let salt: u16 = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| (d.as_millis() % 10000) as u16)
+        .unwrap_or(0);
+    let tmp = std::env::temp_dir();
+    let json_path = tmp.join(format!("peel-{safe_name}-{salt}.json"));
+    let html_path = tmp.join(format!("peel-{safe_name}-{salt}.html"));
+
+    // Half-written files are removed if peel is interrupted; whole ones stay
+    temp_files::track(&json_path);
+    fs::write(&json_path, json_str)
+        .with_context(|| t!("write-json-failed", path = json_path.display()))?;
+    temp_files::keep(&json_path);
+    progress::done(t!(
+        "wrote-file-size",
+        path = style::style(json_path.display()).cyan(),
+        size = format_bytes(json_str.len() as u64)
+    ));
+
+    temp_files::track(&html_path);
+    fs::write(&html_path, html)
+        .with_context(|| t!("write-html-failed", path = html_path.display()))?;
+    temp_files::keep(&html_path);
+    progress::done(t!(
+        "wrote-file-size",
+        path = style::style(html_path.display()).cyan(),
+        size = format_bytes(html.len() as u64)
+    ));
+
+    serve(html)
+}
+
+/// Write a report bundle: `manifest.json`, the `report.json` data and, when the
+/// report kind has a web view, a self-contained `index.html` that can be embedded
+/// with an iframe.
+pub fn write_bundle(dir: &Path, kind: &str, json_str: &str, html: Option<&str>) -> Result<()> {
+    fs::create_dir_all(dir).with_context(|| t!("bundle-create-failed", path = dir.display()))?;
+
+    let manifest = BundleManifest {
+        schema_version: BUNDLE_SCHEMA_VERSION,
+        kind,
+        peel_version: env!("CARGO_PKG_VERSION"),
+        data: "report.json",
+        viewer: html.map(|_| "index.html"),
+    };
+    let mut files = vec![
+        ("manifest.json", serde_json::to_string_pretty(&manifest)?),
+        ("report.json", json_str.to_string()),
+    ];
+    if let Some(html) = html {
+        files.push(("index.html", html.to_string()));
+    }
+
+    for (name, contents) in &files {
+        let path = dir.join(name);
+        fs::write(&path, contents)
+            .with_context(|| t!("bundle-write-failed", path = path.display()))?;
+    }
+    progress::done(t!(
+        "wrote-bundle",
+        path = style::style(dir.display()).cyan(),
+        count = files.len()
+    ));
+    Ok(())
+}
+
 /// Serve the HTML report on a random local port, blocking until Ctrl+C.
 pub fn serve(html: &str) -> Result<()> {
     let listener = TcpListener::bind("127.0.0.1:0")?;
     let addr = listener.local_addr()?;
     eprintln!();
-    eprintln!("Report available at {}", format!("http://{addr}").cyan());
-    eprintln!("Press Ctrl+C to stop.");
+    eprintln!("{}", t!("report-available", url = format!("http://{addr}").cyan()));
+    eprintln!("{}", t!("report-stop"));
+    // Serving lasts until Ctrl+C; the work timed is done
+    timings::print();
 
     for stream in listener.incoming() {
         let mut stream = match stream {
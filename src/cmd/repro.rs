@@ -0,0 +1,167 @@
+use anyhow::{Result, bail};
+
+use super::diff::display_ref;
+use super::inspect::{
+    fit_start, format_bytes, load_image_with_inspector, print_runtime_summary, select_method,
+};
+use super::output::{Output, write_json};
+use crate::analysis::repro::{self, FileFacts, PathChange, PathDiff, ReproReport, Verdict};
+use crate::config;
+use crate::inspector::ImageInfo;
+use crate::style::Stylize;
+
+/// Paths of each kind shown in the terminal summary.
+const TOP_PATHS: usize = 20;
+
+pub fn run(
+    left: &str,
+    right: &str,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, left, use_oci));
+
+    // Only the configs are kept, so one OCI save output is open at a time
+    let load = |image: &str| -> Result<(ImageInfo, Option<Vec<u8>>)> {
+        let (info, inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+        Ok((info, inspector.config().map(<[u8]>::to_vec)))
+    };
+    let (left_info, left_config) = load(left)?;
+    let (right_info, right_config) = load(right)?;
+    let mut report = repro::compare(
+        &left_info,
+        left_config.as_deref(),
+        &right_info,
+        right_config.as_deref(),
+    );
+    report.left = display_ref(&left_info.name, &left_info.tag);
+    report.right = display_ref(&right_info.name, &right_info.tag);
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "repro", &json_str, None)?;
+        }
+        Output::Json(dest) => write_json(dest, &report)?,
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_repro(&report)
+        }
+    }
+
+    // Metadata-only differences are what timestamps do; content isn't reproducible
+    if report.verdict == Verdict::ContentDiffers {
+        bail!(t!("repro-failed", count = report.content_diffs().count()));
+    }
+    Ok(())
+}
+
+/// Counts, then the paths whose content differs, then those whose metadata does.
+pub fn print_repro(report: &ReproReport) {
+    println!("{} → {}", report.left, report.right);
+    println!(
+        "  {}",
+        t!(
+            "repro-layers",
+            identical = report.identical_layers,
+            left = report.left_layers,
+            right = report.right_layers
+        )
+    );
+    println!(
+        "  {}",
+        t!(
+            "repro-files",
+            files = report.files,
+            differing = report.paths.len()
+        )
+    );
+    if !report.config.is_empty() {
+        println!(
+            "  {}",
+            t!("repro-config", fields = report.config.join(", "))
+        );
+    }
+    println!();
+
+    let content: Vec<&PathDiff> = report.content_diffs().collect();
+    if !content.is_empty() {
+        println!("{}", t!("repro-content-heading").dim());
+        for diff in content.iter().take(TOP_PATHS) {
+            let (marker, size) = match (diff.change, &diff.left, &diff.right) {
+                (PathChange::Removed, Some(l), _) => ("-".red(), format_bytes(l.size)),
+                (PathChange::Added, _, Some(r)) => ("+".green(), format_bytes(r.size)),
+                (_, _, r) => (
+                    "~".yellow(),
+                    r.as_ref()
+                        .map_or_else(String::new, |r| format_bytes(r.size)),
+                ),
+            };
+            println!(
+                "  {marker} {size:>10}  /{}",
+                fit_start(&diff.path.to_string_lossy(), 16).cyan()
+            );
+        }
+        print_more(content.len());
+        println!();
+    }
+
+    let metadata: Vec<&PathDiff> = report
+        .paths
+        .iter()
+        .filter(|p| p.change == PathChange::Metadata)
+        .collect();
+    if !metadata.is_empty() {
+        println!("{}", t!("repro-metadata-heading").dim());
+        for diff in metadata.iter().take(TOP_PATHS) {
+            let what = match (&diff.left, &diff.right) {
+                (Some(l), Some(r)) => metadata_changes(l, r),
+                _ => String::new(),
+            };
+            println!("  /{}  {}", diff.path.display(), what.dim());
+        }
+        print_more(metadata.len());
+        println!();
+    }
+
+    match report.verdict {
+        Verdict::Identical => println!("{} {}", "✔".green(), t!("repro-identical")),
+        Verdict::MetadataOnly => {
+            println!("{} {}", "✔".green(), t!("repro-metadata-only"))
+        }
+        Verdict::ContentDiffers => println!(
+            "{} {}",
+            "✘".red(),
+            t!("repro-content-differs", count = content.len())
+        ),
+    }
+}
+
+/// "mode 644 → 755, uid 0 → 1000"
+fn metadata_changes(left: &FileFacts, right: &FileFacts) -> String {
+    let show = |v: Option<u32>, octal: bool| match v {
+        Some(v) if octal => format!("{v:o}"),
+        Some(v) => v.to_string(),
+        None => "-".to_string(),
+    };
+    [
+        ("mode", left.mode, right.mode, true),
+        ("uid", left.uid, right.uid, false),
+        ("gid", left.gid, right.gid, false),
+    ]
+    .into_iter()
+    .filter(|(_, l, r, _)| l != r)
+    .map(|(name, l, r, octal)| format!("{name} {} → {}", show(l, octal), show(r, octal)))
+    .collect::<Vec<_>>()
+    .join(", ")
+}
+
+fn print_more(total: usize) {
+    if total > TOP_PATHS {
+        println!("  {}", t!("repro-more", count = total - TOP_PATHS).dim());
+    }
+}
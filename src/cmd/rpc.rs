@@ -0,0 +1,392 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::export_layer::find_layer;
+use super::inspect::{
+    self, load_image_each_layer, open_inspector, print_runtime_summary, select_method,
+};
+use crate::analysis::diff;
+use crate::config;
+use crate::inspector::LayerInfo;
+use crate::progress::Spinner;
+
+// JSON-RPC 2.0 error codes, and LSP's for a cancelled request
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const FAILED: i64 = -32000;
+const CANCELLED: i64 = -32800;
+
+/// A request, or a notification when it has no `id`.
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+
+    method: String,
+
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Serialize)]
+struct Response {
+    jsonrpc: &'static str,
+    id: Value,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<ErrorObject>,
+}
+
+#[derive(Serialize)]
+struct ErrorObject {
+    code: i64,
+    message: String,
+}
+
+/// `layer`: one layer of a running `inspect` or `diff`, files listed.
+#[derive(Serialize)]
+struct Notification<'a> {
+    jsonrpc: &'static str,
+    method: &'static str,
+    params: LayerParams<'a>,
+}
+
+#[derive(Serialize)]
+struct LayerParams<'a> {
+    /// ID of the request the layer belongs to
+    request: &'a Value,
+
+    /// Which image; `diff` loads two
+    image: &'a str,
+
+    index: usize,
+    layer: &'a LayerInfo,
+}
+
+#[derive(Deserialize)]
+struct ImageParams {
+    image: String,
+}
+
+#[derive(Deserialize)]
+struct ListFilesParams {
+    image: String,
+    layer: LayerRef,
+}
+
+/// A layer by index (base = 0, as in JSON output) or by digest prefix.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum LayerRef {
+    Index(usize),
+    Digest(String),
+}
+
+#[derive(Serialize)]
+struct LayerFiles<'a> {
+    index: usize,
+    layer: &'a LayerInfo,
+}
+
+#[derive(Deserialize)]
+struct DiffParams {
+    left: String,
+    right: String,
+}
+
+#[derive(Deserialize)]
+struct CancelParams {
+    id: Value,
+}
+
+/// Returned from inside a request the client cancelled.
+#[derive(Debug)]
+struct Cancelled;
+
+impl fmt::Display for Cancelled {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&t!("rpc-cancelled"))
+    }
+}
+
+impl std::error::Error for Cancelled {}
+
+/// A response that isn't a result: its code and what went wrong.
+struct Failure(i64, String);
+
+impl From<anyhow::Error> for Failure {
+    fn from(e: anyhow::Error) -> Self {
+        if e.downcast_ref::<Cancelled>().is_some() {
+            Failure(CANCELLED, t!("rpc-cancelled"))
+        } else {
+            Failure(FAILED, format!("{e:#}"))
+        }
+    }
+}
+
+/// Lines from stdin, requests waiting their turn, and stdout.
+struct Client {
+    lines: Receiver<String>,
+    queue: VecDeque<Request>,
+    out: io::Stdout,
+}
+
+struct Session<'a> {
+    cfg: &'a config::AppConfig,
+    opts: inspect::Options<'a>,
+    use_oci: bool,
+}
+
+/// Answer JSON-RPC requests, one per line on stdin, until stdin closes.
+/// Requests run one at a time; `cancel` is handled as soon as it arrives.
+pub fn run(use_oci: bool, runtime: Option<String>, no_sudo: bool) -> Result<()> {
+    config::init_from_cli(true, runtime)?;
+    let cfg = config::get();
+
+    let method = select_method(cfg, "", use_oci);
+    print_runtime_summary(cfg, method);
+    // Escalating for a request would re-run peel without the lines already read
+    inspect::escalate_early(cfg, method, no_sudo)?;
+
+    // Read on a thread so a running request can see cancels
+    let (sender, lines) = mpsc::channel();
+    thread::spawn(move || {
+        for line in io::stdin().lock().lines() {
+            let Ok(line) = line else {
+                break;
+            };
+            if sender.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let session = Session {
+        cfg,
+        opts: inspect::Options::all(),
+        use_oci,
+    };
+    let mut client = Client {
+        lines,
+        queue: VecDeque::new(),
+        out: io::stdout(),
+    };
+    loop {
+        let request = match client.queue.pop_front() {
+            Some(request) => request,
+            None => match client.lines.recv() {
+                Ok(line) => {
+                    client.accept(&line, None)?;
+                    continue;
+                }
+                // Stdin closed
+                Err(_) => return Ok(()),
+            },
+        };
+        let id = request.id.unwrap_or(Value::Null);
+        let result = session.call(&mut client, &id, &request.method, request.params);
+        client.reply(id, result)?;
+    }
+}
+
+impl Session<'_> {
+    fn call(
+        &self,
+        client: &mut Client,
+        id: &Value,
+        method: &str,
+        params: Value,
+    ) -> Result<Value, Failure> {
+        match method {
+            "inspect" => {
+                let ImageParams { image } = parse_params(params)?;
+                let (report, _) = inspect::analyze(
+                    self.cfg,
+                    &image,
+                    &self.opts,
+                    self.use_oci,
+                    true,
+                    &mut |index, layer| client.layer_done(id, &image, index, layer),
+                )?;
+                to_value(&report)
+            }
+            "list_files" => {
+                let ListFilesParams { image, layer } = parse_params(params)?;
+                let spinner = Spinner::new(t!("spinner-resolving-image", image = &image));
+                let mut inspector = open_inspector(self.cfg, &image, self.use_oci, true, &spinner)?;
+                let mut info = inspector.inspect(&image)?;
+                let index = match layer {
+                    LayerRef::Index(index) if index < info.layers.len() => index,
+                    LayerRef::Index(index) => {
+                        return Err(Failure(
+                            INVALID_PARAMS,
+                            t!(
+                                "rpc-bad-layer-index",
+                                index = index,
+                                last = info.layers.len().saturating_sub(1)
+                            ),
+                        ));
+                    }
+                    LayerRef::Digest(digest) => find_layer(&info, &digest)?,
+                };
+                let layer = &mut info.layers[index];
+                layer.files = inspector.list_files(layer)?;
+                spinner.finish(t!("rpc-listed-layer", index = index + 1, image = &image));
+                to_value(&LayerFiles { index, layer })
+            }
+            "diff" => {
+                let DiffParams { left, right } = parse_params(params)?;
+                // One side at a time: only one OCI save output is open
+                let mut load = |image: &str| {
+                    load_image_each_layer(
+                        self.cfg,
+                        image,
+                        self.use_oci,
+                        true,
+                        &mut |index, layer| client.layer_done(id, image, index, layer),
+                    )
+                    .map(|(info, _)| info)
+                };
+                let left = load(&left)?;
+                let right = load(&right)?;
+                to_value(&diff::diff(&left, &right))
+            }
+            _ => Err(Failure(
+                METHOD_NOT_FOUND,
+                t!("rpc-unknown-method", method = method),
+            )),
+        }
+    }
+}
+
+impl Client {
+    /// Take one line from the client. Cancels act at once, other requests
+    /// wait in the queue; returns whether `running` was cancelled.
+    fn accept(&mut self, line: &str, running: Option<&Value>) -> Result<bool> {
+        if line.trim().is_empty() {
+            return Ok(false);
+        }
+        let value: Value = match serde_json::from_str(line) {
+            Ok(value) => value,
+            Err(e) => {
+                let error = Failure(PARSE_ERROR, t!("rpc-parse-error", error = e));
+                self.reply(Value::Null, Err(error))?;
+                return Ok(false);
+            }
+        };
+        let id = value.get("id").cloned();
+        let request: Request = match serde_json::from_value(value) {
+            Ok(request) => request,
+            Err(e) => {
+                let error = Failure(INVALID_REQUEST, t!("rpc-invalid-request", error = e));
+                self.reply(id.unwrap_or(Value::Null), Err(error))?;
+                return Ok(false);
+            }
+        };
+
+        if request.method != "cancel" {
+            // Nothing would hear the outcome of a notification
+            if request.id.is_some() {
+                self.queue.push_back(request);
+            }
+            return Ok(false);
+        }
+
+        let target = match parse_params::<CancelParams>(request.params) {
+            Ok(params) => params.id,
+            Err(error) => {
+                if let Some(id) = request.id {
+                    self.reply(id, Err(error))?;
+                }
+                return Ok(false);
+            }
+        };
+        let running = running == Some(&target);
+        let queued = self
+            .queue
+            .iter()
+            .position(|queued| queued.id.as_ref() == Some(&target));
+        if let Some(id) = request.id {
+            self.reply(id, Ok(Value::Bool(running || queued.is_some())))?;
+        }
+        if let Some(queued) = queued.and_then(|i| self.queue.remove(i)) {
+            let error = Failure(CANCELLED, t!("rpc-cancelled"));
+            self.reply(queued.id.unwrap_or(Value::Null), Err(error))?;
+        }
+        Ok(running)
+    }
+
+    /// Send a listed layer of request `id`, then take in what the client
+    /// sent meanwhile; fails with [`Cancelled`] if that cancelled `id`.
+    fn layer_done(
+        &mut self,
+        id: &Value,
+        image: &str,
+        index: usize,
+        layer: &LayerInfo,
+    ) -> Result<()> {
+        self.send(&Notification {
+            jsonrpc: "2.0",
+            method: "layer",
+            params: LayerParams {
+                request: id,
+                image,
+                index,
+                layer,
+            },
+        })?;
+        loop {
+            match self.lines.try_recv() {
+                Ok(line) => {
+                    if self.accept(&line, Some(id))? {
+                        return Err(Cancelled.into());
+                    }
+                }
+                Err(TryRecvError::Empty | TryRecvError::Disconnected) => return Ok(()),
+            }
+        }
+    }
+
+    fn reply(&mut self, id: Value, result: Result<Value, Failure>) -> Result<()> {
+        let (result, error) = match result {
+            Ok(result) => (Some(result), None),
+            Err(Failure(code, message)) => (None, Some(ErrorObject { code, message })),
+        };
+        self.send(&Response {
+            jsonrpc: "2.0",
+            id,
+            result,
+            error,
+        })
+    }
+
+    /// One message per line, flushed so the client sees it now.
+    fn send<T: Serialize>(&mut self, message: &T) -> Result<()> {
+        let mut out = self.out.lock();
+        serde_json::to_writer(&mut out, message)?;
+        writeln!(out)?;
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn parse_params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, Failure> {
+    serde_json::from_value(params)
+        .map_err(|e| Failure(INVALID_PARAMS, t!("rpc-bad-params", error = e)))
+}
+
+fn to_value<T: Serialize>(result: &T) -> Result<Value, Failure> {
+    Ok(serde_json::to_value(result).map_err(anyhow::Error::from)?)
+}
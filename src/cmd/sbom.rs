@@ -0,0 +1,440 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::Serialize;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use super::inspect::{load_image_with_inspector, print_runtime_summary, select_method, truncate};
+use super::output::{Output, write_document, write_json};
+use crate::analysis::merged::normalize;
+use crate::analysis::distro::Distro;
+use crate::analysis::packages::{self, Package, PackageKind};
+use crate::analysis::spdx;
+use crate::config;
+use crate::inspector::{ImageInfo, Inspector};
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+/// What `peel sbom` writes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// A table, or peel's own JSON with --json
+    Peel,
+    /// SPDX 2.3 JSON document
+    Spdx,
+    /// CycloneDX 1.5 JSON document
+    Cyclonedx,
+}
+
+#[derive(Serialize)]
+pub struct SbomReport {
+    pub image: String,
+    pub architecture: Option<String>,
+
+    /// Layer digests, base first; `Package::layer` indexes into these
+    pub layers: Vec<String>,
+
+    pub distro: Option<Distro>,
+    pub packages: Vec<Package>,
+}
+
+/// List the packages installed in `image` from its dpkg or apk database.
+pub fn run(
+    image: &str,
+    format: Format,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json() || format != Format::Peel, runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let report = read_packages(&info, inspector.as_mut())?;
+
+    if format != Format::Peel {
+        let dest = match output {
+            Output::Json(dest) => dest.as_str(),
+            _ => "-",
+        };
+        let document = match format {
+            Format::Spdx => spdx(&report),
+            _ => cyclonedx(&report),
+        };
+        return write_document(dest, &document);
+    }
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "sbom", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_sbom(&report);
+            Ok(())
+        }
+    }
+}
+
+/// Read every layer's copy of the package databases (so packages can be
+/// traced to the layer that installed them) and list what's installed.
+pub fn read_packages(info: &ImageInfo, inspector: &mut dyn Inspector) -> Result<SbomReport> {
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut contents = Vec::new();
+    for (index, layer) in info.layers.iter().enumerate() {
+        let listed: HashSet<_> = layer
+            .files
+            .iter()
+            .filter(|f| !f.is_whiteout)
+            .map(|f| normalize(&f.path))
+            .filter(|p| packages::wanted(p))
+            .collect();
+        if listed.is_empty() {
+            continue;
+        }
+        spinner.set_message(t!(
+            "sbom-spinner",
+            current = index + 1,
+            total = info.layers.len()
+        ));
+        let mut archive = tar::Archive::new(inspector.read_layer(layer)?);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            let path = normalize(&entry.path()?);
+            if !entry.header().entry_type().is_file() || !listed.contains(&path) {
+                continue;
+            }
+            let mut content = Vec::new();
+            entry
+                .read_to_end(&mut content)
+                .context(t!("repack-read-failed"))?;
+            contents.push((index, path, content));
+        }
+    }
+    let list = packages::list(info, &contents);
+    spinner.finish(t!("sbom-done", count = list.packages.len()));
+
+    Ok(SbomReport {
+        image: info.name.clone(),
+        architecture: info.architecture.clone(),
+        layers: info.layers.iter().map(|l| l.digest.clone()).collect(),
+        distro: list.distro,
+        packages: list.packages,
+    })
+}
+
+/// The report as an SPDX 2.3 document: the image as a container package
+/// that contains one package per installed package.
+fn spdx(r: &SbomReport) -> Value {
+    let now = now();
+    let namespace = format!(
+        "https://spdx.org/spdxdocs/peel/{}-{}",
+        spdx_id_part(&r.image),
+        document_hash(r, now)
+    );
+
+    let mut packages = vec![json!({
+        "SPDXID": "SPDXRef-Image",
+        "name": r.image,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "primaryPackagePurpose": "CONTAINER",
+        "comment": format!(
+            "architecture: {}; layers: {}",
+            r.architecture.as_deref().unwrap_or("unknown"),
+            r.layers.join(", ")
+        ),
+    })];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": "SPDXRef-Image",
+    })];
+
+    for (i, p) in r.packages.iter().enumerate() {
+        let id = format!("SPDXRef-Package-{}-{}", spdx_id_part(&p.name), i + 1);
+        let layer = r.layers.get(p.layer).map_or("", String::as_str);
+        let license = p.license.as_deref().and_then(spdx::expression);
+        let mut package = json!({
+            "SPDXID": id,
+            "name": p.name,
+            "versionInfo": p.version,
+            "supplier": p.supplier.as_ref().map_or("NOASSERTION".to_string(), |s| format!("Person: {s}")),
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": license.as_deref().unwrap_or("NOASSERTION"),
+            "copyrightText": "NOASSERTION",
+            "sourceInfo": format!("installed in layer {} ({layer})", p.layer + 1),
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": purl(p, r.distro.as_ref()),
+            }],
+        });
+        // Validators reject anything but SPDX expressions there; keep what
+        // the package said
+        if let Some(declared) = &p.license
+            && license.is_none()
+        {
+            package["licenseComments"] = json!(format!("Declared as \"{declared}\""));
+        }
+        packages.push(package);
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-Image",
+            "relationshipType": "CONTAINS",
+            "relatedSpdxElement": id,
+        }));
+    }
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": r.image,
+        "documentNamespace": namespace,
+        "creationInfo": {
+            "created": rfc3339(now),
+            "creators": [format!("Tool: peel-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+/// The report as a CycloneDX 1.5 BOM: the image is the subject, each
+/// installed package a component with the layer that installed it as
+/// evidence.
+fn cyclonedx(r: &SbomReport) -> Value {
+    let now = now();
+
+    // A v4-shaped UUID from the document hash, unique per document like
+    // the SPDX namespace
+    let hash = document_hash(r, now);
+    let serial = format!(
+        "urn:uuid:{}-{}-4{}-8{}-{}",
+        &hash[..8],
+        &hash[8..12],
+        &hash[13..16],
+        &hash[17..20],
+        &hash[20..32]
+    );
+
+    let components: Vec<Value> = r
+        .packages
+        .iter()
+        .map(|p| {
+            let purl = purl(p, r.distro.as_ref());
+            let layer = r.layers.get(p.layer).map_or("", String::as_str);
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": purl,
+                "name": p.name,
+                "version": p.version,
+                "purl": purl,
+                "evidence": {
+                    "occurrences": [{ "location": layer }],
+                },
+                "properties": [
+                    { "name": "peel:layer", "value": (p.layer + 1).to_string() },
+                    { "name": "peel:layer-digest", "value": layer },
+                ],
+            });
+            if let Some(supplier) = &p.supplier {
+                component["supplier"] = json!({ "name": supplier });
+            }
+            // Declared licenses aren't always valid SPDX expressions
+            if let Some(license) = &p.license {
+                component["licenses"] = json!([{ "license": { "name": license } }]);
+            }
+            component
+        })
+        .collect();
+    let depends_on: Vec<&Value> = components.iter().map(|c| &c["bom-ref"]).collect();
+
+    let mut properties: Vec<Value> = r
+        .architecture
+        .iter()
+        .map(|a| json!({ "name": "peel:architecture", "value": a }))
+        .collect();
+    properties.extend(
+        r.layers
+            .iter()
+            .map(|d| json!({ "name": "peel:layer-digest", "value": d })),
+    );
+    let image = json!({
+        "type": "container",
+        "bom-ref": "image",
+        "name": r.image,
+        "properties": properties,
+    });
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "serialNumber": serial,
+        "version": 1,
+        "metadata": {
+            "timestamp": rfc3339(now),
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "peel",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": image,
+        },
+        "components": components,
+        "dependencies": [{ "ref": "image", "dependsOn": depends_on }],
+    })
+}
+
+/// Seconds since the epoch, for document timestamps.
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Hex SHA-256 over the image, its layers and `now`, so that each document
+/// gets its own identifier.
+fn document_hash(r: &SbomReport, now: u64) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(r.image.as_bytes());
+    hasher.update(now.to_string());
+    for digest in &r.layers {
+        hasher.update(digest.as_bytes());
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+/// Package URL, e.g. `pkg:deb/debian/curl@7.88.1-10?arch=amd64&distro=debian-12`.
+fn purl(p: &Package, distro: Option<&Distro>) -> String {
+    let (kind, default_namespace) = match p.kind {
+        PackageKind::Deb => ("deb", "debian"),
+        PackageKind::Apk => ("apk", "alpine"),
+    };
+    let namespace = distro.map_or(default_namespace, |d| d.id.as_str());
+    let mut purl = format!(
+        "pkg:{kind}/{}/{}@{}",
+        purl_encode(namespace),
+        purl_encode(&p.name),
+        purl_encode(&p.version)
+    );
+    let mut qualifiers = Vec::new();
+    if let Some(arch) = &p.arch {
+        qualifiers.push(format!("arch={}", purl_encode(arch)));
+    }
+    if let Some(Distro {
+        id,
+        version_id: Some(version),
+        ..
+    }) = distro
+    {
+        qualifiers.push(format!(
+            "distro={}",
+            purl_encode(&format!("{id}-{version}"))
+        ));
+    }
+    if !qualifiers.is_empty() {
+        purl.push('?');
+        purl.push_str(&qualifiers.join("&"));
+    }
+    purl
+}
+
+/// Percent-encode everything but unreserved characters, as purl requires
+/// for e.g. the `:` of a Debian epoch or the `+` of `1.2+dfsg`.
+fn purl_encode(s: &str) -> String {
+    s.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'.' | b'-' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+/// SPDX identifiers only allow letters, digits, `.` and `-`.
+fn spdx_id_part(s: &str) -> String {
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+/// `secs` since the epoch as `YYYY-MM-DDThh:mm:ssZ`.
+pub fn rfc3339(secs: u64) -> String {
+    let (days, rem) = (secs / 86400, secs % 86400);
+    // Howard Hinnant's days-to-civil, for dates after 1970
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+/// Package table followed by the count.
+pub fn print_sbom(r: &SbomReport) {
+    if r.packages.is_empty() {
+        println!("{}", t!("sbom-none"));
+        return;
+    }
+    println!(
+        "  {:<32}  {:<28}  {:<4}  {:>5}",
+        "NAME", "VERSION", "TYPE", "LAYER"
+    );
+    for p in &r.packages {
+        let kind = match p.kind {
+            PackageKind::Deb => "deb",
+            PackageKind::Apk => "apk",
+        };
+        println!(
+            "  {:<32}  {:<28}  {:<4}  {:>5}",
+            truncate(&p.name, 32),
+            truncate(&p.version, 28),
+            kind,
+            p.layer + 1
+        );
+    }
+    println!();
+    let distro = r.distro.as_ref().map_or_else(
+        || "-".to_string(),
+        |d| match &d.version_id {
+            Some(v) => format!("{} {v}", d.id),
+            None => d.id.clone(),
+        },
+    );
+    println!(
+        "{}",
+        t!(
+            "sbom-total",
+            count = r.packages.len().to_string().bold(),
+            distro = distro
+        )
+    );
+}
@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::inspect::{fit, load_image_with_inspector, print_runtime_summary, select_method};
+use super::output::{Annotation, Output, annotate, write_json};
+use super::sbom::read_packages;
+use crate::config;
+use crate::progress::Spinner;
+use crate::scan::{self, Finding, Scanner};
+use crate::style::Stylize;
+
+#[derive(Serialize)]
+pub struct ScanReport {
+    pub image: String,
+    pub scanner: Scanner,
+
+    /// Base first; `Finding::layer` indexes into these
+    pub layers: Vec<ScanLayer>,
+
+    /// Packages peel found installed
+    pub package_count: usize,
+
+    /// By layer, then severity
+    pub findings: Vec<Finding>,
+}
+
+#[derive(Serialize)]
+pub struct ScanLayer {
+    pub digest: String,
+    pub created_by: Option<String>,
+}
+
+/// Find known vulnerabilities in the packages of `image` and trace each to
+/// the layer that installed the package.
+pub fn run(
+    image: &str,
+    scanner: Scanner,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let sbom = read_packages(&info, inspector.as_mut())?;
+
+    let spinner = Spinner::new(t!("scan-spinner", scanner = scanner.name()));
+    let mut findings = match scanner {
+        Scanner::Osv => scan::osv(&sbom.packages, sbom.distro.as_ref())?,
+        Scanner::Trivy => scan::trivy(image)?,
+        Scanner::Grype => scan::grype(image)?,
+    };
+
+    // External scanners name layers by diff ID, if at all; the package
+    // inventory says which layer installed each version
+    let by_version: HashMap<(&str, &str), usize> = sbom
+        .packages
+        .iter()
+        .map(|p| ((p.name.as_str(), p.version.as_str()), p.layer))
+        .collect();
+    for finding in &mut findings {
+        if finding.layer.is_none() {
+            finding.layer = by_version
+                .get(&(finding.package.as_str(), finding.version.as_str()))
+                .copied();
+        }
+    }
+    findings.sort_by(|a, b| {
+        (a.layer.unwrap_or(usize::MAX), rank(a), &a.package, &a.id).cmp(&(
+            b.layer.unwrap_or(usize::MAX),
+            rank(b),
+            &b.package,
+            &b.id,
+        ))
+    });
+    findings.dedup_by(|a, b| a.id == b.id && a.package == b.package && a.version == b.version);
+    spinner.finish(t!("scan-done", count = findings.len()));
+
+    let report = ScanReport {
+        image: info.name.clone(),
+        scanner,
+        layers: info
+            .layers
+            .iter()
+            .map(|l| ScanLayer {
+                digest: l.digest.clone(),
+                created_by: l.created_by.clone(),
+            })
+            .collect(),
+        package_count: sbom.packages.len(),
+        findings,
+    };
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "scan", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Junit(_) | Output::Csv(_) => {
+            print_scan(&report);
+            Ok(())
+        }
+        Output::Github => {
+            print_scan(&report);
+            for f in &report.findings {
+                let level = if rank(f) <= 1 {
+                    Annotation::Error
+                } else {
+                    Annotation::Warning
+                };
+                let package = match &f.fixed_version {
+                    Some(fixed) => format!("{} {} → {fixed}", f.package, f.version),
+                    None => format!("{} {}", f.package, f.version),
+                };
+                let message = match &f.summary {
+                    Some(summary) => format!("{package}: {summary}"),
+                    None => package,
+                };
+                annotate(level, &format!("peel scan: {}", f.id), &message);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Sort key: most severe first, unknown severities last.
+fn rank(f: &Finding) -> u8 {
+    match f.severity.as_deref() {
+        Some("CRITICAL") => 0,
+        Some("HIGH") => 1,
+        Some("MEDIUM" | "MODERATE") => 2,
+        Some("LOW") => 3,
+        _ => 4,
+    }
+}
+
+/// Findings grouped under the layer that installed the package.
+pub fn print_scan(r: &ScanReport) {
+    let mut current = None;
+    for f in &r.findings {
+        if current != Some(f.layer) {
+            current = Some(f.layer);
+            println!();
+            let heading = match f.layer.and_then(|i| Some((i, r.layers.get(i)?))) {
+                Some((i, layer)) => t!(
+                    "scan-layer",
+                    layer = i + 1,
+                    command = fit(layer.created_by.as_deref().unwrap_or("-"), 8)
+                ),
+                None => t!("scan-no-layer"),
+            };
+            println!("{}", heading.bold());
+        }
+
+        let severity = f.severity.as_deref().unwrap_or("-");
+        let padded = format!("{severity:<8}");
+        let severity = match rank(f) {
+            0 => padded.red().bold().to_string(),
+            1 => padded.red().to_string(),
+            2 => padded.yellow().to_string(),
+            _ => padded.dim().to_string(),
+        };
+        let package = match &f.fixed_version {
+            Some(fixed) => format!("{} {} → {fixed}", f.package, f.version),
+            None => format!("{} {}", f.package, f.version),
+        };
+        let summary = match &f.summary {
+            Some(summary) => fit(summary, 36 + package.len()).dim().to_string(),
+            None => String::new(),
+        };
+        println!("  {:<20}  {}  {}  {}", f.id, severity, package, summary);
+    }
+
+    println!();
+    if r.findings.is_empty() {
+        println!("{}", t!("scan-clean", packages = r.package_count).green());
+        return;
+    }
+    let mut affected: Vec<&str> = r.findings.iter().map(|f| f.package.as_str()).collect();
+    affected.sort_unstable();
+    affected.dedup();
+    println!(
+        "{}",
+        t!(
+            "scan-total",
+            count = r.findings.len().to_string().bold(),
+            affected = affected.len(),
+            packages = r.package_count
+        )
+    );
+}
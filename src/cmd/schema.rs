@@ -0,0 +1,8 @@
+/// JSON Schema of what `--json` writes; kept in step with
+/// `output::SCHEMA_VERSION`.
+const SCHEMA: &str = include_str!("../../docs/schema.json");
+
+/// `peel schema`: print the JSON Schema, for validating or generating parsers.
+pub fn run() {
+    print!("{SCHEMA}");
+}
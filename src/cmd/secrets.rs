@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+use std::io::Read;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::inspect::{fit, load_image_with_inspector, print_runtime_summary, select_method};
+use super::output::{Annotation, Output, annotate, write_json};
+use crate::analysis::merged::{self, normalize};
+use crate::analysis::secrets::{self, Secret, SecretKind};
+use crate::config;
+use crate::inspector::{ImageInfo, Inspector};
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+#[derive(Serialize)]
+pub struct SecretsReport {
+    /// Created-by command per layer, base first
+    pub layers: Vec<Option<String>>,
+
+    /// By layer, then path
+    pub secrets: Vec<Secret>,
+
+    /// Files read
+    pub files_scanned: usize,
+}
+
+/// Look for credentials in every layer's copy of every small text file,
+/// including copies that later layers deleted or overwrote.
+pub fn run(
+    image: &str,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let (info, mut inspector) = load_image_with_inspector(cfg, image, use_oci, no_sudo)?;
+    let report = scan_layers(&info, inspector.as_mut())?;
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "secrets", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Junit(_) | Output::Csv(_) => {
+            print_secrets(&report);
+            Ok(())
+        }
+        Output::Github => {
+            print_secrets(&report);
+            for s in &report.secrets {
+                let title = format!("peel secrets: {}", kind_name(s.kind));
+                let message = t!(
+                    "secrets-annotation",
+                    location = location(s),
+                    layer = s.layer + 1
+                );
+                annotate(Annotation::Error, &title, &message);
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Read every small text file from each layer and look for credentials.
+pub fn scan_layers(info: &ImageInfo, inspector: &mut dyn Inspector) -> Result<SecretsReport> {
+    let visible = merged::merge(info);
+
+    let spinner = Spinner::new(t!("spinner-resolving"));
+    let mut report = SecretsReport {
+        layers: info.layers.iter().map(|l| l.created_by.clone()).collect(),
+        secrets: Vec::new(),
+        files_scanned: 0,
+    };
+    for (index, layer) in info.layers.iter().enumerate() {
+        spinner.set_message(t!(
+            "secrets-spinner",
+            current = index + 1,
+            total = info.layers.len()
+        ));
+        // The listing already honours --root
+        let listed: HashSet<PathBuf> = layer
+            .files
+            .iter()
+            .filter(|f| !f.is_whiteout)
+            .map(|f| normalize(&f.path))
+            .collect();
+
+        let mut archive = tar::Archive::new(inspector.read_layer(layer)?);
+        for entry in archive.entries().context(t!("repack-read-failed"))? {
+            let mut entry = entry.context(t!("repack-read-failed"))?;
+            let path = normalize(&entry.path()?);
+            if !entry.header().entry_type().is_file()
+                || !listed.contains(&path)
+                || !secrets::candidate(&path, entry.size())
+            {
+                continue;
+            }
+
+            let mut content = Vec::with_capacity(entry.size() as usize);
+            entry
+                .read_to_end(&mut content)
+                .context(t!("repack-read-failed"))?;
+            report.files_scanned += 1;
+            let hidden = visible.get(&path).is_none_or(|f| f.layer != index);
+            secrets::scan(&path, &content, |kind, line, excerpt| {
+                report.secrets.push(Secret {
+                    layer: index,
+                    path: path.clone(),
+                    kind,
+                    line,
+                    excerpt,
+                    hidden,
+                })
+            });
+        }
+    }
+    spinner.finish(t!("secrets-done", count = report.secrets.len()));
+    Ok(report)
+}
+
+/// `/path:line`, or just the path for findings about the whole file.
+fn location(s: &Secret) -> String {
+    match s.line {
+        Some(line) => format!("/{}:{line}", s.path.display()),
+        None => format!("/{}", s.path.display()),
+    }
+}
+
+fn kind_name(kind: SecretKind) -> String {
+    match kind {
+        SecretKind::PrivateKey => t!("secret-private-key"),
+        SecretKind::AwsAccessKey => t!("secret-aws-access-key"),
+        SecretKind::AwsSecretKey => t!("secret-aws-secret-key"),
+        SecretKind::GcpServiceAccount => t!("secret-gcp-service-account"),
+        SecretKind::GcpApiKey => t!("secret-gcp-api-key"),
+        SecretKind::GithubToken => t!("secret-github-token"),
+        SecretKind::SlackToken => t!("secret-slack-token"),
+        SecretKind::StripeKey => t!("secret-stripe-key"),
+        SecretKind::CredentialsFile => t!("secret-credentials-file"),
+        SecretKind::EnvFile => t!("secret-env-file"),
+        SecretKind::Generic => t!("secret-generic"),
+    }
+}
+
+/// Findings grouped by layer, flagging copies the final filesystem hides.
+pub fn print_secrets(r: &SecretsReport) {
+    let mut current = None;
+    for s in &r.secrets {
+        if current != Some(s.layer) {
+            current = Some(s.layer);
+            println!();
+            let command = r.layers[s.layer].as_deref().unwrap_or("-");
+            println!(
+                "{}",
+                t!(
+                    "secrets-layer",
+                    layer = s.layer + 1,
+                    command = fit(command, 8)
+                )
+                .bold()
+            );
+        }
+        let location = location(s);
+        let hidden = if s.hidden {
+            t!("secrets-hidden")
+        } else {
+            String::new()
+        };
+        let used = 2 + 20 + 2 + location.chars().count() + 2 + hidden.chars().count() + 2;
+        let mut line = format!(
+            "  {}  {}",
+            format!("{:<20}", kind_name(s.kind)).yellow(),
+            location.magenta()
+        );
+        if !s.excerpt.is_empty() {
+            line.push_str(&format!("  {}", fit(&s.excerpt, used).dim()));
+        }
+        if s.hidden {
+            line.push_str(&format!("  {}", hidden.red()));
+        }
+        println!("{line}");
+    }
+
+    println!();
+    if r.secrets.is_empty() {
+        println!("{}", t!("secrets-none", files = r.files_scanned).green());
+        return;
+    }
+    let files: HashSet<(usize, &PathBuf)> = r.secrets.iter().map(|s| (s.layer, &s.path)).collect();
+    println!(
+        "{}",
+        t!(
+            "secrets-total",
+            count = r.secrets.len().to_string().bold(),
+            files = files.len()
+        )
+    );
+    let hidden = r.secrets.iter().filter(|s| s.hidden).count();
+    if hidden > 0 {
+        println!("{}", t!("secrets-hidden-note", count = hidden).yellow());
+    }
+}
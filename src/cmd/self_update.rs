@@ -7,17 +7,10 @@ pub fn run() -> Result<()> {
 
     let status = Command::new(&updater)
         .status()
-        .with_context(|| {
-            format!(
-                "Could not find `{updater}`. \
-                 Reinstall peel via the shell installer to get the updater:\n\n  \
-                 curl --proto '=https' --tlsv1.2 -LsSf \
-                 https://github.com/fudanglp/peel/releases/latest/download/peel-installer.sh | sh"
-            )
-        })?;
+        .with_context(|| t!("update-missing", updater = updater))?;
 
     if !status.success() {
-        anyhow::bail!("Update failed (exit code: {})", status.code().unwrap_or(-1));
+        anyhow::bail!(t!("update-failed", code = status.code().unwrap_or(-1)));
     }
 
     Ok(())
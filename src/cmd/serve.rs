@@ -0,0 +1,329 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::rc::Rc;
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use super::inspect::{
+    self, InspectReport, looks_like_archive, open_inspector, print_runtime_summary, select_method,
+};
+use super::shared::local_images;
+use crate::analysis::diff;
+use crate::config;
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+/// Inspected images kept in memory; each holds every file listing.
+const CACHE_SIZE: usize = 16;
+
+/// Largest request body read; requests only carry an image reference.
+const MAX_BODY: usize = 64 * 1024;
+
+/// Largest request line and headers read, together.
+const MAX_HEAD: usize = 16 * 1024;
+
+/// How long a client may stall reading or writing; requests are served one at
+/// a time, so a silent client would otherwise block every other.
+const IO_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `POST /inspect` body.
+#[derive(Deserialize)]
+struct InspectRequest {
+    image: String,
+}
+
+/// An entry of `GET /images`.
+#[derive(Serialize)]
+struct LocalImage {
+    reference: String,
+
+    /// Other references to the same image ID
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    query: HashMap<String, String>,
+    body: Vec<u8>,
+}
+
+/// A response that isn't a report: the status and what went wrong.
+struct Failure(u16, String);
+
+impl From<anyhow::Error> for Failure {
+    fn from(e: anyhow::Error) -> Self {
+        Failure(500, format!("{e:#}"))
+    }
+}
+
+/// Reports by image ID, so every reference to an image shares one and a
+/// moved tag is inspected again. The oldest is dropped first.
+#[derive(Default)]
+struct Cache {
+    reports: HashMap<String, Rc<InspectReport>>,
+    order: VecDeque<String>,
+}
+
+impl Cache {
+    fn insert(&mut self, id: String, report: Rc<InspectReport>) {
+        if self.reports.insert(id.clone(), report).is_none() {
+            self.order.push_back(id);
+        }
+        while self.order.len() > CACHE_SIZE {
+            if let Some(oldest) = self.order.pop_front() {
+                self.reports.remove(&oldest);
+            }
+        }
+    }
+}
+
+struct Server<'a> {
+    cfg: &'a config::AppConfig,
+    opts: inspect::Options<'a>,
+    use_oci: bool,
+    cache: Cache,
+}
+
+/// Serve inspections over HTTP until interrupted, one request at a time.
+pub fn run(listen: &str, use_oci: bool, runtime: Option<String>, no_sudo: bool) -> Result<()> {
+    config::init_from_cli(false, runtime)?;
+    let cfg = config::get();
+
+    let method = select_method(cfg, "", use_oci);
+    print_runtime_summary(cfg, method);
+    // Before listening: escalating for a request would re-run the server
+    // while this one holds the port
+    inspect::escalate_early(cfg, method, no_sudo)?;
+
+    let listener =
+        TcpListener::bind(listen).with_context(|| t!("serve-bind-failed", address = listen))?;
+    let addr = listener.local_addr()?;
+    eprintln!();
+    eprintln!(
+        "{}",
+        t!("serve-listening", url = format!("http://{addr}").cyan())
+    );
+    eprintln!("{}", t!("report-stop"));
+
+    let mut server = Server {
+        cfg,
+        opts: inspect::Options::all(),
+        use_oci,
+        cache: Cache::default(),
+    };
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+        if stream.set_read_timeout(Some(IO_TIMEOUT)).is_err()
+            || stream.set_write_timeout(Some(IO_TIMEOUT)).is_err()
+        {
+            continue;
+        }
+        let (status, body) = match read_request(&mut stream) {
+            Ok(request) => {
+                let (status, body) = match server.handle(&request) {
+                    Ok(body) => (200, body),
+                    Err(Failure(status, error)) => (status, error_json(&error)),
+                };
+                eprintln!(
+                    "{}",
+                    t!(
+                        "serve-request",
+                        method = &request.method,
+                        path = &request.path,
+                        status = status
+                    )
+                    .dim()
+                );
+                (status, body)
+            }
+            Err(e) => (400, error_json(&format!("{e:#}"))),
+        };
+        // The client may have gone; nothing to tell it then
+        let _ = respond(&mut stream, status, &body);
+    }
+    Ok(())
+}
+
+impl Server<'_> {
+    fn handle(&mut self, request: &Request) -> Result<String, Failure> {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("POST", "/inspect") => {
+                let body: InspectRequest = serde_json::from_slice(&request.body)
+                    .map_err(|e| Failure(400, t!("serve-bad-body", error = e)))?;
+                let report = self.report(&body.image)?;
+                Ok(serde_json::to_string(&*report).map_err(anyhow::Error::from)?)
+            }
+            ("GET", "/images") => {
+                let images: Vec<LocalImage> = local_images(self.cfg)?
+                    .into_iter()
+                    .map(|(reference, aliases)| LocalImage { reference, aliases })
+                    .collect();
+                Ok(serde_json::to_string(&images).map_err(anyhow::Error::from)?)
+            }
+            ("GET", "/diff") => {
+                let param = |name: &str| {
+                    request
+                        .query
+                        .get(name)
+                        .ok_or_else(|| Failure(400, t!("serve-missing-param", name = name)))
+                };
+                let (left, right) = (param("left")?, param("right")?);
+                let left = self.report(left)?;
+                let right = self.report(right)?;
+                let result = diff::diff(&left.info, &right.info);
+                Ok(serde_json::to_string(&result).map_err(anyhow::Error::from)?)
+            }
+            (_, "/inspect" | "/images" | "/diff") => Err(Failure(
+                405,
+                t!("serve-bad-method", method = &request.method),
+            )),
+            (_, path) => Err(Failure(404, t!("serve-not-found", path = path))),
+        }
+    }
+
+    /// The cached report if the reference still points at the same image,
+    /// else a fresh one.
+    fn report(&mut self, image: &str) -> Result<Rc<InspectReport>, Failure> {
+        // Only images the runtime holds: a path would let any client read
+        // archives on this machine
+        if looks_like_archive(image) {
+            return Err(Failure(400, t!("serve-archive")));
+        }
+
+        // `run` escalated already; `no_sudo` keeps a request from re-running
+        // the server under sudo
+        let spinner = Spinner::new(t!("spinner-resolving-image", image = image));
+        let mut inspector = open_inspector(self.cfg, image, self.use_oci, true, &spinner)?;
+        let id = inspector.inspect_metadata(image)?.id;
+        drop(inspector);
+        let cached = id.as_ref().and_then(|id| self.cache.reports.get(id));
+        if let Some(report) = cached {
+            spinner.finish(t!("serve-cached", image = image));
+            return Ok(Rc::clone(report));
+        }
+        spinner.finish(t!("resolved-metadata"));
+
+        let (report, _) =
+            inspect::analyze(self.cfg, image, &self.opts, self.use_oci, true, &mut |_, _| Ok(()))?;
+        let report = Rc::new(report);
+        if let Some(id) = report.info.id.clone().or(id) {
+            self.cache.insert(id, Rc::clone(&report));
+        }
+        Ok(report)
+    }
+}
+
+/// Request line, headers up to `Content-Length`, then the body.
+fn read_request(stream: &mut TcpStream) -> Result<Request> {
+    let mut reader = BufReader::new(stream);
+    let mut budget = MAX_HEAD;
+    let line = read_head_line(&mut reader, &mut budget)?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        bail!(t!("serve-bad-request"));
+    };
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let request_method = method.to_string();
+    let request_path = path.to_string();
+
+    let mut length = 0;
+    loop {
+        let header = read_head_line(&mut reader, &mut budget)?;
+        if header.trim().is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.trim().eq_ignore_ascii_case("content-length")
+        {
+            length = value
+                .trim()
+                .parse()
+                .with_context(|| t!("serve-bad-request"))?;
+        }
+    }
+    if length > MAX_BODY {
+        bail!(t!("serve-body-too-large", max = MAX_BODY));
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    Ok(Request {
+        method: request_method,
+        path: request_path,
+        query: query
+            .split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .map(|(k, v)| (percent_decode(k), percent_decode(v)))
+            .collect(),
+        body,
+    })
+}
+
+/// One line of the request line or headers, taken from what's left of
+/// `MAX_HEAD` in `budget`. Empty at the end of the stream.
+fn read_head_line(reader: &mut impl BufRead, budget: &mut usize) -> Result<String> {
+    let mut line = String::new();
+    let read = reader.by_ref().take(*budget as u64).read_line(&mut line)?;
+    if read == *budget && !line.ends_with('\n') {
+        bail!(t!("serve-head-too-large", max = MAX_HEAD));
+    }
+    *budget -= read;
+    Ok(line)
+}
+
+/// Decode `%XX` escapes and `+` in a query string component.
+fn percent_decode(s: &str) -> String {
+    let hex = |b: Option<&u8>| b.and_then(|b| (*b as char).to_digit(16));
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (bytes[i], hex(bytes.get(i + 1)), hex(bytes.get(i + 2))) {
+            (b'%', Some(high), Some(low)) => {
+                out.push((high * 16 + low) as u8);
+                i += 3;
+                continue;
+            }
+            (b'+', _, _) => out.push(b' '),
+            (b, _, _) => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn error_json(message: &str) -> String {
+    serde_json::to_string(&ErrorBody {
+        error: message.to_string(),
+    })
+    .unwrap_or_default()
+}
+
+fn respond(stream: &mut TcpStream, status: u16, body: &str) -> Result<()> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )?;
+    stream.flush()?;
+    Ok(())
+}
@@ -0,0 +1,187 @@
+use std::collections::HashMap;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+
+use super::inspect::{fit, format_bytes, open_inspector, print_runtime_summary, select_method};
+use super::output::{Output, write_json};
+use crate::analysis::shared::{self, SharedReport};
+use crate::config;
+use crate::probe::{RuntimeInfo, RuntimeKind};
+use crate::progress::Spinner;
+use crate::style::Stylize;
+
+/// Shared layers listed in text output; the JSON has all of them.
+const TOP_LAYERS: usize = 15;
+
+pub fn run(use_oci: bool, output: &Output, runtime: Option<String>, no_sudo: bool) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    let images = local_images(cfg)?;
+    if images.is_empty() {
+        bail!(t!("shared-no-images"));
+    }
+    print_runtime_summary(cfg, select_method(cfg, &images[0].0, use_oci));
+
+    // As with compare, layer digests and sizes are all we need
+    let mut infos = Vec::with_capacity(images.len());
+    for (image, _) in &images {
+        let spinner = Spinner::new(t!("spinner-resolving-image", image = image));
+        let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+        let info = inspector.inspect(image)?;
+        spinner.finish(t!(
+            "resolved-image",
+            image = image,
+            count = info.layers.len()
+        ));
+        infos.push(info);
+    }
+
+    let report = shared::analyze(&infos, images);
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "shared", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_shared(&report);
+            Ok(())
+        }
+    }
+}
+
+/// Tagged images in the default runtime, one entry per image ID: the first
+/// reference listed for it and any others.
+pub fn local_images(cfg: &config::AppConfig) -> Result<Vec<(String, Vec<String>)>> {
+    let Some(idx) = cfg.probe.default else {
+        bail!(t!("runtime-none-installed"));
+    };
+    let rt = &cfg.probe.runtimes[idx];
+    let args: &[&str] = match rt.kind {
+        // REF TYPE DIGEST SIZE PLATFORMS LABELS
+        RuntimeKind::Containerd => &["images", "ls"],
+        RuntimeKind::Docker | RuntimeKind::Podman => {
+            &["images", "--format", "{{.ID}} {{.Repository}}:{{.Tag}}"]
+        }
+    };
+    let stdout = runtime_output(rt, args)?;
+    let lines = stdout
+        .lines()
+        .skip(usize::from(rt.kind.matches(&RuntimeKind::Containerd)));
+    let mut images: Vec<(String, Vec<String>)> = Vec::new();
+    let mut by_id: HashMap<String, usize> = HashMap::new();
+    for line in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let (id, reference) = match (&rt.kind, fields.as_slice()) {
+            (RuntimeKind::Containerd, [reference, _, digest, ..]) => (*digest, *reference),
+            (_, [id, reference]) => (*id, *reference),
+            _ => continue,
+        };
+        // Dangling images have no name to inspect them by
+        if reference.contains("<none>") {
+            continue;
+        }
+        match by_id.get(id) {
+            Some(&i) => images[i].1.push(reference.to_string()),
+            None => {
+                by_id.insert(id.to_string(), images.len());
+                images.push((reference.to_string(), Vec::new()));
+            }
+        }
+    }
+    Ok(images)
+}
+
+/// Run the runtime's CLI and capture its stdout.
+pub fn runtime_output(rt: &RuntimeInfo, args: &[&str]) -> Result<String> {
+    let cmd = rt.binary_path.display();
+    tracing::debug!("running {cmd} {}", args.join(" "));
+    let out = Command::new(&rt.binary_path)
+        .args(args)
+        .output()
+        .with_context(|| format!("Failed to run '{cmd} {}'", args[0]))?;
+    if !out.status.success() {
+        bail!(
+            "'{cmd} {}' failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Per-image table, the largest shared layers and the disk total.
+pub fn print_shared(report: &SharedReport) {
+    let name_width = report
+        .images
+        .iter()
+        .map(|i| i.reference.len())
+        .max()
+        .unwrap_or(5)
+        .max(5);
+
+    println!(
+        "{:<name_width$}  {:>10}  {:>10}  {:>10}  {:>6}",
+        "IMAGE", "SIZE", "SHARED", "UNIQUE", "LAYERS"
+    );
+    for image in &report.images {
+        let aliases = if image.aliases.is_empty() {
+            String::new()
+        } else {
+            let aliases = fit(
+                &format!("  = {}", image.aliases.join(", ")),
+                name_width + 40,
+            );
+            aliases.dim().to_string()
+        };
+        println!(
+            "{:<name_width$}  {:>10}  {:>10}  {:>10}  {:>6}{}",
+            image.reference,
+            format_bytes(image.size),
+            format_bytes(image.shared_size),
+            format_bytes(image.unique_size),
+            image.layer_count,
+            aliases,
+        );
+    }
+
+    if !report.layers.is_empty() {
+        println!();
+        println!("{}", t!("shared-layers").dim());
+        for layer in report.layers.iter().take(TOP_LAYERS) {
+            let short = layer.digest.get(..19).unwrap_or(&layer.digest);
+            let users: Vec<&str> = layer
+                .images
+                .iter()
+                .map(|&i| report.images[i].reference.as_str())
+                .collect();
+            println!(
+                "  {:<19}  {:>10}  {:>3}  {}",
+                short,
+                format_bytes(layer.size),
+                layer.images.len(),
+                fit(&users.join(", "), 41)
+            );
+        }
+        if report.layers.len() > TOP_LAYERS {
+            println!(
+                "  {}",
+                t!("shared-more", count = report.layers.len() - TOP_LAYERS).dim()
+            );
+        }
+    }
+
+    println!();
+    println!(
+        "{}",
+        t!(
+            "shared-total",
+            disk = format_bytes(report.disk_size),
+            total = format_bytes(report.total_size),
+            saved = format_bytes(report.total_size.saturating_sub(report.disk_size))
+        )
+    );
+}
@@ -0,0 +1,84 @@
+use anyhow::{Result, bail};
+
+use super::inspect::{format_bytes, load_image, print_runtime_summary, select_method};
+use super::output::{Output, write_json};
+use crate::analysis::squash::{self, SquashEstimate};
+use crate::config;
+use crate::style::Stylize;
+
+pub fn run(
+    image: &str,
+    from: usize,
+    to: Option<usize>,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let info = load_image(cfg, image, use_oci, no_sudo)?;
+    // Layers are numbered from 1 on the command line, as inspect prints them
+    let to = to.unwrap_or(info.layers.len());
+    if !info.layers.is_empty() && (from == 0 || to == 0) {
+        bail!(t!("squash-bad-range", from = from, to = to, last = info.layers.len()));
+    }
+    let result = squash::simulate(&info, from.saturating_sub(1), to.saturating_sub(1))?;
+
+    // No web view: the summary is a handful of numbers.
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&result)?;
+            super::report::write_bundle(dir, "squash-sim", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &result),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_estimate(&result);
+            Ok(())
+        }
+    }
+}
+
+/// Before/after sizes for the squashed range and the whole image.
+pub fn print_estimate(e: &SquashEstimate) {
+    let saved = e.original_size.saturating_sub(e.squashed_size);
+    println!(
+        "{}",
+        t!(
+            "squash-range",
+            from = e.from + 1,
+            to = e.to + 1,
+            count = e.to - e.from + 1
+        )
+    );
+    println!(
+        "  {}",
+        t!(
+            "squash-layer-size",
+            before = format_bytes(e.original_size),
+            after = format_bytes(e.squashed_size),
+            files = e.file_count
+        )
+    );
+    println!(
+        "  {}",
+        t!(
+            "squash-image-size",
+            before = format_bytes(e.image_size),
+            after = format_bytes(e.squashed_image_size),
+            layers_before = e.layer_count,
+            layers_after = e.squashed_layer_count
+        )
+    );
+    if e.kept_whiteouts > 0 {
+        println!(
+            "  {}",
+            t!("squash-kept-whiteouts", count = e.kept_whiteouts)
+        );
+    }
+    println!();
+    println!("{}", t!("squash-saved", size = format_bytes(saved).bold()));
+}
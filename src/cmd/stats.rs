@@ -0,0 +1,103 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use super::inspect::{fit_start, format_bytes, load_image, print_runtime_summary, select_method};
+use super::output::{Output, write_json};
+use crate::analysis::stats::{self, ImageStats};
+use crate::config;
+use crate::style::Stylize;
+
+#[derive(Serialize)]
+pub struct StatsReport {
+    pub image: String,
+
+    #[serde(flatten)]
+    pub stats: ImageStats,
+}
+
+pub fn run(
+    image: &str,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let info = load_image(cfg, image, use_oci, no_sudo)?;
+    let report = StatsReport {
+        stats: stats::analyze(&info),
+        image: info.name,
+    };
+
+    // No web view: the summary is a handful of numbers.
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "stats", &json_str, None)
+        }
+        Output::Json(dest) => write_json(dest, &report),
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_stats(&report);
+            Ok(())
+        }
+    }
+}
+
+/// The numbers, then the largest top-level directories.
+pub fn print_stats(report: &StatsReport) {
+    let s = &report.stats;
+    println!("{}", report.image);
+    println!("  {}", t!("stats-layers", count = s.layers));
+    match s.compressed_size {
+        Some(compressed) => println!(
+            "  {}",
+            t!(
+                "stats-size-compressed",
+                size = format_bytes(s.total_size),
+                compressed = format_bytes(compressed)
+            )
+        ),
+        None => println!("  {}", t!("stats-size", size = format_bytes(s.total_size))),
+    }
+    println!(
+        "  {}",
+        t!("stats-files", files = s.files, directories = s.directories)
+    );
+    if let Some(largest) = &s.largest_file {
+        println!(
+            "  {}",
+            t!(
+                "stats-file-size",
+                median = format_bytes(s.median_file_size),
+                max = format_bytes(s.max_file_size),
+                path = format!("/{}", largest.display())
+            )
+        );
+    }
+    println!(
+        "  {}",
+        t!(
+            "stats-layer-files",
+            files = s.layer_files,
+            whiteouts = s.whiteouts
+        )
+    );
+
+    if s.top_directories.is_empty() {
+        return;
+    }
+    println!();
+    println!("{}", t!("stats-top-dirs").dim());
+    for dir in &s.top_directories {
+        println!(
+            "  {:>10}  {:>7}  /{}",
+            format_bytes(dir.size),
+            dir.files,
+            fit_start(&dir.path.to_string_lossy(), 24).cyan()
+        );
+    }
+}
@@ -0,0 +1,143 @@
+use anyhow::{Context, Result, bail};
+use sha2::{Digest, Sha256};
+
+use super::inspect::{looks_like_archive, open_inspector, print_runtime_summary, select_method};
+use super::output::{Output, write_json};
+use super::pull_size::{host_platform, platform_entry};
+use crate::analysis::drift::{self, DriftReport, DriftStatus, RemoteImage};
+use crate::config;
+use crate::progress::Spinner;
+use crate::registry::client::Manifest;
+use crate::registry::trace::Trace;
+use crate::registry::{Client, Reference};
+use crate::style::Stylize;
+
+/// `platform` picks from a multi-platform index; it defaults to the local
+/// image's architecture.
+pub fn run(
+    image: &str,
+    platform: Option<&str>,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+    trace: Option<Trace>,
+) -> Result<()> {
+    if looks_like_archive(image) {
+        bail!(t!("verify-archive"));
+    }
+    let reference = Reference::parse(image)?;
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+
+    let spinner = Spinner::new(t!("spinner-resolving-image", image = image));
+    let mut inspector = open_inspector(cfg, image, use_oci, no_sudo, &spinner)?;
+    let local = inspector.inspect_metadata(image)?;
+    spinner.finish(t!(
+        "resolved-image",
+        image = image,
+        count = local.layers.len()
+    ));
+
+    let spinner = Spinner::new(t!("verify-spinner-remote", reference = &reference));
+    let mut client = Client::new(trace);
+    let (_, body) = client.manifest_raw(&reference)?;
+    let digest = format!("sha256:{:x}", Sha256::digest(&body));
+    let manifest: Manifest = serde_json::from_slice(&body)
+        .with_context(|| t!("registry-bad-manifest", reference = &reference))?;
+    let (manifest, manifest_digest, platform) = if manifest.is_index() {
+        let wanted = match (platform, &local.architecture) {
+            (Some(platform), _) => platform.to_string(),
+            (None, Some(arch)) => format!("linux/{arch}"),
+            (None, None) => host_platform(),
+        };
+        let child = platform_entry(&manifest, &wanted)?;
+        let child_ref = Reference {
+            reference: child.digest.clone(),
+            ..reference.clone()
+        };
+        (
+            client.manifest(&child_ref)?,
+            child.digest.clone(),
+            Some(wanted),
+        )
+    } else {
+        (manifest, digest.clone(), None)
+    };
+    let id = manifest
+        .config
+        .with_context(|| t!("registry-bad-manifest", reference = &reference))?
+        .digest;
+    spinner.finish(t!("verify-resolved-remote", reference = &reference));
+
+    let report = drift::compare(
+        &local,
+        RemoteImage {
+            reference: reference.to_string(),
+            platform,
+            digest,
+            manifest_digest,
+            id,
+        },
+    );
+
+    match output {
+        Output::Bundle(dir) => {
+            let json_str = serde_json::to_string_pretty(&report)?;
+            super::report::write_bundle(dir, "verify", &json_str, None)?;
+        }
+        Output::Json(dest) => write_json(dest, &report)?,
+        Output::Web | Output::Text | Output::Github | Output::Junit(_) | Output::Csv(_) => {
+            print_drift(&report)
+        }
+    }
+
+    // After the report, so scripts get both the details and the exit code
+    match report.status {
+        DriftStatus::Current => Ok(()),
+        DriftStatus::Stale => bail!(t!("verify-stale", reference = &report.remote.reference)),
+        DriftStatus::Diverged => {
+            bail!(t!("verify-diverged", reference = &report.remote.reference))
+        }
+    }
+}
+
+/// Local digests, the registry's, then the verdict.
+pub fn print_drift(report: &DriftReport) {
+    println!("{}", report.image);
+    if let Some(id) = &report.local_id {
+        println!("  {}", t!("inspect-id", id = id));
+    }
+    if !report.local_digests.is_empty() {
+        println!(
+            "  {}",
+            t!("inspect-digests", digests = report.local_digests.join(", "))
+        );
+    }
+    println!();
+
+    let remote = &report.remote;
+    println!("{}", remote.reference);
+    if let Some(platform) = &remote.platform {
+        println!("  {}", t!("pull-platform", platform = platform).dim());
+    }
+    println!("  {}", t!("inspect-id", id = &remote.id));
+    println!("  {}", t!("inspect-digests", digests = &remote.digest));
+    if remote.manifest_digest != remote.digest {
+        println!(
+            "  {}",
+            t!("verify-manifest-digest", digest = &remote.manifest_digest)
+        );
+    }
+    println!();
+
+    match report.status {
+        DriftStatus::Current => println!("{} {}", "✔".green(), t!("verify-current")),
+        DriftStatus::Stale => println!("{} {}", "!".yellow().bold(), t!("verify-status-stale")),
+        DriftStatus::Diverged => {
+            println!("{} {}", "✘".red(), t!("verify-status-diverged"))
+        }
+    }
+}
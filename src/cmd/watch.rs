@@ -0,0 +1,225 @@
+use std::collections::HashSet;
+use std::fs;
+use std::thread;
+use std::time::{Duration, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow, bail};
+use serde::Serialize;
+
+use super::inspect::{
+    fit, format_bytes, load_image, looks_like_archive, print_runtime_summary, select_method,
+};
+use super::output::{Output, write_json_line};
+use super::shared::runtime_output;
+use crate::analysis::waste;
+use crate::config::{self, AppConfig};
+use crate::inspector::ImageInfo;
+use crate::probe::RuntimeKind;
+use crate::registry::Reference;
+use crate::style::Stylize;
+
+/// One inspection of a new build.
+#[derive(Serialize)]
+pub struct WatchRun {
+    pub image: String,
+
+    /// Runtime image ID; for a tar archive, its size and modification time
+    pub id: String,
+
+    pub total_size: u64,
+    pub layer_count: usize,
+
+    /// Percent
+    pub efficiency: f64,
+
+    /// Bytes gained (or lost, if negative) since the previous build; absent
+    /// for the first
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delta: Option<i64>,
+
+    /// Layers the previous build didn't have; empty for the first
+    pub new_layers: Vec<NewLayer>,
+}
+
+#[derive(Serialize)]
+pub struct NewLayer {
+    /// Base = 0
+    pub layer: usize,
+    pub digest: String,
+    pub size: u64,
+    pub created_by: Option<String>,
+}
+
+/// Inspect `image`, then again whenever its ID changes, printing how the
+/// size moved since the previous build. Runs until interrupted.
+pub fn run(
+    image: &str,
+    interval: u64,
+    use_oci: bool,
+    output: &Output,
+    runtime: Option<String>,
+    no_sudo: bool,
+) -> Result<()> {
+    config::init_from_cli(output.is_json(), runtime)?;
+    let cfg = config::get();
+
+    print_runtime_summary(cfg, select_method(cfg, image, use_oci));
+    eprintln!(
+        "{}",
+        t!("watch-started", image = image, seconds = interval).dim()
+    );
+
+    let mut seen: Option<String> = None;
+    let mut previous: Option<ImageInfo> = None;
+    loop {
+        match image_id(cfg, image) {
+            Ok(id) if seen.as_ref() != Some(&id) => {
+                seen = Some(id.clone());
+                match load_image(cfg, image, use_oci, no_sudo) {
+                    Ok(info) => {
+                        let run = compare(id, &info, previous.as_ref());
+                        report(&run, output)?;
+                        previous = Some(info);
+                    }
+                    // A build may replace the image while it's read; the
+                    // next ID change tries again
+                    Err(err) if previous.is_some() => warn(&err),
+                    Err(err) => return Err(err),
+                }
+            }
+            Ok(_) => {}
+            // The tag can be missing for a moment while a build replaces it
+            Err(err) if seen.is_none() => return Err(err),
+            Err(_) => {}
+        }
+        thread::sleep(Duration::from_secs(interval));
+    }
+}
+
+/// What identifies this build of `image`: the runtime's image ID, or a tar
+/// archive's size and modification time.
+fn image_id(cfg: &AppConfig, image: &str) -> Result<String> {
+    if looks_like_archive(image) {
+        let meta = fs::metadata(image).with_context(|| t!("watch-image-missing", image = image))?;
+        let modified = meta
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        return Ok(format!("{}:{}", meta.len(), modified.as_nanos()));
+    }
+
+    let Some(idx) = cfg.probe.default else {
+        bail!(t!("runtime-none-installed"));
+    };
+    let rt = &cfg.probe.runtimes[idx];
+    if rt.kind.matches(&RuntimeKind::Containerd) {
+        // REF TYPE DIGEST SIZE PLATFORMS LABELS, with REF fully qualified:
+        // "nginx" is listed as "docker.io/library/nginx:latest"
+        let wanted = Reference::parse(image).ok();
+        let out = runtime_output(rt, &["images", "ls"])?;
+        return out
+            .lines()
+            .skip(1)
+            .find_map(|line| {
+                let fields: Vec<&str> = line.split_whitespace().collect();
+                let name = *fields.first()?;
+                let same = match &wanted {
+                    Some(wanted) => Reference::parse(name).is_ok_and(|r| r == *wanted),
+                    None => name == image,
+                };
+                same.then(|| fields.get(2).map(|d| d.to_string())).flatten()
+            })
+            .ok_or_else(|| anyhow!(t!("watch-image-missing", image = image)));
+    }
+    let out = runtime_output(rt, &["image", "inspect", "--format", "{{.Id}}", image])?;
+    Ok(out.trim().to_string())
+}
+
+/// Measure `info` against the previous build.
+fn compare(id: String, info: &ImageInfo, previous: Option<&ImageInfo>) -> WatchRun {
+    let known: HashSet<&str> = previous
+        .map(|p| p.layers.iter().map(|l| l.digest.as_str()).collect())
+        .unwrap_or_default();
+    let new_layers = match previous {
+        Some(_) => info
+            .layers
+            .iter()
+            .enumerate()
+            .filter(|(_, l)| !known.contains(l.digest.as_str()))
+            .map(|(layer, l)| NewLayer {
+                layer,
+                digest: l.digest.clone(),
+                size: l.size,
+                created_by: l.created_by.clone(),
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    WatchRun {
+        image: info.name.clone(),
+        id,
+        total_size: info.total_size,
+        layer_count: info.layers.len(),
+        efficiency: waste::analyze(info).efficiency * 100.0,
+        delta: previous.map(|p| info.total_size as i64 - p.total_size as i64),
+        new_layers,
+    }
+}
+
+/// A line of JSON per build with `--json`, a short summary otherwise.
+fn report(run: &WatchRun, output: &Output) -> Result<()> {
+    match output {
+        Output::Json(dest) => write_json_line(dest, run),
+        _ => {
+            print_run(run);
+            Ok(())
+        }
+    }
+}
+
+/// Size, layers and efficiency, the change since the previous build, and the
+/// layers it added.
+pub fn print_run(run: &WatchRun) {
+    println!(
+        "{}  {}",
+        run.image.as_str().bold(),
+        t!(
+            "watch-run",
+            size = format_bytes(run.total_size),
+            layers = run.layer_count,
+            efficiency = format!("{:.1}%", run.efficiency)
+        )
+    );
+    match run.delta {
+        Some(0) => println!("  {}", t!("watch-same-size").dim()),
+        Some(delta) => {
+            let change = t!("watch-delta", delta = signed_bytes(delta));
+            let change = if delta > 0 {
+                change.red()
+            } else {
+                change.green()
+            };
+            println!("  {change}");
+        }
+        None => {}
+    }
+    for layer in &run.new_layers {
+        let instruction = layer.created_by.as_deref().unwrap_or("");
+        let line = t!(
+            "watch-new-layer",
+            layer = layer.layer + 1,
+            size = format_bytes(layer.size)
+        );
+        println!("  {line}  {}", fit(instruction, line.len() + 4).dim());
+    }
+    println!();
+}
+
+fn signed_bytes(delta: i64) -> String {
+    let sign = if delta < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_bytes(delta.unsigned_abs()))
+}
+
+fn warn(err: &anyhow::Error) {
+    tracing::warn!("{}", t!("watch-load-failed", error = format!("{err:#}")));
+}
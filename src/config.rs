@@ -1,10 +1,19 @@
+use std::io::{self, IsTerminal};
 use std::sync::OnceLock;
 
 use anyhow::Result;
 
+use crate::cmd::layer_table::Column;
+use crate::inspector::Listing;
 use crate::probe::{self, ProbeResult, RuntimeKind};
+use crate::query::Query;
 
 static CONFIG: OnceLock<AppConfig> = OnceLock::new();
+static WIDTH: OnceLock<Option<usize>> = OnceLock::new();
+static QUERY: OnceLock<Query> = OnceLock::new();
+static BACKEND: OnceLock<&'static str> = OnceLock::new();
+static COLUMNS: OnceLock<Vec<Column>> = OnceLock::new();
+static LISTING: OnceLock<Listing> = OnceLock::new();
 
 #[derive(Debug)]
 pub struct AppConfig {
@@ -17,26 +26,17 @@ pub struct AppConfig {
 
 /// Probe runtimes and initialize the global config.
 pub fn init_from_cli(json: bool, runtime_override: Option<String>) -> Result<()> {
-    let mut probe_result = probe::probe()?;
+    let mut probe_result = tracing::debug_span!("probe").in_scope(probe::probe)?;
 
     if let Some(ref name) = runtime_override {
-        let kind = RuntimeKind::from_name(name).ok_or_else(|| {
-            anyhow::anyhow!(
-                "Unknown runtime '{}'. Valid options: docker, podman, containerd",
-                name
-            )
-        })?;
+        let kind = RuntimeKind::from_name(name)
+            .ok_or_else(|| anyhow::anyhow!(t!("runtime-unknown", name = name)))?;
 
         let idx = probe_result
             .runtimes
             .iter()
             .position(|rt| rt.kind.matches(&kind))
-            .ok_or_else(|| {
-                anyhow::anyhow!(
-                    "Runtime '{}' was not detected on this system. Run `peel probe` to see available runtimes.",
-                    name
-                )
-            })?;
+            .ok_or_else(|| anyhow::anyhow!(t!("runtime-not-detected", name = name)))?;
 
         probe_result.default = Some(idx);
     }
@@ -54,3 +54,71 @@ pub fn init_from_cli(json: bool, runtime_override: Option<String>) -> Result<()>
 pub fn get() -> &'static AppConfig {
     CONFIG.get().expect("config not initialized — call config::init_from_cli() first")
 }
+
+/// Force the width text output is fitted to (`--width`). Call before printing.
+pub fn set_width(width: Option<usize>) {
+    if width.is_some() {
+        let _ = WIDTH.set(width);
+    }
+}
+
+/// Filter JSON output through `--query`. Call before writing any.
+pub fn set_query(query: Option<Query>) {
+    if let Some(query) = query {
+        let _ = QUERY.set(query);
+    }
+}
+
+/// The `--query` expression JSON output goes through, if one was given.
+pub fn query() -> Option<&'static Query> {
+    QUERY.get()
+}
+
+/// Record how images are read, for the JSON envelope. The first image's
+/// backend is kept when a command reads several.
+pub fn set_backend(backend: &'static str) {
+    let _ = BACKEND.set(backend);
+}
+
+/// How images were read (`overlay2`, `oci`, `archive`, `registry`), once
+/// one has been.
+pub fn backend() -> Option<&'static str> {
+    BACKEND.get().copied()
+}
+
+/// Set what file listings include (`--root`, `--checksums`). Call before
+/// opening an inspector.
+pub fn set_listing(listing: Listing) {
+    let _ = LISTING.set(listing);
+}
+
+/// What inspectors list; everything, without checksums, unless set.
+pub fn listing() -> &'static Listing {
+    LISTING.get_or_init(Listing::default)
+}
+
+/// Pick the layer table's columns (`--columns`). Call before printing.
+pub fn set_columns(columns: Vec<Column>) {
+    if !columns.is_empty() {
+        let _ = COLUMNS.set(columns);
+    }
+}
+
+/// The layer table's columns, if `--columns` chose them.
+pub fn columns() -> Option<&'static [Column]> {
+    COLUMNS.get().map(Vec::as_slice)
+}
+
+/// Columns to fit text output into: `--width`, else the terminal's if stdout
+/// is one. `None` means output is captured and lines are left whole.
+pub fn width() -> Option<usize> {
+    *WIDTH.get_or_init(|| io::stdout().is_terminal().then(terminal_width).flatten())
+}
+
+/// Columns of the terminal progress is drawn on (`--width` wins), if known.
+pub fn terminal_width() -> Option<usize> {
+    match WIDTH.get() {
+        Some(Some(width)) => Some(*width),
+        _ => crossterm::terminal::size().ok().map(|(w, _)| w as usize),
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::BTreeMap;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::registry::reference::Reference;
+
+/// Which cluster and namespace to check, from `--kubecontext`/`--namespace`.
+pub struct Target<'a> {
+    /// kubectl context; the current one if `None`
+    pub context: Option<&'a str>,
+
+    /// Namespace; all namespaces if `None`
+    pub namespace: Option<&'a str>,
+}
+
+/// Where the inspected image's repository is running in a cluster.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClusterCheck {
+    /// kubectl context checked, if not the current one
+    pub context: Option<String>,
+
+    /// Namespace checked, if not all of them
+    pub namespace: Option<String>,
+
+    /// Digests the inspected image is known by
+    pub digests: Vec<String>,
+
+    /// Containers running the same repository or one of `digests`, grouped
+    /// by workload
+    pub workloads: Vec<Workload>,
+}
+
+/// Containers of one workload running the inspected repository at one digest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workload {
+    pub namespace: String,
+
+    /// Owner, e.g. `Deployment/web`, or `Pod/<name>` for bare pods
+    pub name: String,
+
+    pub container: String,
+
+    /// Image reference from the pod spec
+    pub image: String,
+
+    /// Digest the pods actually run, once pulled
+    pub digest: Option<String>,
+
+    pub status: Status,
+
+    pub pods: usize,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    /// Running the inspected image
+    Match,
+
+    /// Running another build of the same repository
+    Mismatch,
+
+    /// Can't tell: the digests peel knows aren't the kind the node reports
+    Unknown,
+}
+
+// ---- kubectl get pods -o json ----
+
+#[derive(Deserialize)]
+struct PodList {
+    items: Vec<Pod>,
+}
+
+#[derive(Deserialize)]
+struct Pod {
+    metadata: Metadata,
+    #[serde(default)]
+    status: PodStatus,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Metadata {
+    name: String,
+    #[serde(default)]
+    namespace: String,
+    #[serde(default)]
+    labels: BTreeMap<String, String>,
+    #[serde(default)]
+    owner_references: Vec<OwnerReference>,
+}
+
+#[derive(Deserialize)]
+struct OwnerReference {
+    kind: String,
+    name: String,
+}
+
+#[derive(Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct PodStatus {
+    #[serde(default)]
+    container_statuses: Vec<ContainerStatus>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ContainerStatus {
+    name: String,
+    image: String,
+    #[serde(default, rename = "imageID")]
+    image_id: String,
+}
+
+/// Ask kubectl which containers in `target` run the repository of `image` (or
+/// one of `digests`), and compare what they run against `digests`, which
+/// holds the config digest (image ID) first, then any registry digests.
+/// Containers are matched by digest alone when `image` isn't a valid
+/// reference, such as an archive's file name.
+pub fn check(target: &Target, image: &str, digests: Vec<String>) -> Result<ClusterCheck> {
+    let wanted = Reference::parse(image).ok();
+
+    let mut cmd = Command::new("kubectl");
+    if let Some(context) = target.context {
+        cmd.args(["--context", context]);
+    }
+    cmd.args(["get", "pods", "-o", "json"]);
+    match target.namespace {
+        Some(namespace) => cmd.args(["--namespace", namespace]),
+        None => cmd.arg("--all-namespaces"),
+    };
+    tracing::debug!("running {cmd:?}");
+    let output = cmd.output().context(t!("kube-run-failed"))?;
+    if !output.status.success() {
+        bail!(t!(
+            "kube-failed",
+            error = String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    let pods: PodList = serde_json::from_slice(&output.stdout).context(t!("kube-parse-failed"))?;
+
+    // Registry digests are what nodes normally report; without one, only an
+    // image ID match means anything
+    let has_registry_digest = digests.len() > 1;
+    let mut groups: BTreeMap<(String, String, String, Option<String>), Workload> = BTreeMap::new();
+    for pod in &pods.items {
+        for container in &pod.status.container_statuses {
+            let digest = image_digest(&container.image_id);
+            let same_repo = wanted.as_ref().is_some_and(|wanted| {
+                Reference::parse(&container.image)
+                    .is_ok_and(|r| r.registry == wanted.registry && r.repository == wanted.repository)
+            });
+            // A digest match counts even under another name, e.g. a re-tag
+            // or an archive whose name peel only knows from the file name
+            if !same_repo && !digest.as_ref().is_some_and(|d| digests.contains(d)) {
+                continue;
+            }
+
+            let status = match &digest {
+                Some(d) if digests.contains(d) => Status::Match,
+                Some(d) if has_registry_digest || container.image_id == *d => Status::Mismatch,
+                _ => Status::Unknown,
+            };
+            let name = owner(&pod.metadata);
+            let key = (
+                pod.metadata.namespace.clone(),
+                name.clone(),
+                container.name.clone(),
+                digest.clone(),
+            );
+            groups
+                .entry(key)
+                .or_insert_with(|| Workload {
+                    namespace: pod.metadata.namespace.clone(),
+                    name,
+                    container: container.name.clone(),
+                    image: container.image.clone(),
+                    digest,
+                    status,
+                    pods: 0,
+                })
+                .pods += 1;
+        }
+    }
+
+    Ok(ClusterCheck {
+        context: target.context.map(String::from),
+        namespace: target.namespace.map(String::from),
+        digests,
+        workloads: groups.into_values().collect(),
+    })
+}
+
+/// Registry digests the local runtime recorded for `image` when it was
+/// pulled or pushed. Best effort: empty if the runtime can't tell.
+pub fn repo_digests(runtime: &str, image: &str) -> Vec<String> {
+    tracing::debug!("running {runtime} image inspect {image}");
+    let Ok(output) = Command::new(runtime)
+        .args([
+            "image",
+            "inspect",
+            image,
+            "--format",
+            "{{json .RepoDigests}}",
+        ])
+        .output()
+    else {
+        return Vec::new();
+    };
+    let digests: Vec<String> = serde_json::from_slice(&output.stdout).unwrap_or_default();
+    digests.iter().filter_map(|d| image_digest(d)).collect()
+}
+
+/// `sha256:…` out of `repo@sha256:…`, `docker-pullable://repo@sha256:…` or a
+/// bare image ID.
+fn image_digest(id: &str) -> Option<String> {
+    let digest = id.rsplit_once('@').map_or(id, |(_, d)| d);
+    digest.starts_with("sha256:").then(|| digest.to_string())
+}
+
+/// `Kind/name` of what manages the pod. Deployments are found through the
+/// ReplicaSet name, which is the Deployment's plus the pod template hash.
+fn owner(meta: &Metadata) -> String {
+    let Some(owner) = meta.owner_references.first() else {
+        return format!("Pod/{}", meta.name);
+    };
+    if owner.kind == "ReplicaSet"
+        && let Some(hash) = meta.labels.get("pod-template-hash")
+        && let Some(deployment) = owner.name.strip_suffix(&format!("-{hash}"))
+    {
+        return format!("Deployment/{deployment}");
+    }
+    format!("{}/{}", owner.kind, owner.name)
+}
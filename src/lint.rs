@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+
+use clap::ValueEnum;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::analysis::junk::{self, JunkKind};
+use crate::analysis::{merged, posture};
+use crate::inspector::ImageInfo;
+
+/// Layers a single RUN makes bigger than this are flagged, unless the
+/// config file sets `max_size`.
+const LARGE_LAYER: u64 = 500 * 1024 * 1024;
+
+/// Label BuildKit and other builders set to the image the build started from.
+const BASE_NAME_LABEL: &str = "org.opencontainers.image.base.name";
+
+/// Archive extensions `ADD` unpacks, which `COPY` can't.
+const ARCHIVES: &[&str] = &[
+    ".tar", ".tar.gz", ".tgz", ".tar.bz2", ".tbz2", ".tar.xz", ".txz", ".tar.zst",
+];
+
+/// How much a finding matters. `peel lint --fail-on` exits with an error
+/// when a finding is at least this severe.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, ValueEnum,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleId {
+    /// A single RUN made a layer bigger than 500 MiB (or `max_size`)
+    LargeLayer,
+    /// ADD copying local files, which COPY does more predictably
+    AddInsteadOfCopy,
+    /// The base image is referenced by `:latest` or no tag
+    BaseLatest,
+    /// A package manager's cache or index left in the layer that filled it
+    PackageCache,
+    /// No USER, or USER root
+    RunsAsRoot,
+}
+
+struct Rule {
+    id: RuleId,
+    severity: Severity,
+    check: fn(&Context, &RuleConfig) -> Vec<Detail>,
+}
+
+/// Every rule, with its default severity.
+const RULES: &[Rule] = &[
+    Rule {
+        id: RuleId::LargeLayer,
+        severity: Severity::Warning,
+        check: large_layer,
+    },
+    Rule {
+        id: RuleId::AddInsteadOfCopy,
+        severity: Severity::Info,
+        check: add_instead_of_copy,
+    },
+    Rule {
+        id: RuleId::BaseLatest,
+        severity: Severity::Warning,
+        check: base_latest,
+    },
+    Rule {
+        id: RuleId::PackageCache,
+        severity: Severity::Warning,
+        check: package_cache,
+    },
+    Rule {
+        id: RuleId::RunsAsRoot,
+        severity: Severity::Warning,
+        check: runs_as_root,
+    },
+];
+
+/// What the rules look at.
+pub struct Context<'a> {
+    pub info: &'a ImageInfo,
+
+    /// Raw image config, when the backend has it
+    pub config: Option<&'a [u8]>,
+
+    /// Base image reference given on the command line; the config's base
+    /// name label is used otherwise
+    pub base: Option<&'a str>,
+}
+
+/// Settings for one built-in rule, from the config file and `--rule`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleConfig {
+    /// `false` turns the rule off
+    pub enabled: Option<bool>,
+
+    /// Replaces the rule's default severity
+    pub severity: Option<Severity>,
+
+    /// Size limit in bytes, for rules that have one (`large-layer`)
+    pub max_size: Option<u64>,
+}
+
+/// A team's own rule: files in the final filesystem whose path matches a
+/// glob, optionally only from a size up or only when they add up to more
+/// than a limit.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomRule {
+    /// Shown and reported like a built-in rule's id
+    pub id: String,
+
+    #[serde(default = "CustomRule::default_severity")]
+    pub severity: Severity,
+
+    /// Glob as `peel find` takes it, e.g. `**/*.pem` or `root/.cache/**`
+    pub path: String,
+
+    /// Only files at least this big match
+    #[serde(default)]
+    pub min_size: u64,
+
+    /// Report once when the matching files total more than this, instead
+    /// of every matching file
+    pub max_total: Option<u64>,
+
+    /// Explanation printed with each finding
+    pub message: Option<String>,
+
+    /// `path` compiled; set when the config file is loaded
+    #[serde(skip)]
+    pub pattern: Option<Regex>,
+}
+
+impl CustomRule {
+    fn default_severity() -> Severity {
+        Severity::Warning
+    }
+}
+
+/// Which rules run and how, from the config file with `--rule` applied.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+    #[serde(default)]
+    pub rules: HashMap<RuleId, RuleConfig>,
+
+    #[serde(default)]
+    pub custom: Vec<CustomRule>,
+}
+
+impl Policy {
+    /// Apply `--rule` flags, which win over the config file; `None` turns
+    /// a rule off.
+    pub fn override_severities(&mut self, flags: &[(RuleId, Option<Severity>)]) {
+        for (id, severity) in flags {
+            let rule = self.rules.entry(*id).or_default();
+            rule.enabled = Some(severity.is_some());
+            if severity.is_some() {
+                rule.severity = *severity;
+            }
+        }
+    }
+}
+
+/// Whether a rule's config takes `max_size`.
+pub fn has_threshold(id: RuleId) -> bool {
+    id == RuleId::LargeLayer
+}
+
+/// A built-in rule, or a custom one by its id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RuleName {
+    Builtin(RuleId),
+    Custom(String),
+}
+
+/// One rule violation.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    pub rule: RuleName,
+    pub severity: Severity,
+
+    #[serde(flatten)]
+    pub detail: Detail,
+}
+
+/// What a finding is about; which fields are set depends on the rule.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Detail {
+    /// Layer it's about (base = 0)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<usize>,
+
+    /// Bytes involved: the layer's size, the cache's share of it, or the
+    /// matching files' size
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+
+    /// The instruction, base image reference, cache directory, user, or the
+    /// file or glob a custom rule matched
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+
+    /// Which cache, for `package-cache`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub junk: Option<JunkKind>,
+
+    /// A custom rule's message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ImageConfig {
+    #[serde(default)]
+    config: Option<RunConfig>,
+}
+
+#[derive(Deserialize)]
+struct RunConfig {
+    #[serde(rename = "Labels", default)]
+    labels: Option<HashMap<String, String>>,
+}
+
+/// The rules `run` checks with `policy`, in the order it checks them.
+pub fn enabled(policy: &Policy) -> Vec<RuleName> {
+    let builtin = RULES
+        .iter()
+        .filter(|r| policy.rules.get(&r.id).and_then(|c| c.enabled) != Some(false))
+        .map(|r| RuleName::Builtin(r.id));
+    let custom = policy
+        .custom
+        .iter()
+        .map(|r| RuleName::Custom(r.id.clone()));
+    builtin.chain(custom).collect()
+}
+
+/// Run the built-in rules `policy` leaves on, then its custom rules.
+/// Findings come in rule order, then layer order.
+pub fn run(ctx: &Context, policy: &Policy) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for rule in RULES {
+        let config = policy.rules.get(&rule.id).cloned().unwrap_or_default();
+        if config.enabled == Some(false) {
+            continue;
+        }
+        let severity = config.severity.unwrap_or(rule.severity);
+        findings.extend(
+            (rule.check)(ctx, &config)
+                .into_iter()
+                .map(|detail| Finding {
+                    rule: RuleName::Builtin(rule.id),
+                    severity,
+                    detail,
+                }),
+        );
+    }
+
+    let visible = merged::merge(ctx.info);
+    for rule in &policy.custom {
+        let Some(pattern) = &rule.pattern else {
+            continue;
+        };
+        let matching: Vec<&merged::MergedFile> = visible
+            .values()
+            .filter(|f| f.size >= rule.min_size && pattern.is_match(&f.path.to_string_lossy()))
+            .collect();
+        let details = match rule.max_total {
+            Some(limit) => {
+                let total: u64 = matching.iter().map(|f| f.size).sum();
+                if total > limit {
+                    vec![Detail {
+                        size: Some(total),
+                        subject: Some(rule.path.clone()),
+                        message: rule.message.clone(),
+                        ..Default::default()
+                    }]
+                } else {
+                    Vec::new()
+                }
+            }
+            None => matching
+                .iter()
+                .map(|f| Detail {
+                    layer: Some(f.layer),
+                    size: Some(f.size),
+                    subject: Some(format!("/{}", f.path.display())),
+                    message: rule.message.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+        };
+        findings.extend(details.into_iter().map(|detail| Finding {
+            rule: RuleName::Custom(rule.id.clone()),
+            severity: rule.severity,
+            detail,
+        }));
+    }
+    findings
+}
+
+fn large_layer(ctx: &Context, config: &RuleConfig) -> Vec<Detail> {
+    let limit = config.max_size.unwrap_or(LARGE_LAYER);
+    ctx.info
+        .layers
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| l.size > limit)
+        .filter_map(|(index, l)| {
+            let run = l.instruction.as_ref().filter(|i| i.instruction == "RUN")?;
+            Some(Detail {
+                layer: Some(index),
+                size: Some(l.size),
+                subject: Some(run.to_string()),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// `ADD` is only needed for URLs and archives it should unpack. A base
+/// image's root filesystem (`ADD file:<hash> in /`) is such an archive.
+fn add_instead_of_copy(ctx: &Context, _: &RuleConfig) -> Vec<Detail> {
+    let mut found = Vec::new();
+    for (index, layer) in ctx.info.layers.iter().enumerate() {
+        let Some(add) = layer.instruction.as_ref().filter(|i| i.instruction == "ADD") else {
+            continue;
+        };
+        let words: Vec<&str> = add
+            .arguments
+            .split_whitespace()
+            .filter(|w| !w.starts_with("--"))
+            .collect();
+        // The last word is the destination
+        let sources = &words[..words.len().saturating_sub(1)];
+        let needs_add = sources.iter().any(|s| {
+            s.contains("://")
+                || s.starts_with("file:")
+                || ARCHIVES.iter().any(|ext| s.ends_with(ext))
+        });
+        if !sources.is_empty() && !needs_add {
+            found.push(Detail {
+                layer: Some(index),
+                subject: Some(add.to_string()),
+                ..Default::default()
+            });
+        }
+    }
+    found
+}
+
+fn base_latest(ctx: &Context, _: &RuleConfig) -> Vec<Detail> {
+    let label = ctx
+        .config
+        .and_then(|c| serde_json::from_slice::<ImageConfig>(c).ok())
+        .and_then(|c| c.config?.labels?.remove(BASE_NAME_LABEL));
+    let Some(base) = ctx.base.map(str::to_string).or(label) else {
+        return Vec::new();
+    };
+    // A digest pins the image whatever the tag says
+    if base.contains('@') {
+        return Vec::new();
+    }
+    let name = base.rsplit('/').next().unwrap_or(&base);
+    let floating = name.split_once(':').is_none_or(|(_, tag)| tag == "latest");
+    if !floating {
+        return Vec::new();
+    }
+    vec![Detail {
+        subject: Some(base),
+        ..Default::default()
+    }]
+}
+
+fn package_cache(ctx: &Context, _: &RuleConfig) -> Vec<Detail> {
+    junk::detect(ctx.info)
+        .into_iter()
+        .filter(|j| {
+            matches!(
+                j.kind,
+                JunkKind::AptLists
+                    | JunkKind::AptCache
+                    | JunkKind::PipCache
+                    | JunkKind::NpmCache
+                    | JunkKind::YarnCache
+            )
+        })
+        .flat_map(|j| {
+            let path = j.paths.first().map(|p| format!("/{}", p.display()));
+            j.layers.into_iter().map(move |l| Detail {
+                layer: Some(l.layer),
+                size: Some(l.size),
+                subject: path.clone(),
+                junk: Some(j.kind),
+                message: None,
+            })
+        })
+        .collect()
+}
+
+fn runs_as_root(ctx: &Context, _: &RuleConfig) -> Vec<Detail> {
+    let posture = posture::analyze(ctx.info, ctx.config);
+    if posture.runs_as_root != Some(true) {
+        return Vec::new();
+    }
+    vec![Detail {
+        subject: posture.user,
+        ..Default::default()
+    }]
+}
@@ -0,0 +1,179 @@
+use std::env;
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use tracing::level_filters::LevelFilter;
+use tracing::{Event, Level, Subscriber};
+use tracing_subscriber::filter::filter_fn;
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::time::Uptime;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+use crate::style::{self, Stylize};
+use crate::timings;
+
+static LEVEL: OnceLock<LogLevel> = OnceLock::new();
+
+/// What peel writes to stderr besides its output, picked with `--log-level`
+/// or `-v`/`-q`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum LogLevel {
+    /// Errors only
+    Error,
+    /// Warnings, without spinners or `✔` lines
+    Warn,
+    /// Progress and warnings (the default)
+    Info,
+    /// Also the backend picked, each command run and how long steps took
+    Debug,
+    /// Also each file and request
+    Trace,
+}
+
+impl LogLevel {
+    /// `--log-level` if given, else `info` moved up a level per `-v`, or
+    /// down to `warn` with `-q`.
+    pub fn from_cli(log_level: Option<LogLevel>, verbose: u8, quiet: bool) -> LogLevel {
+        match (log_level, verbose, quiet) {
+            (Some(level), _, _) => level,
+            (None, _, true) => LogLevel::Warn,
+            (None, 0, false) => LogLevel::Info,
+            (None, 1, false) => LogLevel::Debug,
+            (None, _, false) => LogLevel::Trace,
+        }
+    }
+
+    /// The name `--log-level` takes, e.g. for `PEEL_LOG`.
+    pub fn name(self) -> &'static str {
+        match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+}
+
+/// How `--log-file` lines are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum LogFormat {
+    /// One line per event, as `-v` shows them
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Send log events at `level` and above to stderr, and with `file`, events
+/// at `debug` or `level`, whichever says more, to that file. Debug lines on
+/// stderr carry the time since start and where they came from, to see where
+/// time goes; otherwise warnings look like the rest of peel's messages.
+pub fn init(level: LogLevel, file: Option<&Path>, format: LogFormat) -> Result<()> {
+    let _ = LEVEL.set(level);
+    let stderr = tracing_subscriber::fmt::layer()
+        .with_writer(io::stderr)
+        .with_ansi(style::enabled());
+    let stderr = if level >= LogLevel::Debug {
+        stderr.with_timer(Uptime::default()).boxed()
+    } else {
+        stderr.event_format(Plain).boxed()
+    };
+
+    let file = match file {
+        Some(path) => {
+            // The re-run under sudo adds to what the first run wrote
+            let escalated = env::var_os("PEEL_ESCALATED").is_some();
+            let file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .append(escalated)
+                .truncate(!escalated)
+                .open(path)
+                .with_context(|| t!("log-file-failed", path = path.display()))?;
+            let layer = tracing_subscriber::fmt::layer()
+                .with_writer(Mutex::new(file))
+                .with_ansi(false);
+            let layer = match format {
+                LogFormat::Text => layer.boxed(),
+                LogFormat::Json => layer.json().boxed(),
+            };
+            Some(layer.with_filter(level.max(LogLevel::Debug).filter()))
+        }
+        None => None,
+    };
+
+    let timings = timings::enabled()
+        .then(|| timings::Recorder.with_filter(filter_fn(|metadata| metadata.is_span())));
+
+    tracing_subscriber::registry()
+        .with(stderr.with_filter(level.filter()))
+        .with(file)
+        .with(timings)
+        .init();
+    tracing::debug!(
+        "peel {} {:?}",
+        env!("CARGO_PKG_VERSION"),
+        env::args().skip(1).collect::<Vec<_>>()
+    );
+    Ok(())
+}
+
+/// Whether `✔` lines and spinners are shown: not with `-q`.
+pub fn status() -> bool {
+    level() >= LogLevel::Info
+}
+
+/// Whether debug lines are shown on stderr, which a spinner would draw over.
+pub fn verbose() -> bool {
+    level() >= LogLevel::Debug
+}
+
+/// What stderr shows; a log file may record more.
+fn level() -> LogLevel {
+    LEVEL.get().copied().unwrap_or(LogLevel::Info)
+}
+
+/// `! message` for warnings, `✘ message` for errors, the bare message for
+/// the rest.
+struct Plain;
+
+impl<S, N> FormatEvent<S, N> for Plain
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        match *event.metadata().level() {
+            Level::ERROR => write!(writer, "{} ", "✘".red().bold())?,
+            Level::WARN => write!(writer, "{} ", "!".yellow().bold())?,
+            _ => {}
+        }
+        ctx.field_format().format_fields(writer.by_ref(), event)?;
+        writeln!(writer)
+    }
+}
@@ -1,11 +1,30 @@
+#[macro_use]
+extern crate peel_core;
+
+mod analysis;
 mod cmd;
 mod config;
-mod inspector;
-mod probe;
+mod kube;
+mod lint;
+mod logging;
+mod pager;
 mod progress;
+mod query;
+mod registry;
+mod repack;
+mod scan;
+mod signals;
+mod style;
+mod timings;
+
+use std::ffi::OsString;
+use std::path::PathBuf;
+use std::process::ExitCode;
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Result, bail};
+use clap::{CommandFactory, Parser, Subcommand};
+use cmd::output::Output;
+use peel_core::{i18n, inspector, probe, temp_files};
 
 #[derive(Parser)]
 #[command(name = "peel")]
@@ -13,7 +32,7 @@ use clap::{Parser, Subcommand};
 #[command(version)]
 struct Cli {
     /// Override runtime selection (docker, podman, containerd)
-    #[arg(long, global = true)]
+    #[arg(long, global = true, env = "PEEL_RUNTIME")]
     runtime: Option<String>,
 
     /// Output as JSON (optionally to a file)
@@ -21,17 +40,130 @@ struct Cli {
     json: Option<String>,
 
     /// Use OCI/Docker API instead of direct storage access (no root needed, slower)
-    #[arg(long, global = true)]
+    #[arg(long, global = true, env = "PEEL_USE_OCI", value_parser = clap::builder::FalseyValueParser::new())]
     use_oci: bool,
 
+    /// Filter or reshape JSON output with a JMESPath expression, e.g. "layers[?size > `1000000`].digest" (implies --json)
+    #[arg(long, global = true, value_name = "EXPR", value_parser = query::Query::parse, conflicts_with = "output")]
+    query: Option<query::Query>,
+
     /// Disable the interactive web report
     #[arg(long, global = true)]
     no_web: bool,
 
-    /// Don't auto-escalate to sudo for direct storage access
+    /// Style text output: auto styles it when stdout and stderr are terminals and NO_COLOR isn't set
+    #[arg(long, global = true, value_enum, value_name = "WHEN", env = "PEEL_COLOR", default_value_t)]
+    color: style::ColorChoice,
+
+    /// Say more on stderr: -v for the backend picked, commands run and timings, -vv for everything
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only warnings and errors on stderr: no spinners or progress lines
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// How much to say on stderr; overrides -v and -q
+    #[arg(long, global = true, value_enum, value_name = "LEVEL", env = "PEEL_LOG")]
+    log_level: Option<logging::LogLevel>,
+
+    /// Also write the log, at debug level or more, to this file, whatever stderr shows
+    #[arg(long, global = true, value_name = "FILE", env = "PEEL_LOG_FILE")]
+    log_file: Option<PathBuf>,
+
+    /// Format of --log-file
+    #[arg(long, global = true, value_enum, default_value_t, requires = "log_file")]
+    log_format: logging::LogFormat,
+
+    /// Report wall time per phase (resolve, save, parse, each layer, analyze, report) on stderr, and in --json output
+    #[arg(long, global = true)]
+    timings: bool,
+
+    /// Print text output as it is instead of through $PAGER when it's taller than the terminal
     #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Write the report somewhere other than the browser (`bundle:<dir>`, `junit:<file>`, `csv[:<file>]` for a row per file, or `github` for Actions annotations)
+    #[arg(long, global = true, value_name = "KIND:PATH")]
+    output: Option<String>,
+
+    /// Don't auto-escalate to sudo for direct storage access
+    #[arg(long, global = true, env = "PEEL_NO_SUDO", value_parser = clap::builder::FalseyValueParser::new())]
     no_sudo: bool,
 
+    /// Base image to attribute inherited size to (inspect), or to check for a floating tag (lint)
+    #[arg(long, global = true, value_name = "IMAGE")]
+    base: Option<String>,
+
+    /// Show the final merged filesystem instead of per-layer listings (inspect only)
+    #[arg(long, global = true)]
+    merged: bool,
+
+    /// Log registry requests to stderr; with a directory, also dump headers and manifests there
+    /// (verify, pull-size, push-estimate and copy)
+    #[arg(long, global = true, value_name = "DIR", num_args = 0..=1, default_missing_value = "-")]
+    debug_http: Option<String>,
+
+    /// Only list and size files below this path (repeatable)
+    #[arg(long, global = true, value_name = "PATH")]
+    root: Vec<String>,
+
+    /// Record a sha256 for every file (reads all file contents; slower)
+    #[arg(long, global = true)]
+    checksums: bool,
+
+    /// Skip listing files: layer metadata and sizes only, without `docker save` where the backend allows (inspect only)
+    #[arg(long, global = true, conflicts_with_all = ["merged", "root", "checksums", "emit_index", "min_efficiency", "preset"])]
+    no_files: bool,
+
+    /// Hash each layer's uncompressed content and check it against its diff_id (inspect only)
+    #[arg(long, global = true, conflicts_with = "no_files")]
+    verify: bool,
+
+    /// Write one JSON line per tar entry with its offset in the layer blob (inspect only)
+    #[arg(long, global = true, value_name = "FILE")]
+    emit_index: Option<PathBuf>,
+
+    /// Show the Dockerfile line each layer came from (inspect only)
+    #[arg(long, global = true, value_name = "FILE")]
+    dockerfile: Option<PathBuf>,
+
+    /// Check which pods in this kubectl context run the image (inspect only)
+    #[arg(long, global = true, value_name = "CONTEXT")]
+    kubecontext: Option<String>,
+
+    /// Limit the cluster check to one namespace (inspect only)
+    #[arg(long, global = true, value_name = "NAMESPACE")]
+    namespace: Option<String>,
+
+    /// Exit with an error if image efficiency is below this percentage (inspect and check)
+    #[arg(long, global = true, value_name = "PERCENT")]
+    min_efficiency: Option<f64>,
+
+    /// Fail when a condition holds: size>SIZE, layers>N, files>N, efficiency<PERCENT or secrets (inspect and check), or a finding severity (lint); repeatable
+    #[arg(long, global = true, value_name = "CONDITION", value_parser = cmd::check::parse_fail_on)]
+    fail_on: Vec<cmd::check::FailOn>,
+
+    /// Analyzers, output and thresholds for a common job (inspect only; default: `preset` in ./.peel.json); explicit flags win
+    #[arg(long, global = true, value_enum)]
+    preset: Option<cmd::preset::Preset>,
+
+    /// Fit text output to this many columns (default: the terminal's; piped output is left whole)
+    #[arg(long, global = true, value_name = "COLUMNS")]
+    width: Option<usize>,
+
+    /// Layer table columns, in order: index, digest, size, compressed, files, created, command, source
+    #[arg(long, global = true, value_enum, value_delimiter = ',', value_name = "COLUMN,...")]
+    columns: Vec<cmd::layer_table::Column>,
+
+    /// Language for messages (e.g. en, zh-CN); defaults to $LANG
+    #[arg(long, global = true, env = "PEEL_LANG")]
+    lang: Option<String>,
+
+    /// Answer JSON-RPC requests on stdin (inspect, list_files, diff, cancel) instead of running a command
+    #[arg(long, conflicts_with = "image")]
+    rpc: bool,
+
     #[command(subcommand)]
     command: Option<Commands>,
 
@@ -47,15 +179,478 @@ enum Commands {
         image: String,
     },
 
+    /// Build an image with docker or podman, then inspect the result
+    Build {
+        /// Build with `docker buildx build` (adds --load unless told where the image goes)
+        #[arg(long)]
+        buildx: bool,
+
+        /// Also compare with the image the tag named before this build
+        #[arg(long)]
+        compare: bool,
+
+        /// Arguments for the build, e.g. `-t app:dev .`; peel's own flags go before them
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true, value_name = "BUILD_ARGS")]
+        args: Vec<String>,
+    },
+
+    /// Compare two images layer by layer
+    Diff {
+        /// Baseline image name or tar archive
+        left: String,
+
+        /// Image name or tar archive to compare against the baseline
+        right: String,
+    },
+
+    /// Show how a single file differs between two images
+    DiffFile {
+        /// Baseline image name or tar archive
+        left: String,
+
+        /// Image name or tar archive to compare against the baseline
+        right: String,
+
+        /// Path inside the images (e.g. /app/config.yaml)
+        path: String,
+    },
+
+    /// Check whether two builds of the same Dockerfile differ in content or only in metadata
+    Repro {
+        /// First build: image name or tar archive
+        left: String,
+
+        /// Second build: image name or tar archive
+        right: String,
+    },
+
+    /// Compare size, layer count and layer reuse across several images
+    Compare {
+        /// Image names or tar archives, oldest first
+        #[arg(required = true, num_args = 2..)]
+        images: Vec<String>,
+    },
+
+    /// List layer digests, sizes and commands without reading any layer's files
+    Layers {
+        /// Image name or tar archive
+        image: String,
+    },
+
+    /// List every build step, including those that made no layer (ENV, LABEL, CMD, ...)
+    History {
+        /// Image name or tar archive
+        image: String,
+    },
+
+    /// Summarize an image in a few numbers: layers, files, directories, file sizes, whiteouts
+    Stats {
+        /// Image name or tar archive
+        image: String,
+    },
+
+    /// Check whether the local image is still what its tag points at in the registry
+    Verify {
+        /// Local image, named by the tag to check (e.g. ghcr.io/org/app:1.2)
+        image: String,
+
+        /// Platform to pick from a multi-platform image (defaults to the local image's)
+        #[arg(long, value_name = "OS/ARCH")]
+        platform: Option<String>,
+    },
+
+    /// Re-inspect the image whenever it's rebuilt and show how its size changed
+    Watch {
+        /// Image name or tar archive
+        image: String,
+
+        /// Seconds between checks for a new build
+        #[arg(long, value_name = "SECONDS", default_value_t = 2, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+
+    /// Show which layers local images share and how much disk each one alone uses
+    Shared,
+
+    /// Answer inspection requests over HTTP (POST /inspect, GET /images, GET /diff)
+    Serve {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:7777")]
+        listen: String,
+    },
+
+    /// Estimate what promoting images between registries will transfer
+    PushEstimate {
+        /// Image references relative to --from and --to (e.g. app:1.2)
+        #[arg(required = true)]
+        images: Vec<String>,
+
+        /// Source registry and optional repository prefix (e.g. registry.example.com/staging)
+        #[arg(long)]
+        from: String,
+
+        /// Destination registry and optional repository prefix
+        #[arg(long)]
+        to: String,
+    },
+
+    /// Estimate what `docker pull` would download, given the layers already local
+    PullSize {
+        /// Remote image reference (e.g. ghcr.io/org/app:1.2)
+        image: String,
+
+        /// Platform to pick from a multi-platform image (defaults to this machine's)
+        #[arg(long, value_name = "OS/ARCH")]
+        platform: Option<String>,
+    },
+
+    /// Estimate the size of an image if a range of layers were squashed into one
+    SquashSim {
+        /// Image name or tar archive
+        image: String,
+
+        /// First layer to squash (1 = base layer)
+        #[arg(long, default_value_t = 1)]
+        from: usize,
+
+        /// Last layer to squash, inclusive (defaults to the top layer)
+        #[arg(long)]
+        to: Option<usize>,
+    },
+
+    /// Measure how much smaller each layer would be compressed with zstd instead of gzip
+    ///
+    /// Only measures; rewriting layers is `peel copy --recompress`'s job.
+    Recompress {
+        /// Image name or tar archive
+        image: String,
+
+        /// zstd level (1-22)
+        #[arg(long, default_value_t = 3)]
+        level: i32,
+    },
+
+    /// List the packages installed in an image (dpkg and apk)
+    Sbom {
+        /// Image name or tar archive
+        image: String,
+
+        /// Output format; `spdx` and `cyclonedx` write a JSON document to stdout or the --json file
+        #[arg(long, value_enum, default_value_t = cmd::sbom::Format::Peel)]
+        format: cmd::sbom::Format,
+    },
+
+    /// Find known vulnerabilities in an image's packages and the layers that installed them
+    Scan {
+        /// Image name or tar archive
+        image: String,
+
+        /// Where findings come from: osv.dev, or an installed trivy or grype
+        #[arg(long, value_enum, default_value_t = scan::Scanner::Osv)]
+        scanner: scan::Scanner,
+    },
+
+    /// Look for private keys, tokens and credential files in every layer, deleted ones included
+    Secrets {
+        /// Image name or tar archive
+        image: String,
+    },
+
+    /// Summarize the licenses of installed packages and bundled license files
+    Licenses {
+        /// Image name or tar archive
+        image: String,
+    },
+
+    /// Find ELF files whose shared libraries are missing from the final filesystem
+    Ldd {
+        /// Image name or tar archive
+        image: String,
+
+        /// List every dynamically linked file and where its libraries resolve
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Check the image against lint rules
+    Lint {
+        /// Image name or tar archive
+        image: String,
+
+        /// Rule settings and custom rules as JSON (default: ./.peel-lint.json, if present)
+        #[arg(long, value_name = "FILE")]
+        config: Option<PathBuf>,
+
+        /// Change a rule's severity, or turn it off (e.g. `large-layer=error`, `runs-as-root=off`); wins over the config file
+        #[arg(long = "rule", value_name = "RULE=LEVEL", value_parser = cmd::lint::parse_rule)]
+        rules: Vec<(lint::RuleId, Option<lint::Severity>)>,
+    },
+
+    /// Fail if the image breaks size, file count, efficiency, path, growth or directory budget limits (for CI)
+    Check {
+        /// Image name or tar archive
+        image: String,
+
+        /// Largest total image size allowed (e.g. 500MB, 1.5G)
+        #[arg(long, value_name = "SIZE", value_parser = cmd::check::parse_size)]
+        max_size: Option<u64>,
+
+        /// Largest size allowed for any one layer
+        #[arg(long, value_name = "SIZE", value_parser = cmd::check::parse_size)]
+        max_layer_size: Option<u64>,
+
+        /// Most files allowed in the final filesystem
+        #[arg(long, value_name = "COUNT")]
+        max_files: Option<usize>,
+
+        /// Fail if any layer ships a file matching this glob, even one a later layer deletes (repeatable)
+        #[arg(long, value_name = "GLOB")]
+        forbid: Vec<String>,
+
+        /// Saved `peel inspect --json` report to compare against
+        #[arg(long, value_name = "FILE")]
+        baseline: Option<PathBuf>,
+
+        /// Most the image may grow over the baseline (e.g. 5%, 50MB)
+        #[arg(long, value_name = "LIMIT", requires = "baseline", value_parser = cmd::check::parse_growth)]
+        max_growth: Option<cmd::check::Growth>,
+
+        /// Largest file allowed that the baseline didn't have (e.g. 50MB)
+        #[arg(long, value_name = "SIZE", requires = "baseline", value_parser = cmd::check::parse_size)]
+        max_new_file: Option<u64>,
+
+        /// Most a directory of the final filesystem may hold (e.g. '/app/node_modules<=80MB'; repeatable)
+        #[arg(long = "budget", value_name = "PATH<=SIZE", value_parser = cmd::check::parse_budget)]
+        budgets: Vec<cmd::check::Budget>,
+    },
+
+    /// Write a single layer of an image to disk as a tar
+    ExportLayer {
+        /// Image name or tar archive
+        image: String,
+
+        /// Layer number (base = 1) or digest prefix, as inspect prints them
+        layer: String,
+
+        /// File to write
+        #[arg(short = 'o', value_name = "FILE")]
+        destination: PathBuf,
+    },
+
+    /// Print a file from an image to stdout
+    Cat {
+        /// Image name or tar archive
+        image: String,
+
+        /// Path inside the image (e.g. /etc/os-release)
+        path: String,
+
+        /// Show the copy in this layer (index or digest prefix) instead of the final one
+        #[arg(long)]
+        layer: Option<String>,
+
+        /// Refuse files larger than this many bytes
+        #[arg(long, value_name = "BYTES", default_value_t = 1024 * 1024)]
+        max_size: u64,
+    },
+
+    /// Show cumulative directory sizes, like `du --max-depth`
+    Du {
+        /// Image name or tar archive
+        image: String,
+
+        /// Directory to summarize (defaults to /)
+        path: Option<String>,
+
+        /// Count only this layer's files (index or digest prefix) instead of the final filesystem
+        #[arg(long)]
+        layer: Option<String>,
+
+        /// List directories at most this many levels below the path (default 1, or 3 with --tree)
+        #[arg(short = 'd', long)]
+        max_depth: Option<usize>,
+
+        /// Draw files and directories as a tree, largest first
+        #[arg(long)]
+        tree: bool,
+
+        /// Leave tree entries smaller than this out, e.g. 10MB
+        #[arg(long, value_name = "SIZE", requires = "tree", value_parser = cmd::check::parse_size)]
+        min_size: Option<u64>,
+    },
+
+    /// Find paths matching a glob (or regex) in every layer
+    Find {
+        /// Image name or tar archive
+        image: String,
+
+        /// Glob such as 'lib/**/*.so'; without a `/`, matches file names at any depth
+        pattern: String,
+
+        /// Treat the pattern as a regular expression over the whole path
+        #[arg(long)]
+        regex: bool,
+
+        /// Ignore case
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+    },
+
+    /// Search file contents in every layer for a regular expression
+    Grep {
+        /// Image name or tar archive
+        image: String,
+
+        /// Regular expression to look for
+        pattern: String,
+
+        /// Ignore case
+        #[arg(short = 'i', long)]
+        ignore_case: bool,
+
+        /// Skip files larger than this many bytes
+        #[arg(long, value_name = "BYTES", default_value_t = 16 * 1024 * 1024)]
+        max_size: u64,
+    },
+
+    /// Copy a single file out of an image's final filesystem
+    Extract {
+        /// Image name or tar archive
+        image: String,
+
+        /// Path inside the image (e.g. /etc/os-release)
+        path: String,
+
+        /// File to write (defaults to stdout)
+        #[arg(short = 'o', value_name = "FILE")]
+        destination: Option<PathBuf>,
+    },
+
+    /// Write the final filesystem of an image (whiteouts applied) as a tar
+    ExportRootfs {
+        /// Image name or tar archive
+        image: String,
+
+        /// File to write
+        #[arg(short = 'o', value_name = "FILE")]
+        destination: PathBuf,
+    },
+
+    /// Merge all layers of an image into a single-layer tar
+    Flatten {
+        /// Image name or tar archive
+        image: String,
+
+        /// File to write
+        destination: PathBuf,
+
+        /// What to write: the bare filesystem, or a loadable image
+        #[arg(long, value_enum, default_value_t = cmd::flatten::Format::Docker)]
+        format: cmd::flatten::Format,
+
+        /// Tag to record in the output image (defaults to the source's)
+        #[arg(long, value_name = "NAME:TAG")]
+        tag: Option<String>,
+    },
+
+    /// Copy an image between registries, optionally dropping layers or paths
+    Copy {
+        /// Source image reference
+        source: String,
+
+        /// Destination image reference
+        destination: String,
+
+        /// Leave out a layer, by blob digest or diff ID (prefixes accepted)
+        #[arg(long, value_name = "DIGEST")]
+        drop_layer: Vec<String>,
+
+        /// Strip a path (and everything under it) from every layer
+        #[arg(long, value_name = "PATH")]
+        drop_path: Vec<String>,
+
+        /// Recompress every layer with gzip at this level (0-9)
+        #[arg(long, value_name = "LEVEL")]
+        recompress: Option<u32>,
+    },
+
     /// Detect installed container runtimes
     Probe,
 
+    /// Re-render a saved JSON report in another format
+    #[command(hide = true)]
+    Render {
+        /// Report JSON written by --json (`-` for stdin)
+        #[arg(long)]
+        input: String,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = cmd::render::Format::Text)]
+        format: cmd::render::Format,
+    },
+
     /// Update peel to the latest version
     Update,
+
+    /// List plugins: `peel-<name>` executables on PATH, run as `peel <name>`
+    Plugins,
+
+    /// Print the JSON Schema of what --json writes
+    Schema,
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+fn main() -> ExitCode {
+    signals::install();
+    let result = run();
+    pager::finish();
+    timings::print();
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("Error: {err:?}");
+            // A failed gate exits with its class's code, anything else with 1
+            let code = err
+                .downcast_ref::<cmd::check::GateFailed>()
+                .map_or(1, |gate| gate.class as u8);
+            ExitCode::from(code)
+        }
+    }
+}
+
+fn run() -> Result<()> {
+    // `peel <name>` runs `peel-<name>` from PATH, as git does
+    let args: Vec<OsString> = std::env::args_os().collect();
+    if let Some((at, plugin)) = cmd::plugin::find(&Cli::command(), &args) {
+        let cli = Cli::parse_from(&args[..at]);
+        i18n::init(cli.lang.as_deref());
+        style::init(cli.color);
+        let log_level = logging::LogLevel::from_cli(cli.log_level, cli.verbose, cli.quiet);
+        logging::init(log_level, cli.log_file.as_deref(), cli.log_format)?;
+        let globals = cmd::plugin::Globals {
+            runtime: cli.runtime.as_deref(),
+            json: cli.json.as_deref(),
+            use_oci: cli.use_oci,
+            no_sudo: cli.no_sudo,
+            lang: cli.lang.as_deref(),
+            log_level: Some(log_level.name()).filter(|_| log_level != logging::LogLevel::Info),
+        };
+        return cmd::plugin::run(&plugin, &args[at + 1..], &globals);
+    }
+
+    let cli = Cli::parse_from(args);
+    i18n::init(cli.lang.as_deref());
+    // Settled while stdout is still the terminal, before the pager takes it
+    style::init(cli.color);
+    if cli.timings {
+        timings::enable();
+    }
+    logging::init(
+        logging::LogLevel::from_cli(cli.log_level, cli.verbose, cli.quiet),
+        cli.log_file.as_deref(),
+        cli.log_format,
+    )?;
+    // A query has nothing to apply to but JSON
+    let json = cli.json.clone().or(cli.query.as_ref().map(|_| "-".to_string()));
 
     // Resolve: `peel <image>` is shorthand for `peel inspect <image>`
     let image_to_inspect = match &cli.command {
@@ -64,16 +659,431 @@ fn main() -> Result<()> {
         None => cli.image.clone(),
     };
 
+    if cli.rpc {
+        if cli.command.is_some() {
+            bail!(t!("rpc-with-command"));
+        }
+        config::set_listing(inspector::Listing {
+            roots: inspector::Roots::new(&cli.root),
+            checksums: cli.checksums,
+        });
+        return cmd::rpc::run(cli.use_oci, cli.runtime, cli.no_sudo);
+    }
     if cli.command.is_none() && image_to_inspect.is_none() {
         Cli::parse_from(["peel", "--help"]);
         return Ok(());
     }
+    // `peel build` inspects what it built, so inspect's flags apply to it too
+    let inspects =
+        image_to_inspect.is_some() || matches!(cli.command, Some(Commands::Build { .. }));
 
+    // Flags that only change what inspect does, and which would otherwise
+    // be silently ignored
+    let inspect_only = [
+        ("--preset", cli.preset.is_some()),
+        ("--merged", cli.merged),
+        ("--no-files", cli.no_files),
+        ("--verify", cli.verify),
+        ("--emit-index", cli.emit_index.is_some()),
+        ("--dockerfile", cli.dockerfile.is_some()),
+        ("--kubecontext", cli.kubecontext.is_some()),
+        ("--namespace", cli.namespace.is_some()),
+    ];
+    if !inspects && let Some((flag, _)) = inspect_only.iter().find(|(_, given)| *given) {
+        bail!(t!("inspect-only-flag", flag = *flag));
+    }
+    // The schema describes what --json writes; it isn't itself a report
+    if cli.query.is_some() && matches!(cli.command, Some(Commands::Schema)) {
+        bail!(t!("query-schema"));
+    }
+    if cli.base.is_some() && !inspects && !matches!(cli.command, Some(Commands::Lint { .. })) {
+        bail!(t!("base-unsupported"));
+    }
+    // Only the commands that talk to a registry have requests to log
+    let registry = matches!(
+        cli.command,
+        Some(
+            Commands::Verify { .. }
+                | Commands::PullSize { .. }
+                | Commands::PushEstimate { .. }
+                | Commands::Copy { .. }
+        )
+    );
+    if cli.debug_http.is_some() && !registry {
+        bail!(t!("debug-http-unsupported"));
+    }
+    let preset = match cli.preset {
+        Some(preset) => Some(preset),
+        None if inspects => cmd::preset::from_config()?,
+        None => None,
+    }
+    .map(cmd::preset::Preset::settings);
+
+    config::set_width(cli.width);
+    config::set_columns(cli.columns.clone());
+    config::set_listing(inspector::Listing {
+        roots: inspector::Roots::new(&cli.root),
+        // `peel repro` compares files by content
+        checksums: cli.checksums
+            || preset.as_ref().is_some_and(|p| p.checksums)
+            || matches!(cli.command, Some(Commands::Repro { .. })),
+    });
+    config::set_query(cli.query.clone());
+    let output_flags = json.is_some() || cli.no_web || cli.output.is_some();
+    let output = match preset.as_ref().and_then(|p| p.output.clone()) {
+        Some(output) if !output_flags => output,
+        _ => cmd::output::Output::from_cli(json.as_deref(), cli.no_web, cli.output.as_deref())?,
+    };
+    // Only commands that check something have results to report as tests
+    if matches!(output, cmd::output::Output::Junit(_))
+        && !inspects
+        && !matches!(
+            cli.command,
+            Some(Commands::Lint { .. } | Commands::Check { .. })
+        )
+    {
+        bail!(t!("output-junit-unsupported"));
+    }
+    if matches!(output, cmd::output::Output::Csv(_)) && !inspects {
+        bail!(t!("output-csv-unsupported"));
+    }
+    if !cli.fail_on.is_empty()
+        && !inspects
+        && !matches!(
+            cli.command,
+            Some(Commands::Lint { .. } | Commands::Check { .. })
+        )
+    {
+        bail!(t!("fail-on-unsupported"));
+    }
+    // Reports page when they're long; streams, servers and file contents don't
+    let pages = match &cli.command {
+        Some(
+            Commands::Build { .. }
+            | Commands::Watch { .. }
+            | Commands::Serve { .. }
+            | Commands::Cat { .. }
+            | Commands::Extract { .. }
+            | Commands::ExportLayer { .. }
+            | Commands::ExportRootfs { .. }
+            | Commands::Flatten { .. }
+            | Commands::Copy { .. }
+            | Commands::Update,
+        ) => false,
+        Some(Commands::Render { format, .. }) => matches!(format, cmd::render::Format::Text),
+        // The web report is served from this process
+        Some(Commands::Inspect { .. } | Commands::Diff { .. }) | None => {
+            matches!(output, Output::Text | Output::Github | Output::Junit(_))
+        }
+        Some(_) => matches!(
+            output,
+            Output::Web | Output::Text | Output::Github | Output::Junit(_)
+        ),
+    };
+    if pages && !cli.no_pager {
+        pager::start();
+    }
+    let http_trace = || {
+        cli.debug_http
+            .as_deref()
+            .map(registry::trace::Trace::from_cli)
+            .transpose()
+    };
+
+    let inspect_opts = cmd::inspect::Options {
+        base: cli.base.as_deref(),
+        merged: cli.merged || preset.as_ref().is_some_and(|p| p.merged),
+        min_efficiency: cli
+            .min_efficiency
+            .or(preset.as_ref().and_then(|p| p.min_efficiency)),
+        fail_on: &cli.fail_on,
+        emit_index: cli.emit_index.as_deref(),
+        dockerfile: cli.dockerfile.as_deref(),
+        cluster: (cli.kubecontext.is_some() || cli.namespace.is_some()).then_some(
+            kube::Target {
+                context: cli.kubecontext.as_deref(),
+                namespace: cli.namespace.as_deref(),
+            },
+        ),
+        no_files: cli.no_files,
+        verify: cli.verify,
+        analyzers: match &preset {
+            _ if cli.no_files => cmd::preset::Analyzers::NONE,
+            Some(p) => p.analyzers,
+            None => cmd::preset::Analyzers::ALL,
+        },
+    };
     if let Some(image) = &image_to_inspect {
-        let web = !cli.no_web && cli.json.is_none();
-        cmd::inspect::run(image, cli.use_oci, cli.json.as_deref(), cli.runtime, web, cli.no_sudo)?;
+        cmd::inspect::run(
+            image,
+            &inspect_opts,
+            cli.use_oci,
+            &output,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Build {
+        buildx,
+        compare,
+        args,
+    }) = &cli.command
+    {
+        let opts = cmd::build::Options {
+            args,
+            buildx: *buildx,
+            compare: *compare,
+        };
+        cmd::build::run(
+            &opts,
+            &inspect_opts,
+            cli.use_oci,
+            &output,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Diff { left, right }) = &cli.command {
+        cmd::diff::run(left, right, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::DiffFile { left, right, path }) = &cli.command {
+        cmd::diff_file::run(left, right, path, cli.use_oci, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Repro { left, right }) = &cli.command {
+        cmd::repro::run(left, right, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Compare { images }) = &cli.command {
+        cmd::compare::run(images, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Layers { image }) = &cli.command {
+        cmd::layers::run(image, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::History { image }) = &cli.command {
+        cmd::history::run(image, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Stats { image }) = &cli.command {
+        cmd::stats::run(image, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Verify { image, platform }) = &cli.command {
+        cmd::verify::run(
+            image,
+            platform.as_deref(),
+            cli.use_oci,
+            &output,
+            cli.runtime,
+            cli.no_sudo,
+            http_trace()?,
+        )?;
+    } else if let Some(Commands::Watch { image, interval }) = &cli.command {
+        cmd::watch::run(image, *interval, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Shared) = &cli.command {
+        cmd::shared::run(cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Serve { listen }) = &cli.command {
+        cmd::serve::run(listen, cli.use_oci, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::PushEstimate { images, from, to }) = &cli.command {
+        cmd::push_estimate::run(images, from, to, &output, http_trace()?)?;
+    } else if let Some(Commands::PullSize { image, platform }) = &cli.command {
+        cmd::pull_size::run(image, platform.as_deref(), &output, cli.runtime, http_trace()?)?;
+    } else if let Some(Commands::SquashSim { image, from, to }) = &cli.command {
+        cmd::squash_sim::run(image, *from, *to, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Recompress { image, level }) = &cli.command {
+        cmd::recompress::run(image, *level, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Sbom { image, format }) = &cli.command {
+        cmd::sbom::run(image, *format, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Scan { image, scanner }) = &cli.command {
+        cmd::scan::run(image, *scanner, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Secrets { image }) = &cli.command {
+        cmd::secrets::run(image, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Licenses { image }) = &cli.command {
+        cmd::licenses::run(image, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Ldd { image, all }) = &cli.command {
+        cmd::ldd::run(image, *all, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Lint {
+        image,
+        config,
+        rules,
+    }) = &cli.command
+    {
+        let opts = cmd::lint::Options {
+            config: config.as_deref(),
+            rules,
+            fail_on: cmd::lint::fail_on_severity(&cli.fail_on)?,
+            base: cli.base.as_deref(),
+        };
+        cmd::lint::run(image, opts, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Check {
+        image,
+        max_size,
+        max_layer_size,
+        max_files,
+        forbid,
+        baseline,
+        max_growth,
+        max_new_file,
+        budgets,
+    }) = &cli.command
+    {
+        let opts = cmd::check::Options {
+            max_size: *max_size,
+            max_layer_size: *max_layer_size,
+            max_files: *max_files,
+            min_efficiency: cli.min_efficiency,
+            forbid,
+            baseline: baseline.as_deref(),
+            max_growth: *max_growth,
+            max_new_file: *max_new_file,
+            budgets,
+            fail_on: &cli.fail_on,
+        };
+        cmd::check::run(image, &opts, cli.use_oci, &output, cli.runtime, cli.no_sudo)?;
+    } else if let Some(Commands::Du {
+        image,
+        path,
+        layer,
+        max_depth,
+        tree,
+        min_size,
+    }) = &cli.command
+    {
+        let opts = cmd::du::Options {
+            layer: layer.as_deref(),
+            max_depth: max_depth.unwrap_or(if *tree { 3 } else { 1 }),
+            tree: *tree,
+            min_size: min_size.unwrap_or(0),
+        };
+        cmd::du::run(
+            image,
+            path.as_deref(),
+            &opts,
+            cli.use_oci,
+            &output,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Find {
+        image,
+        pattern,
+        regex,
+        ignore_case,
+    }) = &cli.command
+    {
+        let opts = cmd::find::Options {
+            regex: *regex,
+            ignore_case: *ignore_case,
+        };
+        cmd::find::run(
+            image,
+            pattern,
+            &opts,
+            cli.use_oci,
+            &output,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Grep {
+        image,
+        pattern,
+        ignore_case,
+        max_size,
+    }) = &cli.command
+    {
+        let opts = cmd::grep::Options {
+            ignore_case: *ignore_case,
+            max_size: *max_size,
+        };
+        cmd::grep::run(
+            image,
+            pattern,
+            &opts,
+            cli.use_oci,
+            &output,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::ExportLayer {
+        image,
+        layer,
+        destination,
+    }) = &cli.command
+    {
+        cmd::export_layer::run(
+            image,
+            layer,
+            destination,
+            cli.use_oci,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Cat {
+        image,
+        path,
+        layer,
+        max_size,
+    }) = &cli.command
+    {
+        cmd::cat::run(
+            image,
+            path,
+            layer.as_deref(),
+            *max_size,
+            cli.use_oci,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Extract {
+        image,
+        path,
+        destination,
+    }) = &cli.command
+    {
+        cmd::extract::run(
+            image,
+            path,
+            destination.as_deref(),
+            cli.use_oci,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::ExportRootfs { image, destination }) = &cli.command {
+        // Same merge as `flatten`, without the image wrapper
+        cmd::flatten::run(
+            image,
+            destination,
+            cmd::flatten::Format::Rootfs,
+            None,
+            cli.use_oci,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Flatten {
+        image,
+        destination,
+        format,
+        tag,
+    }) = &cli.command
+    {
+        cmd::flatten::run(
+            image,
+            destination,
+            *format,
+            tag.as_deref(),
+            cli.use_oci,
+            cli.runtime,
+            cli.no_sudo,
+        )?;
+    } else if let Some(Commands::Copy {
+        source,
+        destination,
+        drop_layer,
+        drop_path,
+        recompress,
+    }) = &cli.command
+    {
+        let transform = cmd::copy::Transform {
+            drop_layers: drop_layer.clone(),
+            filter: repack::LayerFilter::new(drop_path),
+            recompress: *recompress,
+        };
+        cmd::copy::run(source, destination, &transform, http_trace()?)?;
+    } else if let Some(Commands::Render { input, format }) = &cli.command {
+        cmd::render::run(input, *format)?;
     } else if matches!(cli.command, Some(Commands::Probe)) {
-        cmd::probe::run(cli.json.is_some(), cli.runtime)?;
+        cmd::probe::run(json.is_some(), cli.runtime)?;
+    } else if matches!(cli.command, Some(Commands::Plugins)) {
+        cmd::plugin::list(&output)?;
+    } else if matches!(cli.command, Some(Commands::Schema)) {
+        cmd::schema::run();
     } else if matches!(cli.command, Some(Commands::Update)) {
         cmd::self_update::run()?;
     }
@@ -0,0 +1,128 @@
+use std::env;
+use std::io::{self, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+
+use crate::config;
+use crate::signals;
+
+/// Text output held back by `start`, until `finish` shows it.
+static CAPTURE: Mutex<Option<Capture>> = Mutex::new(None);
+
+struct Capture {
+    /// The terminal stdout was before it went to the pipe
+    #[cfg(unix)]
+    terminal: std::os::fd::OwnedFd,
+
+    /// Reads the pipe until its last writer closes it
+    reader: JoinHandle<Vec<u8>>,
+}
+
+/// Hold back what's printed to stdout so `finish` can page it, if stdout is
+/// a terminal. Child processes that inherit stdout, such as the re-run
+/// under sudo, are captured too.
+#[cfg(unix)]
+pub fn start() {
+    use std::os::fd::{AsFd, AsRawFd};
+
+    let stdout = io::stdout();
+    if !stdout.is_terminal() || pager().is_none() {
+        return;
+    }
+    // Settled while stdout is still the terminal, so text is fitted to it
+    config::width();
+
+    let Ok(terminal) = stdout.as_fd().try_clone_to_owned() else {
+        return;
+    };
+    let Ok((mut pipe, writer)) = io::pipe() else {
+        return;
+    };
+    // SAFETY: both are open descriptors; stdout now writes to the pipe
+    if unsafe { libc::dup2(writer.as_raw_fd(), stdout.as_raw_fd()) } < 0 {
+        return;
+    }
+    drop(writer);
+    let reader = thread::spawn(move || {
+        let mut text = Vec::new();
+        let _ = pipe.read_to_end(&mut text);
+        text
+    });
+    *CAPTURE.lock().unwrap() = Some(Capture { terminal, reader });
+}
+
+#[cfg(not(unix))]
+pub fn start() {}
+
+/// Show what `start` held back: through the pager if it's taller than the
+/// terminal, else as it is. Call before exiting.
+pub fn finish() {
+    let Some(capture) = CAPTURE.lock().unwrap().take() else {
+        return;
+    };
+    let _ = io::stdout().flush();
+    // Putting the terminal back closes the pipe's last writer
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsRawFd;
+        // SAFETY: both are open descriptors
+        unsafe { libc::dup2(capture.terminal.as_raw_fd(), io::stdout().as_raw_fd()) };
+    }
+    let text = capture.reader.join().unwrap_or_default();
+
+    let height = crossterm::terminal::size().map_or(usize::MAX, |(_, rows)| rows as usize);
+    let lines = text.iter().filter(|b| **b == b'\n').count();
+    if lines >= height && page(&text).is_ok() {
+        return;
+    }
+    let _ = io::stdout().write_all(&text);
+}
+
+/// Give stdout back to the terminal, dropping what `start` held back, for
+/// when peel is interrupted.
+pub fn abort() {
+    let Some(capture) = CAPTURE.lock().unwrap_or_else(|e| e.into_inner()).take() else {
+        return;
+    };
+    #[cfg(unix)]
+    {
+        use std::os::fd::AsRawFd;
+        // SAFETY: both are open descriptors
+        unsafe { libc::dup2(capture.terminal.as_raw_fd(), io::stdout().as_raw_fd()) };
+    }
+    // The reader is left to the exit that follows
+    drop(capture);
+}
+
+/// Run the pager on `text` and wait for the user to quit it.
+fn page(text: &[u8]) -> io::Result<()> {
+    let pager = pager().unwrap_or_default();
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(&pager).stdin(Stdio::piped());
+    // Colors through, and the text left on screen after quitting, as git does
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+    let mut child = command.spawn()?;
+    // less takes Ctrl+C to stop a search, not to quit
+    signals::defer(true);
+    if let Some(mut stdin) = child.stdin.take() {
+        // The user may quit before reading everything
+        let _ = stdin.write_all(text);
+    }
+    let status = child.wait();
+    signals::defer(false);
+    status?;
+    Ok(())
+}
+
+/// `$PEEL_PAGER`, else `$PAGER`, else `less`; `None` if set to nothing or
+/// `cat`.
+fn pager() -> Option<String> {
+    let pager = env::var("PEEL_PAGER")
+        .or_else(|_| env::var("PAGER"))
+        .unwrap_or_else(|_| "less".to_string());
+    let pager = pager.trim();
+    (!pager.is_empty() && pager != "cat").then(|| pager.to_string())
+}
@@ -1,20 +1,71 @@
-use crossterm::style::Stylize;
-use indicatif::{ProgressBar, ProgressStyle};
+use std::fmt::Display;
+use std::io::{self, IsTerminal};
+use std::rc::Rc;
+
+use indicatif::{ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use crate::config;
+use crate::inspector::Progress;
+use crate::logging;
+use crate::style::{self, Stylize};
+
+/// Below this many columns, progress bars give way to plain counters.
+const NARROW: usize = 60;
 
 /// A simple spinner for long-running stages.
 pub struct Spinner {
     bar: ProgressBar,
 }
 
-fn spinner_style() -> ProgressStyle {
+/// `wide_msg` cuts long messages to the terminal width; a message that wraps
+/// would leave its first line behind on every redraw.
+pub fn spinner_style() -> ProgressStyle {
     ProgressStyle::default_spinner()
-        .template("{spinner:.dim} {msg}")
+        .template(&format!("{} {{wide_msg}}", spinner()))
+        .unwrap()
+}
+
+/// Style for a bar counting `unit` (`pos`/`len` or `bytes`/`total_bytes`)
+/// after `{msg}`. Narrow terminals get just the counter, without the bar.
+pub fn bar_style(unit: &str) -> ProgressStyle {
+    let counter = if unit == "bytes" {
+        "{bytes}/{total_bytes}"
+    } else {
+        "{pos}/{len}"
+    };
+    let spinner = spinner();
+    let template = if config::terminal_width().is_some_and(|w| w < NARROW) {
+        format!("{spinner} {counter} {{wide_msg}}")
+    } else {
+        format!("{spinner} {{msg}} [{{bar:20}}] {counter} ({{elapsed_precise:.>5}})")
+    };
+    ProgressStyle::with_template(&template)
         .unwrap()
+        .with_key(
+            "elapsed_precise",
+            |state: &indicatif::ProgressState, w: &mut dyn std::fmt::Write| {
+                let _ = write!(w, "{}s", state.elapsed().as_secs());
+            },
+        )
+        .progress_chars("━╸░")
+}
+
+/// The spinner's template key, dimmed unless styling is off.
+fn spinner() -> &'static str {
+    if style::enabled() {
+        "{spinner:.dim}"
+    } else {
+        "{spinner}"
+    }
 }
 
 impl Spinner {
     pub fn new(message: impl Into<String>) -> Self {
         let bar = ProgressBar::new_spinner();
+        // Redraws in a CI log are noise, and would draw over debug lines
+        if !io::stderr().is_terminal() || !logging::status() || logging::verbose() {
+            bar.set_draw_target(ProgressDrawTarget::hidden());
+        }
         bar.set_style(spinner_style());
         bar.set_message(message.into());
         bar.enable_steady_tick(std::time::Duration::from_millis(80));
@@ -25,15 +76,50 @@ impl Spinner {
         self.bar.set_message(message.into());
     }
 
-    /// Return a cheap clone of the inner progress bar (shares the same Arc).
-    pub fn clone_bar(&self) -> ProgressBar {
-        self.bar.clone()
+    /// Draw a backend's progress on this spinner's line.
+    pub fn progress(&self) -> Rc<dyn Progress> {
+        Rc::new(BarProgress(self.bar.clone()))
     }
 
     /// Clear the spinner and print a `✔ message` line to stderr.
-    pub fn finish(self, message: impl Into<String>) {
+    pub fn finish(&self, message: impl Into<String>) {
         self.bar.disable_steady_tick();
         self.bar.finish_and_clear();
-        eprintln!("{} {}", "✔".green(), message.into());
+        done(message.into());
+    }
+}
+
+/// Print a `✔ message` line to stderr, unless `-q` asked for quiet.
+pub fn done(message: impl Display) {
+    if logging::status() {
+        eprintln!("{} {message}", "✔".green());
+    }
+}
+
+/// A spinner's bar as a backend sees it: steps print `✔ done` and go back to
+/// spinning, counted work switches to a bar.
+struct BarProgress(ProgressBar);
+
+impl Progress for BarProgress {
+    fn step(&self, done: &str, next: &str) {
+        let bar = &self.0;
+        bar.finish_and_clear();
+        self::done(done);
+        bar.reset();
+        bar.set_style(spinner_style());
+        bar.set_message(next.to_string());
+        bar.enable_steady_tick(std::time::Duration::from_millis(80));
+    }
+
+    fn start(&self, message: &str, total: u64, unit: &str) {
+        let bar = &self.0;
+        bar.set_length(total);
+        bar.set_position(0);
+        bar.set_style(bar_style(unit));
+        bar.set_message(message.to_string());
+    }
+
+    fn advance(&self, amount: u64) {
+        self.0.inc(amount);
     }
 }
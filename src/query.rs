@@ -0,0 +1,1109 @@
+use std::cmp::Ordering;
+
+use anyhow::{Result, anyhow, bail};
+use serde_json::{Map, Number, Value};
+
+/// Functions a query can call, with their fewest and most arguments.
+const FUNCTIONS: &[(&str, usize, usize)] = &[
+    ("abs", 1, 1),
+    ("avg", 1, 1),
+    ("contains", 2, 2),
+    ("ends_with", 2, 2),
+    ("join", 2, 2),
+    ("keys", 1, 1),
+    ("length", 1, 1),
+    ("map", 2, 2),
+    ("max", 1, 1),
+    ("max_by", 2, 2),
+    ("min", 1, 1),
+    ("min_by", 2, 2),
+    ("not_null", 1, usize::MAX),
+    ("reverse", 1, 1),
+    ("sort", 1, 1),
+    ("sort_by", 2, 2),
+    ("starts_with", 2, 2),
+    ("sum", 1, 1),
+    ("to_number", 1, 1),
+    ("to_string", 1, 1),
+    ("type", 1, 1),
+    ("values", 1, 1),
+];
+
+/// A `--query` expression: the JMESPath syntax most queries need
+/// (sub-expressions, indexes, slices, projections, filters, multi-selects,
+/// pipes and a set of its functions), checked when it's parsed.
+#[derive(Debug, Clone)]
+pub struct Query(Ast);
+
+#[derive(Debug, Clone)]
+enum Ast {
+    /// `@`, and the right side of a projection nothing follows
+    Current,
+    Field(String),
+    Index(i64),
+    Slice(Option<i64>, Option<i64>, Option<i64>),
+    Literal(Value),
+    Subexpr(Box<Ast>, Box<Ast>),
+    /// Like `Subexpr`, but ends any projection on its left
+    Pipe(Box<Ast>, Box<Ast>),
+    /// `a[*].b`: `b` of each element of `a`, nulls dropped
+    Projection(Box<Ast>, Box<Ast>),
+    /// `a.*.b`: `b` of each value of the object `a`
+    ValueProjection(Box<Ast>, Box<Ast>),
+    /// `a[?condition].b`
+    Filter(Box<Ast>, Box<Ast>, Box<Ast>),
+    /// `a[]`: nested arrays one level flatter
+    Flatten(Box<Ast>),
+    Or(Box<Ast>, Box<Ast>),
+    And(Box<Ast>, Box<Ast>),
+    Not(Box<Ast>),
+    Compare(Comparator, Box<Ast>, Box<Ast>),
+    List(Vec<Ast>),
+    Hash(Vec<(String, Ast)>),
+    Call(String, Vec<Ast>),
+    /// `&expr`, which the function it's passed to evaluates
+    ExpRef(Box<Ast>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Dot,
+    Star,
+    /// `[]`
+    Flatten,
+    /// `[?`
+    Filter,
+    LBracket,
+    RBracket,
+    LBrace,
+    RBrace,
+    LParen,
+    RParen,
+    Comma,
+    Colon,
+    Pipe,
+    Or,
+    And,
+    Not,
+    Compare(Comparator),
+    At,
+    Ampersand,
+    Number(i64),
+    Ident(String),
+    /// `"name"`: a field, never a function
+    Quoted(String),
+    /// `` `json` `` or `'raw string'`
+    Literal(Value),
+    Eof,
+}
+
+/// A token and the columns it spans, for errors.
+struct Spanned {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+impl Query {
+    pub fn parse(text: &str) -> Result<Query> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut parser = Parser {
+            tokens: tokenize(&chars)?,
+            chars,
+            pos: 0,
+        };
+        let ast = parser.expression(0)?;
+        if *parser.peek() != Token::Eof {
+            return Err(parser.unexpected());
+        }
+        Ok(Query(ast))
+    }
+
+    /// What the query selects from `value`; null if nothing matched.
+    pub fn apply(&self, value: &Value) -> Result<Value> {
+        eval(&self.0, value)
+    }
+}
+
+fn tokenize(chars: &[char]) -> Result<Vec<Spanned>> {
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let start = i;
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match chars[i] {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '.' => (Token::Dot, 1),
+            '*' => (Token::Star, 1),
+            ']' => (Token::RBracket, 1),
+            '{' => (Token::LBrace, 1),
+            '}' => (Token::RBrace, 1),
+            '(' => (Token::LParen, 1),
+            ')' => (Token::RParen, 1),
+            ',' => (Token::Comma, 1),
+            ':' => (Token::Colon, 1),
+            '@' => (Token::At, 1),
+            '[' if next == Some(']') => (Token::Flatten, 2),
+            '[' if next == Some('?') => (Token::Filter, 2),
+            '[' => (Token::LBracket, 1),
+            '|' if next == Some('|') => (Token::Or, 2),
+            '|' => (Token::Pipe, 1),
+            '&' if next == Some('&') => (Token::And, 2),
+            '&' => (Token::Ampersand, 1),
+            '!' if next == Some('=') => (Token::Compare(Comparator::Ne), 2),
+            '!' => (Token::Not, 1),
+            '=' if next == Some('=') => (Token::Compare(Comparator::Eq), 2),
+            '<' if next == Some('=') => (Token::Compare(Comparator::Le), 2),
+            '<' => (Token::Compare(Comparator::Lt), 1),
+            '>' if next == Some('=') => (Token::Compare(Comparator::Ge), 2),
+            '>' => (Token::Compare(Comparator::Gt), 1),
+            '-' | '0'..='9' => {
+                let digits = chars[i + 1..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_digit())
+                    .count();
+                let len = digits + 1;
+                let text: String = chars[i..i + len].iter().collect();
+                let number = text.parse().map_err(|_| {
+                    anyhow!(t!(
+                        "query-unexpected",
+                        token = format!("'{text}'"),
+                        column = i + 1
+                    ))
+                })?;
+                (Token::Number(number), len)
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let len = chars[i..]
+                    .iter()
+                    .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+                    .count();
+                (Token::Ident(chars[i..i + len].iter().collect()), len)
+            }
+            quote @ ('"' | '\'' | '`') => {
+                let Some(close) = closing(chars, i, quote) else {
+                    bail!(t!("query-unterminated", quote = quote, column = i + 1));
+                };
+                let inner: String = chars[i + 1..close].iter().collect();
+                let token = match quote {
+                    '"' => Token::Quoted(serde_json::from_str(&format!("\"{inner}\"")).map_err(
+                        |e| anyhow!(t!("query-bad-literal", column = i + 1, error = e)),
+                    )?),
+                    '\'' => Token::Literal(Value::String(inner.replace("\\'", "'"))),
+                    _ => Token::Literal(serde_json::from_str(&inner.replace("\\`", "`")).map_err(
+                        |e| anyhow!(t!("query-bad-literal", column = i + 1, error = e)),
+                    )?),
+                };
+                (token, close + 1 - i)
+            }
+            c => bail!(t!(
+                "query-unexpected",
+                token = format!("'{c}'"),
+                column = i + 1
+            )),
+        };
+        i += len;
+        tokens.push(Spanned {
+            token,
+            start,
+            end: i,
+        });
+    }
+    tokens.push(Spanned {
+        token: Token::Eof,
+        start: chars.len(),
+        end: chars.len(),
+    });
+    Ok(tokens)
+}
+
+/// Index of the `quote` closing the one at `open`, skipping escaped ones.
+fn closing(chars: &[char], open: usize, quote: char) -> Option<usize> {
+    let mut i = open + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' => i += 2,
+            c if c == quote => return Some(i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+/// How tightly a token binds the expression on its left; JMESPath's table.
+fn binding_power(token: &Token) -> u8 {
+    match token {
+        Token::Pipe => 1,
+        Token::Or => 2,
+        Token::And => 3,
+        Token::Compare(_) => 5,
+        Token::Flatten => 9,
+        Token::Star => 20,
+        Token::Filter => 21,
+        Token::Dot => 40,
+        Token::Not => 45,
+        Token::LBrace => 50,
+        Token::LBracket => 55,
+        Token::LParen => 60,
+        _ => 0,
+    }
+}
+
+/// Below this, a token ends a projection instead of applying to each element.
+const PROJECTION_STOP: u8 = 10;
+
+/// A Pratt parser following the JMESPath reference implementation.
+struct Parser {
+    tokens: Vec<Spanned>,
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.pos].token
+    }
+
+    fn peek_at(&self, ahead: usize) -> &Token {
+        self.tokens
+            .get(self.pos + ahead)
+            .map_or(&Token::Eof, |t| &t.token)
+    }
+
+    fn advance(&mut self) {
+        if self.pos + 1 < self.tokens.len() {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, token: Token) -> Result<()> {
+        if *self.peek() != token {
+            return Err(self.unexpected());
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn unexpected(&self) -> anyhow::Error {
+        let Spanned { token, start, end } = &self.tokens[self.pos];
+        let text = if *token == Token::Eof {
+            t!("query-end")
+        } else {
+            format!("'{}'", self.chars[*start..*end].iter().collect::<String>())
+        };
+        anyhow!(t!("query-unexpected", token = text, column = start + 1))
+    }
+
+    fn expression(&mut self, rbp: u8) -> Result<Ast> {
+        let mut left = self.nud()?;
+        while rbp < binding_power(self.peek()) {
+            left = self.led(left)?;
+        }
+        Ok(left)
+    }
+
+    /// An expression starting at the current token.
+    fn nud(&mut self) -> Result<Ast> {
+        let token = self.peek().clone();
+        match token {
+            Token::Filter => return self.filter(Ast::Current),
+            Token::Literal(_)
+            | Token::Ident(_)
+            | Token::Quoted(_)
+            | Token::Star
+            | Token::LBrace
+            | Token::Flatten
+            | Token::LBracket
+            | Token::At
+            | Token::Not
+            | Token::LParen
+            | Token::Ampersand => self.advance(),
+            _ => return Err(self.unexpected()),
+        }
+        match token {
+            Token::Literal(value) => Ok(Ast::Literal(value)),
+            Token::Ident(name) => Ok(Ast::Field(name)),
+            Token::Quoted(name) => {
+                if *self.peek() == Token::LParen {
+                    return Err(self.unexpected());
+                }
+                Ok(Ast::Field(name))
+            }
+            Token::Star => {
+                let rhs = self.projection_rhs(binding_power(&Token::Star))?;
+                Ok(Ast::ValueProjection(Box::new(Ast::Current), Box::new(rhs)))
+            }
+            Token::LBrace => self.hash(),
+            Token::Flatten => {
+                let rhs = self.projection_rhs(binding_power(&Token::Flatten))?;
+                Ok(Ast::Projection(
+                    Box::new(Ast::Flatten(Box::new(Ast::Current))),
+                    Box::new(rhs),
+                ))
+            }
+            Token::LBracket => match self.peek() {
+                Token::Number(_) | Token::Colon => {
+                    let index = self.index()?;
+                    self.project_if_slice(Ast::Current, index)
+                }
+                Token::Star if *self.peek_at(1) == Token::RBracket => {
+                    self.advance();
+                    self.advance();
+                    let rhs = self.projection_rhs(binding_power(&Token::Star))?;
+                    Ok(Ast::Projection(Box::new(Ast::Current), Box::new(rhs)))
+                }
+                _ => self.list(),
+            },
+            Token::At => Ok(Ast::Current),
+            Token::Not => Ok(Ast::Not(Box::new(
+                self.expression(binding_power(&Token::Not))?,
+            ))),
+            Token::LParen => {
+                let inner = self.expression(0)?;
+                self.expect(Token::RParen)?;
+                Ok(inner)
+            }
+            _ => Ok(Ast::ExpRef(Box::new(self.expression(0)?))),
+        }
+    }
+
+    /// The current token applied to the expression on its left.
+    fn led(&mut self, left: Ast) -> Result<Ast> {
+        let token = self.peek().clone();
+        let bp = binding_power(&token);
+        match token {
+            Token::Filter => return self.filter(left),
+            Token::LParen => return self.call(left),
+            Token::Star | Token::Not | Token::LBrace => return Err(self.unexpected()),
+            _ => self.advance(),
+        }
+        match token {
+            Token::Dot if *self.peek() == Token::Star => {
+                self.advance();
+                let rhs = self.projection_rhs(bp)?;
+                Ok(Ast::ValueProjection(Box::new(left), Box::new(rhs)))
+            }
+            Token::Dot => {
+                let rhs = self.dot_rhs(bp)?;
+                Ok(Ast::Subexpr(Box::new(left), Box::new(rhs)))
+            }
+            Token::Pipe => Ok(Ast::Pipe(Box::new(left), Box::new(self.expression(bp)?))),
+            Token::Or => Ok(Ast::Or(Box::new(left), Box::new(self.expression(bp)?))),
+            Token::And => Ok(Ast::And(Box::new(left), Box::new(self.expression(bp)?))),
+            Token::Compare(op) => Ok(Ast::Compare(
+                op,
+                Box::new(left),
+                Box::new(self.expression(bp)?),
+            )),
+            Token::Flatten => {
+                let rhs = self.projection_rhs(bp)?;
+                Ok(Ast::Projection(
+                    Box::new(Ast::Flatten(Box::new(left))),
+                    Box::new(rhs),
+                ))
+            }
+            _ => match self.peek() {
+                Token::Number(_) | Token::Colon => {
+                    let index = self.index()?;
+                    self.project_if_slice(left, index)
+                }
+                _ => {
+                    self.expect(Token::Star)?;
+                    self.expect(Token::RBracket)?;
+                    let rhs = self.projection_rhs(binding_power(&Token::Star))?;
+                    Ok(Ast::Projection(Box::new(left), Box::new(rhs)))
+                }
+            },
+        }
+    }
+
+    /// `[?condition]` at the current token, then what applies to each match.
+    fn filter(&mut self, left: Ast) -> Result<Ast> {
+        self.advance();
+        let condition = self.expression(0)?;
+        self.expect(Token::RBracket)?;
+        let rhs = if *self.peek() == Token::Flatten {
+            Ast::Current
+        } else {
+            self.projection_rhs(binding_power(&Token::Filter))?
+        };
+        Ok(Ast::Filter(
+            Box::new(left),
+            Box::new(condition),
+            Box::new(rhs),
+        ))
+    }
+
+    /// `name(args...)`, with the name already parsed as a field.
+    fn call(&mut self, left: Ast) -> Result<Ast> {
+        let Ast::Field(name) = left else {
+            return Err(self.unexpected());
+        };
+        self.advance();
+        let mut args = Vec::new();
+        while *self.peek() != Token::RParen {
+            args.push(self.expression(0)?);
+            if *self.peek() == Token::Comma {
+                self.advance();
+            } else if *self.peek() != Token::RParen {
+                return Err(self.unexpected());
+            }
+        }
+        self.advance();
+
+        let Some(&(_, min, max)) = FUNCTIONS.iter().find(|(f, _, _)| *f == name) else {
+            bail!(t!("query-unknown-function", name = name));
+        };
+        if args.len() < min || args.len() > max {
+            bail!(if min == max {
+                t!(
+                    "query-arity",
+                    name = name,
+                    expected = min,
+                    count = args.len()
+                )
+            } else {
+                t!(
+                    "query-arity-min",
+                    name = name,
+                    expected = min,
+                    count = args.len()
+                )
+            });
+        }
+        Ok(Ast::Call(name, args))
+    }
+
+    /// What a projection applies to each element: whatever follows that
+    /// binds tighter than a pipe or comparison.
+    fn projection_rhs(&mut self, bp: u8) -> Result<Ast> {
+        match self.peek() {
+            token if binding_power(token) < PROJECTION_STOP => Ok(Ast::Current),
+            Token::LBracket | Token::Filter => self.expression(bp),
+            Token::Dot => {
+                self.advance();
+                self.dot_rhs(bp)
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    fn dot_rhs(&mut self, bp: u8) -> Result<Ast> {
+        match self.peek() {
+            Token::Ident(_) | Token::Quoted(_) | Token::Star => self.expression(bp),
+            Token::LBracket => {
+                self.advance();
+                self.list()
+            }
+            Token::LBrace => {
+                self.advance();
+                self.hash()
+            }
+            _ => Err(self.unexpected()),
+        }
+    }
+
+    /// `[n]` or `[start:stop:step]`, after the `[`.
+    fn index(&mut self) -> Result<Ast> {
+        if let (Token::Number(n), Token::RBracket) = (self.peek().clone(), self.peek_at(1)) {
+            self.advance();
+            self.advance();
+            return Ok(Ast::Index(n));
+        }
+        let mut parts = [None; 3];
+        let mut part = 0;
+        loop {
+            match self.peek().clone() {
+                Token::Colon if part < 2 => part += 1,
+                Token::Number(n) if parts[part].is_none() => parts[part] = Some(n),
+                Token::RBracket => break,
+                _ => return Err(self.unexpected()),
+            }
+            self.advance();
+        }
+        if parts[2] == Some(0) {
+            bail!(t!("query-step-zero"));
+        }
+        self.advance();
+        Ok(Ast::Slice(parts[0], parts[1], parts[2]))
+    }
+
+    fn project_if_slice(&mut self, left: Ast, index: Ast) -> Result<Ast> {
+        let is_slice = matches!(index, Ast::Slice(..));
+        let indexed = Ast::Subexpr(Box::new(left), Box::new(index));
+        if !is_slice {
+            return Ok(indexed);
+        }
+        let rhs = self.projection_rhs(binding_power(&Token::Star))?;
+        Ok(Ast::Projection(Box::new(indexed), Box::new(rhs)))
+    }
+
+    /// `[a, b]`, after the `[`.
+    fn list(&mut self) -> Result<Ast> {
+        let mut items = Vec::new();
+        loop {
+            items.push(self.expression(0)?);
+            if *self.peek() != Token::Comma {
+                self.expect(Token::RBracket)?;
+                return Ok(Ast::List(items));
+            }
+            self.advance();
+        }
+    }
+
+    /// `{key: a, other: b}`, after the `{`.
+    fn hash(&mut self) -> Result<Ast> {
+        let mut entries = Vec::new();
+        loop {
+            let (Token::Ident(key) | Token::Quoted(key)) = self.peek().clone() else {
+                return Err(self.unexpected());
+            };
+            self.advance();
+            self.expect(Token::Colon)?;
+            entries.push((key, self.expression(0)?));
+            if *self.peek() != Token::Comma {
+                self.expect(Token::RBrace)?;
+                return Ok(Ast::Hash(entries));
+            }
+            self.advance();
+        }
+    }
+}
+
+fn eval(ast: &Ast, value: &Value) -> Result<Value> {
+    Ok(match ast {
+        Ast::Current => value.clone(),
+        Ast::Field(name) => value.get(name.as_str()).cloned().unwrap_or(Value::Null),
+        Ast::Index(index) => match value {
+            Value::Array(items) => {
+                resolve(items.len(), *index).map_or(Value::Null, |i| items[i].clone())
+            }
+            _ => Value::Null,
+        },
+        Ast::Slice(start, stop, step) => match value {
+            Value::Array(items) => Value::Array(slice(items, *start, *stop, *step)),
+            _ => Value::Null,
+        },
+        Ast::Literal(literal) => literal.clone(),
+        Ast::Subexpr(left, right) | Ast::Pipe(left, right) => eval(right, &eval(left, value)?)?,
+        Ast::Projection(left, right) => match eval(left, value)? {
+            Value::Array(items) => project(&items, right)?,
+            _ => Value::Null,
+        },
+        Ast::ValueProjection(left, right) => match eval(left, value)? {
+            Value::Object(map) => {
+                project(&map.into_iter().map(|(_, v)| v).collect::<Vec<_>>(), right)?
+            }
+            _ => Value::Null,
+        },
+        Ast::Filter(left, condition, right) => match eval(left, value)? {
+            Value::Array(items) => {
+                let mut kept = Vec::new();
+                for item in items {
+                    if truthy(&eval(condition, &item)?) {
+                        kept.push(item);
+                    }
+                }
+                project(&kept, right)?
+            }
+            _ => Value::Null,
+        },
+        Ast::Flatten(inner) => match eval(inner, value)? {
+            Value::Array(items) => Value::Array(
+                items
+                    .into_iter()
+                    .flat_map(|item| match item {
+                        Value::Array(nested) => nested,
+                        other => vec![other],
+                    })
+                    .collect(),
+            ),
+            _ => Value::Null,
+        },
+        Ast::Or(left, right) => {
+            let left = eval(left, value)?;
+            if truthy(&left) {
+                left
+            } else {
+                eval(right, value)?
+            }
+        }
+        Ast::And(left, right) => {
+            let left = eval(left, value)?;
+            if truthy(&left) {
+                eval(right, value)?
+            } else {
+                left
+            }
+        }
+        Ast::Not(inner) => Value::Bool(!truthy(&eval(inner, value)?)),
+        Ast::Compare(op, left, right) => compare(*op, &eval(left, value)?, &eval(right, value)?),
+        Ast::List(_) | Ast::Hash(_) if value.is_null() => Value::Null,
+        Ast::List(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| eval(item, value))
+                .collect::<Result<_>>()?,
+        ),
+        Ast::Hash(entries) => {
+            let mut map = Map::new();
+            for (key, entry) in entries {
+                map.insert(key.clone(), eval(entry, value)?);
+            }
+            Value::Object(map)
+        }
+        Ast::Call(name, args) => call(name, args, value)?,
+        Ast::ExpRef(_) => bail!(t!("query-bad-expref")),
+    })
+}
+
+/// `right` of each item, leaving out nulls.
+fn project(items: &[Value], right: &Ast) -> Result<Value> {
+    let mut projected = Vec::new();
+    for item in items {
+        let value = eval(right, item)?;
+        if !value.is_null() {
+            projected.push(value);
+        }
+    }
+    Ok(Value::Array(projected))
+}
+
+/// False, null, and empty strings, arrays and objects are false; 0 isn't.
+fn truthy(value: &Value) -> bool {
+    match value {
+        Value::Null | Value::Bool(false) => false,
+        Value::String(s) => !s.is_empty(),
+        Value::Array(items) => !items.is_empty(),
+        Value::Object(map) => !map.is_empty(),
+        _ => true,
+    }
+}
+
+/// Equality treats 1 and 1.0 alike; order is defined between two numbers
+/// or two strings, and is null otherwise.
+fn compare(op: Comparator, left: &Value, right: &Value) -> Value {
+    let ordering = order(left, right);
+    Value::Bool(match op {
+        Comparator::Eq => ordering == Some(Ordering::Equal) || left == right,
+        Comparator::Ne => !(ordering == Some(Ordering::Equal) || left == right),
+        _ => match ordering {
+            Some(ordering) => match op {
+                Comparator::Lt => ordering.is_lt(),
+                Comparator::Le => ordering.is_le(),
+                Comparator::Gt => ordering.is_gt(),
+                _ => ordering.is_ge(),
+            },
+            None => return Value::Null,
+        },
+    })
+}
+
+fn order(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64()?.partial_cmp(&r.as_f64()?),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+/// A possibly negative index into `len` items.
+fn resolve(len: usize, index: i64) -> Option<usize> {
+    let index = if index < 0 { len as i64 + index } else { index };
+    (0..len as i64).contains(&index).then_some(index as usize)
+}
+
+/// Python-style slicing, as JMESPath defines it.
+fn slice(items: &[Value], start: Option<i64>, stop: Option<i64>, step: Option<i64>) -> Vec<Value> {
+    let len = items.len() as i64;
+    let step = step.unwrap_or(1);
+    let bound = |index: Option<i64>, default: i64| match index {
+        None => default,
+        Some(i) if i < 0 => (i + len).max(if step < 0 { -1 } else { 0 }),
+        Some(i) => i.min(if step < 0 { len - 1 } else { len }),
+    };
+    let (mut i, stop) = if step < 0 {
+        (bound(start, len - 1), bound(stop, -1))
+    } else {
+        (bound(start, 0), bound(stop, len))
+    };
+    let mut sliced = Vec::new();
+    while (step > 0 && i < stop) || (step < 0 && i > stop) {
+        sliced.push(items[i as usize].clone());
+        i += step;
+    }
+    sliced
+}
+
+fn call(name: &str, args: &[Ast], value: &Value) -> Result<Value> {
+    let arg = |i: usize| eval(&args[i], value);
+    let bad = |i: usize, v: &Value| {
+        anyhow!(t!(
+            "query-bad-arg",
+            name = name,
+            kind = type_name(v),
+            position = i + 1
+        ))
+    };
+    let expref = |i: usize| match &args[i] {
+        Ast::ExpRef(inner) => Ok(inner.as_ref()),
+        other => Err(bad(i, &eval(other, value).unwrap_or(Value::Null))),
+    };
+    let array = |i: usize| match arg(i)? {
+        Value::Array(items) => Ok(items),
+        other => Err(bad(i, &other)),
+    };
+    let string = |i: usize| match arg(i)? {
+        Value::String(s) => Ok(s),
+        other => Err(bad(i, &other)),
+    };
+    let numbers = |i: usize| -> Result<Vec<f64>> {
+        let items = array(i)?;
+        items
+            .iter()
+            .map(|item| item.as_f64().ok_or_else(|| bad(i, item)))
+            .collect()
+    };
+    // Sort keys must all be numbers or all strings
+    let sortable = |i: usize, keys: &[Value]| -> Result<()> {
+        let odd = keys
+            .iter()
+            .find(|k| !(k.is_number() || k.is_string()) || type_name(k) != type_name(&keys[0]));
+        match odd {
+            Some(key) => Err(bad(i, key)),
+            None => Ok(()),
+        }
+    };
+
+    Ok(match name {
+        "abs" => match arg(0)? {
+            Value::Number(n) => number(n.as_f64().unwrap_or_default().abs()),
+            other => return Err(bad(0, &other)),
+        },
+        "avg" => {
+            let numbers = numbers(0)?;
+            if numbers.is_empty() {
+                Value::Null
+            } else {
+                number(numbers.iter().sum::<f64>() / numbers.len() as f64)
+            }
+        }
+        "contains" => match arg(0)? {
+            Value::Array(items) => {
+                let search = arg(1)?;
+                Value::Bool(
+                    items
+                        .iter()
+                        .any(|item| compare(Comparator::Eq, item, &search) == Value::Bool(true)),
+                )
+            }
+            Value::String(s) => Value::Bool(s.contains(string(1)?.as_str())),
+            other => return Err(bad(0, &other)),
+        },
+        "ends_with" => Value::Bool(string(0)?.ends_with(string(1)?.as_str())),
+        "join" => {
+            let separator = string(0)?;
+            let parts = array(1)?;
+            let parts: Vec<&str> = parts
+                .iter()
+                .map(|p| p.as_str().ok_or_else(|| bad(1, p)))
+                .collect::<Result<_>>()?;
+            Value::String(parts.join(&separator))
+        }
+        "keys" => match arg(0)? {
+            Value::Object(map) => Value::Array(map.keys().cloned().map(Value::String).collect()),
+            other => return Err(bad(0, &other)),
+        },
+        "length" => match arg(0)? {
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(items) => Value::from(items.len()),
+            Value::Object(map) => Value::from(map.len()),
+            other => return Err(bad(0, &other)),
+        },
+        "map" => {
+            let inner = expref(0)?;
+            Value::Array(
+                array(1)?
+                    .iter()
+                    .map(|item| eval(inner, item))
+                    .collect::<Result<_>>()?,
+            )
+        }
+        "max" | "min" => {
+            let items = array(0)?;
+            sortable(0, &items)?;
+            let pick = |a: &&Value, b: &&Value| order(a, b).unwrap_or(Ordering::Equal);
+            let found = if name == "max" {
+                items.iter().max_by(pick)
+            } else {
+                items.iter().min_by(pick)
+            };
+            found.cloned().unwrap_or(Value::Null)
+        }
+        "max_by" | "min_by" => {
+            let items = array(0)?;
+            let keyed = keyed(&items, expref(1)?)?;
+            let keys: Vec<Value> = keyed.iter().map(|(k, _)| k.clone()).collect();
+            sortable(1, &keys)?;
+            let pick = |a: &&(Value, &Value), b: &&(Value, &Value)| {
+                order(&a.0, &b.0).unwrap_or(Ordering::Equal)
+            };
+            let found = if name == "max_by" {
+                keyed.iter().max_by(pick)
+            } else {
+                keyed.iter().min_by(pick)
+            };
+            found.map_or(Value::Null, |(_, item)| (*item).clone())
+        }
+        "not_null" => {
+            for i in 0..args.len() {
+                let value = arg(i)?;
+                if !value.is_null() {
+                    return Ok(value);
+                }
+            }
+            Value::Null
+        }
+        "reverse" => match arg(0)? {
+            Value::Array(mut items) => {
+                items.reverse();
+                Value::Array(items)
+            }
+            Value::String(s) => Value::String(s.chars().rev().collect()),
+            other => return Err(bad(0, &other)),
+        },
+        "sort" => {
+            let mut items = array(0)?;
+            sortable(0, &items)?;
+            items.sort_by(|a, b| order(a, b).unwrap_or(Ordering::Equal));
+            Value::Array(items)
+        }
+        "sort_by" => {
+            let items = array(0)?;
+            let mut keyed = keyed(&items, expref(1)?)?;
+            let keys: Vec<Value> = keyed.iter().map(|(k, _)| k.clone()).collect();
+            sortable(1, &keys)?;
+            keyed.sort_by(|a, b| order(&a.0, &b.0).unwrap_or(Ordering::Equal));
+            Value::Array(keyed.into_iter().map(|(_, item)| item.clone()).collect())
+        }
+        "starts_with" => Value::Bool(string(0)?.starts_with(string(1)?.as_str())),
+        "sum" => number(numbers(0)?.iter().sum()),
+        "to_number" => match arg(0)? {
+            Value::Number(n) => Value::Number(n),
+            Value::String(s) => s.trim().parse().map_or(Value::Null, number),
+            _ => Value::Null,
+        },
+        "to_string" => match arg(0)? {
+            Value::String(s) => Value::String(s),
+            other => Value::String(other.to_string()),
+        },
+        "type" => Value::String(type_name(&arg(0)?).to_string()),
+        "values" => match arg(0)? {
+            Value::Object(map) => Value::Array(map.into_iter().map(|(_, v)| v).collect()),
+            other => return Err(bad(0, &other)),
+        },
+        _ => bail!(t!("query-unknown-function", name = name)),
+    })
+}
+
+/// Each item with the key `by` gives it.
+fn keyed<'a>(items: &'a [Value], by: &Ast) -> Result<Vec<(Value, &'a Value)>> {
+    items
+        .iter()
+        .map(|item| Ok((eval(by, item)?, item)))
+        .collect()
+}
+
+/// Whole numbers stay integers, so sums of sizes print as they would in
+/// the report.
+fn number(n: f64) -> Value {
+    if n.fract() == 0.0 && n.abs() < 9_007_199_254_740_992.0 {
+        Value::from(n as i64)
+    } else {
+        Number::from_f64(n).map_or(Value::Null, Value::Number)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn query(text: &str, value: Value) -> Value {
+        Query::parse(text)
+            .and_then(|q| q.apply(&value))
+            .unwrap_or_else(|e| panic!("{text}: {e:#}"))
+    }
+
+    fn parse_error(text: &str) -> String {
+        match Query::parse(text) {
+            Ok(q) => panic!("{text} parsed as {q:?}"),
+            Err(e) => format!("{e:#}"),
+        }
+    }
+
+    fn eval_error(text: &str, value: Value) -> String {
+        let q = Query::parse(text).unwrap_or_else(|e| panic!("{text}: {e:#}"));
+        match q.apply(&value) {
+            Ok(v) => panic!("{text} gave {v}"),
+            Err(e) => format!("{e:#}"),
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        let v = json!({"a": true, "b": false, "c": false, "n": 2});
+        // && binds tighter than ||
+        assert_eq!(query("a || b && c", v.clone()), json!(true));
+        assert_eq!(query("(a || b) && c", v.clone()), json!(false));
+        // ! binds tighter than &&
+        assert_eq!(query("!b && c", v.clone()), json!(false));
+        assert_eq!(query("!(b && c)", v.clone()), json!(true));
+        // Comparisons bind tighter than && and ||
+        assert_eq!(query("n == `2` && a", v.clone()), json!(true));
+        assert_eq!(query("b || n > `1`", v.clone()), json!(true));
+        // || and && return an operand, not a boolean
+        assert_eq!(query("missing || n", v.clone()), json!(2));
+        assert_eq!(query("a && n", v), json!(2));
+    }
+
+    #[test]
+    fn projections() {
+        let v = json!({"people": [
+            {"name": "a", "age": 30, "tags": ["x", "y"]},
+            {"name": "b", "tags": ["z"]},
+            {"name": "c", "age": 50, "tags": []},
+        ]});
+        // Nulls are dropped from a projection
+        assert_eq!(query("people[*].age", v.clone()), json!([30, 50]));
+        assert_eq!(query("people[*].tags[0]", v.clone()), json!(["x", "z"]));
+        assert_eq!(query("people[].tags[]", v.clone()), json!(["x", "y", "z"]));
+        assert_eq!(query("people[0:2].name", v.clone()), json!(["a", "b"]));
+        assert_eq!(
+            query("{x: {a: `1`}, y: {a: `2`}}.*.a", json!({})),
+            json!([1, 2])
+        );
+        assert_eq!(query("*.a", json!({"x": {"a": 1}, "y": {}})), json!([1]));
+    }
+
+    #[test]
+    fn pipe_ends_projection() {
+        let v = json!({"foo": [{"bar": [1, 2]}, {"bar": [3, 4]}]});
+        assert_eq!(query("foo[*].bar[0]", v.clone()), json!([1, 3]));
+        assert_eq!(query("foo[*].bar | [0]", v.clone()), json!([1, 2]));
+        assert_eq!(query("foo[*].bar | length(@)", v), json!(2));
+    }
+
+    #[test]
+    fn indexes_and_slices() {
+        let v = json!([0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(query("[-1]", v.clone()), json!(9));
+        assert_eq!(query("[10]", v.clone()), json!(null));
+        assert_eq!(query("[:2]", v.clone()), json!([0, 1]));
+        assert_eq!(query("[-2:]", v.clone()), json!([8, 9]));
+        assert_eq!(query("[::3]", v.clone()), json!([0, 3, 6, 9]));
+        assert_eq!(query("[::-1]", v.clone()), json!([9, 8, 7, 6, 5, 4, 3, 2, 1, 0]));
+        assert_eq!(query("[::-3]", v.clone()), json!([9, 6, 3, 0]));
+        assert_eq!(query("[5:1:-2]", v.clone()), json!([5, 3]));
+        assert_eq!(query("[-3::-1]", v.clone()), json!([7, 6, 5, 4, 3, 2, 1, 0]));
+        assert_eq!(query("[1:5:-1]", v.clone()), json!([]));
+        assert_eq!(query("[100:]", v.clone()), json!([]));
+        assert_eq!(query("[:-100:-1]", v), json!([9, 8, 7, 6, 5, 4, 3, 2, 1, 0]));
+        assert_eq!(query("[0]", json!({"a": 1})), json!(null));
+        assert!(parse_error("[::0]").contains("step can't be 0"));
+    }
+
+    #[test]
+    fn filters() {
+        let v = json!({"layers": [
+            {"digest": "a", "size": 10, "files": ["x"]},
+            {"digest": "b", "size": 2000, "files": []},
+            {"digest": "c", "size": 3000},
+        ]});
+        assert_eq!(
+            query("layers[?size > `1000`].digest", v.clone()),
+            json!(["b", "c"])
+        );
+        assert_eq!(query("layers[?digest == 'a'].size", v.clone()), json!([10]));
+        assert_eq!(query("layers[?files].digest", v.clone()), json!(["a"]));
+        assert_eq!(query("layers[?!files].digest", v.clone()), json!(["b", "c"]));
+        assert_eq!(
+            query("layers[?size >= `2000` && digest != 'c'].digest", v.clone()),
+            json!(["b"])
+        );
+        // Ordering a number against a string is null, so nothing matches
+        assert_eq!(query("layers[?size < 'z'].digest", v.clone()), json!([]));
+        assert_eq!(query("layers[?size > `1000`] | [0].digest", v), json!("b"));
+    }
+
+    #[test]
+    fn literals() {
+        let v = json!({"a": 1});
+        assert_eq!(query("`{\"b\": [1, 2]}`", v.clone()), json!({"b": [1, 2]}));
+        assert_eq!(query("`\"text\"`", v.clone()), json!("text"));
+        assert_eq!(query("'raw `text`'", v.clone()), json!("raw `text`"));
+        assert_eq!(query("'it\\'s'", v.clone()), json!("it's"));
+        assert_eq!(query("`1.0` == `1`", v.clone()), json!(true));
+        assert_eq!(query("\"a\"", v.clone()), json!(1));
+        assert_eq!(query("[a, `2`]", v.clone()), json!([1, 2]));
+        assert_eq!(query("{x: a, y: 'y'}", v), json!({"x": 1, "y": "y"}));
+        // Multi-selects of null are null
+        assert_eq!(query("missing.[a]", json!({})), json!(null));
+    }
+
+    #[test]
+    fn functions() {
+        let v = json!({"people": [
+            {"name": "a", "age": 30},
+            {"name": "b", "age": 20},
+        ]});
+        assert_eq!(query("length(people)", v.clone()), json!(2));
+        assert_eq!(query("sort_by(people, &age)[0].name", v.clone()), json!("b"));
+        assert_eq!(query("max_by(people, &age).name", v.clone()), json!("a"));
+        assert_eq!(query("sum(people[*].age)", v.clone()), json!(50));
+        assert_eq!(query("join(', ', people[*].name)", v.clone()), json!("a, b"));
+        assert_eq!(query("map(&age, people)", v.clone()), json!([30, 20]));
+        assert_eq!(query("not_null(missing, people[0].age)", v), json!(30));
+    }
+
+    #[test]
+    fn errors_name_the_column() {
+        let e = parse_error("foo bar");
+        assert!(e.contains("'bar'") && e.contains("column 5"), "{e}");
+        let e = parse_error("foo.");
+        assert!(e.contains("column 5"), "{e}");
+        let e = parse_error("a[?b == 'c]");
+        assert!(e.contains("column 9"), "{e}");
+        let e = parse_error("a || `{`");
+        assert!(e.contains("column 6"), "{e}");
+        assert!(parse_error("nope(a)").contains("nope()"));
+        assert!(parse_error("length(a, b)").contains("length()"));
+        assert!(eval_error("length(`1`)", json!(null)).contains("argument 1"));
+        assert!(eval_error("&a", json!(null)).contains("sort_by()"));
+    }
+}
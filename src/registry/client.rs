@@ -0,0 +1,476 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result, bail};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use serde::Deserialize;
+use ureq::{Agent, BodyReader, RequestBuilder};
+
+use super::reference::{DOCKER_HUB, Reference, api_base};
+use super::trace::Trace;
+
+/// Sent with every request so registry operators can tell peel apart in their logs.
+const USER_AGENT: &str = concat!(
+    "peel/",
+    env!("CARGO_PKG_VERSION"),
+    " (+",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+/// Manifest media types we can read, most specific first.
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// A content descriptor: digest and size of a blob or child manifest.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Descriptor {
+    pub digest: String,
+    #[serde(default)]
+    pub size: u64,
+    /// Set on the entries of an index
+    #[serde(default)]
+    pub platform: Option<Platform>,
+}
+
+/// Which OS and CPU an index entry is built for.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Platform {
+    pub os: String,
+    pub architecture: String,
+    pub variant: Option<String>,
+}
+
+impl fmt::Display for Platform {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.os, self.architecture)?;
+        if let Some(variant) = &self.variant {
+            write!(f, "/{variant}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Either an image manifest (config + layers) or an index of per-platform manifests.
+#[derive(Debug, Deserialize)]
+pub struct Manifest {
+    #[serde(default)]
+    pub manifests: Vec<Descriptor>,
+    pub config: Option<Descriptor>,
+    #[serde(default)]
+    pub layers: Vec<Descriptor>,
+}
+
+impl Manifest {
+    pub fn is_index(&self) -> bool {
+        self.config.is_none() && !self.manifests.is_empty()
+    }
+}
+
+/// What to send with a request.
+pub enum Body<'a> {
+    Empty,
+    Bytes(&'a [u8]),
+    /// Read from disk, so large layers are never held in memory
+    File(&'a Path),
+    /// A manifest with its media type
+    Manifest(&'a str, &'a [u8]),
+}
+
+#[derive(Deserialize)]
+struct DockerConfig {
+    #[serde(default)]
+    auths: HashMap<String, DockerAuth>,
+}
+
+#[derive(Deserialize)]
+struct DockerAuth {
+    auth: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    token: Option<String>,
+    access_token: Option<String>,
+}
+
+/// Minimal client for the OCI distribution API.
+///
+/// Authenticates with anonymous or `~/.docker/config.json` credentials, using
+/// the bearer token flow when the registry asks for it.
+pub struct Client {
+    agent: Agent,
+    credentials: HashMap<String, String>,
+    /// `Authorization` header per registry and repository
+    tokens: HashMap<(String, String), String>,
+    trace: Option<Trace>,
+}
+
+impl Client {
+    pub fn new(trace: Option<Trace>) -> Self {
+        let agent = Agent::config_builder()
+            .http_status_as_error(false)
+            .user_agent(USER_AGENT)
+            .build()
+            .into();
+        Self {
+            agent,
+            credentials: load_credentials(),
+            tokens: HashMap::new(),
+            trace,
+        }
+    }
+
+    /// Fetch the manifest (or index) that `reference` points at.
+    pub fn manifest(&mut self, reference: &Reference) -> Result<Manifest> {
+        let (_, body) = self.manifest_raw(reference)?;
+        serde_json::from_slice(&body)
+            .with_context(|| t!("registry-bad-manifest", reference = reference))
+    }
+
+    /// Fetch a manifest's media type and exact bytes, for when it has to be
+    /// pushed back unchanged (its digest is over those bytes).
+    pub fn manifest_raw(&mut self, reference: &Reference) -> Result<(String, Vec<u8>)> {
+        let url = format!(
+            "{}/v2/{}/manifests/{}",
+            reference.api_base(),
+            reference.repository,
+            reference.reference
+        );
+        let mut response = self.send(
+            &reference.registry,
+            &reference.repository,
+            "GET",
+            &url,
+            &Body::Empty,
+        )?;
+        let status = response.status().as_u16();
+        if status != 200 {
+            bail!(t!(
+                "registry-manifest-failed",
+                reference = reference,
+                status = status
+            ));
+        }
+        let media_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = response
+            .body_mut()
+            .read_to_vec()
+            .with_context(|| t!("registry-read-failed", url = &url))?;
+        if let Some(trace) = &self.trace {
+            trace.dump("manifest.json", &body);
+        }
+        Ok((media_type, body))
+    }
+
+    /// Upload a manifest under `reference` (a tag or its digest).
+    pub fn put_manifest(
+        &mut self,
+        registry: &str,
+        repository: &str,
+        reference: &str,
+        media_type: &str,
+        data: &[u8],
+    ) -> Result<()> {
+        let url = format!(
+            "{}/v2/{repository}/manifests/{reference}",
+            api_base(registry)
+        );
+        let response = self.send(
+            registry,
+            repository,
+            "PUT",
+            &url,
+            &Body::Manifest(media_type, data),
+        )?;
+        match response.status().as_u16() {
+            200 | 201 => Ok(()),
+            status => bail!(t!("registry-push-failed", url = &url, status = status)),
+        }
+    }
+
+    /// Stream the blob `digest` out of `repository`.
+    pub fn fetch_blob(
+        &mut self,
+        registry: &str,
+        repository: &str,
+        digest: &str,
+    ) -> Result<BodyReader<'static>> {
+        let url = format!("{}/v2/{repository}/blobs/{digest}", api_base(registry));
+        let response = self.send(registry, repository, "GET", &url, &Body::Empty)?;
+        let status = response.status().as_u16();
+        if status != 200 {
+            bail!(t!("registry-blob-failed", url = &url, status = status));
+        }
+        Ok(response.into_body().into_reader())
+    }
+
+    /// Ask the registry to link a blob it already holds in `from` into
+    /// `repository`. Returns false if it declined and the blob must be uploaded.
+    pub fn mount_blob(
+        &mut self,
+        registry: &str,
+        repository: &str,
+        from: &str,
+        digest: &str,
+    ) -> Result<bool> {
+        let url = format!(
+            "{}/v2/{repository}/blobs/uploads/?mount={digest}&from={from}",
+            api_base(registry)
+        );
+        let response = self.send(registry, repository, "POST", &url, &Body::Empty)?;
+        Ok(response.status().as_u16() == 201)
+    }
+
+    /// Upload a blob with a monolithic PUT.
+    pub fn push_blob(
+        &mut self,
+        registry: &str,
+        repository: &str,
+        digest: &str,
+        body: &Body,
+    ) -> Result<()> {
+        let base = api_base(registry);
+        let url = format!("{base}/v2/{repository}/blobs/uploads/");
+        let response = self.send(registry, repository, "POST", &url, &Body::Empty)?;
+        let status = response.status().as_u16();
+        if status != 202 {
+            bail!(t!("registry-push-failed", url = &url, status = status));
+        }
+        let location = response
+            .headers()
+            .get("location")
+            .and_then(|v| v.to_str().ok())
+            .with_context(|| t!("registry-push-failed", url = &url, status = status))?;
+
+        // The upload URL may be relative and may already carry a query string
+        let location = if location.starts_with('/') {
+            format!("{base}{location}")
+        } else {
+            location.to_string()
+        };
+        let sep = if location.contains('?') { '&' } else { '?' };
+        let upload = format!("{location}{sep}digest={digest}");
+
+        let response = self.send(registry, repository, "PUT", &upload, body)?;
+        match response.status().as_u16() {
+            201 => Ok(()),
+            status => bail!(t!("registry-push-failed", url = &url, status = status)),
+        }
+    }
+
+    /// Whether `repository` on `registry` already has the blob `digest`.
+    pub fn blob_exists(&mut self, registry: &str, repository: &str, digest: &str) -> Result<bool> {
+        let url = format!("{}/v2/{repository}/blobs/{digest}", api_base(registry));
+        let response = self.send(registry, repository, "HEAD", &url, &Body::Empty)?;
+        match response.status().as_u16() {
+            200 => Ok(true),
+            404 => Ok(false),
+            status => bail!(t!("registry-head-failed", url = &url, status = status)),
+        }
+    }
+
+    /// Send a request, authenticating and retrying once if the registry answers 401.
+    fn send(
+        &mut self,
+        registry: &str,
+        repository: &str,
+        method: &str,
+        url: &str,
+        body: &Body,
+    ) -> Result<ureq::http::Response<ureq::Body>> {
+        let key = (registry.to_string(), repository.to_string());
+        let response = self.request(method, url, self.tokens.get(&key), body)?;
+        if response.status().as_u16() != 401 {
+            return Ok(response);
+        }
+
+        let challenge = response
+            .headers()
+            .get("www-authenticate")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let authorization = self.authorize(registry, repository, &challenge)?;
+        let response = self.request(method, url, Some(&authorization), body)?;
+        self.tokens.insert(key, authorization);
+        Ok(response)
+    }
+
+    fn request(
+        &self,
+        method: &str,
+        url: &str,
+        authorization: Option<&String>,
+        body: &Body,
+    ) -> Result<ureq::http::Response<ureq::Body>> {
+        fn with_auth<B>(
+            req: RequestBuilder<B>,
+            authorization: Option<&String>,
+        ) -> RequestBuilder<B> {
+            match authorization {
+                Some(auth) => req.header("Authorization", auth),
+                None => req,
+            }
+        }
+
+        let started = Instant::now();
+        let result = match method {
+            "HEAD" => with_auth(self.agent.head(url), authorization)
+                .header("Accept", MANIFEST_ACCEPT)
+                .call(),
+            "GET" => with_auth(self.agent.get(url), authorization)
+                .header("Accept", MANIFEST_ACCEPT)
+                .call(),
+            _ => {
+                let req = if method == "POST" {
+                    self.agent.post(url)
+                } else {
+                    self.agent.put(url)
+                };
+                let req = with_auth(req, authorization);
+                match body {
+                    Body::Empty => req.send_empty(),
+                    Body::Bytes(data) => req
+                        .header("Content-Type", "application/octet-stream")
+                        .send(*data),
+                    Body::Manifest(media_type, data) => {
+                        req.header("Content-Type", *media_type).send(*data)
+                    }
+                    Body::File(path) => {
+                        let file = File::open(path)
+                            .with_context(|| t!("registry-read-failed", url = path.display()))?;
+                        let len = file.metadata()?.len();
+                        req.header("Content-Type", "application/octet-stream")
+                            .header("Content-Length", &len.to_string())
+                            .send(file)
+                    }
+                }
+            }
+        };
+        let response = result.with_context(|| t!("registry-request-failed", url = url))?;
+
+        if let Some(trace) = &self.trace {
+            let scheme = authorization.and_then(|a| a.split(' ').next());
+            trace.exchange(method, url, scheme, &response, started.elapsed());
+            if let Body::Manifest(_, data) = body {
+                trace.dump("request.json", data);
+            }
+        }
+        Ok(response)
+    }
+
+    /// Turn a `WWW-Authenticate` challenge into an `Authorization` header value.
+    fn authorize(&self, registry: &str, repository: &str, challenge: &str) -> Result<String> {
+        let basic = self.credentials.get(registry);
+
+        let Some(params) = challenge.strip_prefix("Bearer ") else {
+            // Basic auth: send the stored credentials directly
+            return match basic {
+                Some(auth) => Ok(format!("Basic {auth}")),
+                None => bail!(t!("registry-unauthorized", registry = registry)),
+            };
+        };
+
+        let params = parse_challenge(params);
+        let Some(realm) = params.get("realm") else {
+            bail!(t!("registry-unauthorized", registry = registry));
+        };
+        let scope = params
+            .get("scope")
+            .cloned()
+            .unwrap_or_else(|| format!("repository:{repository}:pull"));
+
+        let mut req = self.agent.get(realm).query("scope", &scope);
+        if let Some(service) = params.get("service") {
+            req = req.query("service", service);
+        }
+        if let Some(auth) = basic {
+            req = req.header("Authorization", &format!("Basic {auth}"));
+        }
+        let started = Instant::now();
+        let mut response = req
+            .call()
+            .with_context(|| t!("registry-request-failed", url = realm))?;
+        if let Some(trace) = &self.trace {
+            // The token body is never dumped
+            let url = format!("{realm}?scope={scope}");
+            let scheme = basic.map(|_| "Basic");
+            trace.exchange("GET", &url, scheme, &response, started.elapsed());
+        }
+        if response.status().as_u16() != 200 {
+            bail!(t!("registry-unauthorized", registry = registry));
+        }
+        let body: TokenResponse = serde_json::from_str(&response.body_mut().read_to_string()?)
+            .with_context(|| t!("registry-read-failed", url = realm))?;
+        let token = body
+            .token
+            .or(body.access_token)
+            .with_context(|| t!("registry-unauthorized", registry = registry))?;
+        Ok(format!("Bearer {token}"))
+    }
+}
+
+/// Parse `key="value",key2="value2"` challenge parameters.
+fn parse_challenge(params: &str) -> HashMap<String, String> {
+    let mut out = HashMap::new();
+    let mut rest = params;
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_string();
+        let (value, tail) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        out.insert(key, value.to_string());
+        rest = tail;
+    }
+    out
+}
+
+/// Read base64 `user:password` entries from the Docker CLI config, keyed by registry host.
+fn load_credentials() -> HashMap<String, String> {
+    let path = std::env::var_os("DOCKER_CONFIG")
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|h| PathBuf::from(h).join(".docker")))
+        .map(|dir| dir.join("config.json"));
+    let Some(config) = path
+        .and_then(|p| fs::read_to_string(p).ok())
+        .and_then(|s| serde_json::from_str::<DockerConfig>(&s).ok())
+    else {
+        return HashMap::new();
+    };
+
+    config
+        .auths
+        .into_iter()
+        .filter_map(|(host, entry)| {
+            let auth = entry.auth?;
+            // Only keep entries that decode; a broken one shouldn't block anonymous pulls
+            BASE64.decode(&auth).ok()?;
+            let host = host
+                .trim_start_matches("https://")
+                .trim_start_matches("http://")
+                .split('/')
+                .next()
+                .unwrap_or_default()
+                .to_string();
+            let host = if host == "index.docker.io" {
+                DOCKER_HUB.to_string()
+            } else {
+                host
+            };
+            Some((host, auth))
+        })
+        .collect()
+}
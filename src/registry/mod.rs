@@ -0,0 +1,6 @@
+pub mod client;
+pub mod reference;
+pub mod trace;
+
+pub use client::Client;
+pub use reference::Reference;
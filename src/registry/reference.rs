@@ -0,0 +1,85 @@
+use std::fmt;
+
+use anyhow::{Result, bail};
+
+/// Registry that unqualified references like `nginx:latest` resolve to.
+pub const DOCKER_HUB: &str = "docker.io";
+
+/// A parsed image reference: `[registry/]repository[:tag][@digest]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// Registry host, with port if given (e.g. "ghcr.io", "localhost:5000")
+    pub registry: String,
+
+    /// Repository path within the registry (e.g. "library/nginx")
+    pub repository: String,
+
+    /// Tag or digest to resolve
+    pub reference: String,
+}
+
+impl Reference {
+    /// Parse a reference the way `docker pull` does: a first component
+    /// containing `.` or `:` (or `localhost`) is a registry, anything else is
+    /// a Docker Hub repository.
+    pub fn parse(s: &str) -> Result<Self> {
+        let (name, reference) = if let Some((name, digest)) = s.split_once('@') {
+            (name, digest.to_string())
+        } else {
+            // A `:` after the last `/` is a tag; one before it is a registry port.
+            match s.rsplit_once(':') {
+                Some((name, tag)) if !tag.contains('/') => (name, tag.to_string()),
+                _ => (s, "latest".to_string()),
+            }
+        };
+
+        let (registry, repository) = match name.split_once('/') {
+            Some((first, rest))
+                if first.contains('.') || first.contains(':') || first == "localhost" =>
+            {
+                (first.to_string(), rest.to_string())
+            }
+            _ => (DOCKER_HUB.to_string(), name.to_string()),
+        };
+
+        let repository = if registry == DOCKER_HUB && !repository.contains('/') {
+            format!("library/{repository}")
+        } else {
+            repository
+        };
+
+        if repository.is_empty() || reference.is_empty() {
+            bail!(t!("registry-bad-reference", reference = s));
+        }
+
+        Ok(Self {
+            registry,
+            repository,
+            reference,
+        })
+    }
+
+    /// Base URL of the registry's v2 API.
+    pub fn api_base(&self) -> String {
+        api_base(&self.registry)
+    }
+}
+
+/// Base URL of a registry's v2 API. Docker Hub is served from a different
+/// host than its name, and local registries are assumed to speak plain HTTP.
+pub fn api_base(registry: &str) -> String {
+    if registry == DOCKER_HUB {
+        "https://registry-1.docker.io".to_string()
+    } else if registry.starts_with("localhost") || registry.starts_with("127.0.0.1") {
+        format!("http://{registry}")
+    } else {
+        format!("https://{registry}")
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sep = if self.reference.contains(':') { '@' } else { ':' };
+        write!(f, "{}/{}{}{}", self.registry, self.repository, sep, self.reference)
+    }
+}
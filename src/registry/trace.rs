@@ -0,0 +1,106 @@
+use std::cell::Cell;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::style::Stylize;
+
+/// Response headers worth showing when chasing registry quirks. Everything
+/// else still goes into the dump files.
+const LOGGED_HEADERS: &[&str] = &[
+    "content-type",
+    "content-length",
+    "docker-content-digest",
+    "docker-distribution-api-version",
+    "location",
+    "range",
+    "www-authenticate",
+    "server",
+];
+
+/// Headers that can carry credentials and are never written anywhere.
+const REDACTED_HEADERS: &[&str] = &["authorization", "set-cookie", "cookie"];
+
+/// `--debug-http`: log registry traffic to stderr and optionally dump each
+/// exchange to a directory.
+pub struct Trace {
+    dump_dir: Option<PathBuf>,
+    /// Number of the exchange being traced, used to order dump files
+    seq: Cell<usize>,
+}
+
+impl Trace {
+    /// `-` logs only; anything else is a directory to dump into.
+    pub fn from_cli(arg: &str) -> Result<Self> {
+        let dump_dir = (arg != "-").then(|| PathBuf::from(arg));
+        if let Some(dir) = &dump_dir {
+            fs::create_dir_all(dir)
+                .with_context(|| t!("http-dump-failed", path = dir.display()))?;
+        }
+        Ok(Self {
+            dump_dir,
+            seq: Cell::new(0),
+        })
+    }
+
+    /// Log one request/response pair. `auth` names the scheme that was sent,
+    /// never the credentials.
+    pub fn exchange(
+        &self,
+        method: &str,
+        url: &str,
+        auth: Option<&str>,
+        response: &ureq::http::Response<ureq::Body>,
+        elapsed: Duration,
+    ) {
+        let seq = self.seq.get() + 1;
+        self.seq.set(seq);
+
+        let status = response.status().as_u16();
+        eprintln!(
+            "{} {}",
+            format!("[http #{seq}]").dim(),
+            t!(
+                "http-trace",
+                method = method,
+                url = url,
+                status = status,
+                ms = elapsed.as_millis(),
+                auth = auth.unwrap_or("none")
+            )
+        );
+        for (name, value) in response.headers() {
+            if LOGGED_HEADERS.contains(&name.as_str()) {
+                eprintln!("    {}: {}", name, value.to_str().unwrap_or("<binary>"));
+            }
+        }
+
+        let mut dump = format!(
+            "{method} {url}\nauth: {}\n\nHTTP {status}\n",
+            auth.unwrap_or("none")
+        );
+        for (name, value) in response.headers() {
+            let value = if REDACTED_HEADERS.contains(&name.as_str()) {
+                "<redacted>"
+            } else {
+                value.to_str().unwrap_or("<binary>")
+            };
+            dump.push_str(&format!("{name}: {value}\n"));
+        }
+        self.dump("headers.txt", dump.as_bytes());
+    }
+
+    /// Save a manifest (or other small body) next to the current exchange's headers.
+    pub fn dump(&self, suffix: &str, data: &[u8]) {
+        let Some(dir) = &self.dump_dir else {
+            return;
+        };
+        let path = dir.join(format!("{:04}-{suffix}", self.seq.get()));
+        // A failed dump shouldn't abort the operation being debugged
+        if fs::write(&path, data).is_err() {
+            tracing::warn!("{}", t!("http-dump-failed", path = path.display()));
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use sha2::{Digest, Sha256};
+
+use crate::analysis::merged::normalize;
+
+/// How a layer blob is (or should be) compressed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+}
+
+impl Compression {
+    /// Compression of a layer from its OCI/Docker media type.
+    pub fn from_media_type(media_type: &str) -> Result<Self> {
+        if media_type.contains("gzip") {
+            Ok(Compression::Gzip)
+        } else if media_type.ends_with(".tar") || media_type.ends_with("tar") {
+            Ok(Compression::None)
+        } else {
+            bail!(t!("repack-unsupported-media-type", media_type = media_type))
+        }
+    }
+}
+
+/// Paths to leave out when rewriting a layer. A path drops itself and
+/// everything below it.
+#[derive(Debug, Default)]
+pub struct LayerFilter {
+    pub drop_paths: Vec<PathBuf>,
+}
+
+impl LayerFilter {
+    pub fn new(drop_paths: &[String]) -> Self {
+        Self {
+            drop_paths: drop_paths.iter().map(|p| normalize(Path::new(p))).collect(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.drop_paths.is_empty()
+    }
+
+    fn drops(&self, path: &Path) -> bool {
+        let path = normalize(path);
+        self.drop_paths.iter().any(|d| path.starts_with(d))
+    }
+}
+
+/// A layer blob written by `rewrite_layer`.
+#[derive(Debug)]
+pub struct RewrittenLayer {
+    /// Digest of the blob as stored (compressed)
+    pub digest: String,
+    pub size: u64,
+
+    /// Digest of the uncompressed tar, for the image config's `rootfs.diff_ids`
+    pub diff_id: String,
+
+    /// Number of entries the filter removed
+    pub dropped: usize,
+}
+
+/// Copy the layer tar from `input` to `output`, leaving out entries the
+/// filter drops and compressing with `level` (gzip only).
+///
+/// Extended attributes carried in PAX headers are not preserved.
+pub fn rewrite_layer(
+    input: impl Read,
+    input_compression: Compression,
+    filter: &LayerFilter,
+    output_compression: Compression,
+    level: u32,
+    output: &Path,
+) -> Result<RewrittenLayer> {
+    let reader: Box<dyn Read> = match input_compression {
+        Compression::Gzip => Box::new(GzDecoder::new(input)),
+        Compression::None => Box::new(input),
+    };
+    let file =
+        File::create(output).with_context(|| t!("repack-write-failed", path = output.display()))?;
+    let sink = match output_compression {
+        Compression::Gzip => Sink::Gzip(GzEncoder::new(
+            HashingWriter::new(file),
+            flate2::Compression::new(level),
+        )),
+        Compression::None => Sink::Plain(HashingWriter::new(file)),
+    };
+
+    let mut builder = tar::Builder::new(HashingWriter::new(sink));
+    builder.mode(tar::HeaderMode::Complete);
+    let mut dropped = 0;
+
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().context(t!("repack-read-failed"))? {
+        let mut entry = entry.context(t!("repack-read-failed"))?;
+        let path = entry.path()?.into_owned();
+        if filter.drops(&path) {
+            dropped += 1;
+            continue;
+        }
+
+        let mut header = entry.header().clone();
+        match entry.link_name()? {
+            Some(target) => {
+                let target = target.into_owned();
+                builder.append_link(&mut header, &path, &target)?;
+            }
+            None => builder.append_data(&mut header, &path, &mut entry)?,
+        }
+    }
+
+    let tar_writer = builder.into_inner()?;
+    let diff_id = tar_writer.digest();
+    let (digest, size) = tar_writer.into_inner().finish()?;
+
+    Ok(RewrittenLayer {
+        digest,
+        size,
+        diff_id,
+        dropped,
+    })
+}
+
+/// `sha256:<hex>` of a byte slice.
+pub fn digest_of(data: &[u8]) -> String {
+    format!("sha256:{:x}", Sha256::digest(data))
+}
+
+/// Passes writes through while hashing them.
+pub struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+    size: u64,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        Self {
+            inner,
+            hasher: Sha256::new(),
+            size: 0,
+        }
+    }
+
+    /// `sha256:<hex>` of everything written so far.
+    pub fn digest(&self) -> String {
+        format!("sha256:{:x}", self.hasher.clone().finalize())
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        self.size += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// The compressed side of a rewritten layer, hashed as it hits the file.
+enum Sink {
+    Plain(HashingWriter<File>),
+    Gzip(GzEncoder<HashingWriter<File>>),
+}
+
+impl Sink {
+    /// Flush everything to disk and return the blob digest and size.
+    fn finish(self) -> Result<(String, u64)> {
+        let mut file = match self {
+            Sink::Plain(w) => w,
+            Sink::Gzip(gz) => gz.finish()?,
+        };
+        file.flush()?;
+        Ok((file.digest(), file.size))
+    }
+}
+
+impl Write for Sink {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Sink::Plain(w) => w.write(buf),
+            Sink::Gzip(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Sink::Plain(w) => w.flush(),
+            Sink::Gzip(w) => w.flush(),
+        }
+    }
+}
@@ -0,0 +1,377 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use ureq::Agent;
+
+use crate::analysis::distro::Distro;
+use crate::analysis::packages::{Package, PackageKind};
+
+/// OSV's public API; `PEEL_OSV_URL` points peel at a mirror instead.
+const OSV_API: &str = "https://api.osv.dev/v1";
+
+/// OSV accepts at most this many queries per batch.
+const OSV_BATCH: usize = 1000;
+
+/// Where vulnerability findings come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum Scanner {
+    /// Query osv.dev with the packages peel finds
+    Osv,
+    /// Run an installed trivy
+    Trivy,
+    /// Run an installed grype
+    Grype,
+}
+
+impl Scanner {
+    pub fn name(self) -> &'static str {
+        match self {
+            Scanner::Osv => "osv",
+            Scanner::Trivy => "trivy",
+            Scanner::Grype => "grype",
+        }
+    }
+}
+
+/// One vulnerability affecting one installed package.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Finding {
+    /// Advisory ID, e.g. "CVE-2023-38545" or "DSA-5587-1"
+    pub id: String,
+
+    pub package: String,
+    pub version: String,
+
+    /// First version with the fix, if there is one
+    pub fixed_version: Option<String>,
+
+    /// "CRITICAL", "HIGH", "MEDIUM", "LOW", or whatever the source uses
+    pub severity: Option<String>,
+
+    pub summary: Option<String>,
+
+    /// Layer that installed the package (base = 0), when known
+    pub layer: Option<usize>,
+}
+
+/// Look up `packages` in OSV. Debian, Ubuntu and Alpine advisories are
+/// filed against source packages, so those are queried, at the source
+/// version, where known.
+pub fn osv(packages: &[Package], distro: Option<&Distro>) -> Result<Vec<Finding>> {
+    let base = std::env::var("PEEL_OSV_URL").unwrap_or_else(|_| OSV_API.to_string());
+    let agent: Agent = Agent::config_builder()
+        .http_status_as_error(false)
+        .build()
+        .into();
+
+    let queries: Vec<_> = packages
+        .iter()
+        .map(|p| {
+            json!({
+                "package": {
+                    "name": p.source.as_deref().unwrap_or(&p.name),
+                    "ecosystem": ecosystem(p.kind, distro),
+                },
+                "version": p.source_version.as_deref().unwrap_or(&p.version),
+            })
+        })
+        .collect();
+
+    // Package index per advisory ID
+    let mut hits: Vec<(String, usize)> = Vec::new();
+    for (chunk, queries) in queries.chunks(OSV_BATCH).enumerate() {
+        let batch: BatchResponse = post(&agent, &format!("{base}/querybatch"), queries)?;
+        for (i, result) in batch.results.into_iter().enumerate() {
+            for vuln in result.vulns {
+                hits.push((vuln.id, chunk * OSV_BATCH + i));
+            }
+        }
+    }
+
+    // The batch answer only has IDs; details are fetched once per advisory
+    let mut details: HashMap<String, Vuln> = HashMap::new();
+    let mut findings = Vec::with_capacity(hits.len());
+    for (id, index) in hits {
+        if !details.contains_key(&id) {
+            let vuln = get(&agent, &format!("{base}/vulns/{id}"))?;
+            details.insert(id.clone(), vuln);
+        }
+        let vuln = &details[&id];
+        let package = &packages[index];
+        let queried = package.source.as_deref().unwrap_or(&package.name);
+        findings.push(Finding {
+            fixed_version: vuln.fixed_version(queried),
+            severity: vuln
+                .database_specific
+                .severity
+                .as_ref()
+                .and_then(|s| s.as_str())
+                .map(str::to_uppercase),
+            summary: vuln.summary(),
+            id,
+            package: package.name.clone(),
+            version: package.version.clone(),
+            layer: Some(package.layer),
+        });
+    }
+    Ok(findings)
+}
+
+/// OSV ecosystem name, e.g. "Debian:12", "Ubuntu:22.04:LTS" or "Alpine:v3.20".
+fn ecosystem(kind: PackageKind, distro: Option<&Distro>) -> String {
+    let id = distro.map(|d| d.id.as_str());
+    let version = distro.and_then(|d| d.version_id.as_deref());
+    match (kind, id, version) {
+        (PackageKind::Deb, Some("ubuntu"), Some(v)) => {
+            // LTS releases are the even-year April ones
+            let lts = v
+                .split_once('.')
+                .is_some_and(|(y, m)| m == "04" && y.parse::<u32>().is_ok_and(|y| y % 2 == 0));
+            if lts {
+                format!("Ubuntu:{v}:LTS")
+            } else {
+                format!("Ubuntu:{v}")
+            }
+        }
+        (PackageKind::Deb, Some("debian"), Some(v)) => {
+            format!("Debian:{}", v.split('.').next().unwrap_or(v))
+        }
+        (PackageKind::Deb, _, _) => "Debian".to_string(),
+        (PackageKind::Apk, _, Some(v)) => {
+            let minor: Vec<&str> = v.split('.').take(2).collect();
+            format!("Alpine:v{}", minor.join("."))
+        }
+        (PackageKind::Apk, _, None) => "Alpine".to_string(),
+    }
+}
+
+fn post<T: for<'de> Deserialize<'de>>(
+    agent: &Agent,
+    url: &str,
+    queries: &[serde_json::Value],
+) -> Result<T> {
+    let body = serde_json::to_vec(&json!({ "queries": queries }))?;
+    let mut response = agent
+        .post(url)
+        .header("Content-Type", "application/json")
+        .send(&body[..])
+        .with_context(|| t!("scan-osv-failed", url = url))?;
+    if !response.status().is_success() {
+        bail!(t!(
+            "scan-osv-status",
+            url = url,
+            status = response.status().as_u16()
+        ));
+    }
+    serde_json::from_reader(response.body_mut().as_reader())
+        .with_context(|| t!("scan-osv-failed", url = url))
+}
+
+fn get(agent: &Agent, url: &str) -> Result<Vuln> {
+    let mut response = agent
+        .get(url)
+        .call()
+        .with_context(|| t!("scan-osv-failed", url = url))?;
+    if !response.status().is_success() {
+        bail!(t!(
+            "scan-osv-status",
+            url = url,
+            status = response.status().as_u16()
+        ));
+    }
+    serde_json::from_reader(response.body_mut().as_reader())
+        .with_context(|| t!("scan-osv-failed", url = url))
+}
+
+#[derive(Deserialize)]
+struct BatchResponse {
+    results: Vec<BatchResult>,
+}
+
+#[derive(Deserialize)]
+struct BatchResult {
+    #[serde(default)]
+    vulns: Vec<VulnId>,
+}
+
+#[derive(Deserialize)]
+struct VulnId {
+    id: String,
+}
+
+#[derive(Deserialize)]
+struct Vuln {
+    summary: Option<String>,
+    details: Option<String>,
+    #[serde(default)]
+    affected: Vec<Affected>,
+    #[serde(default)]
+    database_specific: DatabaseSpecific,
+}
+
+#[derive(Deserialize, Default)]
+struct DatabaseSpecific {
+    /// A string for most databases, but not all
+    severity: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct Affected {
+    package: Option<AffectedPackage>,
+    #[serde(default)]
+    ranges: Vec<Range>,
+}
+
+#[derive(Deserialize)]
+struct AffectedPackage {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct Range {
+    #[serde(default)]
+    events: Vec<HashMap<String, String>>,
+}
+
+impl Vuln {
+    /// The summary, or the first line of the details when there's none.
+    fn summary(&self) -> Option<String> {
+        self.summary.clone().or_else(|| {
+            let first = self.details.as_deref()?.lines().next()?.trim();
+            (!first.is_empty()).then(|| first.to_string())
+        })
+    }
+
+    fn fixed_version(&self, package: &str) -> Option<String> {
+        self.affected
+            .iter()
+            .filter(|a| a.package.as_ref().is_some_and(|p| p.name == package))
+            .flat_map(|a| &a.ranges)
+            .flat_map(|r| &r.events)
+            .find_map(|e| e.get("fixed").cloned())
+    }
+}
+
+/// Run `trivy image` on `image` (a reference or an archive path).
+pub fn trivy(image: &str) -> Result<Vec<Finding>> {
+    let mut args = vec!["image", "--quiet", "--format", "json"];
+    if Path::new(image).exists() {
+        args.push("--input");
+    }
+    args.push(image);
+    let report: TrivyReport = serde_json::from_slice(&run_scanner("trivy", &args)?)
+        .context(t!("scan-bad-output", scanner = "trivy"))?;
+    Ok(report
+        .results
+        .into_iter()
+        .flat_map(|r| r.vulnerabilities)
+        .map(|v| Finding {
+            id: v.vulnerability_id,
+            package: v.pkg_name,
+            version: v.installed_version,
+            fixed_version: v.fixed_version.filter(|f| !f.is_empty()),
+            severity: v.severity.map(|s| s.to_uppercase()),
+            summary: v.title,
+            layer: None,
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct TrivyReport {
+    #[serde(rename = "Results", default)]
+    results: Vec<TrivyResult>,
+}
+
+#[derive(Deserialize)]
+struct TrivyResult {
+    #[serde(rename = "Vulnerabilities", default)]
+    vulnerabilities: Vec<TrivyVuln>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct TrivyVuln {
+    #[serde(rename = "VulnerabilityID")]
+    vulnerability_id: String,
+    pkg_name: String,
+    installed_version: String,
+    fixed_version: Option<String>,
+    severity: Option<String>,
+    title: Option<String>,
+}
+
+/// Run `grype` on `image` (a reference or an archive path).
+pub fn grype(image: &str) -> Result<Vec<Finding>> {
+    let report: GrypeReport =
+        serde_json::from_slice(&run_scanner("grype", &[image, "--quiet", "-o", "json"])?)
+            .context(t!("scan-bad-output", scanner = "grype"))?;
+    Ok(report
+        .matches
+        .into_iter()
+        .map(|m| Finding {
+            id: m.vulnerability.id,
+            package: m.artifact.name,
+            version: m.artifact.version,
+            fixed_version: m.vulnerability.fix.versions.into_iter().next(),
+            severity: m.vulnerability.severity.map(|s| s.to_uppercase()),
+            summary: m.vulnerability.description,
+            layer: None,
+        })
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct GrypeReport {
+    #[serde(default)]
+    matches: Vec<GrypeMatch>,
+}
+
+#[derive(Deserialize)]
+struct GrypeMatch {
+    vulnerability: GrypeVuln,
+    artifact: GrypeArtifact,
+}
+
+#[derive(Deserialize)]
+struct GrypeVuln {
+    id: String,
+    severity: Option<String>,
+    description: Option<String>,
+    #[serde(default)]
+    fix: GrypeFix,
+}
+
+#[derive(Deserialize, Default)]
+struct GrypeFix {
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GrypeArtifact {
+    name: String,
+    version: String,
+}
+
+fn run_scanner(program: &str, args: &[&str]) -> Result<Vec<u8>> {
+    tracing::debug!("running {program} {}", args.join(" "));
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .with_context(|| t!("scan-not-installed", scanner = program))?;
+    if !output.status.success() {
+        bail!(t!(
+            "scan-failed",
+            scanner = program,
+            error = String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(output.stdout)
+}
@@ -0,0 +1,74 @@
+use std::io::{self, IsTerminal, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::pager;
+use crate::temp_files;
+
+/// Set while the pager runs: Ctrl+C is for it then, not for peel.
+static DEFERRED: AtomicBool = AtomicBool::new(false);
+
+/// On Ctrl+C or SIGTERM, remove the temp files peel has open, such as a
+/// half-written `docker save`, put the terminal back and exit as the shell
+/// reports a signal: 128 plus its number.
+#[cfg(unix)]
+pub fn install() {
+    use std::io::Read;
+    use std::os::fd::IntoRawFd;
+    use std::sync::atomic::AtomicI32;
+    use std::{process, thread};
+
+    static WRITER: AtomicI32 = AtomicI32::new(-1);
+
+    // Does no more than a signal handler may: the thread below does the rest
+    extern "C" fn notify(signal: libc::c_int) {
+        let byte = signal as u8;
+        // SAFETY: write(2) is async-signal-safe, and the descriptor stays open
+        unsafe { libc::write(WRITER.load(Ordering::Relaxed), (&raw const byte).cast(), 1) };
+    }
+
+    let Ok((mut reader, writer)) = io::pipe() else {
+        return;
+    };
+    WRITER.store(writer.into_raw_fd(), Ordering::Relaxed);
+    for signal in [libc::SIGINT, libc::SIGTERM] {
+        // SAFETY: the action is fully set up, and `notify` only calls write(2)
+        unsafe {
+            let mut action: libc::sigaction = std::mem::zeroed();
+            action.sa_sigaction = notify as extern "C" fn(libc::c_int) as libc::sighandler_t;
+            action.sa_flags = libc::SA_RESTART;
+            libc::sigemptyset(&mut action.sa_mask);
+            libc::sigaction(signal, &action, std::ptr::null_mut());
+        }
+    }
+
+    thread::spawn(move || {
+        let mut byte = [0];
+        while reader.read_exact(&mut byte).is_ok() {
+            let signal = libc::c_int::from(byte[0]);
+            if signal == libc::SIGINT && DEFERRED.load(Ordering::Relaxed) {
+                continue;
+            }
+            clean_up();
+            process::exit(128 + signal);
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn install() {}
+
+/// Leave Ctrl+C to a child that handles it itself, such as the pager, until
+/// called again with `false`.
+pub fn defer(deferred: bool) {
+    DEFERRED.store(deferred, Ordering::Relaxed);
+}
+
+fn clean_up() {
+    temp_files::remove_all();
+    pager::abort();
+    // A spinner may be half drawn, with the cursor hidden
+    let mut stderr = io::stderr();
+    if stderr.is_terminal() {
+        let _ = stderr.write_all(b"\r\x1b[2K\x1b[?25h");
+    }
+}
@@ -0,0 +1,97 @@
+use std::env;
+use std::io::{self, IsTerminal};
+use std::sync::OnceLock;
+
+use crossterm::style as term;
+pub use crossterm::style::style;
+
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// When to style text output, picked with `--color`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// When stdout and stderr are terminals and `NO_COLOR` isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+/// Settle whether text is styled (`--color`). Call before printing.
+pub fn init(choice: ColorChoice) {
+    let _ = ENABLED.set(decide(choice));
+}
+
+/// Whether text is styled: as `init` settled, else as `--color auto` would.
+pub fn enabled() -> bool {
+    *ENABLED.get_or_init(|| decide(ColorChoice::Auto))
+}
+
+fn decide(choice: ColorChoice) -> bool {
+    let enabled = match choice {
+        ColorChoice::Auto => {
+            env::var_os("NO_COLOR").is_none_or(|v| v.is_empty())
+                && io::stdout().is_terminal()
+                && io::stderr().is_terminal()
+        }
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+    };
+    // crossterm drops colors, but not bold or dim, under NO_COLOR on its own
+    term::force_color_output(enabled);
+    enabled
+}
+
+/// crossterm's [`Stylize`](term::Stylize), but leaving text plain when
+/// styling is off, so nothing printed carries escape codes.
+pub trait Stylize: term::Stylize {
+    fn bold(self) -> Self::Styled {
+        styled(self, term::Stylize::bold)
+    }
+
+    fn dim(self) -> Self::Styled {
+        styled(self, term::Stylize::dim)
+    }
+
+    fn red(self) -> Self::Styled {
+        styled(self, term::Stylize::red)
+    }
+
+    fn green(self) -> Self::Styled {
+        styled(self, term::Stylize::green)
+    }
+
+    fn yellow(self) -> Self::Styled {
+        styled(self, term::Stylize::yellow)
+    }
+
+    fn blue(self) -> Self::Styled {
+        styled(self, term::Stylize::blue)
+    }
+
+    fn magenta(self) -> Self::Styled {
+        styled(self, term::Stylize::magenta)
+    }
+
+    fn cyan(self) -> Self::Styled {
+        styled(self, term::Stylize::cyan)
+    }
+
+    fn dark_grey(self) -> Self::Styled {
+        styled(self, term::Stylize::dark_grey)
+    }
+
+    fn stylize(self) -> Self::Styled {
+        term::Stylize::stylize(self)
+    }
+}
+
+impl<T: term::Stylize> Stylize for T {}
+
+fn styled<T: term::Stylize>(text: T, apply: fn(T) -> T::Styled) -> T::Styled {
+    if enabled() {
+        apply(text)
+    } else {
+        term::Stylize::stylize(text)
+    }
+}
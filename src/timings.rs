@@ -0,0 +1,228 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::style::Stylize;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static STARTED: OnceLock<Instant> = OnceLock::new();
+static SPANS: Mutex<Vec<Span>> = Mutex::new(Vec::new());
+
+/// Slowest layers listed under the layer phase in the text summary.
+const SLOWEST: usize = 3;
+
+/// Wall time per phase, for `--timings`: each span peel opens around a
+/// phase (`resolve`, `save`, `parse`, `layer`, `analyze`, `report`, ...)
+/// counts once it closes.
+#[derive(Debug, Serialize)]
+pub struct Timings {
+    /// Since `--timings` took effect, up to when this was taken
+    pub total_seconds: f64,
+
+    /// In the order they started; a phase inside another follows it
+    pub phases: Vec<Phase>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Phase {
+    pub name: &'static str,
+
+    /// How many phases it's nested in
+    pub depth: usize,
+
+    /// Summed over every time the phase ran
+    pub seconds: f64,
+    pub count: usize,
+
+    /// Each layer's time, for the per-layer phase
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub layers: Vec<LayerTime>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LayerTime {
+    /// Digest, or path in the archive
+    pub layer: String,
+    pub seconds: f64,
+}
+
+/// A closed span.
+struct Span {
+    name: &'static str,
+    depth: usize,
+    start: Instant,
+    elapsed: Duration,
+    layer: Option<String>,
+}
+
+/// Kept with each open span until it closes.
+struct Opened {
+    start: Instant,
+    layer: Option<String>,
+}
+
+/// The `tracing` layer that times spans; installed with `--timings`.
+pub struct Recorder;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for Recorder {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut layer = LayerField(None);
+        attrs.record(&mut layer);
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Opened {
+                start: Instant::now(),
+                layer: layer.0,
+            });
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(opened) = span.extensions_mut().remove::<Opened>() else {
+            return;
+        };
+        SPANS.lock().unwrap().push(Span {
+            name: span.name(),
+            depth: span.scope().skip(1).count(),
+            start: opened.start,
+            elapsed: opened.start.elapsed(),
+            layer: opened.layer,
+        });
+    }
+}
+
+/// A span's `layer` field: the digest or path of the layer it's about.
+struct LayerField(Option<String>);
+
+impl Visit for LayerField {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        if field.name() == "layer" {
+            self.0 = Some(value.to_string());
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "layer" {
+            self.0 = Some(format!("{value:?}"));
+        }
+    }
+}
+
+/// Record phases from now on (`--timings`). Call before logging starts.
+pub fn enable() {
+    STARTED.get_or_init(Instant::now);
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+pub fn enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// The phases closed so far, if `--timings` asked for them.
+pub fn take() -> Option<Timings> {
+    if !enabled() {
+        return None;
+    }
+    let mut spans = SPANS.lock().unwrap();
+    spans.sort_by_key(|s| s.start);
+    let mut phases: Vec<Phase> = Vec::new();
+    for span in spans.iter() {
+        let i = match phases
+            .iter()
+            .position(|p| p.name == span.name && p.depth == span.depth)
+        {
+            Some(i) => i,
+            None => {
+                phases.push(Phase {
+                    name: span.name,
+                    depth: span.depth,
+                    seconds: 0.0,
+                    count: 0,
+                    layers: Vec::new(),
+                });
+                phases.len() - 1
+            }
+        };
+        let phase = &mut phases[i];
+        phase.seconds += span.elapsed.as_secs_f64();
+        phase.count += 1;
+        if let Some(layer) = &span.layer {
+            phase.layers.push(LayerTime {
+                layer: layer.clone(),
+                seconds: span.elapsed.as_secs_f64(),
+            });
+        }
+    }
+    Some(Timings {
+        total_seconds: STARTED.get_or_init(Instant::now).elapsed().as_secs_f64(),
+        phases,
+    })
+}
+
+/// Print the phases to stderr, once: when the command is done, or before
+/// the web report starts serving.
+pub fn print() {
+    let Some(timings) = take() else {
+        return;
+    };
+    ENABLED.store(false, Ordering::Relaxed);
+
+    // (label, time, whether it's a layer under its phase)
+    let mut rows: Vec<(String, f64, bool)> = Vec::new();
+    for phase in &timings.phases {
+        let indent = "  ".repeat(phase.depth);
+        let label = match phase.count {
+            1 => format!("{indent}{}", phase.name),
+            n => format!("{indent}{} ×{n}", phase.name),
+        };
+        rows.push((label, phase.seconds, false));
+        let mut layers: Vec<&LayerTime> = phase.layers.iter().collect();
+        if layers.len() > 1 {
+            layers.sort_by(|a, b| b.seconds.total_cmp(&a.seconds));
+            for layer in layers.iter().take(SLOWEST) {
+                let name = layer.layer.get(..19).unwrap_or(&layer.layer);
+                rows.push((format!("{indent}  {name}"), layer.seconds, true));
+            }
+        }
+    }
+    let total = t!("timings-total");
+    let width = rows
+        .iter()
+        .map(|(label, _, _)| label.chars().count())
+        .chain([total.chars().count()])
+        .max()
+        .unwrap_or(0);
+
+    eprintln!();
+    eprintln!("{}", t!("timings-title").bold());
+    for (label, secs, layer) in rows {
+        let line = format!("{label:width$}  {:>8}", seconds(secs));
+        if layer {
+            eprintln!("  {}", line.dim());
+        } else {
+            eprintln!("  {line}");
+        }
+    }
+    let line = format!("{total:width$}  {:>8}", seconds(timings.total_seconds));
+    eprintln!("  {}", line.bold());
+}
+
+fn seconds(secs: f64) -> String {
+    if secs < 0.001 {
+        "<1ms".to_string()
+    } else if secs < 1.0 {
+        format!("{:.0}ms", secs * 1000.0)
+    } else {
+        format!("{secs:.2}s")
+    }
+}
@@ -0,0 +1,117 @@
+//! Golden-file tests for the text formatters, driven through `peel render`.
+//!
+//! Run with `UPDATE_GOLDEN=1 cargo test` to regenerate `tests/golden/` after an
+//! intentional output change.
+
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use serde_json::Value;
+
+fn render(fixture: &str, format: &str) -> String {
+    let input = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(fixture);
+    let output = Command::new(env!("CARGO_BIN_EXE_peel"))
+        .args(["--lang", "en", "render", "--format", format, "--input"])
+        .arg(&input)
+        .output()
+        .expect("failed to run peel");
+    assert!(
+        output.status.success(),
+        "peel render {fixture} failed: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    strip_ansi(&String::from_utf8(output.stdout).unwrap())
+}
+
+/// Drop terminal escape sequences so goldens don't depend on styling.
+fn strip_ansi(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn assert_golden(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/golden").join(name);
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing {}; run with UPDATE_GOLDEN=1", path.display()));
+    assert_eq!(actual, expected, "output differs from {}", path.display());
+}
+
+#[test]
+fn inspect_text() {
+    assert_golden("inspect.txt", &render("inspect.json", "text"));
+}
+
+#[test]
+fn inspect_merged_text() {
+    assert_golden("inspect-merged.txt", &render("inspect-merged.json", "text"));
+}
+
+#[test]
+fn diff_text() {
+    assert_golden("diff.txt", &render("diff.json", "text"));
+}
+
+#[test]
+fn compare_text() {
+    assert_golden("compare.txt", &render("compare.json", "text"));
+}
+
+#[test]
+fn json_round_trips() {
+    for fixture in ["inspect.json", "inspect-merged.json", "diff.json", "compare.json"] {
+        let expected = fs::read_to_string(
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(fixture),
+        )
+        .unwrap();
+        let output: Value = serde_json::from_str(&render(fixture, "json")).unwrap();
+        assert_eq!(output["schema_version"], 1, "{fixture} has no envelope");
+        assert_eq!(
+            output["data"],
+            serde_json::from_str::<Value>(&expected).unwrap(),
+            "{fixture} did not round-trip"
+        );
+    }
+}
+
+#[test]
+fn enveloped_report_renders() {
+    let enveloped = render("inspect.json", "json");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_peel"))
+        .args(["--lang", "en", "render", "--format", "text", "--input", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("failed to run peel");
+    child.stdin.take().unwrap().write_all(enveloped.as_bytes()).unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+    assert_golden(
+        "inspect.txt",
+        &strip_ansi(&String::from_utf8(output.stdout).unwrap()),
+    );
+}
+
+#[test]
+fn html_embeds_report() {
+    let html = render("diff.json", "html");
+    assert!(html.contains(r#"<html lang="en">"#));
+    assert!(html.contains(r#""shared_layers": 2"#));
+}